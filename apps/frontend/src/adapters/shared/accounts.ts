@@ -1,5 +1,5 @@
 // Account Commands
-import type { Account } from "@/lib/types";
+import type { Account, AccountMergeResult } from "@/lib/types";
 import type { newAccountSchema } from "@/lib/schemas";
 import type z from "zod";
 
@@ -49,3 +49,25 @@ export const deleteAccount = async (accountId: string): Promise<void> => {
     throw error;
   }
 };
+
+/**
+ * Merges a duplicate account into another one. Use `dryRun` to preview the merge; applying it
+ * deletes the source account and requires `confirm`.
+ */
+export const mergeAccounts = async (
+  sourceAccountId: string,
+  targetAccountId: string,
+  options: { dryRun?: boolean; confirm?: boolean } = {},
+): Promise<AccountMergeResult> => {
+  try {
+    return await invoke<AccountMergeResult>("merge_accounts", {
+      sourceAccountId,
+      targetAccountId,
+      dryRun: options.dryRun ?? false,
+      confirm: options.confirm ?? false,
+    });
+  } catch (error) {
+    logger.error("Error merging accounts.");
+    throw error;
+  }
+};
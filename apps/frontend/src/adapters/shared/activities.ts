@@ -6,6 +6,7 @@ import type {
   ActivityBulkMutationResult,
   ActivityCreate,
   ActivityDetails,
+  ActivityPage,
   ActivitySearchResponse,
   ActivityUpdate,
   ActivityImport,
@@ -16,6 +17,7 @@ import type {
   ImportTemplateData,
   InternalTransferPairRequest,
   InternalTransferPairResponse,
+  RecentActivity,
   TransferMatchCandidate,
   TransferMatchCandidateRequest,
   BrokerSyncProfileData,
@@ -101,6 +103,44 @@ export const searchActivities = async (
   }
 };
 
+/**
+ * Fetches one keyset page of activities, newest first. Pass the previous page's `nextCursor`
+ * to continue; pages stay consistent while activities are added.
+ */
+export const searchActivitiesPage = async (
+  filters: ActivityFilters,
+  cursor?: string | null,
+  limit?: number,
+): Promise<ActivityPage> => {
+  const assetIdKeyword = filters?.symbol?.trim() ? filters.symbol.trim() : undefined;
+
+  try {
+    return await invoke<ActivityPage>("search_activities_page", {
+      cursor: cursor ?? undefined,
+      limit,
+      accountIdFilter: normalizeStringArray(filters?.accountIds),
+      activityTypeFilter: normalizeStringArray(filters?.activityTypes),
+      assetIdKeyword,
+      needsReviewFilter: filters?.needsReview,
+      dateFrom: filters?.dateFrom,
+      dateTo: filters?.dateTo,
+      instrumentTypeFilter: normalizeStringArray(filters?.instrumentTypes),
+    });
+  } catch (err) {
+    logger.error("Error fetching activity page.");
+    throw err;
+  }
+};
+
+export const getRecentActivities = async (limit?: number): Promise<RecentActivity[]> => {
+  try {
+    return await invoke<RecentActivity[]>("get_recent_activities", { limit });
+  } catch (err) {
+    logger.error("Error fetching recent activities.");
+    throw err;
+  }
+};
+
 export const createActivity = async (activity: ActivityCreate): Promise<Activity> => {
   try {
     return await invoke<Activity>("create_activity", { activity });
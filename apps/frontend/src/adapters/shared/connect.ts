@@ -8,26 +8,37 @@ import type {
   GetPairingResponse,
   PairingMessagesResponse,
   ResetTeamSyncResponse,
+  RevokeDeviceResult,
   SuccessResponse,
 } from "@/features/devices-sync/types";
 import type {
+  AccountResyncResult,
   BrokerAccount,
   BrokerConnection,
   BrokerSyncState,
+  ConnectionExpiry,
+  ConnectionSummary,
+  FakeSubscriptionState,
+  HistoryBackfillJob,
   ImportRun,
   PlansResponse,
+  SyncDashboard,
   UserInfo,
 } from "@/features/wealthfolio-connect/types";
 import type { Account, Platform } from "@/lib/types";
 import type {
   BackendEnableSyncResult,
+  BackendRotateCredentialResult,
   BackendSyncBackgroundEngineResult,
   BackendSyncBootstrapOverwriteCheckResult,
   BackendSyncBootstrapResult,
   BackendSyncCycleResult,
+  BackendCursorExpiryForecast,
   BackendSyncEngineStatusResult,
   BackendSyncPairingSourceStatusResult,
   BackendSyncReconcileReadyResult,
+  BackendSyncRejectionPage,
+  BackendSyncRejectionQuery,
   BackendSyncSnapshotUploadResult,
   BackendSyncStateResult,
   ImportRunsRequest,
@@ -44,6 +55,11 @@ export async function syncBrokerData(): Promise<void> {
   return invoke<void>("broker_ingest_run");
 }
 
+/** Refetches one account's history from the cloud and reconciles it against local data. */
+export async function resyncBrokerAccount(accountId: string): Promise<AccountResyncResult> {
+  return invoke<AccountResyncResult>("resync_broker_account", { accountId });
+}
+
 export async function postLoginBootstrap(): Promise<PostLoginBootstrapResult> {
   return invoke<PostLoginBootstrapResult>("post_login_bootstrap");
 }
@@ -60,6 +76,14 @@ export async function listBrokerConnections(): Promise<BrokerConnection[]> {
   return invoke<BrokerConnection[]>("list_broker_connections");
 }
 
+/** Sets the local display name of a connection; pass `null` or blank to clear it. */
+export async function setConnectionName(
+  connectionId: string,
+  name: string | null,
+): Promise<string | null> {
+  return invoke<string | null>("set_connection_name", { connectionId, name });
+}
+
 export async function listBrokerAccounts(): Promise<BrokerAccount[]> {
   return invoke<BrokerAccount[]>("list_broker_accounts");
 }
@@ -76,6 +100,14 @@ export async function getUserInfo(): Promise<UserInfo> {
   return invoke<UserInfo>("get_user_info");
 }
 
+/**
+ * Simulate a subscription state for UI development; `null` restores the real one.
+ * Only works when the backend runs with `WEALTHFOLIO_DEBUG_ENDPOINTS=true`.
+ */
+export async function setFakeSubscription(state: FakeSubscriptionState | null): Promise<void> {
+  return invoke<void>("set_fake_subscription", { state });
+}
+
 export async function getBrokerSyncStates(): Promise<BrokerSyncState[]> {
   return invoke<BrokerSyncState[]>("get_broker_ingest_states");
 }
@@ -88,6 +120,22 @@ export async function getImportRuns(request?: ImportRunsRequest): Promise<Import
   });
 }
 
+export async function getSyncDashboard(): Promise<SyncDashboard> {
+  return invoke<SyncDashboard>("get_sync_dashboard");
+}
+
+export async function getConnectionSummary(): Promise<ConnectionSummary> {
+  return invoke<ConnectionSummary>("get_connection_summary");
+}
+
+export async function getExpiringConnections(): Promise<ConnectionExpiry[]> {
+  return invoke<ConnectionExpiry[]>("get_expiring_connections");
+}
+
+export async function getHistoryBackfills(): Promise<HistoryBackfillJob[]> {
+  return invoke<HistoryBackfillJob[]>("get_history_backfills");
+}
+
 // ============================================================================
 // Device Sync Commands (DeviceEnrollService)
 // ============================================================================
@@ -108,10 +156,28 @@ export const reinitializeDeviceSync = async (): Promise<BackendEnableSyncResult>
   return invoke<BackendEnableSyncResult>("reinitialize_device_sync");
 };
 
+export const rotateDeviceCredential = async (): Promise<BackendRotateCredentialResult> => {
+  return invoke<BackendRotateCredentialResult>("rotate_device_credential");
+};
+
 export const getSyncEngineStatus = async (): Promise<BackendSyncEngineStatusResult> => {
   return invoke<BackendSyncEngineStatusResult>("device_sync_engine_status");
 };
 
+export const getSyncRejections = async (
+  query?: BackendSyncRejectionQuery,
+): Promise<BackendSyncRejectionPage> => {
+  return invoke<BackendSyncRejectionPage>("get_sync_rejections", { query });
+};
+
+export const acknowledgeSyncRejections = async (eventIds: string[]): Promise<number> => {
+  return invoke<number>("acknowledge_sync_rejections", { eventIds });
+};
+
+export const getCursorExpiryForecast = async (): Promise<BackendCursorExpiryForecast> => {
+  return invoke<BackendCursorExpiryForecast>("device_sync_cursor_expiry_forecast");
+};
+
 export const getPairingSourceStatus = async (): Promise<BackendSyncPairingSourceStatusResult> => {
   return invoke<BackendSyncPairingSourceStatusResult>("device_sync_pairing_source_status");
 };
@@ -178,8 +244,8 @@ export const deleteDevice = async (deviceId: string): Promise<SuccessResponse> =
   return invoke<SuccessResponse>("delete_device", { deviceId });
 };
 
-export const revokeDevice = async (deviceId: string): Promise<SuccessResponse> => {
-  return invoke<SuccessResponse>("revoke_device", { deviceId });
+export const revokeDevice = async (deviceId: string): Promise<RevokeDeviceResult> => {
+  return invoke<RevokeDeviceResult>("revoke_device", { deviceId });
 };
 
 export const resetTeamSync = async (reason?: string): Promise<ResetTeamSyncResponse> => {
@@ -339,3 +405,11 @@ export const storeSyncSession = async (refreshToken: string): Promise<void> => {
 export const clearSyncSession = async (): Promise<void> => {
   return invoke<void>("clear_sync_session");
 };
+
+/**
+ * Revokes the cloud session on every device and deletes all stored cloud credentials,
+ * including the device sync enrollment. Succeeds locally even when the revoke fails.
+ */
+export const signOutEverywhere = async (): Promise<{ remoteRevoked: boolean }> => {
+  return invoke<{ remoteRevoked: boolean }>("sign_out_everywhere");
+};
@@ -59,3 +59,10 @@ export const getHealthConfig = async (): Promise<HealthConfig> => {
 export const updateHealthConfig = async (config: HealthConfig): Promise<void> => {
   return invoke<void>("update_health_config", { config });
 };
+
+/**
+ * Get redacted sync diagnostics to attach to a bug report.
+ */
+export const getDiagnostics = async (): Promise<Record<string, unknown>> => {
+  return invoke<Record<string, unknown>>("get_diagnostics");
+};
@@ -9,6 +9,7 @@ import type {
   MarketDataProviderInfo,
   ExchangeInfo,
   ResolvedQuote,
+  SymbolNormalization,
 } from "@/lib/types";
 import type { QuoteImport } from "@/lib/types/quote-import";
 import type { MarketDataProviderSetting } from "../types";
@@ -98,6 +99,40 @@ export const updateQuoteMode = async (assetId: string, quoteMode: string): Promi
   }
 };
 
+export const normalizeSymbols = async (symbols: string[]): Promise<SymbolNormalization[]> => {
+  try {
+    return await invoke<SymbolNormalization[]>("normalize_symbols", { symbols });
+  } catch (error) {
+    logger.error("Error normalizing symbols.");
+    throw error;
+  }
+};
+
+export const getSymbolMappings = async (): Promise<Record<string, string>> => {
+  try {
+    return await invoke<Record<string, string>>("get_symbol_mappings");
+  } catch (error) {
+    logger.error("Error loading symbol mappings.");
+    throw error;
+  }
+};
+
+/** Maps `rawSymbol` to `targetSymbol`; a null target removes the mapping. */
+export const setSymbolMapping = async (
+  rawSymbol: string,
+  targetSymbol: string | null,
+): Promise<Record<string, string>> => {
+  try {
+    return await invoke<Record<string, string>>("set_symbol_mapping", {
+      rawSymbol,
+      targetSymbol,
+    });
+  } catch (error) {
+    logger.error("Error saving symbol mapping.");
+    throw error;
+  }
+};
+
 export const updateQuote = async (symbol: string, quote: Quote): Promise<void> => {
   try {
     return await invoke<void>("update_quote", { symbol, quote });
@@ -2,8 +2,14 @@
 import type {
   AccountScope,
   Holding,
+  AllocationDimension,
   AllocationHoldings,
+  AllocationSlice,
   IncomeSummary,
+  DividendSummary,
+  DividendSummaryQuery,
+  HoldingGains,
+  LedgerValidationReport,
   AccountValuation,
   CurrentValuationResponse,
   PerformanceSummaryMap,
@@ -17,6 +23,8 @@ import type {
   CheckHoldingsImportResult,
   SnapshotInfo,
   AssetLotView,
+  ArchiveDiff,
+  ShareSnapshot,
 } from "@/lib/types";
 
 import { invoke, logger } from "./platform";
@@ -29,6 +37,15 @@ export const recalculatePortfolio = async (): Promise<void> => {
   return invoke<void>("recalculate_portfolio");
 };
 
+/** Warms allocation, gains and value history caches in the background; see `analytics:ready`. */
+export const warmAnalyticsCaches = async (): Promise<void> => {
+  return invoke<void>("warm_analytics_caches");
+};
+
+export const cancelAnalyticsWarmup = async (): Promise<void> => {
+  return invoke<void>("cancel_analytics_warmup");
+};
+
 export const getHoldings = async (filter: AccountScope): Promise<Holding[]> => {
   return invoke<Holding[]>("get_holdings", { filter });
 };
@@ -37,10 +54,45 @@ export const getHoldingsList = async (filter: AccountScope): Promise<Holding[]>
   return invoke<Holding[]>("get_holdings_list", { filter });
 };
 
+/** Current positions as CSV; the first line is a `# valuedAt=` comment. */
+export const exportHoldingsCsv = async (): Promise<string> => {
+  return invoke<string>("export_holdings_csv");
+};
+
+/** Read-only snapshot for sharing; account details are left out unless opted in. */
+export const exportPortfolioSnapshot = async (
+  includeAccountDetails = false,
+): Promise<ShareSnapshot> => {
+  return invoke<ShareSnapshot>("export_portfolio_snapshot", { includeAccountDetails });
+};
+
+/** Record-level differences between two data archive files' contents; `left` is the older one. */
+export const diffDataArchives = async (left: string, right: string): Promise<ArchiveDiff> => {
+  return invoke<ArchiveDiff>("diff_data_archives", { left, right });
+};
+
 export const getIncomeSummary = async (filter?: AccountScope): Promise<IncomeSummary[]> => {
   return invoke<IncomeSummary[]>("get_income_summary", { filter });
 };
 
+/** Dividend totals by symbol, account or month; all income accounts when `filter` is omitted. */
+export const getDividendSummary = async (
+  query: DividendSummaryQuery,
+  filter?: AccountScope,
+): Promise<DividendSummary> => {
+  return invoke<DividendSummary>("get_dividend_summary", { query, filter });
+};
+
+/** Realized and unrealized gains per holding; all accounts when `accountId` is omitted. */
+export const getGains = async (accountId?: string): Promise<HoldingGains[]> => {
+  return invoke<HoldingGains[]>("get_gains", { accountId });
+};
+
+/** Checks the activity ledger; with `fix`, stale holdings are recomputed. */
+export const validateActivityLedger = async (fix = false): Promise<LedgerValidationReport> => {
+  return invoke<LedgerValidationReport>("validate_activity_ledger", { fix });
+};
+
 export const getHistoricalValuations = async (
   filter?: AccountScope,
   startDate?: string,
@@ -206,6 +258,17 @@ export const getPortfolioAllocations = async (
   return invoke<PortfolioAllocations>("get_portfolio_allocations", { filter });
 };
 
+/**
+ * Gets a flat allocation breakdown (asset class, sector, currency or account)
+ * in base currency.
+ */
+export const getAllocationBreakdown = async (
+  filter: AccountScope,
+  dimension: AllocationDimension,
+): Promise<AllocationSlice[]> => {
+  return invoke<AllocationSlice[]>("get_allocation_breakdown", { filter, dimension });
+};
+
 /**
  * Gets holdings filtered by a taxonomy category.
  * Used for allocation drill-down views when user clicks on a category in charts.
@@ -58,6 +58,11 @@ export const listenPortfolioUpdateComplete = async <T>(
   return adaptUnlisten(unlisten);
 };
 
+export const listenAnalyticsReady = async <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
+  const unlisten = await listen<T>("analytics:ready", adaptCallback(handler));
+  return adaptUnlisten(unlisten);
+};
+
 export const listenDatabaseRestored = async <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
   const unlisten = await listen<T>("database-restored", adaptCallback(handler));
   return adaptUnlisten(unlisten);
@@ -70,6 +75,11 @@ export const listenPortfolioUpdateError = async <T>(
   return adaptUnlisten(unlisten);
 };
 
+export const listenPortfoliosChanged = async <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
+  const unlisten = await listen<T>("portfolio:portfolios-changed", adaptCallback(handler));
+  return adaptUnlisten(unlisten);
+};
+
 export async function listenMarketSyncComplete<T>(handler: EventCallback<T>): Promise<UnlistenFn> {
   const unlisten = await listen<T>("market:sync-complete", adaptCallback(handler));
   return adaptUnlisten(unlisten);
@@ -100,6 +110,11 @@ export async function listenBrokerSyncError<T>(handler: EventCallback<T>): Promi
   return adaptUnlisten(unlisten);
 }
 
+export async function listenBrokerSyncSummary<T>(handler: EventCallback<T>): Promise<UnlistenFn> {
+  const unlisten = await listen<T>("broker:sync-summary", adaptCallback(handler));
+  return adaptUnlisten(unlisten);
+}
+
 export async function listenNavigateToRoute<T>(handler: EventCallback<T>): Promise<UnlistenFn> {
   const unlisten = await listen<T>("navigate-to-route", adaptCallback(handler));
   return adaptUnlisten(unlisten);
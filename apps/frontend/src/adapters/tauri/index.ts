@@ -38,9 +38,13 @@ export type {
   PlatformInfo,
   BackendSyncStateResult,
   BackendEnableSyncResult,
+  BackendRotateCredentialResult,
   BackendSyncEngineStatusResult,
   BackendSyncBootstrapOverwriteCheckResult,
   BackendSyncReconcileReadyResult,
+  BackendSyncRejection,
+  BackendSyncRejectionPage,
+  BackendSyncRejectionQuery,
   BackendSyncBootstrapResult,
   BackendSyncCycleResult,
   BackendSyncBackgroundEngineResult,
@@ -144,6 +148,11 @@ export {
   getSettings,
   updateSettings,
   isAutoUpdateCheckEnabled,
+  getSyncQuietHours,
+  setSyncQuietHours,
+  getCloudStatus,
+  disableCloud,
+  enableCloud,
   backupDatabase,
   deleteDatabaseBackup,
   getDatabaseBackupDownloadUrl,
@@ -210,14 +219,17 @@ export {
   listenFileDropCancelled,
   listenPortfolioUpdateStart,
   listenPortfolioUpdateComplete,
+  listenAnalyticsReady,
   listenDatabaseRestored,
   listenPortfolioUpdateError,
+  listenPortfoliosChanged,
   listenMarketSyncComplete,
   listenMarketSyncStart,
   listenMarketSyncError,
   listenBrokerSyncStart,
   listenBrokerSyncComplete,
   listenBrokerSyncError,
+  listenBrokerSyncSummary,
   listenNavigateToRoute,
   listenDeepLink,
   getCurrentDeepLinks,
@@ -1,5 +1,11 @@
 // Settings Commands
-import type { Settings, UpdateInfo } from "@/lib/types";
+import type {
+  CloudAccessStatus,
+  Settings,
+  SyncQuietHours,
+  SyncQuietHoursStatus,
+  UpdateInfo,
+} from "@/lib/types";
 import type { AppInfo, PlatformInfo } from "../types";
 
 import { invoke, logger } from "./core";
@@ -31,6 +37,55 @@ export const isAutoUpdateCheckEnabled = async (): Promise<boolean> => {
   }
 };
 
+export const getSyncQuietHours = async (): Promise<SyncQuietHoursStatus> => {
+  try {
+    return await invoke<SyncQuietHoursStatus>("get_sync_quiet_hours");
+  } catch (error) {
+    logger.error("Error fetching sync quiet hours.");
+    throw error;
+  }
+};
+
+/** Sets the automatic sync quiet hours; pass `null` to clear them. */
+export const setSyncQuietHours = async (
+  quietHours: SyncQuietHours | null,
+): Promise<SyncQuietHoursStatus> => {
+  try {
+    return await invoke<SyncQuietHoursStatus>("set_sync_quiet_hours", { quietHours });
+  } catch (error) {
+    logger.error("Error updating sync quiet hours.");
+    throw error;
+  }
+};
+
+export const getCloudStatus = async (): Promise<CloudAccessStatus> => {
+  try {
+    return await invoke<CloudAccessStatus>("get_cloud_status");
+  } catch (error) {
+    logger.error("Error fetching cloud access status.");
+    throw error;
+  }
+};
+
+/** Pauses all cloud activity until `enableCloud` is called. */
+export const disableCloud = async (): Promise<CloudAccessStatus> => {
+  try {
+    return await invoke<CloudAccessStatus>("disable_cloud");
+  } catch (error) {
+    logger.error("Error disabling cloud access.");
+    throw error;
+  }
+};
+
+export const enableCloud = async (): Promise<CloudAccessStatus> => {
+  try {
+    return await invoke<CloudAccessStatus>("enable_cloud");
+  } catch (error) {
+    logger.error("Error enabling cloud access.");
+    throw error;
+  }
+};
+
 export const backupDatabase = async (): Promise<{ filename: string }> => {
   try {
     const filename = await invoke<string>("backup_database");
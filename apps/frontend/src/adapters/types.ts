@@ -193,6 +193,8 @@ export interface PlatformCapabilities {
   connect_sync: boolean;
   device_sync: boolean;
   cloud_sync: boolean;
+  /** Scheduled syncs keep running in the background. Missing from older app builds. */
+  background_sync?: boolean;
 }
 
 export interface PlatformInfo {
@@ -202,6 +204,8 @@ export interface PlatformInfo {
   is_desktop: boolean;
   is_tauri?: boolean;
   capabilities?: PlatformCapabilities;
+  /** Resolved Connect API URL, or null when cloud features cannot work. Server only. */
+  cloud_api_base_url?: string | null;
 }
 
 // ============================================================================
@@ -235,6 +239,14 @@ export interface BackendEnableSyncResult {
   trustedDevices: TrustedDeviceSummary[];
 }
 
+/**
+ * Result from rotate_device_credential command.
+ */
+export interface BackendRotateCredentialResult {
+  deviceId: string;
+  devicePublicKey: string;
+}
+
 /**
  * Result from sync_engine_status command.
  */
@@ -251,6 +263,48 @@ export interface BackendSyncEngineStatusResult {
   bootstrapRequired: boolean;
 }
 
+export interface BackendCursorExpiryForecast {
+  cursorAgeSeconds: number | null;
+  serverRetentionDays: number | null;
+  /** False when the server retention window is unknown; expiry fields are then null. */
+  retentionKnown: boolean;
+  expiresAt: string | null;
+  daysRemaining: number | null;
+}
+
+export interface BackendSyncFieldError {
+  path: string;
+  message: string;
+  code?: string;
+}
+
+/**
+ * A local change the cloud rejected as invalid. It stays out of sync until the record is fixed.
+ */
+export interface BackendSyncRejection {
+  eventId: string;
+  entity: string;
+  entityId: string;
+  op: string;
+  errorCode: string | null;
+  errorMessage: string | null;
+  fieldErrors: BackendSyncFieldError[];
+  suggestedFix: string;
+  createdAt: string;
+}
+
+export interface BackendSyncRejectionQuery {
+  page?: number;
+  pageSize?: number;
+  /** Entity names to include, e.g. `activity`; empty or omitted means all. */
+  entities?: string[];
+}
+
+export interface BackendSyncRejectionPage {
+  items: BackendSyncRejection[];
+  totalCount: number;
+}
+
 export interface BackendSyncPairingSourceStatusResult {
   status: "ready" | "restore_required";
   message: string;
@@ -31,6 +31,7 @@ export const COMMANDS: CommandMap = {
   create_account: { method: "POST", path: "/accounts" },
   update_account: { method: "PUT", path: "/accounts" },
   delete_account: { method: "DELETE", path: "/accounts" },
+  merge_accounts: { method: "POST", path: "/accounts" },
   get_portfolios: { method: "GET", path: "/portfolios" },
   create_portfolio: { method: "POST", path: "/portfolios" },
   update_portfolio_entry: { method: "PUT", path: "/portfolios" },
@@ -38,11 +39,20 @@ export const COMMANDS: CommandMap = {
   get_settings: { method: "GET", path: "/settings" },
   update_settings: { method: "PUT", path: "/settings" },
   is_auto_update_check_enabled: { method: "GET", path: "/settings/auto-update-enabled" },
+  get_sync_quiet_hours: { method: "GET", path: "/settings/sync-quiet-hours" },
+  set_sync_quiet_hours: { method: "PUT", path: "/settings/sync-quiet-hours" },
+  get_cloud_status: { method: "GET", path: "/sync/cloud/status" },
+  disable_cloud: { method: "POST", path: "/sync/cloud/disable" },
+  enable_cloud: { method: "POST", path: "/sync/cloud/enable" },
   get_app_info: { method: "GET", path: "/app/info" },
+  get_platform: { method: "GET", path: "/platform" },
   check_update: { method: "GET", path: "/app/check-update" },
   backup_database: { method: "POST", path: "/utilities/database/backup" },
   list_database_backups: { method: "GET", path: "/utilities/database/backups" },
   delete_database_backup: { method: "DELETE", path: "/utilities/database/backups" },
+  export_holdings_csv: { method: "GET", path: "/utilities/export/holdings-csv" },
+  export_portfolio_snapshot: { method: "GET", path: "/portfolio/share-snapshot" },
+  diff_data_archives: { method: "POST", path: "/utilities/export/archive/diff" },
   get_holdings: { method: "POST", path: "/holdings/query" },
   get_holdings_list: { method: "POST", path: "/holdings/list/query" },
   get_holding: { method: "GET", path: "/holdings/item" },
@@ -53,6 +63,7 @@ export const COMMANDS: CommandMap = {
   get_current_valuation: { method: "POST", path: "/valuations/current/query" },
   get_portfolio_allocations: { method: "POST", path: "/allocations/query" },
   get_holdings_by_allocation: { method: "POST", path: "/allocations/holdings/query" },
+  get_allocation_breakdown: { method: "POST", path: "/allocations/breakdown/query" },
   // Snapshot management
   get_snapshots: { method: "GET", path: "/snapshots" },
   get_snapshot_by_date: { method: "GET", path: "/snapshots/holdings" },
@@ -62,12 +73,17 @@ export const COMMANDS: CommandMap = {
   check_holdings_import: { method: "POST", path: "/snapshots/import/check" },
   update_portfolio: { method: "POST", path: "/portfolio/update" },
   recalculate_portfolio: { method: "POST", path: "/portfolio/recalculate" },
+  validate_activity_ledger: { method: "POST", path: "/portfolio/ledger/validate" },
+  warm_analytics_caches: { method: "POST", path: "/portfolio/analytics/warmup" },
+  cancel_analytics_warmup: { method: "DELETE", path: "/portfolio/analytics/warmup" },
   // Performance
   calculate_accounts_simple_performance: { method: "POST", path: "/performance/accounts/simple" },
   calculate_performance_history: { method: "POST", path: "/performance/history" },
   calculate_performance_summary: { method: "POST", path: "/performance/summary" },
   get_performance_summaries: { method: "POST", path: "/performance/summaries" },
   get_income_summary: { method: "POST", path: "/income/summary/query" },
+  get_dividend_summary: { method: "POST", path: "/income/dividends/query" },
+  get_gains: { method: "GET", path: "/gains" },
   // Goals
   get_goals: { method: "GET", path: "/goals" },
   get_goal: { method: "GET", path: "/goals" },
@@ -104,6 +120,8 @@ export const COMMANDS: CommandMap = {
   delete_exchange_rate: { method: "DELETE", path: "/exchange-rates" },
   // Activities
   search_activities: { method: "POST", path: "/activities/search" },
+  search_activities_page: { method: "POST", path: "/activities/page" },
+  get_recent_activities: { method: "GET", path: "/activities/recent" },
   create_activity: { method: "POST", path: "/activities" },
   update_activity: { method: "PUT", path: "/activities" },
   save_activities: { method: "POST", path: "/activities/bulk" },
@@ -148,6 +166,9 @@ export const COMMANDS: CommandMap = {
   get_asset_profile: { method: "GET", path: "/assets/profile" },
   update_asset_profile: { method: "PUT", path: "/assets/profile" },
   update_quote_mode: { method: "PUT", path: "/assets/pricing-mode" },
+  normalize_symbols: { method: "POST", path: "/assets/symbols/normalize" },
+  get_symbol_mappings: { method: "GET", path: "/assets/symbol-mappings" },
+  set_symbol_mapping: { method: "PUT", path: "/assets/symbol-mappings" },
   // Market data
   search_symbol: { method: "GET", path: "/market-data/search" },
   resolve_symbol_quote: { method: "GET", path: "/market-data/resolve-currency" },
@@ -214,6 +235,7 @@ export const COMMANDS: CommandMap = {
   update_categorization_rule: { method: "PUT", path: "/spending/rules" },
   delete_categorization_rule: { method: "DELETE", path: "/spending/rules" },
   rerun_categorization_rules: { method: "POST", path: "/spending/rules/rerun" },
+  preview_categorization_rules: { method: "POST", path: "/spending/rules/preview" },
   list_rule_presets: { method: "GET", path: "/spending/rule-presets" },
   import_rule_preset: { method: "POST", path: "/spending/rule-presets" },
   remove_rule_preset: { method: "DELETE", path: "/spending/rule-presets" },
@@ -239,6 +261,7 @@ export const COMMANDS: CommandMap = {
   execute_health_fix: { method: "POST", path: "/health/fix" },
   get_health_config: { method: "GET", path: "/health/config" },
   update_health_config: { method: "PUT", path: "/health/config" },
+  get_diagnostics: { method: "GET", path: "/diagnostics" },
   // Addons
   list_installed_addons: { method: "GET", path: "/addons/installed" },
   install_addon_zip: { method: "POST", path: "/addons/install-zip" },
@@ -296,18 +319,22 @@ export const COMMANDS: CommandMap = {
   store_sync_session: { method: "POST", path: "/connect/session" },
   post_login_bootstrap: { method: "POST", path: "/connect/post-login-bootstrap" },
   clear_sync_session: { method: "DELETE", path: "/connect/session" },
+  sign_out_everywhere: { method: "POST", path: "/connect/sign-out" },
   get_sync_session_status: { method: "GET", path: "/connect/session/status" },
   restore_sync_session: { method: "GET", path: "/connect/session/restore" },
   list_broker_connections: { method: "GET", path: "/connect/connections" },
+  set_connection_name: { method: "PUT", path: "/connect/connections" },
   list_broker_accounts: { method: "GET", path: "/connect/accounts" },
   sync_broker_data: { method: "POST", path: "/connect/sync" },
   broker_ingest_run: { method: "POST", path: "/connect/sync" },
+  resync_broker_account: { method: "POST", path: "/sync/broker/accounts" },
   sync_broker_connections: { method: "POST", path: "/connect/sync/connections" },
   sync_broker_accounts: { method: "POST", path: "/connect/sync/accounts" },
   sync_broker_activities: { method: "POST", path: "/connect/sync/activities" },
   get_subscription_plans: { method: "GET", path: "/connect/plans" },
   get_subscription_plans_public: { method: "GET", path: "/connect/plans/public" },
   get_user_info: { method: "GET", path: "/connect/user" },
+  set_fake_subscription: { method: "POST", path: "/connect/debug/fake-subscription" },
   // Local data queries (from local database)
   get_synced_accounts: { method: "GET", path: "/connect/synced-accounts" },
   get_platforms: { method: "GET", path: "/connect/platforms" },
@@ -315,6 +342,10 @@ export const COMMANDS: CommandMap = {
   get_broker_ingest_states: { method: "GET", path: "/connect/sync-states" },
   get_import_runs: { method: "GET", path: "/connect/import-runs" },
   get_data_import_runs: { method: "GET", path: "/connect/import-runs" },
+  get_sync_dashboard: { method: "GET", path: "/sync/dashboard" },
+  get_connection_summary: { method: "GET", path: "/connect/connections/summary" },
+  get_expiring_connections: { method: "GET", path: "/connect/connections/expiring" },
+  get_history_backfills: { method: "GET", path: "/connect/sync/backfills" },
   get_broker_sync_profile: { method: "GET", path: "/connect/broker-sync-profile" },
   save_broker_sync_profile_rules: { method: "POST", path: "/connect/broker-sync-profile" },
   // Device Sync / Enrollment
@@ -322,7 +353,11 @@ export const COMMANDS: CommandMap = {
   enable_device_sync: { method: "POST", path: "/connect/device/enable" },
   clear_device_sync_data: { method: "DELETE", path: "/connect/device/sync-data" },
   reinitialize_device_sync: { method: "POST", path: "/connect/device/reinitialize" },
+  rotate_device_credential: { method: "POST", path: "/connect/device/rotate-credential" },
   device_sync_engine_status: { method: "GET", path: "/connect/device/engine-status" },
+  get_sync_rejections: { method: "GET", path: "/sync/rejected" },
+  acknowledge_sync_rejections: { method: "POST", path: "/sync/rejected/acknowledge" },
+  device_sync_cursor_expiry_forecast: { method: "GET", path: "/connect/device/cursor-expiry" },
   device_sync_pairing_source_status: {
     method: "GET",
     path: "/connect/device/pairing-source-status",
@@ -461,6 +496,17 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `/${data.accountId}`;
       break;
     }
+    case "merge_accounts": {
+      const { sourceAccountId, ...rest } = payload as {
+        sourceAccountId: string;
+        targetAccountId: string;
+        dryRun: boolean;
+        confirm: boolean;
+      };
+      url += `/${encodeURIComponent(sourceAccountId)}/merge`;
+      body = JSON.stringify(rest);
+      break;
+    }
     case "create_account": {
       const data = payload as { account: Record<string, unknown> };
       body = JSON.stringify(data.account);
@@ -492,6 +538,11 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       body = JSON.stringify(data.settingsUpdate);
       break;
     }
+    case "set_sync_quiet_hours": {
+      const { quietHours } = payload as { quietHours: unknown };
+      body = JSON.stringify({ quietHours });
+      break;
+    }
     case "get_holdings":
     case "get_holdings_list": {
       const p = payload as { filter: { type: string; accountId?: string } };
@@ -512,6 +563,26 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `?${params.toString()}`;
       break;
     }
+    case "export_portfolio_snapshot": {
+      const { includeAccountDetails } = (payload ?? {}) as { includeAccountDetails?: boolean };
+      url += `?includeAccountDetails=${includeAccountDetails ?? false}`;
+      break;
+    }
+    case "validate_activity_ledger": {
+      const { fix } = (payload ?? {}) as { fix?: boolean };
+      body = JSON.stringify({ fix: fix ?? false });
+      break;
+    }
+    case "get_gains": {
+      const p = (payload ?? {}) as { accountId?: string };
+      if (p.accountId) url += `?accountId=${encodeURIComponent(p.accountId)}`;
+      break;
+    }
+    case "get_recent_activities": {
+      const p = (payload ?? {}) as { limit?: number };
+      if (p.limit !== undefined) url += `?limit=${p.limit}`;
+      break;
+    }
     case "get_asset_holdings": {
       const p = payload as { assetId: string };
       url += `?assetId=${encodeURIComponent(p.assetId)}`;
@@ -602,6 +673,11 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       }
       break;
     }
+    case "get_allocation_breakdown": {
+      const { filter, dimension } = payload as { filter: unknown; dimension: string };
+      body = JSON.stringify({ filter, dimension });
+      break;
+    }
     // Snapshot management
     case "get_snapshots": {
       const { accountId, dateFrom, dateTo } = payload as {
@@ -724,6 +800,11 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       if (qs) url += `?${qs}`;
       break;
     }
+    case "get_dividend_summary": {
+      const { query, filter } = payload as { query: object; filter?: object };
+      body = JSON.stringify({ ...query, filter: filter ?? null });
+      break;
+    }
     case "get_income_summary": {
       const p = payload as { filter?: { type: string; accountId?: string } };
       if (p?.filter?.type === "account" && p.filter.accountId) {
@@ -820,7 +901,8 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
     case "get_exchanges":
     case "synch_quotes":
       break;
-    case "search_activities": {
+    case "search_activities":
+    case "search_activities_page": {
       body = JSON.stringify(payload);
       break;
     }
@@ -997,6 +1079,19 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       body = JSON.stringify({ quoteMode });
       break;
     }
+    case "normalize_symbols": {
+      const { symbols } = payload as { symbols: string[] };
+      body = JSON.stringify({ symbols });
+      break;
+    }
+    case "set_symbol_mapping": {
+      const { rawSymbol, targetSymbol } = payload as {
+        rawSymbol: string;
+        targetSymbol: string | null;
+      };
+      body = JSON.stringify({ rawSymbol, targetSymbol });
+      break;
+    }
     case "search_symbol": {
       const { query } = payload as { query: string };
       const params = new URLSearchParams();
@@ -1400,7 +1495,8 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `/${encodeURIComponent(id)}`;
       break;
     }
-    case "rerun_categorization_rules": {
+    case "rerun_categorization_rules":
+    case "preview_categorization_rules": {
       const { onlyUncategorized } = payload as { onlyUncategorized: boolean };
       body = JSON.stringify({ onlyUncategorized });
       break;
@@ -1471,6 +1567,7 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
     case "run_health_checks":
     case "get_dismissed_health_issues":
     case "get_health_config":
+    case "get_diagnostics":
       break;
     case "dismiss_health_issue": {
       const { issueId, dataHash } = payload as { issueId: string; dataHash: string };
@@ -1738,6 +1835,7 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
     case "rotate_team_keys":
     case "post_login_bootstrap":
     case "clear_sync_session":
+    case "sign_out_everywhere":
     case "get_sync_session_status":
     case "restore_sync_session":
     case "list_broker_connections":
@@ -1760,6 +1858,7 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
     case "enable_device_sync":
     case "clear_device_sync_data":
     case "reinitialize_device_sync":
+    case "rotate_device_credential":
       break;
     case "get_import_runs":
     case "get_data_import_runs": {
@@ -1776,6 +1875,23 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       if (qs) url += `?${qs}`;
       break;
     }
+    case "get_sync_rejections": {
+      const { query } = (payload ?? {}) as {
+        query?: { page?: number; pageSize?: number; entities?: string[] };
+      };
+      const params = new URLSearchParams();
+      if (query?.page !== undefined) params.set("page", String(query.page));
+      if (query?.pageSize !== undefined) params.set("pageSize", String(query.pageSize));
+      if (query?.entities?.length) params.set("entities", query.entities.join(","));
+      const qs = params.toString();
+      if (qs) url += `?${qs}`;
+      break;
+    }
+    case "acknowledge_sync_rejections": {
+      const { eventIds } = payload as { eventIds: string[] };
+      body = JSON.stringify({ eventIds });
+      break;
+    }
     case "get_broker_sync_profile": {
       const { accountId, sourceSystem } = payload as { accountId: string; sourceSystem: string };
       const params = new URLSearchParams();
@@ -1784,6 +1900,22 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `?${params.toString()}`;
       break;
     }
+    case "set_fake_subscription": {
+      const { state } = payload as { state: string | null };
+      body = JSON.stringify({ state });
+      break;
+    }
+    case "resync_broker_account": {
+      const { accountId } = payload as { accountId: string };
+      url += `/${encodeURIComponent(accountId)}/resync`;
+      break;
+    }
+    case "set_connection_name": {
+      const { connectionId, name } = payload as { connectionId: string; name: string | null };
+      url += `/${encodeURIComponent(connectionId)}/name`;
+      body = JSON.stringify({ name });
+      break;
+    }
     case "save_broker_sync_profile_rules": {
       const { request } = payload as { request: Record<string, unknown> };
       body = JSON.stringify(request);
@@ -2018,6 +2150,11 @@ export const invoke = async <T>(command: string, payload?: Record<string, unknow
       url += `/${encodeURIComponent(id)}`;
       break;
     }
+    case "diff_data_archives": {
+      const { left, right } = payload as { left: string; right: string };
+      body = JSON.stringify({ left, right });
+      break;
+    }
     case "list_agent_audit_log": {
       const { page, pageSize, q, tools, outcomes, actorKinds } = payload as {
         page: number;
@@ -128,10 +128,18 @@ export const listenPortfolioUpdateComplete = <T>(
   return portfolioEventBridge.listen("portfolio:update-complete", handler);
 };
 
+export const listenAnalyticsReady = <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
+  return portfolioEventBridge.listen("analytics:ready", handler);
+};
+
 export const listenPortfolioUpdateError = <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
   return portfolioEventBridge.listen("portfolio:update-error", handler);
 };
 
+export const listenPortfoliosChanged = <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
+  return portfolioEventBridge.listen("portfolio:portfolios-changed", handler);
+};
+
 export const listenMarketSyncStart = <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
   return portfolioEventBridge.listen("market:sync-start", handler);
 };
@@ -187,3 +195,7 @@ export const listenBrokerSyncComplete = <T>(handler: EventCallback<T>): Promise<
 export const listenBrokerSyncError = <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
   return portfolioEventBridge.listen("broker:sync-error", handler);
 };
+
+export const listenBrokerSyncSummary = <T>(handler: EventCallback<T>): Promise<UnlistenFn> => {
+  return portfolioEventBridge.listen("broker:sync-summary", handler);
+};
@@ -23,12 +23,16 @@ export type {
   AgentAuditQuery,
   AppInfo,
   BackendEnableSyncResult,
+  BackendRotateCredentialResult,
   BackendSyncBackgroundEngineResult,
   BackendSyncBootstrapOverwriteCheckResult,
   BackendSyncBootstrapResult,
   BackendSyncCycleResult,
   BackendSyncEngineStatusResult,
   BackendSyncReconcileReadyResult,
+  BackendSyncRejection,
+  BackendSyncRejectionPage,
+  BackendSyncRejectionQuery,
   BackendSyncSnapshotUploadResult,
   BackendSyncStateResult,
   CreateAgentAccessTokenInput,
@@ -87,7 +91,13 @@ export {
 } from "../shared/portfolios";
 
 // Account Commands
-export { createAccount, deleteAccount, getAccounts, updateAccount } from "../shared/accounts";
+export {
+  createAccount,
+  deleteAccount,
+  getAccounts,
+  mergeAccounts,
+  updateAccount,
+} from "../shared/accounts";
 
 // Activity Commands
 export {
@@ -114,6 +124,8 @@ export {
   saveInternalTransferPair,
   saveActivities,
   searchActivities,
+  searchActivitiesPage,
+  getRecentActivities,
   updateActivity,
 } from "../shared/activities";
 export { parseCsv } from "./activities";
@@ -167,8 +179,13 @@ export {
   calculatePerformanceHistory,
   calculatePerformanceSummary,
   calculatePerformanceSummaries,
+  cancelAnalyticsWarmup,
   checkHoldingsImport,
   deleteSnapshot,
+  exportHoldingsCsv,
+  exportPortfolioSnapshot,
+  diffDataArchives,
+  getAllocationBreakdown,
   getAssetHoldings,
   getAssetLots,
   getHistoricalValuations,
@@ -177,6 +194,8 @@ export {
   getHoldingsList,
   getHoldingsByAllocation,
   getIncomeSummary,
+  getDividendSummary,
+  getGains,
   getCurrentValuation,
   getLatestValuations,
   getPortfolioAllocations,
@@ -187,6 +206,8 @@ export {
   recalculatePortfolio,
   saveManualHoldings,
   updatePortfolio,
+  validateActivityLedger,
+  warmAnalyticsCaches,
 } from "../shared/portfolio";
 
 // Market Data Commands
@@ -203,9 +224,12 @@ export {
   getMarketDataProviders,
   getMarketDataProviderSettings,
   getQuoteHistory,
+  getSymbolMappings,
   importManualQuotes,
+  normalizeSymbols,
   resolveSymbolQuote,
   searchTicker,
+  setSymbolMapping,
   syncHistoryQuotes,
   syncMarketData,
   updateAssetProfile,
@@ -278,8 +302,11 @@ export {
   deviceSyncStopBackgroundEngine,
   enableDeviceSync,
   getBrokerSyncStates,
+  getConnectionSummary,
   getDevice,
   getDeviceSyncState,
+  getExpiringConnections,
+  getHistoryBackfills,
   getImportRuns,
   getPairingSourceStatus,
   getPairing,
@@ -287,17 +314,26 @@ export {
   getPlatforms,
   getSubscriptionPlans,
   getSubscriptionPlansPublic,
+  getSyncDashboard,
   getSyncedAccounts,
   getSyncEngineStatus,
+  getSyncRejections,
+  acknowledgeSyncRejections,
+  getCursorExpiryForecast,
   getUserInfo,
   listBrokerAccounts,
   listBrokerConnections,
+  setConnectionName,
+  setFakeSubscription,
   postLoginBootstrap,
   listDevices,
   reinitializeDeviceSync,
   resetTeamSync,
   restoreSyncSession,
+  resyncBrokerAccount,
   revokeDevice,
+  rotateDeviceCredential,
+  signOutEverywhere,
   storeSyncSession,
   syncBootstrapSnapshotIfNeeded,
   syncBrokerData,
@@ -331,6 +367,7 @@ export {
   dismissHealthIssue,
   executeHealthFix,
   getDismissedHealthIssues,
+  getDiagnostics,
   getHealthConfig,
   getHealthStatus,
   restoreHealthIssue,
@@ -381,8 +418,10 @@ export { streamAiChat } from "./ai-streaming";
 
 // Event Listeners (web-specific SSE implementation)
 export {
+  listenAnalyticsReady,
   listenBrokerSyncComplete,
   listenBrokerSyncError,
+  listenBrokerSyncSummary,
   listenBrokerSyncStart,
   listenDatabaseRestored,
   listenDeepLink,
@@ -397,6 +436,7 @@ export {
   listenPortfolioUpdateComplete,
   listenPortfolioUpdateError,
   listenPortfolioUpdateStart,
+  listenPortfoliosChanged,
 } from "./events";
 
 // File Dialogs (web-specific implementations)
@@ -416,14 +456,19 @@ export {
   backupDatabaseToPath,
   checkForUpdates,
   deleteDatabaseBackup,
+  disableCloud,
+  enableCloud,
   getAppInfo,
+  getCloudStatus,
   getDatabaseBackupDownloadUrl,
   getPlatform,
   getSettings,
+  getSyncQuietHours,
   installUpdate,
   isAutoUpdateCheckEnabled,
   listDatabaseBackups,
   restoreDatabase,
+  setSyncQuietHours,
   updateSettings,
 } from "./settings";
 export type { DatabaseBackup } from "./settings";
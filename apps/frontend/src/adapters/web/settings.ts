@@ -1,7 +1,13 @@
 // Web adapter - Settings, App Info, Updater Commands
 
 import { API_PREFIX, invoke, logger } from "./core";
-import type { Settings, UpdateInfo } from "@/lib/types";
+import type {
+  CloudAccessStatus,
+  Settings,
+  SyncQuietHours,
+  SyncQuietHoursStatus,
+  UpdateInfo,
+} from "@/lib/types";
 import type { AppInfo, PlatformInfo } from "../types";
 
 // ============================================================================
@@ -35,6 +41,55 @@ export const isAutoUpdateCheckEnabled = async (): Promise<boolean> => {
   }
 };
 
+export const getSyncQuietHours = async (): Promise<SyncQuietHoursStatus> => {
+  try {
+    return await invoke<SyncQuietHoursStatus>("get_sync_quiet_hours");
+  } catch (error) {
+    logger.error("Error fetching sync quiet hours.");
+    throw error;
+  }
+};
+
+/** Sets the automatic sync quiet hours; pass `null` to clear them. */
+export const setSyncQuietHours = async (
+  quietHours: SyncQuietHours | null,
+): Promise<SyncQuietHoursStatus> => {
+  try {
+    return await invoke<SyncQuietHoursStatus>("set_sync_quiet_hours", { quietHours });
+  } catch (error) {
+    logger.error("Error updating sync quiet hours.");
+    throw error;
+  }
+};
+
+export const getCloudStatus = async (): Promise<CloudAccessStatus> => {
+  try {
+    return await invoke<CloudAccessStatus>("get_cloud_status");
+  } catch (error) {
+    logger.error("Error fetching cloud access status.");
+    throw error;
+  }
+};
+
+/** Pauses all cloud activity until `enableCloud` is called. */
+export const disableCloud = async (): Promise<CloudAccessStatus> => {
+  try {
+    return await invoke<CloudAccessStatus>("disable_cloud");
+  } catch (error) {
+    logger.error("Error disabling cloud access.");
+    throw error;
+  }
+};
+
+export const enableCloud = async (): Promise<CloudAccessStatus> => {
+  try {
+    return await invoke<CloudAccessStatus>("enable_cloud");
+  } catch (error) {
+    logger.error("Error enabling cloud access.");
+    throw error;
+  }
+};
+
 export interface DatabaseBackup {
   filename: string;
   sizeBytes: number;
@@ -158,7 +213,17 @@ export const installUpdate = (): Promise<void> => {
 // Platform Commands
 // ============================================================================
 
-export const getPlatform = (): Promise<PlatformInfo> => {
+type ServerPlatformInfo = Pick<PlatformInfo, "capabilities" | "cloud_api_base_url">;
+
+export const getPlatform = async (): Promise<PlatformInfo> => {
+  // The client OS comes from the user agent; what the server was built with comes from the server.
+  let server: ServerPlatformInfo | undefined;
+  try {
+    server = await invoke<ServerPlatformInfo>("get_platform");
+  } catch {
+    logger.warn("Could not read server capabilities; assuming cloud features are available.");
+  }
+
   // Web environment - detect from user agent
   const userAgent = typeof window !== "undefined" ? window.navigator.userAgent.toLowerCase() : "";
   const platform =
@@ -186,15 +251,18 @@ export const getPlatform = (): Promise<PlatformInfo> => {
 
   const is_mobile = isMobileUA || isTablet;
 
-  return Promise.resolve({
+  return {
     os,
     is_mobile,
     is_desktop: !is_mobile,
     is_tauri: false,
-    capabilities: {
+    capabilities: server?.capabilities ?? {
       connect_sync: true,
       device_sync: true,
       cloud_sync: true,
+      // The server runs the sync schedule whether or not a browser is open.
+      background_sync: true,
     },
-  });
+    cloud_api_base_url: server?.cloud_api_base_url,
+  };
 };
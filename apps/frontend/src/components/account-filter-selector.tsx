@@ -37,6 +37,7 @@ function filterLabel(
   portfolios: { id: string; name: string }[],
 ): string {
   if (filter.type === "all") return "All Accounts";
+  if (filter.type === "unassigned") return "Unassigned Accounts";
   if (filter.type === "account") {
     return accounts.find((a) => a.id === filter.accountId)?.name ?? "Account";
   }
@@ -53,7 +54,7 @@ function isAccountChecked(value: AccountScope, accountId: string): boolean {
 }
 
 function ScopeIcon({ value, className }: { value: AccountScope; className?: string }) {
-  if (value.type === "portfolio") {
+  if (value.type === "portfolio" || value.type === "unassigned") {
     return <Icons.Folder className={cn("h-4 w-4 shrink-0 opacity-70", className)} />;
   }
   if (value.type === "account" || value.type === "accounts") {
@@ -170,6 +171,21 @@ function AccountScopeCommand({
                 />
               </CommandItem>
             ))}
+            <CommandItem
+              value="unassigned"
+              keywords={["Unassigned Accounts"]}
+              className={itemClassName}
+              onSelect={() => onSelect({ type: "unassigned" })}
+            >
+              <Icons.Folder className="mr-1 h-4 w-4" />
+              <span className="min-w-0 flex-1 truncate">Unassigned Accounts</span>
+              <Icons.Check
+                className={cn(
+                  "ml-auto h-4 w-4",
+                  value.type === "unassigned" ? "opacity-100" : "opacity-0",
+                )}
+              />
+            </CommandItem>
           </CommandGroup>
         )}
 
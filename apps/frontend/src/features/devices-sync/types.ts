@@ -95,11 +95,19 @@ export interface Device {
   osVersion: string | null;
   appVersion: string | null;
   lastSeenAt: string | null;
+  // Last event cursor the device pulled to, when the server reports it
+  cursor?: number | null;
   createdAt: string;
   // Client-side flag
   isCurrent?: boolean;
 }
 
+// Result of revoke_device; wasCurrent means this device's enrollment was cleared
+export interface RevokeDeviceResult {
+  deviceId: string;
+  wasCurrent: boolean;
+}
+
 // Summary of a trusted device (used in PAIR mode response)
 export interface TrustedDeviceSummary {
   id: string;
@@ -5,6 +5,7 @@ import type {
   NewCategorizationRule,
   RemovePresetResult,
   RulePresetSummary,
+  RulePreview,
   UpdateCategorizationRule,
 } from "../types/rule";
 
@@ -60,6 +61,17 @@ export const rerunCategorizationRules = async (onlyUncategorized: boolean): Prom
   }
 };
 
+export const previewCategorizationRules = async (
+  onlyUncategorized: boolean,
+): Promise<RulePreview> => {
+  try {
+    return await invoke<RulePreview>("preview_categorization_rules", { onlyUncategorized });
+  } catch (e) {
+    logger.error("Error previewing activity rules.");
+    throw e;
+  }
+};
+
 export const listRulePresets = async (): Promise<RulePresetSummary[]> => {
   try {
     return await invoke<RulePresetSummary[]>("list_rule_presets");
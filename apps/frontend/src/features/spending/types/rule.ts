@@ -49,6 +49,21 @@ export interface UpdateCategorizationRule {
   accountId?: string | null;
 }
 
+export interface RulePreviewChange {
+  activityId: string;
+  ruleId: string;
+  ruleName: string;
+  taxonomyId: string;
+  categoryId: string;
+  /** Category the rule would replace; null for uncategorized activities. */
+  previousCategoryId?: string | null;
+}
+
+export interface RulePreview {
+  matched: number;
+  changes: RulePreviewChange[];
+}
+
 export interface RulePresetSummary {
   presetId: string;
   presetVersion: string;
@@ -297,7 +297,10 @@ function BrokerConnectionsCard({
                 connection.brokerage?.display_name ??
                 connection.brokerage?.name ??
                 "Unknown Broker";
-              const isConnected = connection.status === "connected" && !connection.disabled;
+              const isConnected = connection.health
+                ? connection.health !== "BROKEN"
+                : connection.status === "connected" && !connection.disabled;
+              const isDegraded = connection.health === "DEGRADED";
 
               return (
                 <div
@@ -319,12 +322,12 @@ function BrokerConnectionsCard({
                   </span>
                   <Badge
                     className={`shrink-0 ${
-                      isConnected
+                      isConnected && !isDegraded
                         ? "bg-green-100 text-green-700 dark:bg-green-900/30 dark:text-green-400"
                         : "bg-yellow-100 text-yellow-700 dark:bg-yellow-900/30 dark:text-yellow-400"
                     }`}
                   >
-                    {isConnected ? "Connected" : "Disconnected"}
+                    {isDegraded ? "Retrying" : isConnected ? "Connected" : "Disconnected"}
                   </Badge>
                 </div>
               );
@@ -445,6 +448,11 @@ export function ConnectedView() {
                     Active
                   </Badge>
                 )}
+                {userInfo?.team?.simulated_subscription && (
+                  <Badge variant="destructive" className="h-5 shrink-0 px-2 text-[10px] font-medium">
+                    Simulated: {userInfo.team.simulated_subscription}
+                  </Badge>
+                )}
               </div>
               <p className="text-muted-foreground truncate text-sm">{user?.email}</p>
             </div>
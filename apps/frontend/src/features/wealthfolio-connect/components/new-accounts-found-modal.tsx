@@ -107,8 +107,13 @@ export function NewAccountsFoundModal({
       onOpenChange(false);
       onComplete?.();
 
-      // Trigger broker sync to import data for the now-configured accounts
-      syncBrokerData();
+      // Trigger broker sync to import data for the now-configured accounts. A sync just ran,
+      // so the manual sync limit may reject this one; the next sync then picks them up.
+      syncBrokerData().catch((error: unknown) => {
+        toast.info("Accounts will sync on the next run", {
+          description: error instanceof Error ? error.message : String(error),
+        });
+      });
     } catch (error) {
       toast.error("Failed to save accounts", {
         description: String(error),
@@ -519,13 +519,19 @@ function ConnectionItem({
   totalAccountCount: number;
 }) {
   const name =
+    connection.custom_name ||
     connection.brokerage?.display_name ||
     connection.brokerage?.name ||
     connection.name ||
     "Unknown";
   const logoUrl =
     connection.brokerage?.aws_s3_square_logo_url ?? connection.brokerage?.aws_s3_logo_url;
-  const isConnected = connection.status === "connected" && !connection.disabled;
+  // A single auth failure only marks the connection degraded; ask for a reconnect once it is
+  // broken. Without a health classification, fall back to the cloud status.
+  const isConnected = connection.health
+    ? connection.health !== "BROKEN"
+    : connection.status === "connected" && !connection.disabled;
+  const isDegraded = connection.health === "DEGRADED";
   const syncSummary = getConnectionSyncSummary(syncEnabledCount, totalAccountCount);
 
   return (
@@ -543,12 +549,12 @@ function ConnectionItem({
       <div className="flex shrink-0 items-center gap-2">
         <Badge
           className={`shrink-0 ${
-            isConnected
+            isConnected && !isDegraded
               ? "bg-green-100 text-green-700 dark:bg-green-900/30 dark:text-green-400"
               : "bg-yellow-100 text-yellow-700 dark:bg-yellow-900/30 dark:text-yellow-400"
           }`}
         >
-          {isConnected ? "Connected" : "Disconnected"}
+          {isDegraded ? "Retrying" : isConnected ? "Connected" : "Disconnected"}
         </Badge>
         {!isConnected && (
           <Button
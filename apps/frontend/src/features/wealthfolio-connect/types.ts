@@ -1,6 +1,8 @@
 // Wealthfolio Connect Types
 // =========================
 
+import type { CurrencyMismatch } from "@/lib/types";
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Broker Sync Types
 // ─────────────────────────────────────────────────────────────────────────────
@@ -24,6 +26,7 @@ export interface SyncActivitiesResponse {
   assets_inserted: number;
   accounts_failed: number;
   accounts_warned?: number;
+  currency_mismatches?: CurrencyMismatch[];
 }
 
 export interface SyncResult {
@@ -32,8 +35,41 @@ export interface SyncResult {
   connectionsSynced: SyncConnectionsResponse | null;
   accountsSynced: SyncAccountsResponse | null;
   activitiesSynced: SyncActivitiesResponse | null;
+  /** Set when the sync ran without fetching broker data. */
+  skipReason?: SyncSkipReason | null;
+  /** What each broker connection contributed to the totals above. */
+  connectionResults?: ConnectionSyncResult[];
+  /** The sync did not succeed, but at least one connection still synced. */
+  partialSuccess?: boolean;
+}
+
+export interface ConnectionSyncResult {
+  connectionId: string;
+  connectionName: string | null;
+  accountsSynced: number;
+  activitiesUpserted: number;
+  positionsUpserted: number;
+  accountsFailed: number;
+  accountsWarned: number;
+  /** Why the connection's accounts could not be fetched. */
+  error?: string;
+  accounts?: AccountSyncResult[];
+}
+
+export interface AccountSyncResult {
+  accountId: string;
+  accountName: string;
+  activitiesUpserted: number;
+  positionsUpserted: number;
+  failed: boolean;
+  warned: boolean;
+  /** Excluded from sync by configuration. */
+  skipped: boolean;
 }
 
+/** `NO_CONNECTIONS`: signed in, but no broker connected yet. */
+export type SyncSkipReason = "NO_CONNECTIONS";
+
 export interface BrokerConnectionBrokerage {
   id?: string;
   slug?: string;
@@ -43,6 +79,9 @@ export interface BrokerConnectionBrokerage {
   aws_s3_square_logo_url?: string;
 }
 
+/** Local classification from consecutive auth failures seen during sync. */
+export type ConnectionHealth = "HEALTHY" | "DEGRADED" | "BROKEN";
+
 export interface BrokerConnection {
   id: string;
   brokerage?: BrokerConnectionBrokerage;
@@ -51,6 +90,11 @@ export interface BrokerConnection {
   updated_at?: string;
   status?: string;
   name?: string;
+  /** When the broker's consent lapses (RFC 3339), for brokers that expire it. */
+  consent_expires_at?: string;
+  /** Local display name; falls back to the cloud-provided name when unset. */
+  custom_name?: string;
+  health?: ConnectionHealth;
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -154,8 +198,13 @@ export interface UserTeam {
   canceled_at: string | null;
   country_code: string | null;
   created_at: string | null;
+  /** Set when plan and status come from `set_fake_subscription`, not real billing. */
+  simulated_subscription?: FakeSubscriptionState;
 }
 
+/** Subscription states `set_fake_subscription` can simulate (debug builds only). */
+export type FakeSubscriptionState = "free" | "pro" | "expired";
+
 export type DateFormat = "dd/MM/yyyy" | "MM/dd/yyyy" | "yyyy-MM-dd" | "dd.MM.yyyy";
 
 export interface UserInfo {
@@ -233,6 +282,122 @@ export interface ImportRun {
   updatedAt: string;
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Sync Dashboard Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export interface SyncConnectionHealth {
+  accountId: string;
+  provider: string;
+  status: SyncStatus;
+  lastAttemptedAt: string | null;
+  lastSuccessfulAt: string | null;
+  lastError: string | null;
+}
+
+export interface SyncDashboard {
+  state: SyncStatus;
+  lastAttemptAt: string | null;
+  lastSuccessAt: string | null;
+  runsInWindow: number;
+  /** Between 0 and 1 over the last 30 days; null when no runs finished in that window. */
+  successRate: number | null;
+  averageDurationMs: number | null;
+  /** More runs fell in the window than are read; the window figures cover the newest ones. */
+  windowTruncated: boolean;
+  totalActivitiesSynced: number;
+  connections: SyncConnectionHealth[];
+  /** Holdings were not recomputed after sync (`BROKER_SYNC_AUTO_RECOMPUTE=false`). */
+  holdingsRecomputePending: boolean;
+  /** Scheduled sync stopped retrying after repeated permanent failures. */
+  schedulerSuspension: SyncSuspension | null;
+}
+
+export interface SyncSuspension {
+  suspendedAt: string;
+  consecutiveFailures: number;
+  lastError: string;
+}
+
+/** Connection counts per health class, as of the last sync. */
+export interface ConnectionSummary {
+  total: number;
+  healthy: number;
+  degraded: number;
+  broken: number;
+  /** Connections the broker disabled; the user has to sign in again. */
+  needsReauth: number;
+  /** Connections whose broker consent lapses within the warning lead time. */
+  expiring: number;
+  lastSyncAt: string | null;
+}
+
+export type BrokerSyncTrigger = "scheduler" | "manual" | "login";
+
+/** One finished broker sync run (`broker:sync-summary`), successful or not. */
+export interface BrokerSyncSummary {
+  triggeredBy: BrokerSyncTrigger;
+  success: boolean;
+  activitiesUpserted: number;
+  connectionsSynced: number;
+  durationMs: number;
+  finishedAt: string;
+  skipReason?: "NO_CONNECTIONS";
+  /** Why the run failed; absent when it completed. */
+  error?: string;
+  /** Correlation ID the run's server log lines carry. */
+  syncId?: string;
+}
+
+/** A connection to reconnect before its broker consent lapses (`connection:expiring`). */
+export interface ConnectionExpiry {
+  connectionId: string;
+  name: string | null;
+  expiresAt: string;
+  /** Whole days left; negative once consent has lapsed. */
+  daysRemaining: number;
+  health: ConnectionHealth;
+}
+
+export type HistoryBackfillStatus = "PENDING" | "RUNNING" | "COMPLETE" | "FAILED";
+
+/** Older activity history fetched in the background after an account's first sync. */
+export interface HistoryBackfillJob {
+  accountId: string;
+  accountName: string;
+  brokerAccountId: string;
+  /** Activities up to this date (YYYY-MM-DD) are backfilled. */
+  endDate: string;
+  status: HistoryBackfillStatus;
+  fetched: number;
+  inserted: number;
+  error: string | null;
+  importRunId: string | null;
+  scheduledAt: string;
+  updatedAt: string;
+}
+
+/** What a single-account resync changed. */
+export interface AccountResyncResult {
+  accountId: string;
+  accountName: string;
+  activitiesFetched: number;
+  activitiesUpserted: number;
+  assetsInserted: number;
+  needsReview: number;
+  /** Local synced activities the cloud no longer returns, now deleted. */
+  removedActivityIds: string[];
+  /** Activities the cloud no longer returns that were kept because they were edited locally. */
+  preservedActivityIds: string[];
+  /** Holdings refreshed for holdings-tracked accounts. */
+  holdings?: {
+    snapshotsUpserted: number;
+    positionsUpserted: number;
+    assetsInserted: number;
+    accountsFailed: number;
+  };
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Aggregated Sync Status (for navigation icon)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -16,6 +16,8 @@ function currentValuationScopeKey(filter: AccountScope): string {
   switch (filter.type) {
     case "all":
       return "all";
+    case "unassigned":
+      return "unassigned";
     case "account":
       return `account:${filter.accountId}`;
     case "portfolio":
@@ -13,6 +13,7 @@ export function useHoldings(accountFilter: AccountScope) {
       case "portfolio":
         return accountFilter.portfolioId.trim().length > 0;
       case "all":
+      case "unassigned":
         return true;
       default:
         return false;
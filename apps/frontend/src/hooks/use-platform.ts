@@ -14,6 +14,7 @@ function defaultCapabilities(): PlatformCapabilities {
     connect_sync: true,
     device_sync: true,
     cloud_sync: true,
+    background_sync: true,
   };
 }
 
@@ -28,6 +29,8 @@ export interface UsePlatformResult {
   isLinux: boolean;
   isWeb: boolean;
   isTauri: boolean;
+  /** Whether scheduled syncs run while the app is in the background. */
+  supportsBackgroundSync: boolean;
   loading: boolean;
 }
 
@@ -85,6 +88,8 @@ export function usePlatform(): UsePlatformResult {
     isLinux: platform?.os === "linux",
     isWeb,
     isTauri,
+    supportsBackgroundSync:
+      platform?.capabilities?.background_sync ?? !(platform?.is_mobile ?? false),
     loading,
   };
 }
@@ -12,6 +12,17 @@ export function usePortfolios() {
   });
 }
 
+/** Accounts that belong to no saved portfolio, the "Unassigned" default group. */
+export function unassignedAccountIds(
+  accounts: { id: string; isArchived?: boolean }[],
+  portfolios: PortfolioWithAccounts[],
+): string[] {
+  const assigned = new Set(portfolios.flatMap((portfolio) => portfolio.accountIds));
+  return accounts
+    .filter((account) => !account.isArchived && !assigned.has(account.id))
+    .map((account) => account.id);
+}
+
 export function usePortfolioMutations() {
   const queryClient = useQueryClient();
 
@@ -71,7 +71,8 @@ export type AccountScope =
   | { type: "all" }
   | { type: "account"; accountId: string }
   | { type: "portfolio"; portfolioId: string }
-  | { type: "accounts"; accountIds: string[] };
+  | { type: "accounts"; accountIds: string[] }
+  | { type: "unassigned" };
 
 export interface Account {
   id: string;
@@ -93,6 +94,17 @@ export interface Account {
   providerAccountId?: string; // Optional - account ID in the provider's system
 }
 
+/** Outcome of merging a duplicate account, or its preview when `dryRun` is set. */
+export interface AccountMergeResult {
+  sourceAccountId: string;
+  sourceAccountName: string;
+  targetAccountId: string;
+  targetAccountName: string;
+  movedActivityIds: string[];
+  duplicateActivityIds: string[];
+  dryRun: boolean;
+}
+
 /**
  * Activity interface matching the new backend model
  * @deprecated Use the new Activity interface with activityType field
@@ -245,6 +257,11 @@ export interface ActivityDetails {
   subRows?: ActivityDetails[];
 }
 
+/** Activity in the cross-account recent feed. */
+export interface RecentActivity extends ActivityDetails {
+  brokerName?: string | null;
+}
+
 export interface ActivitySearchResponse {
   data: ActivityDetails[];
   meta: {
@@ -252,6 +269,12 @@ export interface ActivitySearchResponse {
   };
 }
 
+/** Keyset page of activities, newest first. `nextCursor` is null on the last page. */
+export interface ActivityPage {
+  data: ActivityDetails[];
+  nextCursor: string | null;
+}
+
 /**
  * Input used by the backend to resolve an activity asset before persistence.
  */
@@ -535,6 +558,28 @@ export interface ResolvedQuote {
   resolvedProviderId?: string;
 }
 
+export type SymbolMatchStatus = "MATCHED" | "OVERRIDDEN" | "AMBIGUOUS" | "UNMATCHED";
+
+export interface SymbolReference {
+  assetId: string;
+  symbol: string;
+  exchangeMic?: string | null;
+  name?: string | null;
+  instrumentType?: string | null;
+}
+
+/** How a raw symbol normalizes against the asset catalog. */
+export interface SymbolNormalization {
+  rawSymbol: string;
+  status: SymbolMatchStatus;
+  canonicalSymbol?: string | null;
+  exchangeMic?: string | null;
+  assetId?: string | null;
+  instrumentType?: string | null;
+  /** Listings an ambiguous symbol could mean. */
+  candidates?: SymbolReference[];
+}
+
 export interface ExchangeInfo {
   mic: string;
   name: string;
@@ -586,6 +631,24 @@ export interface ImportActivitiesResult {
   importRunId: string;
   /** Summary statistics for the import */
   summary: ImportActivitiesSummary;
+  /** Activities whose currency differs from their account currency, when a policy is configured */
+  currencyMismatches?: CurrencyMismatch[];
+}
+
+/**
+ * An activity whose currency differs from its account currency, and how it was handled
+ */
+export interface CurrencyMismatch {
+  activityId?: string | null;
+  accountId: string;
+  activityDate: string;
+  activityCurrency: string;
+  accountCurrency: string;
+  resolution: "CONVERTED" | "FLAGGED";
+  /** Rate from the activity currency to the account currency, when converted */
+  fxRate?: number | null;
+  /** Why the activity was flagged */
+  reason?: string | null;
 }
 
 /**
@@ -851,6 +914,27 @@ export interface Settings {
   syncEnabled: boolean;
 }
 
+/** Daily window, in `HH:MM` local time, during which automatic sync does not run */
+export interface SyncQuietHours {
+  start: string;
+  end: string;
+  /** IANA timezone the window is expressed in */
+  timezone: string;
+}
+
+/** Cloud kill switch: while `disabled`, no cloud sync or subscription calls are made */
+export interface CloudAccessStatus {
+  disabled: boolean;
+}
+
+export interface SyncQuietHoursStatus {
+  quietHours: SyncQuietHours | null;
+  /** Whether quiet hours are active right now */
+  active: boolean;
+  /** Earliest time an automatic sync may run */
+  nextSyncAt: string;
+}
+
 export interface SettingsContextType {
   settings: Settings | null;
   isLoading: boolean;
@@ -966,6 +1050,92 @@ export interface IncomeSummary {
   yoyGrowth: number | null; // Changed from optional to nullable
 }
 
+export type DividendGroupBy = "symbol" | "account" | "month";
+
+export interface DividendSummaryQuery {
+  from?: string;
+  to?: string;
+  groupBy?: DividendGroupBy;
+  /** Adds forward income projected from the last 12 months */
+  includeProjection?: boolean;
+}
+
+/** Dividends of one symbol, account or month, in base currency */
+export interface DividendGroup {
+  /** Asset id, account id or `YYYY-MM` */
+  key: string;
+  label: string;
+  cash: number;
+  /** Paid in shares (DRIP, dividend in kind) */
+  reinvested: number;
+  total: number;
+  payments: number;
+}
+
+export interface DividendSummary {
+  currency: string;
+  groupBy: DividendGroupBy;
+  from: string | null;
+  to: string | null;
+  groups: DividendGroup[];
+  cashTotal: number;
+  reinvestedTotal: number;
+  total: number;
+  projection?: { annual: number; monthly: number; basedOnFrom: string };
+}
+
+/** Realized and unrealized gains for one holding, in base currency */
+/** Payload of the `analytics:ready` event: what a cache warmup computed. */
+export interface AnalyticsWarmupReport {
+  accountCount: number;
+  allocationSlices: number;
+  gainsHoldings: number;
+  valuationPoints: number;
+  cancelled: boolean;
+}
+
+export interface HoldingGains {
+  assetId: string;
+  symbol: string;
+  quantity: number;
+  costBasis: number;
+  /** Null when the holding has no current price */
+  marketValue: number | null;
+  realized: number;
+  unrealized: number | null;
+  /** Fraction of the cost of all lots */
+  totalReturnPct: number | null;
+  currency: string;
+  /** A lot has no cost (missing price or FX rate), or more was sold than held */
+  incompleteCostBasis: boolean;
+}
+
+export type LedgerIssueKind =
+  | "SELL_WITHOUT_HOLDING"
+  | "NEGATIVE_QUANTITY"
+  | "HOLDING_MISMATCH"
+  | "REJECTED_ACTIVITY";
+
+export interface LedgerIssue {
+  kind: LedgerIssueKind;
+  accountId: string;
+  assetId: string;
+  message: string;
+  /** Offending activities, oldest first */
+  activityIds: string[];
+  expectedQuantity: number | null;
+  actualQuantity: number | null;
+  /** Resolved by recomputing the account's holdings */
+  fixable: boolean;
+}
+
+export interface LedgerValidationReport {
+  checkedAccounts: number;
+  checkedActivities: number;
+  issues: LedgerIssue[];
+  recalculatedAccountIds: string[];
+}
+
 // Define custom DateRange type matching react-day-picker's
 export interface DateRange {
   from: Date | undefined;
@@ -1029,6 +1199,8 @@ export interface CurrentAccountValuation {
   sourceDataAsOf: string | null;
   calculatedAt: string;
   warnings: string[];
+  stale?: boolean;
+  oldestQuoteAgeSeconds?: number | null;
 }
 
 export interface CurrentValuationSplit {
@@ -1051,6 +1223,8 @@ export interface CurrentValuationSummary {
   sourceDataAsOf: string | null;
   calculatedAt: string;
   warnings: string[];
+  stale?: boolean;
+  oldestQuoteAgeSeconds?: number | null;
 }
 
 export interface CurrentValuationResponse {
@@ -1888,6 +2062,14 @@ export interface PortfolioAllocations {
   totalValue: number;
 }
 
+export type AllocationDimension = "asset_class" | "sector" | "currency" | "account";
+
+export interface AllocationSlice {
+  label: string; // "Unclassified" when a holding has no value for the dimension
+  value: number; // Base currency value
+  percent: number; // 0-100, slices sum to exactly 100
+}
+
 export interface MigrationResult {
   sectorsMigrated: number;
   countriesMigrated: number;
@@ -2278,6 +2460,61 @@ export interface ImportHoldingsCsvResult {
   errors: string[];
 }
 
+/**
+ * Record-level differences between two data archives
+ */
+export interface ArchiveDiff {
+  leftCreatedAt: string;
+  rightCreatedAt: string;
+  added: number;
+  removed: number;
+  changed: number;
+  sections: ArchiveSectionDiff[];
+}
+
+export interface ArchiveSectionDiff {
+  name: string;
+  added: number;
+  removed: number;
+  changed: number;
+  unchanged: number;
+  records: ArchiveRecordChange[];
+}
+
+export interface ArchiveRecordChange {
+  id: string;
+  kind: "ADDED" | "REMOVED" | "CHANGED";
+  /** Changed top-level fields; empty for added and removed records */
+  fields: { field: string; before: unknown; after: unknown }[];
+}
+
+/**
+ * Read-only portfolio snapshot for sharing; values are in the base currency.
+ * Account details are only present when opted in.
+ */
+export interface ShareSnapshot {
+  version: number;
+  generatedAt: string;
+  baseCurrency: string;
+  totalValue: number;
+  holdings: {
+    symbol?: string | null;
+    name?: string | null;
+    holdingType: HoldingType;
+    assetKind?: AssetKind | null;
+    quantity: number;
+    marketValue: number;
+    weight: number;
+  }[];
+  allocation: { category: string; marketValue: number; weight: number }[];
+  accounts?: {
+    name: string;
+    accountType: string;
+    currency: string;
+    accountNumber?: string | null;
+  }[];
+}
+
 /**
  * Result of checking a single symbol during holdings import
  */
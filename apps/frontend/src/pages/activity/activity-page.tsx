@@ -8,7 +8,7 @@ import {
 import { useSpendingSettings } from "@/features/spending/hooks/use-spending-settings";
 import { SyncButton } from "@/features/wealthfolio-connect/components/sync-button";
 import { usePersistentState } from "@/hooks/use-persistent-state";
-import { usePortfolios } from "@/hooks/use-portfolios";
+import { unassignedAccountIds, usePortfolios } from "@/hooks/use-portfolios";
 import { useIsCompactTableViewport, useIsMobileViewport } from "@/hooks/use-platform";
 import { getActivityRestrictionLevel } from "@/lib/activity-restrictions";
 import { ActivityType } from "@/lib/constants";
@@ -360,8 +360,9 @@ const ActivityPage = () => {
     if (accountScope.type === "portfolio") {
       return portfolios.find((p) => p.id === accountScope.portfolioId)?.accountIds ?? [];
     }
+    if (accountScope.type === "unassigned") return unassignedAccountIds(accounts, portfolios);
     return undefined; // "all" → no filter
-  }, [accountScope, portfolios]);
+  }, [accountScope, accounts, portfolios]);
 
   // Accounts opted into the Spending module are shown on the Spending tab; the
   // Investments tab must exclude them so cash/credit-card activity doesn't double-up.
@@ -4,6 +4,7 @@ type TargetScope = Pick<AllocationTarget, "scopeType" | "scopeId">;
 
 export function accountScopeKey(scope: AccountScope): string {
   if (scope.type === "all") return "all";
+  if (scope.type === "unassigned") return "unassigned";
   if (scope.type === "account") return `account:${scope.accountId}`;
   if (scope.type === "portfolio") return `portfolio:${scope.portfolioId}`;
   return `accounts:${[...scope.accountIds].sort().join(",")}`;
@@ -2,7 +2,7 @@ import { useAccounts } from "@/hooks/use-accounts";
 import { useCurrentValuation } from "@/hooks/use-current-account-valuations";
 import { useHoldings } from "@/hooks/use-holdings";
 import { usePortfolioAllocations } from "@/hooks/use-portfolio-allocations";
-import { usePortfolios } from "@/hooks/use-portfolios";
+import { unassignedAccountIds, usePortfolios } from "@/hooks/use-portfolios";
 import { HoldingType, isAlternativeAssetKind } from "@/lib/constants";
 import { useSettingsContext } from "@/lib/settings-provider";
 import type { AccountScope, AllocationTarget, TaxonomyAllocation } from "@/lib/types";
@@ -116,8 +116,9 @@ export function OverviewPage({
     if (accountFilter.type === "portfolio") {
       return portfolios.find((p) => p.id === accountFilter.portfolioId)?.accountIds ?? [];
     }
+    if (accountFilter.type === "unassigned") return unassignedAccountIds(accounts, portfolios);
     return undefined; // "all" → every account
-  }, [accountFilter, portfolios]);
+  }, [accountFilter, accounts, portfolios]);
 
   const portfolioHoldings = useMemo(
     () =>
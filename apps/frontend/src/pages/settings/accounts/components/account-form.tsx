@@ -7,6 +7,7 @@ import { Button } from "@wealthfolio/ui/components/ui/button";
 import { Switch } from "@wealthfolio/ui/components/ui/switch";
 
 import { newAccountSchema } from "@/lib/schemas";
+import { TimezoneInput } from "@/pages/settings/general/timezone-input";
 import { AccountType } from "@/lib/constants";
 import { useTaxonomy } from "@/hooks/use-taxonomies";
 import { cn } from "@/lib/utils";
@@ -80,6 +81,33 @@ function setCashCategoryInMeta(meta: string | null | undefined, categoryId: stri
   return JSON.stringify(parsed);
 }
 
+function getActivityTimezoneFromMeta(meta?: string | null): string {
+  if (!meta) return "";
+  try {
+    const parsed = JSON.parse(meta) as Record<string, unknown>;
+    return typeof parsed.activityTimezone === "string" ? parsed.activityTimezone : "";
+  } catch {
+    return "";
+  }
+}
+
+function setActivityTimezoneInMeta(meta: string | null | undefined, timezone: string): string {
+  let parsed: Record<string, unknown> = {};
+  if (meta) {
+    try {
+      parsed = JSON.parse(meta) as Record<string, unknown>;
+    } catch {
+      // ignore
+    }
+  }
+  if (timezone) {
+    parsed.activityTimezone = timezone;
+  } else {
+    delete parsed.activityTimezone;
+  }
+  return JSON.stringify(parsed);
+}
+
 function getSelectableCashCategoryFromMeta(meta?: string | null): string {
   const categoryId = getCashCategoryFromMeta(meta);
   return categoryId === CASH_FIXED_INCOME_CATEGORY_ID
@@ -145,6 +173,20 @@ export function AccountForm({ defaultValues, onSuccess = () => undefined }: Acco
   const isCreditCardAccount = currentAccountType === AccountType.CREDIT_CARD;
   const isCashAccount = currentAccountType === AccountType.CASH;
 
+  const timezones = useMemo(() => {
+    const supportedValuesOf = (
+      Intl as unknown as { supportedValuesOf?: (key: "timeZone") => string[] }
+    ).supportedValuesOf;
+    const raw = typeof supportedValuesOf === "function" ? supportedValuesOf("timeZone") : [];
+    const merged = raw.includes("UTC") ? raw : ["UTC", ...raw];
+    return Array.from(new Set(merged)).sort((a, b) => a.localeCompare(b));
+  }, []);
+  const activityTimezone = getActivityTimezoneFromMeta(form.watch("meta"));
+  const setActivityTimezone = (timezone: string) =>
+    form.setValue("meta", setActivityTimezoneInMeta(form.getValues("meta"), timezone), {
+      shouldDirty: true,
+    });
+
   const { data: assetClassesTaxonomy } = useTaxonomy(isCashAccount ? "asset_classes" : null);
   const fixedIncomeCategoryName = useMemo(() => {
     return (
@@ -311,6 +353,34 @@ export function AccountForm({ defaultValues, onSuccess = () => undefined }: Acco
                 />
               ) : null}
 
+              <div className="flex flex-col gap-2">
+                <div>
+                  <label className="text-sm font-medium">Activity Timezone</label>
+                  <p className="text-muted-foreground text-xs">
+                    Imported and synced dates without a timezone are read in this zone
+                  </p>
+                </div>
+                <div className="flex gap-2">
+                  <TimezoneInput
+                    value={activityTimezone}
+                    onChange={setActivityTimezone}
+                    timezones={timezones}
+                    placeholder="Not set (UTC)"
+                  />
+                  {activityTimezone ? (
+                    <Button
+                      type="button"
+                      variant="ghost"
+                      size="icon"
+                      aria-label="Clear activity timezone"
+                      onClick={() => setActivityTimezone("")}
+                    >
+                      <Icons.Close className="h-4 w-4" />
+                    </Button>
+                  ) : null}
+                </div>
+              </div>
+
               {isCashAccount && (
                 <div className="flex flex-col gap-2">
                   <div>
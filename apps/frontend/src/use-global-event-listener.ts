@@ -10,12 +10,15 @@ import {
   listenPortfolioUpdateComplete,
   listenPortfolioUpdateError,
   listenPortfolioUpdateStart,
+  listenPortfoliosChanged,
   logger,
   updatePortfolio,
 } from "@/adapters";
 import { usePortfolioSyncOptional } from "@/context/portfolio-sync-context";
 import { useIsMobileViewport } from "@/hooks/use-platform";
+import { invalidatePerformanceCaches } from "@/lib/performance-cache";
 import { shouldInvalidateAfterPortfolioUpdate } from "@/lib/query-invalidation";
+import { QueryKeys } from "@/lib/query-keys";
 import { useQueryClient } from "@tanstack/react-query";
 import { useEffect, useRef, useState } from "react";
 import { useNavigate } from "react-router-dom";
@@ -163,6 +166,12 @@ const useGlobalEventListener = () => {
       });
     };
 
+    // Portfolios can change from another client (the web app or the desktop window).
+    const handlePortfoliosChanged = () => {
+      queryClientRef.current.invalidateQueries({ queryKey: [QueryKeys.PORTFOLIOS] });
+      invalidatePerformanceCaches(queryClientRef.current);
+    };
+
     const handleBrokerSyncComplete = (event: {
       payload: {
         success: boolean;
@@ -183,13 +192,21 @@ const useGlobalEventListener = () => {
           currency: string;
           institutionName?: string;
         }[];
+        skipReason?: "NO_CONNECTIONS" | null;
       };
     }) => {
-      const { success, message, accountsSynced, activitiesSynced, holdingsSynced, newAccounts } =
-        event.payload || {
-          success: false,
-          message: "Unknown error",
-        };
+      const {
+        success,
+        message,
+        accountsSynced,
+        activitiesSynced,
+        holdingsSynced,
+        newAccounts,
+        skipReason,
+      } = event.payload || {
+        success: false,
+        message: "Unknown error",
+      };
 
       // Dismiss the loading toast
       toast.dismiss(TOAST_IDS.brokerSyncStart);
@@ -198,8 +215,19 @@ const useGlobalEventListener = () => {
       queryClientRef.current.invalidateQueries();
 
       if (success) {
-        // Check if there are new accounts that need configuration
-        if (newAccounts && newAccounts.length > 0) {
+        if (skipReason === "NO_CONNECTIONS") {
+          toast.info("No broker connected", {
+            description: "Connect a broker to start syncing",
+            action: {
+              label: "Connect",
+              onClick: () => {
+                navigateRef.current("/connect");
+              },
+            },
+            duration: 10000,
+          });
+        } else if (newAccounts && newAccounts.length > 0) {
+          // New accounts need configuration
           toast.info("New accounts found", {
             description: `${newAccounts.length} new account(s) need to be configured`,
             action: {
@@ -280,6 +308,7 @@ const useGlobalEventListener = () => {
         ["market-sync-complete", listenMarketSyncComplete(handleMarketSyncComplete)],
         ["market-sync-error", listenMarketSyncError(handleMarketSyncError)],
         ["database-restored", listenDatabaseRestored(handleDatabaseRestored)],
+        ["portfolios-changed", listenPortfoliosChanged(handlePortfoliosChanged)],
         ["broker-sync-complete", listenBrokerSyncComplete(handleBrokerSyncComplete)],
         ["broker-sync-error", listenBrokerSyncError(handleBrokerSyncError)],
       ];
@@ -28,6 +28,7 @@ mod ai_providers;
 mod allocation_targets;
 mod alternative_assets;
 mod assets;
+mod cloud_access;
 #[cfg(any(feature = "connect-sync", feature = "device-sync"))]
 pub mod connect;
 mod custom_providers;
@@ -37,22 +38,29 @@ mod database_backups;
 mod device_sync;
 #[cfg(feature = "device-sync")]
 pub(crate) mod device_sync_engine;
+mod diagnostics;
 mod exchange_rates;
 mod goals;
 mod health;
 mod holdings;
 mod limits;
 mod market_data;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod net_worth;
+mod notifications;
 mod performance;
 mod portfolio;
 mod portfolios;
 mod secrets;
 mod settings;
+mod share_links;
 pub mod shared;
 mod spending;
 #[cfg(feature = "device-sync")]
 mod sync_crypto;
+#[cfg(feature = "connect-sync")]
+pub mod sync_webhook;
 mod taxonomies;
 
 #[utoipa::path(get, path = "/api/v1/healthz", responses((status = 200, description = "Health")))]
@@ -112,14 +120,18 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         .merge(addons::router())
         .merge(taxonomies::router())
         .merge(net_worth::router())
+        .merge(notifications::router())
         .merge(alternative_assets::router())
         .merge(ai_providers::router())
         .merge(ai_chat::router())
         .merge(health::router())
+        .merge(diagnostics::router())
         .merge(custom_providers::router())
         .merge(spending::router())
         .merge(allocation_targets::router())
-        .merge(agent_access::router());
+        .merge(agent_access::router())
+        .merge(share_links::router())
+        .merge(cloud_access::router());
 
     #[cfg(feature = "device-sync")]
     {
@@ -169,6 +181,16 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         .finish()
         .expect("valid governor config");
 
+    // Share link tokens grant read access to one frozen snapshot instead of a user session.
+    #[allow(unused_mut)]
+    let mut public_api = Router::new().merge(share_links::public_router());
+
+    // Signed by the cloud with a shared secret instead of a user session.
+    #[cfg(feature = "connect-sync")]
+    {
+        public_api = public_api.merge(sync_webhook::router());
+    }
+
     let api = Router::new()
         .route("/healthz", get(healthz))
         .route("/readyz", get(readyz))
@@ -188,6 +210,7 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
             "/auth/oidc/callback",
             get(oidc::oidc_callback).layer(GovernorLayer::new(oidc_governor)),
         )
+        .merge(public_api)
         .merge(protected_api)
         .with_state(state.clone());
 
@@ -199,7 +222,13 @@ pub fn app_router(state: Arc<AppState>, config: &Config) -> Router {
         .layer(TimeoutLayer::new(config.request_timeout));
 
     if config.mcp_enabled {
-        router = router.merge(crate::mcp::router(state, config));
+        router = router.merge(crate::mcp::router(state.clone(), config));
+    }
+
+    // Scraped by Prometheus without a user session; exposes counts only.
+    #[cfg(feature = "metrics")]
+    {
+        router = router.merge(metrics::router().with_state(state.clone()));
     }
 
     router
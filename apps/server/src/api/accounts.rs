@@ -8,11 +8,11 @@ use crate::{
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, put},
+    routing::{get, post, put},
     Json, Router,
 };
 use serde::Deserialize;
-use wealthfolio_core::accounts::AccountServiceTrait;
+use wealthfolio_core::accounts::{AccountMergeResult, AccountServiceTrait};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -67,8 +67,33 @@ async fn delete_account(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MergeAccountsBody {
+    target_account_id: String,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    confirm: bool,
+}
+
+/// Merges the account into `targetAccountId`; `dryRun` previews the merge without writing.
+async fn merge_accounts(
+    Path(id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<MergeAccountsBody>,
+) -> ApiResult<Json<AccountMergeResult>> {
+    let result = state
+        .account_merge_service
+        .merge_accounts(&id, &body.target_account_id, body.dry_run, body.confirm)
+        .await?;
+    // Domain events handle portfolio recalculation
+    Ok(Json(result))
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/accounts", get(list_accounts).post(create_account))
         .route("/accounts/{id}", put(update_account).delete(delete_account))
+        .route("/accounts/{id}/merge", post(merge_accounts))
 }
@@ -9,10 +9,10 @@ use axum::{
 };
 use wealthfolio_core::activities::{
     import_type, Activity, ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityImport,
-    ActivitySearchResponse, ActivityUpdate, ImportActivitiesResult, ImportAssetCandidate,
-    ImportAssetPreviewItem, ImportMappingData, ImportTemplateData, InternalTransferPairRequest,
-    InternalTransferPairResponse, NewActivity, ParseConfig, ParsedCsvResult,
-    TransferMatchCandidate, TransferMatchCandidateRequest,
+    ActivityPage, ActivitySearchResponse, ActivityUpdate, ImportActivitiesResult,
+    ImportAssetCandidate, ImportAssetPreviewItem, ImportMappingData, ImportTemplateData,
+    InternalTransferPairRequest, InternalTransferPairResponse, NewActivity, ParseConfig,
+    ParsedCsvResult, RecentActivity, TransferMatchCandidate, TransferMatchCandidateRequest,
 };
 use wealthfolio_core::utils::time_utils::{
     local_date_range_utc_bounds, parse_user_timezone_or_default,
@@ -34,6 +34,14 @@ enum StringOrVec {
     Many(Vec<String>),
 }
 
+fn string_or_vec(value: Option<StringOrVec>) -> Option<Vec<String>> {
+    match value {
+        Some(StringOrVec::One(s)) => Some(vec![s]),
+        Some(StringOrVec::Many(v)) => Some(v),
+        None => None,
+    }
+}
+
 #[derive(serde::Deserialize)]
 struct ActivitySearchBody {
     page: i64,
@@ -67,21 +75,9 @@ async fn search_activities(
         Some(SortWrapper::Many(v)) => v.into_iter().next(),
         None => None,
     };
-    let account_ids: Option<Vec<String>> = match body.account_id_filter {
-        Some(StringOrVec::One(s)) => Some(vec![s]),
-        Some(StringOrVec::Many(v)) => Some(v),
-        None => None,
-    };
-    let types: Option<Vec<String>> = match body.activity_type_filter {
-        Some(StringOrVec::One(s)) => Some(vec![s]),
-        Some(StringOrVec::Many(v)) => Some(v),
-        None => None,
-    };
-    let instrument_types: Option<Vec<String>> = match body.instrument_type_filter {
-        Some(StringOrVec::One(s)) => Some(vec![s]),
-        Some(StringOrVec::Many(v)) => Some(v),
-        None => None,
-    };
+    let account_ids = string_or_vec(body.account_id_filter);
+    let types = string_or_vec(body.activity_type_filter);
+    let instrument_types = string_or_vec(body.instrument_type_filter);
     // Parse date filters
     let date_from_parsed = parse_date_optional(body.date_from, "dateFrom")?;
     let date_to_parsed = parse_date_optional(body.date_to, "dateTo")?;
@@ -105,6 +101,61 @@ async fn search_activities(
     Ok(Json(resp))
 }
 
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ActivityPageBody {
+    /// `nextCursor` of the previous page; omitted for the first page.
+    cursor: Option<String>,
+    limit: Option<i64>,
+    account_id_filter: Option<StringOrVec>,
+    activity_type_filter: Option<StringOrVec>,
+    asset_id_keyword: Option<String>,
+    needs_review_filter: Option<bool>,
+    date_from: Option<String>, // YYYY-MM-DD format
+    date_to: Option<String>,   // YYYY-MM-DD format
+    instrument_type_filter: Option<StringOrVec>,
+}
+
+/// Keyset-paged activity listing, newest first. Deep pages cost the same as the first one and
+/// stay consistent while activities are added.
+async fn search_activities_page(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ActivityPageBody>,
+) -> ApiResult<Json<ActivityPage>> {
+    let date_from_parsed = parse_date_optional(body.date_from, "dateFrom")?;
+    let date_to_parsed = parse_date_optional(body.date_to, "dateTo")?;
+    let timezone = state.timezone.read().unwrap().clone();
+    let tz = parse_user_timezone_or_default(&timezone);
+    let (date_from_utc, date_to_utc_exclusive) =
+        local_date_range_utc_bounds(date_from_parsed, date_to_parsed, tz)?;
+
+    let page = state.activity_service.search_activities_page(
+        body.cursor,
+        body.limit,
+        string_or_vec(body.account_id_filter),
+        string_or_vec(body.activity_type_filter),
+        body.asset_id_keyword,
+        body.needs_review_filter,
+        date_from_utc,
+        date_to_utc_exclusive,
+        string_or_vec(body.instrument_type_filter),
+    )?;
+    Ok(Json(page))
+}
+
+#[derive(serde::Deserialize)]
+struct RecentActivitiesQuery {
+    limit: Option<i64>,
+}
+
+async fn get_recent_activities(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<RecentActivitiesQuery>,
+) -> ApiResult<Json<Vec<RecentActivity>>> {
+    let activities = state.activity_service.get_recent_activities(q.limit)?;
+    Ok(Json(activities))
+}
+
 async fn create_activity(
     State(state): State<Arc<AppState>>,
     Json(activity): Json<NewActivity>,
@@ -433,6 +484,8 @@ async fn parse_csv_endpoint(
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/activities/search", post(search_activities))
+        .route("/activities/page", post(search_activities_page))
+        .route("/activities/recent", get(get_recent_activities))
         .route("/activities", post(create_activity).put(update_activity))
         .route("/activities/bulk", post(save_activities))
         .route("/activities/{id}", delete(delete_activity))
@@ -4,10 +4,14 @@ use crate::{error::ApiResult, main_lib::AppState};
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
     Json, Router,
 };
-use wealthfolio_core::assets::{Asset as CoreAsset, NewAsset, UpdateAssetProfile};
+use std::collections::BTreeMap;
+use wealthfolio_core::assets::{
+    Asset as CoreAsset, NewAsset, SymbolNormalization, SymbolNormalizationService,
+    UpdateAssetProfile,
+};
 
 #[derive(serde::Deserialize)]
 struct AssetQuery {
@@ -75,6 +79,47 @@ async fn delete_asset(
     Ok(StatusCode::NO_CONTENT)
 }
 
+fn symbol_normalization(state: &AppState) -> SymbolNormalizationService {
+    SymbolNormalizationService::new(state.asset_service.clone(), state.settings_service.clone())
+}
+
+#[derive(serde::Deserialize)]
+struct NormalizeSymbolsBody {
+    symbols: Vec<String>,
+}
+
+async fn normalize_symbols(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<NormalizeSymbolsBody>,
+) -> ApiResult<Json<Vec<SymbolNormalization>>> {
+    let results = symbol_normalization(&state).normalize_symbols(&body.symbols)?;
+    Ok(Json(results))
+}
+
+async fn get_symbol_mappings(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<BTreeMap<String, String>>> {
+    Ok(Json(symbol_normalization(&state).get_overrides()?))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolMappingBody {
+    raw_symbol: String,
+    /// `None` removes the mapping.
+    target_symbol: Option<String>,
+}
+
+async fn set_symbol_mapping(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SymbolMappingBody>,
+) -> ApiResult<Json<BTreeMap<String, String>>> {
+    let mappings = symbol_normalization(&state)
+        .set_override(&body.raw_symbol, body.target_symbol.as_deref())
+        .await?;
+    Ok(Json(mappings))
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/assets", get(list_assets).post(create_asset))
@@ -82,4 +127,9 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/assets/profile", get(get_asset_profile))
         .route("/assets/profile/{id}", put(update_asset_profile))
         .route("/assets/pricing-mode/{id}", put(update_quote_mode))
+        .route("/assets/symbols/normalize", post(normalize_symbols))
+        .route(
+            "/assets/symbol-mappings",
+            get(get_symbol_mappings).put(set_symbol_mapping),
+        )
 }
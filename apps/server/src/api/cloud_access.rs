@@ -0,0 +1,60 @@
+//! Cloud kill switch endpoints.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Json, Router,
+};
+use tracing::info;
+use wealthfolio_core::settings::{CloudAccessService, CloudAccessStatus};
+
+use crate::error::ApiResult;
+use crate::events::{ServerEvent, CLOUD_DISABLED, CLOUD_ENABLED};
+use crate::main_lib::AppState;
+
+async fn get_cloud_status(State(state): State<Arc<AppState>>) -> Json<CloudAccessStatus> {
+    Json(CloudAccessService::new(state.settings_service.clone()).status())
+}
+
+async fn disable_cloud(State(state): State<Arc<AppState>>) -> ApiResult<Json<CloudAccessStatus>> {
+    let status = CloudAccessService::new(state.settings_service.clone())
+        .set_disabled(true)
+        .await?;
+    info!("Cloud access disabled");
+
+    #[cfg(feature = "device-sync")]
+    if let Err(err) =
+        super::device_sync_engine::ensure_background_engine_stopped(Arc::clone(&state)).await
+    {
+        tracing::warn!("Failed to stop device sync engine: {}", err);
+    }
+
+    state.event_bus.publish(ServerEvent::new(CLOUD_DISABLED));
+    Ok(Json(status))
+}
+
+async fn enable_cloud(State(state): State<Arc<AppState>>) -> ApiResult<Json<CloudAccessStatus>> {
+    let status = CloudAccessService::new(state.settings_service.clone())
+        .set_disabled(false)
+        .await?;
+    info!("Cloud access enabled");
+    state.event_bus.publish(ServerEvent::new(CLOUD_ENABLED));
+
+    #[cfg(feature = "device-sync")]
+    if let Err(err) =
+        super::device_sync_engine::ensure_background_engine_started(Arc::clone(&state)).await
+    {
+        tracing::warn!("Failed to restart device sync engine: {}", err);
+    }
+
+    Ok(Json(status))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/sync/cloud/status", get(get_cloud_status))
+        .route("/sync/cloud/disable", post(disable_cloud))
+        .route("/sync/cloud/enable", post(enable_cloud))
+}
@@ -7,36 +7,46 @@ use std::future::Future;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
-    routing::{delete, get, post},
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
 #[cfg(feature = "device-sync")]
 use super::device_sync_engine;
 use crate::error::{ApiError, ApiResult};
 use crate::events::{
     EventBus, ServerEvent, BROKER_SYNC_COMPLETE, BROKER_SYNC_ERROR, BROKER_SYNC_START,
+    BROKER_SYNC_SUMMARY, CONNECTION_EXPIRING, CONNECTION_RENAMED, SYNC_ANOMALY,
 };
 use crate::main_lib::AppState;
 use axum::http::StatusCode;
 use wealthfolio_connect::prepare_post_login_broker_bootstrap;
 use wealthfolio_connect::{
-    acquire_broker_sync_guard,
+    access_token_expiry, acquire_broker_sync_guard,
     broker::{
-        BrokerApiClient, PlansResponse, SyncAccountsResponse, SyncActivitiesResponse,
-        SyncConnectionsResponse, UserInfo,
+        AccountResyncResult, BrokerApiClient, PlansResponse, SyncAccountsResponse,
+        SyncActivitiesResponse, SyncConnectionsResponse, UserInfo,
     },
-    ensure_valid_access_token, fetch_subscription_plans_public, BrokerSyncRunGuard,
-    ConnectApiClient, PostLoginBootstrapReason, PostLoginBootstrapResult,
-    PostLoginBootstrapSyncResult, PostLoginBrokerBootstrapDecision, SyncConfig, SyncOrchestrator,
-    SyncProgressPayload, SyncProgressReporter, SyncResult, TokenLifecycleConfig,
+    connect_user_agent, ensure_valid_access_token, fetch_subscription_plans_public,
+    force_refresh_access_token, poll_device_login, resolve_broker_sync_subscription, sign_out,
+    start_device_login, store_cloud_session, ActivityResumeService, BrokerSyncError,
+    BrokerSyncRunGuard, BrokerSyncSummary, BrokerSyncTrigger, ConnectApiClient, ConnectionExpiry,
+    ConnectionNameService, DeviceLoginPoll, DeviceLoginStart, FakeSubscriptionState,
+    HistoryBackfillJob, HistoryBackfillService, PostLoginBootstrapReason, PostLoginBootstrapResult,
+    PostLoginBootstrapSyncResult, PostLoginBrokerBootstrapDecision, SignOutResult,
+    SubscriptionDecision, SubscriptionStatus, SyncAnomaly, SyncOrchestrator, SyncProgressPayload,
+    SyncProgressReporter, SyncResult, SyncSuspensionService, TokenLifecycleConfig,
     TokenLifecycleError, CLOUD_ACCESS_TOKEN_KEY, CLOUD_REFRESH_TOKEN_KEY,
 };
+use wealthfolio_core::settings::CloudAccessService;
 #[cfg(feature = "device-sync")]
-use wealthfolio_device_sync::{EnableSyncResult, SyncState, SyncStateResult};
+use wealthfolio_device_sync::{
+    CursorExpiryForecast, EnableSyncResult, RotateCredentialResult, SyncState, SyncStateResult,
+};
 
 #[cfg(feature = "device-sync")]
 const DEVICE_ID_KEY: &str = "sync_device_id";
@@ -156,7 +166,12 @@ async fn create_connect_client(state: &AppState) -> ApiResult<ConnectApiClient>
     ensure_cloud_sync_enabled()?;
     let token = mint_access_token(state).await?;
     let base_url = cloud_api_base_url()?;
-    ConnectApiClient::new(&base_url, &token).map_err(|e| ApiError::Internal(e.to_string()))
+    ConnectApiClient::with_config(&base_url, &token, crate::features::connect_client_config())
+        .and_then(|client| client.with_user_agent(&connect_user_agent(env!("CARGO_PKG_VERSION"))))
+        .map(|client| {
+            client.with_fallback_url(crate::features::cloud_api_fallback_url().as_deref())
+        })
+        .map_err(|e| ApiError::Internal(e.to_string()))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -353,6 +368,22 @@ impl SyncProgressReporter for EventBusProgressReporter {
             ));
         }
     }
+
+    fn report_anomalies(&self, anomalies: &[SyncAnomaly]) {
+        self.event_bus.publish(ServerEvent::with_payload(
+            SYNC_ANOMALY,
+            serde_json::json!({ "anomalies": anomalies }),
+        ));
+    }
+
+    fn report_expiring_connections(&self, expiring: &[ConnectionExpiry]) {
+        for expiry in expiring {
+            self.event_bus.publish(ServerEvent::with_payload(
+                CONNECTION_EXPIRING,
+                serde_json::to_value(expiry).unwrap_or_default(),
+            ));
+        }
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -364,17 +395,42 @@ async fn store_sync_session(
     Json(body): Json<StoreSyncSessionRequest>,
 ) -> ApiResult<Json<()>> {
     ensure_cloud_sync_enabled()?;
-    state
-        .secret_store
-        .set_secret(CLOUD_REFRESH_TOKEN_KEY, &body.refresh_token)
-        .map_err(|e| ApiError::Internal(format!("Failed to store refresh token: {}", e)))?;
-    // Best-effort cleanup for legacy versions that stored access tokens at rest.
-    let _ = state.secret_store.delete_secret(CLOUD_ACCESS_TOKEN_KEY);
+    store_cloud_session(state.secret_store.as_ref(), &body.refresh_token)
+        .map_err(map_token_lifecycle_error)?;
     state.token_lifecycle.clear_cache().await;
 
     Ok(Json(()))
 }
 
+/// Starts a Connect sign-in for a server without a browser: returns the URL and code the user
+/// enters elsewhere. Poll `/auth/device/poll` until it reports `complete`.
+async fn start_device_login_session(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<DeviceLoginStart>> {
+    ensure_cloud_sync_enabled()?;
+    let config = token_lifecycle_config();
+    let start = start_device_login(state.device_login.as_ref(), config.as_ref())
+        .await
+        .map_err(map_token_lifecycle_error)?;
+    Ok(Json(start))
+}
+
+async fn poll_device_login_session(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<DeviceLoginPoll>> {
+    ensure_cloud_sync_enabled()?;
+    let config = token_lifecycle_config();
+    let poll = poll_device_login(
+        state.device_login.as_ref(),
+        state.secret_store.as_ref(),
+        state.token_lifecycle.as_ref(),
+        config.as_ref(),
+    )
+    .await
+    .map_err(map_token_lifecycle_error)?;
+    Ok(Json(poll))
+}
+
 async fn post_login_bootstrap(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<PostLoginBootstrapResult>> {
@@ -413,7 +469,7 @@ async fn run_post_login_broker_bootstrap(state: Arc<AppState>) -> PostLoginBoots
     };
 
     tokio::spawn(async move {
-        match perform_broker_sync_with_guard(&state, guard).await {
+        match perform_broker_sync_with_guard(&state, guard, BrokerSyncTrigger::Login).await {
             Ok(_result) => {
                 info!("[Connect] Post-login broker sync completed successfully");
             }
@@ -499,6 +555,52 @@ async fn clear_sync_session(State(state): State<Arc<AppState>>) -> ApiResult<Jso
     Ok(Json(()))
 }
 
+/// Signs out everywhere: revokes the cloud session on every device, then deletes all cloud
+/// secrets and resets device sync to FRESH. A failed revoke is logged; the local sign-out
+/// still happens.
+async fn sign_out_everywhere(State(state): State<Arc<AppState>>) -> ApiResult<Json<SignOutResult>> {
+    ensure_cloud_sync_enabled()?;
+    info!("[Connect] Signing out of Connect");
+
+    #[cfg(feature = "device-sync")]
+    {
+        if let Err(e) =
+            device_sync_engine::ensure_background_engine_stopped(Arc::clone(&state)).await
+        {
+            warn!("[Connect] Failed to stop device sync engine: {}", e);
+        }
+        if let Err(e) = state.device_enroll_service.clear_sync_data() {
+            warn!("[Connect] Failed to reset device sync state: {}", e.message);
+        }
+    }
+
+    let config = token_lifecycle_config();
+    let result = sign_out(
+        state.secret_store.as_ref(),
+        state.token_lifecycle.as_ref(),
+        config.as_ref(),
+    )
+    .await
+    .map_err(map_token_lifecycle_error)?;
+
+    #[cfg(feature = "device-sync")]
+    let _ = device_sync_engine::reset_local_sync_session(&state).await;
+    #[cfg(not(feature = "device-sync"))]
+    let _ = state.app_sync_repository.reset_local_sync_session().await;
+    #[cfg(feature = "device-sync")]
+    device_sync_engine::clear_min_snapshot_created_at_from_store();
+    let _ = state
+        .app_sync_repository
+        .clear_all_min_snapshot_created_at()
+        .await;
+
+    info!(
+        "[Connect] Signed out (cloud session revoked: {})",
+        result.remote_revoked
+    );
+    Ok(Json(result))
+}
+
 async fn get_sync_session_status(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<SyncSessionStatus>> {
@@ -535,6 +637,7 @@ async fn restore_sync_session(
 
 pub(crate) async fn mint_access_token(state: &AppState) -> ApiResult<String> {
     ensure_cloud_sync_enabled()?;
+    CloudAccessService::new(state.settings_service.clone()).ensure_enabled()?;
     let config = token_lifecycle_config();
     ensure_valid_access_token(
         state.secret_store.as_ref(),
@@ -635,47 +738,110 @@ async fn sync_broker_activities(
     Ok(Json(result))
 }
 
+/// Refetch one account's history from the cloud and reconcile it against local data.
+/// Runs under the broker sync guard, so it never overlaps a full sync.
+async fn resync_broker_account(
+    State(state): State<Arc<AppState>>,
+    Path(account_id): Path<String>,
+) -> ApiResult<Json<AccountResyncResult>> {
+    ensure_connect_sync_enabled()?;
+    CloudAccessService::new(state.settings_service.clone()).ensure_enabled()?;
+    if !has_broker_sync(&state).await.map_err(ApiError::Internal)? {
+        return Err(ApiError::Forbidden(
+            "Your plan does not include broker sync".to_string(),
+        ));
+    }
+    let _guard = try_acquire_broker_sync_guard(&state)
+        .ok_or_else(|| ApiError::Conflict("Broker sync already running".to_string()))?;
+
+    info!("[Connect] Resyncing account {}", account_id);
+    let client = create_connect_client(&state).await?;
+    let reporter = Arc::new(EventBusProgressReporter::new(state.event_bus.clone()));
+    let orchestrator = SyncOrchestrator::new(
+        state.connect_sync_service.clone(),
+        reporter,
+        crate::features::broker_sync_config(),
+    );
+    let result = orchestrator
+        .resync_account(&client, &account_id)
+        .await
+        .map_err(ApiError::Internal)?;
+
+    Ok(Json(result))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Unified Sync Operation (non-blocking with SSE notifications)
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// Spends one of the manual sync tokens shared by the manual sync endpoints. Checked last, right
+/// before the sync starts, so rejected or overlapping requests do not use up tokens; the
+/// scheduler does not go through it.
+fn check_manual_sync_limit(state: &AppState) -> ApiResult<()> {
+    state
+        .manual_sync_limiter
+        .try_acquire()
+        .map_err(|retry_after| {
+            info!(
+                "[Connect] Manual broker sync rejected: next one allowed in {}s",
+                retry_after.as_secs()
+            );
+            ApiError::TooManyRequests {
+                message: "Too many manual syncs, try again later".to_string(),
+                retry_after,
+            }
+        })
+}
+
 /// Trigger a full broker data sync (connections → accounts → activities).
 /// Returns immediately with 202 Accepted. Sync runs in background and emits SSE events.
-async fn sync_broker_data(State(state): State<Arc<AppState>>) -> StatusCode {
+/// Answers `429` with `Retry-After` when manual syncs are started too often.
+async fn sync_broker_data(State(state): State<Arc<AppState>>) -> Response {
     if let Err(err) = ensure_connect_sync_enabled() {
         error!("[Connect] Broker sync skipped: {}", err);
-        return StatusCode::NOT_IMPLEMENTED;
+        return StatusCode::NOT_IMPLEMENTED.into_response();
+    }
+    if CloudAccessService::new(state.settings_service.clone()).is_disabled() {
+        info!("[Connect] Broker sync skipped: cloud access is disabled");
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
     }
 
+    let Some(guard) = try_acquire_broker_sync_guard(&state) else {
+        info!("[Connect] Broker sync skipped: sync already running");
+        return StatusCode::CONFLICT.into_response();
+    };
+
     // Check plan entitlement before starting sync
     match has_broker_sync(&state).await {
         Ok(true) => {}
         Ok(false) => {
             info!("[Connect] Broker sync skipped: plan does not include broker sync");
-            return StatusCode::FORBIDDEN;
+            return StatusCode::FORBIDDEN.into_response();
         }
         Err(e) => {
             error!(
                 "[Connect] Broker sync skipped: could not verify entitlement ({})",
                 e
             );
-            return StatusCode::FORBIDDEN;
+            return StatusCode::FORBIDDEN.into_response();
         }
     }
 
-    let Some(guard) = try_acquire_broker_sync_guard(&state) else {
-        info!("[Connect] Broker sync skipped: sync already running");
-        return StatusCode::CONFLICT;
-    };
+    if let Err(err) = check_manual_sync_limit(&state) {
+        return err.into_response();
+    }
 
     info!("[Connect] Starting broker data sync (non-blocking)...");
 
     // Spawn background task to perform the sync
     tokio::spawn(async move {
-        match perform_broker_sync_with_guard(&state, guard).await {
+        match perform_broker_sync_with_guard(&state, guard, BrokerSyncTrigger::Manual).await {
             Ok(_result) => {
                 info!("[Connect] Broker sync completed successfully");
                 // Events are emitted by the orchestrator via EventBusProgressReporter
+                if let Err(e) = scheduled_sync_suspension(&state).clear().await {
+                    warn!("[Connect] Failed to clear scheduled sync suspension: {}", e);
+                }
             }
             Err(err) => {
                 error!("[Connect] Broker sync failed: {}", err);
@@ -684,7 +850,49 @@ async fn sync_broker_data(State(state): State<Arc<AppState>>) -> StatusCode {
         }
     });
 
-    StatusCode::ACCEPTED
+    StatusCode::ACCEPTED.into_response()
+}
+
+/// Runs the same full sync as the scheduler and waits for it, so the caller gets the
+/// `SyncResult`. Shares the run guard with the scheduler and `POST /connect/sync`; an
+/// overlapping run answers `409`. Rate limited like `POST /connect/sync`.
+async fn sync_broker_now(State(state): State<Arc<AppState>>) -> ApiResult<Json<SyncResult>> {
+    ensure_connect_sync_enabled()?;
+    CloudAccessService::new(state.settings_service.clone()).ensure_enabled()?;
+
+    let guard = try_acquire_broker_sync_guard(&state)
+        .ok_or_else(|| ApiError::Conflict("Broker sync already running".to_string()))?;
+
+    match has_broker_sync(&state).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(ApiError::Forbidden(
+                "Your plan does not include broker sync".to_string(),
+            ))
+        }
+        Err(e) => {
+            return Err(ApiError::Forbidden(format!(
+                "Could not verify broker sync entitlement: {}",
+                e
+            )))
+        }
+    }
+    check_manual_sync_limit(&state)?;
+
+    info!("[Connect] Starting manual broker sync...");
+    // Run in its own task so a client that disconnects does not cancel the sync midway.
+    let result = tokio::spawn(async move {
+        let result =
+            perform_broker_sync_with_guard(&state, guard, BrokerSyncTrigger::Manual).await?;
+        if let Err(e) = scheduled_sync_suspension(&state).clear().await {
+            warn!("[Connect] Failed to clear scheduled sync suspension: {}", e);
+        }
+        Ok::<_, BrokerSyncError>(result)
+    })
+    .await
+    .map_err(|e| ApiError::Internal(format!("Broker sync task failed: {}", e)))?
+    .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(result))
 }
 
 /// Check if the current user's plan includes broker sync.
@@ -697,44 +905,242 @@ pub async fn has_broker_sync(state: &AppState) -> Result<bool, String> {
     client.has_broker_sync().await.map_err(|e| e.to_string())
 }
 
+/// The plan check with the grace period policy applied: while the cloud cannot be reached, a
+/// recent active check still allows sync. Used by the scheduler.
+pub async fn broker_sync_subscription(state: &AppState) -> SubscriptionDecision {
+    let status = match create_connect_client(state).await {
+        Ok(client) => client.subscription_status().await,
+        Err(e) => SubscriptionStatus::Unknown(e.to_string()),
+    };
+    resolve_broker_sync_subscription(
+        state.settings_service.clone(),
+        crate::features::subscription_grace_period(),
+        status,
+    )
+    .await
+}
+
+/// Why [`refresh_access_token_if_expiring`] could not provide a fresh access token.
+#[derive(Debug, thiserror::Error)]
+pub enum TokenRefreshError {
+    /// The cloud rejected the refresh token (`401`, `invalid_grant`) or there is none; only
+    /// signing in again helps.
+    #[error("Session expired: {0}")]
+    SessionExpired(String),
+    /// The refresh did not complete (network, timeout, server error); retrying may succeed.
+    #[error("Could not refresh the access token: {0}")]
+    Failed(String),
+}
+
+impl From<TokenLifecycleError> for TokenRefreshError {
+    fn from(err: TokenLifecycleError) -> Self {
+        match err {
+            TokenLifecycleError::Unauthorized(message) => Self::SessionExpired(message),
+            TokenLifecycleError::NotConfigured(message)
+            | TokenLifecycleError::RefreshFailed(message)
+            | TokenLifecycleError::Internal(message) => Self::Failed(message),
+        }
+    }
+}
+
+/// Refreshes the access token now when it would expire within `margin`, so the scheduler does
+/// not start a sync that fails with a `401`.
+pub async fn refresh_access_token_if_expiring(
+    state: &AppState,
+    margin: std::time::Duration,
+) -> Result<(), TokenRefreshError> {
+    let config = token_lifecycle_config();
+    let token = ensure_valid_access_token(
+        state.secret_store.as_ref(),
+        state.token_lifecycle.as_ref(),
+        config.as_ref(),
+    )
+    .await?;
+    let expires_soon = access_token_expiry(&token)
+        .is_some_and(|expiry| expiry <= std::time::SystemTime::now() + margin);
+    if expires_soon {
+        debug!("[Connect] Access token expires soon; refreshing before sync");
+        force_refresh_access_token(
+            state.secret_store.as_ref(),
+            state.token_lifecycle.as_ref(),
+            config.as_ref(),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Tracks repeated permanent failures of scheduled sync and the resulting suspension.
+pub fn scheduled_sync_suspension(state: &AppState) -> SyncSuspensionService {
+    SyncSuspensionService::new(
+        state.settings_service.clone(),
+        crate::features::scheduled_sync_suspend_threshold(),
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+/// Lets the scheduler retry again after it suspended itself on repeated permanent failures.
+async fn resume_scheduled_sync(State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    scheduled_sync_suspension(&state)
+        .clear()
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    info!("[Connect] Scheduled broker sync resumed");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// A fresh correlation ID for one sync run, attached to its log lines and summary event.
+pub fn new_sync_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
 /// Core broker sync logic - syncs connections, accounts, and activities from cloud to local DB.
 /// Uses the centralized SyncOrchestrator for full pagination support.
 /// Also used by the background scheduler for periodic syncs.
-pub async fn perform_broker_sync(state: &AppState) -> Result<SyncResult, String> {
-    let guard = try_acquire_broker_sync_guard(state)
-        .ok_or_else(|| "Broker sync already running".to_string())?;
-    perform_broker_sync_with_guard(state, guard).await
+///
+/// Runs under `sync_id`. A run already in progress is an error, and no summary is published
+/// for it.
+pub async fn perform_broker_sync(
+    state: &AppState,
+    sync_id: String,
+) -> Result<SyncResult, BrokerSyncError> {
+    let guard = try_acquire_broker_sync_guard(state).ok_or(BrokerSyncError::AlreadyRunning)?;
+    run_and_publish_broker_sync(state, guard, BrokerSyncTrigger::Scheduler, sync_id).await
 }
 
+/// Syncs a single connection, e.g. when a cloud webhook reports that its data changed.
+pub async fn perform_connection_sync(
+    state: &AppState,
+    connection_id: &str,
+) -> Result<SyncResult, BrokerSyncError> {
+    let guard = try_acquire_broker_sync_guard(state).ok_or(BrokerSyncError::AlreadyRunning)?;
+    run_broker_sync(state, guard, Some(connection_id)).await
+}
+
+/// Runs a full sync and publishes its `broker:sync-summary`, whether it succeeded or not.
 async fn perform_broker_sync_with_guard(
+    state: &AppState,
+    guard: BrokerSyncRunGuard,
+    triggered_by: BrokerSyncTrigger,
+) -> Result<SyncResult, BrokerSyncError> {
+    run_and_publish_broker_sync(state, guard, triggered_by, new_sync_id()).await
+}
+
+/// Every log line of the run, including the orchestrator's, carries `sync_id`, so one run can
+/// be told apart from the others in interleaved logs.
+async fn run_and_publish_broker_sync(
+    state: &AppState,
+    guard: BrokerSyncRunGuard,
+    triggered_by: BrokerSyncTrigger,
+    sync_id: String,
+) -> Result<SyncResult, BrokerSyncError> {
+    let span = info_span!("broker_sync", sync_id = %sync_id, triggered_by = ?triggered_by);
+    let started = std::time::Instant::now();
+    let outcome = run_broker_sync(state, guard, None).instrument(span).await;
+    let summary = BrokerSyncSummary::new(
+        &outcome,
+        triggered_by,
+        started.elapsed(),
+        chrono::Utc::now(),
+    )
+    .with_sync_id(sync_id);
+    state.broker_sync_history.write().unwrap().record(&summary);
+    state.event_bus.publish(ServerEvent::with_payload(
+        BROKER_SYNC_SUMMARY,
+        serde_json::json!(summary),
+    ));
+    outcome
+}
+
+async fn run_broker_sync(
     state: &AppState,
     _guard: BrokerSyncRunGuard,
-) -> Result<SyncResult, String> {
-    ensure_connect_sync_enabled().map_err(|e| e.to_string())?;
+    connection_id: Option<&str>,
+) -> Result<SyncResult, BrokerSyncError> {
+    ensure_connect_sync_enabled().map_err(|e| BrokerSyncError::Failed(e.to_string()))?;
     // Create API client
     let client = match create_connect_client(state).await {
         Ok(client) => client,
         Err(err) => {
             let message = err.to_string();
-            state.event_bus.publish(ServerEvent::with_payload(
-                BROKER_SYNC_ERROR,
-                serde_json::json!({ "error": message }),
-            ));
-            return Err(message);
+            // A targeted (webhook) sync names its connection so the failure can be attributed.
+            let payload = match connection_id {
+                Some(connection_id) => {
+                    serde_json::json!({ "error": message, "connectionId": connection_id })
+                }
+                None => serde_json::json!({ "error": message }),
+            };
+            state
+                .event_bus
+                .publish(ServerEvent::with_payload(BROKER_SYNC_ERROR, payload));
+            // The token mint answers `Forbidden` when the session is gone or was rejected.
+            return Err(match err {
+                ApiError::Unauthorized(_) | ApiError::Forbidden(_) => {
+                    BrokerSyncError::NotAuthenticated(message)
+                }
+                _ => BrokerSyncError::Failed(message),
+            });
         }
     };
 
     // Create progress reporter and orchestrator
+    let reporter = Arc::new(EventBusProgressReporter::new(state.event_bus.clone()));
+    let config = crate::features::broker_sync_config();
+    let connection_health = config.connection_health(state.settings_service.clone());
+    let orchestrator = SyncOrchestrator::new(state.connect_sync_service.clone(), reporter, config)
+        .with_connection_health(connection_health)
+        .with_history_backfill(HistoryBackfillService::new(state.settings_service.clone()))
+        .with_activity_resume(ActivityResumeService::new(state.settings_service.clone()));
+
+    // Run the sync via the centralized orchestrator
+    // Note: Asset enrichment is handled automatically via domain events (AssetsCreated)
+    let result = match connection_id {
+        Some(connection_id) => orchestrator.sync_connection(&client, connection_id).await,
+        None => orchestrator.sync_all(&client).await,
+    };
+    if result.is_ok() {
+        spawn_history_backfills(state, client);
+    }
+    result
+}
+
+/// Runs deferred history backfills in the background once a sync has finished, so recent data
+/// is usable while older history is fetched. Skipped when a backfill run is already going.
+fn spawn_history_backfills(state: &AppState, client: ConnectApiClient) {
+    let history_backfill = HistoryBackfillService::new(state.settings_service.clone());
+    match history_backfill.runnable() {
+        Ok(jobs) if !jobs.is_empty() => {}
+        Ok(_) => return,
+        Err(err) => {
+            warn!("[Connect] Failed to read history backfills: {}", err);
+            return;
+        }
+    }
+    let Some(guard) = acquire_broker_sync_guard(&state.history_backfill_running) else {
+        debug!("[Connect] History backfill already running");
+        return;
+    };
+
     let reporter = Arc::new(EventBusProgressReporter::new(state.event_bus.clone()));
     let orchestrator = SyncOrchestrator::new(
         state.connect_sync_service.clone(),
         reporter,
-        SyncConfig::default(),
+        crate::features::broker_sync_config(),
+    )
+    .with_history_backfill(history_backfill);
+    tokio::spawn(
+        async move {
+            let _guard = guard;
+            match orchestrator.run_history_backfills(&client).await {
+                Ok(jobs) => info!(
+                    "[Connect] History backfill finished for {} account(s)",
+                    jobs.len()
+                ),
+                Err(err) => warn!("[Connect] History backfill failed: {}", err),
+            }
+        }
+        .in_current_span(),
     );
-
-    // Run the sync via the centralized orchestrator
-    // Note: Asset enrichment is handled automatically via domain events (AssetsCreated)
-    orchestrator.sync_all(&client).await
 }
 
 /// Sync only brokerage activities for existing TRANSACTIONS accounts.
@@ -750,7 +1156,7 @@ async fn perform_broker_activities_only_sync(
     let orchestrator = SyncOrchestrator::new(
         state.connect_sync_service.clone(),
         reporter,
-        SyncConfig::default(),
+        crate::features::broker_sync_config(),
     );
 
     orchestrator.sync_activities_only(&client).await
@@ -772,8 +1178,11 @@ async fn get_subscription_plans(
     Ok(Json(plans))
 }
 
-async fn get_subscription_plans_public() -> ApiResult<Json<PlansResponse>> {
+async fn get_subscription_plans_public(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<PlansResponse>> {
     ensure_cloud_sync_enabled()?;
+    CloudAccessService::new(state.settings_service.clone()).ensure_enabled()?;
     info!("[Connect] Getting subscription plans (public)...");
 
     let base_url = cloud_api_base_url()?;
@@ -795,7 +1204,32 @@ async fn get_user_info(State(state): State<Arc<AppState>>) -> ApiResult<Json<Use
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
-    Ok(Json(user_info))
+    Ok(Json(state.subscription_override.apply(user_info)))
+}
+
+#[derive(serde::Deserialize)]
+struct FakeSubscriptionBody {
+    state: Option<FakeSubscriptionState>,
+}
+
+/// Overrides the subscription reported by `get_user_info` for this server session. Answers 404
+/// unless `WEALTHFOLIO_DEBUG_ENDPOINTS=true`; a null state restores the real subscription.
+async fn set_fake_subscription(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<FakeSubscriptionBody>,
+) -> ApiResult<StatusCode> {
+    state
+        .subscription_override
+        .set(body.state)
+        .map_err(|_| ApiError::NotFound)?;
+    match body.state {
+        Some(fake) => warn!(
+            "[Connect] Simulating {:?} subscription for this session",
+            fake
+        ),
+        None => info!("[Connect] Cleared simulated subscription"),
+    }
+    Ok(StatusCode::NO_CONTENT)
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -810,15 +1244,45 @@ async fn list_broker_connections(
 
     let client = create_connect_client(&state).await?;
 
-    let connections = client
+    let mut connections = client
         .list_connections()
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
+    ConnectionNameService::new(state.settings_service.clone()).apply_names(&mut connections)?;
+    crate::features::broker_sync_config()
+        .connection_health(state.settings_service.clone())
+        .apply_health(&mut connections)?;
 
     info!("[Connect] Found {} broker connections", connections.len());
     Ok(Json(connections))
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetConnectionNameRequest {
+    /// New display name; `None` or blank clears it.
+    pub name: Option<String>,
+}
+
+/// Set or clear the local display name of a broker connection
+async fn set_connection_name(
+    State(state): State<Arc<AppState>>,
+    Path(connection_id): Path<String>,
+    Json(body): Json<SetConnectionNameRequest>,
+) -> ApiResult<Json<Option<String>>> {
+    ensure_connect_sync_enabled()?;
+
+    let name = ConnectionNameService::new(state.settings_service.clone())
+        .set_connection_name(&connection_id, body.name.as_deref())
+        .await?;
+    state.event_bus.publish(ServerEvent::with_payload(
+        CONNECTION_RENAMED,
+        serde_json::json!({ "connectionId": connection_id, "name": name }),
+    ));
+
+    Ok(Json(name))
+}
+
 async fn list_broker_accounts(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<wealthfolio_connect::broker::BrokerAccount>>> {
@@ -929,6 +1393,120 @@ async fn get_import_runs(
     Ok(Json(runs))
 }
 
+/// Aggregate sync statistics for the dashboard, from local sync states and run history
+async fn get_sync_dashboard(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<wealthfolio_connect::SyncDashboard>> {
+    if !crate::features::connect_sync_enabled() {
+        return Ok(Json(wealthfolio_connect::build_sync_dashboard(
+            &[],
+            &[],
+            &Default::default(),
+            false,
+            chrono::Utc::now(),
+        )));
+    }
+
+    let sync_in_progress = state
+        .broker_sync_running
+        .load(std::sync::atomic::Ordering::Acquire);
+    let mut dashboard = state
+        .connect_sync_service
+        .get_sync_dashboard(sync_in_progress)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    dashboard.holdings_recompute_pending = state.holdings_recompute.is_pending();
+    dashboard.scheduler_suspension = scheduled_sync_suspension(&state)
+        .get_suspension()
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(dashboard))
+}
+
+/// Request params for get_metrics_history
+#[derive(Debug, Deserialize)]
+pub struct MetricsHistoryParams {
+    pub metric: wealthfolio_connect::MetricsHistoryMetric,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub bucket: Option<wealthfolio_connect::MetricsBucket>,
+}
+
+/// One sync metric as a JSON time series, for dashboards that do not scrape. Built from the
+/// local sync run history only.
+async fn get_metrics_history(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<MetricsHistoryParams>,
+) -> ApiResult<Json<wealthfolio_connect::MetricsHistory>> {
+    let query = wealthfolio_connect::MetricsHistoryQuery::new(
+        params.metric,
+        params.from.as_deref(),
+        params.to.as_deref(),
+        params.bucket,
+        chrono::Utc::now(),
+    )
+    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    if !crate::features::connect_sync_enabled() {
+        return Ok(Json(wealthfolio_connect::build_metrics_history(
+            &[],
+            &query,
+        )));
+    }
+
+    let history = state
+        .connect_sync_service
+        .get_metrics_history(&query)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(history))
+}
+
+/// Connection counts per health class for widgets, from local state only.
+async fn get_connection_summary(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<wealthfolio_connect::ConnectionSummary>> {
+    if !crate::features::connect_sync_enabled() {
+        return Ok(Json(Default::default()));
+    }
+
+    let sync_states = state
+        .connect_sync_service
+        .get_all_sync_states()
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let summary = crate::features::broker_sync_config()
+        .connection_health(state.settings_service.clone())
+        .get_connection_summary(&sync_states)?;
+
+    Ok(Json(summary))
+}
+
+/// Connections whose broker consent lapses within the warning lead time, soonest first. Reads
+/// the connections recorded by the last sync; no cloud call.
+async fn get_expiring_connections(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<ConnectionExpiry>>> {
+    if !crate::features::connect_sync_enabled() {
+        return Ok(Json(Vec::new()));
+    }
+
+    let expiring = crate::features::broker_sync_config()
+        .connection_health(state.settings_service.clone())
+        .get_expiring_connections(chrono::Utc::now())?;
+
+    Ok(Json(expiring))
+}
+
+/// Deferred history backfills and their progress, from local state only.
+async fn get_history_backfills(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<HistoryBackfillJob>>> {
+    if !crate::features::connect_sync_enabled() {
+        return Ok(Json(Vec::new()));
+    }
+
+    let jobs = HistoryBackfillService::new(state.settings_service.clone()).list()?;
+    Ok(Json(jobs))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Broker Sync Profile Operations
 // ─────────────────────────────────────────────────────────────────────────────
@@ -1036,7 +1614,7 @@ async fn clear_device_sync_data(State(state): State<Arc<AppState>>) -> ApiResult
         .device_enroll_service
         .clear_sync_data()
         .map_err(|e| ApiError::Internal(e.message))?;
-    let _ = state.app_sync_repository.reset_local_sync_session().await;
+    let _ = device_sync_engine::reset_local_sync_session(&state).await;
     state
         .secret_store
         .delete_secret(DEVICE_ID_KEY)
@@ -1091,6 +1669,28 @@ async fn reinitialize_device_sync(
     Ok(Json(result))
 }
 
+/// Rotate this device's credential in place. The sync cursor and engine state are untouched.
+#[cfg(feature = "device-sync")]
+async fn rotate_device_credential(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<RotateCredentialResult>> {
+    ensure_device_sync_enabled()?;
+    info!("[Connect] Rotating device credential...");
+    let token = mint_access_token(&state).await?;
+
+    let result = state
+        .device_enroll_service
+        .rotate_device_credential(&token)
+        .await
+        .map_err(|e| match e.code.as_str() {
+            "SYNC_ERROR" => ApiError::Internal(e.message),
+            _ => ApiError::BadRequest(e.message),
+        })?;
+
+    info!("[Connect] Device credential rotated");
+    Ok(Json(result))
+}
+
 #[cfg(feature = "device-sync")]
 async fn get_device_sync_engine_status(
     State(state): State<Arc<AppState>>,
@@ -1113,6 +1713,16 @@ async fn get_device_sync_engine_status(
     }))
 }
 
+#[cfg(feature = "device-sync")]
+async fn get_device_sync_cursor_expiry(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<CursorExpiryForecast>> {
+    ensure_device_sync_enabled()?;
+    let forecast =
+        device_sync_engine::get_cursor_expiry_forecast(&state).map_err(ApiError::Internal)?;
+    Ok(Json(forecast))
+}
+
 #[cfg(feature = "device-sync")]
 async fn get_device_sync_pairing_source_status(
     State(state): State<Arc<AppState>>,
@@ -1291,24 +1901,42 @@ pub fn router() -> Router<Arc<AppState>> {
     let router = Router::new()
         // Session management
         .route("/connect/session", post(store_sync_session))
+        .route("/auth/device/start", post(start_device_login_session))
+        .route("/auth/device/poll", post(poll_device_login_session))
         .route("/connect/post-login-bootstrap", post(post_login_bootstrap))
         .route("/connect/session", delete(clear_sync_session))
+        .route("/connect/sign-out", post(sign_out_everywhere))
         .route("/connect/session/status", get(get_sync_session_status))
         .route("/connect/session/restore", get(restore_sync_session))
         // List operations (fetch from cloud without syncing)
         .route("/connect/connections", get(list_broker_connections))
+        .route("/connect/connections/summary", get(get_connection_summary))
+        .route(
+            "/connect/connections/expiring",
+            get(get_expiring_connections),
+        )
+        .route("/connect/connections/{id}/name", put(set_connection_name))
         .route("/connect/accounts", get(list_broker_accounts))
         // Unified sync (non-blocking, emits SSE events)
         .route("/connect/sync", post(sync_broker_data))
+        .route("/connect/sync/resume", post(resume_scheduled_sync))
+        .route("/sync/broker", post(sync_broker_now))
         // Individual sync operations (kept for backwards compatibility)
         .route("/connect/sync/connections", post(sync_broker_connections))
         .route("/connect/sync/accounts", post(sync_broker_accounts))
         .route("/connect/sync/activities", post(sync_broker_activities))
+        .route("/connect/sync/backfills", get(get_history_backfills))
         // Local data queries (from local database)
         .route("/connect/synced-accounts", get(get_synced_accounts))
         .route("/connect/platforms", get(get_platforms))
         .route("/connect/sync-states", get(get_broker_sync_states))
         .route("/connect/import-runs", get(get_import_runs))
+        .route("/sync/dashboard", get(get_sync_dashboard))
+        .route("/metrics/history", get(get_metrics_history))
+        .route(
+            "/sync/broker/accounts/{id}/resync",
+            post(resync_broker_account),
+        )
         // Broker sync profile
         .route(
             "/connect/broker-sync-profile",
@@ -1317,7 +1945,11 @@ pub fn router() -> Router<Arc<AppState>> {
         // User & Subscription
         .route("/connect/plans", get(get_subscription_plans))
         .route("/connect/plans/public", get(get_subscription_plans_public))
-        .route("/connect/user", get(get_user_info));
+        .route("/connect/user", get(get_user_info))
+        .route(
+            "/connect/debug/fake-subscription",
+            post(set_fake_subscription),
+        );
 
     #[cfg(feature = "device-sync")]
     let router = router
@@ -1328,10 +1960,18 @@ pub fn router() -> Router<Arc<AppState>> {
             "/connect/device/reinitialize",
             post(reinitialize_device_sync),
         )
+        .route(
+            "/connect/device/rotate-credential",
+            post(rotate_device_credential),
+        )
         .route(
             "/connect/device/engine-status",
             get(get_device_sync_engine_status),
         )
+        .route(
+            "/connect/device/cursor-expiry",
+            get(get_device_sync_cursor_expiry),
+        )
         .route(
             "/connect/device/pairing-source-status",
             get(get_device_sync_pairing_source_status),
@@ -1393,6 +2033,9 @@ mod tests {
             disabled_date: None,
             updated_at: None,
             name: None,
+            consent_expires_at: None,
+            custom_name: None,
+            health: None,
         }
     }
 
@@ -1607,6 +2250,26 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn only_a_rejected_session_is_an_expired_session() {
+        assert!(matches!(
+            TokenRefreshError::from(TokenLifecycleError::Unauthorized(
+                "Session expired. Please sign in again. (invalid_grant)".to_string()
+            )),
+            TokenRefreshError::SessionExpired(_)
+        ));
+        for transient in [
+            TokenLifecycleError::RefreshFailed("Auth refresh timed out".to_string()),
+            TokenLifecycleError::RefreshFailed("Auth refresh failed: 503".to_string()),
+            TokenLifecycleError::Internal("Failed to read refresh token".to_string()),
+        ] {
+            assert!(matches!(
+                TokenRefreshError::from(transient),
+                TokenRefreshError::Failed(_)
+            ));
+        }
+    }
+
     #[cfg(feature = "device-sync")]
     #[test]
     fn connect_router_includes_device_engine_routes() {
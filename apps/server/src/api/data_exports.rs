@@ -1,21 +1,25 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use axum::{
-    body::Body,
-    extract::{Path, State},
+    body::{Body, Bytes},
+    extract::{DefaultBodyLimit, Path, State},
     http::{header, Response, StatusCode},
     response::IntoResponse,
-    routing::get,
-    Router,
+    routing::{get, post},
+    Json, Router,
 };
 use wealthfolio_core::{
     accounts::AccountServiceTrait,
     activities::Sort,
     exports::{
-        export_file_name, format_holding_list_records, format_records, ExportDataType,
-        ExportFileFormat,
+        export_file_name, format_holding_list_records, format_records, holdings_snapshot_csv,
+        ExportDataType, ExportFileFormat,
     },
-    portfolio::holdings::HoldingListItem,
+    portability::{
+        build_archive, diff_archives, restore_archive, ArchiveDiff, ArchiveRestoreResult,
+    },
+    portfolio::holdings::{Holding, HoldingListItem},
     portfolios::AccountScope,
 };
 
@@ -26,6 +30,32 @@ use crate::{
 };
 
 const EXPORT_ACTIVITY_PAGE_SIZE: i64 = 9_007_199_254_740_991;
+const ARCHIVE_MAX_BODY_BYTES: usize = 256 * 1024 * 1024;
+
+/// Current holdings across every account, or `None` when there are no accounts to value.
+pub(crate) async fn load_export_holdings(state: &AppState) -> ApiResult<Option<Vec<Holding>>> {
+    let base = state.base_currency.read().unwrap().clone();
+    let resolved = state
+        .portfolio_service
+        .resolve_account_scope(&AccountScope::All, &base)?;
+    let account_ids = holdings_account_ids(state, &resolved.account_ids)?;
+    if account_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let holdings = if account_ids.len() == 1 {
+        state
+            .holdings_service
+            .get_holdings(&account_ids[0], &base)
+            .await?
+    } else {
+        state
+            .holdings_service
+            .get_holdings_for_accounts(&account_ids, &base, &resolved.scope_id)
+            .await?
+    };
+    Ok(Some(holdings))
+}
 
 async fn build_data_export_content(
     state: &AppState,
@@ -59,25 +89,8 @@ async fn build_data_export_content(
             Ok(format_records(&records, format)?)
         }
         ExportDataType::Holdings => {
-            let base = state.base_currency.read().unwrap().clone();
-            let resolved = state
-                .portfolio_service
-                .resolve_account_scope(&AccountScope::All, &base)?;
-            let account_ids = holdings_account_ids(state, &resolved.account_ids)?;
-            if account_ids.is_empty() {
+            let Some(holdings) = load_export_holdings(state).await? else {
                 return Ok(None);
-            }
-
-            let holdings = if account_ids.len() == 1 {
-                state
-                    .holdings_service
-                    .get_holdings(&account_ids[0], &base)
-                    .await?
-            } else {
-                state
-                    .holdings_service
-                    .get_holdings_for_accounts(&account_ids, &base, &resolved.scope_id)
-                    .await?
             };
             let records = holdings
                 .into_iter()
@@ -131,9 +144,98 @@ async fn export_data_route(
         .map_err(|e| ApiError::Internal(format!("Failed to build export response: {}", e)))
 }
 
-pub fn router() -> Router<Arc<AppState>> {
-    Router::new().route(
-        "/utilities/export/{data_type}/{format}",
-        get(export_data_route),
+async fn export_holdings_csv_route(State(state): State<Arc<AppState>>) -> ApiResult<Json<String>> {
+    let holdings = load_export_holdings(&state).await?.unwrap_or_default();
+    let asset_ids = holdings
+        .iter()
+        .filter_map(|holding| holding.instrument.as_ref())
+        .map(|instrument| instrument.id.clone())
+        .collect::<Vec<_>>();
+    let stale_asset_ids = state
+        .quote_service
+        .get_latest_quotes_snapshot(&asset_ids)?
+        .into_iter()
+        .filter(|(_, snapshot)| snapshot.is_stale)
+        .map(|(asset_id, _)| asset_id)
+        .collect::<HashSet<_>>();
+
+    Ok(Json(holdings_snapshot_csv(
+        &holdings,
+        &stale_asset_ids,
+        chrono::Utc::now(),
+    )?))
+}
+
+async fn export_archive_route(State(state): State<Arc<AppState>>) -> ApiResult<Response<Body>> {
+    let archive = build_archive(
+        state.account_service.as_ref(),
+        state.activity_service.as_ref(),
+        state.goal_service.as_ref(),
+        Some(env!("CARGO_PKG_VERSION").to_string()),
+    )?;
+    let content = archive.to_bytes()?;
+    let filename = format!(
+        "wealthfolio_archive_{}.json",
+        chrono::Local::now().date_naive().format("%Y-%m-%d")
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from(content))
+        .map_err(|e| ApiError::Internal(format!("Failed to build export response: {}", e)))
+}
+
+async fn import_archive_route(
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> ApiResult<Json<ArchiveRestoreResult>> {
+    let result = restore_archive(
+        &body,
+        state.account_service.as_ref(),
+        state.activity_service.as_ref(),
+        state.goal_service.as_ref(),
     )
+    .await?;
+    state.health_service.clear_cache().await;
+    Ok(Json(result))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveDiffBody {
+    left: String,
+    right: String,
+}
+
+async fn diff_archives_route(Json(body): Json<ArchiveDiffBody>) -> ApiResult<Json<ArchiveDiff>> {
+    Ok(Json(diff_archives(
+        body.left.as_bytes(),
+        body.right.as_bytes(),
+    )?))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/utilities/export/{data_type}/{format}",
+            get(export_data_route),
+        )
+        .route("/utilities/export/archive", get(export_archive_route))
+        .route(
+            "/utilities/export/holdings-csv",
+            get(export_holdings_csv_route),
+        )
+        .route(
+            "/utilities/export/archive/diff",
+            post(diff_archives_route).layer(DefaultBodyLimit::max(2 * ARCHIVE_MAX_BODY_BYTES)),
+        )
+        .route(
+            "/utilities/import/archive",
+            post(import_archive_route).layer(DefaultBodyLimit::max(ARCHIVE_MAX_BODY_BYTES)),
+        )
 }
@@ -10,7 +10,7 @@ use axum::{
     routing::{delete, get, patch, post},
     Json, Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
 use crate::api::device_sync_engine;
@@ -22,13 +22,18 @@ use wealthfolio_device_sync::{
     CompletePairingRequest, CompletePairingResponse, ConfirmPairingRequest, ConfirmPairingResponse,
     CreatePairingRequest, CreatePairingResponse, Device, DeviceSyncClient, EnrollDeviceResponse,
     GetPairingResponse, InitializeKeysResult, PairingMessagesResponse, RegisterDeviceRequest,
-    ResetTeamSyncResponse, RotateKeysResponse, SuccessResponse, SyncIdentity, UpdateDeviceRequest,
+    ResetTeamSyncResponse, RevokeDeviceResult, RotateKeysResponse, SuccessResponse, SyncIdentity,
+    UpdateDeviceRequest,
 };
 
 // Storage keys (without prefix - the SecretStore adds "wealthfolio_" prefix)
 const DEVICE_ID_KEY: &str = "sync_device_id";
 const SYNC_IDENTITY_KEY: &str = "sync_identity";
 
+/// Returned before any cloud call when this device has no enrolled identity.
+const NOT_ENROLLED_MESSAGE: &str =
+    "NOT_ENROLLED: This device is not enrolled in device sync. Enable sync first.";
+
 fn cloud_api_base_url() -> String {
     crate::features::cloud_api_base_url().unwrap_or_default()
 }
@@ -269,6 +274,9 @@ async fn list_devices(
 ) -> ApiResult<Json<Vec<Device>>> {
     info!("[DeviceSync] Listing devices (scope: {:?})...", query.scope);
 
+    if get_device_id(&state).is_none() {
+        return Err(ApiError::BadRequest(NOT_ENROLLED_MESSAGE.to_string()));
+    }
     let token = get_access_token(&state).await?;
 
     let devices = create_client()
@@ -323,18 +331,27 @@ async fn delete_device_endpoint(
     Ok(Json(result))
 }
 
+/// Revoking this server's own device also stops the engine and drops its enrollment and local
+/// sync session.
 async fn revoke_device_endpoint(
     State(state): State<Arc<AppState>>,
     Path(device_id): Path<String>,
-) -> ApiResult<Json<SuccessResponse>> {
+) -> ApiResult<Json<RevokeDeviceResult>> {
     info!("Revoking device: {}", device_id);
 
     let token = get_access_token(&state).await?;
 
-    let result = create_client()
-        .revoke_device(&token, &device_id)
+    let local = device_sync_engine::ServerSyncTeardown(Arc::clone(&state));
+    let result = state
+        .device_enroll_service
+        .revoke_device(&token, &device_id, &local)
         .await
-        .map_err(|e| ApiError::Internal(e.to_string()))?;
+        .map_err(|e| match e.code.as_str() {
+            "INVALID_REQUEST" => ApiError::BadRequest(e.message),
+            "REVOKE_REJECTED" => ApiError::Conflict(e.message),
+            // LOCAL_CLEANUP_FAILED: the cloud revoked the device, this server did not finish.
+            _ => ApiError::Internal(e.message),
+        })?;
 
     Ok(Json(result))
 }
@@ -767,6 +784,68 @@ async fn cancel_pairing_flow(
     Ok(Json(result))
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncRejectionsParams {
+    page: Option<i64>,
+    page_size: Option<i64>,
+    /// Comma-separated entity names, e.g. `activity,account`.
+    entities: Option<String>,
+}
+
+/// Local changes the cloud rejected as invalid, with the reason and a suggested fix.
+async fn list_sync_rejections(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SyncRejectionsParams>,
+) -> ApiResult<Json<wealthfolio_core::sync::SyncRejectionPage>> {
+    let entities = params
+        .entities
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entity| !entity.is_empty())
+        .map(|entity| {
+            serde_json::from_value(serde_json::Value::String(entity.to_string()))
+                .map_err(|_| ApiError::BadRequest(format!("Unknown sync entity '{}'", entity)))
+        })
+        .collect::<ApiResult<Vec<_>>>()?;
+    let page = state
+        .app_sync_repository
+        .list_sync_rejections(&wealthfolio_core::sync::SyncRejectionQuery {
+            page: params.page,
+            page_size: params.page_size,
+            entities,
+        })
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(page))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AcknowledgeRejectionsBody {
+    event_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AcknowledgeRejectionsResponse {
+    acknowledged: usize,
+}
+
+/// Dismisses the given rejections at once; their changes are dropped from the outbox.
+async fn acknowledge_sync_rejections(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<AcknowledgeRejectionsBody>,
+) -> ApiResult<Json<AcknowledgeRejectionsResponse>> {
+    let acknowledged = state
+        .app_sync_repository
+        .acknowledge_sync_rejections(body.event_ids)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(Json(AcknowledgeRejectionsResponse { acknowledged }))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Router
 // ─────────────────────────────────────────────────────────────────────────────
@@ -784,6 +863,11 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/sync/device/{device_id}", get(get_device_endpoint))
         .route("/sync/device/{device_id}", patch(update_device_endpoint))
         .route("/sync/device/{device_id}", delete(delete_device_endpoint))
+        .route("/sync/rejected", get(list_sync_rejections))
+        .route(
+            "/sync/rejected/acknowledge",
+            post(acknowledge_sync_rejections),
+        )
         .route(
             "/sync/device/{device_id}/revoke",
             post(revoke_device_endpoint),
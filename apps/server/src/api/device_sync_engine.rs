@@ -7,16 +7,20 @@ use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
 use chrono::{Duration, Utc};
 use uuid::Uuid;
 
+use crate::events::{ServerEvent, SYNC_BOOTSTRAP_TRIGGERED};
 use crate::main_lib::AppState;
 use wealthfolio_core::events::DomainEvent;
+use wealthfolio_core::settings::CloudAccessService;
 use wealthfolio_core::sync::APP_SYNC_TABLES;
 use wealthfolio_device_sync::engine::{
-    self, CredentialStore, OutboxStore, ReadyReconcileStore, ReplayEvent, ReplayStore,
-    SyncIdentity, SyncTransport, TransportError,
+    self, CredentialStore, OutboxStore, ReadyReconcileStore, ReplayApplyError, ReplayEvent,
+    ReplayFailurePolicy, ReplayStore, SyncIdentity, SyncTransport, TransportError,
 };
 use wealthfolio_device_sync::{
-    DeviceSyncClient, ReconcileReadyStateResponse, SyncPullResponse, SyncPushRequest,
-    SyncPushResponse, SyncState,
+    fetch_latest_verified_snapshot, forecast_cursor_expiry, parse_sync_datetime_to_utc,
+    CursorExpiryForecast, DeviceSyncClient, LocalSyncTeardown, ReconcileReadyStateResponse,
+    SnapshotFetchError, SyncPullResponse, SyncPushRequest, SyncPushResponse, SyncState,
+    MAX_SNAPSHOT_FETCH_ATTEMPTS, MAX_SNAPSHOT_MANIFEST_REFRESHES, SNAPSHOT_SCHEMA_VERSION,
 };
 
 fn transport_err_from_sync(e: wealthfolio_device_sync::DeviceSyncError) -> TransportError {
@@ -28,6 +32,7 @@ fn transport_err_from_sync(e: wealthfolio_device_sync::DeviceSyncError) -> Trans
             wealthfolio_device_sync::DeviceSyncError::Api { details, .. } => details.clone(),
             _ => None,
         },
+        retry_after: e.retry_after_hint(),
     }
 }
 use wealthfolio_storage_sqlite::sync::{SqliteSyncEngineDbPorts, SyncTableRowCount};
@@ -332,7 +337,7 @@ async fn abort_pairing_flow_local_state(state: &Arc<AppState>, pairing_id: &str)
             err.message
         );
     }
-    let _ = state.app_sync_repository.reset_local_sync_session().await;
+    let _ = reset_local_sync_session(state).await;
     let _ = state.secret_store.delete_secret(DEVICE_ID_KEY);
     clear_min_snapshot_created_at_from_store();
     let _ = state
@@ -453,7 +458,8 @@ struct ServerEnginePorts {
 
 impl ServerEnginePorts {
     fn new(state: Arc<AppState>) -> Self {
-        let db = SqliteSyncEngineDbPorts::new(Arc::clone(&state.app_sync_repository));
+        let db = SqliteSyncEngineDbPorts::new(Arc::clone(&state.app_sync_repository))
+            .with_replay_failure_policy(ReplayFailurePolicy::from_env());
         Self { state, db }
     }
 }
@@ -478,6 +484,18 @@ impl OutboxStore for ServerEnginePorts {
             .await
     }
 
+    async fn mark_outbox_rejected(
+        &self,
+        event_id: String,
+        error_message: Option<String>,
+        error_code: Option<String>,
+        field_errors: Vec<wealthfolio_core::sync::SyncFieldError>,
+    ) -> Result<(), String> {
+        self.db
+            .mark_outbox_rejected(event_id, error_message, error_code, field_errors)
+            .await
+    }
+
     async fn mark_outbox_sent(&self, event_ids: Vec<String>) -> Result<(), String> {
         self.db.mark_outbox_sent(event_ids).await
     }
@@ -505,26 +523,29 @@ impl OutboxStore for ServerEnginePorts {
 
 #[async_trait]
 impl ReplayStore for ServerEnginePorts {
-    async fn acquire_cycle_lock(&self) -> Result<i64, String> {
-        self.db.acquire_cycle_lock().await
+    async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String> {
+        self.state.sync_state_store.acquire_cycle_lock().await
     }
 
     async fn verify_cycle_lock(&self, lock_version: i64) -> Result<bool, String> {
-        self.db.verify_cycle_lock(lock_version).await
+        self.state
+            .sync_state_store
+            .verify_cycle_lock(lock_version)
+            .await
     }
 
     async fn get_cursor(&self) -> Result<i64, String> {
-        self.db.get_cursor().await
+        self.state.sync_state_store.get_cursor().await
     }
 
     async fn set_cursor(&self, cursor: i64) -> Result<(), String> {
-        self.db.set_cursor(cursor).await
+        self.state.sync_state_store.set_cursor(cursor).await
     }
 
     async fn apply_remote_events_lww_batch(
         &self,
         events: Vec<ReplayEvent>,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, ReplayApplyError> {
         self.db.apply_remote_events_lww_batch(events).await
     }
 
@@ -532,6 +553,10 @@ impl ReplayStore for ServerEnginePorts {
         self.db.apply_remote_event_lww(event).await
     }
 
+    fn replay_failure_policy(&self) -> ReplayFailurePolicy {
+        self.db.replay_failure_policy()
+    }
+
     async fn mark_pull_completed(&self) -> Result<(), String> {
         self.db.mark_pull_completed().await
     }
@@ -542,6 +567,7 @@ impl ReplayStore for ServerEnginePorts {
         duration_ms: i64,
         next_retry_at: Option<String>,
     ) -> Result<(), String> {
+        self.state.metrics.record_device_sync_cycle(&status);
         self.db
             .mark_cycle_outcome(status, duration_ms, next_retry_at)
             .await
@@ -575,6 +601,30 @@ impl ReplayStore for ServerEnginePorts {
         }
         Ok(())
     }
+
+    async fn restore_snapshot_for_stale_cursor(
+        &self,
+    ) -> Result<engine::SyncBootstrapResult, String> {
+        let result = restore_snapshot_for_stale_cursor(Arc::clone(&self.state)).await?;
+        Ok(engine::SyncBootstrapResult {
+            status: result.status,
+            message: result.message,
+            snapshot_id: result.snapshot_id,
+        })
+    }
+
+    async fn auto_bootstrap(&self, reason: &str) -> Result<engine::SyncBootstrapResult, String> {
+        self.state.event_bus.publish(ServerEvent::with_payload(
+            SYNC_BOOTSTRAP_TRIGGERED,
+            serde_json::json!({ "reason": reason }),
+        ));
+        let result = sync_bootstrap_snapshot_if_needed(Arc::clone(&self.state)).await?;
+        Ok(engine::SyncBootstrapResult {
+            status: result.status,
+            message: result.message,
+            snapshot_id: result.snapshot_id,
+        })
+    }
 }
 
 #[async_trait]
@@ -681,6 +731,37 @@ impl CredentialStore for ServerEnginePorts {
     }
 }
 
+/// Drops the local sync session and rewinds the cursor in the sync state store with it.
+pub async fn reset_local_sync_session(state: &AppState) -> Result<(), String> {
+    state
+        .app_sync_repository
+        .reset_local_sync_session()
+        .await
+        .map_err(|e| e.to_string())?;
+    state.sync_state_store.set_cursor(0).await
+}
+
+/// Tears down this server's device sync when its own device is revoked.
+pub struct ServerSyncTeardown(pub Arc<AppState>);
+
+#[async_trait]
+impl LocalSyncTeardown for ServerSyncTeardown {
+    async fn stop_engine(&self) -> Result<(), String> {
+        self.0.device_sync_runtime.ensure_background_stopped().await;
+        Ok(())
+    }
+
+    async fn reset_local_session(&self) -> Result<(), String> {
+        reset_local_sync_session(&self.0).await?;
+        clear_min_snapshot_created_at_from_store();
+        self.0
+            .app_sync_repository
+            .clear_all_min_snapshot_created_at()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
 pub async fn get_engine_status(state: &Arc<AppState>) -> Result<SyncEngineStatusResult, String> {
     ensure_device_sync_enabled()?;
     let status = state
@@ -694,10 +775,11 @@ pub async fn get_engine_status(state: &Arc<AppState>) -> Result<SyncEngineStatus
             .map_err(|e| e.to_string())?,
         None => true,
     };
+    let cursor = state.sync_state_store.get_cursor().await?;
     let background_running = state.device_sync_runtime.is_background_running().await;
 
     Ok(SyncEngineStatusResult {
-        cursor: status.cursor,
+        cursor,
         last_push_at: status.last_push_at,
         last_pull_at: status.last_pull_at,
         last_error: status.last_error,
@@ -710,6 +792,24 @@ pub async fn get_engine_status(state: &Arc<AppState>) -> Result<SyncEngineStatus
     })
 }
 
+pub fn get_cursor_expiry_forecast(state: &Arc<AppState>) -> Result<CursorExpiryForecast, String> {
+    ensure_device_sync_enabled()?;
+    let status = state
+        .app_sync_repository
+        .get_engine_status()
+        .map_err(|e| e.to_string())?;
+    let last_pull_at = status
+        .last_pull_at
+        .as_deref()
+        .map(parse_sync_datetime_to_utc)
+        .transpose()?;
+    Ok(forecast_cursor_expiry(
+        last_pull_at,
+        crate::features::sync_server_retention_days(),
+        Utc::now(),
+    ))
+}
+
 pub async fn get_pairing_source_status(
     state: &Arc<AppState>,
 ) -> Result<SyncPairingSourceStatusResult, String> {
@@ -732,10 +832,7 @@ pub async fn get_pairing_source_status(
         return Err("Current device is not ready to connect another device yet.".to_string());
     }
 
-    let local_cursor = state
-        .app_sync_repository
-        .get_cursor()
-        .map_err(|e| e.to_string())?;
+    let local_cursor = state.sync_state_store.get_cursor().await?;
     let server_cursor = client
         .get_events_cursor(&token, &device_id)
         .await
@@ -906,6 +1003,9 @@ pub async fn reconcile_ready_state(
 
 pub async fn ensure_background_engine_started(state: Arc<AppState>) -> Result<(), String> {
     ensure_device_sync_enabled()?;
+    if CloudAccessService::new(state.settings_service.clone()).is_disabled() {
+        return Ok(());
+    }
     let Some(identity) = get_sync_identity_from_store(&state) else {
         return Ok(());
     };
@@ -1107,7 +1207,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                 status: "skipped".to_string(),
                 message: "Snapshot bootstrap already completed".to_string(),
                 snapshot_id: None,
-                cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                cursor: Some(state.sync_state_store.get_cursor().await?),
             });
         }
 
@@ -1138,7 +1238,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                         message: "Waiting for a snapshot generated after pairing confirmation"
                             .to_string(),
                         snapshot_id: None,
-                        cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                        cursor: Some(state.sync_state_store.get_cursor().await?),
                     });
                 }
                 let client = create_client();
@@ -1153,7 +1253,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                             status: "skipped".to_string(),
                             message,
                             snapshot_id: None,
-                            cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                            cursor: Some(state.sync_state_store.get_cursor().await?),
                         });
                     }
                     MissingSnapshotDisposition::WaitForSnapshot { message } => {
@@ -1161,7 +1261,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                             status: "requested".to_string(),
                             message,
                             snapshot_id: None,
-                            cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                            cursor: Some(state.sync_state_store.get_cursor().await?),
                         });
                     }
                 }
@@ -1182,7 +1282,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                     message: "Waiting for a snapshot generated after pairing confirmation"
                         .to_string(),
                     snapshot_id: None,
-                    cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                    cursor: Some(state.sync_state_store.get_cursor().await?),
                 });
             }
             let client = create_client();
@@ -1197,7 +1297,7 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                         status: "skipped".to_string(),
                         message,
                         snapshot_id: None,
-                        cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                        cursor: Some(state.sync_state_store.get_cursor().await?),
                     });
                 }
                 MissingSnapshotDisposition::WaitForSnapshot { message } => {
@@ -1205,30 +1305,27 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                         status: "requested".to_string(),
                         message,
                         snapshot_id: None,
-                        cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                        cursor: Some(state.sync_state_store.get_cursor().await?),
                     });
                 }
             }
         }
     };
 
-    const LOCAL_SCHEMA_VERSION: i32 = 1;
-    if latest.schema_version > LOCAL_SCHEMA_VERSION {
+    if latest.schema_version > SNAPSHOT_SCHEMA_VERSION {
         return Err(format!(
             "Snapshot schema version {} is newer than local version {}. Please update the app.",
-            latest.schema_version, LOCAL_SCHEMA_VERSION
+            latest.schema_version, SNAPSHOT_SCHEMA_VERSION
         ));
     }
 
-    let snapshot_id = latest.snapshot_id.trim().to_string();
-    if snapshot_id.is_empty() {
+    if latest.snapshot_id.trim().is_empty() {
         return Err(
             "Latest snapshot metadata had empty snapshot_id. No valid snapshot available."
                 .to_string(),
         );
     }
 
-    let snapshot_oplog_seq = latest.oplog_seq;
     if let Some(min_created_at) = min_snapshot_created_at.as_deref() {
         let client = create_client();
         if !snapshot_satisfies_freshness_gate(&client, &token, &device_id, &latest, min_created_at)
@@ -1242,56 +1339,127 @@ pub async fn sync_bootstrap_snapshot_if_needed(
                 status: "requested".to_string(),
                 message: "Waiting for a snapshot generated after pairing confirmation".to_string(),
                 snapshot_id: None,
-                cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                cursor: Some(state.sync_state_store.get_cursor().await?),
             });
         }
     }
-    let latest_checksum = if latest.checksum.trim().is_empty() {
-        None
-    } else {
-        Some(latest.checksum)
-    };
-    let latest_tables = if latest.covers_tables.is_empty() {
-        APP_SYNC_TABLES.iter().map(|v| v.to_string()).collect()
-    } else {
-        latest.covers_tables
-    };
 
-    let (headers, blob) = match create_client()
-        .download_snapshot(&token, &device_id, &snapshot_id)
-        .await
+    // Verify the payload against the manifest before anything touches the local database;
+    // corrupt downloads are re-fetched a bounded number of times, and a snapshot pruned since
+    // the manifest was read is replaced by the manifest's latest one.
+    let client = create_client();
+    let (download_client, download_token, download_device_id) =
+        (&client, token.as_str(), device_id.as_str());
+    let (latest, _headers, blob) = match fetch_latest_verified_snapshot(
+        latest,
+        MAX_SNAPSHOT_FETCH_ATTEMPTS,
+        MAX_SNAPSHOT_MANIFEST_REFRESHES,
+        || client.get_latest_snapshot_with_cursor_fallback(&token, &device_id),
+        move |snapshot_id| async move {
+            download_client
+                .download_snapshot(download_token, download_device_id, &snapshot_id)
+                .await
+        },
+    )
+    .await
     {
         Ok(value) => value,
-        Err(err) => {
-            if err.status_code() == Some(404) {
-                return Err(format!(
-                    "Snapshot {} is no longer available. No valid snapshot to download.",
-                    snapshot_id
-                ));
+        Err(SnapshotFetchError::NoSnapshotAvailable { snapshot_id }) => {
+            tracing::warn!(
+                "[DeviceSync] Snapshot {} was pruned and no other snapshot exists",
+                snapshot_id
+            );
+            if min_snapshot_created_at.is_some() {
+                return Ok(SyncBootstrapResult {
+                    status: "requested".to_string(),
+                    message: "Waiting for a snapshot generated after pairing confirmation"
+                        .to_string(),
+                    snapshot_id: None,
+                    cursor: Some(state.sync_state_store.get_cursor().await?),
+                });
+            }
+            // Fall back to an events-only bootstrap when the cloud does not require a snapshot.
+            match classify_missing_snapshot_disposition(&client, &token, &device_id).await {
+                MissingSnapshotDisposition::CompleteNoBootstrap { message } => {
+                    sync_repo
+                        .reset_and_mark_bootstrap_complete(device_id, identity.key_version)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    clear_min_snapshot_created_at_from_store();
+                    return Ok(SyncBootstrapResult {
+                        status: "skipped".to_string(),
+                        message,
+                        snapshot_id: None,
+                        cursor: Some(state.sync_state_store.get_cursor().await?),
+                    });
+                }
+                MissingSnapshotDisposition::WaitForSnapshot { message } => {
+                    return Ok(SyncBootstrapResult {
+                        status: "requested".to_string(),
+                        message,
+                        snapshot_id: None,
+                        cursor: Some(state.sync_state_store.get_cursor().await?),
+                    });
+                }
             }
-            return Err(err.to_string());
         }
+        Err(err) => return Err(err.to_string()),
     };
-    let actual_checksum = sha256_checksum(&blob);
-    if headers.checksum != actual_checksum {
+    // A replacement snapshot may have been written by a newer app version.
+    if latest.schema_version > SNAPSHOT_SCHEMA_VERSION {
         return Err(format!(
-            "Snapshot checksum mismatch (download header): expected={}, got={}",
-            headers.checksum, actual_checksum
+            "Snapshot schema version {} is newer than local version {}. Please update the app.",
+            latest.schema_version, SNAPSHOT_SCHEMA_VERSION
         ));
     }
-    if let Some(expected_checksum) = latest_checksum.as_ref() {
-        if expected_checksum != &actual_checksum {
-            return Err(format!(
-                "Snapshot checksum mismatch (latest metadata): expected={}, got={}",
-                expected_checksum, actual_checksum
-            ));
-        }
+    let (snapshot_id, snapshot_oplog_seq) =
+        restore_verified_snapshot(&state, &identity, &device_id, latest, blob, false).await?;
+
+    // Trigger portfolio recalculation so derived state is up-to-date
+    state
+        .domain_event_sink
+        .emit(DomainEvent::device_sync_pull_complete());
+
+    // Clear freshness gate from both in-memory and SQLite
+    clear_min_snapshot_created_at_from_store();
+    if let Err(err) = sync_repo.clear_min_snapshot_created_at(device_id).await {
+        tracing::warn!(
+            "[DeviceSync] Failed to clear freshness gate from SQLite: {}",
+            err
+        );
     }
 
-    let sqlite_image = decode_snapshot_sqlite_payload(blob, &identity)?;
-    let temp_snapshot_path =
-        std::env::temp_dir().join(format!("wf_snapshot_server_{}.db", Uuid::new_v4()));
-    std::fs::write(&temp_snapshot_path, sqlite_image)
+    Ok(SyncBootstrapResult {
+        status: "applied".to_string(),
+        message: "Snapshot bootstrap completed".to_string(),
+        snapshot_id: Some(snapshot_id),
+        cursor: Some(snapshot_oplog_seq),
+    })
+}
+
+/// Restores the tables a verified snapshot covers and moves the cursor to it. With
+/// `keep_unsynced`, local changes that were not pushed yet are kept on top of the snapshot.
+async fn restore_verified_snapshot(
+    state: &AppState,
+    identity: &SyncIdentity,
+    device_id: &str,
+    latest: wealthfolio_device_sync::SnapshotLatestResponse,
+    blob: Vec<u8>,
+    keep_unsynced: bool,
+) -> Result<(String, i64), String> {
+    let sync_repo = &state.app_sync_repository;
+    let snapshot_id = latest.snapshot_id.trim().to_string();
+    let snapshot_oplog_seq = latest.oplog_seq;
+    let latest_tables = if latest.covers_tables.is_empty() {
+        APP_SYNC_TABLES.iter().map(|v| v.to_string()).collect()
+    } else {
+        latest.covers_tables
+    };
+
+    let sqlite_image = decode_snapshot_sqlite_payload(blob, identity)?;
+    let temp_snapshot_path = sync_repo
+        .blob_storage()
+        .write_blob("wf_snapshot_server", &sqlite_image)
         .map_err(|e| format!("Failed to persist snapshot image: {}", e))?;
     let snapshot_path_str = temp_snapshot_path.to_string_lossy().to_string();
 
@@ -1307,35 +1475,97 @@ pub async fn sync_bootstrap_snapshot_if_needed(
             .collect();
     }
 
-    let restore_result = sync_repo
-        .restore_snapshot_tables_from_file(
-            snapshot_path_str,
-            tables_to_restore,
-            snapshot_oplog_seq,
-            device_id.clone(),
-            identity.key_version,
-        )
-        .await;
+    let restore_result = if keep_unsynced {
+        sync_repo
+            .restore_snapshot_tables_keeping_unsynced_from_file(
+                snapshot_path_str,
+                tables_to_restore,
+                snapshot_oplog_seq,
+                device_id.to_string(),
+                identity.key_version,
+            )
+            .await
+    } else {
+        sync_repo
+            .restore_snapshot_tables_from_file(
+                snapshot_path_str,
+                tables_to_restore,
+                snapshot_oplog_seq,
+                device_id.to_string(),
+                identity.key_version,
+            )
+            .await
+    };
     let _ = std::fs::remove_file(&temp_snapshot_path);
     restore_result.map_err(|e| e.to_string())?;
+    state
+        .sync_state_store
+        .set_cursor(snapshot_oplog_seq)
+        .await?;
+    Ok((snapshot_id, snapshot_oplog_seq))
+}
 
-    // Trigger portfolio recalculation so derived state is up-to-date
+/// Resumes from the latest snapshot after the server reported the local cursor too old.
+/// Unlike [`sync_bootstrap_snapshot_if_needed`] it always restores and keeps local changes
+/// that were not pushed yet.
+pub async fn restore_snapshot_for_stale_cursor(
+    state: Arc<AppState>,
+) -> Result<SyncBootstrapResult, String> {
+    ensure_device_sync_enabled()?;
+    let identity = get_sync_identity_from_store(&state)
+        .ok_or_else(|| "No sync identity configured. Please enable sync first.".to_string())?;
+    let device_id = identity
+        .device_id
+        .clone()
+        .ok_or_else(|| "No device ID configured".to_string())?;
+    let token = crate::api::connect::mint_access_token(&state)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let client = create_client();
+    let Some(latest) = client
+        .get_latest_snapshot_with_cursor_fallback(&token, &device_id)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(SyncBootstrapResult {
+            status: "unavailable".to_string(),
+            message: "No snapshot is available to resume from".to_string(),
+            snapshot_id: None,
+            cursor: None,
+        });
+    };
+    let (download_client, download_token, download_device_id) =
+        (&client, token.as_str(), device_id.as_str());
+    let (latest, _headers, blob) = fetch_latest_verified_snapshot(
+        latest,
+        MAX_SNAPSHOT_FETCH_ATTEMPTS,
+        MAX_SNAPSHOT_MANIFEST_REFRESHES,
+        || client.get_latest_snapshot_with_cursor_fallback(&token, &device_id),
+        move |snapshot_id| async move {
+            download_client
+                .download_snapshot(download_token, download_device_id, &snapshot_id)
+                .await
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if latest.schema_version > SNAPSHOT_SCHEMA_VERSION {
+        return Err(format!(
+            "Snapshot schema version {} is newer than local version {}. Please update the app.",
+            latest.schema_version, SNAPSHOT_SCHEMA_VERSION
+        ));
+    }
+
+    let (snapshot_id, snapshot_oplog_seq) =
+        restore_verified_snapshot(&state, &identity, &device_id, latest, blob, true).await?;
     state
         .domain_event_sink
         .emit(DomainEvent::device_sync_pull_complete());
 
-    // Clear freshness gate from both in-memory and SQLite
-    clear_min_snapshot_created_at_from_store();
-    if let Err(err) = sync_repo.clear_min_snapshot_created_at(device_id).await {
-        tracing::warn!(
-            "[DeviceSync] Failed to clear freshness gate from SQLite: {}",
-            err
-        );
-    }
-
     Ok(SyncBootstrapResult {
         status: "applied".to_string(),
-        message: "Snapshot bootstrap completed".to_string(),
+        message: "Resumed from the latest snapshot".to_string(),
         snapshot_id: Some(snapshot_id),
         cursor: Some(snapshot_oplog_seq),
     })
@@ -1383,7 +1613,7 @@ pub async fn generate_snapshot_now(
         ));
     }
 
-    let local_cursor = state.app_sync_repository.get_cursor().ok();
+    let local_cursor = state.sync_state_store.get_cursor().await.ok();
     let server_cursor = create_client()
         .get_events_cursor(&token, &device_id)
         .await
@@ -1458,7 +1688,7 @@ pub async fn generate_snapshot_now(
     );
     let upload_headers = wealthfolio_device_sync::SnapshotUploadHeaders {
         event_id: Some(Uuid::now_v7().to_string()),
-        schema_version: 1,
+        schema_version: SNAPSHOT_SCHEMA_VERSION,
         covers_tables: APP_SYNC_TABLES.iter().map(|v| v.to_string()).collect(),
         size_bytes: payload.len() as i64,
         checksum,
@@ -2065,6 +2295,7 @@ mod tests {
             "PAIRING_ALREADY_APPROVED",
             "Pairing already approved",
             None,
+            None,
         );
 
         assert!(is_pairing_already_approved_error(&err));
@@ -2077,6 +2308,7 @@ mod tests {
             "PAIRING_INVALID_STATE",
             "Pairing cannot be approved from this state",
             None,
+            None,
         );
 
         assert!(!is_pairing_already_approved_error(&err));
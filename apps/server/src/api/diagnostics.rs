@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use crate::{error::ApiResult, main_lib::AppState};
+use axum::{extract::State, routing::get, Json, Router};
+use wealthfolio_connect::{
+    DeviceSyncDiagnostics, DiagnosticsSources, FeatureDiagnostics, SyncDiagnostics,
+    SyncRunDiagnostics,
+};
+
+/// Redacted sync diagnostics for bug reports. Reads local state only, so it answers even when
+/// the cloud is unreachable.
+async fn get_diagnostics(State(state): State<Arc<AppState>>) -> ApiResult<Json<SyncDiagnostics>> {
+    let broker_sync_states = if crate::features::connect_sync_enabled() {
+        state.connect_sync_service.get_all_sync_states()?
+    } else {
+        Vec::new()
+    };
+    let last_run = state
+        .broker_sync_history
+        .read()
+        .unwrap()
+        .last_run
+        .as_ref()
+        .map(|run| SyncRunDiagnostics {
+            success: run.success,
+            finished_at: run.finished_at,
+            error: run.error.clone(),
+        });
+    let cloud_api_url = crate::features::cloud_api_base_url();
+    let device_sync = DeviceSyncDiagnostics {
+        enrolled: super::health::device_sync_enrolled(&state),
+        engine_running: state.device_sync_runtime.is_background_running().await,
+    };
+
+    Ok(Json(SyncDiagnostics::collect(DiagnosticsSources {
+        runtime: "server",
+        app_version: env!("CARGO_PKG_VERSION"),
+        features: FeatureDiagnostics {
+            connect_sync: crate::features::connect_sync_enabled(),
+            device_sync: crate::features::device_sync_enabled(),
+        },
+        cloud_api_url: cloud_api_url.as_deref(),
+        secret_store: state.secret_store.as_ref(),
+        broker_sync_states: &broker_sync_states,
+        last_run,
+        device_sync,
+        ledger_validation: state.ledger_validation_service.last_summary(),
+    })))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/diagnostics", get(get_diagnostics))
+}
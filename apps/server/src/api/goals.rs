@@ -194,7 +194,8 @@ async fn build_valuation_map(state: &AppState) -> ApiResult<HashMap<String, f64>
         state.asset_service.as_ref(),
         state.quote_service.as_ref(),
         state.fx_service.as_ref(),
-    );
+    )
+    .with_stale_quote_policy(crate::features::stale_quote_policy());
     let response = service
         .get_current_valuation_for_scope(
             "all",
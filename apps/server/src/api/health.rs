@@ -1,6 +1,7 @@
-use std::sync::Arc;
+use std::sync::{atomic::Ordering, Arc};
 
 use crate::{
+    connectivity::ConnectivityStatus,
     error::{ApiError, ApiResult},
     main_lib::AppState,
 };
@@ -10,6 +11,9 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use wealthfolio_connect::{BrokerSyncSummary, CLOUD_REFRESH_TOKEN_KEY};
 use wealthfolio_core::health::{FixAction, HealthConfig, HealthStatus};
 
 /// Get current health status (cached or fresh check).
@@ -179,6 +183,82 @@ async fn update_health_config(
     Ok(())
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BrokerSyncHealth {
+    enabled: bool,
+    running: bool,
+    /// Most recent run since startup, successful or not.
+    last_run: Option<BrokerSyncSummary>,
+    last_success_at: Option<DateTime<Utc>>,
+    /// Error of the most recent run, if it failed.
+    last_error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceSyncHealth {
+    enabled: bool,
+    /// This device has an identity and root key, so the background engine can run.
+    enrolled: bool,
+    engine_running: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncHealth {
+    refresh_token_configured: bool,
+    /// Last cloud reachability check before a scheduled sync, `None` until one ran.
+    connectivity: Option<ConnectivityStatus>,
+    broker_sync: BrokerSyncHealth,
+    device_sync: DeviceSyncHealth,
+}
+
+#[cfg(feature = "device-sync")]
+pub(crate) fn device_sync_enrolled(state: &AppState) -> bool {
+    crate::api::device_sync_engine::get_sync_identity_from_store(state)
+        .as_ref()
+        .is_some_and(crate::api::device_sync_engine::sync_identity_can_run_background)
+}
+
+#[cfg(not(feature = "device-sync"))]
+pub(crate) fn device_sync_enrolled(_state: &AppState) -> bool {
+    false
+}
+
+/// Sync subsystem state from local data only; answers `200` even when sync is not configured.
+async fn get_sync_health(State(state): State<Arc<AppState>>) -> ApiResult<Json<SyncHealth>> {
+    let refresh_token_configured = state
+        .secret_store
+        .get_secret(CLOUD_REFRESH_TOKEN_KEY)
+        .ok()
+        .flatten()
+        .is_some_and(|token| !token.trim().is_empty());
+
+    let (last_run, last_success_at) = {
+        let history = state.broker_sync_history.read().unwrap();
+        (history.last_run.clone(), history.last_success_at)
+    };
+    let last_error = last_run.as_ref().and_then(|run| run.error.clone());
+
+    Ok(Json(SyncHealth {
+        refresh_token_configured,
+        connectivity: state.connectivity.last_status(),
+        broker_sync: BrokerSyncHealth {
+            enabled: crate::features::connect_sync_enabled(),
+            running: state.broker_sync_running.load(Ordering::Acquire),
+            last_run,
+            last_success_at,
+            last_error,
+        },
+        device_sync: DeviceSyncHealth {
+            enabled: crate::features::device_sync_enabled(),
+            enrolled: device_sync_enrolled(&state),
+            engine_running: state.device_sync_runtime.is_background_running().await,
+        },
+    }))
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/health/status", get(get_health_status))
@@ -187,6 +267,7 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/health/restore", post(restore_health_issue))
         .route("/health/dismissed", get(get_dismissed_health_issues))
         .route("/health/fix", post(execute_health_fix))
+        .route("/health/sync", get(get_sync_health))
         .route(
             "/health/config",
             get(get_health_config).put(update_health_config),
@@ -17,6 +17,12 @@ pub struct AllocationFilterBody {
     pub category_id: String,
 }
 
+#[derive(Deserialize)]
+pub struct AllocationBreakdownBody {
+    pub filter: AccountScope,
+    pub dimension: String,
+}
+
 #[derive(Deserialize)]
 pub struct AccountIdQuery {
     #[serde(rename = "accountId")]
@@ -240,3 +246,44 @@ pub struct CheckHoldingsImportResult {
     pub symbols: Vec<SymbolCheckResult>,
     pub validation_errors: Vec<String>,
 }
+
+/// A single position from a statement, imported without transaction history
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPositionInput {
+    /// Known asset ID (preferred when available)
+    pub asset_id: Option<String>,
+    /// Symbol (e.g., "AAPL", "META.TO") - used when asset_id is not provided
+    pub symbol: String,
+    /// Exchange MIC code (e.g., "XNAS", "XTSE")
+    pub exchange_mic: Option<String>,
+    /// Quantity held
+    pub quantity: String,
+    /// Total cost basis for the position, in the position currency
+    pub cost_basis: Option<String>,
+    /// Position currency; defaults to the account currency
+    pub currency: Option<String>,
+    /// Asset name for custom assets
+    pub name: Option<String>,
+    /// Quote mode hint ("MARKET" or "MANUAL")
+    pub quote_mode: Option<String>,
+}
+
+/// Request body for importing a positions snapshot as opening balances
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPositionsRequest {
+    pub account_id: String,
+    /// Opening balance date (YYYY-MM-DD); defaults to today
+    pub as_of_date: Option<String>,
+    pub positions: Vec<ImportPositionInput>,
+}
+
+/// Result of importing a positions snapshot
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPositionsResult {
+    /// IDs of the opening-balance activities that were created
+    pub activity_ids: Vec<String>,
+    pub positions_imported: usize,
+}
@@ -10,9 +10,12 @@ use wealthfolio_core::portfolios::{AccountScope, ResolvedAccountScope};
 use wealthfolio_core::utils::time_utils::{parse_user_timezone_or_default, user_today};
 use wealthfolio_core::{
     accounts::{account_supports_purpose, AccountPurpose, AccountServiceTrait},
+    activities::ActivityBulkMutationRequest,
     lots::AssetLotView,
     portfolio::{
-        allocation::{AllocationHoldings, PortfolioAllocations},
+        allocation::{
+            AllocationDimension, AllocationHoldings, AllocationSlice, PortfolioAllocations,
+        },
         holdings::{Holding, HoldingListItem},
         snapshot::{
             reconcile_quote_sync_from_latest_account_snapshots, CashBalanceInput,
@@ -28,13 +31,15 @@ use wealthfolio_core::{
 use crate::{api::shared::holdings_account_ids, error::ApiResult, main_lib::AppState};
 
 use super::dto::{
-    AccountIdQuery, AllocationFilterBody, AllocationHoldingsQuery, AssetHoldingsQuery,
-    AssetLotsQuery, CheckHoldingsImportRequest, CheckHoldingsImportResult, CurrentValuationBody,
-    DeleteSnapshotQuery, FilterBody, HistoryFilterBody, HistoryQuery, HoldingItemQuery,
-    HoldingsSnapshotInput, ImportHoldingsCsvRequest, ImportHoldingsCsvResult,
-    SaveManualHoldingsRequest, SnapshotDateQuery, SnapshotInfo, SnapshotsQuery, SymbolCheckResult,
+    AccountIdQuery, AllocationBreakdownBody, AllocationFilterBody, AllocationHoldingsQuery,
+    AssetHoldingsQuery, AssetLotsQuery, CheckHoldingsImportRequest, CheckHoldingsImportResult,
+    CurrentValuationBody, DeleteSnapshotQuery, FilterBody, HistoryFilterBody, HistoryQuery,
+    HoldingItemQuery, HoldingsSnapshotInput, ImportHoldingsCsvRequest, ImportHoldingsCsvResult,
+    ImportPositionsRequest, ImportPositionsResult, SaveManualHoldingsRequest, SnapshotDateQuery,
+    SnapshotInfo, SnapshotsQuery, SymbolCheckResult,
 };
 use super::mappers::{parse_date, parse_date_optional, snapshot_source_to_string};
+use super::opening_balance::build_opening_balance_activities;
 
 fn resolve_scope(
     filter: &AccountScope,
@@ -75,7 +80,7 @@ fn resolve_current_valuation_scope(
                 .map_err(crate::error::ApiError::from)?
                 .account_ids
         }
-        AccountScope::All => resolved.account_ids.clone(),
+        AccountScope::All | AccountScope::Unassigned => resolved.account_ids.clone(),
     };
 
     Ok(ResolvedAccountScope {
@@ -368,7 +373,8 @@ pub async fn get_current_valuation(
         state.asset_service.as_ref(),
         state.quote_service.as_ref(),
         state.fx_service.as_ref(),
-    );
+    )
+    .with_stale_quote_policy(crate::features::stale_quote_policy());
     let valuation = service
         .get_current_valuation_for_scope(
             &resolved.scope_id,
@@ -662,6 +668,64 @@ pub async fn save_manual_holdings_handler(
     Ok(axum::http::StatusCode::OK)
 }
 
+/// POST /holdings/import — seed holdings from a positions snapshot.
+///
+/// Each position is recorded as a synthetic opening-balance activity so valuation
+/// works without a full transaction history.
+pub async fn import_positions_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ImportPositionsRequest>,
+) -> ApiResult<Json<ImportPositionsResult>> {
+    tracing::info!(
+        "Importing {} positions as opening balances for account {}",
+        req.positions.len(),
+        req.account_id
+    );
+
+    let account = state.account_service.get_account(&req.account_id)?;
+
+    let as_of = match req.as_of_date.as_deref() {
+        Some(date_str) => NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
+            crate::error::ApiError::BadRequest(format!("Invalid date format: {}", e))
+        })?,
+        None => {
+            let timezone = state.timezone.read().unwrap().clone();
+            user_today(parse_user_timezone_or_default(&timezone))
+        }
+    };
+
+    let creates =
+        build_opening_balance_activities(&req.account_id, &account.currency, as_of, &req.positions)
+            .map_err(|errors| crate::error::ApiError::BadRequest(errors.join("; ")))?;
+
+    let result = state
+        .activity_service
+        .bulk_mutate_activities(ActivityBulkMutationRequest {
+            creates,
+            updates: Vec::new(),
+            delete_ids: Vec::new(),
+        })
+        .await?;
+
+    if !result.errors.is_empty() {
+        let message = result
+            .errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(crate::error::ApiError::BadRequest(message));
+    }
+
+    state.health_service.clear_cache().await;
+    // Domain events handle asset enrichment and portfolio recalculation
+
+    Ok(Json(ImportPositionsResult {
+        positions_imported: result.created.len(),
+        activity_ids: result.created.into_iter().map(|a| a.id).collect(),
+    }))
+}
+
 pub async fn check_holdings_import_handler(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CheckHoldingsImportRequest>,
@@ -911,3 +975,18 @@ async fn import_single_snapshot_impl(
 
     Ok(())
 }
+
+pub async fn get_allocation_breakdown(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<AllocationBreakdownBody>,
+) -> ApiResult<Json<Vec<AllocationSlice>>> {
+    let dimension = AllocationDimension::parse(&body.dimension)?;
+    let base = state.base_currency.read().unwrap().clone();
+    let resolved = resolve_scope(&body.filter, &state)?;
+    let account_ids = holdings_account_ids(&state, &resolved.account_ids)?;
+    let slices = state
+        .allocation_service
+        .get_allocation_breakdown(&account_ids, &base, &resolved.scope_id, dimension)
+        .await?;
+    Ok(Json(slices))
+}
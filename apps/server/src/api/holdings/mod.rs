@@ -1,6 +1,7 @@
 mod dto;
 mod handlers;
 mod mappers;
+mod opening_balance;
 
 use std::sync::Arc;
 
@@ -23,6 +24,7 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/holdings/item", get(handlers::get_holding))
         .route("/holdings/by-asset", get(handlers::get_asset_holdings))
         .route("/holdings/lots", get(handlers::get_asset_lots))
+        .route("/holdings/import", post(handlers::import_positions_handler))
         .route(
             "/valuations/history",
             get(handlers::get_historical_valuations),
@@ -49,6 +51,10 @@ pub fn router() -> Router<Arc<AppState>> {
             "/allocations/holdings/query",
             post(handlers::get_holdings_by_allocation),
         )
+        .route(
+            "/allocations/breakdown/query",
+            post(handlers::get_allocation_breakdown),
+        )
         .route(
             "/snapshots",
             get(handlers::get_snapshots)
@@ -0,0 +1,211 @@
+//! Builds synthetic opening-balance activities from a positions snapshot.
+//!
+//! Users who only have a current statement (no transaction history) can seed
+//! holdings directly. Each position becomes a `TRANSFER_IN` activity tagged with
+//! an `opening_balance` metadata marker so a later broker sync can recognise and
+//! reconcile it.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use wealthfolio_core::activities::{
+    ActivityStatus, AssetResolutionInput, NewActivity, ACTIVITY_TYPE_TRANSFER_IN,
+};
+
+use super::dto::ImportPositionInput;
+
+/// Source system recorded on imported opening-balance activities.
+pub const OPENING_BALANCE_SOURCE_SYSTEM: &str = "MANUAL";
+
+/// Metadata key marking an activity as a synthetic opening balance.
+pub const OPENING_BALANCE_METADATA_KEY: &str = "opening_balance";
+
+const OPENING_BALANCE_NOTE: &str = "Opening balance (imported position)";
+
+/// Validates a positions snapshot and converts it into opening-balance activities.
+///
+/// Returns every validation problem at once so the caller can surface them together.
+pub fn build_opening_balance_activities(
+    account_id: &str,
+    account_currency: &str,
+    as_of: NaiveDate,
+    positions: &[ImportPositionInput],
+) -> Result<Vec<NewActivity>, Vec<String>> {
+    let mut errors: Vec<String> = Vec::new();
+    let mut activities: Vec<NewActivity> = Vec::with_capacity(positions.len());
+
+    if positions.is_empty() {
+        return Err(vec!["No positions provided".to_string()]);
+    }
+
+    for (index, position) in positions.iter().enumerate() {
+        let symbol = position.symbol.trim();
+        let label = if symbol.is_empty() {
+            format!("Position {}", index + 1)
+        } else {
+            symbol.to_string()
+        };
+
+        if symbol.is_empty() && position.asset_id.is_none() {
+            errors.push(format!("{}: symbol is required", label));
+            continue;
+        }
+
+        let quantity = match position.quantity.trim().parse::<Decimal>() {
+            Ok(q) if q > Decimal::ZERO => q,
+            Ok(_) => {
+                errors.push(format!("{}: quantity must be greater than zero", label));
+                continue;
+            }
+            Err(_) => {
+                errors.push(format!(
+                    "{}: invalid quantity '{}'",
+                    label, position.quantity
+                ));
+                continue;
+            }
+        };
+
+        let cost_basis = match position.cost_basis.as_deref().map(str::trim) {
+            Some(raw) if !raw.is_empty() => match raw.parse::<Decimal>() {
+                Ok(c) if c >= Decimal::ZERO => c,
+                Ok(_) => {
+                    errors.push(format!("{}: cost basis cannot be negative", label));
+                    continue;
+                }
+                Err(_) => {
+                    errors.push(format!("{}: invalid cost basis '{}'", label, raw));
+                    continue;
+                }
+            },
+            _ => Decimal::ZERO,
+        };
+
+        let currency = position
+            .currency
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .unwrap_or(account_currency)
+            .to_string();
+
+        let unit_price = cost_basis / quantity;
+
+        activities.push(NewActivity {
+            id: None,
+            account_id: account_id.to_string(),
+            asset: Some(AssetResolutionInput {
+                id: position.asset_id.clone(),
+                symbol: (!symbol.is_empty()).then(|| symbol.to_string()),
+                exchange_mic: position.exchange_mic.clone(),
+                name: position.name.clone(),
+                quote_mode: position.quote_mode.clone(),
+                ..Default::default()
+            }),
+            activity_type: ACTIVITY_TYPE_TRANSFER_IN.to_string(),
+            subtype: None,
+            activity_date: as_of.format("%Y-%m-%d").to_string(),
+            quantity: Some(quantity),
+            unit_price: Some(unit_price),
+            currency,
+            fee: Some(Decimal::ZERO),
+            tax: None,
+            amount: Some(cost_basis),
+            status: Some(ActivityStatus::Posted),
+            notes: Some(OPENING_BALANCE_NOTE.to_string()),
+            fx_rate: None,
+            metadata: Some(
+                serde_json::json!({
+                    "flow": { "is_external": true },
+                    OPENING_BALANCE_METADATA_KEY: true,
+                })
+                .to_string(),
+            ),
+            needs_review: None,
+            source_system: Some(OPENING_BALANCE_SOURCE_SYSTEM.to_string()),
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(activities)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(symbol: &str, quantity: &str, cost_basis: Option<&str>) -> ImportPositionInput {
+        ImportPositionInput {
+            asset_id: None,
+            symbol: symbol.to_string(),
+            exchange_mic: None,
+            quantity: quantity.to_string(),
+            cost_basis: cost_basis.map(str::to_string),
+            currency: None,
+            name: None,
+            quote_mode: None,
+        }
+    }
+
+    fn as_of() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+    }
+
+    #[test]
+    fn builds_transfer_in_with_opening_balance_marker() {
+        let activities = build_opening_balance_activities(
+            "acc-1",
+            "USD",
+            as_of(),
+            &[position("AAPL", "10", Some("1500"))],
+        )
+        .unwrap();
+
+        assert_eq!(activities.len(), 1);
+        let activity = &activities[0];
+        assert_eq!(activity.activity_type, ACTIVITY_TYPE_TRANSFER_IN);
+        assert_eq!(activity.activity_date, "2024-01-02");
+        assert_eq!(activity.quantity, Some(Decimal::from(10)));
+        assert_eq!(activity.unit_price, Some(Decimal::from(150)));
+        assert_eq!(activity.currency, "USD");
+        assert_eq!(activity.source_system.as_deref(), Some("MANUAL"));
+
+        let metadata: serde_json::Value =
+            serde_json::from_str(activity.metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata[OPENING_BALANCE_METADATA_KEY], true);
+        assert_eq!(metadata["flow"]["is_external"], true);
+    }
+
+    #[test]
+    fn rejects_invalid_quantities_and_prices() {
+        let errors = build_opening_balance_activities(
+            "acc-1",
+            "USD",
+            as_of(),
+            &[
+                position("AAPL", "0", None),
+                position("MSFT", "abc", None),
+                position("GOOG", "5", Some("-1")),
+                position("", "5", None),
+            ],
+        )
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].contains("greater than zero"));
+        assert!(errors[1].contains("invalid quantity"));
+        assert!(errors[2].contains("cannot be negative"));
+        assert!(errors[3].contains("symbol is required"));
+    }
+
+    #[test]
+    fn rejects_empty_snapshot() {
+        assert!(build_opening_balance_activities("acc-1", "USD", as_of(), &[]).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    main_lib::AppState,
+};
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+
+/// Sync metrics in the Prometheus text format.
+async fn get_metrics(State(state): State<Arc<AppState>>) -> ApiResult<impl IntoResponse> {
+    state
+        .metrics
+        .set_device_sync_engine_running(state.device_sync_runtime.is_background_running().await);
+    let body = state.metrics.render().map_err(ApiError::Internal)?;
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    ))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/metrics", get(get_metrics))
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{extract::State, routing::post, Json, Router};
+use serde::Serialize;
+
+use crate::{
+    error::ApiResult,
+    main_lib::AppState,
+    notifications::{ChannelDeliveryResult, NotificationPayload},
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TestNotificationResponse {
+    channels: Vec<ChannelDeliveryResult>,
+}
+
+/// Sends a test payload through every configured channel. Does not touch sync state.
+async fn test_notifications(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<TestNotificationResponse>> {
+    let payload = NotificationPayload::test(chrono::Utc::now());
+    let channels = state.notification_channels.deliver(&payload).await;
+    Ok(Json(TestNotificationResponse { channels }))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/notifications/test", post(test_notifications))
+}
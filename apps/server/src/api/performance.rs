@@ -15,7 +15,8 @@ use wealthfolio_core::{
         AccountServiceTrait, TrackingMode,
     },
     portfolio::{
-        income::IncomeSummary,
+        gains::HoldingGains,
+        income::{DividendSummary, DividendSummaryQuery, IncomeSummary},
         performance::{
             calculate_performance_summary_batch_for_accounts, empty_performance_metrics,
             performance_account_ids_from_map, performance_account_tracking_modes_from_map,
@@ -488,12 +489,12 @@ struct IncomeSummaryBody {
     filter: Option<wealthfolio_core::portfolios::AccountScope>,
 }
 
-/// POST /income/summary/query — typed scope query (all, portfolio, multi-account)
-async fn get_income_summary(
-    State(state): State<Arc<AppState>>,
-    Json(body): Json<IncomeSummaryBody>,
-) -> ApiResult<Json<Vec<IncomeSummary>>> {
-    let account_ids: Vec<String> = match &body.filter {
+/// Income-capable accounts in `filter`; every active one when it is `None`.
+fn income_scope_account_ids(
+    state: &AppState,
+    filter: Option<&wealthfolio_core::portfolios::AccountScope>,
+) -> ApiResult<Vec<String>> {
+    Ok(match filter {
         None => state
             .account_service
             .get_active_accounts()?
@@ -509,9 +510,17 @@ async fn get_income_summary(
                 .portfolio_service
                 .resolve_account_scope(filter, &base)
                 .map_err(crate::error::ApiError::from)?;
-            account_ids_for_purpose(&state, &resolved.account_ids, AccountPurpose::Income)?
+            account_ids_for_purpose(state, &resolved.account_ids, AccountPurpose::Income)?
         }
-    };
+    })
+}
+
+/// POST /income/summary/query — typed scope query (all, portfolio, multi-account)
+async fn get_income_summary(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<IncomeSummaryBody>,
+) -> ApiResult<Json<Vec<IncomeSummary>>> {
+    let account_ids = income_scope_account_ids(&state, body.filter.as_ref())?;
     if account_ids.is_empty() {
         return Ok(Json(Vec::new()));
     }
@@ -521,6 +530,43 @@ async fn get_income_summary(
     Ok(Json(items))
 }
 
+#[derive(serde::Deserialize)]
+struct DividendSummaryBody {
+    filter: Option<wealthfolio_core::portfolios::AccountScope>,
+    #[serde(flatten)]
+    query: DividendSummaryQuery,
+}
+
+/// POST /income/dividends/query — dividend totals by symbol, account or month
+async fn get_dividend_summary(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<DividendSummaryBody>,
+) -> ApiResult<Json<DividendSummary>> {
+    let account_ids = income_scope_account_ids(&state, body.filter.as_ref())?;
+    let summary = state
+        .income_service
+        .get_dividend_summary(&body.query, Some(&account_ids))?;
+    Ok(Json(summary))
+}
+
+#[derive(serde::Deserialize)]
+struct GainsQuery {
+    #[serde(rename = "accountId")]
+    account_id: Option<String>,
+}
+
+/// GET /gains?accountId=... — realized and unrealized gains per holding, in base currency
+async fn get_gains(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<GainsQuery>,
+) -> ApiResult<Json<Vec<HoldingGains>>> {
+    let gains = state
+        .gains_service
+        .get_gains(q.account_id.as_deref())
+        .await?;
+    Ok(Json(gains))
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route(
@@ -532,6 +578,8 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/performance/summaries", post(get_performance_summaries))
         .route("/income/summary", get(get_income_summary_for_account))
         .route("/income/summary/query", post(get_income_summary))
+        .route("/income/dividends/query", post(get_dividend_summary))
+        .route("/gains", get(get_gains))
 }
 
 #[cfg(test)]
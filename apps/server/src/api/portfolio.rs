@@ -1,12 +1,15 @@
 use std::{convert::Infallible, sync::Arc, time::Duration};
 
 use crate::{
-    api::shared::{enqueue_portfolio_job, PortfolioRequestBody},
-    error::ApiResult,
+    api::shared::{
+        enqueue_portfolio_job, spawn_analytics_warmup, PortfolioJobConfig, PortfolioRequestBody,
+    },
+    error::{ApiError, ApiResult},
+    events::TopicFilter,
     main_lib::AppState,
 };
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     response::sse::{Event as SseEvent, KeepAlive, Sse},
     routing::{get, post},
@@ -14,7 +17,13 @@ use axum::{
 };
 use futures_core::stream::Stream;
 use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
-use wealthfolio_core::quotes::{MarketSyncMode, DEFAULT_HISTORY_DAYS};
+use wealthfolio_core::{
+    portfolio::{
+        ledger::LedgerValidationReport, snapshot::SnapshotRecalcMode,
+        valuation::ValuationRecalcMode,
+    },
+    quotes::{MarketSyncMode, DEFAULT_HISTORY_DAYS},
+};
 
 async fn update_portfolio(
     State(state): State<Arc<AppState>>,
@@ -49,11 +58,69 @@ async fn recalculate_portfolio(
     Ok(StatusCode::ACCEPTED)
 }
 
+#[derive(Default, serde::Deserialize)]
+struct ValidateLedgerBody {
+    #[serde(default)]
+    fix: bool,
+}
+
+/// Read-only unless `fix` is set, in which case accounts whose holdings no longer match their
+/// activities get a full holdings recompute.
+async fn validate_ledger(
+    State(state): State<Arc<AppState>>,
+    body: Option<Json<ValidateLedgerBody>>,
+) -> ApiResult<Json<LedgerValidationReport>> {
+    let fix = body.map(|Json(inner)| inner).unwrap_or_default().fix;
+    let mut report = state.ledger_validation_service.validate_ledger().await?;
+
+    if fix {
+        let account_ids = report.fixable_account_ids();
+        if !account_ids.is_empty() {
+            enqueue_portfolio_job(
+                state.clone(),
+                PortfolioJobConfig {
+                    account_ids: Some(account_ids.clone()),
+                    market_sync_mode: MarketSyncMode::None,
+                    snapshot_mode: SnapshotRecalcMode::Full,
+                    valuation_mode: ValuationRecalcMode::Full,
+                    since_date: None,
+                },
+            );
+            report.recalculated_account_ids = account_ids;
+        }
+    }
+    Ok(Json(report))
+}
+
+/// Warms the analytics caches in the background; `analytics:ready` follows when done.
+async fn warm_analytics_caches(State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    spawn_analytics_warmup(
+        state.analytics_warmup.clone(),
+        &state.base_currency,
+        state.event_bus.clone(),
+    );
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn cancel_analytics_warmup(State(state): State<Arc<AppState>>) -> ApiResult<StatusCode> {
+    state.analytics_warmup.cancel();
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(serde::Deserialize)]
+struct EventStreamQuery {
+    /// Comma separated topics, e.g. `sync,cloud`. All events are sent when omitted.
+    topics: Option<String>,
+}
+
 async fn stream_events(
     State(state): State<Arc<AppState>>,
-) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    Query(query): Query<EventStreamQuery>,
+) -> ApiResult<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>> {
+    let filter = TopicFilter::parse(query.topics.as_deref()).map_err(ApiError::BadRequest)?;
     let receiver = BroadcastStream::new(state.event_bus.subscribe());
-    let stream = tokio_stream::StreamExt::filter_map(receiver, |event| match event {
+    let stream = tokio_stream::StreamExt::filter_map(receiver, move |event| match event {
+        Ok(evt) if !filter.matches(&evt) => None,
         Ok(evt) => {
             let sse_event = SseEvent::default().event(evt.name);
             let sse_event = if let Some(payload) = evt.payload {
@@ -76,16 +143,21 @@ async fn stream_events(
         Err(BroadcastStreamRecvError::Lagged(_)) => None,
     });
 
-    Sse::new(stream).keep_alive(
+    Ok(Sse::new(stream).keep_alive(
         KeepAlive::new()
             .interval(Duration::from_secs(15))
             .text("keep-alive"),
-    )
+    ))
 }
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/portfolio/update", post(update_portfolio))
         .route("/portfolio/recalculate", post(recalculate_portfolio))
+        .route("/portfolio/ledger/validate", post(validate_ledger))
+        .route(
+            "/portfolio/analytics/warmup",
+            post(warm_analytics_caches).delete(cancel_analytics_warmup),
+        )
         .route("/events/stream", get(stream_events))
 }
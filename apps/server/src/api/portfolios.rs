@@ -1,14 +1,26 @@
 use std::sync::Arc;
 
-use crate::{error::ApiResult, main_lib::AppState};
+use crate::{
+    error::ApiResult,
+    events::{ServerEvent, PORTFOLIOS_CHANGED},
+    main_lib::AppState,
+};
 use axum::{
     extract::{Path, State},
     http::StatusCode,
     routing::get,
     Json, Router,
 };
+use serde_json::json;
 use wealthfolio_core::portfolios::{NewPortfolio, PortfolioUpdate, PortfolioWithAccounts};
 
+fn publish_portfolios_changed(state: &AppState, portfolio_id: &str) {
+    state.event_bus.publish(ServerEvent::with_payload(
+        PORTFOLIOS_CHANGED,
+        json!({ "portfolioId": portfolio_id }),
+    ));
+}
+
 async fn list_portfolios(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<PortfolioWithAccounts>>> {
@@ -29,6 +41,7 @@ async fn create_portfolio(
     Json(payload): Json<NewPortfolio>,
 ) -> ApiResult<Json<PortfolioWithAccounts>> {
     let created = state.portfolio_service.create_portfolio(payload).await?;
+    publish_portfolios_changed(&state, &created.id);
     Ok(Json(created))
 }
 
@@ -39,6 +52,7 @@ async fn update_portfolio(
 ) -> ApiResult<Json<PortfolioWithAccounts>> {
     payload.id = id;
     let updated = state.portfolio_service.update_portfolio(payload).await?;
+    publish_portfolios_changed(&state, &updated.id);
     Ok(Json(updated))
 }
 
@@ -47,6 +61,7 @@ async fn delete_portfolio(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<StatusCode> {
     state.portfolio_service.delete_portfolio(&id).await?;
+    publish_portfolios_changed(&state, &id);
     Ok(StatusCode::NO_CONTENT)
 }
 
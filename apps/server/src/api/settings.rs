@@ -18,7 +18,10 @@ use serde::Deserialize;
 use wealthfolio_core::{
     portfolio::{snapshot::SnapshotRecalcMode, valuation::ValuationRecalcMode},
     quotes::MarketSyncMode,
-    settings::{Settings, SettingsServiceTrait, SettingsUpdate},
+    settings::{
+        PlatformCapabilities, Settings, SettingsServiceTrait, SettingsUpdate, SyncQuietHours,
+        SyncQuietHoursService, SyncQuietHoursStatus,
+    },
 };
 
 async fn get_settings(State(state): State<Arc<AppState>>) -> ApiResult<Json<Settings>> {
@@ -122,6 +125,33 @@ async fn get_app_info(State(state): State<Arc<AppState>>) -> ApiResult<Json<AppI
     }))
 }
 
+/// The server counterpart of the desktop app's `get_platform`. The client device's OS is not
+/// known here, so `os` and `arch` describe the server.
+#[derive(serde::Serialize)]
+struct PlatformResponse {
+    os: &'static str,
+    arch: &'static str,
+    is_tauri: bool,
+    capabilities: PlatformCapabilities,
+    /// Resolved Connect API URL; `None` when cloud features are compiled out or misconfigured.
+    cloud_api_base_url: Option<String>,
+}
+
+async fn get_platform() -> Json<PlatformResponse> {
+    Json(PlatformResponse {
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        is_tauri: false,
+        // The server runs the sync schedule whether or not a browser is open.
+        capabilities: PlatformCapabilities::new(
+            crate::features::connect_sync_enabled(),
+            crate::features::device_sync_enabled(),
+            true,
+        ),
+        cloud_api_base_url: crate::features::cloud_api_base_url(),
+    })
+}
+
 #[derive(Deserialize)]
 struct UpdatePlatformInfo {
     url: Option<String>,
@@ -266,13 +296,40 @@ async fn check_update(
     Ok(Json(result))
 }
 
+async fn get_sync_quiet_hours(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<SyncQuietHoursStatus>> {
+    let service = SyncQuietHoursService::new(state.settings_service.clone());
+    Ok(Json(service.status(chrono::Utc::now())?))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncQuietHoursBody {
+    quiet_hours: Option<SyncQuietHours>,
+}
+
+async fn set_sync_quiet_hours(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SyncQuietHoursBody>,
+) -> ApiResult<Json<SyncQuietHoursStatus>> {
+    let service = SyncQuietHoursService::new(state.settings_service.clone());
+    service.set_quiet_hours(body.quiet_hours).await?;
+    Ok(Json(service.status(chrono::Utc::now())?))
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/settings", get(get_settings).put(update_settings))
+        .route(
+            "/settings/sync-quiet-hours",
+            get(get_sync_quiet_hours).put(set_sync_quiet_hours),
+        )
         .route(
             "/settings/auto-update-enabled",
             get(is_auto_update_check_enabled),
         )
         .route("/app/info", get(get_app_info))
+        .route("/platform", get(get_platform))
         .route("/app/check-update", get(check_update))
 }
@@ -0,0 +1,195 @@
+//! Read-only portfolio snapshots: a downloadable file, or a tokenized link that serves a frozen
+//! snapshot without a session until it expires. Managing links is JWT-protected like the rest of
+//! `/api/v1`; only `GET /shared/{token}` is public. Raw tokens are returned exactly once at
+//! creation and only their hashes are stored.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use wealthfolio_core::accounts::AccountServiceTrait;
+use wealthfolio_core::share_snapshot::{
+    build_share_snapshot, share_link_expiry, ShareSnapshot, ShareSnapshotOptions,
+};
+use wealthfolio_storage_sqlite::portfolio::share_links::{NewShareLink, ShareLinkDB};
+
+use crate::{
+    api::data_exports::load_export_holdings,
+    error::{ApiError, ApiResult},
+    main_lib::AppState,
+    mcp::auth::hash_token,
+};
+
+/// Share link token prefix.
+const SHARE_TOKEN_PREFIX: &str = "wfs_";
+
+fn generate_share_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{SHARE_TOKEN_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+async fn current_snapshot(
+    state: &AppState,
+    options: ShareSnapshotOptions,
+) -> ApiResult<ShareSnapshot> {
+    let base = state.base_currency.read().unwrap().clone();
+    let holdings = load_export_holdings(state).await?.unwrap_or_default();
+    let accounts = if options.include_account_details {
+        state.account_service.get_non_archived_accounts()?
+    } else {
+        Vec::new()
+    };
+    Ok(build_share_snapshot(
+        &holdings,
+        &accounts,
+        &base,
+        options,
+        chrono::Utc::now(),
+    ))
+}
+
+async fn get_share_snapshot(
+    State(state): State<Arc<AppState>>,
+    Query(options): Query<ShareSnapshotOptions>,
+) -> ApiResult<Json<ShareSnapshot>> {
+    Ok(Json(current_snapshot(&state, options).await?))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShareLinkInfo {
+    id: String,
+    include_account_details: bool,
+    created_at: String,
+    expires_at: String,
+}
+
+impl From<ShareLinkDB> for ShareLinkInfo {
+    fn from(row: ShareLinkDB) -> Self {
+        Self {
+            id: row.id,
+            include_account_details: row.include_account_details,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateShareLinkRequest {
+    #[serde(default)]
+    include_account_details: bool,
+    /// Lifetime of the link; defaults to 7 days, at most 30.
+    ttl_hours: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreatedShareLinkResponse {
+    /// Full token — shown exactly once, never retrievable again.
+    token: String,
+    /// Path of the read-only snapshot, relative to the server origin.
+    path: String,
+    #[serde(flatten)]
+    link: ShareLinkInfo,
+}
+
+async fn create_share_link(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateShareLinkRequest>,
+) -> ApiResult<(StatusCode, Json<CreatedShareLinkResponse>)> {
+    let now = chrono::Utc::now();
+    let expires_at = share_link_expiry(payload.ttl_hours, now)?;
+    let options = ShareSnapshotOptions {
+        include_account_details: payload.include_account_details,
+    };
+    let snapshot = current_snapshot(&state, options).await?;
+
+    if let Err(e) = state.share_link_repository.purge_expired(now).await {
+        tracing::warn!("Failed to purge expired share links: {}", e);
+    }
+
+    let token = generate_share_token();
+    let row = state
+        .share_link_repository
+        .create(NewShareLink {
+            token_hash: hash_token(&token),
+            snapshot_json: serde_json::to_string(&snapshot)
+                .map_err(|e| ApiError::Internal(e.to_string()))?,
+            include_account_details: options.include_account_details,
+            expires_at,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedShareLinkResponse {
+            path: format!("/api/v1/shared/{token}"),
+            token,
+            link: row.into(),
+        }),
+    ))
+}
+
+async fn list_share_links(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<Vec<ShareLinkInfo>>> {
+    let links = state
+        .share_link_repository
+        .list_active(chrono::Utc::now())?;
+    Ok(Json(links.into_iter().map(ShareLinkInfo::from).collect()))
+}
+
+async fn delete_share_link(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    if state.share_link_repository.delete(&id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound)
+    }
+}
+
+/// Unknown and expired tokens get the same `404`.
+async fn get_shared_snapshot(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> ApiResult<Json<ShareSnapshot>> {
+    if !token.starts_with(SHARE_TOKEN_PREFIX) {
+        return Err(ApiError::NotFound);
+    }
+    let link = state
+        .share_link_repository
+        .find_active(&hash_token(&token), chrono::Utc::now())?
+        .ok_or(ApiError::NotFound)?;
+    let snapshot = serde_json::from_str(&link.snapshot_json)
+        .map_err(|e| ApiError::Internal(format!("Stored share snapshot is invalid: {}", e)))?;
+    Ok(Json(snapshot))
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/portfolio/share-snapshot", get(get_share_snapshot))
+        .route(
+            "/portfolio/share-links",
+            get(list_share_links).post(create_share_link),
+        )
+        .route("/portfolio/share-links/{id}", delete(delete_share_link))
+}
+
+/// Unauthenticated: the token itself grants read access to one frozen snapshot.
+pub fn public_router() -> Router<Arc<AppState>> {
+    Router::new().route("/shared/{token}", get(get_shared_snapshot))
+}
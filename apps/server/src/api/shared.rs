@@ -1,10 +1,11 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use crate::{
     error::{ApiError, ApiResult},
     events::{
-        ServerEvent, MARKET_SYNC_COMPLETE, MARKET_SYNC_ERROR, MARKET_SYNC_START,
-        PORTFOLIO_UPDATE_COMPLETE, PORTFOLIO_UPDATE_ERROR, PORTFOLIO_UPDATE_START,
+        EventBus, ServerEvent, ANALYTICS_READY, MARKET_SYNC_COMPLETE, MARKET_SYNC_ERROR,
+        MARKET_SYNC_START, PORTFOLIO_UPDATE_COMPLETE, PORTFOLIO_UPDATE_ERROR,
+        PORTFOLIO_UPDATE_START,
     },
     main_lib::AppState,
 };
@@ -14,6 +15,7 @@ use serde_json::json;
 use wealthfolio_core::{
     accounts::{account_supports_portfolio_scope, AccountPurpose, AccountServiceTrait},
     portfolio::{
+        analytics_warmup::AnalyticsWarmupService,
         snapshot::{reconcile_quote_sync_from_latest_account_snapshots, SnapshotRecalcMode},
         valuation::ValuationRecalcMode,
     },
@@ -141,11 +143,32 @@ pub fn trigger_portfolio_recalc_with_asset_sync(state: Arc<AppState>, asset_ids:
     );
 }
 
+/// Warms the analytics caches in the background and publishes [`ANALYTICS_READY`] when done.
+/// A warmup still running from an earlier call is cancelled.
+pub fn spawn_analytics_warmup(
+    warmup: Arc<AnalyticsWarmupService>,
+    base_currency: &RwLock<String>,
+    event_bus: EventBus,
+) {
+    let ticket = warmup.begin();
+    let base_currency = base_currency.read().unwrap().clone();
+    tokio::spawn(async move {
+        match warmup.run(ticket, &base_currency).await {
+            Ok(report) if report.cancelled => tracing::debug!("Analytics warmup cancelled"),
+            Ok(report) => {
+                event_bus.publish(ServerEvent::with_payload(ANALYTICS_READY, json!(report)))
+            }
+            Err(err) => tracing::warn!("Analytics warmup failed: {}", err),
+        }
+    });
+}
+
 pub async fn process_portfolio_job(
     state: Arc<AppState>,
     config: PortfolioJobConfig,
 ) -> ApiResult<()> {
     let event_bus = state.event_bus.clone();
+    let recompute_ticket = state.holdings_recompute.begin_recompute();
     let snapshot_mode = config
         .since_date
         .map(SnapshotRecalcMode::SinceDate)
@@ -239,6 +262,7 @@ pub async fn process_portfolio_job(
         tracing::debug!("Skipping market sync (MarketSyncMode::None)");
     }
 
+    state.analytics_warmup.cancel();
     event_bus.publish(ServerEvent::new(PORTFOLIO_UPDATE_START));
 
     if !account_ids.is_empty() {
@@ -292,6 +316,16 @@ pub async fn process_portfolio_job(
         }
     }
 
+    state.allocation_service.invalidate_cache();
+    state.gains_service.invalidate_cache();
+    state
+        .holdings_recompute
+        .mark_recomputed(recompute_ticket, config.account_ids.as_deref());
     event_bus.publish(ServerEvent::new(PORTFOLIO_UPDATE_COMPLETE));
+    spawn_analytics_warmup(
+        state.analytics_warmup.clone(),
+        &state.base_currency,
+        event_bus,
+    );
     Ok(())
 }
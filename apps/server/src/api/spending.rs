@@ -24,7 +24,8 @@ use wealthfolio_spending::cash_activities::{
     CashActivity, CashActivityFilter, CashActivitySearchRequest, CashActivitySearchResponse,
 };
 use wealthfolio_spending::categorization_rules::{
-    CategorizationRule, CategorizationRulesService, NewCategorizationRule, UpdateCategorizationRule,
+    CategorizationRule, CategorizationRulesService, NewCategorizationRule, RulePreview,
+    UpdateCategorizationRule,
 };
 use wealthfolio_spending::events::{Event, EventType, NewEvent, NewEventType, UpdateEvent};
 use wealthfolio_spending::insight::{SpendingInsight, SpendingInsightRequest};
@@ -340,6 +341,22 @@ async fn rerun_categorization_rules(
     ))
 }
 
+async fn preview_categorization_rules(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<RerunRulesBody>,
+) -> ApiResult<Json<RulePreview>> {
+    let s = state.spending_settings_service.get().await?;
+    if !s.enabled {
+        return Ok(Json(RulePreview::default()));
+    }
+    Ok(Json(
+        state
+            .categorization_rules_service
+            .preview_rerun(&s.account_ids, body.only_uncategorized)
+            .await?,
+    ))
+}
+
 async fn list_rule_presets(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<wealthfolio_spending::categorization_rules::RulePresetSummary>>> {
@@ -759,6 +776,10 @@ pub fn router() -> Router<Arc<AppState>> {
             put(update_categorization_rule).delete(delete_categorization_rule),
         )
         .route("/spending/rules/rerun", post(rerun_categorization_rules))
+        .route(
+            "/spending/rules/preview",
+            post(preview_categorization_rules),
+        )
         .route("/spending/rule-presets", get(list_rule_presets))
         .route(
             "/spending/rule-presets/{preset_id}/import",
@@ -0,0 +1,203 @@
+//! Cloud-pushed broker sync.
+//!
+//! When `CONNECT_WEBHOOK_SECRET` is set, the cloud calls `POST /sync/webhook` whenever a
+//! connection's data changes, and the affected connection is synced right away instead of
+//! waiting for the next scheduler tick. Requests are authenticated by an HMAC-SHA256 over
+//! `<timestamp>.<body>` rather than a user session, so the route sits outside the JWT layer.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+use tracing::{debug, info, warn};
+use wealthfolio_connect::BrokerSyncError;
+use wealthfolio_core::settings::{CloudAccessService, SyncQuietHoursService};
+use wealthfolio_device_sync::crypto;
+
+use crate::api::connect::perform_connection_sync;
+use crate::error::{ApiError, ApiResult};
+use crate::main_lib::AppState;
+
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "x-wealthfolio-timestamp";
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "x-wealthfolio-signature";
+
+/// Maximum clock difference accepted between the cloud and this server.
+const MAX_TIMESTAMP_SKEW_SECS: u64 = 5 * 60;
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// "Data changed" notification for one broker connection.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncWebhookEvent {
+    #[serde(alias = "connection_id")]
+    pub connection_id: String,
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>` keyed with `secret`.
+pub fn sign_webhook(secret: &str, timestamp: i64, body: &str) -> Result<String, String> {
+    crypto::hmac_sha256(
+        &BASE64.encode(secret.as_bytes()),
+        &format!("{}.{}", timestamp, body),
+    )
+}
+
+/// Checks the timestamp and signature headers against `body` and parses the event.
+pub fn verify_webhook(
+    secret: &str,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: i64,
+) -> ApiResult<SyncWebhookEvent> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .ok_or_else(|| ApiError::Unauthorized("Missing webhook signature".to_string()))
+    };
+    let timestamp: i64 = header(WEBHOOK_TIMESTAMP_HEADER)?
+        .parse()
+        .map_err(|_| ApiError::Unauthorized("Invalid webhook timestamp".to_string()))?;
+    if now.abs_diff(timestamp) > MAX_TIMESTAMP_SKEW_SECS {
+        return Err(ApiError::Unauthorized(
+            "Webhook timestamp outside the allowed window".to_string(),
+        ));
+    }
+    let signature = header(WEBHOOK_SIGNATURE_HEADER)?;
+    let signature = signature
+        .strip_prefix(SIGNATURE_PREFIX)
+        .unwrap_or(signature)
+        .to_ascii_lowercase();
+
+    let body = std::str::from_utf8(body)
+        .map_err(|_| ApiError::BadRequest("Webhook body must be UTF-8".to_string()))?;
+    let expected = sign_webhook(secret, timestamp, body).map_err(ApiError::Internal)?;
+    if !bool::from(expected.as_bytes().ct_eq(signature.as_bytes())) {
+        return Err(ApiError::Unauthorized(
+            "Invalid webhook signature".to_string(),
+        ));
+    }
+
+    serde_json::from_str(body).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+async fn receive_sync_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<StatusCode> {
+    let secret = crate::features::connect_webhook_secret()
+        .ok_or_else(|| ApiError::NotImplemented("Sync webhooks are not configured.".to_string()))?;
+    let event = verify_webhook(&secret, &headers, &body, chrono::Utc::now().timestamp())?;
+    CloudAccessService::new(state.settings_service.clone()).ensure_enabled()?;
+
+    if !SyncQuietHoursService::new(state.settings_service.clone())
+        .allows_sync_at(chrono::Utc::now())
+    {
+        info!("Webhook sync skipped: quiet hours are active");
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    info!(
+        "Webhook sync requested for connection {}",
+        event.connection_id
+    );
+    tokio::spawn(async move {
+        match perform_connection_sync(&state, &event.connection_id).await {
+            Ok(result) => info!("Webhook sync completed: success={}", result.success),
+            Err(e @ BrokerSyncError::AlreadyRunning) => {
+                debug!("Webhook sync skipped: {}", e)
+            }
+            Err(e) => warn!("Webhook sync failed: {}", e),
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/sync/webhook", post(receive_sync_webhook))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const SECRET: &str = "webhook-secret";
+    const NOW: i64 = 1_750_000_000;
+    const BODY: &str = r#"{"connectionId":"conn-1"}"#;
+
+    fn signed_headers(secret: &str, timestamp: i64, body: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            WEBHOOK_TIMESTAMP_HEADER,
+            HeaderValue::from_str(&timestamp.to_string()).unwrap(),
+        );
+        let signature = sign_webhook(secret, timestamp, body).unwrap();
+        headers.insert(
+            WEBHOOK_SIGNATURE_HEADER,
+            HeaderValue::from_str(&format!("{}{}", SIGNATURE_PREFIX, signature)).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn valid_signature_yields_the_connection_to_sync() {
+        let headers = signed_headers(SECRET, NOW, BODY);
+
+        let event = verify_webhook(SECRET, &headers, BODY.as_bytes(), NOW + 30).unwrap();
+
+        assert_eq!(
+            event,
+            SyncWebhookEvent {
+                connection_id: "conn-1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn unsigned_tampered_or_stale_requests_are_rejected() {
+        let unauthorized =
+            |result: ApiResult<SyncWebhookEvent>| matches!(result, Err(ApiError::Unauthorized(_)));
+
+        assert!(unauthorized(verify_webhook(
+            SECRET,
+            &HeaderMap::new(),
+            BODY.as_bytes(),
+            NOW
+        )));
+
+        let wrong_secret = signed_headers("other-secret", NOW, BODY);
+        assert!(unauthorized(verify_webhook(
+            SECRET,
+            &wrong_secret,
+            BODY.as_bytes(),
+            NOW
+        )));
+
+        let headers = signed_headers(SECRET, NOW, BODY);
+        assert!(unauthorized(verify_webhook(
+            SECRET,
+            &headers,
+            br#"{"connectionId":"conn-2"}"#,
+            NOW
+        )));
+
+        assert!(unauthorized(verify_webhook(
+            SECRET,
+            &headers,
+            BODY.as_bytes(),
+            NOW + MAX_TIMESTAMP_SKEW_SECS as i64 + 1
+        )));
+    }
+}
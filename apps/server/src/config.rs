@@ -31,6 +31,22 @@ pub struct Config {
     /// headers cross-site, so DNS rebinding gains nothing). Deployments
     /// that want strict Host pinning set WF_MCP_ALLOWED_HOSTS explicitly.
     pub mcp_allowed_hosts: Option<Vec<String>>,
+    /// Periodic broker sync interval (BROKER_SYNC_INTERVAL_SECS). Clamped to
+    /// [`MIN_BROKER_SYNC_INTERVAL_SECS`] so a misconfigured server cannot
+    /// hammer the Connect API. `None` when unset or invalid; the scheduler
+    /// then picks its default.
+    pub broker_sync_interval: Option<Duration>,
+}
+
+/// Broker sync interval when `BROKER_SYNC_INTERVAL_SECS` is unset or invalid.
+pub const DEFAULT_BROKER_SYNC_INTERVAL_SECS: u64 = 4 * 60 * 60;
+/// Shortest broker sync interval the scheduler accepts.
+pub const MIN_BROKER_SYNC_INTERVAL_SECS: u64 = 15 * 60;
+
+fn broker_sync_interval(raw: Option<&str>) -> Option<Duration> {
+    raw.and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(|secs| Duration::from_secs(secs.max(MIN_BROKER_SYNC_INTERVAL_SECS)))
 }
 
 impl Config {
@@ -120,6 +136,8 @@ impl Config {
                     .collect::<Vec<_>>()
             })
             .filter(|hosts| !hosts.is_empty());
+        let broker_sync_interval =
+            broker_sync_interval(std::env::var("BROKER_SYNC_INTERVAL_SECS").ok().as_deref());
 
         // When auth is enabled, wildcard CORS is incompatible with credentials
         if auth.is_some() && cors_allow.iter().any(|o| o == "*") {
@@ -183,6 +201,7 @@ impl Config {
             mcp_enabled,
             mcp_audit_enabled,
             mcp_allowed_hosts,
+            broker_sync_interval,
         }
     }
 }
@@ -0,0 +1,115 @@
+//! Cheap reachability check for the cloud API, so scheduled sync can skip runs while the
+//! machine is offline instead of failing them one request at a time.
+//!
+//! The check is a single `HEAD` to the cloud base URL with a short timeout. Any HTTP answer,
+//! whatever its status, counts as online; only a failed connection or a timeout counts as
+//! offline. The last result is kept for `GET /api/v1/health/sync`.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// How long the check waits before calling the cloud unreachable.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Outcome of the most recent reachability check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    pub online: bool,
+    pub checked_at: DateTime<Utc>,
+}
+
+pub struct Connectivity {
+    client: reqwest::Client,
+    last: RwLock<Option<ConnectivityStatus>>,
+}
+
+impl Default for Connectivity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Connectivity {
+    pub fn new() -> Self {
+        Self::with_timeout(CHECK_TIMEOUT)
+    }
+
+    fn with_timeout(timeout: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            last: RwLock::new(None),
+        }
+    }
+
+    /// Whether the cloud API at `base_url` answers at all. Records the result.
+    #[cfg_attr(not(feature = "connect-sync"), allow(dead_code))]
+    pub async fn check(&self, base_url: &str) -> bool {
+        let online = match self.client.head(base_url).send().await {
+            Ok(_) => true,
+            // Anything past the connection (e.g. a malformed answer) is left for the sync itself
+            // to report.
+            Err(e) => !(e.is_connect() || e.is_timeout()),
+        };
+        *self.last.write().unwrap() = Some(ConnectivityStatus {
+            online,
+            checked_at: Utc::now(),
+        });
+        online
+    }
+
+    /// Result of the last check, `None` before the first one.
+    pub fn last_status(&self) -> Option<ConnectivityStatus> {
+        *self.last.read().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[tokio::test]
+    async fn any_http_answer_counts_as_online() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0_u8; 1024];
+            let _ = stream.read(&mut buffer).unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .unwrap();
+        });
+        let connectivity = Connectivity::with_timeout(Duration::from_secs(2));
+        assert_eq!(connectivity.last_status(), None);
+
+        assert!(connectivity.check(&format!("http://{}", addr)).await);
+        assert_eq!(connectivity.last_status().map(|s| s.online), Some(true));
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn refused_connection_counts_as_offline() {
+        // Bind and drop to get a port nothing listens on.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let connectivity = Connectivity::with_timeout(Duration::from_secs(2));
+
+        assert!(!connectivity.check(&format!("http://{}", addr)).await);
+        assert_eq!(connectivity.last_status().map(|s| s.online), Some(false));
+    }
+}
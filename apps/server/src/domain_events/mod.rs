@@ -5,11 +5,16 @@
 //! - Asset enrichment for newly created assets
 //! - Broker sync for eligible TrackingModeChanged events
 //!
+//! With `BROKER_SYNC_AUTO_RECOMPUTE=false`, recomputation for events emitted during a
+//! broker sync is deferred and surfaced as pending instead (see `recompute`).
+//!
 //! Events are debounced with a 1-second window and processed directly
 //! by the queue worker to ensure proper tracking of in-progress work.
 
 mod planner;
 mod queue_worker;
+mod recompute;
 mod sink;
 
+pub use recompute::HoldingsRecomputeState;
 pub use sink::WebDomainEventSink;
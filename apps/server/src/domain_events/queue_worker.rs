@@ -17,14 +17,15 @@ use wealthfolio_core::{
     assets::AssetServiceTrait,
     events::DomainEvent,
     goals::GoalServiceTrait,
-    portfolio::valuation::CurrentAccountValuationService,
+    portfolio::{
+        analytics_warmup::AnalyticsWarmupService, valuation::CurrentAccountValuationService,
+    },
     secrets::SecretStore,
     utils::time_utils::{parse_user_timezone_or_default, user_today},
 };
 
-use super::planner::{
-    plan_asset_enrichment, plan_broker_sync, plan_categorization_job, plan_portfolio_job,
-};
+use super::planner::{plan_asset_enrichment, plan_broker_sync, plan_categorization_job};
+use super::recompute::{plan_batch_portfolio_job, HoldingsRecomputeState, QueuedDomainEvent};
 use crate::events::EventBus;
 
 /// Debounce window for collecting events before processing.
@@ -50,6 +51,11 @@ pub struct QueueWorkerDeps {
         Arc<dyn wealthfolio_core::portfolio::valuation::ValuationServiceTrait + Send + Sync>,
     pub account_service: Arc<wealthfolio_core::accounts::AccountService>,
     pub goal_service: Arc<dyn GoalServiceTrait + Send + Sync>,
+    /// Allocation service — its breakdown cache is dropped once holdings are recalculated.
+    pub allocation_service:
+        Arc<dyn wealthfolio_core::portfolio::allocation::AllocationServiceTrait + Send + Sync>,
+    /// Gains service — its per-holding cache is dropped with the allocation cache.
+    pub gains_service: Arc<dyn wealthfolio_core::portfolio::gains::GainsServiceTrait + Send + Sync>,
     pub fx_service: Arc<dyn wealthfolio_core::fx::FxServiceTrait + Send + Sync>,
     pub base_currency: Arc<RwLock<String>>,
     pub timezone: Arc<RwLock<String>>,
@@ -62,6 +68,10 @@ pub struct QueueWorkerDeps {
     /// Categorization rules service — auto-runs rules against newly-changed activities.
     pub categorization_rules_service:
         Arc<wealthfolio_spending::categorization_rules::CategorizationRulesService>,
+    /// Tracks holdings left stale by broker syncs with auto-recompute disabled.
+    pub holdings_recompute: Arc<HoldingsRecomputeState>,
+    /// Re-warms the analytics caches once a recalculation completes.
+    pub analytics_warmup: Arc<AnalyticsWarmupService>,
 }
 
 /// Runs the event queue worker.
@@ -72,12 +82,12 @@ pub struct QueueWorkerDeps {
 /// Uses an `is_processing` guard to prevent new batches from being processed
 /// while a previous batch (e.g., broker sync or portfolio recalc) is still running.
 pub async fn event_queue_worker(
-    mut rx: mpsc::UnboundedReceiver<DomainEvent>,
+    mut rx: mpsc::UnboundedReceiver<QueuedDomainEvent>,
     deps: Arc<QueueWorkerDeps>,
 ) {
     tracing::info!("Domain event queue worker started");
 
-    let mut pending_events: Vec<DomainEvent> = Vec::new();
+    let mut pending_events: Vec<QueuedDomainEvent> = Vec::new();
     let is_processing = Arc::new(AtomicBool::new(false));
 
     loop {
@@ -141,8 +151,10 @@ pub async fn event_queue_worker(
 }
 
 /// Processes a batch of domain events.
-async fn process_event_batch(events: &[DomainEvent], deps: Arc<QueueWorkerDeps>) {
-    tracing::info!("Processing batch of {} domain event(s)", events.len());
+async fn process_event_batch(batch: &[QueuedDomainEvent], deps: Arc<QueueWorkerDeps>) {
+    tracing::info!("Processing batch of {} domain event(s)", batch.len());
+    let events: Vec<DomainEvent> = batch.iter().map(|queued| queued.event.clone()).collect();
+    let events = events.as_slice();
 
     // 1. Plan and run asset enrichment FIRST so that bond metadata (coupon rate,
     //    maturity date, etc.) is available before the portfolio job tries to
@@ -216,7 +228,7 @@ async fn process_event_batch(events: &[DomainEvent], deps: Arc<QueueWorkerDeps>)
 
     // 2. Plan and trigger portfolio job
     let timezone = deps.timezone.read().unwrap().clone();
-    if let Some(config) = plan_portfolio_job(events, &timezone) {
+    if let Some(config) = plan_batch_portfolio_job(batch, &timezone, &deps.holdings_recompute) {
         tracing::info!(
             "Triggering portfolio job for accounts: {:?}, market_sync: {:?}",
             config.account_ids,
@@ -302,6 +314,7 @@ async fn run_portfolio_job(
     use wealthfolio_core::portfolio::snapshot::reconcile_quote_sync_from_latest_account_snapshots;
 
     let event_bus = deps.event_bus.clone();
+    let recompute_ticket = deps.holdings_recompute.begin_recompute();
     let snapshot_mode = config
         .since_date
         .map(wealthfolio_core::portfolio::snapshot::SnapshotRecalcMode::SinceDate)
@@ -399,6 +412,7 @@ async fn run_portfolio_job(
         tracing::debug!("Skipping market sync (MarketSyncMode::None)");
     }
 
+    deps.analytics_warmup.cancel();
     event_bus.publish(ServerEvent::new(PORTFOLIO_UPDATE_START));
 
     if !account_ids.is_empty() {
@@ -452,7 +466,16 @@ async fn run_portfolio_job(
         }
     }
 
+    deps.allocation_service.invalidate_cache();
+    deps.gains_service.invalidate_cache();
+    deps.holdings_recompute
+        .mark_recomputed(recompute_ticket, config.account_ids.as_deref());
     event_bus.publish(ServerEvent::new(PORTFOLIO_UPDATE_COMPLETE));
+    crate::api::shared::spawn_analytics_warmup(
+        deps.analytics_warmup.clone(),
+        &deps.base_currency,
+        event_bus,
+    );
 }
 
 /// Plans and spawns auto-categorization for this batch's spending-account
@@ -539,7 +562,8 @@ async fn refresh_all_goal_summaries(deps: Arc<QueueWorkerDeps>) {
         deps.asset_service.as_ref(),
         deps.quote_service.as_ref(),
         deps.fx_service.as_ref(),
-    );
+    )
+    .with_stale_quote_policy(crate::features::stale_quote_policy());
     let response = match service
         .get_current_valuation_for_scope(
             "all",
@@ -658,6 +682,14 @@ impl wealthfolio_connect::SyncProgressReporter for EventBusProgressReporter {
             ));
         }
     }
+
+    fn report_anomalies(&self, anomalies: &[wealthfolio_connect::SyncAnomaly]) {
+        use crate::events::{ServerEvent, SYNC_ANOMALY};
+        self.event_bus.publish(ServerEvent::with_payload(
+            SYNC_ANOMALY,
+            serde_json::json!({ "anomalies": anomalies }),
+        ));
+    }
 }
 
 /// Mint a fresh access token using the stored refresh token.
@@ -680,7 +712,7 @@ async fn perform_broker_sync(
     secret_store: Arc<dyn SecretStore>,
     token_lifecycle: Arc<TokenLifecycleState>,
 ) -> Result<wealthfolio_connect::SyncResult, String> {
-    use wealthfolio_connect::{ConnectApiClient, SyncConfig, SyncOrchestrator};
+    use wealthfolio_connect::{connect_user_agent, ConnectApiClient, SyncOrchestrator};
 
     if !crate::features::connect_sync_enabled() {
         return Err("Connect sync feature is disabled in this build.".to_string());
@@ -688,7 +720,14 @@ async fn perform_broker_sync(
 
     // Create API client with fresh access token
     let token = mint_access_token(&secret_store, token_lifecycle.as_ref()).await?;
-    let client = ConnectApiClient::new(&cloud_api_base_url(), &token).map_err(|e| e.to_string())?;
+    let client = ConnectApiClient::with_config(
+        &cloud_api_base_url(),
+        &token,
+        crate::features::connect_client_config(),
+    )
+    .and_then(|client| client.with_user_agent(&connect_user_agent(env!("CARGO_PKG_VERSION"))))
+    .map(|client| client.with_fallback_url(crate::features::cloud_api_fallback_url().as_deref()))
+    .map_err(|e| e.to_string())?;
 
     // Check plan entitlement before syncing
     if !client.has_broker_sync().await.map_err(|e| e.to_string())? {
@@ -700,10 +739,13 @@ async fn perform_broker_sync(
     let orchestrator = SyncOrchestrator::new(
         connect_sync_service.clone(),
         reporter,
-        SyncConfig::default(),
+        crate::features::broker_sync_config(),
     );
 
     // Run the sync via the centralized orchestrator
     // Note: Asset enrichment is handled automatically via domain events (AssetsCreated)
-    orchestrator.sync_all(&client).await
+    orchestrator
+        .sync_all(&client)
+        .await
+        .map_err(|e| e.to_string())
 }
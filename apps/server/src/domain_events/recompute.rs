@@ -0,0 +1,213 @@
+//! Deferred holdings recomputation for broker sync.
+//!
+//! With `BROKER_SYNC_AUTO_RECOMPUTE=false`, events emitted while a broker sync holds the
+//! sync guard still drive enrichment and categorization, but their portfolio job is skipped
+//! and the affected accounts are marked as needing a recompute. The flag clears once a
+//! portfolio job covering those accounts completes (e.g. `POST /portfolio/recalculate`).
+//!
+//! Every mark carries a version. A job takes a [`RecomputeTicket`] before it reads any data
+//! and only clears marks at or below the ticket's version, so a deferred edit that lands
+//! while an earlier job is still running stays pending after that job completes.
+
+use std::collections::BTreeMap;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use wealthfolio_core::events::DomainEvent;
+
+use super::planner::plan_portfolio_job;
+use crate::api::shared::PortfolioJobConfig;
+
+/// A domain event queued for the worker, tagged with whether it arrived during a broker
+/// sync whose recompute is deferred.
+#[derive(Clone, Debug)]
+pub struct QueuedDomainEvent {
+    pub event: DomainEvent,
+    pub defer_recompute: bool,
+}
+
+/// The mark version observed when a portfolio job started.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecomputeTicket(u64);
+
+#[derive(Debug, Default)]
+struct PendingRecompute {
+    version: u64,
+    /// Version of the latest mark covering every account.
+    all_accounts: Option<u64>,
+    /// Version of the latest mark per account.
+    account_ids: BTreeMap<String, u64>,
+}
+
+pub struct HoldingsRecomputeState {
+    auto_recompute: bool,
+    broker_sync_running: Arc<AtomicBool>,
+    pending: Mutex<PendingRecompute>,
+}
+
+impl HoldingsRecomputeState {
+    pub fn new(auto_recompute: bool, broker_sync_running: Arc<AtomicBool>) -> Self {
+        Self {
+            auto_recompute,
+            broker_sync_running,
+            pending: Mutex::new(PendingRecompute::default()),
+        }
+    }
+
+    /// Whether events emitted right now belong to a broker sync whose recompute is deferred.
+    pub fn should_defer(&self) -> bool {
+        !self.auto_recompute && self.broker_sync_running.load(Ordering::Acquire)
+    }
+
+    /// Whether some holdings still need a recompute after a deferred sync.
+    pub fn is_pending(&self) -> bool {
+        let pending = self.pending.lock().unwrap();
+        pending.all_accounts.is_some() || !pending.account_ids.is_empty()
+    }
+
+    pub fn mark_pending(&self, account_ids: Option<&[String]>) {
+        let mut pending = self.pending.lock().unwrap();
+        pending.version += 1;
+        let version = pending.version;
+        match account_ids {
+            Some(ids) => {
+                for id in ids {
+                    pending.account_ids.insert(id.clone(), version);
+                }
+            }
+            None => pending.all_accounts = Some(version),
+        }
+    }
+
+    /// Taken when a portfolio job starts; pass it to [`Self::mark_recomputed`] on completion.
+    pub fn begin_recompute(&self) -> RecomputeTicket {
+        RecomputeTicket(self.pending.lock().unwrap().version)
+    }
+
+    /// Clears the accounts covered by a completed portfolio job; `None` means every account.
+    /// Marks made after `ticket` was taken are kept, since the job may not have seen them.
+    pub fn mark_recomputed(&self, ticket: RecomputeTicket, account_ids: Option<&[String]>) {
+        let mut pending = self.pending.lock().unwrap();
+        let RecomputeTicket(seen) = ticket;
+        match account_ids {
+            Some(ids) => {
+                for id in ids {
+                    if pending
+                        .account_ids
+                        .get(id)
+                        .is_some_and(|marked| *marked <= seen)
+                    {
+                        pending.account_ids.remove(id);
+                    }
+                }
+            }
+            None => {
+                pending.account_ids.retain(|_, marked| *marked > seen);
+                if pending.all_accounts.is_some_and(|marked| marked <= seen) {
+                    pending.all_accounts = None;
+                }
+            }
+        }
+    }
+}
+
+/// Plans the portfolio job for a batch, leaving out deferred events. Deferred events that
+/// would have triggered a job mark their accounts as pending instead.
+pub fn plan_batch_portfolio_job(
+    batch: &[QueuedDomainEvent],
+    timezone: &str,
+    recompute: &HoldingsRecomputeState,
+) -> Option<PortfolioJobConfig> {
+    let (deferred, immediate): (Vec<_>, Vec<_>) = batch
+        .iter()
+        .map(|queued| (queued.defer_recompute, queued.event.clone()))
+        .partition(|(defer, _)| *defer);
+    let deferred: Vec<DomainEvent> = deferred.into_iter().map(|(_, event)| event).collect();
+    let immediate: Vec<DomainEvent> = immediate.into_iter().map(|(_, event)| event).collect();
+
+    if let Some(skipped) = plan_portfolio_job(&deferred, timezone) {
+        tracing::info!(
+            "Deferring holdings recompute after broker sync for accounts: {:?}",
+            skipped.account_ids
+        );
+        recompute.mark_pending(skipped.account_ids.as_deref());
+    }
+
+    plan_portfolio_job(&immediate, timezone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activities_changed(account_id: &str) -> DomainEvent {
+        DomainEvent::activities_changed(
+            vec![account_id.to_string()],
+            vec!["AAPL".to_string()],
+            vec!["USD".to_string()],
+            None,
+        )
+    }
+
+    #[test]
+    fn auto_recompute_off_skips_sync_recompute_and_sets_flag() {
+        let running = Arc::new(AtomicBool::new(true));
+        let recompute = HoldingsRecomputeState::new(false, running.clone());
+        assert!(recompute.should_defer());
+
+        let batch = vec![QueuedDomainEvent {
+            event: activities_changed("acc-1"),
+            defer_recompute: recompute.should_defer(),
+        }];
+        assert!(plan_batch_portfolio_job(&batch, "UTC", &recompute).is_none());
+        assert!(recompute.is_pending());
+
+        let ticket = recompute.begin_recompute();
+        recompute.mark_recomputed(ticket, Some(&["acc-2".to_string()]));
+        assert!(recompute.is_pending());
+        recompute.mark_recomputed(ticket, None);
+        assert!(!recompute.is_pending());
+
+        running.store(false, Ordering::Release);
+        assert!(!recompute.should_defer());
+    }
+
+    #[test]
+    fn auto_recompute_on_keeps_current_behavior() {
+        let recompute = HoldingsRecomputeState::new(true, Arc::new(AtomicBool::new(true)));
+        assert!(!recompute.should_defer());
+
+        let batch = vec![QueuedDomainEvent {
+            event: activities_changed("acc-1"),
+            defer_recompute: recompute.should_defer(),
+        }];
+        let job = plan_batch_portfolio_job(&batch, "UTC", &recompute).unwrap();
+        assert_eq!(job.account_ids, Some(vec!["acc-1".to_string()]));
+        assert!(!recompute.is_pending());
+    }
+
+    #[test]
+    fn edits_deferred_after_a_job_started_survive_its_completion() {
+        let recompute = HoldingsRecomputeState::new(false, Arc::new(AtomicBool::new(true)));
+        recompute.mark_pending(Some(&["acc-1".to_string()]));
+
+        let ticket = recompute.begin_recompute();
+        // A user edit lands while the job is still running.
+        let edit = vec![QueuedDomainEvent {
+            event: activities_changed("acc-2"),
+            defer_recompute: recompute.should_defer(),
+        }];
+        assert!(plan_batch_portfolio_job(&edit, "UTC", &recompute).is_none());
+        recompute.mark_pending(Some(&["acc-1".to_string()]));
+
+        recompute.mark_recomputed(ticket, Some(&["acc-1".to_string(), "acc-2".to_string()]));
+        assert!(recompute.is_pending());
+        recompute.mark_recomputed(ticket, None);
+        assert!(recompute.is_pending());
+
+        recompute.mark_recomputed(recompute.begin_recompute(), None);
+        assert!(!recompute.is_pending());
+    }
+}
@@ -11,10 +11,15 @@ use wealthfolio_core::{
     assets::AssetServiceTrait,
     events::{DomainEvent, DomainEventSink},
     goals::GoalServiceTrait,
+    portfolio::{
+        allocation::AllocationServiceTrait, analytics_warmup::AnalyticsWarmupService,
+        gains::GainsServiceTrait,
+    },
     secrets::SecretStore,
 };
 
 use super::queue_worker::{event_queue_worker, QueueWorkerDeps};
+use super::recompute::{HoldingsRecomputeState, QueuedDomainEvent};
 use crate::events::EventBus;
 
 /// Domain event sink for the web server runtime.
@@ -29,8 +34,9 @@ use crate::events::EventBus;
 /// 1. Create the sink with `new()` - this just creates the channel
 /// 2. Call `start_worker()` after all services are created - this spawns the worker
 pub struct WebDomainEventSink {
-    tx: mpsc::UnboundedSender<DomainEvent>,
-    rx: std::sync::Mutex<Option<mpsc::UnboundedReceiver<DomainEvent>>>,
+    tx: mpsc::UnboundedSender<QueuedDomainEvent>,
+    rx: std::sync::Mutex<Option<mpsc::UnboundedReceiver<QueuedDomainEvent>>>,
+    holdings_recompute: Option<Arc<HoldingsRecomputeState>>,
 }
 
 impl WebDomainEventSink {
@@ -44,9 +50,19 @@ impl WebDomainEventSink {
         Self {
             tx,
             rx: std::sync::Mutex::new(Some(rx)),
+            holdings_recompute: None,
         }
     }
 
+    /// Tags events emitted during a broker sync so the worker can defer their recompute.
+    pub fn with_holdings_recompute(
+        mut self,
+        holdings_recompute: Arc<HoldingsRecomputeState>,
+    ) -> Self {
+        self.holdings_recompute = Some(holdings_recompute);
+        self
+    }
+
     /// Starts the background worker that processes events.
     ///
     /// This must be called after all services are created. Events received
@@ -74,6 +90,8 @@ impl WebDomainEventSink {
         >,
         account_service: Arc<wealthfolio_core::accounts::AccountService>,
         goal_service: Arc<dyn GoalServiceTrait + Send + Sync>,
+        allocation_service: Arc<dyn AllocationServiceTrait + Send + Sync>,
+        gains_service: Arc<dyn GainsServiceTrait + Send + Sync>,
         fx_service: Arc<dyn wealthfolio_core::fx::FxServiceTrait + Send + Sync>,
         base_currency: Arc<RwLock<String>>,
         timezone: Arc<RwLock<String>>,
@@ -83,6 +101,8 @@ impl WebDomainEventSink {
         categorization_rules_service: Arc<
             wealthfolio_spending::categorization_rules::CategorizationRulesService,
         >,
+        holdings_recompute: Arc<HoldingsRecomputeState>,
+        analytics_warmup: Arc<AnalyticsWarmupService>,
     ) {
         let rx = self
             .rx
@@ -103,6 +123,8 @@ impl WebDomainEventSink {
             valuation_service,
             account_service,
             goal_service,
+            allocation_service,
+            gains_service,
             fx_service,
             base_currency,
             timezone,
@@ -110,6 +132,8 @@ impl WebDomainEventSink {
             token_lifecycle,
             spending_settings_service,
             categorization_rules_service,
+            holdings_recompute,
+            analytics_warmup,
         });
 
         // Spawn the background worker
@@ -121,10 +145,11 @@ impl WebDomainEventSink {
     /// Use this when you want to manually control the worker lifecycle.
     /// The caller is responsible for spawning the worker with the receiver.
     #[cfg(test)]
-    pub fn with_sender(tx: mpsc::UnboundedSender<DomainEvent>) -> Self {
+    pub fn with_sender(tx: mpsc::UnboundedSender<QueuedDomainEvent>) -> Self {
         Self {
             tx,
             rx: std::sync::Mutex::new(None),
+            holdings_recompute: None,
         }
     }
 }
@@ -139,7 +164,14 @@ impl DomainEventSink for WebDomainEventSink {
     fn emit(&self, event: DomainEvent) {
         // Send is non-blocking. If the channel is full or closed, we drop the event.
         // This is intentional - domain events are best-effort.
-        if let Err(e) = self.tx.send(event) {
+        let defer_recompute = self
+            .holdings_recompute
+            .as_ref()
+            .is_some_and(|recompute| recompute.should_defer());
+        if let Err(e) = self.tx.send(QueuedDomainEvent {
+            event,
+            defer_recompute,
+        }) {
             tracing::warn!("Failed to emit domain event: {}", e);
         }
     }
@@ -159,7 +191,7 @@ mod tests {
             asset_ids: vec!["AAPL".to_string()],
         });
 
-        let event = rx.try_recv().unwrap();
+        let event = rx.try_recv().unwrap().event;
         match event {
             DomainEvent::AssetsCreated { asset_ids } => {
                 assert_eq!(asset_ids, vec!["AAPL".to_string()]);
@@ -184,8 +216,8 @@ mod tests {
             },
         ]);
 
-        let event1 = rx.try_recv().unwrap();
-        let event2 = rx.try_recv().unwrap();
+        let event1 = rx.try_recv().unwrap().event;
+        let event2 = rx.try_recv().unwrap().event;
 
         assert!(matches!(event1, DomainEvent::AssetsCreated { .. }));
         assert!(matches!(event2, DomainEvent::AssetsCreated { .. }));
@@ -200,9 +232,24 @@ mod tests {
         sink.emit(DomainEvent::device_sync_pull_complete());
 
         assert!(matches!(
-            rx.try_recv().unwrap(),
+            rx.try_recv().unwrap().event,
             DomainEvent::DeviceSyncPullComplete
         ));
         assert!(rx.try_recv().is_err());
     }
+
+    #[tokio::test]
+    async fn events_during_deferred_broker_sync_are_tagged() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let running = Arc::new(AtomicBool::new(false));
+        let recompute = Arc::new(HoldingsRecomputeState::new(false, running.clone()));
+        let sink = WebDomainEventSink::with_sender(tx).with_holdings_recompute(recompute);
+
+        sink.emit(DomainEvent::device_sync_pull_complete());
+        running.store(true, std::sync::atomic::Ordering::Release);
+        sink.emit(DomainEvent::device_sync_pull_complete());
+
+        assert!(!rx.try_recv().unwrap().defer_recompute);
+        assert!(rx.try_recv().unwrap().defer_recompute);
+    }
 }
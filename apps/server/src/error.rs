@@ -1,5 +1,7 @@
+use std::time::Duration;
+
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -24,6 +26,14 @@ pub enum ApiError {
     #[error("{0}")]
     Forbidden(String),
     #[error("{0}")]
+    Conflict(String),
+    /// `429`, with a `Retry-After` header saying when to try again.
+    #[error("{message}")]
+    TooManyRequests {
+        message: String,
+        retry_after: Duration,
+    },
+    #[error("{0}")]
     Internal(String),
     // Surface the underlying error message to help debugging during development
     #[error("{0}")]
@@ -42,6 +52,7 @@ impl IntoResponse for ApiError {
             ApiError::Core(e) => match e {
                 CoreError::ConstraintViolation(_) => (StatusCode::CONFLICT, e.to_string()),
                 CoreError::Validation(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+                CoreError::CloudDisabled => (StatusCode::SERVICE_UNAVAILABLE, e.to_string()),
                 _ => (StatusCode::BAD_REQUEST, e.to_string()),
             },
             ApiError::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
@@ -49,6 +60,10 @@ impl IntoResponse for ApiError {
             ApiError::BadRequest(reason) => (StatusCode::BAD_REQUEST, reason.clone()),
             ApiError::Unauthorized(reason) => (StatusCode::UNAUTHORIZED, reason.clone()),
             ApiError::Forbidden(reason) => (StatusCode::FORBIDDEN, reason.clone()),
+            ApiError::Conflict(reason) => (StatusCode::CONFLICT, reason.clone()),
+            ApiError::TooManyRequests { message, .. } => {
+                (StatusCode::TOO_MANY_REQUESTS, message.clone())
+            }
             ApiError::Internal(reason) => (StatusCode::INTERNAL_SERVER_ERROR, reason.clone()),
             ApiError::Anyhow(e) => {
                 // Downcast to known typed errors so user-facing validation
@@ -77,7 +92,15 @@ impl IntoResponse for ApiError {
             code: status.as_u16(),
             message: msg,
         });
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let ApiError::TooManyRequests { retry_after, .. } = &self {
+            // Whole seconds, rounded up so a client waiting that long is let through.
+            let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use serde_json::Value;
 use tokio::sync::broadcast;
 
@@ -8,6 +10,10 @@ pub const MARKET_SYNC_ERROR: &str = "market:sync-error";
 pub const PORTFOLIO_UPDATE_START: &str = "portfolio:update-start";
 pub const PORTFOLIO_UPDATE_COMPLETE: &str = "portfolio:update-complete";
 pub const PORTFOLIO_UPDATE_ERROR: &str = "portfolio:update-error";
+/// A saved portfolio (account group) was created, updated or deleted.
+pub const PORTFOLIOS_CHANGED: &str = "portfolio:portfolios-changed";
+/// Analytics caches were warmed after a recalculation or on request.
+pub const ANALYTICS_READY: &str = "analytics:ready";
 pub const ASSET_ENRICHMENT_START: &str = "asset:enrichment-start";
 pub const ASSET_ENRICHMENT_COMPLETE: &str = "asset:enrichment-complete";
 
@@ -15,11 +21,113 @@ pub const ASSET_ENRICHMENT_PROGRESS: &str = "asset:enrichment-progress";
 pub const BROKER_SYNC_START: &str = "broker:sync-start";
 pub const BROKER_SYNC_COMPLETE: &str = "broker:sync-complete";
 pub const BROKER_SYNC_ERROR: &str = "broker:sync-error";
+/// A broker sync run finished (or failed); the payload is a `BrokerSyncSummary`.
+pub const BROKER_SYNC_SUMMARY: &str = "broker:sync-summary";
+pub const SYNC_ANOMALY: &str = "sync:anomaly";
+/// The cloud refused a scheduled broker sync because the subscription expired or does not
+/// cover it; scheduled runs stay skipped until the plan allows sync again.
+pub const BROKER_SUBSCRIPTION_REQUIRED: &str = "broker:subscription-required";
+/// Scheduled broker sync stopped retrying after repeated permanent failures.
+pub const SYNC_SUSPENDED: &str = "sync:suspended";
+/// Device sync is re-downloading its snapshot after a cycle found local state unrecoverable.
+pub const SYNC_BOOTSTRAP_TRIGGERED: &str = "sync:bootstrap-triggered";
+pub const CONNECTION_RENAMED: &str = "connection:renamed";
+/// A connection's broker consent lapses within the warning lead time.
+pub const CONNECTION_EXPIRING: &str = "connection:expiring";
+pub const CLOUD_DISABLED: &str = "cloud:disabled";
+pub const CLOUD_ENABLED: &str = "cloud:enabled";
+
+/// Category an event belongs to, used to filter SSE subscriptions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventTopic {
+    Market,
+    Portfolio,
+    Asset,
+    /// Broker sync runs, their progress and anomalies.
+    Sync,
+    Connection,
+    Cloud,
+}
+
+impl EventTopic {
+    pub const ALL: [EventTopic; 6] = [
+        EventTopic::Market,
+        EventTopic::Portfolio,
+        EventTopic::Asset,
+        EventTopic::Sync,
+        EventTopic::Connection,
+        EventTopic::Cloud,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventTopic::Market => "market",
+            EventTopic::Portfolio => "portfolio",
+            EventTopic::Asset => "asset",
+            EventTopic::Sync => "sync",
+            EventTopic::Connection => "connection",
+            EventTopic::Cloud => "cloud",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|topic| topic.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// Topic of an event, derived from the prefix of its name.
+    pub fn of_event(name: &str) -> Option<Self> {
+        let prefix = name.split([':', '-']).next().unwrap_or(name);
+        match prefix {
+            "broker" => Some(EventTopic::Sync),
+            "analytics" => Some(EventTopic::Portfolio),
+            other => Self::parse(other),
+        }
+    }
+}
+
+/// Set of topics one SSE connection listens to. Empty means every event.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TopicFilter {
+    topics: BTreeSet<EventTopic>,
+}
+
+impl TopicFilter {
+    /// Parses a comma separated `topics` query value. Unknown topics are rejected.
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        let mut topics = BTreeSet::new();
+        for part in value
+            .unwrap_or_default()
+            .split(',')
+            .filter(|part| !part.trim().is_empty())
+        {
+            let topic = EventTopic::parse(part).ok_or_else(|| {
+                let known: Vec<&str> = EventTopic::ALL.iter().map(EventTopic::as_str).collect();
+                format!(
+                    "Unknown event topic '{}'; expected one of: {}",
+                    part.trim(),
+                    known.join(", ")
+                )
+            })?;
+            topics.insert(topic);
+        }
+        Ok(Self { topics })
+    }
+
+    pub fn matches(&self, event: &ServerEvent) -> bool {
+        self.topics.is_empty()
+            || event
+                .topic
+                .is_some_and(|topic| self.topics.contains(&topic))
+    }
+}
 
 /// Serializable envelope that carries event names and optional payloads.
 #[derive(Clone, Debug)]
 pub struct ServerEvent {
     pub name: &'static str,
+    pub topic: Option<EventTopic>,
     pub payload: Option<Value>,
 }
 
@@ -27,6 +135,7 @@ impl ServerEvent {
     pub fn new(name: &'static str) -> Self {
         Self {
             name,
+            topic: EventTopic::of_event(name),
             payload: None,
         }
     }
@@ -34,6 +143,7 @@ impl ServerEvent {
     pub fn with_payload(name: &'static str, payload: Value) -> Self {
         Self {
             name,
+            topic: EventTopic::of_event(name),
             payload: Some(payload),
         }
     }
@@ -60,3 +170,62 @@ impl EventBus {
         let _ = self.sender.send(event);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_event_name_has_a_topic() {
+        for name in [
+            MARKET_SYNC_START,
+            MARKET_SYNC_COMPLETE,
+            MARKET_SYNC_ERROR,
+            PORTFOLIO_UPDATE_START,
+            PORTFOLIO_UPDATE_COMPLETE,
+            PORTFOLIO_UPDATE_ERROR,
+            PORTFOLIOS_CHANGED,
+            ANALYTICS_READY,
+            ASSET_ENRICHMENT_START,
+            ASSET_ENRICHMENT_COMPLETE,
+            ASSET_ENRICHMENT_PROGRESS,
+            BROKER_SYNC_START,
+            BROKER_SYNC_COMPLETE,
+            BROKER_SYNC_ERROR,
+            BROKER_SYNC_SUMMARY,
+            SYNC_ANOMALY,
+            SYNC_SUSPENDED,
+            SYNC_BOOTSTRAP_TRIGGERED,
+            CONNECTION_RENAMED,
+            CONNECTION_EXPIRING,
+            CLOUD_DISABLED,
+            CLOUD_ENABLED,
+            "sync-progress",
+        ] {
+            assert!(EventTopic::of_event(name).is_some(), "{name} has no topic");
+        }
+        assert_eq!(
+            EventTopic::of_event(BROKER_SYNC_START),
+            Some(EventTopic::Sync)
+        );
+        assert_eq!(
+            EventTopic::of_event("sync-progress"),
+            Some(EventTopic::Sync)
+        );
+    }
+
+    #[test]
+    fn filter_parses_topics_and_rejects_unknown_ones() {
+        let filter = TopicFilter::parse(Some("sync, Cloud")).unwrap();
+        assert!(filter.matches(&ServerEvent::new(BROKER_SYNC_COMPLETE)));
+        assert!(filter.matches(&ServerEvent::new(CLOUD_DISABLED)));
+        assert!(!filter.matches(&ServerEvent::new(PORTFOLIO_UPDATE_START)));
+
+        let all = TopicFilter::parse(None).unwrap();
+        assert!(all.matches(&ServerEvent::new(MARKET_SYNC_START)));
+        assert_eq!(TopicFilter::parse(Some("")), Ok(TopicFilter::default()));
+
+        let err = TopicFilter::parse(Some("sync,weather")).unwrap_err();
+        assert!(err.contains("weather"));
+    }
+}
@@ -1,4 +1,14 @@
-use wealthfolio_connect::DEFAULT_CLOUD_API_URL;
+use std::sync::Once;
+use std::time::Duration;
+
+use wealthfolio_connect::{
+    require_https_from_env, resolve_cloud_api_url, resolve_cloud_api_urls,
+    subscription_grace_period_from_env, validate_cloud_api_url, CloudApiUrlError,
+    ConnectClientConfig, SyncConfig, DEFAULT_PERMANENT_FAILURE_THRESHOLD,
+};
+use wealthfolio_core::activities::CurrencyMismatchPolicy;
+use wealthfolio_core::portfolio::valuation::StaleQuotePolicy;
+use wealthfolio_core::utils::http_retry::MAX_HTTP_TIMEOUT_SECS;
 
 pub fn connect_sync_enabled() -> bool {
     cfg!(feature = "connect-sync")
@@ -17,9 +27,134 @@ pub fn cloud_api_base_url() -> Option<String> {
         return None;
     }
 
-    std::env::var("CONNECT_API_URL")
+    match resolve_cloud_api_url(std::env::var("CONNECT_API_URL").ok().as_deref()) {
+        Ok(url) => Some(url),
+        Err(e) => {
+            static WARNED: Once = Once::new();
+            WARNED.call_once(|| tracing::warn!("Cloud features are unavailable: {}", e));
+            None
+        }
+    }
+}
+
+/// Read replica listed after the primary in `CONNECT_API_URL`, for Connect API GETs to fail over
+/// to.
+pub fn cloud_api_fallback_url() -> Option<String> {
+    if !cloud_sync_enabled() {
+        return None;
+    }
+    resolve_cloud_api_urls(std::env::var("CONNECT_API_URL").ok().as_deref())
+        .ok()
+        .and_then(|urls| urls.fallback)
+}
+
+/// Rejects a malformed `CONNECT_API_URL`, and a plain-HTTP one (primary or fallback) unless it
+/// points at localhost or `CONNECT_API_REQUIRE_HTTPS=false`. Checked at startup so tokens never
+/// go out unencrypted.
+pub fn validate_cloud_api_base_url() -> Result<(), CloudApiUrlError> {
+    if !cloud_sync_enabled() {
+        return Ok(());
+    }
+    let urls = resolve_cloud_api_urls(std::env::var("CONNECT_API_URL").ok().as_deref())?;
+    validate_cloud_api_url(&urls.primary, require_https_from_env())?;
+    match &urls.fallback {
+        Some(fallback) => validate_cloud_api_url(fallback, require_https_from_env()),
+        None => Ok(()),
+    }
+}
+
+/// Broker sync configuration from the environment; see [`SyncConfig::from_env`] for the
+/// variables it reads.
+pub fn broker_sync_config() -> SyncConfig {
+    SyncConfig::from_env()
+}
+
+/// HTTP settings for Connect API clients. `CONNECT_HTTP_TIMEOUT_SECS` bounds each whole request
+/// (capped at 300); unset keeps the connect plus read timeout.
+pub fn connect_client_config() -> ConnectClientConfig {
+    let config = ConnectClientConfig::from_env();
+    match std::env::var("CONNECT_HTTP_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+    {
+        Some(secs) => {
+            config.with_request_timeout(Duration::from_secs(secs.min(MAX_HTTP_TIMEOUT_SECS)))
+        }
+        None => config,
+    }
+}
+
+/// Consecutive identical permanent failures before scheduled broker sync suspends itself, from
+/// `CONNECT_SYNC_SUSPEND_AFTER_FAILURES`. `0` keeps retrying forever.
+pub fn scheduled_sync_suspend_threshold() -> u32 {
+    std::env::var("CONNECT_SYNC_SUSPEND_AFTER_FAILURES")
         .ok()
-        .map(|v| v.trim().trim_end_matches('/').to_string())
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .unwrap_or(DEFAULT_PERMANENT_FAILURE_THRESHOLD)
+}
+
+/// How long scheduled sync trusts the last active subscription check while the cloud cannot be
+/// reached, from `CONNECT_SUBSCRIPTION_GRACE_HOURS` (default 24). `0` skips sync on any failed
+/// check.
+pub fn subscription_grace_period() -> chrono::Duration {
+    subscription_grace_period_from_env()
+}
+
+/// Whether holdings are recomputed right after a broker sync, from `BROKER_SYNC_AUTO_RECOMPUTE`
+/// (default `true`). When `false`, sync only upserts data and marks holdings as needing a
+/// recompute, leaving it to `POST /portfolio/recalculate`.
+pub fn broker_sync_auto_recompute() -> bool {
+    std::env::var("BROKER_SYNC_AUTO_RECOMPUTE")
+        .ok()
+        .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "false" | "0" | "no"))
+        .unwrap_or(true)
+}
+
+/// Whether the scheduler runs its first broker sync right at startup instead of 30–90 seconds
+/// later, from `BROKER_SYNC_ON_START` (default `false`).
+#[cfg(feature = "connect-sync")]
+pub fn broker_sync_on_start() -> bool {
+    std::env::var("BROKER_SYNC_ON_START")
+        .ok()
+        .is_some_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "true" | "1" | "yes"))
+}
+
+/// Shared secret the cloud signs sync webhooks with, from `CONNECT_WEBHOOK_SECRET`. When unset,
+/// `POST /api/v1/sync/webhook` is disabled and broker sync relies on the scheduler alone.
+#[cfg(feature = "connect-sync")]
+pub fn connect_webhook_secret() -> Option<String> {
+    std::env::var("CONNECT_WEBHOOK_SECRET")
+        .ok()
+        .map(|v| v.trim().to_string())
         .filter(|v| !v.is_empty())
-        .or_else(|| Some(DEFAULT_CLOUD_API_URL.to_string()))
+}
+
+/// Device sync server event retention in days, from `WF_SYNC_SERVER_RETENTION_DAYS`. The cloud
+/// service does not advertise it, so the cursor expiry forecast reports it as unknown when unset.
+#[cfg(feature = "device-sync")]
+pub fn sync_server_retention_days() -> Option<i64> {
+    std::env::var("WF_SYNC_SERVER_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|days| *days > 0)
+}
+
+/// Stale-quote policy for current valuations. `WF_STALE_QUOTE_MAX_AGE_HOURS` overrides the
+/// default age after which a cached quote is flagged as stale.
+pub fn stale_quote_policy() -> StaleQuotePolicy {
+    std::env::var("WF_STALE_QUOTE_MAX_AGE_HOURS")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|hours| *hours > 0)
+        .map(StaleQuotePolicy::from_hours)
+        .unwrap_or_default()
+}
+
+/// How sync and import handle activities whose currency differs from their account currency,
+/// from `WF_ACTIVITY_CURRENCY_MISMATCH_POLICY` (`convert` or `flag`). Disabled when unset.
+pub fn currency_mismatch_policy() -> Option<CurrencyMismatchPolicy> {
+    std::env::var("WF_ACTIVITY_CURRENCY_MISMATCH_POLICY")
+        .ok()
+        .and_then(|v| CurrencyMismatchPolicy::parse(&v))
 }
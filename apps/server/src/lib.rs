@@ -2,15 +2,29 @@ pub mod ai_environment;
 pub mod api;
 pub mod auth;
 pub mod config;
+pub mod connectivity;
 mod domain_events;
 pub mod error;
 pub mod events;
 pub mod features;
 mod main_lib;
+pub mod maintenance;
 pub mod mcp;
+pub mod metrics;
 pub mod models;
+pub mod notifications;
 pub mod oidc;
+pub mod rate_limit;
 pub mod secrets;
+mod sync_state;
 
 pub use ai_environment::ServerAiEnvironment;
-pub use main_lib::{build_state, init_tracing, AppState};
+pub use main_lib::{build_state_with_maintenance, init_tracing, AppState};
+
+use std::sync::Arc;
+
+/// Builds the state without a startup maintenance window. The binary starts through
+/// [`build_state_with_maintenance`] instead.
+pub async fn build_state(config: &config::Config) -> anyhow::Result<Arc<AppState>> {
+    build_state_with_maintenance(config, Arc::new(maintenance::MaintenanceState::new())).await
+}
@@ -2,30 +2,37 @@ mod ai_environment;
 mod api;
 mod auth;
 mod config;
+mod connectivity;
 mod domain_events;
 mod error;
 mod events;
 mod features;
 mod main_lib;
+mod maintenance;
 mod mcp;
+mod metrics;
 mod models;
+mod notifications;
 mod oidc;
+mod rate_limit;
 mod scheduler;
 mod secrets;
+mod sync_state;
 
 use api::app_router;
 use config::Config;
-use main_lib::{build_state, init_tracing};
+use main_lib::{build_state_with_maintenance, init_tracing};
+use maintenance::{startup_router, MaintenanceState};
+use std::sync::{Arc, OnceLock};
 use tower_http::services::{ServeDir, ServeFile};
 #[cfg(feature = "device-sync")]
 use tracing::{info, warn};
-#[cfg(feature = "device-sync")]
-use wealthfolio_device_sync::SyncState;
 
 #[cfg(feature = "device-sync")]
 fn is_expected_startup_token_warmup_error(err: &crate::error::ApiError) -> bool {
     match err {
         crate::error::ApiError::Unauthorized(_) | crate::error::ApiError::Forbidden(_) => true,
+        crate::error::ApiError::Core(wealthfolio_core::errors::Error::CloudDisabled) => true,
         crate::error::ApiError::Internal(message) => {
             message.contains("No refresh token configured")
                 || message.contains("Auth refresh configuration is missing")
@@ -54,6 +61,12 @@ mod tests {
         assert!(is_expected_startup_token_warmup_error(&err));
     }
 
+    #[test]
+    fn startup_token_warmup_treats_cloud_disabled_as_expected() {
+        let err = ApiError::Core(wealthfolio_core::errors::Error::CloudDisabled);
+        assert!(is_expected_startup_token_warmup_error(&err));
+    }
+
     #[test]
     fn startup_token_warmup_treats_unexpected_internal_as_warning_candidate() {
         let err = ApiError::Internal("Upstream refresh timeout".to_string());
@@ -65,7 +78,23 @@ mod tests {
 async fn main() -> anyhow::Result<()> {
     let config = Config::from_env();
     init_tracing();
-    let state = build_state(&config).await?;
+
+    // Bind before building the state so requests made while migrations run get a
+    // maintenance response instead of a connection error.
+    let maintenance = Arc::new(MaintenanceState::new());
+    let app = Arc::new(OnceLock::new());
+    tracing::info!("Listening on {}", config.listen_addr);
+    let listener = tokio::net::TcpListener::bind(config.listen_addr).await?;
+    let startup = startup_router(maintenance.clone(), app.clone());
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            startup.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+    });
+
+    let state = build_state_with_maintenance(&config, maintenance).await?;
 
     #[cfg(feature = "device-sync")]
     #[allow(clippy::collapsible_if)]
@@ -74,23 +103,13 @@ async fn main() -> anyhow::Result<()> {
         tokio::spawn(async move {
             match api::connect::mint_access_token(&startup_state).await {
                 Ok(token) => {
-                    if startup_state
+                    // A READY state starts the background engine through the state watcher
+                    if let Err(err) = startup_state
                         .device_enroll_service
                         .get_sync_state(&token)
                         .await
-                        .map(|sync_state| sync_state.state == SyncState::Ready)
-                        .unwrap_or(false)
                     {
-                        if let Err(err) = api::device_sync_engine::ensure_background_engine_started(
-                            startup_state.clone(),
-                        )
-                        .await
-                        {
-                            warn!(
-                                "Failed to auto-start device sync background engine: {}",
-                                err
-                            );
-                        }
+                        warn!("Could not read device sync state during startup: {}", err);
                     }
                 }
                 Err(err) => {
@@ -108,7 +127,7 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Start background broker sync scheduler (4-hour interval)
-    scheduler::start_broker_sync_scheduler(state.clone());
+    let broker_sync_scheduler = scheduler::start_broker_sync_scheduler(state.clone());
 
     // Start periodic market data sync (6h interval, 2min initial delay)
     let quote_svc = state.quote_service.clone();
@@ -133,12 +152,60 @@ async fn main() -> anyhow::Result<()> {
     } else {
         tracing::info!("Authentication disabled");
     }
-    tracing::info!("Listening on {}", config.listen_addr);
-    let listener = tokio::net::TcpListener::bind(config.listen_addr).await?;
-    axum::serve(
-        listener,
-        router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
-    )
-    .await?;
+    let _ = app.set(router);
+
+    let mut server = server;
+    tokio::select! {
+        result = &mut server => {
+            result??;
+            return Ok(());
+        }
+        _ = shutdown_signal() => {}
+    }
+
+    tracing::info!("Shutdown signal received, waiting for background sync to finish");
+    if !broker_sync_scheduler
+        .shutdown(SCHEDULER_SHUTDOWN_TIMEOUT)
+        .await
+    {
+        tracing::warn!(
+            "Broker sync still running after {}s, stopping it",
+            SCHEDULER_SHUTDOWN_TIMEOUT.as_secs()
+        );
+    }
     Ok(())
 }
+
+/// How long shutdown waits for a running broker sync. Docker sends `SIGKILL` 10 seconds after
+/// `SIGTERM` by default, so this stays below that.
+const SCHEDULER_SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// Resolves on Ctrl+C or, on Unix, `SIGTERM`.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::warn!("Could not listen for Ctrl+C: {}", err);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::warn!("Could not listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
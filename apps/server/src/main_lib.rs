@@ -2,21 +2,34 @@ use std::path::PathBuf;
 use std::sync::{atomic::AtomicBool, Arc, RwLock};
 
 use crate::{
-    ai_environment::ServerAiEnvironment, auth::AuthManager, config::Config,
-    domain_events::WebDomainEventSink, events::EventBus, oidc::OidcManager,
+    ai_environment::ServerAiEnvironment,
+    auth::AuthManager,
+    config::Config,
+    connectivity::Connectivity,
+    domain_events::{HoldingsRecomputeState, WebDomainEventSink},
+    events::EventBus,
+    maintenance::MaintenanceState,
+    metrics::SyncMetrics,
+    notifications::NotificationChannels,
+    oidc::OidcManager,
+    rate_limit::RateLimiter,
     secrets::build_secret_store,
+    sync_state::build_sync_state_store,
 };
 use tracing::{error, warn};
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, EnvFilter};
 use wealthfolio_ai::{AiProviderService, AiProviderServiceTrait, ChatConfig, ChatService};
 use wealthfolio_connect::{
-    BrokerSyncService, BrokerSyncServiceTrait, CoreImportRunRepositoryAdapter,
-    ImportRunRepositoryTrait, TokenLifecycleState,
+    BrokerSyncService, BrokerSyncServiceTrait, BrokerSyncSummary, CoreImportRunRepositoryAdapter,
+    DeviceLoginState, HistoryBackfillService, ImportRunRepositoryTrait, SubscriptionOverride,
+    TokenLifecycleState,
 };
 use wealthfolio_core::addons::{AddonService, AddonServiceTrait};
 use wealthfolio_core::{
-    accounts::{AccountService, AccountServiceTrait},
+    accounts::{
+        AccountMergeService, AccountMergeServiceTrait, AccountService, AccountServiceTrait,
+    },
     activities::{ActivityService as CoreActivityService, ActivityServiceTrait},
     assets::{
         AlternativeAssetRepositoryTrait, AlternativeAssetService, AlternativeAssetServiceTrait,
@@ -28,23 +41,29 @@ use wealthfolio_core::{
     health::{HealthService, HealthServiceTrait},
     limits::{ContributionLimitService, ContributionLimitServiceTrait},
     portfolio::allocation::{AllocationService, AllocationServiceTrait},
+    portfolio::gains::{GainsService, GainsServiceTrait},
     portfolio::income::{IncomeService, IncomeServiceTrait},
+    portfolio::ledger::{LedgerValidationService, LedgerValidationServiceTrait},
     portfolio::{
+        analytics_warmup::AnalyticsWarmupService,
         holdings::{
             holdings_valuation_service::HoldingsValuationService, HoldingsService,
             HoldingsServiceTrait,
         },
         net_worth::{NetWorthService, NetWorthServiceTrait},
-        snapshot::{SnapshotService, SnapshotServiceTrait},
+        snapshot::{HoldingsCalculator, SnapshotService, SnapshotServiceTrait},
         valuation::{ValuationService, ValuationServiceTrait},
     },
     portfolios::{PortfolioService, PortfolioServiceTrait},
     quotes::{QuoteService, QuoteServiceTrait},
-    secrets::SecretStore,
+    secrets::{migrate_secret_keys, SecretStore, SECRET_KEY_MIGRATIONS},
     settings::{SettingsRepositoryTrait, SettingsService, SettingsServiceTrait},
     taxonomies::{TaxonomyService, TaxonomyServiceTrait},
 };
-use wealthfolio_device_sync::{engine::DeviceSyncRuntimeState, DeviceEnrollService};
+use wealthfolio_device_sync::{
+    engine::{DeviceSyncRuntimeState, SyncStateStore},
+    DeviceEnrollService,
+};
 use wealthfolio_storage_sqlite::{
     accounts::AccountRepository,
     activities::ActivityRepository,
@@ -57,20 +76,37 @@ use wealthfolio_storage_sqlite::{
     health::HealthDismissalRepository,
     limits::ContributionLimitRepository,
     market_data::{MarketDataRepository, QuoteSyncStateRepository},
-    portfolio::{snapshot::SnapshotRepository, valuation::ValuationRepository},
+    portfolio::{
+        share_links::ShareLinkRepository, snapshot::SnapshotRepository,
+        valuation::ValuationRepository,
+    },
     portfolios::PortfolioRepository,
     settings::SettingsRepository,
     sync::{AppSyncRepository, BrokerSyncStateRepository, ImportRunRepository, PlatformRepository},
     taxonomies::TaxonomyRepository,
 };
 
+/// Outcome of the broker syncs run since startup, for `GET /api/v1/health/sync`.
+#[derive(Debug, Default)]
+pub struct BrokerSyncHistory {
+    pub last_run: Option<BrokerSyncSummary>,
+    pub last_success_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl BrokerSyncHistory {
+    pub fn record(&mut self, summary: &BrokerSyncSummary) {
+        if summary.success {
+            self.last_success_at = Some(summary.finished_at);
+        }
+        self.last_run = Some(summary.clone());
+    }
+}
+
 pub struct AppState {
     /// Domain event sink for emitting events after mutations.
-    /// Note: The sink is used by services injected at construction time; this field
-    /// is kept for documentation and possible future access patterns.
-    #[allow(dead_code)]
     pub domain_event_sink: Arc<dyn DomainEventSink>,
     pub account_service: Arc<AccountService>,
+    pub account_merge_service: Arc<dyn AccountMergeServiceTrait + Send + Sync>,
     pub settings_service: Arc<SettingsService>,
     pub holdings_service: Arc<dyn HoldingsServiceTrait + Send + Sync>,
     pub valuation_service: Arc<dyn ValuationServiceTrait + Send + Sync>,
@@ -79,15 +115,14 @@ pub struct AppState {
     pub base_currency: Arc<RwLock<String>>,
     pub timezone: Arc<RwLock<String>>,
     pub snapshot_service: Arc<dyn SnapshotServiceTrait + Send + Sync>,
-    /// Direct repository handle. No handler reads it currently — snapshot
-    /// access goes through `snapshot_service`. Retained for tests and any
-    /// future maintenance path that needs raw repository access.
-    #[allow(dead_code)]
     pub snapshot_repository: Arc<SnapshotRepository>,
     pub lots_repository: Arc<dyn wealthfolio_core::lots::LotRepositoryTrait + Send + Sync>,
     pub performance_service:
         Arc<dyn wealthfolio_core::portfolio::performance::PerformanceServiceTrait + Send + Sync>,
     pub income_service: Arc<dyn IncomeServiceTrait + Send + Sync>,
+    pub gains_service: Arc<dyn GainsServiceTrait + Send + Sync>,
+    pub analytics_warmup: Arc<AnalyticsWarmupService>,
+    pub ledger_validation_service: Arc<dyn LedgerValidationServiceTrait + Send + Sync>,
     pub goal_service: Arc<dyn GoalServiceTrait + Send + Sync>,
     pub limits_service: Arc<dyn ContributionLimitServiceTrait + Send + Sync>,
     pub fx_service: Arc<dyn FxServiceTrait + Send + Sync>,
@@ -109,10 +144,25 @@ pub struct AppState {
     pub oidc: Option<Arc<OidcManager>>,
     pub device_enroll_service: Arc<DeviceEnrollService>,
     pub app_sync_repository: Arc<AppSyncRepository>,
+    /// Device sync cursor and cycle lock; local database unless `WF_SYNC_STATE_URL` is set.
+    pub sync_state_store: Arc<dyn SyncStateStore>,
     pub device_sync_runtime: Arc<DeviceSyncRuntimeState>,
     pub broker_sync_running: Arc<AtomicBool>,
+    pub broker_sync_history: Arc<RwLock<BrokerSyncHistory>>,
+    /// Last cloud reachability check; scheduled sync skips its run while offline.
+    pub connectivity: Arc<Connectivity>,
+    /// Limits how often manual broker syncs start; scheduled syncs bypass it.
+    pub manual_sync_limiter: Arc<RateLimiter>,
+    /// Held while deferred broker history backfills run; separate from regular syncs.
+    pub history_backfill_running: Arc<AtomicBool>,
+    /// Session-only subscription override for UI development (`WEALTHFOLIO_DEBUG_ENDPOINTS`).
+    pub subscription_override: Arc<SubscriptionOverride>,
+    /// Holdings left stale by broker syncs run with `BROKER_SYNC_AUTO_RECOMPUTE=false`.
+    pub holdings_recompute: Arc<HoldingsRecomputeState>,
     pub health_service: Arc<dyn HealthServiceTrait + Send + Sync>,
     pub token_lifecycle: Arc<TokenLifecycleState>,
+    /// Connect sign-in started with a device code, waiting for the user to approve it.
+    pub device_login: Arc<DeviceLoginState>,
     pub custom_provider_service: Arc<wealthfolio_core::custom_provider::CustomProviderService>,
     pub portfolio_service: Arc<dyn PortfolioServiceTrait + Send + Sync>,
     pub spending_settings_service: Arc<wealthfolio_spending::settings::SpendingSettingsService>,
@@ -135,11 +185,17 @@ pub struct AppState {
     >,
     pub pat_repository: Arc<PatRepository>,
     pub mcp_audit_repository: Arc<McpAuditRepository>,
+    pub share_link_repository: Arc<ShareLinkRepository>,
     pub agent_environment: Arc<dyn wealthfolio_agent_tools::AgentEnvironment>,
     /// Whether the `/mcp` endpoint is mounted (from `Config::mcp_enabled`).
     pub mcp_enabled: bool,
     /// Whether agent tool calls are audited (from `Config::mcp_audit_enabled`).
     pub mcp_audit_enabled: bool,
+    /// Operator-set broker sync interval (from `Config::broker_sync_interval`).
+    pub broker_sync_interval: Option<std::time::Duration>,
+    pub notification_channels: Arc<NotificationChannels>,
+    /// Sync counters served at `/metrics`; records nothing without the `metrics` feature.
+    pub metrics: Arc<SyncMetrics>,
 }
 
 pub fn init_tracing() {
@@ -209,6 +265,35 @@ fn portfolio_history_backfill_needed(state: &AppState) -> bool {
     })
 }
 
+/// Starts the background engine each time the enrollment state becomes `READY`: at boot, after
+/// pairing, or after a recovery, instead of only when the server starts.
+#[cfg(feature = "device-sync")]
+fn start_sync_state_watcher(state: Arc<AppState>) {
+    let mut receiver = state.device_enroll_service.subscribe_state();
+    tokio::spawn(async move {
+        let mut was_ready = false;
+        while receiver.changed().await.is_ok() {
+            let is_ready = matches!(
+                *receiver.borrow_and_update(),
+                Some(wealthfolio_device_sync::SyncState::Ready)
+            );
+            if is_ready && !was_ready {
+                if let Err(err) = crate::api::device_sync_engine::ensure_background_engine_started(
+                    Arc::clone(&state),
+                )
+                .await
+                {
+                    warn!(
+                        "Failed to start background device sync engine after sync became ready: {}",
+                        err
+                    );
+                }
+            }
+            was_ready = is_ready;
+        }
+    });
+}
+
 #[cfg(feature = "device-sync")]
 fn start_sync_outbox_wake_worker(
     mut receiver: tokio::sync::mpsc::Receiver<()>,
@@ -235,9 +320,40 @@ fn start_sync_outbox_wake_worker(
     });
 }
 
-pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
+/// Passphrase for an encrypted database: `WF_DB_ENCRYPTION_KEY`, or the contents of the file
+/// named by `WF_DB_ENCRYPTION_KEY_FILE` (e.g. a Docker secret).
+fn database_encryption_key() -> anyhow::Result<Option<db::encryption::DatabaseKey>> {
+    let passphrase = match std::env::var("WF_DB_ENCRYPTION_KEY_FILE") {
+        Ok(path) => Some(
+            std::fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read WF_DB_ENCRYPTION_KEY_FILE: {}", e))?
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+        ),
+        Err(_) => std::env::var("WF_DB_ENCRYPTION_KEY").ok(),
+    };
+    passphrase
+        .map(|passphrase| db::encryption::DatabaseKey::new(passphrase).map_err(anyhow::Error::from))
+        .transpose()
+}
+
+/// Builds the state, keeping `maintenance` active while database migrations run.
+pub async fn build_state_with_maintenance(
+    config: &Config,
+    maintenance: Arc<MaintenanceState>,
+) -> anyhow::Result<Arc<AppState>> {
+    // Fail before anything can send a token to a misconfigured plaintext cloud URL
+    crate::features::validate_cloud_api_base_url().map_err(anyhow::Error::new)?;
+    if let Some(url) = crate::features::cloud_api_base_url() {
+        tracing::info!("Cloud API base URL: {}", url);
+    }
+
     // Ensure DATABASE_URL aligns with WF_DB_PATH so core picks the right file
     std::env::set_var("DATABASE_URL", &config.db_path);
+    if let Some(key) = database_encryption_key()? {
+        db::encryption::set_database_key(key)?;
+        tracing::info!("Database encryption at rest enabled");
+    }
     let db_path = db::init(&config.db_path)?;
     tracing::info!("Database path in use: {}", db_path);
     let data_root_path = std::path::Path::new(&db_path)
@@ -256,12 +372,20 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
     )
     .map_err(anyhow::Error::new)?;
     let secret_store: Arc<dyn SecretStore> = Arc::new(file_store);
+    migrate_secret_keys(secret_store.as_ref(), SECRET_KEY_MIGRATIONS);
     std::env::set_var(
         "WF_SECRET_FILE",
         resolved_secret_path.to_string_lossy().to_string(),
     );
 
-    db::run_migrations(&db_path)?;
+    maintenance.begin();
+    let migration_db_path = db_path.clone();
+    let migrated = tokio::task::spawn_blocking(move || db::run_migrations(&migration_db_path))
+        .await
+        .map_err(anyhow::Error::new)
+        .and_then(|result| result.map_err(anyhow::Error::from));
+    maintenance.finish();
+    migrated?;
 
     let pool = db::create_pool(&db_path)?;
     let (sync_outbox_wake_sender, sync_outbox_wake_receiver) = tokio::sync::mpsc::channel(128);
@@ -276,9 +400,16 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         e
     })?;
 
+    let broker_sync_running = Arc::new(AtomicBool::new(false));
+    let holdings_recompute = Arc::new(HoldingsRecomputeState::new(
+        crate::features::broker_sync_auto_recompute(),
+        broker_sync_running.clone(),
+    ));
+
     // Domain event sink - two-phase initialization to handle circular dependencies
     // Phase 1: Create the sink (can receive events immediately, buffers until worker starts)
-    let domain_event_sink = Arc::new(WebDomainEventSink::new());
+    let domain_event_sink =
+        Arc::new(WebDomainEventSink::new().with_holdings_recompute(holdings_recompute.clone()));
 
     let fx_repo = Arc::new(FxRepository::new(pool.clone(), writer.clone()));
     let fx_service = Arc::new(FxService::new(fx_repo).with_event_sink(domain_event_sink.clone()));
@@ -347,7 +478,11 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         pool.clone(),
         writer.clone(),
     ));
-    let app_sync_repository = Arc::new(AppSyncRepository::new(pool.clone(), writer.clone()));
+    let sync_blob_storage = wealthfolio_device_sync::SyncBlobStorage::from_env()?;
+    let app_sync_repository = Arc::new(
+        AppSyncRepository::new(pool.clone(), writer.clone()).with_blob_storage(sync_blob_storage),
+    );
+    let sync_state_store = build_sync_state_store(Arc::clone(&app_sync_repository))?;
     let quote_sync_state_repository =
         Arc::new(QuoteSyncStateRepository::new(pool.clone(), writer.clone()));
 
@@ -359,6 +494,12 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         asset_repository.clone(),
         quote_sync_state_repository.clone(),
     ));
+    let account_merge_service = Arc::new(AccountMergeService::new(
+        account_repo.clone(),
+        activity_repository.clone(),
+        account_repo.clone(),
+        domain_event_sink.clone(),
+    ));
     let custom_provider_repository = Arc::new(
         wealthfolio_storage_sqlite::custom_provider::CustomProviderSqliteRepository::new(
             pool.clone(),
@@ -517,6 +658,35 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         timezone.clone(),
     ));
 
+    let gains_service = Arc::new(
+        GainsService::new(
+            account_service.clone(),
+            holdings_service.clone(),
+            base_currency.clone(),
+        )
+        .with_lot_repository(lots_repository.clone())
+        .with_asset_service(asset_service.clone()),
+    );
+
+    let analytics_warmup = Arc::new(AnalyticsWarmupService::new(
+        portfolio_service.clone(),
+        allocation_service.clone(),
+        gains_service.clone(),
+        valuation_service.clone(),
+    ));
+
+    let ledger_validation_service = Arc::new(LedgerValidationService::new(
+        account_service.clone(),
+        activity_repository.clone(),
+        snapshot_service.clone(),
+        HoldingsCalculator::new_with_timezone(
+            fx_service.clone(),
+            base_currency.clone(),
+            timezone.clone(),
+            asset_repository.clone(),
+        ),
+    ));
+
     let goal_repository = Arc::new(GoalRepository::new(pool.clone(), writer.clone()));
     let goal_service = Arc::new(GoalService::new(goal_repository, account_service.clone()));
 
@@ -550,7 +720,9 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
             quote_service.clone(),
             core_import_run_repository,
         )
-        .with_event_sink(domain_event_sink.clone()),
+        .with_event_sink(domain_event_sink.clone())
+        .with_currency_mismatch_policy(crate::features::currency_mismatch_policy())
+        .with_symbol_normalization(settings_service.clone()),
     );
 
     // Spending: events + event_types
@@ -753,6 +925,7 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
     // Agent access: PAT auth + MCP audit trail (server-mode MCP)
     let pat_repository = Arc::new(PatRepository::new(pool.clone(), writer.clone()));
     let mcp_audit_repository = Arc::new(McpAuditRepository::new(pool.clone(), writer.clone()));
+    let share_link_repository = Arc::new(ShareLinkRepository::new(pool.clone(), writer.clone()));
 
     // Device enroll service for E2EE sync
     let cloud_api_url = crate::features::cloud_api_base_url().unwrap_or_default();
@@ -767,7 +940,6 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
 
     let event_bus = EventBus::new(256);
     let device_sync_runtime = Arc::new(DeviceSyncRuntimeState::new());
-    let broker_sync_running = Arc::new(AtomicBool::new(false));
     let token_lifecycle = Arc::new(TokenLifecycleState::new());
     let now = chrono::Utc::now();
     if let Err(err) = app_sync_repository
@@ -780,6 +952,14 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         warn!("Failed to prune local sync outbox: {}", err);
     }
 
+    // A backfill is saved as running before its fetch; one cut off by a restart is requeued.
+    if let Err(err) = HistoryBackfillService::new(settings_service.clone())
+        .requeue_interrupted()
+        .await
+    {
+        warn!("Failed to requeue interrupted history backfills: {}", err);
+    }
+
     // Domain event sink - Phase 2: Start the worker now that all services are ready
     domain_event_sink.start_worker(
         asset_service.clone(),
@@ -793,6 +973,8 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         valuation_service.clone(),
         account_service.clone(),
         goal_service.clone(),
+        allocation_service.clone(),
+        gains_service.clone(),
         fx_service.clone(),
         base_currency.clone(),
         timezone.clone(),
@@ -800,6 +982,8 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         token_lifecycle.clone(),
         spending_settings_service.clone(),
         categorization_rules_service.clone(),
+        holdings_recompute.clone(),
+        analytics_warmup.clone(),
     );
 
     let addon_service: Arc<dyn AddonServiceTrait + Send + Sync> = Arc::new(AddonService::new(
@@ -824,6 +1008,7 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
     let state = Arc::new(AppState {
         domain_event_sink,
         account_service,
+        account_merge_service,
         settings_service,
         holdings_service,
         valuation_service,
@@ -836,6 +1021,9 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         lots_repository,
         performance_service,
         income_service,
+        gains_service,
+        analytics_warmup,
+        ledger_validation_service,
         goal_service,
         limits_service,
         fx_service: fx_service.clone(),
@@ -857,10 +1045,18 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         oidc: oidc_manager,
         device_enroll_service,
         app_sync_repository,
+        sync_state_store,
         device_sync_runtime,
         broker_sync_running,
+        broker_sync_history: Arc::new(RwLock::new(BrokerSyncHistory::default())),
+        connectivity: Arc::new(Connectivity::new()),
+        manual_sync_limiter: Arc::new(RateLimiter::manual_sync()),
+        history_backfill_running: Arc::new(AtomicBool::new(false)),
+        subscription_override: Arc::new(SubscriptionOverride::from_env()),
+        holdings_recompute,
         health_service,
         token_lifecycle,
+        device_login: Arc::new(DeviceLoginState::new()),
         custom_provider_service,
         portfolio_service,
         spending_settings_service,
@@ -875,13 +1071,20 @@ pub async fn build_state(config: &Config) -> anyhow::Result<Arc<AppState>> {
         rebalance_service,
         pat_repository,
         mcp_audit_repository,
+        share_link_repository,
         agent_environment,
         mcp_enabled: config.mcp_enabled,
         mcp_audit_enabled: config.mcp_audit_enabled,
+        broker_sync_interval: config.broker_sync_interval,
+        notification_channels: Arc::new(NotificationChannels::from_env()),
+        metrics: Arc::new(SyncMetrics::new()),
     });
 
     #[cfg(feature = "device-sync")]
-    start_sync_outbox_wake_worker(sync_outbox_wake_receiver, Arc::clone(&state));
+    {
+        start_sync_outbox_wake_worker(sync_outbox_wake_receiver, Arc::clone(&state));
+        start_sync_state_watcher(Arc::clone(&state));
+    }
 
     if portfolio_history_backfill_needed(&state) {
         tracing::info!(
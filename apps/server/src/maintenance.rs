@@ -0,0 +1,95 @@
+//! Maintenance responses while startup database migrations run.
+//!
+//! The listener is bound before migrations start, so clients get a `503` with `Retry-After`
+//! instead of connection errors during upgrades: API routes answer with a JSON error body,
+//! everything else with a small self-refreshing maintenance page. `/api/v1/healthz` stays
+//! available so liveness probes do not restart the server mid-migration.
+
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Json, Router,
+};
+use serde_json::json;
+use tower::ServiceExt;
+
+/// Seconds clients are told to wait before retrying.
+pub const MAINTENANCE_RETRY_AFTER_SECS: u64 = 5;
+
+const MAINTENANCE_MESSAGE: &str = "Wealthfolio is upgrading its database. Please retry shortly.";
+const HEALTHZ_PATH: &str = "/api/v1/healthz";
+
+/// Set while startup migrations run.
+#[derive(Debug, Default)]
+pub struct MaintenanceState {
+    active: AtomicBool,
+}
+
+impl MaintenanceState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin(&self) {
+        self.active.store(true, Ordering::Release);
+    }
+
+    pub fn finish(&self) {
+        self.active.store(false, Ordering::Release);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+}
+
+/// `503` maintenance response: JSON for API paths, an HTML page for the SPA.
+pub fn maintenance_response(path: &str) -> Response {
+    if path == HEALTHZ_PATH {
+        return "ok".into_response();
+    }
+    let retry_after = [(
+        header::RETRY_AFTER,
+        MAINTENANCE_RETRY_AFTER_SECS.to_string(),
+    )];
+    if path.starts_with("/api/") {
+        let body = Json(json!({
+            "code": StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+            "message": MAINTENANCE_MESSAGE,
+        }));
+        return (StatusCode::SERVICE_UNAVAILABLE, retry_after, body).into_response();
+    }
+
+    let page = format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><meta http-equiv=\"refresh\" content=\"{}\"><title>Wealthfolio</title></head><body><p>{}</p></body></html>",
+        MAINTENANCE_RETRY_AFTER_SECS, MAINTENANCE_MESSAGE
+    );
+    (StatusCode::SERVICE_UNAVAILABLE, retry_after, Html(page)).into_response()
+}
+
+/// Router served from process start: forwards to `app` once it is built and migrations are
+/// done, and answers with the maintenance response until then.
+pub fn startup_router(maintenance: Arc<MaintenanceState>, app: Arc<OnceLock<Router>>) -> Router {
+    Router::new().fallback(move |request: Request| {
+        let maintenance = maintenance.clone();
+        let app = app.clone();
+        async move {
+            match app.get() {
+                Some(router) if !maintenance.is_active() => {
+                    let result: Result<Response, Infallible> =
+                        router.clone().oneshot(request).await;
+                    match result {
+                        Ok(response) => response,
+                        Err(never) => match never {},
+                    }
+                }
+                _ => maintenance_response(request.uri().path()),
+            }
+        }
+    })
+}
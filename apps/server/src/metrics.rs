@@ -0,0 +1,194 @@
+//! Prometheus metrics for broker and device sync, served at `/metrics`.
+//!
+//! Collected only with the `metrics` feature. Without it [`SyncMetrics`] records nothing and
+//! the endpoint is not mounted, so minimal builds do not pull in `prometheus`. Metrics carry
+//! counts and timestamps only, never account or activity data.
+
+#[cfg(feature = "metrics")]
+use prometheus::{Encoder, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+
+#[cfg(feature = "metrics")]
+struct Collectors {
+    registry: Registry,
+    broker_syncs_attempted: IntCounter,
+    broker_syncs_succeeded: IntCounter,
+    broker_syncs_failed: IntCounter,
+    broker_activities_upserted: IntCounter,
+    broker_last_success: IntGauge,
+    device_sync_cycles: IntCounterVec,
+    device_sync_last_cycle: IntGauge,
+    device_sync_engine_running: IntGauge,
+}
+
+#[cfg(feature = "metrics")]
+impl Collectors {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("wealthfolio".to_string()), None)
+            .expect("valid metrics registry");
+        let counter = |name: &str, help: &str| {
+            let counter = IntCounter::new(name, help).expect("valid metric");
+            registry
+                .register(Box::new(counter.clone()))
+                .expect("metric registered once");
+            counter
+        };
+        let gauge = |name: &str, help: &str| {
+            let gauge = IntGauge::new(name, help).expect("valid metric");
+            registry
+                .register(Box::new(gauge.clone()))
+                .expect("metric registered once");
+            gauge
+        };
+
+        let broker_syncs_attempted = counter(
+            "broker_syncs_attempted_total",
+            "Scheduled broker syncs started",
+        );
+        let broker_syncs_succeeded = counter(
+            "broker_syncs_succeeded_total",
+            "Scheduled broker syncs that completed without errors",
+        );
+        let broker_syncs_failed = counter(
+            "broker_syncs_failed_total",
+            "Scheduled broker syncs that failed or completed with errors",
+        );
+        let broker_activities_upserted = counter(
+            "broker_activities_upserted_total",
+            "Activities inserted or updated by scheduled broker syncs",
+        );
+        let broker_last_success = gauge(
+            "broker_sync_last_success_timestamp_seconds",
+            "Unix time of the last scheduled broker sync that completed without errors",
+        );
+        let device_sync_cycles = IntCounterVec::new(
+            Opts::new("device_sync_cycles_total", "Device sync cycles by outcome"),
+            &["status"],
+        )
+        .expect("valid metric");
+        registry
+            .register(Box::new(device_sync_cycles.clone()))
+            .expect("metric registered once");
+        let device_sync_last_cycle = gauge(
+            "device_sync_last_cycle_timestamp_seconds",
+            "Unix time of the last finished device sync cycle",
+        );
+        let device_sync_engine_running = gauge(
+            "device_sync_engine_running",
+            "1 while the background device sync engine is running",
+        );
+
+        Self {
+            registry,
+            broker_syncs_attempted,
+            broker_syncs_succeeded,
+            broker_syncs_failed,
+            broker_activities_upserted,
+            broker_last_success,
+            device_sync_cycles,
+            device_sync_last_cycle,
+            device_sync_engine_running,
+        }
+    }
+}
+
+/// Sync counters and gauges, shared through `AppState`.
+pub struct SyncMetrics {
+    #[cfg(feature = "metrics")]
+    collectors: Collectors,
+}
+
+impl Default for SyncMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncMetrics {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            collectors: Collectors::new(),
+        }
+    }
+
+    /// Records one finished scheduled broker sync.
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    pub fn record_broker_sync(&self, succeeded: bool, activities_upserted: usize) {
+        #[cfg(feature = "metrics")]
+        {
+            let collectors = &self.collectors;
+            collectors.broker_syncs_attempted.inc();
+            collectors
+                .broker_activities_upserted
+                .inc_by(activities_upserted as u64);
+            if succeeded {
+                collectors.broker_syncs_succeeded.inc();
+                collectors
+                    .broker_last_success
+                    .set(chrono::Utc::now().timestamp());
+            } else {
+                collectors.broker_syncs_failed.inc();
+            }
+        }
+    }
+
+    /// Records one finished device sync cycle with its outcome, e.g. `ok` or `stale_cursor`.
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    pub fn record_device_sync_cycle(&self, status: &str) {
+        #[cfg(feature = "metrics")]
+        {
+            self.collectors
+                .device_sync_cycles
+                .with_label_values(&[status])
+                .inc();
+            self.collectors
+                .device_sync_last_cycle
+                .set(chrono::Utc::now().timestamp());
+        }
+    }
+
+    /// Only the `/metrics` handler samples the engine state, so this exists with the feature only.
+    #[cfg(feature = "metrics")]
+    pub fn set_device_sync_engine_running(&self, running: bool) {
+        self.collectors
+            .device_sync_engine_running
+            .set(i64::from(running));
+    }
+
+    /// Everything recorded so far, in the Prometheus text format.
+    #[cfg(feature = "metrics")]
+    pub fn render(&self) -> Result<String, String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.collectors.registry.gather(), &mut buffer)
+            .map_err(|e| e.to_string())?;
+        String::from_utf8(buffer).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_recorded_sync_metrics() {
+        let metrics = SyncMetrics::new();
+        metrics.record_broker_sync(true, 12);
+        metrics.record_broker_sync(false, 3);
+        metrics.record_device_sync_cycle("ok");
+        metrics.record_device_sync_cycle("ok");
+        metrics.record_device_sync_cycle("stale_cursor");
+        metrics.set_device_sync_engine_running(true);
+
+        let text = metrics.render().unwrap();
+
+        assert!(text.contains("wealthfolio_broker_syncs_attempted_total 2"));
+        assert!(text.contains("wealthfolio_broker_syncs_succeeded_total 1"));
+        assert!(text.contains("wealthfolio_broker_syncs_failed_total 1"));
+        assert!(text.contains("wealthfolio_broker_activities_upserted_total 15"));
+        assert!(text.contains("wealthfolio_device_sync_cycles_total{status=\"ok\"} 2"));
+        assert!(text.contains("wealthfolio_device_sync_cycles_total{status=\"stale_cursor\"} 1"));
+        assert!(text.contains("wealthfolio_device_sync_engine_running 1"));
+        assert!(!text.contains("wealthfolio_device_sync_last_cycle_timestamp_seconds 0"));
+    }
+}
@@ -0,0 +1,78 @@
+//! In-process token bucket limiting how often manual broker syncs can be started, so a client
+//! retrying in a loop cannot get the account rate-limited by the Connect API.
+//!
+//! The bucket lives in `AppState`, so it counts across requests until the server restarts.
+//! Scheduled syncs do not go through it.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// One manual sync per this interval.
+pub const MANUAL_SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub struct RateLimiter {
+    /// Tokens the bucket holds when full.
+    burst: u32,
+    /// Time to earn back one token.
+    refill_every: Duration,
+    /// When the bucket is full again; a token is spent by moving it one refill later. Kept as
+    /// an instant rather than a token count so waits come out exact.
+    full_at: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Allows bursts of `burst` (at least one), refilling one token every `refill_every`.
+    /// Starts full.
+    pub fn new(burst: u32, refill_every: Duration) -> Self {
+        Self {
+            burst: burst.max(1),
+            refill_every,
+            full_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// One manual broker sync per [`MANUAL_SYNC_INTERVAL`].
+    pub fn manual_sync() -> Self {
+        Self::new(1, MANUAL_SYNC_INTERVAL)
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> Result<(), Duration> {
+        let mut full_at = self.full_at.lock().unwrap();
+        let until_full = full_at.saturating_duration_since(now);
+        // Spending a token must leave the bucket no further than `burst` refills from full.
+        let limit = self.refill_every * (self.burst - 1);
+        if until_full > limit {
+            return Err(until_full - limit);
+        }
+        *full_at = now + until_full + self.refill_every;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_one_sync_per_interval() {
+        let limiter = RateLimiter::manual_sync();
+        let start = Instant::now();
+
+        assert_eq!(limiter.try_acquire_at(start), Ok(()));
+        let wait = limiter
+            .try_acquire_at(start + Duration::from_secs(60))
+            .unwrap_err();
+        assert_eq!(wait.as_secs(), 4 * 60);
+
+        // Failed attempts do not push the next token further out.
+        assert!(limiter
+            .try_acquire_at(start + Duration::from_secs(120))
+            .is_err());
+        assert_eq!(limiter.try_acquire_at(start + MANUAL_SYNC_INTERVAL), Ok(()));
+    }
+}
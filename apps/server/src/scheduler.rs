@@ -1,56 +1,335 @@
 //! Background scheduler for periodic broker sync.
 //!
-//! Runs a fixed 4-hour interval sync for the Docker/Web server.
+//! Runs a periodic sync for the Docker/Web server every `BROKER_SYNC_INTERVAL_SECS` (4 hours by
+//! default), or a 24-hour fallback when the cloud pushes changes through the sync webhook
+//! (`CONNECT_WEBHOOK_SECRET`). Each tick is jittered by up to ±10% of the interval and the first
+//! one waits a random 30–90 seconds, so instances restarted together do not all hit the Connect
+//! API at once; the average cadence is unchanged. With `BROKER_SYNC_ON_START` the first tick
+//! runs right away instead; it is still the loop's first tick, so the next one comes a full
+//! interval later rather than running a second sync moments after. Ticks that fall
+//! inside the user's sync quiet hours are skipped; the next tick after the window runs as
+//! usual. A signed-in user without broker connections is skipped before any account or
+//! activity fetches.
+//!
+//! When the same permanent error fails `CONNECT_SYNC_SUSPEND_AFTER_FAILURES` runs in a row,
+//! the scheduler suspends itself and emits `sync:suspended`. Ticks are skipped until the user
+//! resumes (`POST /connect/sync/resume`), a manual sync succeeds, or the app is updated.
+//!
+//! Every run that gets as far as syncing publishes `broker:sync-summary` with its counts,
+//! duration and error, if any, with `triggeredBy: "scheduler"` and the `syncId` its log lines
+//! carry.
+//!
+//! An access token within 5 minutes of expiry is refreshed before the sync starts. When the
+//! cloud rejects the session (`401`, `invalid_grant`) ticks are skipped until the user signs in
+//! again; any other refresh failure is retried like a transient sync failure.
+//!
+//! A failed subscription check does not block sync when an earlier check within
+//! `CONNECT_SUBSCRIPTION_GRACE_HOURS` found an active subscription. When the cloud refuses a
+//! sync because the subscription expired (`402`, or `403` with `SYNC_SUBSCRIPTION_REQUIRED`),
+//! the cached active status is dropped, `broker:subscription-required` is emitted and later
+//! ticks are skipped quietly at the subscription check.
+//!
+//! A sync that fails with a transient error (network, 5xx, rate limit) is retried after 15
+//! minutes instead of a full interval, doubling with each further failure until the retry is no
+//! sooner than the next regular tick. A success resets the backoff. Auth errors and permanent
+//! failures wait for the regular tick, since retrying them early would only fail again.
+//!
+//! Before a run the cloud API is probed with a quick `HEAD`; while it cannot be reached the
+//! run is skipped quietly and retried like a transient failure.
+//!
+//! On shutdown (`SIGTERM`, e.g. `docker stop`) the scheduler stops before the next tick and a
+//! sync already running is given a short grace period to finish its writes.
 
 use std::sync::Arc;
 
 #[cfg(feature = "connect-sync")]
-use tokio::time::{interval, Duration};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::task::JoinHandle;
+#[cfg(feature = "connect-sync")]
+use tokio::time::{sleep_until, Duration, Instant};
+use tokio_util::sync::CancellationToken;
 #[cfg(not(feature = "connect-sync"))]
 use tracing::info;
 #[cfg(feature = "connect-sync")]
-use tracing::{debug, info, warn};
+use tracing::{debug, info, info_span, warn, Instrument};
 
 #[cfg(feature = "connect-sync")]
-use crate::api::connect::{has_broker_sync, perform_broker_sync};
+use crate::api::connect::{
+    broker_sync_subscription, new_sync_id, perform_broker_sync, refresh_access_token_if_expiring,
+    scheduled_sync_suspension, TokenRefreshError,
+};
+#[cfg(feature = "connect-sync")]
+use crate::config::DEFAULT_BROKER_SYNC_INTERVAL_SECS;
+#[cfg(feature = "connect-sync")]
+use crate::events::{ServerEvent, BROKER_SUBSCRIPTION_REQUIRED, SYNC_SUSPENDED};
 use crate::main_lib::AppState;
+#[cfg(feature = "connect-sync")]
+use wealthfolio_connect::{
+    BrokerSyncError, SkipReason, SubscriptionDecision, SubscriptionStatus,
+    SubscriptionStatusService,
+};
+#[cfg(feature = "connect-sync")]
+use wealthfolio_core::settings::{CloudAccessService, SyncQuietHoursService};
+#[cfg(feature = "connect-sync")]
+use wealthfolio_core::utils::backoff::exponential_backoff;
 
-/// Sync interval: 4 hours (not user-configurable to prevent API abuse)
+/// Fallback interval when the cloud pushes changes through the sync webhook.
 #[cfg(feature = "connect-sync")]
-const SYNC_INTERVAL_SECS: u64 = 4 * 60 * 60;
+const WEBHOOK_FALLBACK_INTERVAL_SECS: u64 = 24 * 60 * 60;
 
-/// Initial delay before first sync (60 seconds to let server fully start)
+/// An access token expiring sooner than this is refreshed before a scheduled sync starts.
 #[cfg(feature = "connect-sync")]
-const INITIAL_DELAY_SECS: u64 = 60;
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
 
-/// Starts the background broker sync scheduler.
+/// Window the delay before the first sync is drawn from (lets the server fully start)
+#[cfg(feature = "connect-sync")]
+const INITIAL_DELAY_SECS: std::ops::RangeInclusive<u64> = 30..=90;
+
+/// Largest deviation of a tick from the interval, in percent of the interval.
+#[cfg(feature = "connect-sync")]
+const TICK_JITTER_PERCENT: u64 = 10;
+
+/// Delay before the first retry of a transiently failed sync.
+#[cfg(feature = "connect-sync")]
+const FAILURE_RETRY_BASE_SECS: u64 = 15 * 60;
+
+/// How a scheduled run ended, as far as the next tick is concerned.
+#[cfg(feature = "connect-sync")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScheduledSyncOutcome {
+    Succeeded,
+    /// Nothing ran, or it failed in a way an early retry would not fix.
+    Skipped,
+    /// Failed with an error that may clear up on its own.
+    TransientFailure,
+}
+
+/// Delay before retrying after `consecutive_failures` transient failures in a row:
+/// [`FAILURE_RETRY_BASE_SECS`], doubling per failure, never longer than the interval.
+#[cfg(feature = "connect-sync")]
+fn failure_retry_delay(consecutive_failures: u32, interval_secs: u64) -> Duration {
+    exponential_backoff(
+        Duration::from_secs(FAILURE_RETRY_BASE_SECS),
+        consecutive_failures,
+        Duration::from_secs(interval_secs),
+    )
+}
+
+#[cfg(feature = "connect-sync")]
+fn initial_delay(rng: &mut impl Rng) -> Duration {
+    Duration::from_secs(rng.gen_range(INITIAL_DELAY_SECS))
+}
+
+/// Seconds between scheduled syncs. An operator-set interval always wins; otherwise the
+/// webhook fallback applies when the cloud pushes changes, and the default when it doesn't.
+#[cfg(feature = "connect-sync")]
+fn scheduler_interval_secs(configured: Option<Duration>, webhook_enabled: bool) -> u64 {
+    match configured {
+        Some(interval) => interval.as_secs(),
+        None if webhook_enabled => WEBHOOK_FALLBACK_INTERVAL_SECS,
+        None => DEFAULT_BROKER_SYNC_INTERVAL_SECS,
+    }
+}
+
+/// Wait before the first tick: none when syncing on start, otherwise [`initial_delay`].
 #[cfg(feature = "connect-sync")]
-pub fn start_broker_sync_scheduler(state: Arc<AppState>) {
-    tokio::spawn(async move {
-        info!("Broker sync scheduler started (4-hour interval)");
+fn first_tick_delay(sync_on_start: bool, rng: &mut impl Rng) -> Duration {
+    if sync_on_start {
+        Duration::ZERO
+    } else {
+        initial_delay(rng)
+    }
+}
 
-        // Initial delay before first sync
-        tokio::time::sleep(Duration::from_secs(INITIAL_DELAY_SECS)).await;
+/// The interval moved by a random offset of up to ±[`TICK_JITTER_PERCENT`].
+#[cfg(feature = "connect-sync")]
+fn jittered_interval(interval_secs: u64, rng: &mut impl Rng) -> Duration {
+    let max_jitter = interval_secs * TICK_JITTER_PERCENT / 100;
+    Duration::from_secs(rng.gen_range(interval_secs - max_jitter..=interval_secs + max_jitter))
+}
 
-        // Set up periodic sync - first tick is immediate, subsequent ticks are 4h apart
-        let mut sync_interval = interval(Duration::from_secs(SYNC_INTERVAL_SECS));
+/// The running scheduler, returned so shutdown can stop it without cutting a sync short.
+pub struct BrokerSyncSchedulerHandle {
+    cancel: CancellationToken,
+    task: JoinHandle<()>,
+}
 
-        loop {
-            sync_interval.tick().await;
-            run_scheduled_sync(&state).await;
+impl BrokerSyncSchedulerHandle {
+    /// Stops scheduling new syncs and waits up to `timeout` for one in flight to finish.
+    /// Returns `false` when the sync was still running at the timeout; it is then aborted.
+    pub async fn shutdown(self, timeout: std::time::Duration) -> bool {
+        self.cancel.cancel();
+        let abort = self.task.abort_handle();
+        match tokio::time::timeout(timeout, self.task).await {
+            Ok(_) => true,
+            Err(_) => {
+                abort.abort();
+                false
+            }
+        }
+    }
+}
+
+/// Starts the background broker sync scheduler.
+#[cfg(feature = "connect-sync")]
+pub fn start_broker_sync_scheduler(state: Arc<AppState>) -> BrokerSyncSchedulerHandle {
+    let cancel = CancellationToken::new();
+    let task = tokio::spawn({
+        let cancel = cancel.clone();
+        async move {
+            let interval_secs = scheduler_interval_secs(
+                state.broker_sync_interval,
+                crate::features::connect_webhook_secret().is_some(),
+            );
+            info!(
+                "Broker sync scheduler started ({}-minute interval)",
+                interval_secs / 60
+            );
+
+            let sync_on_start = crate::features::broker_sync_on_start();
+            if sync_on_start {
+                info!("Running broker sync on startup");
+            }
+            let mut rng = StdRng::from_entropy();
+            let first_tick = Instant::now() + first_tick_delay(sync_on_start, &mut rng);
+            run_scheduler_loop(cancel, interval_secs, first_tick, rng, || {
+                run_scheduled_sync(&state)
+            })
+            .await;
+            info!("Broker sync scheduler stopped");
         }
     });
+    BrokerSyncSchedulerHandle { cancel, task }
+}
+
+/// Runs `run_once` at each tick until `cancel` fires. Cancellation is only noticed between
+/// runs, so a sync that has started always finishes.
+#[cfg(feature = "connect-sync")]
+async fn run_scheduler_loop<F, Fut>(
+    cancel: CancellationToken,
+    interval_secs: u64,
+    first_tick: Instant,
+    mut rng: impl Rng,
+    mut run_once: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = ScheduledSyncOutcome>,
+{
+    // Ticks are scheduled from the previous deadline rather than from when a sync finished,
+    // so slow syncs do not stretch the cadence.
+    let mut next_tick = first_tick;
+    let mut consecutive_failures: u32 = 0;
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = sleep_until(next_tick) => {}
+        }
+        let regular_tick = next_tick + jittered_interval(interval_secs, &mut rng);
+        next_tick = match run_once().await {
+            ScheduledSyncOutcome::TransientFailure => {
+                consecutive_failures = consecutive_failures.saturating_add(1);
+                let retry = failure_retry_delay(consecutive_failures, interval_secs);
+                info!(
+                    "Retrying broker sync in {} minutes ({} failure(s) in a row)",
+                    retry.as_secs() / 60,
+                    consecutive_failures
+                );
+                regular_tick.min(Instant::now() + retry)
+            }
+            ScheduledSyncOutcome::Succeeded => {
+                consecutive_failures = 0;
+                regular_tick
+            }
+            ScheduledSyncOutcome::Skipped => regular_tick,
+        };
+    }
 }
 
 /// Starts the background broker sync scheduler.
 #[cfg(not(feature = "connect-sync"))]
-pub fn start_broker_sync_scheduler(_state: Arc<AppState>) {
+pub fn start_broker_sync_scheduler(_state: Arc<AppState>) -> BrokerSyncSchedulerHandle {
     info!("Broker sync scheduler disabled: connect-sync feature is not compiled");
+    BrokerSyncSchedulerHandle {
+        cancel: CancellationToken::new(),
+        task: tokio::spawn(async {}),
+    }
+}
+
+/// Auth errors (expected when the user isn't logged in), an expired subscription and
+/// overlapping runs are skips, not failures.
+#[cfg(feature = "connect-sync")]
+fn is_expected_skip(error: &BrokerSyncError) -> bool {
+    match error {
+        BrokerSyncError::NotAuthenticated(_) | BrokerSyncError::AlreadyRunning => true,
+        BrokerSyncError::Cloud(_) => error.is_subscription_required(),
+        BrokerSyncError::Failed(_) => false,
+    }
+}
+
+/// Drops the cached active subscription so later ticks stop at the subscription check instead
+/// of trusting it for the grace period, and tells the UI.
+#[cfg(feature = "connect-sync")]
+async fn handle_subscription_required(state: &AppState, error: &str) {
+    let subscription = SubscriptionStatusService::new(
+        state.settings_service.clone(),
+        crate::features::subscription_grace_period(),
+    );
+    if let Err(e) = subscription
+        .resolve(SubscriptionStatus::Inactive, chrono::Utc::now())
+        .await
+    {
+        warn!("Could not clear the cached subscription status: {}", e);
+    }
+    state.event_bus.publish(ServerEvent::with_payload(
+        BROKER_SUBSCRIPTION_REQUIRED,
+        serde_json::json!({ "error": error }),
+    ));
 }
 
-/// Runs a single scheduled sync operation.
+/// Runs a single scheduled sync operation. Its log lines, from the pre-checks to the sync
+/// itself, carry one `sync_id`, which is also in the run's `broker:sync-summary`.
 #[cfg(feature = "connect-sync")]
-async fn run_scheduled_sync(state: &Arc<AppState>) {
+async fn run_scheduled_sync(state: &Arc<AppState>) -> ScheduledSyncOutcome {
+    let sync_id = new_sync_id();
+    let span = info_span!("scheduled_sync", sync_id = %sync_id);
+    run_scheduled_sync_steps(state, sync_id)
+        .instrument(span)
+        .await
+}
+
+#[cfg(feature = "connect-sync")]
+async fn run_scheduled_sync_steps(state: &Arc<AppState>, sync_id: String) -> ScheduledSyncOutcome {
+    if CloudAccessService::new(state.settings_service.clone()).is_disabled() {
+        info!("Scheduled sync skipped: cloud access is disabled");
+        return ScheduledSyncOutcome::Skipped;
+    }
+
+    let suspension = scheduled_sync_suspension(state);
+    match suspension.get_suspension() {
+        Ok(Some(suspended)) => {
+            debug!(
+                "Scheduled sync skipped: suspended since {} after {} identical failures",
+                suspended.suspended_at.to_rfc3339(),
+                suspended.consecutive_failures
+            );
+            return ScheduledSyncOutcome::Skipped;
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Could not read scheduled sync suspension: {}", e),
+    }
+
+    let quiet_hours = SyncQuietHoursService::new(state.settings_service.clone());
+    let now = chrono::Utc::now();
+    if !quiet_hours.allows_sync_at(now) {
+        if let Ok(status) = quiet_hours.status(now) {
+            info!(
+                "Scheduled sync skipped: quiet hours until {}",
+                status.next_sync_at.to_rfc3339()
+            );
+        }
+        return ScheduledSyncOutcome::Skipped;
+    }
+
     info!("Running scheduled broker sync...");
 
     // Check if user has a refresh token configured (indicates they've logged in)
@@ -62,22 +341,51 @@ async fn run_scheduled_sync(state: &Arc<AppState>) {
 
     if !has_token {
         debug!("Scheduled sync skipped: no refresh token configured");
-        return;
+        return ScheduledSyncOutcome::Skipped;
     }
 
-    // Check if user's plan includes broker sync
-    match has_broker_sync(state).await {
-        Ok(true) => {}
-        Ok(false) => {
+    // Every request below would fail while offline; retry early once the network is back
+    if let Some(base_url) = crate::features::cloud_api_base_url() {
+        if !state.connectivity.check(&base_url).await {
+            debug!("Scheduled sync skipped: cloud API unreachable, probably offline");
+            return ScheduledSyncOutcome::TransientFailure;
+        }
+    }
+
+    // Refresh a token that would lapse mid-sync now rather than failing on a 401 later
+    match refresh_access_token_if_expiring(state, TOKEN_REFRESH_MARGIN).await {
+        Ok(()) => {}
+        Err(e @ TokenRefreshError::SessionExpired(_)) => {
+            debug!("Scheduled sync skipped: {}", e);
+            return ScheduledSyncOutcome::Skipped;
+        }
+        Err(e @ TokenRefreshError::Failed(_)) => {
+            warn!("Scheduled sync deferred: {}", e);
+            return ScheduledSyncOutcome::TransientFailure;
+        }
+    }
+
+    // Check if user's plan includes broker sync; during a cloud outage a recent active check
+    // is trusted for `CONNECT_SUBSCRIPTION_GRACE_HOURS`.
+    match broker_sync_subscription(state).await {
+        SubscriptionDecision::Active => {}
+        SubscriptionDecision::CachedActive { checked_at, reason } => {
+            info!(
+                "Could not verify broker sync access ({}); using active status from {}",
+                reason,
+                checked_at.to_rfc3339()
+            );
+        }
+        SubscriptionDecision::Inactive => {
             debug!("Scheduled sync skipped: plan does not include broker sync");
-            return;
+            return ScheduledSyncOutcome::Skipped;
         }
-        Err(e) => {
+        SubscriptionDecision::Unknown(reason) => {
             debug!(
                 "Scheduled sync skipped: could not verify broker sync access ({})",
-                e
+                reason
             );
-            return;
+            return ScheduledSyncOutcome::Skipped;
         }
     }
 
@@ -86,7 +394,56 @@ async fn run_scheduled_sync(state: &Arc<AppState>) {
     // - Emits broker:sync-start, broker:sync-complete, broker:sync-error events via SSE
     // - Handles subscription validation internally
     // - Syncs connections, accounts, activities, and holdings
-    match perform_broker_sync(state).await {
+    let result = perform_broker_sync(state, sync_id).await;
+    // Refused up front, or part way through for one of the connections.
+    let subscription_error = match &result {
+        Err(e) => e.cloud_error().filter(|e| e.is_subscription_required()),
+        Ok(result) => result
+            .connection_results
+            .iter()
+            .filter_map(|connection| connection.cloud_error.as_ref())
+            .find(|e| e.is_subscription_required()),
+    }
+    .map(ToString::to_string);
+    if let Some(error) = subscription_error {
+        handle_subscription_required(state, &error).await;
+    }
+    match &result {
+        Ok(result) => state.metrics.record_broker_sync(
+            result.success,
+            result
+                .activities_synced
+                .as_ref()
+                .map_or(0, |a| a.activities_upserted),
+        ),
+        Err(e) if is_expected_skip(e) => {}
+        Err(_) => state.metrics.record_broker_sync(false, 0),
+    }
+    let recorded = match &result {
+        Ok(_) => suspension.clear().await.map(|_| None),
+        Err(e) if is_expected_skip(e) => Ok(None),
+        Err(e) => suspension.record_failure(e, chrono::Utc::now()).await,
+    };
+    match recorded {
+        Ok(Some(suspended)) => {
+            warn!(
+                "Scheduled broker sync suspended after {} identical permanent failures",
+                suspended.consecutive_failures
+            );
+            state.event_bus.publish(ServerEvent::with_payload(
+                SYNC_SUSPENDED,
+                serde_json::json!(suspended),
+            ));
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Could not update scheduled sync suspension: {}", e),
+    }
+
+    match result {
+        Ok(result) if result.skip_reason == Some(SkipReason::NoConnections) => {
+            info!("Scheduled sync skipped: no broker connections");
+            ScheduledSyncOutcome::Skipped
+        }
         Ok(result) => {
             let activities_count = result
                 .activities_synced
@@ -97,18 +454,176 @@ async fn run_scheduled_sync(state: &Arc<AppState>) {
                 "Scheduled broker sync completed: {} activities synced",
                 activities_count
             );
+            for connection in &result.connection_results {
+                if let Some(error) = &connection.error {
+                    warn!(
+                        "Scheduled broker sync: connection {} failed: {}",
+                        connection.connection_id, error
+                    );
+                } else if connection.accounts_failed > 0 {
+                    warn!(
+                        "Scheduled broker sync: {} account(s) of connection {} failed",
+                        connection.accounts_failed, connection.connection_id
+                    );
+                }
+            }
+            ScheduledSyncOutcome::Succeeded
+        }
+        Err(e) if is_expected_skip(&e) => {
+            debug!("Scheduled sync skipped: {}", e);
+            ScheduledSyncOutcome::Skipped
         }
         Err(e) => {
-            // Check if this is an auth error (expected when user isn't logged in)
-            if e.contains("No refresh token")
-                || e.contains("not authenticated")
-                || e.contains("Session expired")
-                || e.contains("Broker sync already running")
-            {
-                debug!("Scheduled sync skipped: {}", e);
+            warn!("Scheduled broker sync failed: {}", e);
+            if e.is_permanent() {
+                ScheduledSyncOutcome::Skipped
             } else {
-                warn!("Scheduled broker sync failed: {}", e);
+                ScheduledSyncOutcome::TransientFailure
             }
         }
     }
 }
+
+#[cfg(all(test, feature = "connect-sync"))]
+mod tests {
+    use super::*;
+    use wealthfolio_core::errors::SYNC_SUBSCRIPTION_REQUIRED;
+
+    fn api_error(status: u16, code: Option<&str>) -> BrokerSyncError {
+        wealthfolio_core::errors::CloudApiError::Status {
+            status,
+            code: code.map(str::to_string),
+            message: Some("Refused".to_string()),
+            request: "clientRequestId=a, requestId=b".to_string(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn expired_subscriptions_are_skipped_not_failed() {
+        assert!(is_expected_skip(&api_error(
+            403,
+            Some(SYNC_SUBSCRIPTION_REQUIRED)
+        )));
+        assert!(is_expected_skip(&api_error(402, None)));
+        assert!(!is_expected_skip(&api_error(403, None)));
+        assert!(!is_expected_skip(&api_error(500, None)));
+        assert!(!is_expected_skip(&BrokerSyncError::Failed(
+            "Request failed: SYNC_SUBSCRIPTION_REQUIRED".to_string()
+        )));
+    }
+
+    #[test]
+    fn missing_sessions_and_overlapping_runs_are_skipped() {
+        assert!(is_expected_skip(&BrokerSyncError::AlreadyRunning));
+        assert!(is_expected_skip(&BrokerSyncError::NotAuthenticated(
+            "No refresh token configured. Please sign in first.".to_string()
+        )));
+    }
+
+    #[test]
+    fn jitter_stays_within_ten_percent_of_the_interval() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let interval_secs = 4 * 60 * 60;
+        let (min, max) = (interval_secs * 9 / 10, interval_secs * 11 / 10);
+
+        let ticks: Vec<u64> = (0..1000)
+            .map(|_| jittered_interval(interval_secs, &mut rng).as_secs())
+            .collect();
+        assert!(ticks.iter().all(|secs| (min..=max).contains(secs)));
+        assert!(ticks.iter().any(|secs| *secs != interval_secs));
+        let mean = ticks.iter().sum::<u64>() / ticks.len() as u64;
+        assert!(mean.abs_diff(interval_secs) < interval_secs / 100);
+
+        for _ in 0..100 {
+            assert!(INITIAL_DELAY_SECS.contains(&initial_delay(&mut rng).as_secs()));
+        }
+        assert_eq!(first_tick_delay(true, &mut rng), Duration::ZERO);
+        assert!(INITIAL_DELAY_SECS.contains(&first_tick_delay(false, &mut rng).as_secs()));
+    }
+
+    #[test]
+    fn webhook_fallback_only_replaces_an_unset_interval() {
+        let configured = Some(Duration::from_secs(30 * 60));
+        assert_eq!(scheduler_interval_secs(configured, true), 30 * 60);
+        assert_eq!(scheduler_interval_secs(configured, false), 30 * 60);
+        assert_eq!(
+            scheduler_interval_secs(None, true),
+            WEBHOOK_FALLBACK_INTERVAL_SECS
+        );
+        assert_eq!(
+            scheduler_interval_secs(None, false),
+            DEFAULT_BROKER_SYNC_INTERVAL_SECS
+        );
+    }
+
+    #[test]
+    fn failure_retries_double_from_fifteen_minutes_up_to_the_interval() {
+        let interval_secs = 4 * 60 * 60;
+        let minutes: Vec<u64> = (1..=6)
+            .map(|failures| failure_retry_delay(failures, interval_secs).as_secs() / 60)
+            .collect();
+        assert_eq!(minutes, vec![15, 30, 60, 120, 240, 240]);
+        assert_eq!(
+            failure_retry_delay(u32::MAX, interval_secs).as_secs(),
+            interval_secs
+        );
+    }
+
+    fn spawn_loop<F, Fut>(run_once: F) -> BrokerSyncSchedulerHandle
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ScheduledSyncOutcome> + Send + 'static,
+    {
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn(run_scheduler_loop(
+            cancel.clone(),
+            4 * 60 * 60,
+            Instant::now(),
+            StdRng::seed_from_u64(7),
+            run_once,
+        ));
+        BrokerSyncSchedulerHandle { cancel, task }
+    }
+
+    #[tokio::test]
+    async fn cancelling_between_ticks_stops_the_scheduler() {
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (ran_tx, mut ran_rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = spawn_loop({
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let _ = ran_tx.send(());
+                async { ScheduledSyncOutcome::Succeeded }
+            }
+        });
+
+        // The first tick is due immediately; the next one is hours away.
+        ran_rx.recv().await.unwrap();
+        assert!(handle.shutdown(Duration::from_secs(1)).await);
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_waits_for_the_sync_in_flight() {
+        let finished = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (started_tx, mut started_rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle = spawn_loop({
+            let finished = finished.clone();
+            move || {
+                let finished = finished.clone();
+                let _ = started_tx.send(());
+                async move {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    finished.store(true, std::sync::atomic::Ordering::SeqCst);
+                    ScheduledSyncOutcome::Succeeded
+                }
+            }
+        });
+
+        started_rx.recv().await.unwrap();
+        assert!(handle.shutdown(Duration::from_secs(5)).await);
+        assert!(finished.load(std::sync::atomic::Ordering::SeqCst));
+    }
+}
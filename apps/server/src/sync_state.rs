@@ -0,0 +1,61 @@
+//! Sync state store selection for the server.
+//!
+//! By default the device sync cursor and cycle lock live in the local database. Set
+//! `WF_SYNC_STATE_URL=redis://[:password@]host[:port][/db]` to share them between server
+//! instances; `WF_SYNC_STATE_NAMESPACE` (default `wealthfolio:sync`) prefixes every key.
+//! The Redis client is built only with the `redis-sync-state` feature.
+
+#[cfg(feature = "redis-sync-state")]
+mod redis_store;
+
+use std::sync::Arc;
+
+#[cfg(feature = "redis-sync-state")]
+use wealthfolio_device_sync::engine::KeyValueSyncStateStore;
+use wealthfolio_device_sync::engine::SyncStateStore;
+use wealthfolio_storage_sqlite::sync::{AppSyncRepository, SqliteSyncEngineDbPorts};
+
+#[cfg(feature = "redis-sync-state")]
+use redis_store::RedisKeyValueClient;
+
+#[cfg(feature = "redis-sync-state")]
+const DEFAULT_NAMESPACE: &str = "wealthfolio:sync";
+
+pub fn build_sync_state_store(
+    app_sync_repository: Arc<AppSyncRepository>,
+) -> anyhow::Result<Arc<dyn SyncStateStore>> {
+    let Some(url) = std::env::var("WF_SYNC_STATE_URL")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    else {
+        return Ok(Arc::new(SqliteSyncEngineDbPorts::new(app_sync_repository)));
+    };
+
+    external_sync_state_store(&url)
+}
+
+#[cfg(feature = "redis-sync-state")]
+fn external_sync_state_store(url: &str) -> anyhow::Result<Arc<dyn SyncStateStore>> {
+    let namespace = std::env::var("WF_SYNC_STATE_NAMESPACE")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string());
+    let client = RedisKeyValueClient::from_url(url)?;
+    tracing::info!(
+        "Using external sync state store at {}",
+        client.redacted_address()
+    );
+    Ok(Arc::new(KeyValueSyncStateStore::new(
+        Arc::new(client),
+        namespace,
+    )))
+}
+
+#[cfg(not(feature = "redis-sync-state"))]
+fn external_sync_state_store(_url: &str) -> anyhow::Result<Arc<dyn SyncStateStore>> {
+    anyhow::bail!(
+        "WF_SYNC_STATE_URL is set but this server was built without the `redis-sync-state` feature"
+    )
+}
@@ -0,0 +1,106 @@
+//! Redis-backed [`KeyValueClient`] for the shared sync state store.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, ExistenceCheck, SetExpiry, SetOptions};
+use tokio::sync::OnceCell;
+use wealthfolio_device_sync::engine::KeyValueClient;
+
+/// Redis-backed key-value client. The connection is opened on first use and reconnects on
+/// its own after failures.
+pub(super) struct RedisKeyValueClient {
+    client: redis::Client,
+    connection: OnceCell<ConnectionManager>,
+}
+
+impl RedisKeyValueClient {
+    pub(super) fn from_url(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| anyhow::anyhow!("Invalid WF_SYNC_STATE_URL: {}", e))?;
+        Ok(Self {
+            client,
+            connection: OnceCell::new(),
+        })
+    }
+
+    pub(super) fn redacted_address(&self) -> String {
+        format!("redis://{}", self.client.get_connection_info().addr)
+    }
+
+    async fn connection(&self) -> Result<ConnectionManager, String> {
+        self.connection
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await
+            .cloned()
+            .map_err(|e| format!("Sync state store connection failed: {}", e))
+    }
+}
+
+fn store_error(err: redis::RedisError) -> String {
+    format!("Sync state store error: {}", err)
+}
+
+#[async_trait]
+impl KeyValueClient for RedisKeyValueClient {
+    async fn get(&self, key: &str) -> Result<Option<String>, String> {
+        self.connection().await?.get(key).await.map_err(store_error)
+    }
+
+    async fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.connection()
+            .await?
+            .set(key, value)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn incr(&self, key: &str) -> Result<i64, String> {
+        self.connection()
+            .await?
+            .incr(key, 1)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn set_if_absent(&self, key: &str, value: &str, ttl: Duration) -> Result<bool, String> {
+        let options = SetOptions::default()
+            .conditional_set(ExistenceCheck::NX)
+            .with_expiration(SetExpiry::PX(
+                u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX),
+            ));
+        let reply: Option<String> = self
+            .connection()
+            .await?
+            .set_options(key, value, options)
+            .await
+            .map_err(store_error)?;
+        Ok(reply.is_some())
+    }
+
+    async fn expire(&self, key: &str, ttl: Duration) -> Result<bool, String> {
+        let ttl_ms = i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX);
+        self.connection()
+            .await?
+            .pexpire(key, ttl_ms)
+            .await
+            .map_err(store_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_redis_url() {
+        let client = RedisKeyValueClient::from_url("redis://:s3cret@cache.local:6380/2").unwrap();
+        let info = client.client.get_connection_info();
+        assert_eq!(info.redis.password.as_deref(), Some("s3cret"));
+        assert_eq!(info.redis.db, 2);
+        assert_eq!(client.redacted_address(), "redis://cache.local:6380");
+
+        assert!(RedisKeyValueClient::from_url("http://cache.local").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use common::test_app;
+use serde_json::Value;
+use tower::ServiceExt;
+use wealthfolio_connect::CLOUD_REFRESH_TOKEN_KEY;
+use wealthfolio_core::settings::CloudAccessService;
+
+async fn send(app: &Router, method: &str, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn kill_switch_blocks_cloud_calls_and_persists() {
+    let (state, app, _temp_dir) = test_app().await;
+    state
+        .secret_store
+        .set_secret(CLOUD_REFRESH_TOKEN_KEY, "refresh-token")
+        .unwrap();
+
+    let (status, body) = send(&app, "POST", "/api/v1/sync/cloud/disable").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["disabled"], true);
+
+    // Requests are refused before any token refresh or network call is attempted.
+    for (method, uri) in [
+        ("GET", "/api/v1/connect/user"),
+        ("GET", "/api/v1/connect/plans/public"),
+        ("POST", "/api/v1/connect/sync/connections"),
+        ("POST", "/api/v1/sync/broker"),
+    ] {
+        let (status, body) = send(&app, method, uri).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{method} {uri}");
+        assert!(body["message"].as_str().unwrap().contains("disabled"));
+    }
+    let (status, _) = send(&app, "POST", "/api/v1/connect/sync").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    // The flag lives in the settings store, so a fresh service sees it as well.
+    assert!(CloudAccessService::new(state.settings_service.clone()).is_disabled());
+    let (_, body) = send(&app, "GET", "/api/v1/sync/cloud/status").await;
+    assert_eq!(body["disabled"], true);
+
+    let (status, body) = send(&app, "POST", "/api/v1/sync/cloud/enable").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["disabled"], false);
+    assert!(!CloudAccessService::new(state.settings_service.clone()).is_disabled());
+}
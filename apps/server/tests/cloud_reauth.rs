@@ -0,0 +1,66 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::test_app;
+use serde_json::json;
+use tower::ServiceExt;
+use wealthfolio_connect::CLOUD_REFRESH_TOKEN_KEY;
+
+#[tokio::test]
+async fn cloud_token_reauth_leaves_device_sync_state_untouched() {
+    let (state, app, _temp_dir) = test_app().await;
+
+    let identity = r#"{"deviceId":"device-1","keyVersion":2}"#;
+    state
+        .secret_store
+        .set_secret("sync_identity", identity)
+        .unwrap();
+    state
+        .secret_store
+        .set_secret(CLOUD_REFRESH_TOKEN_KEY, "expired-refresh")
+        .unwrap();
+    state.app_sync_repository.set_cursor(42).await.unwrap();
+    let status_before = state.app_sync_repository.get_engine_status().unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/connect/session")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    json!({ "refreshToken": "fresh-refresh" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+        state
+            .secret_store
+            .get_secret(CLOUD_REFRESH_TOKEN_KEY)
+            .unwrap()
+            .as_deref(),
+        Some("fresh-refresh")
+    );
+    assert_eq!(
+        state
+            .secret_store
+            .get_secret("sync_identity")
+            .unwrap()
+            .as_deref(),
+        Some(identity)
+    );
+    let status_after = state.app_sync_repository.get_engine_status().unwrap();
+    assert_eq!(status_after.cursor, 42);
+    assert_eq!(status_after.last_pull_at, status_before.last_pull_at);
+    assert_eq!(
+        status_after.last_cycle_status,
+        status_before.last_cycle_status
+    );
+}
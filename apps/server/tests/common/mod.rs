@@ -0,0 +1,48 @@
+//! Fixture shared by the server integration tests: a server `Config` on a fresh temp directory
+//! and the state and router built from it.
+
+// Each test binary compiles its own copy and uses only part of it.
+#![allow(dead_code)]
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::Router;
+use tempfile::{tempdir, TempDir};
+use wealthfolio_server::{api::app_router, build_state, config::Config, AppState};
+
+/// Config with its database and addons under `temp_dir` and no auth.
+pub fn test_config(temp_dir: &TempDir) -> Config {
+    Config {
+        listen_addr: "127.0.0.1:0".parse::<SocketAddr>().unwrap(),
+        db_path: temp_dir
+            .path()
+            .join("app.db")
+            .to_string_lossy()
+            .into_owned(),
+        cors_allow: vec!["*".to_string()],
+        request_timeout: Duration::from_secs(30),
+        static_dir: "dist".to_string(),
+        addons_root: temp_dir
+            .path()
+            .join("addons")
+            .to_string_lossy()
+            .into_owned(),
+        raw_secret_key: vec![7; 32],
+        secrets_encryption_key: [7; 32],
+        auth: None,
+        oidc: None,
+        mcp_enabled: false,
+        mcp_audit_enabled: true,
+        mcp_allowed_hosts: None,
+        broker_sync_interval: None,
+    }
+}
+
+/// Builds the state and router on a fresh database. Keep the `TempDir` alive for the test.
+pub async fn test_app() -> (Arc<AppState>, Router, TempDir) {
+    let temp_dir = tempdir().unwrap();
+    let config = test_config(&temp_dir);
+    let state = build_state(&config).await.unwrap();
+    let app = app_router(state.clone(), &config);
+    (state, app, temp_dir)
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use common::test_app;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn put_name(app: &Router, connection_id: &str, name: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!(
+                    "/api/v1/connect/connections/{}/name",
+                    connection_id
+                ))
+                .header("content-type", "application/json")
+                .body(Body::from(json!({ "name": name }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn connection_name_can_be_set_cleared_and_is_length_limited() {
+    let (_, app, _temp_dir) = test_app().await;
+
+    let (status, body) = put_name(&app, "conn-1", json!("  My Roth IRA at Broker X ")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!("My Roth IRA at Broker X"));
+
+    let (status, _) = put_name(&app, "conn-1", json!("x".repeat(65))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = put_name(&app, "conn-1", Value::Null).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, Value::Null);
+}
@@ -0,0 +1,227 @@
+mod common;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use common::test_app;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn send(app: &Router, method: &str, uri: &str, body: Vec<u8>) -> (StatusCode, Vec<u8>) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, bytes.to_vec())
+}
+
+async fn start_app() -> (tempfile::TempDir, Router) {
+    let (_, app, temp_dir) = test_app().await;
+    (temp_dir, app)
+}
+
+async fn setup() -> (tempfile::TempDir, Router) {
+    let (temp_dir, app) = start_app().await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/v1/accounts",
+        json!({
+            "name": "Archive account",
+            "accountType": "SECURITIES",
+            "currency": "USD",
+            "isDefault": false,
+            "isActive": true
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    assert!(status.is_success());
+
+    (temp_dir, app)
+}
+
+#[tokio::test]
+async fn exported_archive_verifies_on_import() {
+    let (_temp_dir, app) = setup().await;
+
+    let (status, archive) = send(&app, "GET", "/api/v1/utilities/export/archive", Vec::new()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let manifest: Value = serde_json::from_slice(&archive).unwrap();
+    assert_eq!(manifest["manifest"]["format"], "wealthfolio-portability");
+
+    let (status, body) = send(&app, "POST", "/api/v1/utilities/import/archive", archive).await;
+    assert_eq!(status, StatusCode::OK);
+    let result: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result["accountsSkipped"], 1);
+    assert_eq!(result["accountsRestored"], 0);
+}
+
+#[tokio::test]
+async fn tampered_archive_fails_verification_on_import() {
+    let (_temp_dir, app) = setup().await;
+
+    let (status, archive) = send(&app, "GET", "/api/v1/utilities/export/archive", Vec::new()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let mut tampered: Value = serde_json::from_slice(&archive).unwrap();
+    tampered["sections"]["accounts"][0]["name"] = json!("Tampered");
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/v1/utilities/import/archive",
+        serde_json::to_vec(&tampered).unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error: Value = serde_json::from_slice(&body).unwrap();
+    assert!(error["message"]
+        .as_str()
+        .unwrap()
+        .contains("checksum mismatch"));
+}
+
+#[tokio::test]
+async fn diff_reports_accounts_added_between_exports() {
+    let (_temp_dir, app) = setup().await;
+
+    let (_, before) = send(&app, "GET", "/api/v1/utilities/export/archive", Vec::new()).await;
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/v1/accounts",
+        json!({
+            "name": "Second account",
+            "accountType": "SECURITIES",
+            "currency": "CAD",
+            "isDefault": false,
+            "isActive": true
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    assert!(status.is_success());
+    let (_, after) = send(&app, "GET", "/api/v1/utilities/export/archive", Vec::new()).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/v1/utilities/export/archive/diff",
+        serde_json::to_vec(&json!({
+            "left": String::from_utf8(before).unwrap(),
+            "right": String::from_utf8(after).unwrap(),
+        }))
+        .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let diff: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(diff["added"], 1);
+    assert_eq!(diff["removed"], 0);
+    let accounts = diff["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|section| section["name"] == "accounts")
+        .unwrap();
+    assert_eq!(accounts["unchanged"], 1);
+    assert_eq!(accounts["records"][0]["kind"], "ADDED");
+}
+
+#[tokio::test]
+async fn archive_restores_into_an_empty_database() {
+    let (_source_dir, source) = setup().await;
+    let (_, body) = send(&source, "GET", "/api/v1/accounts", Vec::new()).await;
+    let accounts: Value = serde_json::from_slice(&body).unwrap();
+    let account_id = accounts[0]["id"].as_str().unwrap().to_string();
+
+    let (status, _) = send(
+        &source,
+        "POST",
+        "/api/v1/activities",
+        json!({
+            "accountId": account_id,
+            "activityType": "DEPOSIT",
+            "activityDate": "2026-01-15T00:00:00Z",
+            "amount": "1000",
+            "currency": "USD"
+        })
+        .to_string()
+        .into_bytes(),
+    )
+    .await;
+    assert!(status.is_success());
+    let (status, body) = send(
+        &source,
+        "POST",
+        "/api/v1/goals",
+        json!({ "goalType": "custom_save_up", "title": "House", "targetAmount": 50000 })
+            .to_string()
+            .into_bytes(),
+    )
+    .await;
+    assert!(status.is_success());
+    let goal: Value = serde_json::from_slice(&body).unwrap();
+    let (status, _) = send(
+        &source,
+        "PUT",
+        &format!("/api/v1/goals/{}/funding", goal["id"].as_str().unwrap()),
+        json!([{ "accountId": account_id, "sharePercent": 50 }])
+            .to_string()
+            .into_bytes(),
+    )
+    .await;
+    assert!(status.is_success());
+    let (_, archive) = send(
+        &source,
+        "GET",
+        "/api/v1/utilities/export/archive",
+        Vec::new(),
+    )
+    .await;
+
+    let (_target_dir, target) = start_app().await;
+    let (status, body) = send(&target, "POST", "/api/v1/utilities/import/archive", archive).await;
+    assert_eq!(status, StatusCode::OK);
+    let result: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result["accountsRestored"], 1);
+    assert_eq!(result["activitiesRestored"], 1);
+    assert_eq!(result["goalsRestored"], 1);
+
+    // Everything points at the restored account and goal, not the archived IDs.
+    let (_, restored) = send(
+        &target,
+        "GET",
+        "/api/v1/utilities/export/archive",
+        Vec::new(),
+    )
+    .await;
+    let restored: Value = serde_json::from_slice(&restored).unwrap();
+    let sections = &restored["sections"];
+    let new_account_id = sections["accounts"][0]["id"].as_str().unwrap();
+    assert_ne!(new_account_id, account_id);
+    assert_eq!(sections["activities"][0]["accountId"], new_account_id);
+    assert_eq!(sections["goals"][0]["title"], "House");
+    assert_eq!(sections["goalFunding"][0]["accountId"], new_account_id);
+    assert_eq!(
+        sections["goalFunding"][0]["goalId"],
+        sections["goals"][0]["id"]
+    );
+}
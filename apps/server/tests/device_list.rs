@@ -0,0 +1,113 @@
+#![cfg(feature = "device-sync")]
+
+mod common;
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use common::test_app;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use wealthfolio_connect::CLOUD_REFRESH_TOKEN_KEY;
+
+/// `CONNECT_AUTH_URL` and `CONNECT_API_URL` are process-global; serialize the tests that set them.
+static ENV_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Serves the token refresh and an empty team device list; returns how many requests it saw.
+async fn start_mock_cloud() -> Arc<AtomicUsize> {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let token_hits = requests.clone();
+    let device_hits = requests.clone();
+    let cloud = Router::new()
+        .route(
+            "/auth/v1/token",
+            post(move || async move {
+                token_hits.fetch_add(1, Ordering::SeqCst);
+                Json(json!({ "access_token": "test-token", "expires_in": 3600 }))
+            }),
+        )
+        .route(
+            "/api/v1/sync/team/devices",
+            get(move || async move {
+                device_hits.fetch_add(1, Ordering::SeqCst);
+                Json(json!([]))
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, cloud).await;
+    });
+    std::env::set_var("CONNECT_AUTH_URL", &base);
+    std::env::set_var("CONNECT_API_URL", &base);
+    requests
+}
+
+async fn list_devices(app: Router) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/sync/devices")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn enrolled_device_lists_an_empty_team() {
+    let _env = ENV_LOCK.lock().await;
+    let requests = start_mock_cloud().await;
+    let (state, app, _temp_dir) = test_app().await;
+    state
+        .secret_store
+        .set_secret(CLOUD_REFRESH_TOKEN_KEY, "refresh")
+        .unwrap();
+    state
+        .secret_store
+        .set_secret("sync_identity", r#"{"deviceId":"device-1"}"#)
+        .unwrap();
+
+    let (status, body) = list_devices(app).await;
+    std::env::remove_var("CONNECT_AUTH_URL");
+    std::env::remove_var("CONNECT_API_URL");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!([]));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn device_list_is_rejected_before_enrollment_without_calling_the_cloud() {
+    let _env = ENV_LOCK.lock().await;
+    let requests = start_mock_cloud().await;
+    let (state, app, _temp_dir) = test_app().await;
+    state
+        .secret_store
+        .set_secret(CLOUD_REFRESH_TOKEN_KEY, "refresh")
+        .unwrap();
+
+    let (status, body) = list_devices(app).await;
+    std::env::remove_var("CONNECT_AUTH_URL");
+    std::env::remove_var("CONNECT_API_URL");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("NOT_ENROLLED"));
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+}
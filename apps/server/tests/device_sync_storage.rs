@@ -0,0 +1,45 @@
+mod common;
+
+use common::test_config;
+use tempfile::tempdir;
+use wealthfolio_device_sync::DEVICE_SYNC_STORAGE_DIR_ENV;
+use wealthfolio_server::build_state;
+
+// One test so the process-wide environment variable is not shared between tests.
+#[tokio::test]
+async fn snapshot_images_are_staged_in_the_configured_storage_dir() {
+    let temp_dir = tempdir().unwrap();
+    let config = test_config(&temp_dir);
+
+    let storage_dir = temp_dir.path().join("big-drive/sync");
+    std::env::set_var(DEVICE_SYNC_STORAGE_DIR_ENV, &storage_dir);
+    let state = build_state(&config).await.unwrap();
+    assert!(storage_dir.is_dir());
+    assert_eq!(state.app_sync_repository.blob_storage().dir(), storage_dir);
+
+    let image = state
+        .app_sync_repository
+        .export_snapshot_sqlite_image(vec!["accounts".to_string()])
+        .await
+        .unwrap();
+    assert!(image.starts_with(b"SQLite format 3\0"));
+    let staged = state
+        .app_sync_repository
+        .blob_storage()
+        .write_blob("wf_snapshot_server", &image)
+        .unwrap();
+    assert_eq!(staged.parent(), Some(storage_dir.as_path()));
+    assert_eq!(std::fs::read(&staged).unwrap(), image);
+
+    // A location that cannot hold a directory fails startup.
+    let blocked = temp_dir.path().join("blocked");
+    std::fs::write(&blocked, b"").unwrap();
+    std::env::set_var(DEVICE_SYNC_STORAGE_DIR_ENV, blocked.join("sync"));
+    let err = build_state(&config)
+        .await
+        .err()
+        .expect("startup should fail");
+    assert!(err.to_string().contains(DEVICE_SYNC_STORAGE_DIR_ENV));
+
+    std::env::remove_var(DEVICE_SYNC_STORAGE_DIR_ENV);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::test_app;
+use tokio_stream::StreamExt;
+use tower::ServiceExt;
+use wealthfolio_server::events::{
+    ServerEvent, BROKER_SYNC_START, MARKET_SYNC_START, PORTFOLIO_UPDATE_COMPLETE,
+};
+
+#[tokio::test]
+async fn topic_filtered_stream_only_receives_matching_events() {
+    let (state, app, _temp_dir) = test_app().await;
+
+    let rejected = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/events/stream?topics=sync,weather")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/events/stream?topics=portfolio")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The handler has subscribed by the time the response is returned.
+    state.event_bus.publish(ServerEvent::new(MARKET_SYNC_START));
+    state.event_bus.publish(ServerEvent::new(BROKER_SYNC_START));
+    state
+        .event_bus
+        .publish(ServerEvent::new(PORTFOLIO_UPDATE_COMPLETE));
+
+    let mut body = response.into_body().into_data_stream();
+    let mut received = String::new();
+    while !received.contains(PORTFOLIO_UPDATE_COMPLETE) {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+            .await
+            .expect("portfolio event was not streamed")
+            .unwrap()
+            .unwrap();
+        received.push_str(&String::from_utf8_lossy(&chunk));
+    }
+
+    assert!(received.contains(&format!("event: {PORTFOLIO_UPDATE_COMPLETE}")));
+    assert!(!received.contains(MARKET_SYNC_START));
+    assert!(!received.contains(BROKER_SYNC_START));
+}
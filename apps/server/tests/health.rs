@@ -14,6 +14,7 @@ async fn healthz_works() {
     let app = app_router(state, &config);
 
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
                 .uri("/api/v1/healthz")
@@ -24,6 +25,26 @@ async fn healthz_works() {
         .unwrap();
     assert_eq!(response.status(), 200);
 
+    // Sync health answers even when nothing is configured.
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/health/sync")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let health: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(health["refreshTokenConfigured"], false);
+    assert_eq!(health["brokerSync"]["running"], false);
+    assert!(health["brokerSync"]["lastRun"].is_null());
+    assert_eq!(health["deviceSync"]["engineRunning"], false);
+
     for key in ["WF_DB_PATH", "WF_SECRET_KEY", "WF_LISTEN_ADDR"] {
         std::env::remove_var(key);
     }
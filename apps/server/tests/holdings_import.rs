@@ -0,0 +1,108 @@
+mod common;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use common::test_app;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn post_json(app: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, value)
+}
+
+async fn setup() -> (tempfile::TempDir, Router, String) {
+    let (_, app, temp_dir) = test_app().await;
+
+    let (status, account) = post_json(
+        &app,
+        "/api/v1/accounts",
+        json!({
+            "name": "Statement account",
+            "accountType": "SECURITIES",
+            "currency": "USD",
+            "isDefault": false,
+            "isActive": true
+        }),
+    )
+    .await;
+    assert!(status.is_success(), "account creation failed: {account}");
+    let account_id = account["id"].as_str().unwrap().to_string();
+
+    (temp_dir, app, account_id)
+}
+
+#[tokio::test]
+async fn imported_positions_create_opening_balance_activities() {
+    let (_temp_dir, app, account_id) = setup().await;
+
+    let (status, result) = post_json(
+        &app,
+        "/api/v1/holdings/import",
+        json!({
+            "accountId": account_id,
+            "asOfDate": "2024-01-02",
+            "positions": [{
+                "symbol": "STATEMENT-FUND",
+                "name": "Statement Fund",
+                "quoteMode": "MANUAL",
+                "quantity": "10",
+                "costBasis": "1500"
+            }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "import failed: {result}");
+    assert_eq!(result["positionsImported"], 1);
+
+    let (status, search) = post_json(
+        &app,
+        "/api/v1/activities/search",
+        json!({ "page": 0, "pageSize": 50, "accountIdFilter": account_id }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let activities = search["data"].as_array().unwrap();
+    assert_eq!(activities.len(), 1);
+    let activity = &activities[0];
+    assert_eq!(activity["activityType"], "TRANSFER_IN");
+    assert_eq!(activity["sourceSystem"], "MANUAL");
+    assert_eq!(activity["metadata"]["opening_balance"], true);
+}
+
+#[tokio::test]
+async fn import_rejects_invalid_quantities() {
+    let (_temp_dir, app, account_id) = setup().await;
+
+    let (status, body) = post_json(
+        &app,
+        "/api/v1/holdings/import",
+        json!({
+            "accountId": account_id,
+            "positions": [{ "symbol": "AAPL", "quantity": "-5", "costBasis": "100" }]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["message"]
+        .as_str()
+        .unwrap()
+        .contains("quantity must be greater than zero"));
+}
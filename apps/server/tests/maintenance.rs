@@ -0,0 +1,68 @@
+mod common;
+
+use std::sync::{Arc, OnceLock};
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    Router,
+};
+use common::test_config;
+use serde_json::Value;
+use tempfile::tempdir;
+use tower::ServiceExt;
+use wealthfolio_server::{
+    api::app_router,
+    build_state_with_maintenance,
+    maintenance::{startup_router, MaintenanceState, MAINTENANCE_RETRY_AFTER_SECS},
+};
+
+async fn get(app: &Router, uri: &str) -> axum::response::Response {
+    app.clone()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn requests_get_maintenance_response_until_migrations_finish() {
+    let temp_dir = tempdir().unwrap();
+    let config = test_config(&temp_dir);
+    let maintenance = Arc::new(MaintenanceState::new());
+    let app = Arc::new(OnceLock::new());
+    let startup = startup_router(maintenance.clone(), app.clone());
+
+    // Before the state exists, API and SPA requests are answered by the startup router.
+    let response = get(&startup, "/api/v1/accounts").await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(
+        response.headers()[header::RETRY_AFTER],
+        MAINTENANCE_RETRY_AFTER_SECS.to_string()
+    );
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["code"], 503);
+
+    let response = get(&startup, "/holdings").await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/html"));
+
+    assert_eq!(
+        get(&startup, "/api/v1/healthz").await.status(),
+        StatusCode::OK
+    );
+
+    let state = build_state_with_maintenance(&config, maintenance.clone())
+        .await
+        .unwrap();
+    assert!(!maintenance.is_active());
+    let _ = app.set(app_router(state, &config));
+
+    assert_eq!(
+        get(&startup, "/api/v1/accounts").await.status(),
+        StatusCode::OK
+    );
+}
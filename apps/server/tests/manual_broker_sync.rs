@@ -0,0 +1,63 @@
+mod common;
+
+use std::sync::atomic::Ordering;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use common::test_app;
+use serde_json::Value;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn manual_sync_is_rejected_while_another_sync_runs() {
+    let (state, app, _temp_dir) = test_app().await;
+
+    // A scheduled or background sync holds the shared run guard.
+    state.broker_sync_running.store(true, Ordering::SeqCst);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/sync/broker")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert!(body["message"]
+        .as_str()
+        .unwrap()
+        .contains("already running"));
+    assert!(state.broker_sync_running.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn rejected_manual_syncs_do_not_spend_the_rate_limit() {
+    let (state, app, _temp_dir) = test_app().await;
+
+    state.broker_sync_running.store(true, Ordering::SeqCst);
+
+    for uri in ["/api/v1/connect/sync", "/api/v1/sync/broker"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT, "{}", uri);
+    }
+
+    // Neither rejected request used the single manual sync token.
+    assert!(state.manual_sync_limiter.try_acquire().is_ok());
+}
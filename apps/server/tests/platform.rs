@@ -0,0 +1,48 @@
+mod common;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use common::test_app;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn platform_reports_the_server_and_its_compiled_capabilities() {
+    let (_, app, _temp_dir) = test_app().await;
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/platform")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let platform: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(platform["os"], std::env::consts::OS);
+    assert_eq!(platform["arch"], std::env::consts::ARCH);
+    assert_eq!(platform["is_tauri"], false);
+
+    let connect_sync = cfg!(feature = "connect-sync");
+    let device_sync = cfg!(feature = "device-sync");
+    let cloud_sync = connect_sync || device_sync;
+    // The server keeps syncing without a browser open, so background sync follows cloud sync.
+    assert_eq!(
+        platform["capabilities"],
+        json!({
+            "connect_sync": connect_sync,
+            "device_sync": device_sync,
+            "cloud_sync": cloud_sync,
+            "background_sync": cloud_sync,
+        })
+    );
+    if !cloud_sync {
+        assert_eq!(platform["cloud_api_base_url"], Value::Null);
+    }
+}
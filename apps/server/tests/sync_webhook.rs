@@ -0,0 +1,71 @@
+#![cfg(feature = "connect-sync")]
+
+mod common;
+
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::test_app;
+use tower::ServiceExt;
+use wealthfolio_server::{
+    api::sync_webhook::{sign_webhook, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER},
+    events::BROKER_SYNC_ERROR,
+};
+
+const SECRET: &str = "webhook-secret";
+const BODY: &str = r#"{"connectionId":"conn-1"}"#;
+
+/// `CONNECT_WEBHOOK_SECRET` is process-global; serialize the tests that set or clear it.
+static ENV_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn signed_webhook(body: &str) -> Request<Body> {
+    let timestamp = chrono::Utc::now().timestamp();
+    let signature = sign_webhook(SECRET, timestamp, body).unwrap();
+    Request::builder()
+        .method("POST")
+        .uri("/api/v1/sync/webhook")
+        .header("content-type", "application/json")
+        .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+        .header(WEBHOOK_SIGNATURE_HEADER, format!("sha256={}", signature))
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn signed_webhook_starts_a_sync_of_its_connection() {
+    let _env = ENV_LOCK.lock().await;
+    std::env::set_var("CONNECT_WEBHOOK_SECRET", SECRET);
+    let (state, app, _temp_dir) = test_app().await;
+    let mut events = state.event_bus.subscribe();
+
+    let response = app.oneshot(signed_webhook(BODY)).await.unwrap();
+    std::env::remove_var("CONNECT_WEBHOOK_SECRET");
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+    // No cloud session is stored, so the targeted sync fails right after it starts and reports
+    // which connection it was for.
+    let payload = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let event = events.recv().await.unwrap();
+            if event.name == BROKER_SYNC_ERROR {
+                return event.payload.unwrap();
+            }
+        }
+    })
+    .await
+    .expect("webhook did not start a broker sync");
+    assert_eq!(payload["connectionId"], "conn-1");
+}
+
+#[tokio::test]
+async fn webhook_is_not_implemented_without_a_secret() {
+    let _env = ENV_LOCK.lock().await;
+    std::env::remove_var("CONNECT_WEBHOOK_SECRET");
+    let (_state, app, _temp_dir) = test_app().await;
+
+    let response = app.oneshot(signed_webhook(BODY)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+}
@@ -71,6 +71,7 @@ fn read_env_value_from_dotenv(key: &str) -> Option<String> {
 
 fn main() {
     println!("cargo:rerun-if-env-changed=CONNECT_API_URL");
+    println!("cargo:rerun-if-env-changed=CONNECT_API_REQUIRE_HTTPS");
     println!("cargo:rerun-if-env-changed=CONNECT_AUTH_URL");
     println!("cargo:rerun-if-env-changed=CONNECT_AUTH_PUBLISHABLE_KEY");
 
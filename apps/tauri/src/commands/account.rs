@@ -4,7 +4,7 @@ use crate::context::ServiceContext;
 use log::{debug, error};
 use tauri::State;
 
-use wealthfolio_core::accounts::{Account, AccountUpdate, NewAccount};
+use wealthfolio_core::accounts::{Account, AccountMergeResult, AccountUpdate, NewAccount};
 
 #[tauri::command]
 pub async fn get_accounts(
@@ -74,3 +74,35 @@ pub async fn delete_account(
             e.to_string()
         })
 }
+
+/// Merges `source_account_id` into `target_account_id`; `dry_run` only previews the merge.
+#[tauri::command]
+pub async fn merge_accounts(
+    source_account_id: String,
+    target_account_id: String,
+    dry_run: Option<bool>,
+    confirm: Option<bool>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<AccountMergeResult, String> {
+    debug!(
+        "Merging account {} into {}...",
+        source_account_id, target_account_id
+    );
+    // Domain events handle recalculation automatically
+    state
+        .account_merge_service()
+        .merge_accounts(
+            &source_account_id,
+            &target_account_id,
+            dry_run.unwrap_or(false),
+            confirm.unwrap_or(false),
+        )
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to merge account {} into {}: {}",
+                source_account_id, target_account_id, e
+            );
+            e.to_string()
+        })
+}
@@ -6,10 +6,10 @@ use log::debug;
 use tauri::State;
 use wealthfolio_core::activities::{
     Activity, ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityImport,
-    ActivitySearchResponse, ActivityUpdate, ImportActivitiesResult, ImportAssetCandidate,
-    ImportAssetPreviewItem, ImportMappingData, ImportTemplateData, InternalTransferPairRequest,
-    InternalTransferPairResponse, NewActivity, ParseConfig, ParsedCsvResult, Sort,
-    TransferMatchCandidate, TransferMatchCandidateRequest,
+    ActivityPage, ActivitySearchResponse, ActivityUpdate, ImportActivitiesResult,
+    ImportAssetCandidate, ImportAssetPreviewItem, ImportMappingData, ImportTemplateData,
+    InternalTransferPairRequest, InternalTransferPairResponse, NewActivity, ParseConfig,
+    ParsedCsvResult, RecentActivity, Sort, TransferMatchCandidate, TransferMatchCandidateRequest,
 };
 use wealthfolio_core::health::HealthServiceTrait;
 use wealthfolio_core::utils::time_utils::{
@@ -62,6 +62,62 @@ pub async fn search_activities(
     )?)
 }
 
+/// Keyset-paged activity listing, newest first; pass the previous page's `next_cursor`.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn search_activities_page(
+    cursor: Option<String>,
+    limit: Option<i64>,
+    account_id_filter: Option<Vec<String>>,
+    activity_type_filter: Option<Vec<String>>,
+    asset_id_keyword: Option<String>,
+    needs_review_filter: Option<bool>,
+    date_from: Option<String>, // YYYY-MM-DD, inclusive
+    date_to: Option<String>,   // YYYY-MM-DD, inclusive
+    instrument_type_filter: Option<Vec<String>>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ActivityPage, String> {
+    debug!("Search activities page... {:?}", limit);
+
+    let date_from_parsed = date_from
+        .map(|s| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|e| format!("Invalid date_from format: {}", e))?;
+    let date_to_parsed = date_to
+        .map(|s| chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|e| format!("Invalid date_to format: {}", e))?;
+    let timezone = state.get_timezone();
+    let tz = parse_user_timezone_or_default(&timezone);
+    let (date_from_utc, date_to_utc_exclusive) =
+        local_date_range_utc_bounds(date_from_parsed, date_to_parsed, tz)
+            .map_err(|e| e.to_string())?;
+
+    Ok(state.activity_service().search_activities_page(
+        cursor,
+        limit,
+        account_id_filter,
+        activity_type_filter,
+        asset_id_keyword,
+        needs_review_filter,
+        date_from_utc,
+        date_to_utc_exclusive,
+        instrument_type_filter,
+    )?)
+}
+
+#[tauri::command]
+pub async fn get_recent_activities(
+    limit: Option<i64>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<RecentActivity>, String> {
+    debug!("Fetching recent activities...");
+    state
+        .activity_service()
+        .get_recent_activities(limit)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn create_activity(
     activity: NewActivity,
@@ -1,8 +1,11 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::context::ServiceContext;
 use tauri::State;
-use wealthfolio_core::assets::{Asset, NewAsset, UpdateAssetProfile};
+use wealthfolio_core::assets::{
+    Asset, NewAsset, SymbolNormalization, SymbolNormalizationService, UpdateAssetProfile,
+};
 
 #[tauri::command]
 pub async fn get_asset_profile(
@@ -70,3 +73,38 @@ pub async fn delete_asset(id: String, state: State<'_, Arc<ServiceContext>>) ->
         .await
         .map_err(|e| e.to_string())
 }
+
+fn symbol_normalization(state: &ServiceContext) -> SymbolNormalizationService {
+    SymbolNormalizationService::new(state.asset_service(), state.settings_service())
+}
+
+#[tauri::command]
+pub async fn normalize_symbols(
+    symbols: Vec<String>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<SymbolNormalization>, String> {
+    symbol_normalization(&state)
+        .normalize_symbols(&symbols)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_symbol_mappings(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<BTreeMap<String, String>, String> {
+    symbol_normalization(&state)
+        .get_overrides()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_symbol_mapping(
+    raw_symbol: String,
+    target_symbol: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<BTreeMap<String, String>, String> {
+    symbol_normalization(&state)
+        .set_override(&raw_symbol, target_symbol.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
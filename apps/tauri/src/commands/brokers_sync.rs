@@ -1,17 +1,38 @@
 //! Commands for syncing broker data from the cloud API.
 
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, State};
 
 use crate::context::ServiceContext;
-use crate::events::{BROKER_SYNC_COMPLETE, BROKER_SYNC_ERROR, BROKER_SYNC_START};
+use crate::events::{
+    BROKER_SYNC_COMPLETE, BROKER_SYNC_ERROR, BROKER_SYNC_START, CONNECTION_EXPIRING,
+    CONNECTION_RENAMED, SYNC_ANOMALY,
+};
 use wealthfolio_connect::{
-    acquire_broker_sync_guard, broker::BrokerApiClient, fetch_subscription_plans_public,
-    BrokerAccount, BrokerConnection, BrokerSyncRunGuard, PlansResponse, Platform, SyncConfig,
-    SyncOrchestrator, SyncProgressPayload, SyncProgressReporter, SyncResult, UserInfo,
+    acquire_broker_sync_guard,
+    broker::{AccountResyncResult, BrokerApiClient},
+    fetch_subscription_plans_public, ActivityResumeService, BrokerAccount, BrokerConnection,
+    BrokerSyncRunGuard, ConnectApiClient, ConnectionExpiry, ConnectionNameService,
+    ConnectionSummary, FakeSubscriptionState, HistoryBackfillJob, HistoryBackfillService,
+    PlansResponse, Platform, SyncAnomaly, SyncConfig, SyncOrchestrator, SyncProgressPayload,
+    SyncProgressReporter, SyncResult, UserInfo,
 };
 
+/// Days of activity fetched on an account's first sync; older history is backfilled in the
+/// background so a new connection shows recent data right away.
+const DESKTOP_RECENT_FIRST_DAYS: u32 = 90;
+
+/// Sync configuration used by the desktop app: the same environment overrides as the server,
+/// with recent-first syncing on unless the environment sets its own window.
+fn broker_sync_config() -> SyncConfig {
+    let config = SyncConfig::from_env();
+    SyncConfig {
+        recent_first_days: config.recent_first_days.or(Some(DESKTOP_RECENT_FIRST_DAYS)),
+        ..config
+    }
+}
+
 pub(crate) fn try_acquire_broker_sync_guard(
     context: &ServiceContext,
 ) -> Option<BrokerSyncRunGuard> {
@@ -70,6 +91,24 @@ impl SyncProgressReporter for TauriProgressReporter {
             emit_broker_sync_error(&self.app_handle, &result.message);
         }
     }
+
+    fn report_anomalies(&self, anomalies: &[SyncAnomaly]) {
+        self.app_handle
+            .emit(SYNC_ANOMALY, serde_json::json!({ "anomalies": anomalies }))
+            .unwrap_or_else(|e| {
+                error!("Failed to emit sync:anomaly event: {}", e);
+            });
+    }
+
+    fn report_expiring_connections(&self, expiring: &[ConnectionExpiry]) {
+        for expiry in expiring {
+            self.app_handle
+                .emit(CONNECTION_EXPIRING, expiry)
+                .unwrap_or_else(|e| {
+                    error!("Failed to emit connection:expiring event: {}", e);
+                });
+        }
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -172,17 +211,113 @@ pub(crate) async fn perform_broker_sync_with_guard(
 
     // Create progress reporter and orchestrator
     // Use TauriProgressReporter if we have an AppHandle, otherwise use NoOp
-    if let Some(app_handle) = app {
+    let config = broker_sync_config();
+    let connection_health = config.connection_health(context.settings_service());
+    let activity_resume = ActivityResumeService::new(context.settings_service());
+    let history_backfill = HistoryBackfillService::new(context.settings_service());
+    let result = if let Some(app_handle) = app {
         let reporter = Arc::new(TauriProgressReporter::new(app_handle.clone()));
-        let orchestrator =
-            SyncOrchestrator::new(context.sync_service(), reporter, SyncConfig::default());
+        let orchestrator = SyncOrchestrator::new(context.sync_service(), reporter, config)
+            .with_connection_health(connection_health)
+            .with_history_backfill(history_backfill)
+            .with_activity_resume(activity_resume);
         orchestrator.sync_all(&client).await
     } else {
         let reporter = Arc::new(wealthfolio_connect::NoOpProgressReporter);
-        let orchestrator =
-            SyncOrchestrator::new(context.sync_service(), reporter, SyncConfig::default());
+        let orchestrator = SyncOrchestrator::new(context.sync_service(), reporter, config)
+            .with_connection_health(connection_health)
+            .with_history_backfill(history_backfill)
+            .with_activity_resume(activity_resume);
         orchestrator.sync_all(&client).await
+    };
+    if result.is_ok() {
+        spawn_history_backfills(context, app, client);
+    }
+    result.map_err(|e| e.to_string())
+}
+
+/// Runs deferred history backfills in the background once a sync has finished, so recent data
+/// is usable while older history is fetched. Skipped when a backfill run is already going.
+fn spawn_history_backfills(
+    context: &Arc<ServiceContext>,
+    app: Option<&AppHandle>,
+    client: ConnectApiClient,
+) {
+    let history_backfill = HistoryBackfillService::new(context.settings_service());
+    match history_backfill.runnable() {
+        Ok(jobs) if !jobs.is_empty() => {}
+        Ok(_) => return,
+        Err(err) => {
+            warn!("[Connect] Failed to read history backfills: {}", err);
+            return;
+        }
     }
+    let Some(guard) = acquire_broker_sync_guard(&context.history_backfill_running()) else {
+        debug!("[Connect] History backfill already running");
+        return;
+    };
+
+    let sync_service = context.sync_service();
+    let app_handle = app.cloned();
+    tauri::async_runtime::spawn(async move {
+        let _guard = guard;
+        let result = match app_handle {
+            Some(app_handle) => {
+                SyncOrchestrator::new(
+                    sync_service,
+                    Arc::new(TauriProgressReporter::new(app_handle)),
+                    broker_sync_config(),
+                )
+                .with_history_backfill(history_backfill)
+                .run_history_backfills(&client)
+                .await
+            }
+            None => {
+                SyncOrchestrator::new(
+                    sync_service,
+                    Arc::new(wealthfolio_connect::NoOpProgressReporter),
+                    broker_sync_config(),
+                )
+                .with_history_backfill(history_backfill)
+                .run_history_backfills(&client)
+                .await
+            }
+        };
+        match result {
+            Ok(jobs) => info!(
+                "[Connect] History backfill finished for {} account(s)",
+                jobs.len()
+            ),
+            Err(err) => warn!("[Connect] History backfill failed: {}", err),
+        }
+    });
+}
+
+/// Refetch one account's history from the cloud and reconcile it against local data.
+/// Runs under the broker sync guard, so it never overlaps a full sync.
+#[tauri::command]
+pub async fn resync_broker_account(
+    account_id: String,
+    app: AppHandle,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<AccountResyncResult, String> {
+    if !state
+        .connect_service()
+        .has_broker_sync()
+        .await
+        .map_err(|e| format!("Could not verify broker sync entitlement: {}", e))?
+    {
+        return Err("Plan does not include broker sync".to_string());
+    }
+    let Some(_guard) = try_acquire_broker_sync_guard(state.inner().as_ref()) else {
+        return Err("Broker sync already running".to_string());
+    };
+
+    info!("[Connect] Resyncing account {}", account_id);
+    let client = state.connect_service().get_api_client().await?;
+    let reporter = Arc::new(TauriProgressReporter::new(app));
+    let orchestrator = SyncOrchestrator::new(state.sync_service(), reporter, broker_sync_config());
+    orchestrator.resync_account(&client, &account_id).await
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -221,11 +356,43 @@ pub async fn list_broker_connections(
     debug!("Fetching broker connections from cloud API...");
 
     let client = state.connect_service().get_api_client().await?;
-    let connections = client.list_connections().await.map_err(|e| e.to_string())?;
+    let mut connections = client.list_connections().await.map_err(|e| e.to_string())?;
+    ConnectionNameService::new(state.settings_service())
+        .apply_names(&mut connections)
+        .map_err(|e| e.to_string())?;
+    broker_sync_config()
+        .connection_health(state.settings_service())
+        .apply_health(&mut connections)
+        .map_err(|e| e.to_string())?;
 
     Ok(connections)
 }
 
+/// Set or clear (`None` or blank) the local display name of a broker connection
+#[tauri::command]
+pub async fn set_connection_name(
+    connection_id: String,
+    name: Option<String>,
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+) -> Result<Option<String>, String> {
+    let name = ConnectionNameService::new(state.settings_service())
+        .set_connection_name(&connection_id, name.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    handle
+        .emit(
+            CONNECTION_RENAMED,
+            serde_json::json!({ "connectionId": connection_id, "name": name }),
+        )
+        .unwrap_or_else(|e| {
+            error!("Failed to emit connection:renamed event: {}", e);
+        });
+
+    Ok(name)
+}
+
 /// List broker accounts from the cloud API
 /// Returns the live account data including sync_enabled and owner info
 #[tauri::command]
@@ -292,7 +459,7 @@ pub async fn get_user_info(state: State<'_, Arc<ServiceContext>>) -> Result<User
 
     let client = state.connect_service().get_api_client().await?;
     match client.get_user_info().await {
-        Ok(user_info) => Ok(user_info),
+        Ok(user_info) => Ok(state.subscription_override().apply(user_info)),
         Err(e) => {
             error!("Failed to get user info: {}", e);
             Err(e.to_string())
@@ -300,6 +467,24 @@ pub async fn get_user_info(state: State<'_, Arc<ServiceContext>>) -> Result<User
     }
 }
 
+/// Simulate a subscription state for this session (never persisted or sent to the cloud).
+/// Requires `WEALTHFOLIO_DEBUG_ENDPOINTS=true`; `None` restores the real subscription.
+#[tauri::command]
+pub async fn set_fake_subscription(
+    state: Option<FakeSubscriptionState>,
+    context: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    context
+        .subscription_override()
+        .set(state)
+        .map_err(|e| e.to_string())?;
+    match state {
+        Some(fake) => warn!("Simulating {:?} subscription for this session", fake),
+        None => info!("Cleared simulated subscription"),
+    }
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Sync State and Import Run Commands
 // ─────────────────────────────────────────────────────────────────────────────
@@ -356,6 +541,56 @@ pub async fn get_data_import_runs(
     get_import_runs(run_type, limit, offset, state).await
 }
 
+/// Aggregate sync statistics for the dashboard, from local sync states and run history
+#[tauri::command]
+pub async fn get_sync_dashboard(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<wealthfolio_connect::SyncDashboard, String> {
+    let sync_in_progress = state
+        .broker_sync_running()
+        .load(std::sync::atomic::Ordering::Acquire);
+    state
+        .sync_service()
+        .get_sync_dashboard(sync_in_progress)
+        .map_err(|e| format!("Failed to get sync dashboard: {}", e))
+}
+
+/// Connection counts per health class for widgets, from local state only
+#[tauri::command]
+pub async fn get_connection_summary(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ConnectionSummary, String> {
+    let sync_states = state
+        .sync_service()
+        .get_all_sync_states()
+        .map_err(|e| format!("Failed to get broker sync states: {}", e))?;
+    broker_sync_config()
+        .connection_health(state.settings_service())
+        .get_connection_summary(&sync_states)
+        .map_err(|e| format!("Failed to get connection summary: {}", e))
+}
+
+/// Connections whose broker consent lapses soon, from local state only
+#[tauri::command]
+pub async fn get_expiring_connections(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<ConnectionExpiry>, String> {
+    broker_sync_config()
+        .connection_health(state.settings_service())
+        .get_expiring_connections(chrono::Utc::now())
+        .map_err(|e| format!("Failed to get expiring connections: {}", e))
+}
+
+/// Deferred history backfills and their progress, from local state only
+#[tauri::command]
+pub async fn get_history_backfills(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<Vec<HistoryBackfillJob>, String> {
+    HistoryBackfillService::new(state.settings_service())
+        .list()
+        .map_err(|e| format!("Failed to get history backfills: {}", e))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Broker Sync Profile Commands
 // ─────────────────────────────────────────────────────────────────────────────
@@ -13,7 +13,9 @@ use crate::commands::device_sync::{
 use crate::context::ServiceContext;
 
 // Re-export types for use in other modules
-pub use wealthfolio_device_sync::{EnableSyncResult, SyncState, SyncStateResult};
+pub use wealthfolio_device_sync::{
+    EnableSyncResult, RotateCredentialResult, SyncState, SyncStateResult,
+};
 
 /// Get the current device sync state.
 /// Returns the state machine status: FRESH, REGISTERED, READY, STALE, or RECOVERY.
@@ -129,3 +131,17 @@ pub async fn reinitialize_device_sync(
 
     Ok(result)
 }
+
+/// Rotate this device's credential without re-enrolling.
+/// The sync cursor and engine state are left as they are.
+#[tauri::command]
+pub async fn rotate_device_credential(
+    context: State<'_, Arc<ServiceContext>>,
+) -> Result<RotateCredentialResult, String> {
+    let token = context.connect_service().get_valid_access_token().await?;
+    context
+        .device_enroll_service()
+        .rotate_device_credential(&token)
+        .await
+        .map_err(|e| e.message)
+}
@@ -3,12 +3,15 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use tauri::Emitter;
 
 use crate::context::ServiceContext;
+use crate::events::SYNC_BOOTSTRAP_TRIGGERED;
 use wealthfolio_core::events::DomainEvent;
+use wealthfolio_core::settings::CloudAccessService;
 use wealthfolio_device_sync::engine::{
-    CredentialStore, OutboxStore, ReplayEvent, ReplayStore, SyncIdentity, SyncTransport,
-    TransportError,
+    CredentialStore, OutboxStore, ReplayApplyError, ReplayEvent, ReplayFailurePolicy, ReplayStore,
+    SyncBootstrapResult, SyncIdentity, SyncTransport, TransportError,
 };
 use wealthfolio_device_sync::{
     ReconcileReadyStateResponse, SyncPullResponse, SyncPushRequest, SyncPushResponse, SyncState,
@@ -23,6 +26,7 @@ fn transport_err_from_sync(e: wealthfolio_device_sync::DeviceSyncError) -> Trans
             wealthfolio_device_sync::DeviceSyncError::Api { details, .. } => details.clone(),
             _ => None,
         },
+        retry_after: e.retry_after_hint(),
     }
 }
 
@@ -32,6 +36,7 @@ fn transport_err_permanent(message: String) -> TransportError {
         retry_class: wealthfolio_device_sync::ApiRetryClass::Permanent,
         error_code: None,
         details: None,
+        retry_after: None,
     }
 }
 use wealthfolio_storage_sqlite::sync::SqliteSyncEngineDbPorts;
@@ -48,7 +53,8 @@ struct TauriEnginePorts {
 
 impl TauriEnginePorts {
     fn new(context: Arc<ServiceContext>) -> Self {
-        let db = SqliteSyncEngineDbPorts::new(context.app_sync_repository());
+        let db = SqliteSyncEngineDbPorts::new(context.app_sync_repository())
+            .with_replay_failure_policy(ReplayFailurePolicy::from_env());
         Self { context, db }
     }
 
@@ -81,6 +87,18 @@ impl OutboxStore for TauriEnginePorts {
             .await
     }
 
+    async fn mark_outbox_rejected(
+        &self,
+        event_id: String,
+        error_message: Option<String>,
+        error_code: Option<String>,
+        field_errors: Vec<wealthfolio_core::sync::SyncFieldError>,
+    ) -> Result<(), String> {
+        self.db
+            .mark_outbox_rejected(event_id, error_message, error_code, field_errors)
+            .await
+    }
+
     async fn mark_outbox_sent(&self, event_ids: Vec<String>) -> Result<(), String> {
         self.db.mark_outbox_sent(event_ids).await
     }
@@ -108,7 +126,7 @@ impl OutboxStore for TauriEnginePorts {
 
 #[async_trait]
 impl ReplayStore for TauriEnginePorts {
-    async fn acquire_cycle_lock(&self) -> Result<i64, String> {
+    async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String> {
         self.db.acquire_cycle_lock().await
     }
 
@@ -127,7 +145,7 @@ impl ReplayStore for TauriEnginePorts {
     async fn apply_remote_events_lww_batch(
         &self,
         events: Vec<ReplayEvent>,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, ReplayApplyError> {
         self.db.apply_remote_events_lww_batch(events).await
     }
 
@@ -135,6 +153,10 @@ impl ReplayStore for TauriEnginePorts {
         self.db.apply_remote_event_lww(event).await
     }
 
+    fn replay_failure_policy(&self) -> ReplayFailurePolicy {
+        self.db.replay_failure_policy()
+    }
+
     async fn mark_pull_completed(&self) -> Result<(), String> {
         self.db.mark_pull_completed().await
     }
@@ -178,6 +200,38 @@ impl ReplayStore for TauriEnginePorts {
         }
         Ok(())
     }
+
+    async fn restore_snapshot_for_stale_cursor(&self) -> Result<SyncBootstrapResult, String> {
+        let handle = super::registered_app_handle()
+            .ok_or_else(|| "App handle is not registered".to_string())?;
+        let result =
+            super::snapshot::restore_snapshot_for_stale_cursor(handle, &self.context).await?;
+        Ok(SyncBootstrapResult {
+            status: result.status,
+            message: result.message,
+            snapshot_id: result.snapshot_id,
+        })
+    }
+
+    async fn auto_bootstrap(&self, reason: &str) -> Result<SyncBootstrapResult, String> {
+        let handle = super::registered_app_handle()
+            .ok_or_else(|| "App handle is not registered".to_string())?;
+        handle
+            .emit(
+                SYNC_BOOTSTRAP_TRIGGERED,
+                serde_json::json!({ "reason": reason }),
+            )
+            .unwrap_or_else(|e| {
+                log::error!("Failed to emit {} event: {}", SYNC_BOOTSTRAP_TRIGGERED, e)
+            });
+        let result =
+            super::snapshot::sync_bootstrap_snapshot_if_needed(handle, &self.context).await?;
+        Ok(SyncBootstrapResult {
+            status: result.status,
+            message: result.message,
+            snapshot_id: result.snapshot_id,
+        })
+    }
 }
 
 #[async_trait]
@@ -326,6 +380,9 @@ pub(super) async fn run_sync_cycle(
 }
 
 pub async fn ensure_background_engine_started(context: Arc<ServiceContext>) -> Result<(), String> {
+    if CloudAccessService::new(context.settings_service()).is_disabled() {
+        return Ok(());
+    }
     let Some(identity) = get_sync_identity_from_store() else {
         return Ok(());
     };
@@ -15,17 +15,17 @@ use std::sync::{Arc, Mutex, OnceLock};
 use tauri::{AppHandle, State};
 
 use crate::context::ServiceContext;
-use crate::secret_store::KeyringSecretStore;
+use crate::secret_store::shared_secret_store;
 use wealthfolio_core::secrets::SecretStore;
 use wealthfolio_device_sync::engine as shared_sync_engine;
 use wealthfolio_device_sync::{
     ClaimPairingRequest, ClaimPairingResponse, CommitInitializeKeysRequest,
     CommitInitializeKeysResponse, CommitRotateKeysRequest, CommitRotateKeysResponse,
     CompletePairingRequest, CompletePairingResponse, ConfirmPairingRequest, ConfirmPairingResponse,
-    CreatePairingRequest, CreatePairingResponse, Device, DevicePlatform, DeviceSyncClient,
-    EnrollDeviceResponse, GetPairingResponse, InitializeKeysResult, PairingMessagesResponse,
-    RegisterDeviceRequest, ResetTeamSyncResponse, RotateKeysResponse, SuccessResponse,
-    UpdateDeviceRequest,
+    CreatePairingRequest, CreatePairingResponse, CursorExpiryForecast, Device, DevicePlatform,
+    DeviceSyncClient, EnrollDeviceResponse, GetPairingResponse, InitializeKeysResult,
+    LocalSyncTeardown, PairingMessagesResponse, RegisterDeviceRequest, ResetTeamSyncResponse,
+    RevokeDeviceResult, RotateKeysResponse, SuccessResponse, UpdateDeviceRequest,
 };
 use wealthfolio_storage_sqlite::sync::SyncTableRowCount;
 
@@ -57,7 +57,7 @@ pub(crate) struct SyncIdentity {
 pub(crate) fn get_sync_identity_from_store() -> Option<SyncIdentity> {
     const SYNC_IDENTITY_KEY: &str = "sync_identity";
 
-    match KeyringSecretStore.get_secret(SYNC_IDENTITY_KEY) {
+    match shared_secret_store().get_secret(SYNC_IDENTITY_KEY) {
         Ok(Some(json)) => match serde_json::from_str::<SyncIdentity>(&json) {
             Ok(identity) => {
                 if let Some(ref device_id) = identity.device_id {
@@ -139,6 +139,16 @@ pub(super) const SYNC_SOURCE_RESTORE_REQUIRED_CODE: &str = "SYNC_SOURCE_RESTORE_
 static MIN_SNAPSHOT_CREATED_AT: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
 static READY_STATE_OVERWRITE_APPROVALS: OnceLock<Mutex<HashMap<String, bool>>> = OnceLock::new();
 static PAIRING_OVERWRITE_APPROVALS: OnceLock<Mutex<HashMap<String, bool>>> = OnceLock::new();
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// Lets the background engine bootstrap on its own, which needs the handle to emit events.
+pub fn register_app_handle(handle: &AppHandle) {
+    let _ = APP_HANDLE.set(handle.clone());
+}
+
+pub(super) fn registered_app_handle() -> Option<AppHandle> {
+    APP_HANDLE.get().cloned()
+}
 
 fn min_snapshot_created_at_state() -> &'static Mutex<HashMap<String, String>> {
     MIN_SNAPSHOT_CREATED_AT.get_or_init(|| Mutex::new(HashMap::new()))
@@ -443,6 +453,30 @@ impl From<shared_sync_engine::SyncReadyReconcileResult> for SyncReconcileReadySt
     }
 }
 
+/// Tears down this device's sync when its own device is revoked.
+struct TauriSyncTeardown(Arc<ServiceContext>);
+
+#[async_trait]
+impl LocalSyncTeardown for TauriSyncTeardown {
+    async fn stop_engine(&self) -> Result<(), String> {
+        ensure_background_engine_stopped(Arc::clone(&self.0)).await
+    }
+
+    async fn reset_local_session(&self) -> Result<(), String> {
+        self.0
+            .app_sync_repository()
+            .reset_local_sync_session()
+            .await
+            .map_err(|e| e.to_string())?;
+        clear_min_snapshot_created_at_from_store();
+        self.0
+            .app_sync_repository()
+            .clear_all_min_snapshot_created_at()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
 struct TauriReadyReconcileRunner {
     handle: AppHandle,
     context: Arc<ServiceContext>,
@@ -740,6 +774,12 @@ pub async fn list_devices(
 ) -> Result<Vec<Device>, String> {
     info!("[DeviceSync] Listing devices (scope: {:?})...", scope);
 
+    if get_device_id_from_store().is_none() {
+        return Err(
+            "NOT_ENROLLED: This device is not enrolled in device sync. Enable sync first."
+                .to_string(),
+        );
+    }
     let token = get_access_token(state.inner()).await?;
 
     let devices = create_client()?
@@ -792,19 +832,22 @@ pub async fn delete_device(
         .map_err(|e| e.to_string())
 }
 
+/// Revoking this device also stops the engine and drops its enrollment and local sync session.
 #[tauri::command(rename_all = "camelCase")]
 pub async fn revoke_device(
     device_id: String,
     state: State<'_, Arc<ServiceContext>>,
-) -> Result<SuccessResponse, String> {
+) -> Result<RevokeDeviceResult, String> {
     info!("[DeviceSync] Revoking device: {}", device_id);
 
     let token = get_access_token(state.inner()).await?;
 
-    create_client()?
-        .revoke_device(&token, &device_id)
+    let local = TauriSyncTeardown(Arc::clone(state.inner()));
+    state
+        .device_enroll_service()
+        .revoke_device(&token, &device_id, &local)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.message)
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -1075,6 +1118,53 @@ pub async fn device_sync_engine_status(
     sync_engine_status(state).await
 }
 
+/// Local changes the cloud rejected as invalid, with the reason and a suggested fix.
+#[tauri::command]
+pub async fn get_sync_rejections(
+    state: State<'_, Arc<ServiceContext>>,
+    query: Option<wealthfolio_core::sync::SyncRejectionQuery>,
+) -> Result<wealthfolio_core::sync::SyncRejectionPage, String> {
+    state
+        .app_sync_repository()
+        .list_sync_rejections(&query.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Dismisses the given rejections at once; their changes are dropped from the outbox.
+#[tauri::command]
+pub async fn acknowledge_sync_rejections(
+    state: State<'_, Arc<ServiceContext>>,
+    event_ids: Vec<String>,
+) -> Result<usize, String> {
+    state
+        .app_sync_repository()
+        .acknowledge_sync_rejections(event_ids)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The cloud service does not advertise its event retention window, so desktop forecasts
+/// report it as unknown and only fill in the cursor age.
+#[tauri::command]
+pub async fn device_sync_cursor_expiry_forecast(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<CursorExpiryForecast, String> {
+    let status = state
+        .app_sync_repository()
+        .get_engine_status()
+        .map_err(|e| e.to_string())?;
+    let last_pull_at = status
+        .last_pull_at
+        .as_deref()
+        .map(wealthfolio_device_sync::parse_sync_datetime_to_utc)
+        .transpose()?;
+    Ok(wealthfolio_device_sync::forecast_cursor_expiry(
+        last_pull_at,
+        None,
+        chrono::Utc::now(),
+    ))
+}
+
 #[tauri::command]
 pub async fn device_sync_pairing_source_status(
     state: State<'_, Arc<ServiceContext>>,
@@ -1940,6 +2030,7 @@ mod tests {
             "PAIRING_ALREADY_APPROVED",
             "Pairing already approved",
             None,
+            None,
         );
 
         assert!(is_pairing_already_approved_error(&err));
@@ -1952,6 +2043,7 @@ mod tests {
             "PAIRING_INVALID_STATE",
             "Pairing cannot be approved from this state",
             None,
+            None,
         );
 
         assert!(!is_pairing_already_approved_error(&err));
@@ -12,7 +12,11 @@ use crate::context::ServiceContext;
 use crate::events::{emit_portfolio_trigger_recalculate, PortfolioRequestPayload};
 use wealthfolio_core::quotes::MarketSyncMode;
 use wealthfolio_core::sync::APP_SYNC_TABLES;
-use wealthfolio_device_sync::SyncState;
+use wealthfolio_device_sync::{
+    fetch_latest_verified_snapshot, SnapshotFetchError, SnapshotLatestResponse, SyncState,
+    MAX_SNAPSHOT_FETCH_ATTEMPTS, MAX_SNAPSHOT_MANIFEST_REFRESHES, SNAPSHOT_SCHEMA_VERSION,
+};
+use wealthfolio_storage_sqlite::sync::AppSyncRepository;
 
 use super::{
     clear_min_snapshot_created_at_from_store, create_client, encrypt_sync_payload,
@@ -454,48 +458,87 @@ pub async fn sync_bootstrap_snapshot_if_needed(
         }
     }
 
-    const LOCAL_SCHEMA_VERSION: i32 = 1;
-    if latest.schema_version > LOCAL_SCHEMA_VERSION {
+    if latest.schema_version > SNAPSHOT_SCHEMA_VERSION {
         return Err(format!(
             "Snapshot schema version {} is newer than local version {}. Please update the app.",
-            latest.schema_version, LOCAL_SCHEMA_VERSION
+            latest.schema_version, SNAPSHOT_SCHEMA_VERSION
         ));
     }
 
-    let snapshot_id = latest.snapshot_id.trim().to_string();
-    if snapshot_id.is_empty() {
+    if latest.snapshot_id.trim().is_empty() {
         return Err(
             "Latest snapshot metadata had empty snapshot_id. No valid snapshot available."
                 .to_string(),
         );
     }
-    let snapshot_oplog_seq = latest.oplog_seq;
-    let latest_checksum = if latest.checksum.trim().is_empty() {
-        None
-    } else {
-        Some(latest.checksum)
-    };
-    let latest_tables = if latest.covers_tables.is_empty() {
-        APP_SYNC_TABLES.iter().map(|v| v.to_string()).collect()
-    } else {
-        latest.covers_tables
-    };
 
-    let (headers, blob) = match client
-        .download_snapshot(&token, &device_id, &snapshot_id)
-        .await
+    // Verify the payload against the manifest before anything touches the local database;
+    // corrupt downloads are re-fetched a bounded number of times, and a snapshot pruned since
+    // the manifest was read is replaced by the manifest's latest one.
+    let (download_client, download_token, download_device_id) =
+        (&client, token.as_str(), device_id.as_str());
+    let (latest, headers, blob) = match fetch_latest_verified_snapshot(
+        latest,
+        MAX_SNAPSHOT_FETCH_ATTEMPTS,
+        MAX_SNAPSHOT_MANIFEST_REFRESHES,
+        || client.get_latest_snapshot_with_cursor_fallback(&token, &device_id),
+        move |snapshot_id| async move {
+            download_client
+                .download_snapshot(download_token, download_device_id, &snapshot_id)
+                .await
+        },
+    )
+    .await
     {
         Ok(value) => value,
-        Err(err) => {
-            if err.status_code() == Some(404) {
-                return Err(format!(
-                    "Snapshot {} is no longer available. No valid snapshot to download.",
-                    snapshot_id
-                ));
+        Err(SnapshotFetchError::NoSnapshotAvailable { snapshot_id }) => {
+            log::warn!(
+                "[DeviceSync] Snapshot {} was pruned and no other snapshot exists",
+                snapshot_id
+            );
+            if min_snapshot_created_at.is_some() {
+                return Ok(SyncBootstrapResult {
+                    status: "requested".to_string(),
+                    message: "Waiting for a snapshot generated after pairing confirmation"
+                        .to_string(),
+                    snapshot_id: None,
+                    cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                });
+            }
+            // Fall back to an events-only bootstrap when the cloud does not require a snapshot.
+            match classify_missing_snapshot_disposition(&client, &token, &device_id).await {
+                MissingSnapshotDisposition::CompleteNoBootstrap { message } => {
+                    sync_repo
+                        .reset_and_mark_bootstrap_complete(device_id, identity.key_version)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    clear_min_snapshot_created_at_from_store();
+                    return Ok(SyncBootstrapResult {
+                        status: "skipped".to_string(),
+                        message,
+                        snapshot_id: None,
+                        cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                    });
+                }
+                MissingSnapshotDisposition::WaitForSnapshot { message } => {
+                    return Ok(SyncBootstrapResult {
+                        status: "requested".to_string(),
+                        message,
+                        snapshot_id: None,
+                        cursor: Some(sync_repo.get_cursor().map_err(|e| e.to_string())?),
+                    });
+                }
             }
-            return Err(err.to_string());
         }
+        Err(err) => return Err(err.to_string()),
     };
+    // A replacement snapshot may have been written by a newer app version.
+    if latest.schema_version > SNAPSHOT_SCHEMA_VERSION {
+        return Err(format!(
+            "Snapshot schema version {} is newer than local version {}. Please update the app.",
+            latest.schema_version, SNAPSHOT_SCHEMA_VERSION
+        ));
+    }
     debug!(
         "[DeviceSync] Snapshot download response headers: schema_version={} tables={} checksum={} blob_size={}",
         headers.schema_version,
@@ -503,27 +546,54 @@ pub async fn sync_bootstrap_snapshot_if_needed(
         headers.checksum,
         blob.len()
     );
+    let (snapshot_id, snapshot_oplog_seq) =
+        restore_verified_snapshot(&sync_repo, &identity, &device_id, latest, blob, false).await?;
 
-    let actual_checksum = sha256_checksum(&blob);
-    if headers.checksum != actual_checksum {
-        return Err(format!(
-            "Snapshot checksum mismatch (download header): expected={}, got={}",
-            headers.checksum, actual_checksum
-        ));
-    }
-    if let Some(expected_checksum) = latest_checksum.as_ref() {
-        if expected_checksum != &actual_checksum {
-            return Err(format!(
-                "Snapshot checksum mismatch (latest metadata): expected={}, got={}",
-                expected_checksum, actual_checksum
-            ));
-        }
+    let payload = PortfolioRequestPayload::builder()
+        .account_ids(None)
+        .market_sync_mode(MarketSyncMode::Incremental { asset_ids: None })
+        .build();
+    emit_portfolio_trigger_recalculate(&handle, payload);
+
+    // Clear freshness gate from both in-memory and SQLite
+    clear_min_snapshot_created_at_from_store();
+    if let Err(err) = sync_repo.clear_min_snapshot_created_at(device_id).await {
+        log::warn!(
+            "[DeviceSync] Failed to clear freshness gate from SQLite: {}",
+            err
+        );
     }
 
-    let sqlite_image = decode_snapshot_sqlite_payload(blob, &identity)?;
-    let temp_snapshot_path =
-        std::env::temp_dir().join(format!("wf_snapshot_{}.db", Uuid::new_v4()));
-    std::fs::write(&temp_snapshot_path, sqlite_image)
+    Ok(SyncBootstrapResult {
+        status: "applied".to_string(),
+        message: "Snapshot bootstrap completed".to_string(),
+        snapshot_id: Some(snapshot_id),
+        cursor: Some(snapshot_oplog_seq),
+    })
+}
+
+/// Restores the tables a verified snapshot covers and moves the cursor to it. With
+/// `keep_unsynced`, local changes that were not pushed yet are kept on top of the snapshot.
+async fn restore_verified_snapshot(
+    sync_repo: &AppSyncRepository,
+    identity: &SyncIdentity,
+    device_id: &str,
+    latest: SnapshotLatestResponse,
+    blob: Vec<u8>,
+    keep_unsynced: bool,
+) -> Result<(String, i64), String> {
+    let snapshot_id = latest.snapshot_id.trim().to_string();
+    let snapshot_oplog_seq = latest.oplog_seq;
+    let latest_tables = if latest.covers_tables.is_empty() {
+        APP_SYNC_TABLES.iter().map(|v| v.to_string()).collect()
+    } else {
+        latest.covers_tables
+    };
+
+    let sqlite_image = decode_snapshot_sqlite_payload(blob, identity)?;
+    let temp_snapshot_path = sync_repo
+        .blob_storage()
+        .write_blob("wf_snapshot", &sqlite_image)
         .map_err(|e| format!("Failed to persist snapshot image: {}", e))?;
     let snapshot_path_str = temp_snapshot_path.to_string_lossy().to_string();
 
@@ -539,36 +609,94 @@ pub async fn sync_bootstrap_snapshot_if_needed(
             .collect();
     }
 
-    let restore_result = sync_repo
-        .restore_snapshot_tables_from_file(
-            snapshot_path_str,
-            tables_to_restore,
-            snapshot_oplog_seq,
-            device_id.clone(),
-            identity.key_version,
-        )
-        .await;
+    let restore_result = if keep_unsynced {
+        sync_repo
+            .restore_snapshot_tables_keeping_unsynced_from_file(
+                snapshot_path_str,
+                tables_to_restore,
+                snapshot_oplog_seq,
+                device_id.to_string(),
+                identity.key_version,
+            )
+            .await
+    } else {
+        sync_repo
+            .restore_snapshot_tables_from_file(
+                snapshot_path_str,
+                tables_to_restore,
+                snapshot_oplog_seq,
+                device_id.to_string(),
+                identity.key_version,
+            )
+            .await
+    };
     let _ = std::fs::remove_file(&temp_snapshot_path);
     restore_result.map_err(|e| e.to_string())?;
+    Ok((snapshot_id, snapshot_oplog_seq))
+}
 
+/// Resumes from the latest snapshot after the server reported the local cursor too old.
+/// Unlike [`sync_bootstrap_snapshot_if_needed`] it always restores and keeps local changes
+/// that were not pushed yet.
+pub async fn restore_snapshot_for_stale_cursor(
+    handle: AppHandle,
+    context: &Arc<ServiceContext>,
+) -> Result<SyncBootstrapResult, String> {
+    let identity = get_sync_identity_from_store()
+        .ok_or_else(|| "No sync identity configured. Please enable sync first.".to_string())?;
+    let device_id = identity
+        .device_id
+        .clone()
+        .ok_or_else(|| "No device ID configured".to_string())?;
+    let token = get_access_token(context).await?;
+
+    let client = create_client()?;
+    let Some(latest) = client
+        .get_latest_snapshot_with_cursor_fallback(&token, &device_id)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(SyncBootstrapResult {
+            status: "unavailable".to_string(),
+            message: "No snapshot is available to resume from".to_string(),
+            snapshot_id: None,
+            cursor: None,
+        });
+    };
+    let (download_client, download_token, download_device_id) =
+        (&client, token.as_str(), device_id.as_str());
+    let (latest, _headers, blob) = fetch_latest_verified_snapshot(
+        latest,
+        MAX_SNAPSHOT_FETCH_ATTEMPTS,
+        MAX_SNAPSHOT_MANIFEST_REFRESHES,
+        || client.get_latest_snapshot_with_cursor_fallback(&token, &device_id),
+        move |snapshot_id| async move {
+            download_client
+                .download_snapshot(download_token, download_device_id, &snapshot_id)
+                .await
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if latest.schema_version > SNAPSHOT_SCHEMA_VERSION {
+        return Err(format!(
+            "Snapshot schema version {} is newer than local version {}. Please update the app.",
+            latest.schema_version, SNAPSHOT_SCHEMA_VERSION
+        ));
+    }
+
+    let sync_repo = context.app_sync_repository();
+    let (snapshot_id, snapshot_oplog_seq) =
+        restore_verified_snapshot(&sync_repo, &identity, &device_id, latest, blob, true).await?;
     let payload = PortfolioRequestPayload::builder()
         .account_ids(None)
         .market_sync_mode(MarketSyncMode::Incremental { asset_ids: None })
         .build();
     emit_portfolio_trigger_recalculate(&handle, payload);
 
-    // Clear freshness gate from both in-memory and SQLite
-    clear_min_snapshot_created_at_from_store();
-    if let Err(err) = sync_repo.clear_min_snapshot_created_at(device_id).await {
-        log::warn!(
-            "[DeviceSync] Failed to clear freshness gate from SQLite: {}",
-            err
-        );
-    }
-
     Ok(SyncBootstrapResult {
         status: "applied".to_string(),
-        message: "Snapshot bootstrap completed".to_string(),
+        message: "Resumed from the latest snapshot".to_string(),
         snapshot_id: Some(snapshot_id),
         cursor: Some(snapshot_oplog_seq),
     })
@@ -708,7 +836,7 @@ pub async fn generate_snapshot_now_internal(
     );
     let upload_headers = wealthfolio_device_sync::SnapshotUploadHeaders {
         event_id: Some(Uuid::now_v7().to_string()),
-        schema_version: 1,
+        schema_version: SNAPSHOT_SCHEMA_VERSION,
         covers_tables: APP_SYNC_TABLES.iter().map(|v| v.to_string()).collect(),
         size_bytes: payload.len() as i64,
         checksum,
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use log::debug;
+use tauri::State;
+use wealthfolio_connect::{
+    DeviceSyncDiagnostics, DiagnosticsSources, FeatureDiagnostics, SyncDiagnostics,
+};
+
+use crate::context::ServiceContext;
+use crate::secret_store::shared_secret_store;
+use crate::services::{cloud_api_base_url, is_connect_sync_enabled, is_device_sync_enabled};
+
+#[cfg(feature = "device-sync")]
+fn device_sync_enrolled() -> bool {
+    crate::commands::device_sync::get_sync_identity_from_store()
+        .as_ref()
+        .is_some_and(crate::commands::device_sync::sync_identity_can_run_background)
+}
+
+#[cfg(not(feature = "device-sync"))]
+fn device_sync_enrolled() -> bool {
+    false
+}
+
+/// Redacted sync diagnostics for bug reports, in the same schema as the server's
+/// `GET /api/v1/diagnostics`.
+#[tauri::command]
+pub async fn get_diagnostics(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<SyncDiagnostics, String> {
+    debug!("Collecting sync diagnostics...");
+
+    let broker_sync_states = if is_connect_sync_enabled() {
+        state
+            .sync_service()
+            .get_all_sync_states()
+            .map_err(|e| format!("Failed to get broker sync states: {}", e))?
+    } else {
+        Vec::new()
+    };
+    let cloud_api_url = cloud_api_base_url();
+    let device_sync = DeviceSyncDiagnostics {
+        enrolled: device_sync_enrolled(),
+        engine_running: state.device_sync_runtime().is_background_running().await,
+    };
+    let secret_store = shared_secret_store();
+
+    Ok(SyncDiagnostics::collect(DiagnosticsSources {
+        runtime: "desktop",
+        app_version: env!("CARGO_PKG_VERSION"),
+        features: FeatureDiagnostics {
+            connect_sync: is_connect_sync_enabled(),
+            device_sync: is_device_sync_enabled(),
+        },
+        cloud_api_url: cloud_api_url.as_deref(),
+        secret_store: secret_store.as_ref(),
+        broker_sync_states: &broker_sync_states,
+        // The desktop app does not keep a run history; per-account results are in the states.
+        last_run: None,
+        device_sync,
+        ledger_validation: state.ledger_validation_service().last_summary(),
+    }))
+}
@@ -1,11 +1,5 @@
 use serde::Serialize;
-
-#[derive(Serialize)]
-pub struct PlatformCapabilities {
-    pub connect_sync: bool,
-    pub device_sync: bool,
-    pub cloud_sync: bool,
-}
+use wealthfolio_core::settings::PlatformCapabilities;
 
 #[derive(Serialize)]
 pub struct PlatformInfo {
@@ -19,20 +13,19 @@ pub struct PlatformInfo {
 
 #[tauri::command]
 pub fn get_platform() -> PlatformInfo {
-    let connect_sync = cfg!(feature = "connect-sync");
-    let device_sync = cfg!(feature = "device-sync");
+    let is_desktop = cfg!(not(any(target_os = "ios", target_os = "android")));
 
     PlatformInfo {
         os: std::env::consts::OS,
         arch: std::env::consts::ARCH,
-        is_mobile: cfg!(any(target_os = "ios", target_os = "android")),
-        is_desktop: cfg!(not(any(target_os = "ios", target_os = "android"))),
+        is_mobile: !is_desktop,
+        is_desktop,
         is_tauri: true,
-        capabilities: PlatformCapabilities {
-            connect_sync,
-            device_sync,
-            cloud_sync: connect_sync || device_sync,
-        },
+        capabilities: PlatformCapabilities::new(
+            cfg!(feature = "connect-sync"),
+            cfg!(feature = "device-sync"),
+            is_desktop,
+        ),
     }
 }
 
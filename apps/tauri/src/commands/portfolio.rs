@@ -19,9 +19,11 @@ use wealthfolio_core::{
         account_supports_portfolio_scope, account_supports_purpose, Account, AccountPurpose,
         TrackingMode,
     },
-    allocation::{AllocationHoldings, PortfolioAllocations},
+    allocation::{AllocationDimension, AllocationHoldings, AllocationSlice, PortfolioAllocations},
+    gains::HoldingGains,
     holdings::{Holding, HoldingListItem},
-    income::IncomeSummary,
+    income::{DividendSummary, DividendSummaryQuery, IncomeSummary},
+    ledger::LedgerValidationReport,
     lots::AssetLotView,
     performance::{
         calculate_performance_summary_batch_for_accounts, empty_performance_metrics,
@@ -70,6 +72,7 @@ impl AccountScopeInput {
     pub fn into_account_filter(self) -> Result<AccountScope, String> {
         match self.kind.as_str() {
             "all" => Ok(AccountScope::All),
+            "unassigned" => Ok(AccountScope::Unassigned),
             "account" => {
                 let id = self
                     .account_id
@@ -170,6 +173,22 @@ pub async fn recalculate_portfolio(handle: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Warms the analytics caches in the background; `analytics:ready` follows when done.
+#[tauri::command]
+pub async fn warm_analytics_caches(
+    handle: AppHandle,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<(), String> {
+    crate::listeners::spawn_analytics_warmup(&handle, &state);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_analytics_warmup(state: State<'_, Arc<ServiceContext>>) -> Result<(), String> {
+    state.analytics_warmup().cancel();
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn update_portfolio(handle: AppHandle) -> Result<(), String> {
     debug!("Emitting PORTFOLIO_TRIGGER_UPDATE event...");
@@ -213,7 +232,7 @@ async fn resolve_current_valuation_scope(
                 .map_err(|e| e.to_string())?
                 .account_ids
         }
-        AccountScope::All => resolved.account_ids.clone(),
+        AccountScope::All | AccountScope::Unassigned => resolved.account_ids.clone(),
     };
 
     Ok(ResolvedAccountScope {
@@ -388,6 +407,24 @@ pub async fn get_holdings_by_allocation(
     }
 }
 
+#[tauri::command]
+pub async fn get_allocation_breakdown(
+    state: State<'_, Arc<ServiceContext>>,
+    filter: AccountScopeInput,
+    dimension: String,
+) -> Result<Vec<AllocationSlice>, String> {
+    let dimension = AllocationDimension::parse(&dimension).map_err(|e| e.to_string())?;
+    let base_currency = state.get_base_currency();
+    let filter = filter.into_account_filter()?;
+    let resolved = resolve_scope(&filter, &state).await?;
+    let account_ids = holdings_account_ids(&state, &resolved.account_ids)?;
+    state
+        .allocation_service()
+        .get_allocation_breakdown(&account_ids, &base_currency, &resolved.scope_id, dimension)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_historical_valuations(
     state: State<'_, Arc<ServiceContext>>,
@@ -589,6 +626,81 @@ pub async fn get_income_summary(
         .map_err(|e| e.to_string())
 }
 
+/// Dividend totals in the base currency by symbol, account or month.
+#[tauri::command]
+pub async fn get_dividend_summary(
+    state: State<'_, Arc<ServiceContext>>,
+    query: DividendSummaryQuery,
+    filter: Option<AccountScopeInput>,
+) -> Result<DividendSummary, String> {
+    let account_ids: Vec<String> = if let Some(input) = filter {
+        let af = input.into_account_filter()?;
+        let resolved = resolve_scope(&af, &state).await?;
+        income_account_ids(&state, &resolved.account_ids)?
+    } else {
+        state
+            .account_service()
+            .get_active_accounts()
+            .map_err(|e| format!("Failed to fetch active accounts: {}", e))?
+            .into_iter()
+            .filter(|account| {
+                account_supports_purpose(&account.account_type, AccountPurpose::Income)
+            })
+            .map(|account| account.id)
+            .collect()
+    };
+    state
+        .income_service()
+        .get_dividend_summary(&query, Some(&account_ids))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_gains(
+    state: State<'_, Arc<ServiceContext>>,
+    account_id: Option<String>,
+) -> Result<Vec<HoldingGains>, String> {
+    debug!("Fetching holding gains...");
+    state
+        .gains_service()
+        .get_gains(account_id.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Read-only unless `fix` is set, in which case accounts whose holdings no longer match their
+/// activities get a full holdings recompute.
+#[tauri::command]
+pub async fn validate_activity_ledger(
+    state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
+    fix: Option<bool>,
+) -> Result<LedgerValidationReport, String> {
+    debug!("Validating activity ledger...");
+    let mut report = state
+        .ledger_validation_service()
+        .validate_ledger()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if fix.unwrap_or(false) {
+        let account_ids = report.fixable_account_ids();
+        if !account_ids.is_empty() {
+            info!(
+                "Recomputing holdings for {} account(s) after ledger validation",
+                account_ids.len()
+            );
+            let payload = PortfolioRequestPayload::builder()
+                .account_ids(Some(account_ids.clone()))
+                .market_sync_mode(MarketSyncMode::None)
+                .build();
+            emit_portfolio_trigger_recalculate(&handle, payload);
+            report.recalculated_account_ids = account_ids;
+        }
+    }
+    Ok(report)
+}
+
 #[tauri::command]
 pub async fn calculate_accounts_simple_performance(
     state: State<'_, Arc<ServiceContext>>,
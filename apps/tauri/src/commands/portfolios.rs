@@ -1,10 +1,18 @@
 use std::sync::Arc;
 
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 use crate::context::ServiceContext;
+use crate::events::PORTFOLIOS_CHANGED;
 use wealthfolio_core::portfolios::{NewPortfolio, PortfolioUpdate, PortfolioWithAccounts};
 
+fn emit_portfolios_changed(handle: &AppHandle, portfolio_id: &str) {
+    let payload = serde_json::json!({ "portfolioId": portfolio_id });
+    if let Err(e) = handle.emit(PORTFOLIOS_CHANGED, &payload) {
+        log::error!("Failed to emit {} event: {}", PORTFOLIOS_CHANGED, e);
+    }
+}
+
 #[tauri::command]
 pub async fn get_portfolios(
     state: State<'_, Arc<ServiceContext>>,
@@ -30,34 +38,43 @@ pub async fn get_portfolio(
 pub async fn create_portfolio(
     portfolio: NewPortfolio,
     state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
 ) -> Result<PortfolioWithAccounts, String> {
-    state
+    let created = state
         .portfolio_service()
         .create_portfolio(portfolio)
         .await
-        .map_err(|e| format!("Failed to create portfolio: {}", e))
+        .map_err(|e| format!("Failed to create portfolio: {}", e))?;
+    emit_portfolios_changed(&handle, &created.id);
+    Ok(created)
 }
 
 #[tauri::command]
 pub async fn update_portfolio_entry(
     portfolio: PortfolioUpdate,
     state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
 ) -> Result<PortfolioWithAccounts, String> {
-    state
+    let updated = state
         .portfolio_service()
         .update_portfolio(portfolio)
         .await
-        .map_err(|e| format!("Failed to update portfolio: {}", e))
+        .map_err(|e| format!("Failed to update portfolio: {}", e))?;
+    emit_portfolios_changed(&handle, &updated.id);
+    Ok(updated)
 }
 
 #[tauri::command]
 pub async fn delete_portfolio_entry(
     portfolio_id: String,
     state: State<'_, Arc<ServiceContext>>,
+    handle: AppHandle,
 ) -> Result<(), String> {
     state
         .portfolio_service()
         .delete_portfolio(&portfolio_id)
         .await
-        .map_err(|e| format!("Failed to delete portfolio: {}", e))
+        .map_err(|e| format!("Failed to delete portfolio: {}", e))?;
+    emit_portfolios_changed(&handle, &portfolio_id);
+    Ok(())
 }
@@ -1,13 +1,18 @@
 use std::sync::Arc;
 
 use crate::context::ServiceContext;
-use crate::events::{emit_portfolio_trigger_recalculate, PortfolioRequestPayload};
+use crate::events::{
+    emit_portfolio_trigger_recalculate, PortfolioRequestPayload, CLOUD_DISABLED, CLOUD_ENABLED,
+};
 use log::debug;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, State};
 use wealthfolio_core::fx::{ExchangeRate, NewExchangeRate};
 use wealthfolio_core::health::HealthServiceTrait;
 use wealthfolio_core::quotes::{MarketSyncMode, DATA_SOURCE_MANUAL};
-use wealthfolio_core::settings::{Settings, SettingsUpdate};
+use wealthfolio_core::settings::{
+    CloudAccessService, CloudAccessStatus, Settings, SettingsUpdate, SyncQuietHours,
+    SyncQuietHoursService, SyncQuietHoursStatus,
+};
 
 fn recalculate_mode_for_settings_change(
     base_currency_changed: bool,
@@ -129,6 +134,87 @@ pub async fn update_settings(
     Ok(updated_settings)
 }
 
+#[tauri::command]
+pub async fn get_sync_quiet_hours(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<SyncQuietHoursStatus, String> {
+    SyncQuietHoursService::new(state.settings_service())
+        .status(chrono::Utc::now())
+        .map_err(|e| format!("Failed to load sync quiet hours: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_sync_quiet_hours(
+    quiet_hours: Option<SyncQuietHours>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<SyncQuietHoursStatus, String> {
+    let service = SyncQuietHoursService::new(state.settings_service());
+    service
+        .set_quiet_hours(quiet_hours)
+        .await
+        .map_err(|e| e.to_string())?;
+    service
+        .status(chrono::Utc::now())
+        .map_err(|e| format!("Failed to load sync quiet hours: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_cloud_status(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<CloudAccessStatus, String> {
+    Ok(CloudAccessService::new(state.settings_service()).status())
+}
+
+#[tauri::command]
+pub async fn disable_cloud(
+    handle: AppHandle,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<CloudAccessStatus, String> {
+    let status = CloudAccessService::new(state.settings_service())
+        .set_disabled(true)
+        .await
+        .map_err(|e| e.to_string())?;
+    log::info!("Cloud access disabled");
+
+    #[cfg(feature = "device-sync")]
+    if let Err(err) =
+        crate::commands::device_sync::ensure_background_engine_stopped(Arc::clone(state.inner()))
+            .await
+    {
+        log::warn!("Failed to stop device sync engine: {}", err);
+    }
+
+    if let Err(e) = handle.emit(CLOUD_DISABLED, &status) {
+        log::error!("Failed to emit {} event: {}", CLOUD_DISABLED, e);
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn enable_cloud(
+    handle: AppHandle,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<CloudAccessStatus, String> {
+    let status = CloudAccessService::new(state.settings_service())
+        .set_disabled(false)
+        .await
+        .map_err(|e| e.to_string())?;
+    log::info!("Cloud access enabled");
+    if let Err(e) = handle.emit(CLOUD_ENABLED, &status) {
+        log::error!("Failed to emit {} event: {}", CLOUD_ENABLED, e);
+    }
+
+    #[cfg(feature = "device-sync")]
+    if let Err(err) =
+        crate::commands::device_sync::ensure_background_engine_started(Arc::clone(state.inner()))
+            .await
+    {
+        log::warn!("Failed to restart device sync engine: {}", err);
+    }
+
+    Ok(status)
+}
+
 #[tauri::command]
 pub async fn update_exchange_rate(
     rate: ExchangeRate,
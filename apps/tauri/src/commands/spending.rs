@@ -20,7 +20,7 @@ use wealthfolio_spending::cash_activities::{
 };
 use wealthfolio_spending::categorization_rules::{
     CategorizationRule, CategorizationRulesService, ImportPresetResult, NewCategorizationRule,
-    RemovePresetResult, RulePresetSummary, UpdateCategorizationRule,
+    RemovePresetResult, RulePresetSummary, RulePreview, UpdateCategorizationRule,
 };
 use wealthfolio_spending::events::{Event, EventType, NewEvent, NewEventType, UpdateEvent};
 use wealthfolio_spending::insight::{SpendingInsight, SpendingInsightRequest};
@@ -347,6 +347,26 @@ pub async fn rerun_categorization_rules(
         .map_err(|e| format!("Failed to re-run rules: {}", e))
 }
 
+#[tauri::command]
+pub async fn preview_categorization_rules(
+    only_uncategorized: bool,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<RulePreview, String> {
+    let s = state
+        .spending_settings_service()
+        .get()
+        .await
+        .map_err(|e| format!("Failed to load spending settings: {}", e))?;
+    if !s.enabled {
+        return Ok(RulePreview::default());
+    }
+    state
+        .categorization_rules_service()
+        .preview_rerun(&s.account_ids, only_uncategorized)
+        .await
+        .map_err(|e| format!("Failed to preview rules: {}", e))
+}
+
 #[tauri::command]
 pub async fn list_rule_presets(
     state: State<'_, Arc<ServiceContext>>,
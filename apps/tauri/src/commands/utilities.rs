@@ -1,6 +1,7 @@
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
 use chrono;
 use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -14,11 +15,13 @@ use uuid::Uuid;
 use wealthfolio_core::{
     activities::Sort,
     exports::{
-        export_file_name, format_holding_list_records, format_records, ExportDataType,
-        ExportFileFormat,
+        export_file_name, format_holding_list_records, format_records, holdings_snapshot_csv,
+        ExportDataType, ExportFileFormat,
     },
-    portfolio::holdings::HoldingListItem,
+    portability::{build_archive, diff_archives, ArchiveDiff},
+    portfolio::holdings::{Holding, HoldingListItem},
     portfolios::AccountScope,
+    share_snapshot::{build_share_snapshot, ShareSnapshot, ShareSnapshotOptions},
 };
 use wealthfolio_storage_sqlite::db;
 
@@ -253,6 +256,34 @@ fn write_pending_export_content(
     })
 }
 
+/// Current holdings across every account, or `None` when there are no accounts to value.
+async fn load_export_holdings(state: &ServiceContext) -> Result<Option<Vec<Holding>>, String> {
+    let base_currency = state.get_base_currency();
+    let resolved = state
+        .portfolio_service()
+        .resolve_account_scope(&AccountScope::All, &base_currency)
+        .map_err(|e| format!("Failed to resolve portfolio scope: {}", e))?;
+    let account_ids = holdings_account_ids(state, &resolved.account_ids)?;
+    if account_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let holdings = if account_ids.len() == 1 {
+        state
+            .holdings_service()
+            .get_holdings(&account_ids[0], &base_currency)
+            .await
+            .map_err(|e| format!("Failed to load holdings for export: {}", e))?
+    } else {
+        state
+            .holdings_service()
+            .get_holdings_for_accounts(&account_ids, &base_currency, &resolved.scope_id)
+            .await
+            .map_err(|e| format!("Failed to load holdings for export: {}", e))?
+    };
+    Ok(Some(holdings))
+}
+
 async fn build_data_export_content(
     state: &ServiceContext,
     data_type: ExportDataType,
@@ -289,28 +320,8 @@ async fn build_data_export_content(
             format_records(&records, format).map_err(|e| e.to_string())
         }
         ExportDataType::Holdings => {
-            let base_currency = state.get_base_currency();
-            let resolved = state
-                .portfolio_service()
-                .resolve_account_scope(&AccountScope::All, &base_currency)
-                .map_err(|e| format!("Failed to resolve portfolio scope: {}", e))?;
-            let account_ids = holdings_account_ids(state, &resolved.account_ids)?;
-            if account_ids.is_empty() {
+            let Some(holdings) = load_export_holdings(state).await? else {
                 return Ok(None);
-            }
-
-            let holdings = if account_ids.len() == 1 {
-                state
-                    .holdings_service()
-                    .get_holdings(&account_ids[0], &base_currency)
-                    .await
-                    .map_err(|e| format!("Failed to load holdings for export: {}", e))?
-            } else {
-                state
-                    .holdings_service()
-                    .get_holdings_for_accounts(&account_ids, &base_currency, &resolved.scope_id)
-                    .await
-                    .map_err(|e| format!("Failed to load holdings for export: {}", e))?
             };
             let records = holdings
                 .into_iter()
@@ -439,6 +450,99 @@ pub async fn export_data_file(
     }
 }
 
+#[tauri::command]
+pub async fn export_holdings_csv(state: State<'_, Arc<ServiceContext>>) -> Result<String, String> {
+    let holdings = load_export_holdings(state.inner().as_ref())
+        .await?
+        .unwrap_or_default();
+    let asset_ids = holdings
+        .iter()
+        .filter_map(|holding| holding.instrument.as_ref())
+        .map(|instrument| instrument.id.clone())
+        .collect::<Vec<_>>();
+    let stale_asset_ids = state
+        .quote_service()
+        .get_latest_quotes_snapshot(&asset_ids)
+        .map_err(|e| format!("Failed to load quotes for export: {}", e))?
+        .into_iter()
+        .filter(|(_, snapshot)| snapshot.is_stale)
+        .map(|(asset_id, _)| asset_id)
+        .collect::<HashSet<_>>();
+
+    holdings_snapshot_csv(&holdings, &stale_asset_ids, chrono::Utc::now())
+        .map_err(|e| e.to_string())
+}
+
+/// Read-only snapshot for sharing. Account details are left out unless opted in.
+#[tauri::command]
+pub async fn export_portfolio_snapshot(
+    include_account_details: Option<bool>,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<ShareSnapshot, String> {
+    let options = ShareSnapshotOptions {
+        include_account_details: include_account_details.unwrap_or(false),
+    };
+    let holdings = load_export_holdings(state.inner().as_ref())
+        .await?
+        .unwrap_or_default();
+    let accounts = if options.include_account_details {
+        state
+            .account_service()
+            .get_non_archived_accounts()
+            .map_err(|e| format!("Failed to load accounts for export: {}", e))?
+    } else {
+        Vec::new()
+    };
+
+    Ok(build_share_snapshot(
+        &holdings,
+        &accounts,
+        &state.get_base_currency(),
+        options,
+        chrono::Utc::now(),
+    ))
+}
+
+#[tauri::command]
+pub async fn export_data_archive(
+    app_handle: AppHandle,
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<DataExportResult, String> {
+    let archive = build_archive(
+        state.account_service().as_ref(),
+        state.activity_service().as_ref(),
+        state.goal_service().as_ref(),
+        Some(app_handle.package_info().version.to_string()),
+    )
+    .map_err(|e| e.to_string())?;
+    let content = archive.to_bytes().map_err(|e| e.to_string())?;
+    let filename = format!(
+        "wealthfolio_archive_{}.json",
+        chrono::Local::now().date_naive().format("%Y-%m-%d")
+    );
+
+    #[cfg(any(target_os = "ios", target_os = "android"))]
+    {
+        let pending_export = write_pending_export_content(&app_handle, &filename, &content)?;
+        Ok(DataExportResult::pending(pending_export))
+    }
+
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    {
+        if save_content_with_dialog(&app_handle, &filename, &content)? {
+            Ok(DataExportResult::saved(filename))
+        } else {
+            Ok(DataExportResult::canceled())
+        }
+    }
+}
+
+/// Compares two archive files' contents, `left` being the older export.
+#[tauri::command]
+pub async fn diff_data_archives(left: String, right: String) -> Result<ArchiveDiff, String> {
+    diff_archives(left.as_bytes(), right.as_bytes()).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn open_external_url(app_handle: AppHandle, url: String) -> Result<(), String> {
     let url = url.trim();
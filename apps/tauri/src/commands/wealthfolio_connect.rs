@@ -5,10 +5,11 @@ use crate::commands::brokers_sync::{
 #[cfg(feature = "device-sync")]
 use crate::commands::device_sync::{
     clear_min_snapshot_created_at_from_store, ensure_background_engine_started,
-    get_sync_identity_from_store, sync_identity_can_run_background,
+    ensure_background_engine_stopped, get_sync_identity_from_store,
+    sync_identity_can_run_background,
 };
 use crate::context::ServiceContext;
-use crate::secret_store::KeyringSecretStore;
+use crate::secret_store::shared_secret_store;
 use log::{debug, error};
 use serde::Serialize;
 use std::future::Future;
@@ -19,7 +20,8 @@ use wealthfolio_connect::{
     prepare_post_login_broker_bootstrap, BrokerApiClient, PostLoginBrokerBootstrapDecision,
 };
 use wealthfolio_connect::{
-    PostLoginBootstrapReason, PostLoginBootstrapResult, PostLoginBootstrapSyncResult,
+    store_cloud_session, PostLoginBootstrapReason, PostLoginBootstrapResult,
+    PostLoginBootstrapSyncResult, SignOutResult,
 };
 use wealthfolio_core::secrets::SecretStore;
 #[cfg(feature = "device-sync")]
@@ -82,17 +84,15 @@ pub async fn store_sync_session(
 ) -> Result<(), String> {
     match refresh_token.as_deref().map(str::trim) {
         Some(token) if !token.is_empty() => {
-            if let Err(e) = KeyringSecretStore.set_secret(SYNC_REFRESH_TOKEN_KEY, token) {
-                error!("Failed to store refresh token in keyring: {}", e);
-                return Err(format!("Failed to store refresh token: {}", e));
+            if let Err(e) = store_cloud_session(shared_secret_store().as_ref(), token) {
+                error!("Failed to store refresh token: {}", e);
+                return Err(e.to_string());
             }
-            // Best-effort cleanup for legacy versions that stored access tokens at rest.
-            let _ = KeyringSecretStore.delete_secret(SYNC_ACCESS_TOKEN_KEY);
             debug!("Refresh token stored successfully");
         }
         _ => {
-            if let Err(e) = KeyringSecretStore.delete_secret(SYNC_REFRESH_TOKEN_KEY) {
-                error!("Failed to delete refresh token from keyring: {}", e);
+            if let Err(e) = shared_secret_store().delete_secret(SYNC_REFRESH_TOKEN_KEY) {
+                error!("Failed to delete refresh token: {}", e);
                 // Don't fail the whole operation if we can't delete
             }
         }
@@ -131,7 +131,7 @@ async fn run_post_login_broker_bootstrap(
         move || async move {
             entitlement_context
                 .connect_service()
-                .has_broker_sync()
+                .refresh_subscription()
                 .await
         },
         move || async move {
@@ -238,13 +238,13 @@ async fn run_post_login_device_bootstrap(
 #[tauri::command]
 pub async fn clear_sync_session(state: State<'_, Arc<ServiceContext>>) -> Result<(), String> {
     // Best-effort cleanup for legacy installs that persisted the access token.
-    let _ = KeyringSecretStore.delete_secret(SYNC_ACCESS_TOKEN_KEY);
-    let refresh_result = KeyringSecretStore.delete_secret(SYNC_REFRESH_TOKEN_KEY);
+    let _ = shared_secret_store().delete_secret(SYNC_ACCESS_TOKEN_KEY);
+    let refresh_result = shared_secret_store().delete_secret(SYNC_REFRESH_TOKEN_KEY);
 
     // Report refresh-token errors but don't fail on legacy access-token cleanup.
     let mut errors = Vec::new();
     if let Err(e) = refresh_result {
-        error!("Failed to delete refresh token from keyring: {}", e);
+        error!("Failed to delete refresh token: {}", e);
         errors.push(format!("refresh_token: {}", e));
     }
 
@@ -257,7 +257,7 @@ pub async fn clear_sync_session(state: State<'_, Arc<ServiceContext>>) -> Result
         .await;
 
     if errors.is_empty() {
-        debug!("Sync session cleared from keyring");
+        debug!("Sync session cleared from the secret store");
         Ok(())
     } else {
         Err(format!(
@@ -267,6 +267,40 @@ pub async fn clear_sync_session(state: State<'_, Arc<ServiceContext>>) -> Result
     }
 }
 
+/// Signs out everywhere: revokes the cloud session on every device, then deletes all cloud
+/// secrets and resets device sync to FRESH. A failed revoke is logged; the local sign-out
+/// still happens.
+#[tauri::command]
+pub async fn sign_out_everywhere(
+    state: State<'_, Arc<ServiceContext>>,
+) -> Result<SignOutResult, String> {
+    #[cfg(feature = "device-sync")]
+    {
+        if let Err(e) = ensure_background_engine_stopped(Arc::clone(state.inner())).await {
+            log::warn!("Failed to stop device sync engine: {}", e);
+        }
+        if let Err(e) = state.device_enroll_service().clear_sync_data() {
+            log::warn!("Failed to reset device sync state: {}", e.message);
+        }
+    }
+
+    let result = state.connect_service().sign_out().await?;
+
+    let _ = state.app_sync_repository().reset_local_sync_session().await;
+    #[cfg(feature = "device-sync")]
+    clear_min_snapshot_created_at_from_store();
+    let _ = state
+        .app_sync_repository()
+        .clear_all_min_snapshot_created_at()
+        .await;
+
+    debug!(
+        "Signed out of Connect (cloud session revoked: {})",
+        result.remote_revoked
+    );
+    Ok(result)
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RestoreSyncSessionResponse {
@@ -280,7 +314,7 @@ pub async fn restore_sync_session(
 ) -> Result<RestoreSyncSessionResponse, String> {
     let access_token = state.connect_service().get_valid_access_token().await?;
 
-    let refresh_token = KeyringSecretStore
+    let refresh_token = shared_secret_store()
         .get_secret(SYNC_REFRESH_TOKEN_KEY)
         .map_err(|e| format!("Failed to read refresh token: {}", e))?
         .ok_or_else(|| "No sync session configured".to_string())?;
@@ -313,6 +347,9 @@ mod tests {
             disabled_date: None,
             updated_at: None,
             name: None,
+            consent_expires_at: None,
+            custom_name: None,
+            health: None,
         }
     }
 
@@ -1,18 +1,19 @@
 use super::ai_environment::TauriAiEnvironment;
 use super::registry::ServiceContext;
 use crate::domain_events::TauriDomainEventSink;
-use crate::secret_store::shared_secret_store;
+use crate::secret_store::init_secret_store;
 use crate::services::ConnectService;
-use log::{error, warn};
+use log::{error, info, warn};
 use std::sync::{Arc, RwLock};
 use tokio::sync::mpsc;
 use wealthfolio_ai::{AiProviderService, ChatConfig, ChatService};
 use wealthfolio_connect::{
-    BrokerSyncService, CoreImportRunRepositoryAdapter, ImportRunRepositoryTrait,
+    BrokerSyncService, CoreImportRunRepositoryAdapter, HistoryBackfillService,
+    ImportRunRepositoryTrait, SubscriptionOverride,
 };
 use wealthfolio_core::{
-    accounts::AccountService,
-    activities::ActivityService,
+    accounts::{AccountMergeService, AccountService},
+    activities::{ActivityService, CurrencyMismatchPolicy, CURRENCY_MISMATCH_POLICY_SETTING_KEY},
     assets::{AlternativeAssetService, AssetClassificationService, AssetService},
     events::DomainEvent,
     fx::{FxService, FxServiceTrait},
@@ -22,15 +23,19 @@ use wealthfolio_core::{
     portfolio::{
         allocation::AllocationService,
         allocation_targets::{AllocationTargetService, DriftService, RebalanceService},
+        analytics_warmup::AnalyticsWarmupService,
+        gains::GainsService,
         holdings::{HoldingsService, HoldingsValuationService},
         income::IncomeService,
+        ledger::LedgerValidationService,
         net_worth::NetWorthService,
         performance::PerformanceService,
-        snapshot::SnapshotService,
+        snapshot::{HoldingsCalculator, SnapshotService},
         valuation::ValuationService,
     },
     portfolios::PortfolioService,
     quotes::{QuoteService, QuoteServiceTrait},
+    secrets::{migrate_secret_keys, SECRET_KEY_MIGRATIONS},
     settings::{SettingsRepositoryTrait, SettingsService, SettingsServiceTrait},
     taxonomies::TaxonomyService,
 };
@@ -67,6 +72,15 @@ pub struct ContextInitResult {
 pub async fn initialize_context(
     app_data_dir: &str,
 ) -> Result<ContextInitResult, Box<dyn std::error::Error>> {
+    // Fail before anything can send a token to a misconfigured plaintext cloud URL
+    crate::services::validate_cloud_api_base_url().map_err(|e| {
+        error!("{}", e);
+        e
+    })?;
+    if let Some(url) = crate::services::cloud_api_base_url() {
+        info!("Cloud API base URL: {}", url);
+    }
+
     let db_path = db::init(app_data_dir)?;
     db::run_migrations(&db_path)?;
 
@@ -100,7 +114,10 @@ pub async fn initialize_context(
         pool.clone(),
         writer.clone(),
     ));
-    let app_sync_repository = Arc::new(AppSyncRepository::new(pool.clone(), writer.clone()));
+    let sync_blob_storage = wealthfolio_device_sync::SyncBlobStorage::from_env()?;
+    let app_sync_repository = Arc::new(
+        AppSyncRepository::new(pool.clone(), writer.clone()).with_blob_storage(sync_blob_storage),
+    );
     let valuation_repository = Arc::new(ValuationRepository::new(pool.clone(), writer.clone()));
     let platform_repository = Arc::new(PlatformRepository::new(pool.clone(), writer.clone()));
     let broker_sync_state_repository =
@@ -174,7 +191,11 @@ pub async fn initialize_context(
     let timezone = Arc::new(RwLock::new(settings.timezone.clone()));
     let instance_id = Arc::new(settings.instance_id.clone());
 
-    let secret_store = shared_secret_store();
+    let secret_store = init_secret_store(app_data_dir).map_err(|e| {
+        error!("Failed to initialize the secret store: {}", e);
+        e
+    })?;
+    migrate_secret_keys(secret_store.as_ref(), SECRET_KEY_MIGRATIONS);
 
     // Custom provider repository
     let custom_provider_repository = Arc::new(
@@ -238,6 +259,12 @@ pub async fn initialize_context(
         asset_repository.clone(),
         quote_sync_state_repository.clone(),
     ));
+    let account_merge_service = Arc::new(AccountMergeService::new(
+        account_repository.clone(),
+        activity_repository.clone(),
+        account_repository.clone(),
+        domain_event_sink.clone(),
+    ));
 
     // Spending: events + event_types
     let event_types_repo: Arc<dyn wealthfolio_spending::events::EventTypesRepositoryTrait> =
@@ -359,6 +386,10 @@ pub async fn initialize_context(
         import_run_repository.clone(),
     ));
 
+    // Read once at startup, like the server's WF_ACTIVITY_CURRENCY_MISMATCH_POLICY.
+    let currency_mismatch_policy = settings_service
+        .get_setting_value(CURRENCY_MISMATCH_POLICY_SETTING_KEY)?
+        .and_then(|value| CurrencyMismatchPolicy::parse(&value));
     let activity_service = Arc::new(
         ActivityService::with_import_run_repository(
             activity_repository.clone(),
@@ -368,7 +399,9 @@ pub async fn initialize_context(
             quote_service.clone(),
             core_import_run_repository,
         )
-        .with_event_sink(domain_event_sink.clone()),
+        .with_event_sink(domain_event_sink.clone())
+        .with_symbol_normalization(settings_service.clone())
+        .with_currency_mismatch_policy(currency_mismatch_policy),
     );
     let goal_service = Arc::new(GoalService::new(goal_repo.clone(), account_service.clone()));
     let limits_service = Arc::new(ContributionLimitService::new_with_timezone(
@@ -399,6 +432,18 @@ pub async fn initialize_context(
         .with_lot_repository(lots_repository.clone()),
     );
 
+    let ledger_validation_service = Arc::new(LedgerValidationService::new(
+        account_service.clone(),
+        activity_repository.clone(),
+        snapshot_service.clone(),
+        HoldingsCalculator::new_with_timezone(
+            fx_service.clone(),
+            base_currency.clone(),
+            timezone.clone(),
+            asset_repository.clone(),
+        ),
+    ));
+
     let holdings_valuation_service = Arc::new(HoldingsValuationService::new_with_timezone(
         fx_service.clone(),
         quote_service.clone(),
@@ -445,6 +490,21 @@ pub async fn initialize_context(
         AllocationService::new(holdings_service.clone(), taxonomy_service.clone())
             .with_account_service(account_service.clone()),
     );
+    let gains_service = Arc::new(
+        GainsService::new(
+            account_service.clone(),
+            holdings_service.clone(),
+            base_currency.clone(),
+        )
+        .with_lot_repository(lots_repository.clone())
+        .with_asset_service(asset_service.clone()),
+    );
+    let analytics_warmup = Arc::new(AnalyticsWarmupService::new(
+        portfolio_service.clone(),
+        allocation_service.clone(),
+        gains_service.clone(),
+        valuation_service.clone(),
+    ));
 
     let allocation_target_repository = Arc::new(AllocationTargetRepository::new(
         pool.clone(),
@@ -509,7 +569,10 @@ pub async fn initialize_context(
         .with_quote_store(market_data_repo.clone()),
     );
 
-    let connect_service = Arc::new(ConnectService::new(secret_store.clone()));
+    let connect_service = Arc::new(ConnectService::new(
+        secret_store.clone(),
+        settings_service.clone(),
+    ));
 
     // AI provider service - catalog is embedded at compile time
     let ai_catalog_json = include_str!("../../../../crates/ai/src/ai_providers.json");
@@ -574,6 +637,7 @@ pub async fn initialize_context(
     ));
     let device_sync_runtime = Arc::new(DeviceSyncRuntimeState::new());
     let broker_sync_running = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let subscription_override = Arc::new(SubscriptionOverride::from_env());
     let now = chrono::Utc::now();
     if let Err(err) = app_sync_repository
         .prune_sync_outbox(
@@ -585,6 +649,14 @@ pub async fn initialize_context(
         warn!("Failed to prune local sync outbox: {}", err);
     }
 
+    // A backfill is saved as running before its fetch; one cut off by a restart is requeued.
+    if let Err(err) = HistoryBackfillService::new(settings_service.clone())
+        .requeue_interrupted()
+        .await
+    {
+        warn!("Failed to requeue interrupted history backfills: {}", err);
+    }
+
     Ok(ContextInitResult {
         context: ServiceContext {
             base_currency,
@@ -593,6 +665,7 @@ pub async fn initialize_context(
             domain_event_sink,
             settings_service,
             account_service,
+            account_merge_service,
             activity_service,
             asset_service,
             goal_service,
@@ -601,6 +674,9 @@ pub async fn initialize_context(
             fx_service,
             performance_service,
             income_service,
+            gains_service,
+            analytics_warmup,
+            ledger_validation_service,
             snapshot_service,
             snapshot_repository,
             lots_repository,
@@ -624,6 +700,8 @@ pub async fn initialize_context(
             device_enroll_service,
             device_sync_runtime,
             broker_sync_running,
+            history_backfill_running: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            subscription_override,
             health_service,
             custom_provider_service,
             portfolio_service,
@@ -1,6 +1,6 @@
 use std::sync::{atomic::AtomicBool, Arc, RwLock};
 use wealthfolio_ai::{AiProviderServiceTrait, ChatService};
-use wealthfolio_connect::BrokerSyncServiceTrait;
+use wealthfolio_connect::{BrokerSyncServiceTrait, SubscriptionOverride};
 use wealthfolio_core::{
     self, accounts, activities,
     assets::{self, AlternativeAssetServiceTrait},
@@ -43,6 +43,7 @@ pub struct ServiceContext {
     pub settings_service: Arc<dyn settings::SettingsServiceTrait>,
     pub activity_service: Arc<dyn activities::ActivityServiceTrait>,
     pub account_service: Arc<dyn accounts::AccountServiceTrait>,
+    pub account_merge_service: Arc<dyn accounts::AccountMergeServiceTrait>,
     pub goal_service: Arc<dyn goals::GoalServiceTrait>,
     pub asset_service: Arc<dyn assets::AssetServiceTrait>,
     pub quote_service: Arc<dyn quotes::QuoteServiceTrait>,
@@ -50,6 +51,9 @@ pub struct ServiceContext {
     pub fx_service: Arc<dyn fx::FxServiceTrait>,
     pub performance_service: Arc<dyn portfolio::performance::PerformanceServiceTrait>,
     pub income_service: Arc<dyn portfolio::income::IncomeServiceTrait>,
+    pub gains_service: Arc<dyn portfolio::gains::GainsServiceTrait>,
+    pub analytics_warmup: Arc<portfolio::analytics_warmup::AnalyticsWarmupService>,
+    pub ledger_validation_service: Arc<dyn portfolio::ledger::LedgerValidationServiceTrait>,
     pub snapshot_service: Arc<dyn portfolio::snapshot::SnapshotServiceTrait>,
     pub snapshot_repository: Arc<SnapshotRepository>,
     pub lots_repository: Arc<dyn LotRepositoryTrait>,
@@ -74,6 +78,9 @@ pub struct ServiceContext {
     pub device_enroll_service: Arc<DeviceEnrollService>,
     pub device_sync_runtime: Arc<DeviceSyncRuntimeState>,
     pub broker_sync_running: Arc<AtomicBool>,
+    /// Held while deferred broker history backfills run; separate from regular syncs.
+    pub history_backfill_running: Arc<AtomicBool>,
+    pub subscription_override: Arc<SubscriptionOverride>,
     pub health_service: Arc<health::HealthService>,
     pub custom_provider_service: Arc<wealthfolio_core::custom_provider::CustomProviderService>,
     pub portfolio_service: Arc<dyn portfolios::PortfolioServiceTrait>,
@@ -139,6 +146,10 @@ impl ServiceContext {
         Arc::clone(&self.account_service)
     }
 
+    pub fn account_merge_service(&self) -> Arc<dyn accounts::AccountMergeServiceTrait> {
+        Arc::clone(&self.account_merge_service)
+    }
+
     pub fn activity_service(&self) -> Arc<dyn activities::ActivityServiceTrait> {
         Arc::clone(&self.activity_service)
     }
@@ -171,6 +182,20 @@ impl ServiceContext {
         Arc::clone(&self.income_service)
     }
 
+    pub fn gains_service(&self) -> Arc<dyn portfolio::gains::GainsServiceTrait> {
+        Arc::clone(&self.gains_service)
+    }
+
+    pub fn analytics_warmup(&self) -> Arc<portfolio::analytics_warmup::AnalyticsWarmupService> {
+        Arc::clone(&self.analytics_warmup)
+    }
+
+    pub fn ledger_validation_service(
+        &self,
+    ) -> Arc<dyn portfolio::ledger::LedgerValidationServiceTrait> {
+        Arc::clone(&self.ledger_validation_service)
+    }
+
     pub fn snapshot_service(&self) -> Arc<dyn portfolio::snapshot::SnapshotServiceTrait> {
         Arc::clone(&self.snapshot_service)
     }
@@ -267,6 +292,14 @@ impl ServiceContext {
         Arc::clone(&self.broker_sync_running)
     }
 
+    pub fn history_backfill_running(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.history_backfill_running)
+    }
+
+    pub fn subscription_override(&self) -> Arc<SubscriptionOverride> {
+        Arc::clone(&self.subscription_override)
+    }
+
     pub fn health_service(&self) -> Arc<health::HealthService> {
         Arc::clone(&self.health_service)
     }
@@ -440,6 +440,8 @@ async fn run_portfolio_calculation(
     snapshot_mode: SnapshotRecalcMode,
     valuation_mode: ValuationRecalcMode,
 ) {
+    context.analytics_warmup().cancel();
+
     // Emit start event
     if let Err(e) = app_handle.emit(PORTFOLIO_UPDATE_START, &()) {
         error!("Failed to emit portfolio:update-start event: {}", e);
@@ -513,10 +515,14 @@ async fn run_portfolio_calculation(
         }
     }
 
+    context.allocation_service().invalidate_cache();
+    context.gains_service().invalidate_cache();
+
     // Emit completion event
     if let Err(e) = app_handle.emit(PORTFOLIO_UPDATE_COMPLETE, &()) {
         error!("Failed to emit portfolio:update-complete event: {}", e);
     }
+    crate::listeners::spawn_analytics_warmup(app_handle, context);
 }
 
 /// Refreshes cached summary fields for all active goals.
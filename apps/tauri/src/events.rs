@@ -21,6 +21,12 @@ pub const PORTFOLIO_UPDATE_COMPLETE: &str = "portfolio:update-complete";
 /// Event emitted when the background portfolio recalculation process encounters an error.
 pub const PORTFOLIO_UPDATE_ERROR: &str = "portfolio:update-error";
 
+/// Event emitted when a saved portfolio (account group) is created, updated or deleted.
+pub const PORTFOLIOS_CHANGED: &str = "portfolio:portfolios-changed";
+
+/// Event emitted when the analytics caches have been warmed, with the warmup report as payload.
+pub const ANALYTICS_READY: &str = "analytics:ready";
+
 /// Event emitted when the market data sync process starts.
 pub const MARKET_SYNC_START: &str = "market:sync-start";
 
@@ -48,6 +54,25 @@ pub const BROKER_SYNC_COMPLETE: &str = "broker:sync-complete";
 /// Event emitted when the broker sync process fails.
 pub const BROKER_SYNC_ERROR: &str = "broker:sync-error";
 
+/// Event emitted when a broker sync flags position changes above the anomaly thresholds.
+pub const SYNC_ANOMALY: &str = "sync:anomaly";
+
+/// Event emitted when device sync re-downloads its snapshot after a cycle found local state
+/// unrecoverable, so the UI can show that it is re-syncing from scratch.
+pub const SYNC_BOOTSTRAP_TRIGGERED: &str = "sync:bootstrap-triggered";
+
+/// Event emitted when a broker connection's local display name is set or cleared.
+pub const CONNECTION_RENAMED: &str = "connection:renamed";
+
+/// Event emitted when a broker connection's consent lapses within the warning lead time.
+pub const CONNECTION_EXPIRING: &str = "connection:expiring";
+
+/// Event emitted when the cloud kill switch is turned on.
+pub const CLOUD_DISABLED: &str = "cloud:disabled";
+
+/// Event emitted when the cloud kill switch is turned off again.
+pub const CLOUD_ENABLED: &str = "cloud:enabled";
+
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct PortfolioRequestPayload {
     /// Optional list of account IDs. None implies all/total accounts.
@@ -164,7 +164,10 @@ mod desktop {
         }
 
         #[cfg(feature = "device-sync")]
-        start_sync_outbox_wake_worker(sync_outbox_wake_receiver, Arc::clone(&context));
+        {
+            crate::commands::device_sync::register_app_handle(&handle);
+            start_sync_outbox_wake_worker(sync_outbox_wake_receiver, Arc::clone(&context));
+        }
 
         // Start the domain event queue worker now that context is managed
         // This must be done in an async context since it spawns a tokio task
@@ -260,7 +263,13 @@ mod mobile {
                     handle.manage(Arc::clone(&context));
 
                     #[cfg(feature = "device-sync")]
-                    start_sync_outbox_wake_worker(sync_outbox_wake_receiver, Arc::clone(&context));
+                    {
+                        crate::commands::device_sync::register_app_handle(&handle);
+                        start_sync_outbox_wake_worker(
+                            sync_outbox_wake_receiver,
+                            Arc::clone(&context),
+                        );
+                    }
 
                     // Start the domain event queue worker now that context is managed
                     domain_events::TauriDomainEventSink::start_queue_worker(
@@ -421,8 +430,11 @@ pub fn run() {
             commands::account::create_account,
             commands::account::update_account,
             commands::account::delete_account,
+            commands::account::merge_accounts,
             // Activity commands
             commands::activity::search_activities,
+            commands::activity::search_activities_page,
+            commands::activity::get_recent_activities,
             commands::activity::create_activity,
             commands::activity::update_activity,
             commands::activity::save_activities,
@@ -448,6 +460,11 @@ pub fn run() {
             commands::settings::get_settings,
             commands::settings::is_auto_update_check_enabled,
             commands::settings::update_settings,
+            commands::settings::get_sync_quiet_hours,
+            commands::settings::set_sync_quiet_hours,
+            commands::settings::get_cloud_status,
+            commands::settings::disable_cloud,
+            commands::settings::enable_cloud,
             commands::settings::get_latest_exchange_rates,
             commands::settings::update_exchange_rate,
             commands::settings::add_exchange_rate,
@@ -470,6 +487,7 @@ pub fn run() {
             commands::spending::update_categorization_rule,
             commands::spending::delete_categorization_rule,
             commands::spending::rerun_categorization_rules,
+            commands::spending::preview_categorization_rules,
             commands::spending::list_rule_presets,
             commands::spending::import_rule_preset,
             commands::spending::remove_rule_preset,
@@ -525,7 +543,13 @@ pub fn run() {
             commands::portfolio::get_asset_lots,
             commands::portfolio::get_portfolio_allocations,
             commands::portfolio::get_holdings_by_allocation,
+            commands::portfolio::get_allocation_breakdown,
+            commands::portfolio::warm_analytics_caches,
+            commands::portfolio::cancel_analytics_warmup,
             commands::portfolio::get_income_summary,
+            commands::portfolio::get_dividend_summary,
+            commands::portfolio::get_gains,
+            commands::portfolio::validate_activity_ledger,
             commands::portfolio::get_historical_valuations,
             commands::portfolio::get_latest_valuations,
             commands::portfolio::get_current_valuation,
@@ -553,6 +577,10 @@ pub fn run() {
             commands::utilities::write_pending_export_text_file,
             commands::utilities::write_pending_export_file,
             commands::utilities::export_data_file,
+            commands::utilities::export_data_archive,
+            commands::utilities::diff_data_archives,
+            commands::utilities::export_holdings_csv,
+            commands::utilities::export_portfolio_snapshot,
             commands::utilities::open_external_url,
             commands::utilities::get_app_info,
             commands::utilities::check_for_updates,
@@ -568,6 +596,9 @@ pub fn run() {
             commands::asset::update_quote_mode,
             commands::asset::delete_asset,
             commands::asset::create_asset,
+            commands::asset::normalize_symbols,
+            commands::asset::get_symbol_mappings,
+            commands::asset::set_symbol_mapping,
             // Alternative asset commands
             commands::alternative_assets::create_alternative_asset,
             commands::alternative_assets::update_alternative_asset_valuation,
@@ -674,18 +705,24 @@ pub fn run() {
             #[cfg(any(feature = "connect-sync", feature = "device-sync"))]
             commands::wealthfolio_connect::clear_sync_session,
             #[cfg(any(feature = "connect-sync", feature = "device-sync"))]
+            commands::wealthfolio_connect::sign_out_everywhere,
+            #[cfg(any(feature = "connect-sync", feature = "device-sync"))]
             commands::wealthfolio_connect::restore_sync_session,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::sync_broker_data,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::broker_ingest_run,
             #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::resync_broker_account,
+            #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_synced_accounts,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_platforms,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::list_broker_connections,
             #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::set_connection_name,
+            #[cfg(feature = "connect-sync")]
             commands::brokers_sync::list_broker_accounts,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_subscription_plans,
@@ -694,6 +731,8 @@ pub fn run() {
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_user_info,
             #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::set_fake_subscription,
+            #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_broker_sync_states,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_broker_ingest_states,
@@ -702,6 +741,14 @@ pub fn run() {
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_data_import_runs,
             #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::get_sync_dashboard,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::get_connection_summary,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::get_expiring_connections,
+            #[cfg(feature = "connect-sync")]
+            commands::brokers_sync::get_history_backfills,
+            #[cfg(feature = "connect-sync")]
             commands::brokers_sync::get_broker_sync_profile,
             #[cfg(feature = "connect-sync")]
             commands::brokers_sync::save_broker_sync_profile_rules,
@@ -734,6 +781,12 @@ pub fn run() {
             #[cfg(feature = "device-sync")]
             commands::device_sync::device_sync_engine_status,
             #[cfg(feature = "device-sync")]
+            commands::device_sync::get_sync_rejections,
+            #[cfg(feature = "device-sync")]
+            commands::device_sync::acknowledge_sync_rejections,
+            #[cfg(feature = "device-sync")]
+            commands::device_sync::device_sync_cursor_expiry_forecast,
+            #[cfg(feature = "device-sync")]
             commands::device_sync::device_sync_pairing_source_status,
             #[cfg(feature = "device-sync")]
             commands::device_sync::device_sync_bootstrap_overwrite_check,
@@ -790,6 +843,8 @@ pub fn run() {
             commands::device_enroll_service::clear_device_sync_data,
             #[cfg(feature = "device-sync")]
             commands::device_enroll_service::reinitialize_device_sync,
+            #[cfg(feature = "device-sync")]
+            commands::device_enroll_service::rotate_device_credential,
             // Sync crypto commands
             #[cfg(feature = "device-sync")]
             commands::sync_crypto::sync_generate_root_key,
@@ -830,6 +885,7 @@ pub fn run() {
             commands::health::execute_health_fix,
             commands::health::get_health_config,
             commands::health::update_health_config,
+            commands::diagnostics::get_diagnostics,
             // Allocation target commands
             commands::allocation_targets::list_allocation_targets,
             commands::allocation_targets::get_allocation_target,
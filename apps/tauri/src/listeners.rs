@@ -13,9 +13,9 @@ use wealthfolio_core::quotes::MarketSyncMode;
 use crate::context::ServiceContext;
 use crate::events::{
     emit_portfolio_trigger_recalculate, emit_portfolio_trigger_update, MarketSyncResult,
-    PortfolioRequestPayload, MARKET_SYNC_COMPLETE, MARKET_SYNC_ERROR, MARKET_SYNC_START,
-    PORTFOLIO_TRIGGER_RECALCULATE, PORTFOLIO_TRIGGER_UPDATE, PORTFOLIO_UPDATE_COMPLETE,
-    PORTFOLIO_UPDATE_ERROR, PORTFOLIO_UPDATE_START,
+    PortfolioRequestPayload, ANALYTICS_READY, MARKET_SYNC_COMPLETE, MARKET_SYNC_ERROR,
+    MARKET_SYNC_START, PORTFOLIO_TRIGGER_RECALCULATE, PORTFOLIO_TRIGGER_UPDATE,
+    PORTFOLIO_UPDATE_COMPLETE, PORTFOLIO_UPDATE_ERROR, PORTFOLIO_UPDATE_START,
 };
 
 /// Sets up the global event listeners for the application.
@@ -33,6 +33,26 @@ pub fn setup_event_listeners(handle: AppHandle) {
     });
 }
 
+/// Warms the analytics caches in the background and emits [`ANALYTICS_READY`] when done.
+/// A warmup still running from an earlier call is cancelled.
+pub fn spawn_analytics_warmup(app_handle: &AppHandle, context: &Arc<ServiceContext>) {
+    let warmup = context.analytics_warmup();
+    let ticket = warmup.begin();
+    let base_currency = context.get_base_currency();
+    let app_handle = app_handle.clone();
+    spawn(async move {
+        match warmup.run(ticket, &base_currency).await {
+            Ok(report) if report.cancelled => info!("Analytics warmup cancelled"),
+            Ok(report) => {
+                if let Err(e) = app_handle.emit(ANALYTICS_READY, &report) {
+                    error!("Failed to emit {} event: {}", ANALYTICS_READY, e);
+                }
+            }
+            Err(e) => warn!("Analytics warmup failed: {}", e),
+        }
+    });
+}
+
 fn resolve_listener_account_ids(
     context: &Arc<ServiceContext>,
     account_ids: Option<&Vec<String>>,
@@ -252,6 +272,7 @@ fn handle_portfolio_calculation(
                 return;
             }
         };
+        context.analytics_warmup().cancel();
 
         let account_service = context.account_service();
         let snapshot_service = context.snapshot_service();
@@ -357,8 +378,12 @@ fn handle_portfolio_calculation(
             }
         }
 
+        context.allocation_service().invalidate_cache();
+        context.gains_service().invalidate_cache();
+
         if let Err(e) = app_handle.emit(PORTFOLIO_UPDATE_COMPLETE, ()) {
             error!("Failed to emit {} event: {}", PORTFOLIO_UPDATE_COMPLETE, e);
         }
+        spawn_analytics_warmup(&app_handle, &context);
     });
 }
@@ -1,9 +1,13 @@
 //! Startup sync for broker data.
 //!
-//! Syncs broker data once on app startup. After that, user manually triggers sync.
+//! Syncs broker data once on app startup, unless the user's sync quiet hours are active.
+//! After that, user manually triggers sync. An access token close to expiry is refreshed
+//! before the sync starts rather than failing with a `401` partway through.
 
 #[cfg(feature = "connect-sync")]
 use std::sync::Arc;
+#[cfg(feature = "connect-sync")]
+use std::time::Duration;
 
 #[cfg(feature = "connect-sync")]
 use log::{debug, info, warn};
@@ -12,13 +16,21 @@ use tauri::AppHandle;
 #[cfg(feature = "connect-sync")]
 use tauri::AppHandle;
 
+#[cfg(feature = "connect-sync")]
+use wealthfolio_connect::{SkipReason, SubscriptionDecision};
 #[cfg(feature = "connect-sync")]
 use wealthfolio_core::quotes::MarketSyncMode;
+#[cfg(feature = "connect-sync")]
+use wealthfolio_core::settings::{CloudAccessService, SyncQuietHoursService};
 
 #[cfg(feature = "connect-sync")]
 use crate::commands::brokers_sync::perform_broker_sync;
 use crate::context::ServiceContext;
 
+/// A broker sync can take a few minutes; a token expiring sooner is refreshed up front.
+#[cfg(feature = "connect-sync")]
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
 /// Runs broker sync once on startup (async, non-blocking).
 ///
 /// This function:
@@ -27,29 +39,58 @@ use crate::context::ServiceContext;
 /// - Triggers portfolio update if activities were synced
 #[cfg(feature = "connect-sync")]
 pub async fn run_startup_sync(handle: &AppHandle, context: &Arc<ServiceContext>) {
+    if CloudAccessService::new(context.settings_service()).is_disabled() {
+        info!("Startup sync skipped: cloud access is disabled");
+        return;
+    }
+    if !SyncQuietHoursService::new(context.settings_service()).allows_sync_at(chrono::Utc::now()) {
+        info!("Startup sync skipped: quiet hours are active");
+        return;
+    }
+
     info!("Running startup broker sync...");
 
-    // Check if user's plan includes broker sync
-    match context.connect_service().has_broker_sync().await {
-        Ok(true) => {
-            // User has broker sync, proceed
+    // Check if user's plan includes broker sync; during a cloud outage a recent active check
+    // is trusted for a grace period.
+    match context.connect_service().broker_sync_subscription().await {
+        SubscriptionDecision::Active => {}
+        SubscriptionDecision::CachedActive { checked_at, reason } => {
+            info!(
+                "Could not verify broker sync access ({}); using active status from {}",
+                reason,
+                checked_at.to_rfc3339()
+            );
         }
-        Ok(false) => {
+        SubscriptionDecision::Inactive => {
             debug!("Startup sync skipped: plan does not include broker sync");
             return;
         }
-        Err(e) => {
+        SubscriptionDecision::Unknown(reason) => {
             // If we can't check (no token, network error, etc.), skip silently
             debug!(
                 "Startup sync skipped: could not verify broker sync access ({})",
-                e
+                reason
             );
             return;
         }
     }
 
+    if let Err(e) = context
+        .connect_service()
+        .refresh_access_token_if_expiring(TOKEN_REFRESH_MARGIN)
+        .await
+    {
+        debug!(
+            "Could not refresh the access token before startup sync: {}",
+            e
+        );
+    }
+
     // Perform sync (orchestrator emits broker:sync-start and broker:sync-complete events)
     match perform_broker_sync(context, Some(handle)).await {
+        Ok(result) if result.skip_reason == Some(SkipReason::NoConnections) => {
+            info!("Startup sync skipped: no broker connections");
+        }
         Ok(result) => {
             info!(
                 "Startup sync completed: success={}, message={}",
@@ -58,9 +99,9 @@ pub async fn run_startup_sync(handle: &AppHandle, context: &Arc<ServiceContext>)
 
             // Note: broker:sync-complete event is emitted by the orchestrator via TauriProgressReporter
 
-            // Trigger portfolio update if sync was successful
+            // Trigger portfolio update if sync was successful, even if only for some connections
             // Note: Asset enrichment is handled automatically via domain events (AssetsCreated)
-            if result.success {
+            if result.success || result.partial_success {
                 if let Some(ref activities) = result.activities_synced {
                     if activities.activities_upserted > 0 {
                         info!(
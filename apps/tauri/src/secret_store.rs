@@ -1,29 +1,46 @@
-use std::sync::Arc;
+//! Secrets live in the OS keyring. Where there is none (headless Linux, some containers),
+//! `WEALTHFOLIO_SECRET_BACKEND` selects an encrypted file in the app data directory instead:
+//!
+//! - `auto` (default): the keyring, or the file when the keyring is unreachable and
+//!   `WEALTHFOLIO_SECRET_PASSPHRASE` is set.
+//! - `keyring`: always the keyring.
+//! - `file`: always the file; the passphrase is required.
+//!
+//! The file is the [`FileSecretStore`] the server uses, encrypted under a key derived from
+//! the passphrase with Argon2id and a random per-file salt.
+
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use keyring::Entry;
+use log::{info, warn};
 
 use wealthfolio_core::{
     errors::Error,
-    secrets::{format_service_id, SecretStore},
+    secrets::{format_service_id, FileSecretStore, SecretStore},
     Result,
 };
 
 const USERNAME: &str = "default";
 
+/// Total attempts for transient keyring failures. Kept low so a broken keyring
+/// never stalls the UI for long.
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
 #[derive(Debug, Default)]
 pub struct KeyringSecretStore;
 
 impl SecretStore for KeyringSecretStore {
     fn set_secret(&self, service: &str, secret: &str) -> Result<()> {
         let entry = entry_for(service)?;
-        entry
-            .set_password(secret)
-            .map_err(|err| Error::Secret(err.to_string()))
+        with_retry(|| entry.set_password(secret)).map_err(|err| Error::Secret(err.to_string()))
     }
 
     fn get_secret(&self, service: &str) -> Result<Option<String>> {
         let entry = entry_for(service)?;
-        match entry.get_password() {
+        match with_retry(|| entry.get_password()) {
             Ok(value) => Ok(Some(value)),
             Err(keyring::Error::NoEntry) => Ok(None),
             Err(err) => Err(Error::Secret(err.to_string())),
@@ -32,7 +49,7 @@ impl SecretStore for KeyringSecretStore {
 
     fn delete_secret(&self, service: &str) -> Result<()> {
         let entry = entry_for(service)?;
-        match entry.delete_password() {
+        match with_retry(|| entry.delete_password()) {
             Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
             Err(err) => Err(Error::Secret(err.to_string())),
         }
@@ -44,6 +61,215 @@ fn entry_for(service: &str) -> Result<Entry> {
     Entry::new(&service_id, USERNAME).map_err(|err| Error::Secret(err.to_string()))
 }
 
+/// Platform and storage-access failures (DBus hiccups, a keychain that is still
+/// unlocking) can clear on their own. Everything else, including a missing entry,
+/// is permanent.
+fn is_transient(err: &keyring::Error) -> bool {
+    matches!(
+        err,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
+
+fn with_retry<T>(mut op: impl FnMut() -> keyring::Result<T>) -> keyring::Result<T> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(err) if is_transient(&err) && attempt < MAX_ATTEMPTS => {
+                warn!(
+                    "Transient keyring failure (attempt {}/{}): {}",
+                    attempt, MAX_ATTEMPTS, err
+                );
+                std::thread::sleep(RETRY_BACKOFF * attempt);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+pub const SECRET_BACKEND_ENV: &str = "WEALTHFOLIO_SECRET_BACKEND";
+pub const SECRET_PASSPHRASE_ENV: &str = "WEALTHFOLIO_SECRET_PASSPHRASE";
+const SECRETS_FILE_NAME: &str = "secrets.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum SecretBackend {
+    #[default]
+    Auto,
+    Keyring,
+    File,
+}
+
+impl SecretBackend {
+    /// Unknown values fall back to [`SecretBackend::Auto`].
+    fn parse(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("keyring") => Self::Keyring,
+            Some("file") => Self::File,
+            Some("auto") | Some("") | None => Self::Auto,
+            Some(other) => {
+                warn!(
+                    "Ignoring unknown {} value '{}'; using auto",
+                    SECRET_BACKEND_ENV, other
+                );
+                Self::Auto
+            }
+        }
+    }
+}
+
+/// A missing entry still means the keyring answered.
+fn keyring_available() -> bool {
+    let Ok(entry) = entry_for("keyring_probe") else {
+        return false;
+    };
+    match with_retry(|| entry.get_password()) {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(err) => {
+            warn!("OS keyring is unavailable: {}", err);
+            false
+        }
+    }
+}
+
+fn select_secret_store(
+    backend: SecretBackend,
+    passphrase: Option<&str>,
+    app_data_dir: &Path,
+    keyring_available: impl FnOnce() -> bool,
+) -> Result<Arc<dyn SecretStore>> {
+    let passphrase = passphrase.filter(|value| !value.trim().is_empty());
+    let file_store = |passphrase: &str| -> Result<Arc<dyn SecretStore>> {
+        let path = app_data_dir.join(SECRETS_FILE_NAME);
+        info!("Storing secrets in encrypted file {}", path.display());
+        let store = FileSecretStore::with_passphrase(path, passphrase)
+            .map_err(|e| Error::Secret(format!("{} (check {})", e, SECRET_PASSPHRASE_ENV)))?;
+        Ok(Arc::new(store))
+    };
+    match backend {
+        SecretBackend::Keyring => Ok(Arc::new(KeyringSecretStore)),
+        SecretBackend::File => match passphrase {
+            Some(passphrase) => file_store(passphrase),
+            None => Err(Error::Secret(format!(
+                "{}=file requires {}",
+                SECRET_BACKEND_ENV, SECRET_PASSPHRASE_ENV
+            ))),
+        },
+        SecretBackend::Auto => {
+            if keyring_available() {
+                return Ok(Arc::new(KeyringSecretStore));
+            }
+            match passphrase {
+                Some(passphrase) => file_store(passphrase),
+                None => {
+                    warn!(
+                        "Set {} to store secrets in an encrypted file instead of the keyring",
+                        SECRET_PASSPHRASE_ENV
+                    );
+                    Ok(Arc::new(KeyringSecretStore))
+                }
+            }
+        }
+    }
+}
+
+static SECRET_STORE: OnceLock<Arc<dyn SecretStore>> = OnceLock::new();
+
+/// Picks the secret backend for this process. Called once while the app context starts.
+pub fn init_secret_store(app_data_dir: &str) -> Result<Arc<dyn SecretStore>> {
+    if let Some(store) = SECRET_STORE.get() {
+        return Ok(store.clone());
+    }
+    let store = select_secret_store(
+        SecretBackend::parse(std::env::var(SECRET_BACKEND_ENV).ok().as_deref()),
+        std::env::var(SECRET_PASSPHRASE_ENV).ok().as_deref(),
+        Path::new(app_data_dir),
+        keyring_available,
+    )?;
+    Ok(SECRET_STORE.get_or_init(|| store).clone())
+}
+
+/// The store chosen by [`init_secret_store`]; the keyring before that runs.
 pub fn shared_secret_store() -> Arc<dyn SecretStore> {
-    Arc::new(KeyringSecretStore)
+    SECRET_STORE
+        .get()
+        .cloned()
+        .unwrap_or_else(|| Arc::new(KeyringSecretStore))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::fs;
+
+    fn platform_failure() -> keyring::Error {
+        keyring::Error::PlatformFailure("dbus connection reset".into())
+    }
+
+    #[test]
+    fn transient_failure_then_success_returns_value() {
+        let calls = Cell::new(0);
+        let result = with_retry(|| {
+            calls.set(calls.get() + 1);
+            if calls.get() == 1 {
+                Err(platform_failure())
+            } else {
+                Ok("secret".to_string())
+            }
+        });
+
+        assert_eq!(result.unwrap(), "secret");
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn not_found_returns_immediately() {
+        let calls = Cell::new(0);
+        let result: keyring::Result<String> = with_retry(|| {
+            calls.set(calls.get() + 1);
+            Err(keyring::Error::NoEntry)
+        });
+
+        assert!(matches!(result, Err(keyring::Error::NoEntry)));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn persistent_transient_failure_gives_up_after_max_attempts() {
+        let calls = Cell::new(0);
+        let result: keyring::Result<String> = with_retry(|| {
+            calls.set(calls.get() + 1);
+            Err(platform_failure())
+        });
+
+        assert!(matches!(result, Err(keyring::Error::PlatformFailure(_))));
+        assert_eq!(calls.get(), MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn backend_selection_falls_back_to_the_file_only_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join(SECRETS_FILE_NAME);
+        let uses_file = |store: Arc<dyn SecretStore>| {
+            let _ = fs::remove_file(&file_path);
+            store.set_secret("probe", "value").is_ok() && file_path.exists()
+        };
+
+        assert_eq!(SecretBackend::parse(None), SecretBackend::Auto);
+        assert_eq!(SecretBackend::parse(Some(" File ")), SecretBackend::File);
+        assert_eq!(SecretBackend::parse(Some("vault")), SecretBackend::Auto);
+
+        let fallback =
+            select_secret_store(SecretBackend::Auto, Some("passphrase"), dir.path(), || {
+                false
+            })
+            .unwrap();
+        assert!(uses_file(fallback));
+        let forced =
+            select_secret_store(SecretBackend::File, Some("passphrase"), dir.path(), || true)
+                .unwrap();
+        assert!(uses_file(forced));
+        assert!(select_secret_store(SecretBackend::File, Some("  "), dir.path(), || true).is_err());
+    }
 }
@@ -3,13 +3,18 @@
 //! This service wraps the ConnectApiClient with keyring token retrieval,
 //! providing a simple interface for cloud API operations.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant, SystemTime};
 
 use wealthfolio_connect::{
-    ensure_valid_access_token, ConnectApiClient, TokenLifecycleConfig, TokenLifecycleState,
-    DEFAULT_CLOUD_API_URL,
+    access_token_expiry, connect_user_agent, ensure_valid_access_token, force_refresh_access_token,
+    parse_require_https, resolve_broker_sync_subscription, resolve_cloud_api_url,
+    resolve_cloud_api_urls, sign_out, subscription_grace_period_from_env, validate_cloud_api_url,
+    CloudApiUrlError, ConnectApiClient, SignOutResult, SubscriptionDecision, SubscriptionStatus,
+    TokenLifecycleConfig, TokenLifecycleState,
 };
 use wealthfolio_core::secrets::SecretStore;
+use wealthfolio_core::settings::{CloudAccessService, SettingsServiceTrait};
 
 /// Returns true when broker/connect sync was compiled in.
 pub fn is_connect_sync_enabled() -> bool {
@@ -32,10 +37,41 @@ pub fn cloud_api_base_url() -> Option<String> {
         return None;
     }
 
-    option_env!("CONNECT_API_URL")
-        .map(|v| v.trim().trim_end_matches('/').to_string())
-        .filter(|v| !v.is_empty())
-        .or_else(|| Some(DEFAULT_CLOUD_API_URL.to_string()))
+    match resolve_cloud_api_url(option_env!("CONNECT_API_URL")) {
+        Ok(url) => Some(url),
+        Err(e) => {
+            static WARNED: Once = Once::new();
+            WARNED.call_once(|| log::warn!("Cloud features are unavailable: {}", e));
+            None
+        }
+    }
+}
+
+/// Read replica listed after the primary in `CONNECT_API_URL`, for Connect API GETs to fail over
+/// to.
+fn cloud_api_fallback_url() -> Option<String> {
+    if !is_cloud_sync_enabled() {
+        return None;
+    }
+    resolve_cloud_api_urls(option_env!("CONNECT_API_URL"))
+        .ok()
+        .and_then(|urls| urls.fallback)
+}
+
+/// Rejects a malformed `CONNECT_API_URL`, and a plain-HTTP one (primary or fallback) unless it
+/// points at localhost or the build set `CONNECT_API_REQUIRE_HTTPS=false`. Checked at startup so
+/// tokens never go out unencrypted.
+pub fn validate_cloud_api_base_url() -> Result<(), CloudApiUrlError> {
+    if !is_cloud_sync_enabled() {
+        return Ok(());
+    }
+    let urls = resolve_cloud_api_urls(option_env!("CONNECT_API_URL"))?;
+    let require_https = parse_require_https(option_env!("CONNECT_API_REQUIRE_HTTPS"));
+    validate_cloud_api_url(&urls.primary, require_https)?;
+    match &urls.fallback {
+        Some(fallback) => validate_cloud_api_url(fallback, require_https),
+        None => Ok(()),
+    }
 }
 
 fn connect_auth_url() -> Option<String> {
@@ -56,21 +92,73 @@ fn token_lifecycle_config() -> Option<TokenLifecycleConfig> {
     Some(TokenLifecycleConfig::new(auth_url, publishable_key))
 }
 
+/// How long a plan check is reused before the cloud is asked again.
+pub const DEFAULT_SUBSCRIPTION_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Build-time `CONNECT_SUBSCRIPTION_CACHE_TTL_SECS` overrides the default; `0` disables the cache.
+fn subscription_cache_ttl(raw: Option<&str>) -> Duration {
+    raw.and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_SUBSCRIPTION_CACHE_TTL)
+}
+
+/// True when `expiry` falls within `margin` of `now`. Tokens without a known expiry never do.
+fn expires_within(expiry: Option<SystemTime>, now: SystemTime, margin: Duration) -> bool {
+    expiry.is_some_and(|expiry| expiry <= now + margin)
+}
+
+/// The last successful plan check. Failures are never stored, so a check that could not reach
+/// the cloud or was not authorized is retried on the next call instead of read as "no plan".
+#[derive(Default)]
+struct SubscriptionCache(Mutex<Option<(bool, Instant)>>);
+
+impl SubscriptionCache {
+    fn get(&self, ttl: Duration, now: Instant) -> Option<bool> {
+        let entry = *self.0.lock().ok()?;
+        entry
+            .filter(|(_, checked_at)| now.saturating_duration_since(*checked_at) < ttl)
+            .map(|(active, _)| active)
+    }
+
+    fn store(&self, result: &Result<bool, String>, now: Instant) {
+        if let Ok(mut entry) = self.0.lock() {
+            *entry = result.as_ref().ok().map(|active| (*active, now));
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut entry) = self.0.lock() {
+            *entry = None;
+        }
+    }
+}
+
 /// Service for interacting with Wealthfolio Connect cloud API.
 ///
 /// This service handles keyring token retrieval and provides
 /// convenient methods for common cloud API operations.
 pub struct ConnectService {
     secret_store: Arc<dyn SecretStore>,
+    settings_service: Arc<dyn SettingsServiceTrait>,
     token_lifecycle: Arc<TokenLifecycleState>,
+    subscription_cache: Arc<SubscriptionCache>,
+    subscription_cache_ttl: Duration,
 }
 
 impl ConnectService {
     /// Create a new ConnectService instance.
-    pub fn new(secret_store: Arc<dyn SecretStore>) -> Self {
+    pub fn new(
+        secret_store: Arc<dyn SecretStore>,
+        settings_service: Arc<dyn SettingsServiceTrait>,
+    ) -> Self {
         Self {
             secret_store,
+            settings_service,
             token_lifecycle: Arc::new(TokenLifecycleState::new()),
+            subscription_cache: Arc::new(SubscriptionCache::default()),
+            subscription_cache_ttl: subscription_cache_ttl(option_env!(
+                "CONNECT_SUBSCRIPTION_CACHE_TTL_SECS"
+            )),
         }
     }
 
@@ -79,6 +167,9 @@ impl ConnectService {
         if !is_cloud_sync_enabled() {
             return Err("Cloud sync feature is disabled in this build.".to_string());
         }
+        CloudAccessService::new(Arc::clone(&self.settings_service))
+            .ensure_enabled()
+            .map_err(|err| err.to_string())?;
 
         let config = token_lifecycle_config();
         ensure_valid_access_token(
@@ -90,8 +181,48 @@ impl ConnectService {
         .map_err(|err| err.to_string())
     }
 
+    /// When the current access token expires, refreshing it first if needed. `Ok(None)` for
+    /// opaque tokens whose expiry cannot be read.
+    pub async fn access_token_expiry(&self) -> Result<Option<SystemTime>, String> {
+        let token = self.get_valid_access_token().await?;
+        Ok(access_token_expiry(&token))
+    }
+
+    /// Refreshes the access token now if it would expire within `margin`, so a long operation
+    /// does not hit a `401` halfway through.
+    pub async fn refresh_access_token_if_expiring(&self, margin: Duration) -> Result<(), String> {
+        if expires_within(self.access_token_expiry().await?, SystemTime::now(), margin) {
+            let config = token_lifecycle_config();
+            force_refresh_access_token(
+                self.secret_store.as_ref(),
+                self.token_lifecycle.as_ref(),
+                config.as_ref(),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Revokes the cloud session on every device and deletes all cloud secrets from the
+    /// keyring. Signs out locally even when the revoke fails; see [`sign_out`].
+    pub async fn sign_out(&self) -> Result<SignOutResult, String> {
+        let config = token_lifecycle_config();
+        let result = sign_out(
+            self.secret_store.as_ref(),
+            self.token_lifecycle.as_ref(),
+            config.as_ref(),
+        )
+        .await
+        .map_err(|err| err.to_string())?;
+        self.subscription_cache.clear();
+        Ok(result)
+    }
+
+    /// Also forgets the plan check, since it belonged to the previous session.
     pub async fn clear_cached_token(&self) {
         self.token_lifecycle.clear_cache().await;
+        self.subscription_cache.clear();
     }
 
     /// Get an authenticated API client using the stored access token.
@@ -111,15 +242,109 @@ impl ConnectService {
 
         let access_token = self.get_valid_access_token().await?;
 
-        ConnectApiClient::new(&cloud_api_base_url, &access_token).map_err(|e| e.to_string())
+        ConnectApiClient::new(&cloud_api_base_url, &access_token)
+            .and_then(|client| {
+                client.with_user_agent(&connect_user_agent(env!("CARGO_PKG_VERSION")))
+            })
+            .map(|client| client.with_fallback_url(cloud_api_fallback_url().as_deref()))
+            .map_err(|e| e.to_string())
     }
 
     /// Check if the current user's plan includes broker sync.
     ///
     /// Returns `Ok(true)` only when the user has an active subscription
-    /// on a plan that includes broker sync (i.e. not "basic").
+    /// on a plan that includes broker sync (i.e. not "basic"). The answer is reused for
+    /// the subscription cache TTL (five minutes by default).
     pub async fn has_broker_sync(&self) -> Result<bool, String> {
-        let client = self.get_api_client().await?;
-        client.has_broker_sync().await.map_err(|e| e.to_string())
+        if let Some(active) = self
+            .subscription_cache
+            .get(self.subscription_cache_ttl, Instant::now())
+        {
+            return Ok(active);
+        }
+        self.refresh_subscription().await
+    }
+
+    /// Checks the plan with the cloud now, bypassing and then updating the cache.
+    pub async fn refresh_subscription(&self) -> Result<bool, String> {
+        let result = match self.get_api_client().await {
+            Ok(client) => client.has_broker_sync().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        self.subscription_cache.store(&result, Instant::now());
+        result
+    }
+
+    /// The plan check with the grace period policy applied: while the cloud cannot be
+    /// reached, a check within the last `CONNECT_SUBSCRIPTION_GRACE_HOURS` (default 24) that
+    /// found an active subscription still allows sync.
+    pub async fn broker_sync_subscription(&self) -> SubscriptionDecision {
+        let status = match self.get_api_client().await {
+            Ok(client) => client.subscription_status().await,
+            Err(e) => SubscriptionStatus::Unknown(e),
+        };
+        resolve_broker_sync_subscription(
+            Arc::clone(&self.settings_service),
+            subscription_grace_period_from_env(),
+            status,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscription_cache_expires_and_never_keeps_a_failed_check() {
+        let cache = SubscriptionCache::default();
+        let ttl = Duration::from_secs(300);
+        let checked_at = Instant::now();
+
+        cache.store(&Ok(true), checked_at);
+        assert_eq!(
+            cache.get(ttl, checked_at + Duration::from_secs(299)),
+            Some(true)
+        );
+        assert_eq!(cache.get(ttl, checked_at + ttl), None);
+
+        cache.store(&Err("API error 401".to_string()), checked_at);
+        assert_eq!(cache.get(ttl, checked_at), None);
+
+        cache.store(&Ok(false), checked_at);
+        assert_eq!(cache.get(ttl, checked_at), Some(false));
+        cache.clear();
+        assert_eq!(cache.get(ttl, checked_at), None);
+
+        assert_eq!(subscription_cache_ttl(None), DEFAULT_SUBSCRIPTION_CACHE_TTL);
+        assert_eq!(subscription_cache_ttl(Some("60")), Duration::from_secs(60));
+        assert_eq!(
+            subscription_cache_ttl(Some("soon")),
+            DEFAULT_SUBSCRIPTION_CACHE_TTL
+        );
+    }
+
+    #[test]
+    fn only_tokens_expiring_within_the_margin_need_a_refresh() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let margin = Duration::from_secs(300);
+
+        assert!(expires_within(
+            Some(now + Duration::from_secs(120)),
+            now,
+            margin
+        ));
+        assert!(expires_within(
+            Some(now - Duration::from_secs(1)),
+            now,
+            margin
+        ));
+        assert!(!expires_within(
+            Some(now + Duration::from_secs(3600)),
+            now,
+            margin
+        ));
+        assert!(!expires_within(None, now, margin));
     }
 }
@@ -2,4 +2,7 @@
 
 mod connect_service;
 
-pub use connect_service::{cloud_api_base_url, ConnectService};
+pub use connect_service::{
+    cloud_api_base_url, is_connect_sync_enabled, is_device_sync_enabled,
+    validate_cloud_api_base_url, ConnectService,
+};
@@ -15,8 +15,9 @@ use wealthfolio_core::{
         ActivityImport, ActivitySearchResponse, ActivitySearchResponseMeta, ActivityServiceTrait,
         ActivityUpdate, BrokerSyncProfileData, ImportAssetCandidate, ImportAssetPreviewItem,
         ImportMappingData, ImportTemplateData, ImportTemplateScope, InternalTransferPairRequest,
-        InternalTransferPairResponse, NewActivity, SaveBrokerSyncProfileRulesRequest, Sort,
-        TransferMatchCandidate, TransferMatchCandidateRequest,
+        InternalTransferPairResponse, NewActivity, RecentActivity,
+        SaveBrokerSyncProfileRulesRequest, Sort, TransferMatchCandidate,
+        TransferMatchCandidateRequest,
     },
     assets::{
         Asset, AssetMetadata, AssetResolutionInput, AssetResolutionOutput, AssetServiceTrait,
@@ -38,12 +39,15 @@ use wealthfolio_core::{
     holdings::{Holding, HoldingsServiceTrait},
     planning::SaveUpOverview,
     portfolio::allocation::{
-        AllocationHoldings, AllocationServiceTrait, PortfolioAllocations,
-        TaxonomyHoldingContributions,
+        AllocationDimension, AllocationHoldings, AllocationServiceTrait, AllocationSlice,
+        PortfolioAllocations, TaxonomyHoldingContributions,
     },
     portfolio::economic_events::BasisStatus,
     portfolio::fire::RetirementOverview,
-    portfolio::income::{IncomeServiceTrait, IncomeSummary},
+    portfolio::income::{
+        build_dividend_summary, DividendSummary, DividendSummaryQuery, IncomeServiceTrait,
+        IncomeSummary,
+    },
     portfolio::performance::{
         DataQualityStatus, PerformanceAttribution, PerformanceDataQuality, PerformancePeriod,
         PerformanceResult, PerformanceReturns, PerformanceRisk, PerformanceScopeDescriptor,
@@ -419,6 +423,25 @@ impl ActivityServiceTrait for MockActivityService {
         unimplemented!("MockActivityService::get_income_activities")
     }
 
+    fn get_recent_activities(&self, _limit: Option<i64>) -> CoreResult<Vec<RecentActivity>> {
+        unimplemented!("MockActivityService::get_recent_activities")
+    }
+
+    fn search_activities_page(
+        &self,
+        _cursor: Option<String>,
+        _limit: Option<i64>,
+        _account_id_filter: Option<Vec<String>>,
+        _activity_type_filter: Option<Vec<String>>,
+        _asset_id_keyword: Option<String>,
+        _needs_review_filter: Option<bool>,
+        _date_from_utc: Option<chrono::DateTime<chrono::Utc>>,
+        _date_to_utc_exclusive: Option<chrono::DateTime<chrono::Utc>>,
+        _instrument_type_filter: Option<Vec<String>>,
+    ) -> CoreResult<wealthfolio_core::activities::ActivityPage> {
+        unimplemented!("MockActivityService::search_activities_page")
+    }
+
     fn search_activities(
         &self,
         _page: i64,
@@ -583,6 +606,15 @@ impl ActivityServiceTrait for MockActivityService {
         unimplemented!("MockActivityService::upsert_activities_bulk")
     }
 
+    async fn upsert_activities_bulk_removing_stale(
+        &self,
+        _account_id: &str,
+        _activities: Vec<wealthfolio_core::activities::ActivityUpsert>,
+        _select_stale: wealthfolio_core::activities::StaleActivitySelector,
+    ) -> CoreResult<wealthfolio_core::activities::ActivityReconcileResult> {
+        unimplemented!("MockActivityService::upsert_activities_bulk_removing_stale")
+    }
+
     fn list_import_templates(&self) -> CoreResult<Vec<ImportTemplateData>> {
         Ok(vec![])
     }
@@ -1372,6 +1404,16 @@ impl AllocationServiceTrait for MockAllocationService {
             contributions: Vec::new(),
         })
     }
+
+    async fn get_allocation_breakdown(
+        &self,
+        _account_ids: &[String],
+        _base_currency: &str,
+        _aggregated_account_id: &str,
+        _dimension: AllocationDimension,
+    ) -> CoreResult<Vec<AllocationSlice>> {
+        Ok(Vec::new())
+    }
 }
 
 /// Mock income service for testing.
@@ -1389,6 +1431,20 @@ impl IncomeServiceTrait for MockIncomeService {
             IncomeSummary::new("LAST_YEAR", "USD".to_string()),
         ])
     }
+
+    fn get_dividend_summary(
+        &self,
+        query: &DividendSummaryQuery,
+        _account_ids: Option<&[String]>,
+    ) -> CoreResult<DividendSummary> {
+        Ok(build_dividend_summary(
+            &[],
+            query,
+            "USD",
+            |amount, _, _| amount,
+            chrono::Utc::now().date_naive(),
+        ))
+    }
 }
 
 /// Mock performance service for testing.
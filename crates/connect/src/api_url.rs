@@ -0,0 +1,250 @@
+//! Transport checks for the cloud API base URL.
+//!
+//! Every cloud request carries a bearer token, so the base URL must use HTTPS unless the host is
+//! a loopback address (a locally running API during development). Setting
+//! `CONNECT_API_REQUIRE_HTTPS=false` lifts the requirement entirely, for development setups that
+//! reach a plaintext API on another machine.
+//!
+//! A `CONNECT_API_URL` override is checked for shape first: it must be an absolute `http` or
+//! `https` URL with a host, so a typo is reported where it was made rather than as a failed
+//! request later.
+//!
+//! The override may also list a read replica after the primary, comma-separated
+//! (`https://api.example.com,https://replica.example.com`). Only [`ConnectApiClient`] GETs fail
+//! over to it; everything else uses the primary.
+//!
+//! [`ConnectApiClient`]: crate::ConnectApiClient
+
+use std::net::IpAddr;
+
+use reqwest::Url;
+
+use crate::client::DEFAULT_CLOUD_API_URL;
+
+/// Environment variable controlling the HTTPS requirement; `true` unless set to `false`.
+pub const REQUIRE_HTTPS_ENV: &str = "CONNECT_API_REQUIRE_HTTPS";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CloudApiUrlError {
+    #[error("Invalid cloud API URL '{url}': {reason}")]
+    Invalid { url: String, reason: String },
+    #[error(
+        "Refusing to send credentials over plain HTTP to '{url}'. Use an https:// \
+         CONNECT_API_URL, or set {REQUIRE_HTTPS_ENV}=false for development."
+    )]
+    InsecureScheme { url: String },
+}
+
+/// Trims whitespace and trailing slashes from a `CONNECT_API_URL` value and checks that it is an
+/// absolute `http`/`https` URL with a host. Returns the trimmed URL.
+pub fn normalize_cloud_api_url(value: &str) -> Result<String, CloudApiUrlError> {
+    let trimmed = value.trim().trim_end_matches('/');
+    let invalid = |reason: &str| CloudApiUrlError::Invalid {
+        url: trimmed.to_string(),
+        reason: reason.to_string(),
+    };
+    if trimmed.is_empty() {
+        return Err(invalid("the URL is empty"));
+    }
+    let url = Url::parse(trimmed).map_err(|e| invalid(&e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid(&format!("unsupported scheme '{}'", url.scheme())));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(invalid("the URL has no host"));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Primary cloud API base URL and an optional read-only fallback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudApiUrls {
+    pub primary: String,
+    /// Read replica that idempotent GETs retry against when the primary fails.
+    pub fallback: Option<String>,
+}
+
+/// The cloud API base URLs for an optional `CONNECT_API_URL` override: a primary URL, optionally
+/// followed by a comma and a fallback. Unset or blank means [`DEFAULT_CLOUD_API_URL`] with no
+/// fallback; every listed URL must pass [`normalize_cloud_api_url`].
+pub fn resolve_cloud_api_urls(
+    override_value: Option<&str>,
+) -> Result<CloudApiUrls, CloudApiUrlError> {
+    let Some(value) = override_value.filter(|value| !value.trim().is_empty()) else {
+        return Ok(CloudApiUrls {
+            primary: DEFAULT_CLOUD_API_URL.to_string(),
+            fallback: None,
+        });
+    };
+    let urls: Vec<&str> = value.split(',').collect();
+    match urls.as_slice() {
+        [primary] => Ok(CloudApiUrls {
+            primary: normalize_cloud_api_url(primary)?,
+            fallback: None,
+        }),
+        [primary, fallback] => Ok(CloudApiUrls {
+            primary: normalize_cloud_api_url(primary)?,
+            fallback: Some(normalize_cloud_api_url(fallback)?),
+        }),
+        _ => Err(CloudApiUrlError::Invalid {
+            url: value.trim().to_string(),
+            reason: "expected a primary URL and at most one fallback".to_string(),
+        }),
+    }
+}
+
+/// The primary cloud API base URL for an optional `CONNECT_API_URL` override (see
+/// [`resolve_cloud_api_urls`]).
+pub fn resolve_cloud_api_url(override_value: Option<&str>) -> Result<String, CloudApiUrlError> {
+    resolve_cloud_api_urls(override_value).map(|urls| urls.primary)
+}
+
+/// Parses a [`REQUIRE_HTTPS_ENV`] value. Only an explicit `false`/`0`/`no` turns it off.
+pub fn parse_require_https(value: Option<&str>) -> bool {
+    !value
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "false" | "0" | "no"))
+        .unwrap_or(false)
+}
+
+/// Reads [`REQUIRE_HTTPS_ENV`] from the process environment.
+pub fn require_https_from_env() -> bool {
+    parse_require_https(std::env::var(REQUIRE_HTTPS_ENV).ok().as_deref())
+}
+
+fn is_loopback(url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase();
+    host == "localhost"
+        || host.ends_with(".localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Checks that `base_url` is safe to send tokens to: `https`, or `http` to a loopback host.
+/// With `require_https` off any `http` URL is accepted.
+pub fn validate_cloud_api_url(base_url: &str, require_https: bool) -> Result<(), CloudApiUrlError> {
+    let url = Url::parse(base_url).map_err(|e| CloudApiUrlError::Invalid {
+        url: base_url.to_string(),
+        reason: e.to_string(),
+    })?;
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if !require_https || is_loopback(&url) => Ok(()),
+        "http" => Err(CloudApiUrlError::InsecureScheme {
+            url: base_url.to_string(),
+        }),
+        scheme => Err(CloudApiUrlError::Invalid {
+            url: base_url.to_string(),
+            reason: format!("unsupported scheme '{}'", scheme),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_is_rejected_unless_loopback_or_overridden() {
+        assert_eq!(
+            validate_cloud_api_url("http://api.example.com", true),
+            Err(CloudApiUrlError::InsecureScheme {
+                url: "http://api.example.com".to_string()
+            })
+        );
+        assert!(validate_cloud_api_url("http://192.168.1.20:8080", true).is_err());
+
+        assert!(validate_cloud_api_url("http://localhost:8787", true).is_ok());
+        assert!(validate_cloud_api_url("http://127.0.0.1:8787", true).is_ok());
+        assert!(validate_cloud_api_url("http://[::1]:8787", true).is_ok());
+
+        assert!(validate_cloud_api_url("http://api.example.com", false).is_ok());
+    }
+
+    #[test]
+    fn https_is_always_accepted_and_other_schemes_never() {
+        assert!(validate_cloud_api_url("https://api.wealthfolio.app", true).is_ok());
+        assert!(matches!(
+            validate_cloud_api_url("ftp://api.example.com", false),
+            Err(CloudApiUrlError::Invalid { .. })
+        ));
+        assert!(matches!(
+            validate_cloud_api_url("not a url", true),
+            Err(CloudApiUrlError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn malformed_overrides_are_rejected() {
+        for value in [
+            "ftp://api.example.com",
+            "http://",
+            "api.example.com",
+            "   ",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    normalize_cloud_api_url(value),
+                    Err(CloudApiUrlError::Invalid { .. })
+                ),
+                "{value:?} should be rejected"
+            );
+        }
+        assert_eq!(
+            normalize_cloud_api_url(" https://api.example.com/ ").unwrap(),
+            "https://api.example.com"
+        );
+
+        assert_eq!(resolve_cloud_api_url(None).unwrap(), DEFAULT_CLOUD_API_URL);
+        assert_eq!(
+            resolve_cloud_api_url(Some("  ")).unwrap(),
+            DEFAULT_CLOUD_API_URL
+        );
+        assert!(resolve_cloud_api_url(Some("ftp://api.example.com")).is_err());
+    }
+
+    #[test]
+    fn override_may_list_a_fallback_after_the_primary() {
+        assert_eq!(
+            resolve_cloud_api_urls(Some(
+                "https://api.example.com/, https://replica.example.com"
+            ))
+            .unwrap(),
+            CloudApiUrls {
+                primary: "https://api.example.com".to_string(),
+                fallback: Some("https://replica.example.com".to_string()),
+            }
+        );
+        assert_eq!(
+            resolve_cloud_api_url(Some("https://api.example.com,https://replica.example.com"))
+                .unwrap(),
+            "https://api.example.com"
+        );
+        assert_eq!(resolve_cloud_api_urls(None).unwrap().fallback, None);
+
+        for value in [
+            "https://api.example.com,",
+            "https://api.example.com,ftp://replica.example.com",
+            "https://a.example.com,https://b.example.com,https://c.example.com",
+        ] {
+            assert!(
+                resolve_cloud_api_urls(Some(value)).is_err(),
+                "{value:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn https_requirement_defaults_to_on() {
+        assert!(parse_require_https(None));
+        assert!(parse_require_https(Some("true")));
+        assert!(parse_require_https(Some("nonsense")));
+        assert!(!parse_require_https(Some("false")));
+        assert!(!parse_require_https(Some(" 0 ")));
+    }
+}
@@ -0,0 +1,211 @@
+//! Targeted repair of a single broker account.
+//!
+//! A resync refetches the account's activities from the cloud, starting a day before the
+//! account's cursor when it has one and at the beginning of its history otherwise. Once every
+//! page has been fetched, the activities are upserted and the local synced activities of the
+//! fetched window the cloud no longer returns are removed, both in one transaction, so a failed
+//! fetch never deletes anything. Activities from another source or edited locally are kept.
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use wealthfolio_core::activities::Activity;
+pub use wealthfolio_core::activities::StaleActivityPlan;
+
+use super::models::{AccountUniversalActivity, SyncHoldingsResponse};
+
+/// References under which a fetched activity may be stored locally: its id, and the source
+/// record id the mapping derives from it.
+pub fn fetched_activity_references(activity: &AccountUniversalActivity) -> Vec<String> {
+    [
+        activity.id.as_ref(),
+        activity.source_record_id.as_ref(),
+        activity.external_reference_id.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|value| !value.trim().is_empty())
+    .cloned()
+    .collect()
+}
+
+/// What a resync fetched, against which an account's local activities are reconciled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResyncScope {
+    /// References of every fetched activity, see [`fetched_activity_references`].
+    pub references: HashSet<String>,
+    /// Source systems the fetched activities are stored under, uppercase.
+    pub source_systems: HashSet<String>,
+    /// First day of the fetched window. Older activities were not refetched and are kept.
+    pub since: Option<NaiveDate>,
+}
+
+impl ResyncScope {
+    /// Whether `activity` came from the provider the resync fetched from, within its window.
+    fn covers(&self, activity: &Activity) -> bool {
+        let from_provider = activity
+            .source_system
+            .as_deref()
+            .is_some_and(|source| self.source_systems.contains(&source.to_ascii_uppercase()));
+        from_provider
+            && self
+                .since
+                .is_none_or(|since| activity.activity_date.date_naive() >= since)
+    }
+}
+
+/// Plans which of an account's local activities a resync removes: those from the fetched
+/// provider and window whose references the cloud no longer returned.
+pub fn plan_stale_activity_removal(
+    local_activities: &[Activity],
+    scope: &ResyncScope,
+) -> StaleActivityPlan {
+    let mut plan = StaleActivityPlan::default();
+    for activity in local_activities.iter().filter(|a| scope.covers(a)) {
+        let still_fetched = scope.references.contains(&activity.id)
+            || activity
+                .source_record_id
+                .as_ref()
+                .is_some_and(|id| scope.references.contains(id));
+        if still_fetched {
+            continue;
+        }
+        if activity.is_user_modified {
+            plan.preserved_ids.push(activity.id.clone());
+        } else {
+            plan.remove_ids.push(activity.id.clone());
+        }
+    }
+    plan
+}
+
+/// The write side of a resync: what the upsert wrote and which stale activities it removed.
+#[derive(Debug, Clone, Default)]
+pub struct ResyncWriteOutcome {
+    pub upserted: usize,
+    pub assets_created: usize,
+    pub needs_review: usize,
+    pub plan: StaleActivityPlan,
+}
+
+/// What a single-account resync changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountResyncResult {
+    pub account_id: String,
+    pub account_name: String,
+    pub activities_fetched: usize,
+    pub activities_upserted: usize,
+    pub assets_inserted: usize,
+    pub needs_review: usize,
+    /// Local synced activities the cloud no longer returns, now deleted.
+    pub removed_activity_ids: Vec<String>,
+    /// Activities the cloud no longer returns that were kept because they were edited locally.
+    pub preserved_activity_ids: Vec<String>,
+    /// Holdings refreshed for holdings-tracked accounts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holdings: Option<SyncHoldingsResponse>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use wealthfolio_core::activities::{ActivityStatus, ACTIVITY_TYPE_DEPOSIT};
+
+    fn local(id: &str, source_system: Option<&str>, user_modified: bool) -> Activity {
+        let now = Utc::now();
+        Activity {
+            id: id.to_string(),
+            account_id: "acct".to_string(),
+            asset_id: None,
+            activity_type: ACTIVITY_TYPE_DEPOSIT.to_string(),
+            activity_type_override: None,
+            source_type: None,
+            subtype: None,
+            status: ActivityStatus::Posted,
+            activity_date: now,
+            settlement_date: None,
+            quantity: None,
+            unit_price: None,
+            amount: None,
+            fee: None,
+            tax: None,
+            currency: "USD".to_string(),
+            fx_rate: None,
+            notes: None,
+            metadata: None,
+            source_system: source_system.map(str::to_string),
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+            is_user_modified: user_modified,
+            needs_review: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn scope(references: &[&str], since: Option<NaiveDate>) -> ResyncScope {
+        ResyncScope {
+            references: references.iter().map(|r| r.to_string()).collect(),
+            source_systems: HashSet::from(["SNAPTRADE".to_string()]),
+            since,
+        }
+    }
+
+    #[test]
+    fn only_unedited_provider_activities_missing_upstream_are_removed() {
+        let mut matched_by_reference = local("local-uuid", Some("SNAPTRADE"), false);
+        matched_by_reference.source_record_id = Some("ext-2".to_string());
+        let activities = vec![
+            local("act-1", Some("SNAPTRADE"), false),
+            matched_by_reference,
+            local("gone", Some("snaptrade"), false),
+            local("gone-but-edited", Some("SNAPTRADE"), true),
+            local("typed-in", Some("MANUAL"), false),
+            local("from-csv", Some("csv"), false),
+            local("other-provider", Some("PLAID"), false),
+            local("legacy", None, false),
+        ];
+
+        let plan =
+            plan_stale_activity_removal(&activities, &scope(&["act-1", "act-2", "ext-2"], None));
+
+        assert_eq!(plan.remove_ids, vec!["gone".to_string()]);
+        assert_eq!(plan.preserved_ids, vec!["gone-but-edited".to_string()]);
+    }
+
+    #[test]
+    fn activities_before_the_fetched_window_are_kept() {
+        let mut before = local("before-window", Some("SNAPTRADE"), false);
+        before.activity_date = Utc.with_ymd_and_hms(2026, 4, 29, 12, 0, 0).unwrap();
+        let mut inside = local("inside-window", Some("SNAPTRADE"), false);
+        inside.activity_date = Utc.with_ymd_and_hms(2026, 4, 30, 0, 0, 0).unwrap();
+
+        let plan = plan_stale_activity_removal(
+            &[before, inside],
+            &scope(&[], NaiveDate::from_ymd_opt(2026, 4, 30)),
+        );
+
+        assert_eq!(plan.remove_ids, vec!["inside-window".to_string()]);
+        assert!(plan.preserved_ids.is_empty());
+    }
+
+    #[test]
+    fn fetched_references_include_ids_and_source_record_ids() {
+        let activity = AccountUniversalActivity {
+            id: Some("activity-2".to_string()),
+            source_record_id: Some("record-2".to_string()),
+            external_reference_id: Some(" ".to_string()),
+            ..AccountUniversalActivity::default()
+        };
+
+        assert_eq!(
+            fetched_activity_references(&activity),
+            vec!["activity-2".to_string(), "record-2".to_string()]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Resumable activity pagination.
+//!
+//! While an account's activities are fetched page by page, the offset of the next page is saved
+//! here after every page that was stored. When the sync is interrupted (a failed request, the
+//! app closing), the next sync of the same window picks up at that page instead of fetching the
+//! whole window again. Cursors are stored in the settings table, keyed by connection id and then
+//! local account id, and removed once the account's activities are fully synced.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use wealthfolio_core::errors::{Error, Result};
+use wealthfolio_core::settings::SettingsServiceTrait;
+
+/// Settings key holding the `connection_id -> account_id -> cursor` map.
+pub const ACTIVITY_PAGE_CURSORS_SETTING_KEY: &str = "connect_activity_page_cursors";
+
+/// Serializes the read-modify-write of the stored map. Services are constructed per
+/// call, so the lock is process-wide rather than per instance.
+static CURSORS_WRITE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Where an interrupted activity sync left off.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPageCursor {
+    /// Query window the offset belongs to; `None` start means the full history.
+    pub start_date: Option<String>,
+    pub end_date: String,
+    /// Offset of the first page not yet stored.
+    pub offset: i64,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl ActivityPageCursor {
+    pub fn new(start_date: Option<String>, end_date: impl Into<String>, offset: i64) -> Self {
+        Self {
+            start_date,
+            end_date: end_date.into(),
+            offset,
+            saved_at: Utc::now(),
+        }
+    }
+}
+
+pub struct ActivityResumeService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+}
+
+impl ActivityResumeService {
+    pub fn new(settings_service: Arc<dyn SettingsServiceTrait>) -> Self {
+        Self { settings_service }
+    }
+
+    /// Returns the stored cursors. A stored value that is not a valid map is an error, so a
+    /// later write never silently replaces cursors it could not read.
+    fn load(&self) -> Result<BTreeMap<String, BTreeMap<String, ActivityPageCursor>>> {
+        match self
+            .settings_service
+            .get_setting_value(ACTIVITY_PAGE_CURSORS_SETTING_KEY)?
+        {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                Error::Unexpected(format!("Stored activity page cursors are invalid: {}", e))
+            }),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    async fn store(
+        &self,
+        cursors: &BTreeMap<String, BTreeMap<String, ActivityPageCursor>>,
+    ) -> Result<()> {
+        let raw = serde_json::to_string(cursors).map_err(|e| Error::Unexpected(e.to_string()))?;
+        self.settings_service
+            .set_setting_value(ACTIVITY_PAGE_CURSORS_SETTING_KEY, &raw)
+            .await
+    }
+
+    pub fn get(&self, connection_id: &str, account_id: &str) -> Result<Option<ActivityPageCursor>> {
+        Ok(self
+            .load()?
+            .get(connection_id)
+            .and_then(|accounts| accounts.get(account_id))
+            .cloned())
+    }
+
+    pub async fn save(
+        &self,
+        connection_id: &str,
+        account_id: &str,
+        cursor: ActivityPageCursor,
+    ) -> Result<()> {
+        let _guard = CURSORS_WRITE_LOCK.lock().await;
+        let mut cursors = self.load()?;
+        cursors
+            .entry(connection_id.to_string())
+            .or_default()
+            .insert(account_id.to_string(), cursor);
+        self.store(&cursors).await
+    }
+
+    /// Forgets the account's cursor. Does not write when there is nothing to remove.
+    pub async fn clear(&self, connection_id: &str, account_id: &str) -> Result<()> {
+        let _guard = CURSORS_WRITE_LOCK.lock().await;
+        let mut cursors = self.load()?;
+        let Some(accounts) = cursors.get_mut(connection_id) else {
+            return Ok(());
+        };
+        if accounts.remove(account_id).is_none() {
+            return Ok(());
+        }
+        if accounts.is_empty() {
+            cursors.remove(connection_id);
+        }
+        self.store(&cursors).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wealthfolio_core::settings::MemorySettingsService;
+
+    #[tokio::test]
+    async fn corrupt_stored_cursors_are_not_overwritten() {
+        let settings = Arc::new(MemorySettingsService::default());
+        settings
+            .set_setting_value(ACTIVITY_PAGE_CURSORS_SETTING_KEY, "{not json")
+            .await
+            .unwrap();
+        let service = ActivityResumeService::new(settings.clone());
+
+        let result = service
+            .save(
+                "conn-1",
+                "acc-1",
+                ActivityPageCursor::new(None, "2026-01-31", 100),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::Unexpected(_))));
+        assert!(matches!(
+            service.get("conn-1", "acc-1"),
+            Err(Error::Unexpected(_))
+        ));
+        assert_eq!(
+            settings
+                .get_setting_value(ACTIVITY_PAGE_CURSORS_SETTING_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("{not json")
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_cursor_saves_for_different_accounts_are_all_kept() {
+        let settings: Arc<dyn SettingsServiceTrait> = Arc::new(MemorySettingsService::default());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let settings = settings.clone();
+                tokio::spawn(async move {
+                    ActivityResumeService::new(settings)
+                        .save(
+                            "conn-1",
+                            &format!("acc-{}", i),
+                            ActivityPageCursor::new(None, "2026-01-31", 100),
+                        )
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let service = ActivityResumeService::new(settings);
+        for i in 0..8 {
+            assert!(service
+                .get("conn-1", &format!("acc-{}", i))
+                .unwrap()
+                .is_some());
+        }
+    }
+}
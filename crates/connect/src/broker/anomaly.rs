@@ -0,0 +1,177 @@
+//! Anomaly detection for broker holdings syncs.
+//!
+//! A bad data feed can double a position overnight. Changes are still applied,
+//! but positions that move by more than the configured thresholds are reported
+//! back in the sync result so the user can review them.
+
+use rust_decimal::Decimal;
+
+use super::models::{PositionChange, SyncAnomaly};
+
+/// Thresholds for flagging suspicious position changes. Both are disabled by default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnomalyThresholds {
+    /// Relative change in quantity or cost basis (e.g. `0.5` for 50%) above which a
+    /// position is flagged.
+    pub max_change_ratio: Option<Decimal>,
+    /// Absolute change in total cost basis (position currency) above which a position
+    /// is flagged.
+    pub max_value_change: Option<Decimal>,
+}
+
+impl AnomalyThresholds {
+    /// Whether any threshold is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.max_change_ratio.is_some() || self.max_value_change.is_some()
+    }
+
+    /// Returns a reason when the change exceeds a threshold.
+    pub fn evaluate(&self, change: &PositionChange) -> Option<String> {
+        if let Some(max_ratio) = self.max_change_ratio {
+            if let Some(ratio) = relative_change(change.previous_quantity, change.current_quantity)
+            {
+                if ratio > max_ratio {
+                    return Some(format!(
+                        "Quantity changed by {}% (threshold {}%)",
+                        percent(ratio),
+                        percent(max_ratio)
+                    ));
+                }
+            }
+            if let Some(ratio) =
+                relative_change(change.previous_cost_basis, change.current_cost_basis)
+            {
+                if ratio > max_ratio {
+                    return Some(format!(
+                        "Value changed by {}% (threshold {}%)",
+                        percent(ratio),
+                        percent(max_ratio)
+                    ));
+                }
+            }
+        }
+
+        if let Some(max_value) = self.max_value_change {
+            let delta = (change.current_cost_basis - change.previous_cost_basis).abs();
+            if delta > max_value {
+                return Some(format!(
+                    "Value changed by {} (threshold {})",
+                    delta.round_dp(2),
+                    max_value
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// Flag position changes that exceed the thresholds.
+pub fn detect_anomalies(
+    account_id: &str,
+    account_name: &str,
+    changes: &[PositionChange],
+    thresholds: &AnomalyThresholds,
+) -> Vec<SyncAnomaly> {
+    if !thresholds.is_enabled() {
+        return Vec::new();
+    }
+
+    changes
+        .iter()
+        .filter_map(|change| {
+            thresholds.evaluate(change).map(|reason| SyncAnomaly {
+                account_id: account_id.to_string(),
+                account_name: account_name.to_string(),
+                change: change.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+fn relative_change(previous: Decimal, current: Decimal) -> Option<Decimal> {
+    if previous.is_zero() {
+        return None;
+    }
+    Some(((current - previous) / previous).abs())
+}
+
+fn percent(ratio: Decimal) -> Decimal {
+    (ratio * Decimal::ONE_HUNDRED).round_dp(2).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn d(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn change(
+        prev_qty: Decimal,
+        qty: Decimal,
+        prev_cost: Decimal,
+        cost: Decimal,
+    ) -> PositionChange {
+        PositionChange {
+            asset_id: "AAPL".to_string(),
+            previous_quantity: prev_qty,
+            current_quantity: qty,
+            previous_cost_basis: prev_cost,
+            current_cost_basis: cost,
+        }
+    }
+
+    #[test]
+    fn disabled_thresholds_never_flag() {
+        let changes = vec![change(d("10"), d("1000"), d("100"), d("10000"))];
+        assert!(
+            detect_anomalies("acc", "Account", &changes, &AnomalyThresholds::default()).is_empty()
+        );
+    }
+
+    #[test]
+    fn change_above_ratio_is_flagged_and_normal_change_is_not() {
+        let thresholds = AnomalyThresholds {
+            max_change_ratio: Some(d("0.5")),
+            max_value_change: None,
+        };
+        let changes = vec![
+            change(d("10"), d("20"), d("1000"), d("2000")),
+            change(d("10"), d("11"), d("1000"), d("1100")),
+        ];
+
+        let anomalies = detect_anomalies("acc", "Account", &changes, &thresholds);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].change.current_quantity, d("20"));
+        assert!(anomalies[0].reason.contains("Quantity changed by 100%"));
+    }
+
+    #[test]
+    fn removed_position_exceeds_ratio() {
+        let thresholds = AnomalyThresholds {
+            max_change_ratio: Some(d("0.9")),
+            max_value_change: None,
+        };
+        let changes = vec![change(d("10"), d("0"), d("1000"), d("0"))];
+        assert_eq!(
+            detect_anomalies("acc", "Account", &changes, &thresholds).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn absolute_value_threshold_flags_large_moves() {
+        let thresholds = AnomalyThresholds {
+            max_change_ratio: None,
+            max_value_change: Some(d("5000")),
+        };
+        let big = change(d("100"), d("110"), d("100000"), d("110000"));
+        let small = change(d("100"), d("101"), d("100000"), d("101000"));
+        assert!(thresholds.evaluate(&big).is_some());
+        assert!(thresholds.evaluate(&small).is_none());
+    }
+}
@@ -0,0 +1,446 @@
+//! Health classification for broker connections.
+//!
+//! A connection reported as disabled or not connected during a sync counts as an auth
+//! failure. Consecutive failures are stored locally in the settings table, keyed by the
+//! cloud connection id, so a single transient failure only marks the connection
+//! `Degraded`; it becomes `Broken` once the configured threshold is reached.
+//!
+//! The connections listed by the last sync are kept alongside, so a status summary can be
+//! served without calling the cloud.
+//!
+//! Some brokers expire consent after a fixed period (e.g. 90 days). A connection whose consent
+//! lapses within the warning lead time is reported as expiring, once per remaining day, so the
+//! user can reconnect before sync stops working.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use wealthfolio_core::errors::{Error, Result};
+use wealthfolio_core::settings::SettingsServiceTrait;
+
+use super::models::{BrokerConnection, ConnectionExpiry, ConnectionHealth, ConnectionSummary};
+use crate::broker_ingest::BrokerSyncState;
+
+/// Settings key holding the `connection_id -> consecutive auth failures` map.
+pub const CONNECTION_AUTH_FAILURES_SETTING_KEY: &str = "connect_connection_auth_failures";
+
+/// Settings key holding the connections listed by the last sync.
+pub const OBSERVED_CONNECTIONS_SETTING_KEY: &str = "connect_observed_connections";
+
+/// Settings key holding the `connection_id -> days remaining` of the last expiry warning.
+pub const CONNECTION_EXPIRY_WARNINGS_SETTING_KEY: &str = "connect_connection_expiry_warnings";
+
+/// Consecutive auth failures before a connection is classified `Broken`.
+pub const DEFAULT_AUTH_FAILURE_THRESHOLD: u32 = 3;
+
+/// Days before consent lapses that a connection starts being reported as expiring.
+pub const DEFAULT_EXPIRY_WARNING_DAYS: u32 = 14;
+
+/// Classifies a connection from its consecutive auth failure count.
+pub fn classify_connection_health(consecutive_failures: u32, threshold: u32) -> ConnectionHealth {
+    if consecutive_failures == 0 {
+        ConnectionHealth::Healthy
+    } else if consecutive_failures < threshold.max(1) {
+        ConnectionHealth::Degraded
+    } else {
+        ConnectionHealth::Broken
+    }
+}
+
+fn is_auth_failure(connection: &BrokerConnection) -> bool {
+    connection.disabled
+        || connection
+            .status
+            .as_deref()
+            .is_some_and(|status| !status.eq_ignore_ascii_case("connected"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObservedConnection {
+    id: String,
+    auth_failure: bool,
+    disabled: bool,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    consent_expires_at: Option<String>,
+}
+
+impl ObservedConnection {
+    /// When consent lapses, if that falls within `lead_days` of `now`.
+    fn expiring_at(&self, now: DateTime<Utc>, lead_days: u32) -> Option<DateTime<Utc>> {
+        let expires_at = DateTime::parse_from_rfc3339(self.consent_expires_at.as_deref()?)
+            .ok()?
+            .with_timezone(&Utc);
+        (lead_days > 0 && expires_at <= now + chrono::Duration::days(i64::from(lead_days)))
+            .then_some(expires_at)
+    }
+}
+
+pub struct ConnectionHealthService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+    threshold: u32,
+    expiry_warning_days: u32,
+}
+
+impl ConnectionHealthService {
+    pub fn new(settings_service: Arc<dyn SettingsServiceTrait>, threshold: u32) -> Self {
+        Self {
+            settings_service,
+            threshold: threshold.max(1),
+            expiry_warning_days: DEFAULT_EXPIRY_WARNING_DAYS,
+        }
+    }
+
+    /// Lead time for expiry warnings; `0` disables them.
+    pub fn with_expiry_warning_days(mut self, days: u32) -> Self {
+        self.expiry_warning_days = days;
+        self
+    }
+
+    pub fn get_failure_counts(&self) -> Result<BTreeMap<String, u32>> {
+        Ok(self
+            .settings_service
+            .get_setting_value(CONNECTION_AUTH_FAILURES_SETTING_KEY)?
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    fn get_observed_connections(&self) -> Result<Vec<ObservedConnection>> {
+        Ok(self
+            .settings_service
+            .get_setting_value(OBSERVED_CONNECTIONS_SETTING_KEY)?
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default())
+    }
+
+    /// Records the connections seen by one sync: failing connections gain a consecutive
+    /// failure, healthy or no longer listed ones are reset.
+    pub async fn record_sync_observation(&self, connections: &[BrokerConnection]) -> Result<()> {
+        let observed: Vec<ObservedConnection> = connections
+            .iter()
+            .map(|connection| ObservedConnection {
+                id: connection.id.clone(),
+                auth_failure: is_auth_failure(connection),
+                disabled: connection.disabled,
+                name: connection.display_name().map(str::to_string),
+                consent_expires_at: connection.consent_expires_at.clone(),
+            })
+            .collect();
+        if observed != self.get_observed_connections()? {
+            let raw =
+                serde_json::to_string(&observed).map_err(|e| Error::Unexpected(e.to_string()))?;
+            self.settings_service
+                .set_setting_value(OBSERVED_CONNECTIONS_SETTING_KEY, &raw)
+                .await?;
+        }
+
+        let previous = self.get_failure_counts()?;
+        let counts: BTreeMap<String, u32> = connections
+            .iter()
+            .filter(|connection| is_auth_failure(connection))
+            .map(|connection| {
+                let failures = previous.get(&connection.id).copied().unwrap_or(0);
+                (connection.id.clone(), failures.saturating_add(1))
+            })
+            .collect();
+        if counts == previous {
+            return Ok(());
+        }
+
+        let raw = serde_json::to_string(&counts).map_err(|e| Error::Unexpected(e.to_string()))?;
+        self.settings_service
+            .set_setting_value(CONNECTION_AUTH_FAILURES_SETTING_KEY, &raw)
+            .await
+    }
+
+    /// Fills `health` on each connection. A failing connection that no sync has recorded
+    /// yet counts as a single failure.
+    pub fn apply_health(&self, connections: &mut [BrokerConnection]) -> Result<()> {
+        let counts = self.get_failure_counts()?;
+        for connection in connections {
+            let failures = match counts.get(&connection.id) {
+                Some(failures) => *failures,
+                None => u32::from(is_auth_failure(connection)),
+            };
+            connection.health = Some(classify_connection_health(failures, self.threshold));
+        }
+        Ok(())
+    }
+
+    /// Counts the connections listed by the last sync per health class. Reads local state
+    /// only; `last_sync_at` is the latest successful sync among `sync_states`.
+    pub fn get_connection_summary(
+        &self,
+        sync_states: &[BrokerSyncState],
+    ) -> Result<ConnectionSummary> {
+        let counts = self.get_failure_counts()?;
+        let mut summary = ConnectionSummary {
+            last_sync_at: sync_states
+                .iter()
+                .filter_map(|state| state.last_successful_at)
+                .max(),
+            ..Default::default()
+        };
+        for connection in self.get_observed_connections()? {
+            let failures = match counts.get(&connection.id) {
+                Some(failures) => *failures,
+                None => u32::from(connection.auth_failure),
+            };
+            match classify_connection_health(failures, self.threshold) {
+                ConnectionHealth::Healthy => summary.healthy += 1,
+                ConnectionHealth::Degraded => summary.degraded += 1,
+                ConnectionHealth::Broken => summary.broken += 1,
+            }
+            if connection.disabled {
+                summary.needs_reauth += 1;
+            }
+            if connection
+                .expiring_at(Utc::now(), self.expiry_warning_days)
+                .is_some()
+            {
+                summary.expiring += 1;
+            }
+            summary.total += 1;
+        }
+        Ok(summary)
+    }
+
+    /// Connections listed by the last sync whose consent lapses within the warning lead time,
+    /// soonest first. Reads local state only.
+    pub fn get_expiring_connections(&self, now: DateTime<Utc>) -> Result<Vec<ConnectionExpiry>> {
+        let counts = self.get_failure_counts()?;
+        let mut expiring: Vec<ConnectionExpiry> = self
+            .get_observed_connections()?
+            .into_iter()
+            .filter_map(|connection| {
+                let expires_at = connection.expiring_at(now, self.expiry_warning_days)?;
+                let failures = match counts.get(&connection.id) {
+                    Some(failures) => *failures,
+                    None => u32::from(connection.auth_failure),
+                };
+                Some(ConnectionExpiry {
+                    days_remaining: (expires_at - now).num_seconds().div_euclid(86_400),
+                    health: classify_connection_health(failures, self.threshold),
+                    connection_id: connection.id,
+                    name: connection.name,
+                    expires_at,
+                })
+            })
+            .collect();
+        expiring.sort_by_key(|expiry| expiry.expires_at);
+        Ok(expiring)
+    }
+
+    /// Expiring connections that have not been warned about with the same days remaining, so
+    /// repeated syncs on one day warn once. Call after [`Self::record_sync_observation`].
+    pub async fn take_expiry_warnings(&self, now: DateTime<Utc>) -> Result<Vec<ConnectionExpiry>> {
+        let expiring = self.get_expiring_connections(now)?;
+        let previous: BTreeMap<String, i64> = self
+            .settings_service
+            .get_setting_value(CONNECTION_EXPIRY_WARNINGS_SETTING_KEY)?
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        let warned: BTreeMap<String, i64> = expiring
+            .iter()
+            .map(|expiry| (expiry.connection_id.clone(), expiry.days_remaining))
+            .collect();
+        if warned != previous {
+            let raw =
+                serde_json::to_string(&warned).map_err(|e| Error::Unexpected(e.to_string()))?;
+            self.settings_service
+                .set_setting_value(CONNECTION_EXPIRY_WARNINGS_SETTING_KEY, &raw)
+                .await?;
+        }
+        Ok(expiring
+            .into_iter()
+            .filter(|expiry| previous.get(&expiry.connection_id) != Some(&expiry.days_remaining))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wealthfolio_core::settings::MemorySettingsService;
+
+    fn connection(id: &str, status: &str) -> BrokerConnection {
+        BrokerConnection {
+            id: id.to_string(),
+            brokerage: None,
+            connection_type: None,
+            status: Some(status.to_string()),
+            disabled: false,
+            disabled_date: None,
+            updated_at: None,
+            name: None,
+            consent_expires_at: None,
+            custom_name: None,
+            health: None,
+        }
+    }
+
+    fn health_after_sync(
+        service: &ConnectionHealthService,
+        listed: &BrokerConnection,
+    ) -> ConnectionHealth {
+        let mut connections = vec![listed.clone()];
+        service.apply_health(&mut connections).unwrap();
+        connections[0].health.unwrap()
+    }
+
+    #[tokio::test]
+    async fn single_auth_failure_degrades_and_nth_consecutive_breaks() {
+        let service = ConnectionHealthService::new(Arc::new(MemorySettingsService::default()), 3);
+        let failing = connection("conn-1", "disconnected");
+
+        service
+            .record_sync_observation(std::slice::from_ref(&failing))
+            .await
+            .unwrap();
+        assert_eq!(
+            health_after_sync(&service, &failing),
+            ConnectionHealth::Degraded
+        );
+
+        service
+            .record_sync_observation(std::slice::from_ref(&failing))
+            .await
+            .unwrap();
+        assert_eq!(
+            health_after_sync(&service, &failing),
+            ConnectionHealth::Degraded
+        );
+
+        service
+            .record_sync_observation(std::slice::from_ref(&failing))
+            .await
+            .unwrap();
+        assert_eq!(
+            health_after_sync(&service, &failing),
+            ConnectionHealth::Broken
+        );
+    }
+
+    #[tokio::test]
+    async fn summary_counts_connections_by_health() {
+        let service = ConnectionHealthService::new(Arc::new(MemorySettingsService::default()), 2);
+        let mut revoked = connection("conn-3", "connected");
+        revoked.disabled = true;
+        let first_sync = vec![
+            connection("conn-1", "connected"),
+            connection("conn-2", "disconnected"),
+            revoked.clone(),
+            connection("conn-4", "error"),
+        ];
+        service.record_sync_observation(&first_sync).await.unwrap();
+        // conn-2 recovers, conn-3 and conn-4 fail again and break, conn-5 fails for the first
+        // time.
+        let second_sync = vec![
+            connection("conn-1", "connected"),
+            connection("conn-2", "connected"),
+            revoked,
+            connection("conn-4", "error"),
+            connection("conn-5", "disconnected"),
+        ];
+        service.record_sync_observation(&second_sync).await.unwrap();
+
+        let mut synced = BrokerSyncState::new("acc-1".to_string(), "SNAPTRADE".to_string());
+        let last_sync_at = chrono::Utc::now();
+        synced.last_successful_at = Some(last_sync_at - chrono::Duration::hours(2));
+        let mut latest = BrokerSyncState::new("acc-2".to_string(), "SNAPTRADE".to_string());
+        latest.last_successful_at = Some(last_sync_at);
+        let never = BrokerSyncState::new("acc-3".to_string(), "PLAID".to_string());
+
+        let summary = service
+            .get_connection_summary(&[synced, latest, never])
+            .unwrap();
+        assert_eq!(
+            summary,
+            ConnectionSummary {
+                total: 5,
+                healthy: 2,
+                degraded: 1,
+                broken: 2,
+                needs_reauth: 1,
+                expiring: 0,
+                last_sync_at: Some(last_sync_at),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn summary_is_empty_before_the_first_sync() {
+        let service = ConnectionHealthService::new(Arc::new(MemorySettingsService::default()), 3);
+        assert_eq!(
+            service.get_connection_summary(&[]).unwrap(),
+            ConnectionSummary::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_expiring_within_the_lead_time_is_warned_once_per_day() {
+        let service = ConnectionHealthService::new(Arc::new(MemorySettingsService::default()), 3)
+            .with_expiry_warning_days(14);
+        let now = Utc::now();
+        let mut expiring = connection("conn-1", "connected");
+        expiring.name = Some("Questrade".to_string());
+        expiring.consent_expires_at = Some((now + chrono::Duration::days(5)).to_rfc3339());
+        let mut fresh = connection("conn-2", "connected");
+        fresh.consent_expires_at = Some((now + chrono::Duration::days(80)).to_rfc3339());
+        let never_expires = connection("conn-3", "connected");
+
+        service
+            .record_sync_observation(&[expiring, fresh, never_expires])
+            .await
+            .unwrap();
+
+        let warnings = service.take_expiry_warnings(now).await.unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].connection_id, "conn-1");
+        assert_eq!(warnings[0].name.as_deref(), Some("Questrade"));
+        assert_eq!(warnings[0].days_remaining, 5);
+        assert_eq!(warnings[0].health, ConnectionHealth::Healthy);
+        assert_eq!(service.get_connection_summary(&[]).unwrap().expiring, 1);
+
+        // A later sync the same day does not warn again; the next day does.
+        assert!(service.take_expiry_warnings(now).await.unwrap().is_empty());
+        let next_day = service
+            .take_expiry_warnings(now + chrono::Duration::days(1))
+            .await
+            .unwrap();
+        assert_eq!(next_day[0].days_remaining, 4);
+    }
+
+    #[tokio::test]
+    async fn successful_sync_resets_consecutive_failures() {
+        let service = ConnectionHealthService::new(Arc::new(MemorySettingsService::default()), 2);
+        let failing = connection("conn-1", "disconnected");
+        let recovered = connection("conn-1", "connected");
+
+        service
+            .record_sync_observation(std::slice::from_ref(&failing))
+            .await
+            .unwrap();
+        service
+            .record_sync_observation(std::slice::from_ref(&recovered))
+            .await
+            .unwrap();
+        assert_eq!(
+            health_after_sync(&service, &recovered),
+            ConnectionHealth::Healthy
+        );
+
+        service
+            .record_sync_observation(std::slice::from_ref(&failing))
+            .await
+            .unwrap();
+        assert_eq!(
+            health_after_sync(&service, &failing),
+            ConnectionHealth::Degraded
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! User-assigned display names for broker connections.
+//!
+//! Names are stored locally as a JSON map in the settings table, keyed by the cloud
+//! connection id. Connections without a custom name keep the cloud-provided name.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use wealthfolio_core::errors::{Error, Result, ValidationError};
+use wealthfolio_core::settings::SettingsServiceTrait;
+
+use super::models::BrokerConnection;
+
+/// Settings key holding the `connection_id -> name` map.
+pub const CONNECTION_NAMES_SETTING_KEY: &str = "connect_connection_names";
+
+/// Maximum length of a custom connection name, in characters.
+pub const MAX_CONNECTION_NAME_LENGTH: usize = 64;
+
+/// Serializes the read-modify-write of the stored map. Services are constructed per
+/// call, so the lock is process-wide rather than per instance.
+static NAMES_WRITE_LOCK: Mutex<()> = Mutex::const_new(());
+
+pub struct ConnectionNameService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+}
+
+impl ConnectionNameService {
+    pub fn new(settings_service: Arc<dyn SettingsServiceTrait>) -> Self {
+        Self { settings_service }
+    }
+
+    /// Returns the stored names. A stored value that is not a valid map is an error, so a
+    /// later write never silently replaces names it could not read.
+    pub fn get_names(&self) -> Result<BTreeMap<String, String>> {
+        match self
+            .settings_service
+            .get_setting_value(CONNECTION_NAMES_SETTING_KEY)?
+        {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                Error::Unexpected(format!("Stored connection names are invalid: {}", e))
+            }),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// Sets or clears (`None` or blank) the custom name of a connection and returns the
+    /// stored name.
+    pub async fn set_connection_name(
+        &self,
+        connection_id: &str,
+        name: Option<&str>,
+    ) -> Result<Option<String>> {
+        let connection_id = connection_id.trim();
+        if connection_id.is_empty() {
+            return Err(Error::Validation(ValidationError::MissingField(
+                "connectionId".to_string(),
+            )));
+        }
+        let name = name.map(str::trim).filter(|name| !name.is_empty());
+        if let Some(name) = name {
+            if name.chars().count() > MAX_CONNECTION_NAME_LENGTH {
+                return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                    "Connection name must be at most {} characters",
+                    MAX_CONNECTION_NAME_LENGTH
+                ))));
+            }
+        }
+
+        let _guard = NAMES_WRITE_LOCK.lock().await;
+        let mut names = self.get_names()?;
+        match name {
+            Some(name) => names.insert(connection_id.to_string(), name.to_string()),
+            None => names.remove(connection_id),
+        };
+        let raw = serde_json::to_string(&names).map_err(|e| Error::Unexpected(e.to_string()))?;
+        self.settings_service
+            .set_setting_value(CONNECTION_NAMES_SETTING_KEY, &raw)
+            .await?;
+
+        Ok(name.map(str::to_string))
+    }
+
+    /// Fills `custom_name` on each connection from the stored names.
+    pub fn apply_names(&self, connections: &mut [BrokerConnection]) -> Result<()> {
+        let names = self.get_names()?;
+        for connection in connections {
+            connection.custom_name = names.get(&connection.id).cloned();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wealthfolio_core::settings::MemorySettingsService;
+
+    fn connection(id: &str, name: &str) -> BrokerConnection {
+        BrokerConnection {
+            id: id.to_string(),
+            brokerage: None,
+            connection_type: None,
+            status: Some("connected".to_string()),
+            disabled: false,
+            disabled_date: None,
+            updated_at: None,
+            name: Some(name.to_string()),
+            consent_expires_at: None,
+            custom_name: None,
+            health: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_name_persists_and_clearing_falls_back_to_cloud_name() {
+        let settings: Arc<dyn SettingsServiceTrait> = Arc::new(MemorySettingsService::default());
+        let service = ConnectionNameService::new(settings.clone());
+
+        let stored = service
+            .set_connection_name("conn-1", Some("  My Roth IRA  "))
+            .await
+            .unwrap();
+        assert_eq!(stored.as_deref(), Some("My Roth IRA"));
+
+        // A fresh service over the same settings sees the persisted name.
+        let reloaded = ConnectionNameService::new(settings);
+        let mut connections = vec![connection("conn-1", "Broker X"), connection("conn-2", "Y")];
+        reloaded.apply_names(&mut connections).unwrap();
+        assert_eq!(connections[0].display_name(), Some("My Roth IRA"));
+        assert_eq!(connections[1].display_name(), Some("Y"));
+
+        reloaded
+            .set_connection_name("conn-1", Some(" "))
+            .await
+            .unwrap();
+        reloaded.apply_names(&mut connections).unwrap();
+        assert_eq!(connections[0].custom_name, None);
+        assert_eq!(connections[0].display_name(), Some("Broker X"));
+    }
+
+    #[tokio::test]
+    async fn rejects_names_over_the_length_limit() {
+        let service = ConnectionNameService::new(Arc::new(MemorySettingsService::default()));
+        let too_long = "x".repeat(MAX_CONNECTION_NAME_LENGTH + 1);
+
+        let result = service.set_connection_name("conn-1", Some(&too_long)).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Validation(ValidationError::InvalidInput(_)))
+        ));
+        assert!(service.get_names().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn corrupt_stored_names_are_not_overwritten() {
+        let settings = Arc::new(MemorySettingsService::default());
+        settings
+            .set_setting_value(CONNECTION_NAMES_SETTING_KEY, "{not json")
+            .await
+            .unwrap();
+        let service = ConnectionNameService::new(settings.clone());
+
+        let result = service.set_connection_name("conn-1", Some("Roth")).await;
+
+        assert!(matches!(result, Err(Error::Unexpected(_))));
+        assert_eq!(
+            settings
+                .get_setting_value(CONNECTION_NAMES_SETTING_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("{not json")
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_renames_of_different_connections_are_all_kept() {
+        let settings: Arc<dyn SettingsServiceTrait> = Arc::new(MemorySettingsService::default());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let settings = settings.clone();
+                tokio::spawn(async move {
+                    ConnectionNameService::new(settings)
+                        .set_connection_name(&format!("conn-{}", i), Some("Name"))
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let names = ConnectionNameService::new(settings).get_names().unwrap();
+        assert_eq!(names.len(), 8);
+    }
+}
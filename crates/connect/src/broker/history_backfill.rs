@@ -0,0 +1,244 @@
+//! Deferred history backfills for newly connected broker accounts.
+//!
+//! With [`SyncConfig::recent_first_days`](super::SyncConfig) set, an account's first activity
+//! sync only fetches recent activity so it shows up right away. The older history is recorded
+//! here as a pending backfill job and fetched afterwards by
+//! [`SyncOrchestrator::run_history_backfills`](super::SyncOrchestrator). Jobs are stored in the
+//! settings table, keyed by local account id, so they survive restarts.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use wealthfolio_core::errors::{Error, Result};
+use wealthfolio_core::settings::SettingsServiceTrait;
+
+/// Settings key holding the `account_id -> job` map.
+pub const HISTORY_BACKFILLS_SETTING_KEY: &str = "connect_history_backfills";
+
+/// Serializes the read-modify-write of the stored job map. A sync schedules jobs while a
+/// backfill run updates others, each through its own service, so the lock is process-wide.
+static JOBS_WRITE_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HistoryBackfillStatus {
+    Pending,
+    Running,
+    Complete,
+    Failed,
+}
+
+/// Older activity history still to be fetched for one account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryBackfillJob {
+    pub account_id: String,
+    pub account_name: String,
+    pub broker_account_id: String,
+    /// Activities up to this date (YYYY-MM-DD) are fetched; later ones came with the first sync.
+    pub end_date: String,
+    pub status: HistoryBackfillStatus,
+    pub fetched: u32,
+    pub inserted: u32,
+    pub error: Option<String>,
+    pub import_run_id: Option<String>,
+    pub scheduled_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl HistoryBackfillJob {
+    pub fn new(
+        account_id: impl Into<String>,
+        account_name: impl Into<String>,
+        broker_account_id: impl Into<String>,
+        end_date: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            account_id: account_id.into(),
+            account_name: account_name.into(),
+            broker_account_id: broker_account_id.into(),
+            end_date: end_date.into(),
+            status: HistoryBackfillStatus::Pending,
+            fetched: 0,
+            inserted: 0,
+            error: None,
+            import_run_id: None,
+            scheduled_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Pending jobs and failed ones (retried on the next run).
+    pub fn is_runnable(&self) -> bool {
+        matches!(
+            self.status,
+            HistoryBackfillStatus::Pending | HistoryBackfillStatus::Failed
+        )
+    }
+}
+
+pub struct HistoryBackfillService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+}
+
+impl HistoryBackfillService {
+    pub fn new(settings_service: Arc<dyn SettingsServiceTrait>) -> Self {
+        Self { settings_service }
+    }
+
+    /// Reads the stored jobs. A stored value that is not a valid job map is an error, so a
+    /// later write never silently drops jobs it could not read.
+    fn load(&self) -> Result<BTreeMap<String, HistoryBackfillJob>> {
+        match self
+            .settings_service
+            .get_setting_value(HISTORY_BACKFILLS_SETTING_KEY)?
+        {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                Error::Unexpected(format!("Stored history backfills are invalid: {}", e))
+            }),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    async fn store(&self, jobs: &BTreeMap<String, HistoryBackfillJob>) -> Result<()> {
+        let raw = serde_json::to_string(jobs).map_err(|e| Error::Unexpected(e.to_string()))?;
+        self.settings_service
+            .set_setting_value(HISTORY_BACKFILLS_SETTING_KEY, &raw)
+            .await
+    }
+
+    /// All jobs, oldest first.
+    pub fn list(&self) -> Result<Vec<HistoryBackfillJob>> {
+        let mut jobs: Vec<HistoryBackfillJob> = self.load()?.into_values().collect();
+        jobs.sort_by_key(|job| job.scheduled_at);
+        Ok(jobs)
+    }
+
+    pub fn runnable(&self) -> Result<Vec<HistoryBackfillJob>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(HistoryBackfillJob::is_runnable)
+            .collect())
+    }
+
+    /// Records a backfill for the account. An account gets at most one job; scheduling again
+    /// keeps the existing one.
+    pub async fn schedule(&self, job: HistoryBackfillJob) -> Result<bool> {
+        let _guard = JOBS_WRITE_LOCK.lock().await;
+        let mut jobs = self.load()?;
+        if jobs.contains_key(&job.account_id) {
+            return Ok(false);
+        }
+        jobs.insert(job.account_id.clone(), job);
+        self.store(&jobs).await?;
+        Ok(true)
+    }
+
+    /// Puts jobs left `Running` back to `Pending`. A job is saved as running before its fetch,
+    /// so one interrupted by a crash or restart would otherwise never run again. Call once at
+    /// startup, before any backfill run. Returns how many jobs were requeued.
+    pub async fn requeue_interrupted(&self) -> Result<usize> {
+        let _guard = JOBS_WRITE_LOCK.lock().await;
+        let mut jobs = self.load()?;
+        let now = Utc::now();
+        let mut requeued = 0;
+        for job in jobs.values_mut() {
+            if job.status == HistoryBackfillStatus::Running {
+                job.status = HistoryBackfillStatus::Pending;
+                job.updated_at = now;
+                requeued += 1;
+            }
+        }
+        if requeued > 0 {
+            self.store(&jobs).await?;
+        }
+        Ok(requeued)
+    }
+
+    /// Saves the job's progress, stamping `updated_at`.
+    pub async fn update(&self, mut job: HistoryBackfillJob) -> Result<()> {
+        let _guard = JOBS_WRITE_LOCK.lock().await;
+        let mut jobs = self.load()?;
+        job.updated_at = Utc::now();
+        jobs.insert(job.account_id.clone(), job);
+        self.store(&jobs).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wealthfolio_core::settings::MemorySettingsService;
+
+    #[tokio::test]
+    async fn interrupted_jobs_are_requeued_on_startup() {
+        let service = HistoryBackfillService::new(Arc::new(MemorySettingsService::default()));
+        service
+            .schedule(HistoryBackfillJob::new(
+                "acc-1",
+                "Brokerage",
+                "b-1",
+                "2024-01-01",
+            ))
+            .await
+            .unwrap();
+        service
+            .schedule(HistoryBackfillJob::new(
+                "acc-2",
+                "TFSA",
+                "b-2",
+                "2024-01-01",
+            ))
+            .await
+            .unwrap();
+        let mut running = service.list().unwrap().remove(0);
+        running.status = HistoryBackfillStatus::Running;
+        service.update(running).await.unwrap();
+        let mut complete = service.list().unwrap().remove(1);
+        complete.status = HistoryBackfillStatus::Complete;
+        service.update(complete).await.unwrap();
+        assert_eq!(service.runnable().unwrap().len(), 0);
+
+        assert_eq!(service.requeue_interrupted().await.unwrap(), 1);
+
+        let runnable = service.runnable().unwrap();
+        assert_eq!(runnable.len(), 1);
+        assert_eq!(runnable[0].account_id, "acc-1");
+        assert_eq!(runnable[0].status, HistoryBackfillStatus::Pending);
+        assert_eq!(service.requeue_interrupted().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn corrupt_stored_jobs_are_an_error_and_not_overwritten() {
+        let settings = Arc::new(MemorySettingsService::default());
+        settings
+            .set_setting_value(HISTORY_BACKFILLS_SETTING_KEY, "{not json")
+            .await
+            .unwrap();
+        let service = HistoryBackfillService::new(settings.clone());
+
+        assert!(matches!(service.list(), Err(Error::Unexpected(_))));
+        let result = service
+            .schedule(HistoryBackfillJob::new(
+                "acc-1",
+                "Brokerage",
+                "b-1",
+                "2024-01-01",
+            ))
+            .await;
+
+        assert!(matches!(result, Err(Error::Unexpected(_))));
+        assert_eq!(
+            settings
+                .get_setting_value(HISTORY_BACKFILLS_SETTING_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("{not json")
+        );
+    }
+}
@@ -1,17 +1,61 @@
+pub mod account_resync;
+pub mod activity_resume;
+pub mod anomaly;
+pub mod connection_health;
+pub mod connection_names;
+pub mod history_backfill;
 pub mod mapping;
 mod models;
 pub mod orchestrator;
 pub mod progress;
 mod service;
+pub mod subscription_override;
+pub mod subscription_status;
+pub mod sync_error;
 pub mod sync_readiness;
+pub mod sync_suspension;
 mod traits;
 
+pub use account_resync::{
+    fetched_activity_references, plan_stale_activity_removal, AccountResyncResult, ResyncScope,
+    ResyncWriteOutcome, StaleActivityPlan,
+};
+pub use activity_resume::{
+    ActivityPageCursor, ActivityResumeService, ACTIVITY_PAGE_CURSORS_SETTING_KEY,
+};
+pub use anomaly::{detect_anomalies, AnomalyThresholds};
+pub use connection_health::{
+    classify_connection_health, ConnectionHealthService, CONNECTION_AUTH_FAILURES_SETTING_KEY,
+    CONNECTION_EXPIRY_WARNINGS_SETTING_KEY, DEFAULT_AUTH_FAILURE_THRESHOLD,
+    DEFAULT_EXPIRY_WARNING_DAYS, OBSERVED_CONNECTIONS_SETTING_KEY,
+};
+pub use connection_names::{
+    ConnectionNameService, CONNECTION_NAMES_SETTING_KEY, MAX_CONNECTION_NAME_LENGTH,
+};
+pub use history_backfill::{
+    HistoryBackfillJob, HistoryBackfillService, HistoryBackfillStatus,
+    HISTORY_BACKFILLS_SETTING_KEY,
+};
 pub use models::*;
 pub use orchestrator::{SyncConfig, SyncOrchestrator};
 pub use progress::{NoOpProgressReporter, SyncProgressPayload, SyncProgressReporter, SyncStatus};
 pub use service::BrokerSyncService;
+pub use subscription_override::{
+    debug_endpoints_enabled, FakeSubscriptionState, SubscriptionOverride,
+    SubscriptionOverrideError, DEBUG_ENDPOINTS_ENV,
+};
+pub use subscription_status::{
+    broker_sync_status, resolve_broker_sync_subscription, subscription_grace_period_from_env,
+    SubscriptionDecision, SubscriptionStatus, SubscriptionStatusService,
+    DEFAULT_SUBSCRIPTION_GRACE_HOURS, SUBSCRIPTION_GRACE_HOURS_ENV,
+    SUBSCRIPTION_STATUS_SETTING_KEY,
+};
+pub use sync_error::BrokerSyncError;
 pub use sync_readiness::{
     provider_waterline_precedes_local_cursor, resolve_activity_readiness,
     resolve_holdings_readiness, should_advance_activity_cursor, ProviderReadiness,
 };
+pub use sync_suspension::{
+    SyncSuspensionService, DEFAULT_PERMANENT_FAILURE_THRESHOLD, SYNC_SUSPENSION_SETTING_KEY,
+};
 pub use traits::*;
@@ -1,7 +1,10 @@
 //! Models representing broker data from the cloud API.
 //! These models mirror Wealthfolio Connect API response structures.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use wealthfolio_core::activities::CurrencyMismatch;
+use wealthfolio_core::errors::CloudApiError;
 
 /// Broker account balance total (amount + currency)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -175,6 +178,75 @@ pub struct BrokerConnection {
 
     /// Connection name (user-assigned)
     pub name: Option<String>,
+
+    /// When the broker's consent for this connection lapses (RFC 3339), for brokers that
+    /// expire consent after a fixed period.
+    #[serde(default)]
+    pub consent_expires_at: Option<String>,
+
+    /// Local display name set through `ConnectionNameService`; never sent by the cloud.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_name: Option<String>,
+
+    /// Local health classification set through `ConnectionHealthService`; never sent by the
+    /// cloud.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<ConnectionHealth>,
+}
+
+/// Health of a broker connection, based on consecutive auth failures seen during sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ConnectionHealth {
+    Healthy,
+    /// Auth failed recently, but not enough times in a row to ask for a reconnect.
+    Degraded,
+    /// Auth failed on enough consecutive syncs that the user needs to reconnect.
+    Broken,
+}
+
+/// Compact connection status for widgets, built from local state only.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSummary {
+    /// Connections listed by the last sync.
+    pub total: u32,
+    pub healthy: u32,
+    pub degraded: u32,
+    pub broken: u32,
+    /// Connections the cloud reported as disabled; the user has to sign in to the broker again.
+    pub needs_reauth: u32,
+    /// Connections whose broker consent lapses within the warning lead time.
+    pub expiring: u32,
+    /// Most recent successful sync of any account.
+    pub last_sync_at: Option<DateTime<Utc>>,
+}
+
+/// A connection whose broker consent lapses soon; the user should reconnect before it does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionExpiry {
+    pub connection_id: String,
+    pub name: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    /// Whole days left; `0` on the last day, negative once consent has lapsed.
+    pub days_remaining: i64,
+    pub health: ConnectionHealth,
+}
+
+impl BrokerConnection {
+    /// The local custom name when set, otherwise the cloud-provided brokerage or
+    /// connection name.
+    pub fn display_name(&self) -> Option<&str> {
+        self.custom_name
+            .as_deref()
+            .or_else(|| {
+                self.brokerage
+                    .as_ref()
+                    .and_then(|b| b.display_name.as_deref().or(b.name.as_deref()))
+            })
+            .or(self.name.as_deref())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -428,6 +500,10 @@ pub struct SyncActivitiesResponse {
     /// IDs of newly created assets (for background enrichment)
     #[serde(default)]
     pub new_asset_ids: Vec<String>,
+    /// Activities whose currency differs from their account currency, when a
+    /// mismatch policy is configured.
+    #[serde(default)]
+    pub currency_mismatches: Vec<CurrencyMismatch>,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -595,6 +671,32 @@ pub struct SyncHoldingsResponse {
     /// IDs of newly created assets (for background enrichment)
     #[serde(default)]
     pub new_asset_ids: Vec<String>,
+    /// Position changes that exceeded the configured anomaly thresholds.
+    #[serde(default)]
+    pub anomalies: Vec<SyncAnomaly>,
+}
+
+/// Quantity and cost basis of a position before and after a holdings sync.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionChange {
+    pub asset_id: String,
+    pub previous_quantity: rust_decimal::Decimal,
+    pub current_quantity: rust_decimal::Decimal,
+    pub previous_cost_basis: rust_decimal::Decimal,
+    pub current_cost_basis: rust_decimal::Decimal,
+}
+
+/// A position change flagged for user review because it exceeded an anomaly threshold.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncAnomaly {
+    pub account_id: String,
+    pub account_name: String,
+    #[serde(flatten)]
+    pub change: PositionChange,
+    /// Human-readable reason the change was flagged
+    pub reason: String,
 }
 
 /// Position-level diff for a holdings sync, compared to the latest snapshot.
@@ -607,6 +709,9 @@ pub struct HoldingsDiff {
     pub removed_positions: usize,
     pub unchanged_positions: usize,
     pub snapshot_saved: bool,
+    /// Updated and removed positions (added positions are not included).
+    #[serde(default)]
+    pub position_changes: Vec<PositionChange>,
 }
 
 /// Information about a newly created account that needs user configuration.
@@ -625,6 +730,60 @@ pub struct NewAccountInfo {
     pub institution_name: Option<String>,
 }
 
+/// Why a broker sync finished without syncing anything.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SkipReason {
+    /// The user is signed in but has not connected a broker yet.
+    NoConnections,
+}
+
+/// What one broker connection contributed to a sync run.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSyncResult {
+    pub connection_id: String,
+    pub connection_name: Option<String>,
+    /// Accounts of this connection that synced without errors
+    pub accounts_synced: usize,
+    pub activities_upserted: usize,
+    pub positions_upserted: usize,
+    /// Accounts of this connection whose activities or holdings failed to sync
+    pub accounts_failed: usize,
+    pub accounts_warned: usize,
+    /// Why the connection's accounts could not be fetched; its accounts were skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The cloud's refusal behind `error`, when the accounts request got a response.
+    #[serde(skip)]
+    pub cloud_error: Option<CloudApiError>,
+    /// The connection's accounts that were considered for sync, including excluded ones
+    #[serde(default)]
+    pub accounts: Vec<AccountSyncResult>,
+}
+
+/// One account's part of a [`ConnectionSyncResult`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSyncResult {
+    pub account_id: String,
+    pub account_name: String,
+    pub activities_upserted: usize,
+    pub positions_upserted: usize,
+    pub failed: bool,
+    pub warned: bool,
+    /// Excluded from sync by configuration; nothing was fetched or written for it
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+impl ConnectionSyncResult {
+    /// Nothing of this connection synced.
+    pub fn is_failed(&self) -> bool {
+        self.error.is_some() || (self.accounts_failed > 0 && self.accounts_synced == 0)
+    }
+}
+
 /// Combined result from a full broker sync operation.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -643,6 +802,89 @@ pub struct SyncResult {
     pub holdings_synced: Option<SyncHoldingsResponse>,
     /// List of newly created accounts that need tracking mode configuration
     pub new_accounts: Option<Vec<NewAccountInfo>>,
+    /// Set when the sync was skipped before fetching any broker data
+    #[serde(default)]
+    pub skip_reason: Option<SkipReason>,
+    /// Per-connection breakdown of the activity and holdings totals above
+    #[serde(default)]
+    pub connection_results: Vec<ConnectionSyncResult>,
+    /// Set when the sync did not succeed but at least one connection still synced
+    #[serde(default)]
+    pub partial_success: bool,
+}
+
+/// What started a broker sync run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BrokerSyncTrigger {
+    Scheduler,
+    /// The user asked for a sync.
+    Manual,
+    /// The sync that runs right after signing in.
+    Login,
+}
+
+/// One finished broker sync run, successful or not, for "last sync" status.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerSyncSummary {
+    pub triggered_by: BrokerSyncTrigger,
+    pub success: bool,
+    pub activities_upserted: usize,
+    pub connections_synced: usize,
+    pub duration_ms: u64,
+    pub finished_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<SkipReason>,
+    /// Why the run failed; `None` when it completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Correlation ID the run's log lines carry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_id: Option<String>,
+}
+
+impl BrokerSyncSummary {
+    pub fn new<E: std::fmt::Display>(
+        outcome: &std::result::Result<SyncResult, E>,
+        triggered_by: BrokerSyncTrigger,
+        duration: std::time::Duration,
+        finished_at: DateTime<Utc>,
+    ) -> Self {
+        let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        match outcome {
+            Ok(result) => Self {
+                triggered_by,
+                success: result.success,
+                activities_upserted: result
+                    .activities_synced
+                    .as_ref()
+                    .map_or(0, |a| a.activities_upserted),
+                connections_synced: result.connections_synced.as_ref().map_or(0, |c| c.synced),
+                duration_ms,
+                finished_at,
+                skip_reason: result.skip_reason,
+                error: None,
+                sync_id: None,
+            },
+            Err(e) => Self {
+                triggered_by,
+                success: false,
+                activities_upserted: 0,
+                connections_synced: 0,
+                duration_ms,
+                finished_at,
+                skip_reason: None,
+                error: Some(e.to_string()),
+                sync_id: None,
+            },
+        }
+    }
+
+    pub fn with_sync_id(mut self, sync_id: impl Into<String>) -> Self {
+        self.sync_id = Some(sync_id.into());
+        self
+    }
 }
 
 impl BrokerAccount {
@@ -774,7 +1016,56 @@ fn map_broker_account_type(raw: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{map_broker_account_type, BrokerAccount};
+    use super::{
+        map_broker_account_type, BrokerAccount, BrokerSyncSummary, BrokerSyncTrigger,
+        SyncActivitiesResponse, SyncConnectionsResponse, SyncResult,
+    };
+    use chrono::Utc;
+    use std::time::Duration;
+
+    #[test]
+    fn sync_summary_counts_a_completed_run_and_keeps_a_failed_one() {
+        let finished_at = Utc::now();
+        let result = SyncResult {
+            success: true,
+            connections_synced: Some(SyncConnectionsResponse {
+                synced: 2,
+                platforms_created: 0,
+                platforms_updated: 2,
+            }),
+            activities_synced: Some(SyncActivitiesResponse {
+                activities_upserted: 14,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let summary = BrokerSyncSummary::new(
+            &Ok::<_, String>(result),
+            BrokerSyncTrigger::Scheduler,
+            Duration::from_millis(1_250),
+            finished_at,
+        );
+        assert!(summary.success);
+        assert_eq!(summary.activities_upserted, 14);
+        assert_eq!(summary.connections_synced, 2);
+        assert_eq!(summary.duration_ms, 1_250);
+
+        let failed = BrokerSyncSummary::new(
+            &Err("API error 503".to_string()),
+            BrokerSyncTrigger::Manual,
+            Duration::from_secs(2),
+            finished_at,
+        );
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(json["triggeredBy"], "manual");
+        assert_eq!(json["success"], false);
+        assert_eq!(json["error"], "API error 503");
+        assert!(json.get("skipReason").is_none());
+        assert!(json.get("syncId").is_none());
+
+        let json = serde_json::to_value(failed.with_sync_id("run-1")).unwrap();
+        assert_eq!(json["syncId"], "run-1");
+    }
 
     #[test]
     fn maps_direct_account_type_to_supported_constant() {
@@ -919,6 +1210,9 @@ pub struct UserTeam {
     pub canceled_at: Option<String>,
     pub country_code: Option<String>,
     pub created_at: Option<String>,
+    /// Set when the plan and status come from a debug override, not the cloud.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulated_subscription: Option<super::subscription_override::FakeSubscriptionState>,
 }
 
 /// User information from the cloud API
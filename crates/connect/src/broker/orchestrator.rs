@@ -6,19 +6,32 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+mod account_resync_phase;
 mod activity_pagination;
 mod activity_phase;
+mod history_backfill_phase;
 mod holdings_phase;
 
-use log::{debug, info};
+use log::{debug, info, warn};
+use rust_decimal::Decimal;
 
+use super::activity_resume::ActivityResumeService;
+use super::anomaly::AnomalyThresholds;
+use super::connection_health::{
+    ConnectionHealthService, DEFAULT_AUTH_FAILURE_THRESHOLD, DEFAULT_EXPIRY_WARNING_DAYS,
+};
+use super::history_backfill::HistoryBackfillService;
 use super::models::{
-    BrokerSyncStatusDetail, NewAccountInfo, SyncActivitiesResponse, SyncHoldingsResponse,
+    AccountSyncResult, AccountUniversalActivity, BrokerConnection, BrokerSyncStatusDetail,
+    ConnectionSyncResult, NewAccountInfo, SkipReason, SyncActivitiesResponse, SyncHoldingsResponse,
     SyncResult,
 };
 use super::progress::SyncProgressReporter;
+use super::sync_error::BrokerSyncError;
 use super::traits::{BrokerApiClient, BrokerSyncServiceTrait};
 use wealthfolio_core::accounts::{Account, TrackingMode};
+use wealthfolio_core::activities::CurrencyMismatch;
+use wealthfolio_core::settings::SettingsServiceTrait;
 
 /// Configuration for sync operations.
 #[derive(Debug, Clone)]
@@ -27,6 +40,20 @@ pub struct SyncConfig {
     pub page_limit: i64,
     /// Maximum number of pages to fetch per account (safety limit).
     pub max_pages: usize,
+    /// Thresholds for flagging suspicious holdings changes (disabled by default).
+    pub anomaly_thresholds: AnomalyThresholds,
+    /// Consecutive syncs a connection must fail auth before it is classified `Broken`.
+    pub auth_failure_threshold: u32,
+    /// Days before broker consent lapses that a connection is reported as expiring; `0`
+    /// disables the warning.
+    pub expiry_warning_days: u32,
+    /// On an account's first activity sync, fetch only this many days and leave older history
+    /// to a background backfill. `None` fetches the full history inline. Needs
+    /// [`SyncOrchestrator::with_history_backfill`]; without it history is always fetched inline.
+    pub recent_first_days: Option<u32>,
+    /// Accounts never synced, by local or provider account id. They still show up in the
+    /// connection results, marked skipped.
+    pub excluded_account_ids: HashSet<String>,
 }
 
 impl Default for SyncConfig {
@@ -34,16 +61,107 @@ impl Default for SyncConfig {
         Self {
             page_limit: 1000,
             max_pages: 10_000,
+            anomaly_thresholds: AnomalyThresholds::default(),
+            auth_failure_threshold: DEFAULT_AUTH_FAILURE_THRESHOLD,
+            expiry_warning_days: DEFAULT_EXPIRY_WARNING_DAYS,
+            recent_first_days: None,
+            excluded_account_ids: HashSet::new(),
         }
     }
 }
 
+impl SyncConfig {
+    /// Defaults overridden from the environment, shared by the server and the desktop app:
+    /// - `CONNECT_SYNC_ANOMALY_MAX_CHANGE_PCT`: flag positions whose quantity or value moves by
+    ///   more than this percentage in one sync (e.g. `50`).
+    /// - `CONNECT_SYNC_ANOMALY_MAX_VALUE_CHANGE`: flag positions whose value moves by more than
+    ///   this absolute amount in one sync.
+    ///
+    /// Both are disabled when unset. `CONNECT_AUTH_FAILURE_THRESHOLD` sets how many consecutive
+    /// syncs a connection must fail auth before it is reported as broken, and
+    /// `CONNECT_EXPIRY_WARNING_DAYS` how many days before broker consent lapses a connection is
+    /// reported as expiring (`0` disables the warning).
+    /// `BROKER_SYNC_RECENT_FIRST_DAYS` limits an account's first sync to that many days and
+    /// backfills older history in the background; unset fetches all history in the first sync.
+    /// `BROKER_SYNC_EXCLUDED_ACCOUNTS` is a comma-separated list of local or provider account
+    /// IDs that are never synced.
+    pub fn from_env() -> Self {
+        let env_decimal = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<Decimal>().ok())
+                .filter(|v| *v > Decimal::ZERO)
+        };
+        let defaults = Self::default();
+
+        Self {
+            anomaly_thresholds: AnomalyThresholds {
+                max_change_ratio: env_decimal("CONNECT_SYNC_ANOMALY_MAX_CHANGE_PCT")
+                    .map(|pct| pct / Decimal::ONE_HUNDRED),
+                max_value_change: env_decimal("CONNECT_SYNC_ANOMALY_MAX_VALUE_CHANGE"),
+            },
+            auth_failure_threshold: std::env::var("CONNECT_AUTH_FAILURE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .filter(|threshold| *threshold > 0)
+                .unwrap_or(defaults.auth_failure_threshold),
+            expiry_warning_days: std::env::var("CONNECT_EXPIRY_WARNING_DAYS")
+                .ok()
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .unwrap_or(defaults.expiry_warning_days),
+            recent_first_days: std::env::var("BROKER_SYNC_RECENT_FIRST_DAYS")
+                .ok()
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .filter(|days| *days > 0),
+            excluded_account_ids: std::env::var("BROKER_SYNC_EXCLUDED_ACCOUNTS")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|id| !id.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            ..defaults
+        }
+    }
+
+    /// Connection health tracking with this config's auth failure threshold and expiry lead time.
+    pub fn connection_health(
+        &self,
+        settings: Arc<dyn SettingsServiceTrait>,
+    ) -> ConnectionHealthService {
+        ConnectionHealthService::new(settings, self.auth_failure_threshold)
+            .with_expiry_warning_days(self.expiry_warning_days)
+    }
+
+    fn is_excluded(&self, job: &AccountSyncJob) -> bool {
+        self.excluded_account_ids.contains(&job.account_id)
+            || self.excluded_account_ids.contains(&job.broker_account_id)
+    }
+
+    /// Provider account ids of excluded accounts, resolving excluded local ids through the
+    /// accounts already linked to the provider.
+    fn excluded_provider_account_ids(&self, local_accounts: &[Account]) -> HashSet<String> {
+        let mut ids = self.excluded_account_ids.clone();
+        ids.extend(
+            local_accounts
+                .iter()
+                .filter(|account| self.excluded_account_ids.contains(&account.id))
+                .filter_map(|account| account.provider_account_id.clone()),
+        );
+        ids
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct AccountSyncJob {
     account_id: String,
     account_name: String,
     broker_account_id: String,
     tracking_mode: TrackingMode,
+    /// Connection the account belongs to, when the sync listed it.
+    connection_id: Option<String>,
 }
 
 impl AccountSyncJob {
@@ -53,6 +171,7 @@ impl AccountSyncJob {
             account_name: account.name,
             broker_account_id: account.provider_account_id?,
             tracking_mode: account.tracking_mode,
+            connection_id: None,
         })
     }
 
@@ -66,6 +185,16 @@ pub(super) struct ActivityQueryWindow {
     start_date: Option<String>,
     end_date: String,
     has_local_cursor: bool,
+    /// Set when older history is deferred to a backfill ending on this date.
+    backfill_end_date: Option<String>,
+}
+
+/// Persisted position of an activity sync, see [`SyncOrchestrator::with_activity_resume`].
+#[derive(Debug, Clone)]
+pub(super) struct ActivityResumePoint {
+    connection_id: String,
+    /// Offset of the first page to fetch.
+    offset: i64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -75,7 +204,19 @@ pub(super) struct ActivitySyncOutcome {
     assets_created: u32,
     needs_review: u32,
     new_asset_ids: Vec<String>,
+    currency_mismatches: Vec<CurrencyMismatch>,
     inconsistent_empty_page: bool,
+    /// Every fetched activity, when pages are collected rather than upserted.
+    collected: Vec<AccountUniversalActivity>,
+}
+
+/// What [`SyncOrchestrator::sync_account_activities`] does with each fetched page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ActivityPageHandling {
+    /// Upsert the page as it arrives.
+    Upsert,
+    /// Keep the page for the caller to write once the fetch is complete.
+    Collect,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -122,6 +263,9 @@ pub struct SyncOrchestrator<P: SyncProgressReporter> {
     sync_service: Arc<dyn BrokerSyncServiceTrait>,
     progress_reporter: Arc<P>,
     config: SyncConfig,
+    connection_health: Option<ConnectionHealthService>,
+    history_backfill: Option<HistoryBackfillService>,
+    activity_resume: Option<ActivityResumeService>,
 }
 
 impl<P: SyncProgressReporter> SyncOrchestrator<P> {
@@ -135,34 +279,93 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             sync_service,
             progress_reporter,
             config,
+            connection_health: None,
+            history_backfill: None,
+            activity_resume: None,
         }
     }
 
+    /// Records connection auth failures seen by each full sync and reports connections whose
+    /// consent is about to lapse.
+    pub fn with_connection_health(mut self, connection_health: ConnectionHealthService) -> Self {
+        self.connection_health = Some(connection_health);
+        self
+    }
+
+    /// Stores deferred history backfills, enabling [`SyncConfig::recent_first_days`].
+    pub fn with_history_backfill(mut self, history_backfill: HistoryBackfillService) -> Self {
+        self.history_backfill = Some(history_backfill);
+        self
+    }
+
+    /// Saves the activity page cursor of each account as it syncs, so an interrupted sync
+    /// resumes at the page it stopped on.
+    pub fn with_activity_resume(mut self, activity_resume: ActivityResumeService) -> Self {
+        self.activity_resume = Some(activity_resume);
+        self
+    }
+
     /// Perform a full sync: connections -> accounts -> activities.
     ///
     /// This is the main entry point for broker synchronization.
     /// Always emits sync-start and sync-complete/error events.
-    pub async fn sync_all(&self, api_client: &dyn BrokerApiClient) -> Result<SyncResult, String> {
+    pub async fn sync_all(
+        &self,
+        api_client: &dyn BrokerApiClient,
+    ) -> Result<SyncResult, BrokerSyncError> {
         info!("Starting broker data sync...");
+        self.run_sync(api_client, None).await
+    }
+
+    /// Sync a single connection and its accounts, e.g. when the cloud reports that its data
+    /// changed. Emits the same events as [`Self::sync_all`].
+    pub async fn sync_connection(
+        &self,
+        api_client: &dyn BrokerApiClient,
+        connection_id: &str,
+    ) -> Result<SyncResult, BrokerSyncError> {
+        info!(
+            "Starting broker data sync for connection {}...",
+            connection_id
+        );
+        self.run_sync(api_client, Some(connection_id)).await
+    }
+
+    async fn run_sync(
+        &self,
+        api_client: &dyn BrokerApiClient,
+        connection_id: Option<&str>,
+    ) -> Result<SyncResult, BrokerSyncError> {
         self.progress_reporter.report_sync_start();
 
         // Run the sync and ensure we always emit completion event
-        let result = self.sync_all_internal(api_client).await;
+        let result = self.sync_all_internal(api_client, connection_id).await;
 
         match &result {
             Ok(sync_result) => {
+                if let Some(anomalies) = sync_result
+                    .holdings_synced
+                    .as_ref()
+                    .map(|h| &h.anomalies)
+                    .filter(|a| !a.is_empty())
+                {
+                    self.progress_reporter.report_anomalies(anomalies);
+                }
                 self.progress_reporter.report_sync_complete(sync_result);
             }
             Err(err) => {
                 // Create a failed result to emit the error event
                 let failed_result = SyncResult {
                     success: false,
-                    message: err.clone(),
+                    message: err.to_string(),
                     connections_synced: None,
                     accounts_synced: None,
                     activities_synced: None,
                     holdings_synced: None,
                     new_accounts: None,
+                    skip_reason: None,
+                    connection_results: Vec::new(),
+                    partial_success: false,
                 };
                 self.progress_reporter.report_sync_complete(&failed_result);
             }
@@ -171,16 +374,55 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         result
     }
 
-    /// Internal sync logic that may fail at any step.
+    /// Internal sync logic that may fail at any step. With `connection_id`, only that
+    /// connection and its accounts are synced.
     async fn sync_all_internal(
         &self,
         api_client: &dyn BrokerApiClient,
-    ) -> Result<SyncResult, String> {
+        connection_id: Option<&str>,
+    ) -> Result<SyncResult, BrokerSyncError> {
         // Step 1: Sync connections (platforms)
-        let connections = api_client
-            .list_connections()
-            .await
-            .map_err(|e| e.to_string())?;
+        let mut connections = api_client.list_connections().await?;
+
+        if let Some(connection_health) = &self.connection_health {
+            if let Err(err) = connection_health
+                .record_sync_observation(&connections)
+                .await
+            {
+                warn!("Failed to record connection health: {}", err);
+            }
+            match connection_health
+                .take_expiry_warnings(chrono::Utc::now())
+                .await
+            {
+                Ok(expiring) if !expiring.is_empty() => {
+                    self.progress_reporter
+                        .report_expiring_connections(&expiring);
+                }
+                Ok(_) => {}
+                Err(err) => warn!("Failed to check connection consent expiry: {}", err),
+            }
+        }
+
+        // Health is observed over the full list; a targeted sync then narrows to one connection.
+        if let Some(connection_id) = connection_id {
+            connections.retain(|connection| connection.id == connection_id);
+            if connections.is_empty() {
+                return Err(format!("Connection {} not found", connection_id).into());
+            }
+        }
+
+        // Nothing to fetch until the user connects a broker.
+        if connections.is_empty() {
+            info!("Broker sync skipped: no broker connections");
+            return Ok(SyncResult {
+                success: true,
+                message: "No broker connections yet. Connect a broker to start syncing."
+                    .to_string(),
+                skip_reason: Some(SkipReason::NoConnections),
+                ..Default::default()
+            });
+        }
 
         let connections_result = self
             .sync_service
@@ -193,16 +435,31 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             connections_result.platforms_created, connections_result.platforms_updated
         );
 
-        // Step 2: Sync accounts (filter by sync_enabled)
-        let authorization_ids: Vec<String> = connections.iter().map(|c| c.id.clone()).collect();
-        let all_accounts = api_client
-            .list_accounts(if authorization_ids.is_empty() {
-                None
-            } else {
-                Some(authorization_ids)
-            })
-            .await
-            .map_err(|e| e.to_string())?;
+        // Step 2: Sync accounts (filter by sync_enabled). Each connection's accounts are
+        // fetched on their own, so one failing connection does not abort the others.
+        let mut all_accounts = Vec::new();
+        let mut connection_errors: HashMap<String, BrokerSyncError> = HashMap::new();
+        for connection in &connections {
+            match api_client
+                .list_accounts(Some(vec![connection.id.clone()]))
+                .await
+            {
+                Ok(accounts) => all_accounts.extend(accounts),
+                Err(err) => {
+                    warn!(
+                        "Failed to fetch accounts of connection {}: {}",
+                        connection.id, err
+                    );
+                    connection_errors.insert(connection.id.clone(), err.into());
+                }
+            }
+        }
+        // With nothing fetched, fail the run so callers can classify the error.
+        if connection_errors.len() == connections.len() {
+            return Err(connection_errors
+                .remove(&connections[0].id)
+                .unwrap_or_else(|| BrokerSyncError::Failed(String::new())));
+        }
 
         let provider_transaction_statuses: HashMap<String, BrokerSyncStatusDetail> = all_accounts
             .iter()
@@ -223,6 +480,17 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             })
             .collect();
 
+        // Broker account ID -> connection ID, for the per-connection breakdown
+        let account_connection_ids: HashMap<String, String> = all_accounts
+            .iter()
+            .filter_map(|account| {
+                Some((
+                    account.id.clone()?,
+                    account.brokerage_authorization.clone()?,
+                ))
+            })
+            .collect();
+
         // Track sync-enabled broker IDs for data sync
         let sync_enabled_broker_ids: HashSet<String> = all_accounts
             .iter()
@@ -230,10 +498,25 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             .filter_map(|a| a.id.clone())
             .collect();
 
-        // Only create/update local accounts for sync-enabled broker accounts
+        // Only create/update local accounts for sync-enabled broker accounts that are not
+        // excluded by configuration
+        let excluded_provider_ids = if self.config.excluded_account_ids.is_empty() {
+            HashSet::new()
+        } else {
+            let local_accounts = self
+                .sync_service
+                .get_synced_accounts()
+                .map_err(|e| format!("Failed to get synced accounts: {}", e))?;
+            self.config.excluded_provider_account_ids(&local_accounts)
+        };
         let accounts: Vec<_> = all_accounts
             .into_iter()
             .filter(|a| a.sync_enabled)
+            .filter(|a| {
+                !a.id
+                    .as_deref()
+                    .is_some_and(|id| excluded_provider_ids.contains(id))
+            })
             .collect();
 
         let accounts_result = self
@@ -251,9 +534,11 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         // - TRANSACTIONS mode: sync activities
         // - HOLDINGS mode: sync holdings (positions)
         // - NOT_SET mode: skip (needs user configuration first)
-        let (activities_result, holdings_result) = self
+        let (activities_result, holdings_result, mut connection_results) = self
             .sync_account_data(
                 api_client,
+                &connections,
+                &account_connection_ids,
                 &sync_enabled_broker_ids,
                 &provider_transaction_statuses,
                 &provider_holdings_statuses,
@@ -289,12 +574,24 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             Some(accounts_needing_setup)
         };
 
+        for connection_result in &mut connection_results {
+            if let Some(err) = connection_errors.remove(&connection_result.connection_id) {
+                connection_result.error = Some(err.to_string());
+                connection_result.cloud_error = err.cloud_error().cloned();
+            }
+        }
+        let connections_failed = connection_results
+            .iter()
+            .filter(|c| c.error.is_some())
+            .count();
+
         let total_failed = activities_result.accounts_failed + holdings_result.accounts_failed;
         let total_warnings = activities_result.accounts_warned + holdings_result.accounts_warned;
+        let success = total_failed == 0 && connections_failed == 0;
         let result = SyncResult {
-            success: total_failed == 0,
+            success,
             message: format!(
-                "Sync completed. {} accounts created, {} activities synced, {} holdings synced{}{}",
+                "Sync completed. {} accounts created, {} activities synced, {} holdings synced{}{}{}",
                 accounts_result.created,
                 activities_result.activities_upserted,
                 holdings_result.positions_upserted,
@@ -311,6 +608,15 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                         total_warnings,
                         if total_warnings == 1 { "" } else { "s" }
                     )
+                },
+                if connections_failed == 0 {
+                    "".to_string()
+                } else {
+                    format!(
+                        " ({} connection{} failed).",
+                        connections_failed,
+                        if connections_failed == 1 { "" } else { "s" }
+                    )
                 }
             ),
             connections_synced: Some(connections_result),
@@ -318,6 +624,9 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             activities_synced: Some(activities_result),
             holdings_synced: Some(holdings_result),
             new_accounts,
+            skip_reason: None,
+            partial_success: !success && connection_results.iter().any(|c| !c.is_failed()),
+            connection_results,
         };
 
         Ok(result)
@@ -353,7 +662,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                 continue;
             };
 
-            if job.tracking_mode != TrackingMode::Transactions {
+            if job.tracking_mode != TrackingMode::Transactions || self.config.is_excluded(&job) {
                 continue;
             }
 
@@ -374,13 +683,24 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
     /// - TRANSACTIONS mode: sync activities
     /// - HOLDINGS mode: sync holdings (positions)
     /// - NOT_SET mode: skip (needs user configuration first)
+    ///
+    /// Also returns what each of `connections` contributed, in the same order.
     async fn sync_account_data(
         &self,
         api_client: &dyn BrokerApiClient,
+        connections: &[BrokerConnection],
+        account_connection_ids: &HashMap<String, String>,
         sync_enabled_broker_ids: &HashSet<String>,
         provider_transaction_statuses: &HashMap<String, BrokerSyncStatusDetail>,
         provider_holdings_statuses: &HashMap<String, BrokerSyncStatusDetail>,
-    ) -> Result<(SyncActivitiesResponse, SyncHoldingsResponse), String> {
+    ) -> Result<
+        (
+            SyncActivitiesResponse,
+            SyncHoldingsResponse,
+            Vec<ConnectionSyncResult>,
+        ),
+        String,
+    > {
         let synced_accounts = self
             .sync_service
             .get_synced_accounts()
@@ -388,11 +708,42 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
 
         let mut activities_summary = SyncActivitiesResponse::default();
         let mut holdings_summary = SyncHoldingsResponse::default();
+        let mut connection_results: Vec<ConnectionSyncResult> = connections
+            .iter()
+            .map(|connection| ConnectionSyncResult {
+                connection_id: connection.id.clone(),
+                connection_name: connection.display_name().map(str::to_string),
+                ..Default::default()
+            })
+            .collect();
 
         for account in synced_accounts {
-            let Some(job) = AccountSyncJob::from_account(account) else {
+            let Some(mut job) = AccountSyncJob::from_account(account) else {
                 continue;
             };
+            job.connection_id = account_connection_ids.get(&job.broker_account_id).cloned();
+
+            let connection_result = job.connection_id.as_ref().and_then(|connection_id| {
+                connection_results
+                    .iter_mut()
+                    .find(|result| &result.connection_id == connection_id)
+            });
+
+            if self.config.is_excluded(&job) {
+                info!(
+                    "Skipping sync for account '{}' (excluded by configuration)",
+                    job.account_name
+                );
+                if let Some(connection_result) = connection_result {
+                    connection_result.accounts.push(AccountSyncResult {
+                        account_id: job.account_id.clone(),
+                        account_name: job.account_name.clone(),
+                        skipped: true,
+                        ..Default::default()
+                    });
+                }
+                continue;
+            }
 
             if !sync_enabled_broker_ids.contains(&job.broker_account_id) {
                 info!(
@@ -423,27 +774,56 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                 activity_import_run_id,
                 continue_account,
             } = activity_result;
+            let mut account_result = AccountSyncResult {
+                account_id: job.account_id.clone(),
+                account_name: job.account_name.clone(),
+                activities_upserted: summary.activities_upserted,
+                failed: summary.accounts_failed > 0,
+                warned: summary.accounts_warned > 0,
+                ..Default::default()
+            };
             Self::merge_activities_summary(&mut activities_summary, summary);
 
-            if !continue_account || !job.is_holdings_mode() {
-                continue;
+            if continue_account && job.is_holdings_mode() {
+                let holdings_result = self
+                    .sync_holdings_phase(
+                        api_client,
+                        &job,
+                        provider_holdings_statuses.get(&job.broker_account_id),
+                        HoldingsPhaseContext {
+                            activity_warning,
+                            activity_import_run_id,
+                        },
+                    )
+                    .await;
+                account_result.positions_upserted = holdings_result.positions_upserted;
+                account_result.failed |= holdings_result.accounts_failed > 0;
+                account_result.warned |= holdings_result.accounts_warned > 0;
+                Self::merge_holdings_summary(&mut holdings_summary, holdings_result);
             }
 
-            let holdings_result = self
-                .sync_holdings_phase(
-                    api_client,
-                    &job,
-                    provider_holdings_statuses.get(&job.broker_account_id),
-                    HoldingsPhaseContext {
-                        activity_warning,
-                        activity_import_run_id,
-                    },
-                )
-                .await;
-            Self::merge_holdings_summary(&mut holdings_summary, holdings_result);
+            if let Some(connection_result) = connection_result {
+                Self::merge_connection_result(connection_result, account_result);
+            }
         }
 
-        Ok((activities_summary, holdings_summary))
+        Ok((activities_summary, holdings_summary, connection_results))
+    }
+
+    /// Adds one account's counts to its connection's. An account that failed in either phase
+    /// counts once as failed, never as synced.
+    fn merge_connection_result(total: &mut ConnectionSyncResult, account: AccountSyncResult) {
+        if account.failed {
+            total.accounts_failed += 1;
+        } else {
+            total.accounts_synced += 1;
+        }
+        if account.warned {
+            total.accounts_warned += 1;
+        }
+        total.activities_upserted += account.activities_upserted;
+        total.positions_upserted += account.positions_upserted;
+        total.accounts.push(account);
     }
 
     fn merge_activities_summary(total: &mut SyncActivitiesResponse, delta: SyncActivitiesResponse) {
@@ -453,6 +833,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         total.accounts_failed += delta.accounts_failed;
         total.accounts_warned += delta.accounts_warned;
         total.new_asset_ids.extend(delta.new_asset_ids);
+        total.currency_mismatches.extend(delta.currency_mismatches);
     }
 
     fn merge_holdings_summary(total: &mut SyncHoldingsResponse, delta: SyncHoldingsResponse) {
@@ -463,6 +844,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         total.accounts_failed += delta.accounts_failed;
         total.accounts_warned += delta.accounts_warned;
         total.new_asset_ids.extend(delta.new_asset_ids);
+        total.anomalies.extend(delta.anomalies);
     }
 }
 
@@ -470,7 +852,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
 mod tests {
     use super::*;
     use async_trait::async_trait;
-    use chrono::{DateTime, Utc};
+    use chrono::{DateTime, NaiveDate, Utc};
     use std::sync::Mutex;
 
     #[test]
@@ -478,11 +860,16 @@ mod tests {
         let config = SyncConfig::default();
         assert_eq!(config.page_limit, 1000);
         assert_eq!(config.max_pages, 10_000);
+        assert!(!config.anomaly_thresholds.is_enabled());
+        assert_eq!(config.recent_first_days, None);
     }
 
+    use super::super::account_resync::{ResyncWriteOutcome, StaleActivityPlan};
+    use super::super::activity_resume::ActivityResumeService;
+    use super::super::history_backfill::HistoryBackfillStatus;
     use super::super::models::{
         AccountUniversalActivity, BrokerAccount, BrokerAccountSyncStatus, BrokerBrokerage,
-        BrokerConnection, BrokerHoldingsResponse, HoldingsBalance, HoldingsDiff,
+        BrokerConnection, BrokerHoldingsResponse, ConnectionExpiry, HoldingsBalance, HoldingsDiff,
         HoldingsOptionPosition, HoldingsPosition, PaginatedUniversalActivity, PaginationDetails,
         SyncAccountsResponse, SyncConnectionsResponse,
     };
@@ -490,9 +877,10 @@ mod tests {
     use super::super::traits::BrokerSyncServiceTrait;
     use crate::broker_ingest::{
         BrokerSyncState, ImportRun, ImportRunMode, ImportRunStatus, ImportRunSummary,
-        ImportRunType, ReviewMode,
+        ImportRunTotals, ImportRunType, ImportRunWindow, ReviewMode,
     };
     use wealthfolio_core::accounts::Account;
+    use wealthfolio_core::settings::MemorySettingsService;
     use wealthfolio_core::Result;
 
     #[derive(Default)]
@@ -500,19 +888,55 @@ mod tests {
         broker_accounts: Vec<BrokerAccount>,
         activity_pages: Mutex<Vec<PaginatedUniversalActivity>>,
         activity_calls: Mutex<usize>,
+        activity_windows: Mutex<Vec<(Option<String>, Option<String>)>>,
+        activity_accounts: Mutex<Vec<String>>,
+        activity_offsets: Mutex<Vec<Option<i64>>>,
+        /// Activity request (1-based) that fails with a server error.
+        failing_activity_call: Option<usize>,
+        connections: Vec<BrokerConnection>,
+        /// Connections whose accounts cannot be listed.
+        failing_connections: HashSet<String>,
+        list_accounts_requests: Mutex<Vec<Option<Vec<String>>>>,
     }
 
     #[async_trait]
     impl BrokerApiClient for MockBrokerApiClient {
         async fn list_connections(&self) -> Result<Vec<BrokerConnection>> {
-            Ok(Vec::new())
+            Ok(self.connections.clone())
         }
 
         async fn list_accounts(
             &self,
-            _authorization_ids: Option<Vec<String>>,
+            authorization_ids: Option<Vec<String>>,
         ) -> Result<Vec<BrokerAccount>> {
-            Ok(self.broker_accounts.clone())
+            self.list_accounts_requests
+                .lock()
+                .unwrap()
+                .push(authorization_ids.clone());
+            let Some(ids) = authorization_ids else {
+                return Ok(self.broker_accounts.clone());
+            };
+            if ids.iter().any(|id| self.failing_connections.contains(id)) {
+                return Err(wealthfolio_core::errors::CloudApiError::Status {
+                    status: 500,
+                    code: None,
+                    message: None,
+                    request: "clientRequestId=req-1, requestId=none".to_string(),
+                }
+                .into());
+            }
+            // Accounts without a connection belong to whichever connection is asked for.
+            Ok(self
+                .broker_accounts
+                .iter()
+                .filter(|account| {
+                    account
+                        .brokerage_authorization
+                        .as_ref()
+                        .is_none_or(|id| ids.contains(id))
+                })
+                .cloned()
+                .collect())
         }
 
         async fn list_brokerages(&self) -> Result<Vec<BrokerBrokerage>> {
@@ -521,13 +945,31 @@ mod tests {
 
         async fn get_account_activities(
             &self,
-            _account_id: &str,
-            _start_date: Option<&str>,
-            _end_date: Option<&str>,
-            _offset: Option<i64>,
+            account_id: &str,
+            start_date: Option<&str>,
+            end_date: Option<&str>,
+            offset: Option<i64>,
             _limit: Option<i64>,
         ) -> Result<PaginatedUniversalActivity> {
-            *self.activity_calls.lock().unwrap() += 1;
+            let call = {
+                let mut calls = self.activity_calls.lock().unwrap();
+                *calls += 1;
+                *calls
+            };
+            self.activity_offsets.lock().unwrap().push(offset);
+            if self.failing_activity_call == Some(call) {
+                return Err(wealthfolio_core::Error::Unexpected(
+                    "API error 503 (request_id=req-1)".to_string(),
+                ));
+            }
+            self.activity_accounts
+                .lock()
+                .unwrap()
+                .push(account_id.to_string());
+            self.activity_windows
+                .lock()
+                .unwrap()
+                .push((start_date.map(str::to_string), end_date.map(str::to_string)));
             let mut pages = self.activity_pages.lock().unwrap();
             if pages.is_empty() {
                 return Ok(PaginatedUniversalActivity::default());
@@ -544,8 +986,11 @@ mod tests {
     struct MockSyncService {
         accounts: Vec<Account>,
         activity_state: Option<BrokerSyncState>,
-        upsert_result: (usize, usize, Vec<String>, usize),
+        upsert_result: (usize, usize, Vec<String>, usize, Vec<CurrencyMismatch>),
         holdings_result: (HoldingsDiff, usize, Vec<String>),
+        stale_plan: StaleActivityPlan,
+        /// Local account IDs whose activity sync cannot start.
+        failing_accounts: HashSet<String>,
         calls: Mutex<MockSyncServiceCalls>,
     }
 
@@ -555,6 +1000,12 @@ mod tests {
         activity_failures: Vec<(String, String, Option<String>)>,
         activity_needs_review: Vec<(String, String, Option<String>)>,
         finalized_import_runs: Vec<(String, ImportRunSummary, ImportRunStatus, Option<String>)>,
+        created_import_runs: Vec<ImportRunMode>,
+        upserted_accounts: Vec<String>,
+        /// Provider account ids passed to `sync_accounts`, i.e. account rows written.
+        synced_broker_accounts: Vec<String>,
+        /// (account id, fetched activity ids, window start) of each resync write.
+        resync_writes: Vec<(String, Vec<String>, Option<NaiveDate>)>,
         save_holdings_calls: usize,
     }
 
@@ -573,8 +1024,13 @@ mod tests {
 
         async fn sync_accounts(
             &self,
-            _broker_accounts: Vec<BrokerAccount>,
+            broker_accounts: Vec<BrokerAccount>,
         ) -> Result<SyncAccountsResponse> {
+            self.calls
+                .lock()
+                .unwrap()
+                .synced_broker_accounts
+                .extend(broker_accounts.into_iter().filter_map(|account| account.id));
             Ok(SyncAccountsResponse {
                 synced: 0,
                 created: 0,
@@ -601,19 +1057,50 @@ mod tests {
             Ok(self.activity_state.clone())
         }
 
-        async fn mark_activity_sync_attempt(&self, _account_id: String) -> Result<()> {
+        async fn mark_activity_sync_attempt(&self, account_id: String) -> Result<()> {
+            if self.failing_accounts.contains(&account_id) {
+                return Err(wealthfolio_core::Error::Unexpected(
+                    "database is locked".to_string(),
+                ));
+            }
             Ok(())
         }
 
         async fn upsert_account_activities(
             &self,
-            _account_id: String,
+            account_id: String,
             _import_run_id: Option<String>,
             _activities: Vec<AccountUniversalActivity>,
-        ) -> Result<(usize, usize, Vec<String>, usize)> {
+        ) -> Result<(usize, usize, Vec<String>, usize, Vec<CurrencyMismatch>)> {
+            self.calls
+                .lock()
+                .unwrap()
+                .upserted_accounts
+                .push(account_id);
             Ok(self.upsert_result.clone())
         }
 
+        async fn resync_account_activities(
+            &self,
+            account_id: String,
+            _import_run_id: Option<String>,
+            activities: Vec<AccountUniversalActivity>,
+            since: Option<NaiveDate>,
+        ) -> Result<ResyncWriteOutcome> {
+            let activity_ids = activities.into_iter().filter_map(|a| a.id).collect();
+            self.calls
+                .lock()
+                .unwrap()
+                .resync_writes
+                .push((account_id, activity_ids, since));
+            Ok(ResyncWriteOutcome {
+                upserted: self.upsert_result.0,
+                assets_created: self.upsert_result.1,
+                needs_review: self.upsert_result.3,
+                plan: self.stale_plan.clone(),
+            })
+        }
+
         async fn finalize_activity_sync_success(
             &self,
             account_id: String,
@@ -669,11 +1156,28 @@ mod tests {
             Ok(Vec::new())
         }
 
+        fn get_sync_runs_between(
+            &self,
+            _from: DateTime<Utc>,
+            _to: DateTime<Utc>,
+        ) -> Result<ImportRunWindow> {
+            Ok(ImportRunWindow::default())
+        }
+
+        fn get_sync_run_totals(&self) -> Result<ImportRunTotals> {
+            Ok(ImportRunTotals::default())
+        }
+
         async fn create_import_run(
             &self,
             account_id: &str,
             mode: ImportRunMode,
         ) -> Result<ImportRun> {
+            self.calls
+                .lock()
+                .unwrap()
+                .created_import_runs
+                .push(mode.clone());
             Ok(ImportRun::new(
                 account_id.to_string(),
                 "TEST".to_string(),
@@ -770,6 +1274,302 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn sync_without_connections_skips_before_fetching_broker_data() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![synced_account(
+                "account-1",
+                "broker-1",
+                TrackingMode::Transactions,
+            )],
+            ..MockSyncService::default()
+        });
+        let api_client = MockBrokerApiClient::default();
+
+        let result = orchestrator(service).sync_all(&api_client).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.skip_reason, Some(SkipReason::NoConnections));
+        assert!(result.accounts_synced.is_none());
+        assert!(result.activities_synced.is_none());
+        assert!(api_client.list_accounts_requests.lock().unwrap().is_empty());
+        assert_eq!(*api_client.activity_calls.lock().unwrap(), 0);
+    }
+
+    fn connection(id: &str) -> BrokerConnection {
+        BrokerConnection {
+            id: id.to_string(),
+            brokerage: None,
+            connection_type: None,
+            status: Some("connected".to_string()),
+            disabled: false,
+            disabled_date: None,
+            updated_at: None,
+            name: None,
+            consent_expires_at: None,
+            custom_name: None,
+            health: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn connection_sync_only_fetches_accounts_of_that_connection() {
+        let api_client = MockBrokerApiClient {
+            connections: vec![connection("conn-1"), connection("conn-2")],
+            ..MockBrokerApiClient::default()
+        };
+        let orchestrator = orchestrator(Arc::new(MockSyncService::default()));
+
+        let result = orchestrator
+            .sync_connection(&api_client, "conn-2")
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            *api_client.list_accounts_requests.lock().unwrap(),
+            vec![Some(vec!["conn-2".to_string()])]
+        );
+
+        let missing = orchestrator.sync_connection(&api_client, "conn-3").await;
+        assert!(missing.is_err());
+        assert_eq!(api_client.list_accounts_requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn sync_breaks_totals_down_per_connection_and_shows_a_failed_one() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![
+                synced_account("account-1", "broker-1", TrackingMode::Transactions),
+                synced_account("account-2", "broker-2", TrackingMode::Transactions),
+                synced_account("account-3", "broker-3", TrackingMode::Transactions),
+            ],
+            upsert_result: (2, 0, Vec::new(), 0, Vec::new()),
+            failing_accounts: HashSet::from(["account-3".to_string()]),
+            ..MockSyncService::default()
+        });
+        let activity_page = || PaginatedUniversalActivity {
+            data: vec![AccountUniversalActivity {
+                id: Some("activity".to_string()),
+                ..AccountUniversalActivity::default()
+            }],
+            pagination: Some(PaginationDetails {
+                has_more: Some(false),
+                total: Some(1),
+                ..PaginationDetails::default()
+            }),
+        };
+        let connected_account = |id: &str, connection_id: &str| BrokerAccount {
+            brokerage_authorization: Some(connection_id.to_string()),
+            sync_enabled: true,
+            ..broker_account(id, Some(ready_status("2026-05-22", None)), None)
+        };
+        let account_result =
+            |id: &str, activities_upserted: usize, failed: bool| AccountSyncResult {
+                account_id: id.to_string(),
+                account_name: "Brokerage".to_string(),
+                activities_upserted,
+                failed,
+                ..Default::default()
+            };
+        let mut first = connection("conn-1");
+        first.custom_name = Some("Joint".to_string());
+        let api_client = MockBrokerApiClient {
+            connections: vec![first, connection("conn-2")],
+            broker_accounts: vec![
+                connected_account("broker-1", "conn-1"),
+                connected_account("broker-2", "conn-1"),
+                connected_account("broker-3", "conn-2"),
+            ],
+            activity_pages: Mutex::new(vec![activity_page(), activity_page()]),
+            ..MockBrokerApiClient::default()
+        };
+
+        let result = orchestrator(service).sync_all(&api_client).await.unwrap();
+
+        assert!(!result.success);
+        assert_eq!(
+            result
+                .activities_synced
+                .as_ref()
+                .unwrap()
+                .activities_upserted,
+            4
+        );
+        assert_eq!(
+            result.connection_results,
+            vec![
+                ConnectionSyncResult {
+                    connection_id: "conn-1".to_string(),
+                    connection_name: Some("Joint".to_string()),
+                    accounts_synced: 2,
+                    activities_upserted: 4,
+                    accounts: vec![
+                        account_result("account-1", 2, false),
+                        account_result("account-2", 2, false),
+                    ],
+                    ..Default::default()
+                },
+                ConnectionSyncResult {
+                    connection_id: "conn-2".to_string(),
+                    accounts_failed: 1,
+                    accounts: vec![account_result("account-3", 0, true)],
+                    ..Default::default()
+                },
+            ]
+        );
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["connectionResults"][1]["accountsFailed"], 1);
+    }
+
+    #[tokio::test]
+    async fn sync_continues_with_the_next_connection_when_one_fails() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![
+                synced_account("account-1", "broker-1", TrackingMode::Transactions),
+                synced_account("account-2", "broker-2", TrackingMode::Transactions),
+            ],
+            upsert_result: (1, 0, Vec::new(), 0, Vec::new()),
+            ..MockSyncService::default()
+        });
+        let connected_account = |id: &str, connection_id: &str| BrokerAccount {
+            brokerage_authorization: Some(connection_id.to_string()),
+            sync_enabled: true,
+            ..broker_account(id, Some(ready_status("2026-05-22", None)), None)
+        };
+        let api_client = MockBrokerApiClient {
+            connections: vec![connection("conn-1"), connection("conn-2")],
+            failing_connections: HashSet::from(["conn-1".to_string()]),
+            broker_accounts: vec![
+                connected_account("broker-1", "conn-1"),
+                connected_account("broker-2", "conn-2"),
+            ],
+            activity_pages: Mutex::new(vec![PaginatedUniversalActivity {
+                data: vec![AccountUniversalActivity {
+                    id: Some("activity-1".to_string()),
+                    ..AccountUniversalActivity::default()
+                }],
+                pagination: Some(PaginationDetails {
+                    has_more: Some(false),
+                    total: Some(1),
+                    ..PaginationDetails::default()
+                }),
+            }]),
+            ..MockBrokerApiClient::default()
+        };
+        let orchestrator = orchestrator(service);
+
+        let result = orchestrator.sync_all(&api_client).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result.partial_success);
+        assert!(result.message.contains("1 connection failed"));
+        assert_eq!(
+            *api_client.activity_accounts.lock().unwrap(),
+            vec!["broker-2"]
+        );
+        let [failed, synced] = result.connection_results.as_slice() else {
+            panic!("expected two connection results");
+        };
+        assert_eq!(failed.connection_id, "conn-1");
+        assert!(failed.error.as_deref().unwrap().contains("API error 500"));
+        assert_eq!(
+            failed.cloud_error.as_ref().and_then(|error| error.status()),
+            Some(500)
+        );
+        assert_eq!(synced.connection_id, "conn-2");
+        assert_eq!(synced.error, None);
+        assert_eq!(synced.accounts_synced, 1);
+        assert_eq!(synced.activities_upserted, 1);
+
+        // A connection that is synced alone still fails the run.
+        let only_failing = orchestrator.sync_connection(&api_client, "conn-1").await;
+        assert_eq!(
+            only_failing
+                .unwrap_err()
+                .cloud_error()
+                .and_then(|error| error.status()),
+            Some(500)
+        );
+    }
+
+    #[tokio::test]
+    async fn excluded_accounts_are_skipped_without_writes() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![
+                synced_account("account-1", "broker-1", TrackingMode::Transactions),
+                synced_account("account-2", "broker-2", TrackingMode::Holdings),
+                synced_account("account-3", "broker-3", TrackingMode::Transactions),
+            ],
+            upsert_result: (1, 0, Vec::new(), 0, Vec::new()),
+            ..MockSyncService::default()
+        });
+        let connected_account = |id: &str| BrokerAccount {
+            brokerage_authorization: Some("conn-1".to_string()),
+            sync_enabled: true,
+            ..broker_account(
+                id,
+                Some(ready_status("2026-05-22", None)),
+                Some(ready_status("2026-05-22", None)),
+            )
+        };
+        let api_client = MockBrokerApiClient {
+            connections: vec![connection("conn-1")],
+            broker_accounts: vec![
+                connected_account("broker-1"),
+                connected_account("broker-2"),
+                connected_account("broker-3"),
+            ],
+            activity_pages: Mutex::new(vec![PaginatedUniversalActivity {
+                data: vec![AccountUniversalActivity {
+                    id: Some("activity-1".to_string()),
+                    ..AccountUniversalActivity::default()
+                }],
+                pagination: Some(PaginationDetails {
+                    has_more: Some(false),
+                    total: Some(1),
+                    ..PaginationDetails::default()
+                }),
+            }]),
+            ..MockBrokerApiClient::default()
+        };
+        // One local id and one provider id.
+        let config = SyncConfig {
+            excluded_account_ids: HashSet::from(["account-1".to_string(), "broker-2".to_string()]),
+            ..SyncConfig::default()
+        };
+        let orchestrator =
+            SyncOrchestrator::new(service.clone(), Arc::new(NoOpProgressReporter), config);
+
+        let result = orchestrator.sync_all(&api_client).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(
+            *api_client.activity_accounts.lock().unwrap(),
+            vec!["broker-3"]
+        );
+        let calls = service.calls.lock().unwrap();
+        assert_eq!(calls.synced_broker_accounts, vec!["broker-3"]);
+        assert_eq!(calls.upserted_accounts, vec!["account-3"]);
+        assert_eq!(calls.activity_successes.len(), 1);
+        assert_eq!(calls.activity_successes[0].0, "account-3");
+        assert_eq!(calls.created_import_runs.len(), 1);
+        assert_eq!(calls.save_holdings_calls, 0);
+
+        let [connection_result] = result.connection_results.as_slice() else {
+            panic!("expected one connection result");
+        };
+        assert_eq!(connection_result.accounts_synced, 1);
+        let skipped: Vec<_> = connection_result
+            .accounts
+            .iter()
+            .filter(|account| account.skipped)
+            .map(|account| account.account_id.as_str())
+            .collect();
+        assert_eq!(skipped, vec!["account-1", "account-2"]);
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["connectionResults"][0]["accounts"][0]["skipped"], true);
+    }
+
     #[tokio::test]
     async fn cursor_blocked_activity_sync_reports_upserts_without_advancing_cursor() {
         let service = Arc::new(MockSyncService {
@@ -778,7 +1578,7 @@ mod tests {
                 "broker-1",
                 TrackingMode::Transactions,
             )],
-            upsert_result: (1, 1, vec!["asset-1".to_string()], 0),
+            upsert_result: (1, 1, vec!["asset-1".to_string()], 0, Vec::new()),
             ..MockSyncService::default()
         });
         let api_client = MockBrokerApiClient {
@@ -808,9 +1608,11 @@ mod tests {
         let mut provider_statuses = HashMap::new();
         provider_statuses.insert("broker-1".to_string(), ready_status("2026-05-22", None));
 
-        let (activities, holdings) = orchestrator(service.clone())
+        let (activities, holdings, _) = orchestrator(service.clone())
             .sync_account_data(
                 &api_client,
+                &[],
+                &HashMap::new(),
                 &HashSet::from(["broker-1".to_string()]),
                 &provider_statuses,
                 &HashMap::new(),
@@ -861,9 +1663,11 @@ mod tests {
         let mut provider_holdings_statuses = HashMap::new();
         provider_holdings_statuses.insert("broker-1".to_string(), ready_status("2026-05-22", None));
 
-        let (activities, holdings) = orchestrator(service.clone())
+        let (activities, holdings, _) = orchestrator(service.clone())
             .sync_account_data(
                 &MockBrokerApiClient::default(),
+                &[],
+                &HashMap::new(),
                 &HashSet::from(["broker-1".to_string()]),
                 &provider_transaction_statuses,
                 &provider_holdings_statuses,
@@ -884,6 +1688,85 @@ mod tests {
             .any(|(_, warning, _)| warning.contains("Holdings synced")));
     }
 
+    #[tokio::test]
+    async fn holdings_sync_flags_position_changes_above_anomaly_threshold() {
+        use super::super::models::PositionChange;
+        use rust_decimal::Decimal;
+
+        let doubled = PositionChange {
+            asset_id: "asset-doubled".to_string(),
+            previous_quantity: Decimal::from(10),
+            current_quantity: Decimal::from(20),
+            previous_cost_basis: Decimal::from(1000),
+            current_cost_basis: Decimal::from(2000),
+        };
+        let normal = PositionChange {
+            asset_id: "asset-normal".to_string(),
+            previous_quantity: Decimal::from(10),
+            current_quantity: Decimal::from(11),
+            previous_cost_basis: Decimal::from(1000),
+            current_cost_basis: Decimal::from(1100),
+        };
+        let service = Arc::new(MockSyncService {
+            accounts: vec![synced_account(
+                "account-1",
+                "broker-1",
+                TrackingMode::Holdings,
+            )],
+            holdings_result: (
+                HoldingsDiff {
+                    total_positions: 2,
+                    updated_positions: 2,
+                    snapshot_saved: true,
+                    position_changes: vec![doubled, normal],
+                    ..HoldingsDiff::default()
+                },
+                0,
+                Vec::new(),
+            ),
+            ..MockSyncService::default()
+        });
+        let mut provider_transaction_statuses = HashMap::new();
+        provider_transaction_statuses.insert(
+            "broker-1".to_string(),
+            BrokerSyncStatusDetail {
+                initial_sync_completed: Some(false),
+                last_successful_sync: None,
+                first_transaction_date: None,
+            },
+        );
+        let mut provider_holdings_statuses = HashMap::new();
+        provider_holdings_statuses.insert("broker-1".to_string(), ready_status("2026-05-22", None));
+
+        let orchestrator = SyncOrchestrator::new(
+            service,
+            Arc::new(NoOpProgressReporter),
+            SyncConfig {
+                anomaly_thresholds: AnomalyThresholds {
+                    max_change_ratio: Some(Decimal::new(5, 1)),
+                    max_value_change: None,
+                },
+                ..SyncConfig::default()
+            },
+        );
+        let (_activities, holdings, _) = orchestrator
+            .sync_account_data(
+                &MockBrokerApiClient::default(),
+                &[],
+                &HashMap::new(),
+                &HashSet::from(["broker-1".to_string()]),
+                &provider_transaction_statuses,
+                &provider_holdings_statuses,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(holdings.accounts_synced, 1);
+        assert_eq!(holdings.anomalies.len(), 1);
+        assert_eq!(holdings.anomalies[0].change.asset_id, "asset-doubled");
+        assert_eq!(holdings.anomalies[0].account_id, "account-1");
+    }
+
     #[tokio::test]
     async fn stale_provider_waterline_restores_cursor_without_counting_synced_account() {
         let service = Arc::new(MockSyncService {
@@ -899,9 +1782,11 @@ mod tests {
         let mut provider_statuses = HashMap::new();
         provider_statuses.insert("broker-1".to_string(), ready_status("2026-05-21", None));
 
-        let (activities, _holdings) = orchestrator(service.clone())
+        let (activities, _holdings, _) = orchestrator(service.clone())
             .sync_account_data(
                 &api_client,
+                &[],
+                &HashMap::new(),
                 &HashSet::from(["broker-1".to_string()]),
                 &provider_statuses,
                 &HashMap::new(),
@@ -923,7 +1808,7 @@ mod tests {
                 synced_account("account-1", "broker-1", TrackingMode::Transactions),
                 synced_account("account-2", "broker-2", TrackingMode::Holdings),
             ],
-            upsert_result: (1, 0, Vec::new(), 0),
+            upsert_result: (1, 0, Vec::new(), 0, Vec::new()),
             ..MockSyncService::default()
         });
         let api_client = MockBrokerApiClient {
@@ -955,4 +1840,336 @@ mod tests {
         assert_eq!(*api_client.activity_calls.lock().unwrap(), 1);
         assert_eq!(service.calls.lock().unwrap().save_holdings_calls, 0);
     }
+
+    fn activity_page(id: &str) -> PaginatedUniversalActivity {
+        PaginatedUniversalActivity {
+            data: vec![AccountUniversalActivity {
+                id: Some(id.to_string()),
+                ..AccountUniversalActivity::default()
+            }],
+            pagination: Some(PaginationDetails {
+                has_more: Some(false),
+                total: Some(1),
+                ..PaginationDetails::default()
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn first_sync_fetches_recent_history_and_defers_the_rest_to_a_backfill() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![synced_account(
+                "account-1",
+                "broker-1",
+                TrackingMode::Transactions,
+            )],
+            upsert_result: (1, 0, Vec::new(), 0, Vec::new()),
+            ..MockSyncService::default()
+        });
+        let api_client = MockBrokerApiClient {
+            activity_pages: Mutex::new(vec![activity_page("recent"), activity_page("old")]),
+            ..MockBrokerApiClient::default()
+        };
+        let settings = Arc::new(MemorySettingsService::default());
+        let orchestrator = SyncOrchestrator::new(
+            service.clone(),
+            Arc::new(NoOpProgressReporter),
+            SyncConfig {
+                recent_first_days: Some(30),
+                ..SyncConfig::default()
+            },
+        )
+        .with_history_backfill(HistoryBackfillService::new(settings.clone()));
+        let mut provider_statuses = HashMap::new();
+        provider_statuses.insert("broker-1".to_string(), ready_status("2024-05-22", None));
+
+        let (activities, _holdings, _) = orchestrator
+            .sync_account_data(
+                &api_client,
+                &[],
+                &HashMap::new(),
+                &HashSet::from(["broker-1".to_string()]),
+                &provider_statuses,
+                &HashMap::new(),
+            )
+            .await
+            .unwrap();
+
+        // The first sync only covers the last 30 days and still advances the cursor.
+        assert_eq!(activities.accounts_synced, 1);
+        assert_eq!(
+            *api_client.activity_windows.lock().unwrap(),
+            vec![(
+                Some("2024-04-22".to_string()),
+                Some("2024-05-22".to_string())
+            )]
+        );
+        let backfills = HistoryBackfillService::new(settings.clone());
+        let pending = backfills.runnable().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].end_date, "2024-04-22");
+        assert_eq!(pending[0].status, HistoryBackfillStatus::Pending);
+
+        let finished = orchestrator
+            .run_history_backfills(&api_client)
+            .await
+            .unwrap();
+
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].status, HistoryBackfillStatus::Complete);
+        assert_eq!(finished[0].inserted, 1);
+        assert_eq!(
+            api_client.activity_windows.lock().unwrap()[1],
+            (None, Some("2024-04-22".to_string()))
+        );
+        {
+            let calls = service.calls.lock().unwrap();
+            assert_eq!(
+                calls.created_import_runs,
+                vec![ImportRunMode::Initial, ImportRunMode::Backfill]
+            );
+            // The backfill is tracked on its own and does not move the sync cursor.
+            assert_eq!(calls.activity_successes.len(), 1);
+            assert_eq!(calls.activity_successes[0].1, "2024-05-22");
+        }
+        assert!(backfills.runnable().unwrap().is_empty());
+        assert!(orchestrator
+            .run_history_backfills(&api_client)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn interrupted_activity_sync_resumes_at_the_failed_page() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![synced_account(
+                "account-1",
+                "broker-1",
+                TrackingMode::Transactions,
+            )],
+            upsert_result: (2, 0, Vec::new(), 0, Vec::new()),
+            ..MockSyncService::default()
+        });
+        let page = |first: &str, second: &str, has_more: bool| PaginatedUniversalActivity {
+            data: [first, second]
+                .into_iter()
+                .map(|id| AccountUniversalActivity {
+                    id: Some(id.to_string()),
+                    ..AccountUniversalActivity::default()
+                })
+                .collect(),
+            pagination: Some(PaginationDetails {
+                has_more: Some(has_more),
+                ..PaginationDetails::default()
+            }),
+        };
+        let api_client = MockBrokerApiClient {
+            connections: vec![connection("conn-1")],
+            broker_accounts: vec![BrokerAccount {
+                brokerage_authorization: Some("conn-1".to_string()),
+                sync_enabled: true,
+                ..broker_account("broker-1", Some(ready_status("2026-05-22", None)), None)
+            }],
+            activity_pages: Mutex::new(vec![
+                page("a-1", "a-2", true),
+                page("a-3", "a-4", true),
+                page("a-5", "a-6", true),
+                page("a-7", "a-8", false),
+            ]),
+            failing_activity_call: Some(3),
+            ..MockBrokerApiClient::default()
+        };
+        let settings = Arc::new(MemorySettingsService::default());
+        let orchestrator = SyncOrchestrator::new(
+            service.clone(),
+            Arc::new(NoOpProgressReporter),
+            SyncConfig {
+                page_limit: 2,
+                ..SyncConfig::default()
+            },
+        )
+        .with_activity_resume(ActivityResumeService::new(settings.clone()));
+        let cursors = ActivityResumeService::new(settings.clone());
+
+        // Page 3 fails; the two pages before it are stored and remembered.
+        let first = orchestrator.sync_all(&api_client).await.unwrap();
+        assert!(!first.success);
+        let cursor = cursors.get("conn-1", "account-1").unwrap().unwrap();
+        assert_eq!(cursor.offset, 4);
+        assert_eq!(cursor.start_date, None);
+
+        // The next sync starts at page 3 instead of the beginning.
+        let second = orchestrator.sync_all(&api_client).await.unwrap();
+        assert!(second.success);
+        assert_eq!(
+            *api_client.activity_offsets.lock().unwrap(),
+            vec![Some(0), Some(2), Some(4), Some(4), Some(6)]
+        );
+        assert_eq!(cursors.get("conn-1", "account-1").unwrap(), None);
+        let calls = service.calls.lock().unwrap();
+        assert_eq!(calls.upserted_accounts.len(), 4);
+        assert_eq!(calls.activity_failures.len(), 1);
+        assert_eq!(calls.activity_successes.len(), 1);
+        assert_eq!(calls.activity_successes[0].1, "2026-05-22");
+    }
+
+    #[tokio::test]
+    async fn resync_refetches_only_the_requested_account_and_removes_stale_activities() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![
+                synced_account("account-1", "broker-1", TrackingMode::Transactions),
+                synced_account("account-2", "broker-2", TrackingMode::Transactions),
+            ],
+            activity_state: Some(sync_state("account-2", "2026-05-01T00:00:00Z")),
+            upsert_result: (2, 0, Vec::new(), 0, Vec::new()),
+            stale_plan: StaleActivityPlan {
+                remove_ids: vec!["deleted-upstream".to_string()],
+                preserved_ids: vec!["edited-locally".to_string()],
+            },
+            ..MockSyncService::default()
+        });
+        let api_client = MockBrokerApiClient {
+            broker_accounts: vec![
+                broker_account("broker-1", Some(ready_status("2026-05-22", None)), None),
+                broker_account("broker-2", Some(ready_status("2026-05-22", None)), None),
+            ],
+            activity_pages: Mutex::new(vec![PaginatedUniversalActivity {
+                data: vec![
+                    AccountUniversalActivity {
+                        id: Some("activity-1".to_string()),
+                        ..AccountUniversalActivity::default()
+                    },
+                    AccountUniversalActivity {
+                        id: Some("activity-2".to_string()),
+                        source_record_id: Some("record-2".to_string()),
+                        ..AccountUniversalActivity::default()
+                    },
+                ],
+                pagination: Some(PaginationDetails {
+                    has_more: Some(false),
+                    total: Some(2),
+                    ..PaginationDetails::default()
+                }),
+            }]),
+            ..MockBrokerApiClient::default()
+        };
+
+        let result = orchestrator(service.clone())
+            .resync_account(&api_client, "account-2")
+            .await
+            .unwrap();
+
+        // Only the requested account is fetched, from a day before its cursor.
+        assert_eq!(
+            *api_client.activity_accounts.lock().unwrap(),
+            vec!["broker-2".to_string()]
+        );
+        assert_eq!(
+            *api_client.activity_windows.lock().unwrap(),
+            vec![(
+                Some("2026-04-30".to_string()),
+                Some("2026-05-22".to_string())
+            )]
+        );
+        assert_eq!(result.account_id, "account-2");
+        assert_eq!(result.activities_fetched, 2);
+        assert_eq!(result.removed_activity_ids, vec!["deleted-upstream"]);
+        assert_eq!(result.preserved_activity_ids, vec!["edited-locally"]);
+
+        // The fetch is written once, in one call that also removes the stale activities.
+        let calls = service.calls.lock().unwrap();
+        assert!(calls.upserted_accounts.is_empty());
+        assert_eq!(
+            calls.resync_writes,
+            vec![(
+                "account-2".to_string(),
+                vec!["activity-1".to_string(), "activity-2".to_string()],
+                NaiveDate::from_ymd_opt(2026, 4, 30),
+            )]
+        );
+        assert_eq!(calls.created_import_runs, vec![ImportRunMode::Repair]);
+        assert_eq!(calls.activity_successes.len(), 1);
+        assert_eq!(calls.activity_successes[0].0, "account-2");
+        assert_eq!(calls.finalized_import_runs[0].1.removed, 1);
+        assert_eq!(calls.finalized_import_runs[0].2, ImportRunStatus::Applied);
+    }
+
+    #[tokio::test]
+    async fn resync_keeps_local_activities_when_the_cloud_returns_nothing() {
+        let service = Arc::new(MockSyncService {
+            accounts: vec![synced_account(
+                "account-1",
+                "broker-1",
+                TrackingMode::Transactions,
+            )],
+            ..MockSyncService::default()
+        });
+        let api_client = MockBrokerApiClient {
+            broker_accounts: vec![broker_account(
+                "broker-1",
+                Some(ready_status("2026-05-22", None)),
+                None,
+            )],
+            ..MockBrokerApiClient::default()
+        };
+
+        let result = orchestrator(service.clone())
+            .resync_account(&api_client, "account-1")
+            .await
+            .unwrap();
+
+        assert!(result.removed_activity_ids.is_empty());
+        let calls = service.calls.lock().unwrap();
+        assert!(calls.resync_writes.is_empty());
+        assert!(calls.activity_successes.is_empty());
+        assert_eq!(calls.activity_needs_review.len(), 1);
+    }
+
+    #[derive(Default)]
+    struct RecordingReporter {
+        expiring: Mutex<Vec<ConnectionExpiry>>,
+    }
+
+    impl SyncProgressReporter for RecordingReporter {
+        fn report_progress(&self, _payload: super::super::progress::SyncProgressPayload) {}
+
+        fn report_sync_start(&self) {}
+
+        fn report_sync_complete(&self, _result: &SyncResult) {}
+
+        fn report_expiring_connections(&self, expiring: &[ConnectionExpiry]) {
+            self.expiring.lock().unwrap().extend_from_slice(expiring);
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_reports_connections_whose_consent_lapses_within_the_lead_time() {
+        let mut expiring = connection("conn-1");
+        expiring.consent_expires_at =
+            Some((Utc::now() + chrono::Duration::hours(3 * 24 + 12)).to_rfc3339());
+        let mut fresh = connection("conn-2");
+        fresh.consent_expires_at = Some((Utc::now() + chrono::Duration::days(60)).to_rfc3339());
+        let api_client = MockBrokerApiClient {
+            connections: vec![expiring, fresh],
+            ..MockBrokerApiClient::default()
+        };
+        let reporter = Arc::new(RecordingReporter::default());
+        let orchestrator = SyncOrchestrator::new(
+            Arc::new(MockSyncService::default()),
+            reporter.clone(),
+            SyncConfig::default(),
+        )
+        .with_connection_health(
+            ConnectionHealthService::new(Arc::new(MemorySettingsService::default()), 3)
+                .with_expiry_warning_days(14),
+        );
+
+        orchestrator.sync_all(&api_client).await.unwrap();
+
+        let reported = reporter.expiring.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].connection_id, "conn-1");
+        assert_eq!(reported[0].days_remaining, 3);
+    }
 }
@@ -0,0 +1,297 @@
+use chrono::NaiveDate;
+use log::{error, info, warn};
+
+use super::super::account_resync::{AccountResyncResult, ResyncWriteOutcome};
+use super::super::progress::{SyncProgressPayload, SyncProgressReporter, SyncStatus};
+use super::super::sync_readiness::{
+    resolve_activity_readiness, should_advance_activity_cursor, ProviderReadiness,
+};
+use super::super::traits::BrokerApiClient;
+use super::{AccountSyncJob, ActivityPageHandling, HoldingsPhaseContext, SyncOrchestrator};
+use crate::broker_ingest::{ImportRunMode, ImportRunStatus, ImportRunSummary};
+use wealthfolio_core::accounts::TrackingMode;
+
+impl<P: SyncProgressReporter> SyncOrchestrator<P> {
+    /// Refetch one account's activities from its cursor, or its full history without one (and
+    /// holdings, for holdings-tracked accounts), reconcile them against local data, and report
+    /// what changed. Other accounts and connections are not touched. See
+    /// [`crate::broker::account_resync`].
+    pub async fn resync_account(
+        &self,
+        api_client: &dyn BrokerApiClient,
+        account_id: &str,
+    ) -> Result<AccountResyncResult, String> {
+        let job = self
+            .sync_service
+            .get_synced_accounts()
+            .map_err(|e| format!("Failed to get synced accounts: {}", e))?
+            .into_iter()
+            .find(|account| account.id == account_id)
+            .and_then(AccountSyncJob::from_account)
+            .ok_or_else(|| format!("Account {} is not a synced broker account", account_id))?;
+        if job.tracking_mode == TrackingMode::NotSet {
+            return Err(format!(
+                "Choose a tracking mode for '{}' before resyncing it",
+                job.account_name
+            ));
+        }
+
+        let broker_account = api_client
+            .list_accounts(None)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|account| account.id.as_deref() == Some(job.broker_account_id.as_str()))
+            .ok_or_else(|| {
+                format!(
+                    "Broker account for '{}' is no longer available",
+                    job.account_name
+                )
+            })?;
+        let transactions_status = broker_account
+            .sync_status
+            .as_ref()
+            .and_then(|status| status.transactions.clone());
+        let holdings_status = broker_account
+            .sync_status
+            .as_ref()
+            .and_then(|status| status.holdings.clone());
+
+        let provider_waterline = match resolve_activity_readiness(transactions_status.as_ref())? {
+            ProviderReadiness::Ready(date) => date,
+            ProviderReadiness::NotReady(reason) => {
+                return Err(format!("Resync deferred: {}", reason));
+            }
+        };
+        let query_window =
+            self.compute_activity_query_window(&job.account_id, provider_waterline)?;
+        // Without a cursor the whole history is refetched; a resync never defers to a backfill.
+        let start_date = query_window
+            .start_date
+            .filter(|_| query_window.has_local_cursor);
+        let end_date = query_window.end_date;
+        let window_label = format!("{} -> {}", start_date.as_deref().unwrap_or("ALL"), end_date);
+
+        self.sync_service
+            .mark_activity_sync_attempt(job.account_id.clone())
+            .await
+            .map_err(|e| format!("Failed to mark activity sync attempt: {}", e))?;
+        let import_run_id = match self
+            .sync_service
+            .create_import_run(&job.account_id, ImportRunMode::Repair)
+            .await
+        {
+            Ok(run) => Some(run.id),
+            Err(e) => {
+                error!(
+                    "Failed to create import run for '{}': {}",
+                    job.account_name, e
+                );
+                None
+            }
+        };
+
+        info!(
+            "Resyncing account '{}' ({}): {}",
+            job.account_name, job.broker_account_id, window_label
+        );
+        self.progress_reporter.report_progress(
+            SyncProgressPayload::new(&job.account_id, &job.account_name, SyncStatus::Syncing)
+                .with_message(format!("Starting resync: {}", window_label)),
+        );
+
+        let outcome = match self
+            .sync_account_activities(
+                api_client,
+                &job.account_id,
+                &job.account_name,
+                &job.broker_account_id,
+                start_date.as_deref(),
+                Some(end_date.as_str()),
+                import_run_id.clone(),
+                false,
+                // Stale activities are found from the complete fetch, so a resync never resumes.
+                None,
+                ActivityPageHandling::Collect,
+            )
+            .await
+        {
+            Ok(outcome) => outcome,
+            Err(err) => return Err(self.fail_resync(&job, import_run_id, err).await),
+        };
+
+        // An empty or inconsistent history is more likely an upstream gap than every activity
+        // having been deleted, so nothing is removed on it.
+        let complete_history = outcome.fetched > 0
+            && should_advance_activity_cursor(
+                outcome.fetched as usize,
+                false,
+                outcome.inconsistent_empty_page,
+                transactions_status.as_ref(),
+            );
+        let written = if complete_history {
+            let since = start_date
+                .as_deref()
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+            self.sync_service
+                .resync_account_activities(
+                    job.account_id.clone(),
+                    import_run_id.clone(),
+                    outcome.collected,
+                    since,
+                )
+                .await
+                .map_err(|e| format!("Failed to write resynced activities: {}", e))
+        } else {
+            warn!(
+                "Resync of '{}' fetched no usable history; keeping all local activities",
+                job.account_name
+            );
+            self.sync_service
+                .upsert_account_activities(
+                    job.account_id.clone(),
+                    import_run_id.clone(),
+                    outcome.collected,
+                )
+                .await
+                .map(
+                    |(upserted, assets_created, _, needs_review, _)| ResyncWriteOutcome {
+                        upserted,
+                        assets_created,
+                        needs_review,
+                        ..ResyncWriteOutcome::default()
+                    },
+                )
+                .map_err(|e| format!("Failed to upsert activities: {}", e))
+        };
+        let written = match written {
+            Ok(written) => written,
+            Err(err) => return Err(self.fail_resync(&job, import_run_id, err).await),
+        };
+        let removal = &written.plan;
+
+        if complete_history {
+            if let Err(e) = self
+                .sync_service
+                .finalize_activity_sync_success(
+                    job.account_id.clone(),
+                    end_date.clone(),
+                    import_run_id.clone(),
+                )
+                .await
+            {
+                error!(
+                    "Failed to update activity sync state for '{}': {}",
+                    job.account_name, e
+                );
+            }
+        } else if let Err(e) = self
+            .sync_service
+            .finalize_activity_sync_needs_review(
+                job.account_id.clone(),
+                "Resync returned no activities; local activities were kept".to_string(),
+                import_run_id.clone(),
+            )
+            .await
+        {
+            error!(
+                "Failed to mark activity sync as needs review for '{}': {}",
+                job.account_name, e
+            );
+        }
+
+        if let Some(run_id) = &import_run_id {
+            let summary = ImportRunSummary {
+                fetched: outcome.fetched,
+                inserted: written.upserted as u32,
+                warnings: written.needs_review as u32,
+                removed: removal.remove_ids.len() as u32,
+                assets_created: written.assets_created as u32,
+                ..ImportRunSummary::default()
+            };
+            let status = if written.needs_review > 0 || !complete_history {
+                ImportRunStatus::NeedsReview
+            } else {
+                ImportRunStatus::Applied
+            };
+            let _ = self
+                .sync_service
+                .finalize_import_run(run_id, summary, status, None)
+                .await;
+        }
+
+        let holdings = if job.is_holdings_mode() {
+            Some(
+                self.sync_holdings_phase(
+                    api_client,
+                    &job,
+                    holdings_status.as_ref(),
+                    HoldingsPhaseContext {
+                        activity_warning: None,
+                        activity_import_run_id: import_run_id,
+                    },
+                )
+                .await,
+            )
+        } else {
+            None
+        };
+
+        self.progress_reporter.report_progress(
+            SyncProgressPayload::new(&job.account_id, &job.account_name, SyncStatus::Complete)
+                .with_activities_fetched(outcome.fetched as usize)
+                .with_message(format!(
+                    "Resynced {} activities, removed {} ({} kept because edited locally)",
+                    written.upserted,
+                    removal.remove_ids.len(),
+                    removal.preserved_ids.len()
+                )),
+        );
+
+        Ok(AccountResyncResult {
+            account_id: job.account_id,
+            account_name: job.account_name,
+            activities_fetched: outcome.fetched as usize,
+            activities_upserted: written.upserted,
+            assets_inserted: written.assets_created,
+            needs_review: written.needs_review,
+            removed_activity_ids: written.plan.remove_ids,
+            preserved_activity_ids: written.plan.preserved_ids,
+            holdings,
+        })
+    }
+
+    /// Records a failed resync and returns the error to surface.
+    async fn fail_resync(
+        &self,
+        job: &AccountSyncJob,
+        import_run_id: Option<String>,
+        err: String,
+    ) -> String {
+        error!("Failed to resync '{}': {}", job.account_name, err);
+        let _ = self
+            .sync_service
+            .finalize_activity_sync_failure(
+                job.account_id.clone(),
+                err.clone(),
+                import_run_id.clone(),
+            )
+            .await;
+        if let Some(run_id) = &import_run_id {
+            let _ = self
+                .sync_service
+                .finalize_import_run(
+                    run_id,
+                    ImportRunSummary::default(),
+                    ImportRunStatus::Failed,
+                    Some(err.clone()),
+                )
+                .await;
+        }
+        self.progress_reporter.report_progress(
+            SyncProgressPayload::new(&job.account_id, &job.account_name, SyncStatus::Failed)
+                .with_message(err.clone()),
+        );
+        err
+    }
+}
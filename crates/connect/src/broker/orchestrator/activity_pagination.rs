@@ -1,9 +1,13 @@
 use chrono::{NaiveDate, Utc};
-use log::{debug, info};
+use log::{debug, info, warn};
 
+use super::super::activity_resume::ActivityPageCursor;
 use super::super::progress::{SyncProgressPayload, SyncProgressReporter, SyncStatus};
 use super::super::traits::BrokerApiClient;
-use super::{ActivityQueryWindow, ActivitySyncOutcome, SyncOrchestrator};
+use super::{
+    AccountSyncJob, ActivityPageHandling, ActivityQueryWindow, ActivityResumePoint,
+    ActivitySyncOutcome, SyncOrchestrator,
+};
 
 impl<P: SyncProgressReporter> SyncOrchestrator<P> {
     #[allow(clippy::too_many_arguments)]
@@ -16,8 +20,11 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         start_date: Option<&str>,
         end_date: Option<&str>,
         import_run_id: Option<String>,
+        backfill: bool,
+        resume: Option<&ActivityResumePoint>,
+        handling: ActivityPageHandling,
     ) -> Result<ActivitySyncOutcome, String> {
-        let mut offset: i64 = 0;
+        let mut offset: i64 = resume.map_or(0, |point| point.offset);
         let limit = self.config.page_limit;
         let mut pages_fetched: usize = 0;
         let mut last_page_first_id: Option<String> = None;
@@ -28,6 +35,8 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         let mut total_assets_created: u32 = 0;
         let mut total_needs_review: u32 = 0;
         let mut all_new_asset_ids: Vec<String> = Vec::new();
+        let mut all_currency_mismatches = Vec::new();
+        let mut collected = Vec::new();
 
         loop {
             if pages_fetched >= self.config.max_pages {
@@ -58,6 +67,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                 SyncProgressPayload::new(account_id, account_name, SyncStatus::Syncing)
                     .with_page(pages_fetched)
                     .with_activities_fetched(total_fetched as usize)
+                    .with_backfill(backfill)
                     .with_message(format!(
                         "Fetched {} activities (total: {:?})",
                         total_fetched, page_total
@@ -87,31 +97,36 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                     last_page_first_id = Some(first_id);
                 }
 
-                debug!(
-                    "Upserting {} activities for account '{}'...",
-                    data.len(),
-                    account_name
-                );
+                if handling == ActivityPageHandling::Collect {
+                    collected.extend(data.iter().cloned());
+                } else {
+                    debug!(
+                        "Upserting {} activities for account '{}'...",
+                        data.len(),
+                        account_name
+                    );
 
-                let (upserted, assets, new_asset_ids, needs_review) = self
-                    .sync_service
-                    .upsert_account_activities(
-                        account_id.to_string(),
-                        import_run_id.clone(),
-                        data.clone(),
-                    )
-                    .await
-                    .map_err(|e| format!("Failed to upsert activities: {}", e))?;
+                    let (upserted, assets, new_asset_ids, needs_review, currency_mismatches) = self
+                        .sync_service
+                        .upsert_account_activities(
+                            account_id.to_string(),
+                            import_run_id.clone(),
+                            data.clone(),
+                        )
+                        .await
+                        .map_err(|e| format!("Failed to upsert activities: {}", e))?;
 
-                info!(
-                    "Upserted {} activities, {} assets for '{}' ({} need review)",
-                    upserted, assets, account_name, needs_review
-                );
+                    info!(
+                        "Upserted {} activities, {} assets for '{}' ({} need review)",
+                        upserted, assets, account_name, needs_review
+                    );
 
-                total_inserted += upserted as u32;
-                total_assets_created += assets as u32;
-                total_needs_review += needs_review as u32;
-                all_new_asset_ids.extend(new_asset_ids);
+                    total_inserted += upserted as u32;
+                    total_assets_created += assets as u32;
+                    total_needs_review += needs_review as u32;
+                    all_new_asset_ids.extend(new_asset_ids);
+                    all_currency_mismatches.extend(currency_mismatches);
+                }
             }
 
             let received = data.len() as i64;
@@ -146,6 +161,11 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             if !has_more {
                 break;
             }
+
+            if let Some(point) = resume {
+                self.save_activity_resume_point(point, account_id, start_date, end_date, offset)
+                    .await;
+            }
         }
 
         Ok(ActivitySyncOutcome {
@@ -154,10 +174,86 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             assets_created: total_assets_created,
             needs_review: total_needs_review,
             new_asset_ids: all_new_asset_ids,
+            currency_mismatches: all_currency_mismatches,
             inconsistent_empty_page,
+            collected,
+        })
+    }
+
+    /// Where to start fetching `job`'s activities. An interrupted sync of the same window
+    /// resumes at its saved offset, adopting the saved end date since offsets only hold within
+    /// one window. `None` when cursors are not persisted for this sync.
+    pub(super) fn activity_resume_point(
+        &self,
+        job: &AccountSyncJob,
+        query_window: &mut ActivityQueryWindow,
+    ) -> Option<ActivityResumePoint> {
+        let activity_resume = self.activity_resume.as_ref()?;
+        let connection_id = job.connection_id.clone()?;
+        let offset = match activity_resume.get(&connection_id, &job.account_id) {
+            Ok(Some(cursor))
+                if cursor.start_date == query_window.start_date
+                    && cursor.end_date <= query_window.end_date =>
+            {
+                info!(
+                    "Resuming activity sync for '{}' at offset {}",
+                    job.account_name, cursor.offset
+                );
+                query_window.end_date = cursor.end_date;
+                cursor.offset
+            }
+            Ok(_) => 0,
+            Err(err) => {
+                warn!(
+                    "Failed to read activity resume cursor for '{}': {}",
+                    job.account_name, err
+                );
+                0
+            }
+        };
+        Some(ActivityResumePoint {
+            connection_id,
+            offset,
         })
     }
 
+    /// Resuming is best effort: a cursor that cannot be saved only costs a refetch.
+    async fn save_activity_resume_point(
+        &self,
+        point: &ActivityResumePoint,
+        account_id: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        offset: i64,
+    ) {
+        let (Some(activity_resume), Some(end_date)) = (&self.activity_resume, end_date) else {
+            return;
+        };
+        let cursor = ActivityPageCursor::new(start_date.map(str::to_string), end_date, offset);
+        if let Err(err) = activity_resume
+            .save(&point.connection_id, account_id, cursor)
+            .await
+        {
+            warn!("Failed to save activity resume cursor: {}", err);
+        }
+    }
+
+    pub(super) async fn clear_activity_resume_point(
+        &self,
+        point: &ActivityResumePoint,
+        account_id: &str,
+    ) {
+        let Some(activity_resume) = &self.activity_resume else {
+            return;
+        };
+        if let Err(err) = activity_resume
+            .clear(&point.connection_id, account_id)
+            .await
+        {
+            warn!("Failed to clear activity resume cursor: {}", err);
+        }
+    }
+
     pub(super) fn compute_activity_query_window(
         &self,
         account_id: &str,
@@ -172,15 +268,30 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
 
         let local_cursor = sync_state.and_then(|s| s.last_successful_at);
         let has_local_cursor = local_cursor.is_some();
-        let start_date = local_cursor
+        let mut start_date = local_cursor
             .map(|dt| dt.date_naive())
             .map(|d| (d - chrono::Days::new(1)).min(end_date))
             .map(|d| d.format("%Y-%m-%d").to_string());
 
+        // First sync: fetch recent activity now and leave the rest to a backfill job.
+        let mut backfill_end_date = None;
+        if let Some(days) = self
+            .config
+            .recent_first_days
+            .filter(|_| !has_local_cursor && self.history_backfill.is_some())
+        {
+            let recent_start = (end_date - chrono::Days::new(u64::from(days)))
+                .format("%Y-%m-%d")
+                .to_string();
+            start_date = Some(recent_start.clone());
+            backfill_end_date = Some(recent_start);
+        }
+
         Ok(ActivityQueryWindow {
             start_date,
             end_date: end_date.format("%Y-%m-%d").to_string(),
             has_local_cursor,
+            backfill_end_date,
         })
     }
 }
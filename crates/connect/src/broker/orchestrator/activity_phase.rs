@@ -7,7 +7,8 @@ use super::super::sync_readiness::{
 };
 use super::super::traits::BrokerApiClient;
 use super::{
-    AccountSyncJob, ActivityPhaseResult, ActivityQueryWindow, ActivitySyncOutcome, SyncOrchestrator,
+    AccountSyncJob, ActivityPageHandling, ActivityPhaseResult, ActivityQueryWindow,
+    ActivitySyncOutcome, SyncOrchestrator,
 };
 use crate::broker_ingest::{ImportRunMode, ImportRunStatus, ImportRunSummary};
 
@@ -243,7 +244,7 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             }
         };
 
-        let query_window = match self
+        let mut query_window = match self
             .compute_activity_query_window(&job.account_id, activity_waterline)
         {
             Ok(window) => window,
@@ -303,11 +304,14 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
             }
         };
 
-        let import_mode = if query_window.start_date.is_none() {
-            ImportRunMode::Initial
-        } else {
-            ImportRunMode::Incremental
-        };
+        let resume = self.activity_resume_point(job, &mut query_window);
+
+        let import_mode =
+            if query_window.start_date.is_none() || query_window.backfill_end_date.is_some() {
+                ImportRunMode::Initial
+            } else {
+                ImportRunMode::Incremental
+            };
 
         let import_run = match self
             .sync_service
@@ -354,10 +358,20 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                 query_window.start_date.as_deref(),
                 Some(query_window.end_date.as_str()),
                 result.activity_import_run_id.clone(),
+                false,
+                resume.as_ref(),
+                ActivityPageHandling::Upsert,
             )
             .await
         {
             Ok(outcome) => {
+                if let Some(point) = &resume {
+                    self.clear_activity_resume_point(point, &job.account_id)
+                        .await;
+                }
+                if let Some(end_date) = &query_window.backfill_end_date {
+                    self.schedule_history_backfill(job, end_date).await;
+                }
                 self.handle_activity_sync_success(
                     job,
                     provider_activity_status,
@@ -475,6 +489,10 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
         result.summary.activities_upserted += outcome.inserted as usize;
         result.summary.assets_inserted += outcome.assets_created as usize;
         result.summary.new_asset_ids.extend(outcome.new_asset_ids);
+        result
+            .summary
+            .currency_mismatches
+            .extend(outcome.currency_mismatches);
 
         if !should_advance_cursor {
             result.continue_account = job.is_holdings_mode();
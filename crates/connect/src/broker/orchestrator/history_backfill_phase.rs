@@ -0,0 +1,166 @@
+use log::{error, info, warn};
+
+use super::super::history_backfill::{HistoryBackfillJob, HistoryBackfillStatus};
+use super::super::progress::{SyncProgressPayload, SyncProgressReporter, SyncStatus};
+use super::super::traits::BrokerApiClient;
+use super::{AccountSyncJob, ActivityPageHandling, SyncOrchestrator};
+use crate::broker_ingest::{ImportRunMode, ImportRunStatus, ImportRunSummary};
+
+impl<P: SyncProgressReporter> SyncOrchestrator<P> {
+    pub(super) async fn schedule_history_backfill(&self, job: &AccountSyncJob, end_date: &str) {
+        let Some(history_backfill) = &self.history_backfill else {
+            return;
+        };
+        match history_backfill
+            .schedule(HistoryBackfillJob::new(
+                &job.account_id,
+                &job.account_name,
+                &job.broker_account_id,
+                end_date,
+            ))
+            .await
+        {
+            Ok(true) => info!(
+                "Scheduled history backfill for '{}' up to {}",
+                job.account_name, end_date
+            ),
+            Ok(false) => {}
+            Err(e) => warn!(
+                "Failed to schedule history backfill for '{}': {}",
+                job.account_name, e
+            ),
+        }
+    }
+
+    /// Fetches the older history deferred by [`super::SyncConfig::recent_first_days`], one
+    /// account at a time. Each job gets its own `BACKFILL` import run and reports progress
+    /// with `backfill` set; sync cursors are left alone. Failed jobs are retried on the next
+    /// call. Returns the jobs that ran, in their final state.
+    pub async fn run_history_backfills(
+        &self,
+        api_client: &dyn BrokerApiClient,
+    ) -> Result<Vec<HistoryBackfillJob>, String> {
+        let Some(history_backfill) = &self.history_backfill else {
+            return Ok(Vec::new());
+        };
+        let jobs = history_backfill.runnable().map_err(|e| e.to_string())?;
+
+        let mut finished = Vec::with_capacity(jobs.len());
+        for mut job in jobs {
+            job.status = HistoryBackfillStatus::Running;
+            job.error = None;
+            job.import_run_id = match self
+                .sync_service
+                .create_import_run(&job.account_id, ImportRunMode::Backfill)
+                .await
+            {
+                Ok(run) => Some(run.id),
+                Err(e) => {
+                    error!(
+                        "Failed to create backfill import run for '{}': {}",
+                        job.account_name, e
+                    );
+                    None
+                }
+            };
+            if let Err(e) = history_backfill.update(job.clone()).await {
+                warn!(
+                    "Failed to save history backfill for '{}': {}",
+                    job.account_name, e
+                );
+            }
+
+            info!(
+                "Backfilling activity history for '{}' ({}): ALL -> {}",
+                job.account_name, job.broker_account_id, job.end_date
+            );
+            self.progress_reporter.report_progress(
+                SyncProgressPayload::new(&job.account_id, &job.account_name, SyncStatus::Syncing)
+                    .with_backfill(true)
+                    .with_message(format!("Backfilling history up to {}", job.end_date)),
+            );
+
+            let outcome = self
+                .sync_account_activities(
+                    api_client,
+                    &job.account_id,
+                    &job.account_name,
+                    &job.broker_account_id,
+                    None,
+                    Some(job.end_date.as_str()),
+                    job.import_run_id.clone(),
+                    true,
+                    None,
+                    ActivityPageHandling::Upsert,
+                )
+                .await;
+
+            let (status, summary, error) = match outcome {
+                Ok(outcome) => {
+                    job.status = HistoryBackfillStatus::Complete;
+                    job.fetched = outcome.fetched;
+                    job.inserted = outcome.inserted;
+                    let status = if outcome.needs_review > 0 {
+                        ImportRunStatus::NeedsReview
+                    } else {
+                        ImportRunStatus::Applied
+                    };
+                    let summary = ImportRunSummary {
+                        fetched: outcome.fetched,
+                        inserted: outcome.inserted,
+                        warnings: outcome.needs_review,
+                        assets_created: outcome.assets_created,
+                        ..ImportRunSummary::default()
+                    };
+                    (status, summary, None)
+                }
+                Err(err) => {
+                    error!(
+                        "History backfill failed for '{}': {}",
+                        job.account_name, err
+                    );
+                    job.status = HistoryBackfillStatus::Failed;
+                    job.error = Some(err.clone());
+                    (
+                        ImportRunStatus::Failed,
+                        ImportRunSummary::default(),
+                        Some(err),
+                    )
+                }
+            };
+
+            if let Some(run_id) = &job.import_run_id {
+                let _ = self
+                    .sync_service
+                    .finalize_import_run(run_id, summary, status, error.clone())
+                    .await;
+            }
+            if let Err(e) = history_backfill.update(job.clone()).await {
+                warn!(
+                    "Failed to save history backfill for '{}': {}",
+                    job.account_name, e
+                );
+            }
+
+            let payload = match &error {
+                None => SyncProgressPayload::new(
+                    &job.account_id,
+                    &job.account_name,
+                    SyncStatus::Complete,
+                )
+                .with_activities_fetched(job.fetched as usize)
+                .with_message(format!("History backfilled: {} activities", job.inserted)),
+                Some(err) => {
+                    SyncProgressPayload::new(&job.account_id, &job.account_name, SyncStatus::Failed)
+                        .with_message(format!("History backfill failed: {}", err))
+                }
+            };
+            self.progress_reporter
+                .report_progress(payload.with_backfill(true));
+
+            finished.push(job);
+        }
+
+        Ok(finished)
+    }
+}
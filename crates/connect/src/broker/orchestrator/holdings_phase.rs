@@ -1,5 +1,6 @@
 use log::{debug, error, info, warn};
 
+use super::super::anomaly::detect_anomalies;
 use super::super::models::{BrokerSyncStatusDetail, HoldingsDiff, SyncHoldingsResponse};
 use super::super::progress::{SyncProgressPayload, SyncProgressReporter, SyncStatus};
 use super::super::sync_readiness::{resolve_holdings_readiness, ProviderReadiness};
@@ -142,7 +143,21 @@ impl<P: SyncProgressReporter> SyncOrchestrator<P> {
                         .await;
                 }
 
+                let anomalies = detect_anomalies(
+                    &job.account_id,
+                    &job.account_name,
+                    &diff.position_changes,
+                    &self.config.anomaly_thresholds,
+                );
+                for anomaly in &anomalies {
+                    warn!(
+                        "Holdings anomaly for '{}' ({}): {}",
+                        job.account_name, anomaly.change.asset_id, anomaly.reason
+                    );
+                }
+
                 summary.accounts_synced += 1;
+                summary.anomalies.extend(anomalies);
                 summary.positions_upserted += diff.added_positions + diff.updated_positions;
                 summary.snapshots_upserted += if diff.snapshot_saved { 1 } else { 0 };
                 summary.assets_inserted += assets_created;
@@ -5,7 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::models::SyncResult;
+use super::models::{ConnectionExpiry, SyncAnomaly, SyncResult};
 
 /// Status of a sync operation.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -51,6 +51,9 @@ pub struct SyncProgressPayload {
     pub activities_fetched: usize,
     /// Optional status message
     pub message: Option<String>,
+    /// Whether this reports a background history backfill rather than a regular sync
+    #[serde(default)]
+    pub backfill: bool,
 }
 
 impl SyncProgressPayload {
@@ -67,6 +70,7 @@ impl SyncProgressPayload {
             current_page: 0,
             activities_fetched: 0,
             message: None,
+            backfill: false,
         }
     }
 
@@ -87,6 +91,12 @@ impl SyncProgressPayload {
         self.message = Some(message.into());
         self
     }
+
+    /// Mark the payload as history backfill progress.
+    pub fn with_backfill(mut self, backfill: bool) -> Self {
+        self.backfill = backfill;
+        self
+    }
 }
 
 /// Trait for reporting sync progress.
@@ -101,6 +111,12 @@ pub trait SyncProgressReporter: Send + Sync {
 
     /// Report that sync completed (successfully or with errors).
     fn report_sync_complete(&self, result: &SyncResult);
+
+    /// Report holdings changes that exceeded the anomaly thresholds.
+    fn report_anomalies(&self, _anomalies: &[SyncAnomaly]) {}
+
+    /// Report connections whose broker consent lapses soon.
+    fn report_expiring_connections(&self, _expiring: &[ConnectionExpiry]) {}
 }
 
 /// A no-op progress reporter for contexts where progress reporting is not needed.
@@ -4,16 +4,20 @@ use async_trait::async_trait;
 use log::{debug, info, warn};
 use std::sync::Arc;
 
+use super::account_resync::{
+    fetched_activity_references, plan_stale_activity_removal, ResyncScope, ResyncWriteOutcome,
+};
 use super::mapping;
 use super::models::{
     AccountUniversalActivity, BrokerAccount, BrokerConnection, HoldingsBalance, HoldingsDiff,
-    HoldingsOptionPosition, HoldingsPosition, NewAccountInfo, SyncAccountsResponse,
+    HoldingsOptionPosition, HoldingsPosition, NewAccountInfo, PositionChange, SyncAccountsResponse,
     SyncConnectionsResponse,
 };
 use super::traits::{BrokerSyncServiceTrait, PlatformRepositoryTrait};
 use crate::broker_ingest::{
     BrokerSyncState, BrokerSyncStateRepositoryTrait, ImportRun, ImportRunMode,
-    ImportRunRepositoryTrait, ImportRunStatus, ImportRunSummary, ImportRunType, ReviewMode,
+    ImportRunRepositoryTrait, ImportRunStatus, ImportRunSummary, ImportRunTotals, ImportRunType,
+    ImportRunWindow, ReviewMode,
 };
 use crate::platform::Platform;
 use chrono::{DateTime, NaiveDate, Utc};
@@ -25,7 +29,7 @@ use wealthfolio_core::accounts::{
 };
 use wealthfolio_core::activities::{
     compute_idempotency_key, ActivityRepositoryTrait, ActivityServiceTrait, ActivityUpsert,
-    NewActivity, ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_SELL,
+    CurrencyMismatch, NewActivity, ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_SELL,
 };
 use wealthfolio_core::assets::{
     build_option_metadata, parse_crypto_pair_symbol, parse_symbol_with_exchange_suffix,
@@ -46,6 +50,10 @@ const DEFAULT_BROKERAGE_PROVIDER: &str = "snaptrade";
 /// Precision used for holdings normalization/diff comparisons.
 /// Higher than generic valuation precision to preserve crypto fidelity.
 const HOLDINGS_DECIMAL_PRECISION: u32 = 12;
+/// Stored `run_type` of broker sync runs.
+const SYNC_RUN_TYPE: &str = "SYNC";
+/// Most sync runs read for one history window; the oldest runs of a larger window are left out.
+const SYNC_RUN_HISTORY_LIMIT: i64 = 10_000;
 
 fn normalize_holdings_money(amount: Decimal, currency: &str) -> (Decimal, String) {
     let (amount, currency) = normalize_amount(amount, currency);
@@ -65,6 +73,18 @@ struct HoldingsPositionData {
     position_currency: String,
 }
 
+/// Broker activities mapped and prepared for an upsert.
+#[derive(Default)]
+struct PreparedActivityUpserts {
+    upserts: Vec<ActivityUpsert>,
+    /// (asset_id, price, datetime, currency) of trades, saved as broker quotes.
+    quote_data: Vec<(String, Decimal, DateTime<Utc>, String)>,
+    assets_created: usize,
+    new_asset_ids: Vec<String>,
+    needs_review: usize,
+    currency_mismatches: Vec<CurrencyMismatch>,
+}
+
 /// Service for syncing broker data to the local database
 pub struct BrokerSyncService {
     account_service: Arc<dyn AccountServiceTrait>,
@@ -336,211 +356,102 @@ impl BrokerSyncServiceTrait for BrokerSyncService {
         account_id: String,
         import_run_id: Option<String>,
         activities_data: Vec<AccountUniversalActivity>,
-    ) -> Result<(usize, usize, Vec<String>, usize)> {
+    ) -> Result<(usize, usize, Vec<String>, usize, Vec<CurrencyMismatch>)> {
         if activities_data.is_empty() {
-            return Ok((0, 0, Vec::new(), 0));
-        }
-
-        let account = self.account_service.get_account(&account_id)?;
-        let base_currency = self
-            .account_service
-            .get_base_currency()
-            .filter(|c| !c.trim().is_empty());
-        let account_currency = if !account.currency.is_empty() {
-            Some(account.currency.clone())
-        } else {
-            base_currency.clone()
-        };
-
-        // 1. Map broker data → NewActivity (dedup by activity ID)
-        let mut seen_activity_ids: HashSet<String> = HashSet::new();
-        let mut new_activities: Vec<NewActivity> = Vec::new();
-
-        for activity in &activities_data {
-            if let Some(new_act) = mapping::map_broker_activity(
-                activity,
-                &account_id,
-                account_currency.as_deref(),
-                base_currency.as_deref(),
-            ) {
-                let activity_id = new_act.id.as_deref().unwrap_or("").to_string();
-                if seen_activity_ids.insert(activity_id) {
-                    new_activities.push(new_act);
-                }
-            }
-        }
-
-        if new_activities.is_empty() {
-            return Ok((0, 0, Vec::new(), 0));
+            return Ok((0, 0, Vec::new(), 0, Vec::new()));
         }
 
-        // 2. Use sync preparation for asset creation + FX registration
-        let prepare_result = self
-            .activity_service
-            .prepare_activities_for_sync(new_activities, &account)
+        let prepared = self
+            .prepare_activity_upserts(&account_id, import_run_id, &activities_data)
             .await?;
-        let new_asset_ids = prepare_result.created_asset_ids.clone();
-
-        let assets_created = prepare_result.assets_created as usize;
-
-        // Count needs_review activities
-        let needs_review_count = prepare_result
-            .prepared
-            .iter()
-            .filter(|p| p.activity.needs_review.unwrap_or(false))
-            .count();
-
-        // 3. Convert prepared activities into ActivityUpsert payloads
-        //    and collect quote data from trade activities
-        let mut activity_upserts: Vec<ActivityUpsert> = Vec::new();
-        let mut quote_data: Vec<(String, Decimal, DateTime<Utc>, String)> = Vec::new(); // (asset_id, price, datetime, currency)
-
-        for prepared in prepare_result.prepared {
-            let act = prepared.activity;
-            let asset_id = prepared.resolved_asset_id.clone();
-            let activity_id = act.id.unwrap_or_default();
-            if activity_id.is_empty() {
-                continue;
-            }
-
-            // Parse activity date for idempotency key computation
-            let activity_datetime: DateTime<Utc> = DateTime::parse_from_rfc3339(&act.activity_date)
-                .map(|dt| dt.with_timezone(&Utc))
-                .unwrap_or_else(|_| Utc::now());
-
-            // Collect quote data from BUY/SELL activities with a resolved asset and non-zero price
-            if act.activity_type == ACTIVITY_TYPE_BUY || act.activity_type == ACTIVITY_TYPE_SELL {
-                if let (Some(ref aid), Some(price)) = (&asset_id, act.unit_price) {
-                    if price > Decimal::ZERO {
-                        quote_data.push((
-                            aid.clone(),
-                            price,
-                            activity_datetime,
-                            act.currency.clone(),
-                        ));
-                    }
-                }
-            }
-
-            // Compute idempotency key for content-based deduplication
-            let idempotency_key = compute_idempotency_key(
-                &account_id,
-                &act.activity_type,
-                &activity_datetime,
-                asset_id.as_deref(),
-                act.quantity,
-                act.unit_price,
-                act.amount,
-                act.fee,
-                &act.currency,
-                act.source_record_id.as_deref(),
-                act.notes.as_deref(),
-            );
-
-            activity_upserts.push(ActivityUpsert {
-                id: activity_id,
-                account_id: act.account_id,
-                asset_id,
-                activity_type: act.activity_type,
-                subtype: act.subtype,
-                activity_date: act.activity_date,
-                quantity: act.quantity,
-                unit_price: act.unit_price,
-                currency: act.currency,
-                fee: act.fee,
-                tax: act.tax,
-                amount: act.amount,
-                status: act.status,
-                notes: act.notes,
-                fx_rate: act.fx_rate,
-                metadata: act.metadata,
-                needs_review: act.needs_review,
-                source_system: act.source_system,
-                source_record_id: act.source_record_id,
-                source_group_id: act.source_group_id,
-                idempotency_key: Some(idempotency_key),
-                import_run_id: import_run_id.clone(),
-            });
+        if prepared.upserts.is_empty() {
+            return Ok((0, 0, Vec::new(), 0, Vec::new()));
         }
 
-        let activities_count = activity_upserts.len();
-
+        let activities_count = prepared.upserts.len();
         debug!(
             "Preparing to upsert {} activities and {} assets for account {}",
-            activities_count, assets_created, account_id
+            activities_count, prepared.assets_created, account_id
         );
 
         let bulk_result = self
             .activity_service
-            .upsert_activities_bulk(activity_upserts)
+            .upsert_activities_bulk(prepared.upserts)
             .await?;
         let activities_upserted = bulk_result.upserted;
 
-        // 3b. Create quotes from trade activity prices (dedup by asset+date, last write wins)
-        if let Some(ref quote_store) = self.quote_store {
-            let now = Utc::now();
-            let mut quotes_map: HashMap<String, Quote> = HashMap::new();
-
-            for (asset_id, price, activity_datetime, currency) in &quote_data {
-                let date_str = activity_datetime.format("%Y-%m-%d").to_string();
-                let quote_id = format!("{}_{}_{}", asset_id, date_str, DATA_SOURCE_BROKER);
-                quotes_map.insert(
-                    quote_id.clone(),
-                    Quote {
-                        id: quote_id,
-                        asset_id: asset_id.clone(),
-                        timestamp: *activity_datetime,
-                        open: *price,
-                        high: *price,
-                        low: *price,
-                        close: *price,
-                        adjclose: *price,
-                        volume: Decimal::ZERO,
-                        currency: currency.clone(),
-                        data_source: DATA_SOURCE_BROKER.to_string(),
-                        created_at: now,
-                        notes: None,
-                    },
-                );
-            }
-
-            let quotes: Vec<Quote> = quotes_map.into_values().collect();
-
-            if !quotes.is_empty() {
-                match quote_store.upsert_quotes(&quotes).await {
-                    Ok(count) => {
-                        debug!(
-                            "Saved {} broker-provided quotes from activities for account {}",
-                            count, account_id
-                        );
-                    }
-                    Err(e) => {
-                        warn!(
-                            "Failed to save broker quotes from activities for account {}: {}",
-                            account_id, e
-                        );
-                    }
-                }
-            }
-        }
+        self.save_activity_quotes(&account_id, &prepared.quote_data)
+            .await;
 
         debug!(
             "Upserted {} activities for account {} ({} assets created, {} new asset IDs, {} need review)",
             activities_count,
             account_id,
-            assets_created,
-            new_asset_ids.len(),
-            needs_review_count
+            prepared.assets_created,
+            prepared.new_asset_ids.len(),
+            prepared.needs_review
         );
 
         Ok((
             activities_upserted,
-            assets_created,
-            new_asset_ids,
-            needs_review_count,
+            prepared.assets_created,
+            prepared.new_asset_ids,
+            prepared.needs_review,
+            prepared.currency_mismatches,
         ))
     }
 
+    async fn resync_account_activities(
+        &self,
+        account_id: String,
+        import_run_id: Option<String>,
+        activities_data: Vec<AccountUniversalActivity>,
+        since: Option<NaiveDate>,
+    ) -> Result<ResyncWriteOutcome> {
+        let prepared = self
+            .prepare_activity_upserts(&account_id, import_run_id, &activities_data)
+            .await?;
+        let scope = ResyncScope {
+            references: activities_data
+                .iter()
+                .flat_map(fetched_activity_references)
+                .collect(),
+            source_systems: prepared
+                .upserts
+                .iter()
+                .filter_map(|upsert| upsert.source_system.as_deref())
+                .map(str::to_ascii_uppercase)
+                .collect(),
+            since,
+        };
+
+        let result = self
+            .activity_service
+            .upsert_activities_bulk_removing_stale(
+                &account_id,
+                prepared.upserts,
+                Box::new(move |stored| plan_stale_activity_removal(stored, &scope)),
+            )
+            .await?;
+        if !result.plan.remove_ids.is_empty() {
+            info!(
+                "Removed {} activities no longer returned by the broker for account {}",
+                result.plan.remove_ids.len(),
+                account_id
+            );
+        }
+
+        self.save_activity_quotes(&account_id, &prepared.quote_data)
+            .await;
+
+        Ok(ResyncWriteOutcome {
+            upserted: result.upserted.upserted,
+            assets_created: prepared.assets_created,
+            needs_review: prepared.needs_review,
+            plan: result.plan,
+        })
+    }
+
     async fn finalize_activity_sync_success(
         &self,
         account_id: String,
@@ -608,6 +519,24 @@ impl BrokerSyncServiceTrait for BrokerSyncService {
         Ok(runs)
     }
 
+    fn get_sync_runs_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<ImportRunWindow> {
+        self.import_run_repository.get_by_run_type_started_between(
+            SYNC_RUN_TYPE,
+            from,
+            to,
+            SYNC_RUN_HISTORY_LIMIT,
+        )
+    }
+
+    fn get_sync_run_totals(&self) -> Result<ImportRunTotals> {
+        self.import_run_repository
+            .get_totals_by_run_type(SYNC_RUN_TYPE)
+    }
+
     async fn create_import_run(&self, account_id: &str, mode: ImportRunMode) -> Result<ImportRun> {
         let import_run = ImportRun::new(
             account_id.to_string(),
@@ -1141,6 +1070,203 @@ impl BrokerSyncServiceTrait for BrokerSyncService {
     }
 }
 
+impl BrokerSyncService {
+    /// Maps broker activities to deduplicated upserts, creating their assets and FX pairs.
+    async fn prepare_activity_upserts(
+        &self,
+        account_id: &str,
+        import_run_id: Option<String>,
+        activities_data: &[AccountUniversalActivity],
+    ) -> Result<PreparedActivityUpserts> {
+        let account = self.account_service.get_account(account_id)?;
+        let base_currency = self
+            .account_service
+            .get_base_currency()
+            .filter(|c| !c.trim().is_empty());
+        let account_currency = if !account.currency.is_empty() {
+            Some(account.currency.clone())
+        } else {
+            base_currency.clone()
+        };
+
+        // 1. Map broker data → NewActivity (dedup by activity ID)
+        let mut seen_activity_ids: HashSet<String> = HashSet::new();
+        let mut new_activities: Vec<NewActivity> = Vec::new();
+
+        for activity in activities_data {
+            if let Some(new_act) = mapping::map_broker_activity(
+                activity,
+                account_id,
+                account_currency.as_deref(),
+                base_currency.as_deref(),
+            ) {
+                let activity_id = new_act.id.as_deref().unwrap_or("").to_string();
+                if seen_activity_ids.insert(activity_id) {
+                    new_activities.push(new_act);
+                }
+            }
+        }
+
+        if new_activities.is_empty() {
+            return Ok(PreparedActivityUpserts::default());
+        }
+
+        // 2. Use sync preparation for asset creation + FX registration
+        let prepare_result = self
+            .activity_service
+            .prepare_activities_for_sync(new_activities, &account)
+            .await?;
+        let new_asset_ids = prepare_result.created_asset_ids.clone();
+        let currency_mismatches = prepare_result.currency_mismatches;
+
+        let assets_created = prepare_result.assets_created as usize;
+
+        // Count needs_review activities
+        let needs_review_count = prepare_result
+            .prepared
+            .iter()
+            .filter(|p| p.activity.needs_review.unwrap_or(false))
+            .count();
+
+        // 3. Convert prepared activities into ActivityUpsert payloads
+        //    and collect quote data from trade activities
+        let mut activity_upserts: Vec<ActivityUpsert> = Vec::new();
+        let mut quote_data: Vec<(String, Decimal, DateTime<Utc>, String)> = Vec::new(); // (asset_id, price, datetime, currency)
+
+        for prepared in prepare_result.prepared {
+            let act = prepared.activity;
+            let asset_id = prepared.resolved_asset_id.clone();
+            let activity_id = act.id.unwrap_or_default();
+            if activity_id.is_empty() {
+                continue;
+            }
+
+            // Parse activity date for idempotency key computation
+            let activity_datetime: DateTime<Utc> = DateTime::parse_from_rfc3339(&act.activity_date)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now());
+
+            // Collect quote data from BUY/SELL activities with a resolved asset and non-zero price
+            if act.activity_type == ACTIVITY_TYPE_BUY || act.activity_type == ACTIVITY_TYPE_SELL {
+                if let (Some(ref aid), Some(price)) = (&asset_id, act.unit_price) {
+                    if price > Decimal::ZERO {
+                        quote_data.push((
+                            aid.clone(),
+                            price,
+                            activity_datetime,
+                            act.currency.clone(),
+                        ));
+                    }
+                }
+            }
+
+            // Compute idempotency key for content-based deduplication
+            let idempotency_key = compute_idempotency_key(
+                account_id,
+                &act.activity_type,
+                &activity_datetime,
+                asset_id.as_deref(),
+                act.quantity,
+                act.unit_price,
+                act.amount,
+                act.fee,
+                &act.currency,
+                act.source_record_id.as_deref(),
+                act.notes.as_deref(),
+            );
+
+            activity_upserts.push(ActivityUpsert {
+                id: activity_id,
+                account_id: act.account_id,
+                asset_id,
+                activity_type: act.activity_type,
+                subtype: act.subtype,
+                activity_date: act.activity_date,
+                quantity: act.quantity,
+                unit_price: act.unit_price,
+                currency: act.currency,
+                fee: act.fee,
+                tax: act.tax,
+                amount: act.amount,
+                status: act.status,
+                notes: act.notes,
+                fx_rate: act.fx_rate,
+                metadata: act.metadata,
+                needs_review: act.needs_review,
+                source_system: act.source_system,
+                source_record_id: act.source_record_id,
+                source_group_id: act.source_group_id,
+                idempotency_key: Some(idempotency_key),
+                import_run_id: import_run_id.clone(),
+            });
+        }
+
+        Ok(PreparedActivityUpserts {
+            upserts: activity_upserts,
+            quote_data,
+            assets_created,
+            new_asset_ids,
+            needs_review: needs_review_count,
+            currency_mismatches,
+        })
+    }
+
+    /// Saves the prices of upserted trades as broker quotes (dedup by asset+date, last write
+    /// wins). Best effort: a failure only costs the quotes.
+    async fn save_activity_quotes(
+        &self,
+        account_id: &str,
+        quote_data: &[(String, Decimal, DateTime<Utc>, String)],
+    ) {
+        if let Some(ref quote_store) = self.quote_store {
+            let now = Utc::now();
+            let mut quotes_map: HashMap<String, Quote> = HashMap::new();
+
+            for (asset_id, price, activity_datetime, currency) in quote_data {
+                let date_str = activity_datetime.format("%Y-%m-%d").to_string();
+                let quote_id = format!("{}_{}_{}", asset_id, date_str, DATA_SOURCE_BROKER);
+                quotes_map.insert(
+                    quote_id.clone(),
+                    Quote {
+                        id: quote_id,
+                        asset_id: asset_id.clone(),
+                        timestamp: *activity_datetime,
+                        open: *price,
+                        high: *price,
+                        low: *price,
+                        close: *price,
+                        adjclose: *price,
+                        volume: Decimal::ZERO,
+                        currency: currency.clone(),
+                        data_source: DATA_SOURCE_BROKER.to_string(),
+                        created_at: now,
+                        notes: None,
+                    },
+                );
+            }
+
+            let quotes: Vec<Quote> = quotes_map.into_values().collect();
+
+            if !quotes.is_empty() {
+                match quote_store.upsert_quotes(&quotes).await {
+                    Ok(count) => {
+                        debug!(
+                            "Saved {} broker-provided quotes from activities for account {}",
+                            count, account_id
+                        );
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to save broker quotes from activities for account {}: {}",
+                            account_id, e
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl BrokerSyncService {
     fn should_preserve_manual_snapshot_for_date(
         latest_snapshot: Option<&AccountStateSnapshot>,
@@ -1230,6 +1356,13 @@ impl BrokerSyncService {
                             diff.unchanged_positions += 1;
                         } else {
                             diff.updated_positions += 1;
+                            diff.position_changes.push(PositionChange {
+                                asset_id: asset_id.clone(),
+                                previous_quantity: previous_position.quantity,
+                                current_quantity: current_position.quantity,
+                                previous_cost_basis: previous_position.total_cost_basis,
+                                current_cost_basis: current_position.total_cost_basis,
+                            });
                         }
                     }
                     None => {
@@ -1238,11 +1371,19 @@ impl BrokerSyncService {
                 }
             }
 
-            diff.removed_positions = latest
-                .positions
-                .keys()
-                .filter(|asset_id| !current_positions.contains_key(*asset_id))
-                .count();
+            for (asset_id, previous_position) in &latest.positions {
+                if current_positions.contains_key(asset_id) {
+                    continue;
+                }
+                diff.removed_positions += 1;
+                diff.position_changes.push(PositionChange {
+                    asset_id: asset_id.clone(),
+                    previous_quantity: previous_position.quantity,
+                    current_quantity: Decimal::ZERO,
+                    previous_cost_basis: previous_position.total_cost_basis,
+                    current_cost_basis: Decimal::ZERO,
+                });
+            }
         } else {
             diff.added_positions = current_positions.len();
         }
@@ -1645,6 +1786,11 @@ mod tests {
         assert_eq!(diff.updated_positions, 1);
         assert_eq!(diff.removed_positions, 1);
         assert_eq!(diff.unchanged_positions, 1);
+        assert_eq!(diff.position_changes.len(), 2);
+        assert!(diff
+            .position_changes
+            .iter()
+            .any(|c| c.asset_id == "c" && c.current_quantity == Decimal::ZERO));
     }
 
     #[test]
@@ -0,0 +1,220 @@
+//! Session-only subscription override for developing subscription-gated UI.
+//!
+//! With `WEALTHFOLIO_DEBUG_ENDPOINTS=true`, `set_fake_subscription` replaces the team plan and
+//! status in the user info returned to the frontend, so every gate can be exercised without real
+//! billing. The override lives in memory only: it is never persisted and never sent to the
+//! cloud. Overridden teams carry `simulated_subscription` so the UI can flag them.
+
+use std::sync::RwLock;
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{UserInfo, UserTeam};
+
+/// Environment variable that enables debug-only endpoints and commands.
+pub const DEBUG_ENDPOINTS_ENV: &str = "WEALTHFOLIO_DEBUG_ENDPOINTS";
+
+/// Whether debug-only endpoints and commands are enabled for this process.
+pub fn debug_endpoints_enabled() -> bool {
+    std::env::var(DEBUG_ENDPOINTS_ENV)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FakeSubscriptionState {
+    /// Basic plan; paid features are gated.
+    Free,
+    /// Active paid plan.
+    Pro,
+    /// Paid plan whose subscription has been canceled and ran out.
+    Expired,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SubscriptionOverrideError {
+    #[error("Fake subscriptions require {DEBUG_ENDPOINTS_ENV}=true")]
+    Disabled,
+}
+
+pub struct SubscriptionOverride {
+    enabled: bool,
+    state: RwLock<Option<FakeSubscriptionState>>,
+}
+
+impl SubscriptionOverride {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            state: RwLock::new(None),
+        }
+    }
+
+    /// Enabled by [`DEBUG_ENDPOINTS_ENV`].
+    pub fn from_env() -> Self {
+        Self::new(debug_endpoints_enabled())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The active override, if any. Always `None` when debug endpoints are off.
+    pub fn current(&self) -> Option<FakeSubscriptionState> {
+        if !self.enabled {
+            return None;
+        }
+        *self.state.read().unwrap()
+    }
+
+    /// Sets the override for this session; `None` goes back to the real subscription.
+    pub fn set(
+        &self,
+        state: Option<FakeSubscriptionState>,
+    ) -> Result<(), SubscriptionOverrideError> {
+        if !self.enabled {
+            return Err(SubscriptionOverrideError::Disabled);
+        }
+        *self.state.write().unwrap() = state;
+        Ok(())
+    }
+
+    /// Applies the override to user info fetched from the cloud. Users without a team get a
+    /// placeholder one so plan gates still have something to read.
+    pub fn apply(&self, mut user_info: UserInfo) -> UserInfo {
+        let Some(state) = self.current() else {
+            return user_info;
+        };
+
+        let mut team = user_info.team.take().unwrap_or_else(|| UserTeam {
+            id: "simulated".to_string(),
+            name: "Simulated team".to_string(),
+            logo_url: None,
+            plan: None,
+            subscription_status: None,
+            subscription_current_period_end: None,
+            subscription_cancel_at_period_end: None,
+            canceled_at: None,
+            country_code: None,
+            created_at: None,
+            simulated_subscription: None,
+        });
+        let now = Utc::now();
+        let (plan, status, period_end, canceled_at) = match state {
+            FakeSubscriptionState::Free => ("basic", "active", now + Duration::days(30), None),
+            FakeSubscriptionState::Pro => ("essentials", "active", now + Duration::days(30), None),
+            FakeSubscriptionState::Expired => (
+                "essentials",
+                "canceled",
+                now - Duration::days(1),
+                Some(now - Duration::days(31)),
+            ),
+        };
+        team.plan = Some(plan.to_string());
+        team.subscription_status = Some(status.to_string());
+        team.subscription_current_period_end = Some(period_end.to_rfc3339());
+        team.subscription_cancel_at_period_end = Some(canceled_at.is_some());
+        team.canceled_at = canceled_at.map(|at| at.to_rfc3339());
+        team.simulated_subscription = Some(state);
+
+        user_info.team_id = Some(team.id.clone());
+        user_info.team = Some(team);
+        user_info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_info() -> UserInfo {
+        UserInfo {
+            id: "user-1".to_string(),
+            full_name: None,
+            email: None,
+            avatar_url: None,
+            locale: None,
+            week_starts_on_monday: None,
+            timezone: None,
+            timezone_auto_sync: None,
+            time_format: None,
+            date_format: None,
+            team_id: Some("team-1".to_string()),
+            team_role: None,
+            team: Some(UserTeam {
+                id: "team-1".to_string(),
+                name: "Team".to_string(),
+                logo_url: None,
+                plan: Some("essentials".to_string()),
+                subscription_status: Some("active".to_string()),
+                subscription_current_period_end: None,
+                subscription_cancel_at_period_end: Some(false),
+                canceled_at: None,
+                country_code: None,
+                created_at: None,
+                simulated_subscription: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn override_replaces_the_reported_subscription_in_debug_mode() {
+        let subscription = SubscriptionOverride::new(true);
+
+        subscription.set(Some(FakeSubscriptionState::Free)).unwrap();
+        let team = subscription.apply(user_info()).team.unwrap();
+        assert_eq!(team.id, "team-1");
+        assert_eq!(team.plan.as_deref(), Some("basic"));
+        assert_eq!(
+            team.simulated_subscription,
+            Some(FakeSubscriptionState::Free)
+        );
+
+        subscription
+            .set(Some(FakeSubscriptionState::Expired))
+            .unwrap();
+        let team = subscription.apply(user_info()).team.unwrap();
+        assert_eq!(team.subscription_status.as_deref(), Some("canceled"));
+        assert!(team.canceled_at.is_some());
+
+        subscription.set(None).unwrap();
+        let team = subscription.apply(user_info()).team.unwrap();
+        assert_eq!(team.plan.as_deref(), Some("essentials"));
+        assert_eq!(team.simulated_subscription, None);
+    }
+
+    #[test]
+    fn override_creates_a_team_for_users_without_one() {
+        let subscription = SubscriptionOverride::new(true);
+        subscription.set(Some(FakeSubscriptionState::Pro)).unwrap();
+
+        let mut info = user_info();
+        info.team = None;
+        info.team_id = None;
+        let info = subscription.apply(info);
+
+        let team = info.team.unwrap();
+        assert_eq!(info.team_id.as_deref(), Some(team.id.as_str()));
+        assert_eq!(team.subscription_status.as_deref(), Some("active"));
+        assert_eq!(
+            team.simulated_subscription,
+            Some(FakeSubscriptionState::Pro)
+        );
+    }
+
+    #[test]
+    fn override_is_ignored_outside_debug_mode() {
+        let subscription = SubscriptionOverride::new(false);
+
+        assert!(matches!(
+            subscription.set(Some(FakeSubscriptionState::Free)),
+            Err(SubscriptionOverrideError::Disabled)
+        ));
+        assert_eq!(subscription.current(), None);
+        let team = subscription.apply(user_info()).team.unwrap();
+        assert_eq!(team.plan.as_deref(), Some("essentials"));
+        assert_eq!(team.simulated_subscription, None);
+    }
+}
@@ -0,0 +1,276 @@
+//! Subscription checks that tell "no subscription" apart from "could not check".
+//!
+//! The plan check calls the cloud, so it fails whenever the cloud is unreachable. Callers get
+//! [`SubscriptionStatus::Unknown`] in that case and decide the policy themselves. The scheduler
+//! keeps syncing during an outage when the last successful check, stored in the settings table,
+//! found an active subscription within the configured grace period.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use wealthfolio_core::errors::{Error, Result};
+use wealthfolio_core::settings::SettingsServiceTrait;
+
+use super::models::UserInfo;
+
+/// Settings key holding the last check that found an active subscription.
+pub const SUBSCRIPTION_STATUS_SETTING_KEY: &str = "connect_subscription_last_active";
+
+/// How long a cached active subscription is trusted while the cloud cannot be reached.
+pub const DEFAULT_SUBSCRIPTION_GRACE_HOURS: i64 = 24;
+
+/// Overrides [`DEFAULT_SUBSCRIPTION_GRACE_HOURS`]; `0` skips sync on any failed check.
+pub const SUBSCRIPTION_GRACE_HOURS_ENV: &str = "CONNECT_SUBSCRIPTION_GRACE_HOURS";
+
+/// The grace period from [`SUBSCRIPTION_GRACE_HOURS_ENV`]; unset, negative or unparsable
+/// values fall back to [`DEFAULT_SUBSCRIPTION_GRACE_HOURS`].
+pub fn subscription_grace_period_from_env() -> Duration {
+    Duration::hours(
+        std::env::var(SUBSCRIPTION_GRACE_HOURS_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|hours| *hours >= 0)
+            .unwrap_or(DEFAULT_SUBSCRIPTION_GRACE_HOURS),
+    )
+}
+
+/// Whether the current plan includes broker sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionStatus {
+    Active,
+    /// No subscription, an inactive one, or a plan without broker sync.
+    Inactive,
+    /// The check itself failed, e.g. because the cloud was unreachable.
+    Unknown(String),
+}
+
+/// Evaluates the plan returned by the cloud: broker sync needs an active or trialing
+/// subscription on a plan other than "basic", which only includes device sync.
+pub fn broker_sync_status(user_info: &UserInfo) -> SubscriptionStatus {
+    let Some(team) = &user_info.team else {
+        debug!("[ConnectApi] No team info, broker sync not available");
+        return SubscriptionStatus::Inactive;
+    };
+    if !matches!(
+        team.subscription_status.as_deref(),
+        Some("active") | Some("trialing")
+    ) {
+        debug!("[ConnectApi] No active subscription, broker sync not available");
+        return SubscriptionStatus::Inactive;
+    }
+    match team.plan.as_deref() {
+        None => {
+            debug!("[ConnectApi] No plan metadata, broker sync not available");
+            SubscriptionStatus::Inactive
+        }
+        Some("basic") => {
+            debug!("[ConnectApi] Basic plan, broker sync not available");
+            SubscriptionStatus::Inactive
+        }
+        Some(_) => {
+            debug!("[ConnectApi] Broker sync is available");
+            SubscriptionStatus::Active
+        }
+    }
+}
+
+/// A [`SubscriptionStatus`] after the grace period policy was applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionDecision {
+    Active,
+    /// The check failed, but a check within the grace period found an active subscription.
+    CachedActive {
+        checked_at: DateTime<Utc>,
+        reason: String,
+    },
+    Inactive,
+    /// The check failed and there is no recent active status to fall back on.
+    Unknown(String),
+}
+
+impl SubscriptionDecision {
+    pub fn allows_sync(&self) -> bool {
+        matches!(self, Self::Active | Self::CachedActive { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LastActiveRecord {
+    checked_at: DateTime<Utc>,
+}
+
+pub struct SubscriptionStatusService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+    grace_period: Duration,
+}
+
+impl SubscriptionStatusService {
+    /// A zero `grace_period` never falls back on the cached status.
+    pub fn new(settings_service: Arc<dyn SettingsServiceTrait>, grace_period: Duration) -> Self {
+        Self {
+            settings_service,
+            grace_period,
+        }
+    }
+
+    fn get_record(&self) -> Result<Option<LastActiveRecord>> {
+        Ok(self
+            .settings_service
+            .get_setting_value(SUBSCRIPTION_STATUS_SETTING_KEY)?
+            .and_then(|raw| serde_json::from_str::<LastActiveRecord>(&raw).ok()))
+    }
+
+    async fn save_record(&self, record: Option<&LastActiveRecord>) -> Result<()> {
+        let raw = match record {
+            Some(record) => {
+                serde_json::to_string(record).map_err(|e| Error::Unexpected(e.to_string()))?
+            }
+            None => String::new(),
+        };
+        self.settings_service
+            .set_setting_value(SUBSCRIPTION_STATUS_SETTING_KEY, &raw)
+            .await
+    }
+
+    /// Applies the grace period policy to a fresh check and remembers its outcome: an active
+    /// status is cached, an inactive one clears the cache.
+    pub async fn resolve(
+        &self,
+        status: SubscriptionStatus,
+        now: DateTime<Utc>,
+    ) -> Result<SubscriptionDecision> {
+        match status {
+            SubscriptionStatus::Active => {
+                self.save_record(Some(&LastActiveRecord { checked_at: now }))
+                    .await?;
+                Ok(SubscriptionDecision::Active)
+            }
+            SubscriptionStatus::Inactive => {
+                if self.get_record()?.is_some() {
+                    self.save_record(None).await?;
+                }
+                Ok(SubscriptionDecision::Inactive)
+            }
+            SubscriptionStatus::Unknown(reason) => {
+                let cached = self.get_record()?.filter(|record| {
+                    self.grace_period > Duration::zero()
+                        && record.checked_at <= now
+                        && now - record.checked_at <= self.grace_period
+                });
+                Ok(match cached {
+                    Some(record) => SubscriptionDecision::CachedActive {
+                        checked_at: record.checked_at,
+                        reason,
+                    },
+                    None => SubscriptionDecision::Unknown(reason),
+                })
+            }
+        }
+    }
+}
+
+/// The decision the scheduler acts on for a fresh check. When the cached status cannot be read
+/// or saved, the check is taken as is rather than failing the run.
+pub async fn resolve_broker_sync_subscription(
+    settings_service: Arc<dyn SettingsServiceTrait>,
+    grace_period: Duration,
+    status: SubscriptionStatus,
+) -> SubscriptionDecision {
+    let service = SubscriptionStatusService::new(settings_service, grace_period);
+    match service.resolve(status.clone(), Utc::now()).await {
+        Ok(decision) => decision,
+        Err(e) => {
+            warn!("[Connect] Failed to apply subscription grace period: {}", e);
+            match status {
+                SubscriptionStatus::Active => SubscriptionDecision::Active,
+                SubscriptionStatus::Inactive => SubscriptionDecision::Inactive,
+                SubscriptionStatus::Unknown(reason) => SubscriptionDecision::Unknown(reason),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wealthfolio_core::settings::MemorySettingsService;
+
+    fn unreachable() -> SubscriptionStatus {
+        SubscriptionStatus::Unknown("Request failed: connection refused".to_string())
+    }
+
+    #[tokio::test]
+    async fn unknown_within_grace_window_uses_cached_active_status() {
+        let service = SubscriptionStatusService::new(
+            Arc::new(MemorySettingsService::default()),
+            Duration::hours(DEFAULT_SUBSCRIPTION_GRACE_HOURS),
+        );
+        let checked_at = Utc::now();
+        assert_eq!(
+            service
+                .resolve(SubscriptionStatus::Active, checked_at)
+                .await
+                .unwrap(),
+            SubscriptionDecision::Active
+        );
+
+        let during_outage = service
+            .resolve(unreachable(), checked_at + Duration::hours(3))
+            .await
+            .unwrap();
+        assert!(during_outage.allows_sync());
+        assert!(matches!(
+            during_outage,
+            SubscriptionDecision::CachedActive { checked_at: cached, .. } if cached == checked_at
+        ));
+
+        let after_grace = service
+            .resolve(unreachable(), checked_at + Duration::hours(25))
+            .await
+            .unwrap();
+        assert!(!after_grace.allows_sync());
+    }
+
+    #[tokio::test]
+    async fn inactive_check_clears_the_cached_status() {
+        let service = SubscriptionStatusService::new(
+            Arc::new(MemorySettingsService::default()),
+            Duration::hours(24),
+        );
+        let now = Utc::now();
+        service
+            .resolve(SubscriptionStatus::Active, now)
+            .await
+            .unwrap();
+        service
+            .resolve(SubscriptionStatus::Inactive, now)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            service.resolve(unreachable(), now).await.unwrap(),
+            SubscriptionDecision::Unknown("Request failed: connection refused".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_cache_falls_back_to_the_fresh_check() {
+        let settings = Arc::new(MemorySettingsService::failing_reads());
+        assert_eq!(
+            resolve_broker_sync_subscription(
+                settings.clone(),
+                Duration::hours(24),
+                SubscriptionStatus::Inactive
+            )
+            .await,
+            SubscriptionDecision::Inactive
+        );
+        assert_eq!(
+            resolve_broker_sync_subscription(settings, Duration::hours(24), unreachable()).await,
+            SubscriptionDecision::Unknown("Request failed: connection refused".to_string())
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Why a broker sync run failed, kept typed so the scheduler can tell a cloud refusal from a
+//! network failure or a local one without parsing the message.
+
+use wealthfolio_core::errors::{CloudApiError, Error};
+
+/// A broker sync run that did not complete.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BrokerSyncError {
+    /// A request to the cloud API failed.
+    #[error("{0}")]
+    Cloud(CloudApiError),
+    /// Another sync holds the run guard.
+    #[error("Broker sync already running")]
+    AlreadyRunning,
+    /// There is no usable session; only signing in again helps.
+    #[error("{0}")]
+    NotAuthenticated(String),
+    /// Anything else, e.g. writing the synced data locally.
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl BrokerSyncError {
+    /// The cloud error behind this failure, if it came from a request.
+    pub fn cloud_error(&self) -> Option<&CloudApiError> {
+        match self {
+            Self::Cloud(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Whether the cloud refused the run because the subscription expired or does not cover
+    /// broker sync.
+    pub fn is_subscription_required(&self) -> bool {
+        self.cloud_error()
+            .is_some_and(CloudApiError::is_subscription_required)
+    }
+
+    /// Whether the same run will keep failing no matter how often it is retried.
+    pub fn is_permanent(&self) -> bool {
+        self.cloud_error().is_some_and(CloudApiError::is_permanent)
+    }
+
+    /// Identifies the failure across runs; request ids differ on every call.
+    pub fn signature(&self) -> String {
+        match self {
+            Self::Cloud(error) => error.signature(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl From<Error> for BrokerSyncError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::CloudApi(error) => Self::Cloud(error),
+            other => Self::Failed(other.to_string()),
+        }
+    }
+}
+
+impl From<CloudApiError> for BrokerSyncError {
+    fn from(error: CloudApiError) -> Self {
+        Self::Cloud(error)
+    }
+}
+
+impl From<String> for BrokerSyncError {
+    fn from(message: String) -> Self {
+        Self::Failed(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(status: u16, request: &str) -> BrokerSyncError {
+        CloudApiError::Status {
+            status,
+            code: None,
+            message: Some("Unsupported brokerage".to_string()),
+            request: format!("clientRequestId={}, requestId=none", request),
+        }
+        .into()
+    }
+
+    #[test]
+    fn only_non_retryable_client_errors_are_permanent() {
+        assert!(status(422, "a").is_permanent());
+        assert!(BrokerSyncError::from(CloudApiError::InvalidResponse {
+            message: "missing field `id`".to_string(),
+            request: String::new(),
+        })
+        .is_permanent());
+        assert!(!status(429, "a").is_permanent());
+        assert!(!status(503, "a").is_permanent());
+        assert!(!BrokerSyncError::from(CloudApiError::Timeout {
+            message: "operation timed out".to_string(),
+            request: String::new(),
+        })
+        .is_permanent());
+        assert!(
+            !BrokerSyncError::Failed("API error 422: from a message".to_string()).is_permanent()
+        );
+    }
+
+    #[test]
+    fn subscription_refusals_are_told_apart_from_auth_errors() {
+        let refused = |status, code: Option<&str>| {
+            BrokerSyncError::from(CloudApiError::Status {
+                status,
+                code: code.map(str::to_string),
+                message: None,
+                request: String::new(),
+            })
+        };
+        assert!(refused(402, None).is_subscription_required());
+        assert!(refused(403, Some("SYNC_SUBSCRIPTION_REQUIRED")).is_subscription_required());
+        assert!(!refused(403, None).is_subscription_required());
+        assert!(!BrokerSyncError::Failed("API error 402".to_string()).is_subscription_required());
+    }
+
+    #[test]
+    fn signatures_ignore_request_ids() {
+        assert_eq!(status(422, "a").signature(), status(422, "b").signature());
+        assert_ne!(status(422, "a").signature(), status(400, "a").signature());
+        assert_eq!(
+            status(422, "a").to_string(),
+            "API error 422: Unsupported brokerage (clientRequestId=a, requestId=none)"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Suspension of scheduled broker sync after repeated permanent failures.
+//!
+//! A permanent failure (a 4xx the cloud will keep returning, or a response the client cannot
+//! parse) does not go away by retrying. Once the same permanent failure comes back for the
+//! configured number of consecutive scheduled runs, the scheduler suspends itself instead of
+//! retrying every tick. The suspension is stored in the settings table and lasts until the user
+//! resumes, a manual sync succeeds, or the app is updated to another version.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use wealthfolio_core::errors::{Error, Result};
+use wealthfolio_core::settings::SettingsServiceTrait;
+
+use super::sync_error::BrokerSyncError;
+use crate::broker_ingest::SyncSuspension;
+
+/// Settings key holding the scheduled sync failure streak and suspension.
+pub const SYNC_SUSPENSION_SETTING_KEY: &str = "connect_scheduled_sync_suspension";
+
+/// Consecutive identical permanent failures before scheduled sync is suspended.
+pub const DEFAULT_PERMANENT_FAILURE_THRESHOLD: u32 = 5;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SuspensionRecord {
+    signature: String,
+    consecutive_failures: u32,
+    last_error: String,
+    suspended_at: Option<DateTime<Utc>>,
+    app_version: String,
+}
+
+pub struct SyncSuspensionService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+    threshold: u32,
+    app_version: String,
+}
+
+impl SyncSuspensionService {
+    /// A `threshold` of 0 never suspends.
+    pub fn new(
+        settings_service: Arc<dyn SettingsServiceTrait>,
+        threshold: u32,
+        app_version: impl Into<String>,
+    ) -> Self {
+        Self {
+            settings_service,
+            threshold,
+            app_version: app_version.into(),
+        }
+    }
+
+    /// Reads the stored streak. One written by another app version is ignored, so an update
+    /// lifts the suspension.
+    fn get_record(&self) -> Result<Option<SuspensionRecord>> {
+        Ok(self
+            .settings_service
+            .get_setting_value(SYNC_SUSPENSION_SETTING_KEY)?
+            .and_then(|raw| serde_json::from_str::<SuspensionRecord>(&raw).ok())
+            .filter(|record| record.app_version == self.app_version))
+    }
+
+    async fn save_record(&self, record: Option<&SuspensionRecord>) -> Result<()> {
+        let raw = match record {
+            Some(record) => {
+                serde_json::to_string(record).map_err(|e| Error::Unexpected(e.to_string()))?
+            }
+            None => String::new(),
+        };
+        self.settings_service
+            .set_setting_value(SYNC_SUSPENSION_SETTING_KEY, &raw)
+            .await
+    }
+
+    /// The active suspension, if scheduled sync is suspended.
+    pub fn get_suspension(&self) -> Result<Option<SyncSuspension>> {
+        Ok(self.get_record()?.and_then(|record| {
+            record.suspended_at.map(|suspended_at| SyncSuspension {
+                suspended_at,
+                consecutive_failures: record.consecutive_failures,
+                last_error: record.last_error,
+            })
+        }))
+    }
+
+    pub fn is_suspended(&self) -> Result<bool> {
+        Ok(self.get_suspension()?.is_some())
+    }
+
+    /// Records a failed scheduled run. A permanent failure extends the streak when it matches
+    /// the previous one and starts a new streak otherwise; any other failure ends the streak.
+    /// Returns the suspension when this failure is the one that triggers it.
+    pub async fn record_failure(
+        &self,
+        error: &BrokerSyncError,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SyncSuspension>> {
+        let previous = self.get_record()?;
+        if !error.is_permanent() {
+            if previous.is_some() {
+                self.save_record(None).await?;
+            }
+            return Ok(None);
+        }
+
+        let signature = error.signature();
+        let consecutive_failures = match &previous {
+            Some(record) if record.signature == signature => {
+                record.consecutive_failures.saturating_add(1)
+            }
+            _ => 1,
+        };
+        let newly_suspended = self.threshold > 0
+            && consecutive_failures >= self.threshold
+            && previous.as_ref().and_then(|r| r.suspended_at).is_none();
+        let record = SuspensionRecord {
+            signature,
+            consecutive_failures,
+            last_error: error.to_string(),
+            suspended_at: previous
+                .and_then(|r| r.suspended_at)
+                .or_else(|| newly_suspended.then_some(now)),
+            app_version: self.app_version.clone(),
+        };
+        self.save_record(Some(&record)).await?;
+
+        Ok(newly_suspended.then_some(SyncSuspension {
+            suspended_at: now,
+            consecutive_failures,
+            last_error: record.last_error,
+        }))
+    }
+
+    /// Clears the streak and any suspension, after a successful sync or when the user resumes.
+    pub async fn clear(&self) -> Result<()> {
+        if self
+            .settings_service
+            .get_setting_value(SYNC_SUSPENSION_SETTING_KEY)?
+            .is_some_and(|raw| !raw.is_empty())
+        {
+            self.save_record(None).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wealthfolio_core::errors::CloudApiError;
+    use wealthfolio_core::settings::MemorySettingsService;
+
+    fn api_error(status: u16, message: &str, request_id: &str) -> BrokerSyncError {
+        CloudApiError::Status {
+            status,
+            code: None,
+            message: Some(message.to_string()),
+            request: format!("clientRequestId={}, requestId=none", request_id),
+        }
+        .into()
+    }
+
+    fn unprocessable(request_id: &str) -> BrokerSyncError {
+        api_error(422, "Unsupported brokerage", request_id)
+    }
+
+    #[tokio::test]
+    async fn identical_permanent_failures_suspend_and_success_clears() {
+        let settings = Arc::new(MemorySettingsService::default());
+        let service = SyncSuspensionService::new(settings.clone(), 3, "3.0.0");
+        let now = Utc::now();
+
+        assert_eq!(
+            service
+                .record_failure(&unprocessable("1"), now)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            service
+                .record_failure(&unprocessable("2"), now)
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(!service.is_suspended().unwrap());
+
+        let suspension = service
+            .record_failure(&unprocessable("3"), now)
+            .await
+            .unwrap()
+            .expect("third identical failure suspends");
+        assert_eq!(suspension.consecutive_failures, 3);
+        assert!(service.is_suspended().unwrap());
+        // Already suspended: further failures do not report a new suspension.
+        assert_eq!(
+            service
+                .record_failure(&unprocessable("4"), now)
+                .await
+                .unwrap(),
+            None
+        );
+
+        service.clear().await.unwrap();
+        assert!(!service.is_suspended().unwrap());
+        assert_eq!(
+            service
+                .record_failure(&unprocessable("5"), now)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn different_or_transient_failures_restart_the_streak() {
+        let settings = Arc::new(MemorySettingsService::default());
+        let service = SyncSuspensionService::new(settings, 2, "3.0.0");
+        let now = Utc::now();
+
+        service
+            .record_failure(&unprocessable("1"), now)
+            .await
+            .unwrap();
+        service
+            .record_failure(&api_error(400, "Invalid account", "2"), now)
+            .await
+            .unwrap();
+        assert!(!service.is_suspended().unwrap());
+
+        service
+            .record_failure(
+                &CloudApiError::Timeout {
+                    message: "operation timed out".to_string(),
+                    request: String::new(),
+                }
+                .into(),
+                now,
+            )
+            .await
+            .unwrap();
+        service
+            .record_failure(&unprocessable("2"), now)
+            .await
+            .unwrap();
+        assert!(!service.is_suspended().unwrap());
+    }
+
+    #[tokio::test]
+    async fn an_app_update_lifts_the_suspension() {
+        let settings = Arc::new(MemorySettingsService::default());
+        let old = SyncSuspensionService::new(settings.clone(), 1, "3.0.0");
+        old.record_failure(&unprocessable("1"), Utc::now())
+            .await
+            .unwrap();
+        assert!(old.is_suspended().unwrap());
+
+        let updated = SyncSuspensionService::new(settings, 1, "3.0.1");
+        assert!(!updated.is_suspended().unwrap());
+    }
+}
@@ -1,16 +1,24 @@
 //! Traits defining the contract for sync operations.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 
+use super::account_resync::ResyncWriteOutcome;
 use super::models::{
     AccountUniversalActivity, BrokerAccount, BrokerBrokerage, BrokerConnection,
     BrokerHoldingsResponse, HoldingsBalance, HoldingsDiff, HoldingsOptionPosition,
     HoldingsPosition, PaginatedUniversalActivity, SyncAccountsResponse, SyncConnectionsResponse,
 };
-use crate::broker_ingest::BrokerSyncState;
-use crate::broker_ingest::{ImportRun, ImportRunMode, ImportRunStatus, ImportRunSummary};
+use crate::broker_ingest::{
+    build_metrics_history, build_sync_dashboard, BrokerSyncState, MetricsHistory,
+    MetricsHistoryQuery, SyncDashboard, SYNC_DASHBOARD_WINDOW_DAYS,
+};
+use crate::broker_ingest::{
+    ImportRun, ImportRunMode, ImportRunStatus, ImportRunSummary, ImportRunTotals, ImportRunWindow,
+};
 use crate::platform::Platform;
 use wealthfolio_core::accounts::Account;
+use wealthfolio_core::activities::CurrencyMismatch;
 use wealthfolio_core::errors::Result;
 
 /// Trait for fetching data from the cloud broker API
@@ -97,13 +105,25 @@ pub trait BrokerSyncServiceTrait: Send + Sync {
     async fn mark_activity_sync_attempt(&self, account_id: String) -> Result<()>;
 
     /// Upsert a batch/page of broker activities for a local account.
-    /// Returns (activities_upserted, assets_inserted, new_asset_ids, needs_review_count).
+    /// Returns (activities_upserted, assets_inserted, new_asset_ids, needs_review_count,
+    /// currency_mismatches).
     async fn upsert_account_activities(
         &self,
         account_id: String,
         import_run_id: Option<String>,
         activities: Vec<AccountUniversalActivity>,
-    ) -> Result<(usize, usize, Vec<String>, usize)>;
+    ) -> Result<(usize, usize, Vec<String>, usize, Vec<CurrencyMismatch>)>;
+
+    /// Upsert an account's complete fetch of activities since `since` and delete its synced
+    /// activities of that window the fetch no longer contains, in one transaction. Locally
+    /// edited activities are kept. See [`crate::broker::account_resync`].
+    async fn resync_account_activities(
+        &self,
+        account_id: String,
+        import_run_id: Option<String>,
+        activities: Vec<AccountUniversalActivity>,
+        since: Option<NaiveDate>,
+    ) -> Result<ResyncWriteOutcome>;
 
     /// Finalize an activity sync as successful for an account.
     async fn finalize_activity_sync_success(
@@ -140,6 +160,40 @@ pub trait BrokerSyncServiceTrait: Send + Sync {
         offset: i64,
     ) -> Result<Vec<ImportRun>>;
 
+    /// Sync runs started in `[from, to)`, newest first. Large windows are capped at the most
+    /// recent runs and flagged as truncated.
+    fn get_sync_runs_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<ImportRunWindow>;
+
+    /// Totals over every recorded sync run, aggregated by the repository.
+    fn get_sync_run_totals(&self) -> Result<ImportRunTotals>;
+
+    /// Aggregate sync statistics built from the local sync states, the sync runs of the
+    /// dashboard window and the all-time run totals. Never calls the cloud API.
+    fn get_sync_dashboard(&self, sync_in_progress: bool) -> Result<SyncDashboard> {
+        let now = Utc::now();
+        let window =
+            self.get_sync_runs_between(now - Duration::days(SYNC_DASHBOARD_WINDOW_DAYS), now)?;
+        let totals = self.get_sync_run_totals()?;
+        let states = self.get_all_sync_states()?;
+        let mut dashboard =
+            build_sync_dashboard(&states, &window.runs, &totals, sync_in_progress, now);
+        dashboard.window_truncated = window.truncated;
+        Ok(dashboard)
+    }
+
+    /// One sync metric over time, bucketed from the sync runs of the queried range. Never calls
+    /// the cloud API.
+    fn get_metrics_history(&self, query: &MetricsHistoryQuery) -> Result<MetricsHistory> {
+        let window = self.get_sync_runs_between(query.from, query.to)?;
+        let mut history = build_metrics_history(&window.runs, query);
+        history.truncated = window.truncated;
+        Ok(history)
+    }
+
     /// Create a new import run for broker sync.
     async fn create_import_run(&self, account_id: &str, mode: ImportRunMode) -> Result<ImportRun>;
 
@@ -0,0 +1,275 @@
+//! Aggregate sync statistics for the dashboard, computed from the persisted sync states
+//! and import run history only.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::models::{
+    BrokerSyncState, ImportRun, ImportRunStatus, ImportRunTotals, ImportRunType, SyncStatus,
+};
+
+/// Window used for the success rate and average duration.
+pub const SYNC_DASHBOARD_WINDOW_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConnectionHealth {
+    pub account_id: String,
+    pub provider: String,
+    pub status: SyncStatus,
+    pub last_attempted_at: Option<DateTime<Utc>>,
+    pub last_successful_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDashboard {
+    pub state: SyncStatus,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Finished sync runs started inside the window. Cancelled runs are not counted.
+    pub runs_in_window: u32,
+    /// Share of `runs_in_window` that applied or reached review, between 0 and 1.
+    /// `None` when there were no finished runs in the window.
+    pub success_rate: Option<f64>,
+    pub average_duration_ms: Option<i64>,
+    /// The window held more sync runs than are read at once, so the window figures cover the
+    /// most recent runs only.
+    #[serde(default)]
+    pub window_truncated: bool,
+    /// Activities inserted or updated across every recorded sync run.
+    pub total_activities_synced: u64,
+    pub connections: Vec<SyncConnectionHealth>,
+    /// Holdings were not recomputed after a sync and still need a manual recompute.
+    /// Filled in by runtimes that can defer the recompute.
+    #[serde(default)]
+    pub holdings_recompute_pending: bool,
+    /// Scheduled sync stopped retrying after repeated permanent failures. Filled in by
+    /// runtimes that run a sync scheduler.
+    #[serde(default)]
+    pub scheduler_suspension: Option<SyncSuspension>,
+}
+
+/// Scheduled sync suspension: the scheduler no longer retries until it is resumed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSuspension {
+    pub suspended_at: DateTime<Utc>,
+    pub consecutive_failures: u32,
+    pub last_error: String,
+}
+
+/// Builds the dashboard from sync states, the import runs of the window and the totals over
+/// every sync run. Runs outside the window are ignored. `sync_in_progress` reports a run
+/// holding the broker sync guard, which may not have persisted a state yet.
+pub fn build_sync_dashboard(
+    states: &[BrokerSyncState],
+    runs: &[ImportRun],
+    totals: &ImportRunTotals,
+    sync_in_progress: bool,
+    now: DateTime<Utc>,
+) -> SyncDashboard {
+    let window_start = now - Duration::days(SYNC_DASHBOARD_WINDOW_DAYS);
+
+    let mut runs_in_window = 0u32;
+    let mut successes = 0u32;
+    let mut duration_total_ms = 0i64;
+    let mut duration_count = 0i64;
+    for run in runs
+        .iter()
+        .filter(|run| run.run_type == ImportRunType::Sync && run.started_at >= window_start)
+    {
+        let succeeded = match run.status {
+            ImportRunStatus::Applied | ImportRunStatus::NeedsReview => true,
+            ImportRunStatus::Failed => false,
+            ImportRunStatus::Running | ImportRunStatus::Cancelled => continue,
+        };
+        runs_in_window += 1;
+        if succeeded {
+            successes += 1;
+        }
+        if let Some(finished_at) = run.finished_at {
+            duration_total_ms += (finished_at - run.started_at).num_milliseconds().max(0);
+            duration_count += 1;
+        }
+    }
+
+    let last_attempt_at = states
+        .iter()
+        .filter_map(|state| state.last_attempted_at)
+        .chain(totals.last_started_at)
+        .max();
+    let last_success_at = states
+        .iter()
+        .filter_map(|state| state.last_successful_at)
+        .chain(totals.last_applied_at)
+        .max();
+
+    let state = if sync_in_progress
+        || states
+            .iter()
+            .any(|state| state.sync_status == SyncStatus::Running)
+    {
+        SyncStatus::Running
+    } else if states
+        .iter()
+        .any(|state| state.sync_status == SyncStatus::Failed)
+    {
+        SyncStatus::Failed
+    } else if states
+        .iter()
+        .any(|state| state.sync_status == SyncStatus::NeedsReview)
+    {
+        SyncStatus::NeedsReview
+    } else {
+        SyncStatus::Idle
+    };
+
+    let connections = states
+        .iter()
+        .map(|state| SyncConnectionHealth {
+            account_id: state.account_id.clone(),
+            provider: state.provider.clone(),
+            status: state.sync_status.clone(),
+            last_attempted_at: state.last_attempted_at,
+            last_successful_at: state.last_successful_at,
+            last_error: state.last_error.clone(),
+        })
+        .collect();
+
+    SyncDashboard {
+        state,
+        last_attempt_at,
+        last_success_at,
+        runs_in_window,
+        success_rate: (runs_in_window > 0)
+            .then(|| f64::from(successes) / f64::from(runs_in_window)),
+        average_duration_ms: (duration_count > 0).then(|| duration_total_ms / duration_count),
+        window_truncated: false,
+        total_activities_synced: totals.activities_synced,
+        connections,
+        holdings_recompute_pending: false,
+        scheduler_suspension: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broker_ingest::models::{ImportRunMode, ImportRunSummary, ReviewMode};
+    use chrono::TimeZone;
+
+    fn run(
+        run_type: ImportRunType,
+        status: ImportRunStatus,
+        started_at: DateTime<Utc>,
+        duration_secs: i64,
+        inserted: u32,
+    ) -> ImportRun {
+        let mut run = ImportRun::new(
+            "acc-1".to_string(),
+            "SNAPTRADE".to_string(),
+            run_type,
+            ImportRunMode::Incremental,
+            ReviewMode::Never,
+        );
+        run.status = status;
+        run.started_at = started_at;
+        run.finished_at = Some(started_at + Duration::seconds(duration_secs));
+        run.summary = Some(ImportRunSummary {
+            inserted,
+            updated: 1,
+            ..Default::default()
+        });
+        run
+    }
+
+    #[test]
+    fn aggregates_seeded_run_history() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+        let day = |d: i64| now - Duration::days(d);
+        let runs = vec![
+            run(ImportRunType::Sync, ImportRunStatus::Applied, day(1), 10, 5),
+            run(
+                ImportRunType::Sync,
+                ImportRunStatus::NeedsReview,
+                day(2),
+                20,
+                3,
+            ),
+            run(ImportRunType::Sync, ImportRunStatus::Failed, day(3), 30, 0),
+            run(ImportRunType::Sync, ImportRunStatus::Applied, day(4), 40, 2),
+            run(
+                ImportRunType::Sync,
+                ImportRunStatus::Cancelled,
+                day(5),
+                99,
+                0,
+            ),
+            // Outside the window: ignored, the totals below cover it.
+            run(
+                ImportRunType::Sync,
+                ImportRunStatus::Failed,
+                day(45),
+                500,
+                10,
+            ),
+            // CSV imports are not sync runs.
+            run(
+                ImportRunType::Import,
+                ImportRunStatus::Applied,
+                day(1),
+                5,
+                100,
+            ),
+        ];
+        let mut failed = BrokerSyncState::new("acc-2".to_string(), "PLAID".to_string());
+        failed.sync_status = SyncStatus::Failed;
+        failed.last_error = Some("token expired".to_string());
+        failed.last_attempted_at = Some(now - Duration::hours(1));
+        let states = vec![
+            BrokerSyncState::new("acc-1".to_string(), "SNAPTRADE".to_string()),
+            failed,
+        ];
+
+        let totals = ImportRunTotals {
+            activities_synced: 26,
+            last_started_at: Some(day(1)),
+            last_applied_at: Some(day(1) + Duration::seconds(10)),
+        };
+
+        let dashboard = build_sync_dashboard(&states, &runs, &totals, false, now);
+
+        assert_eq!(dashboard.runs_in_window, 4);
+        assert_eq!(dashboard.success_rate, Some(0.75));
+        assert_eq!(dashboard.average_duration_ms, Some(25_000));
+        assert_eq!(dashboard.total_activities_synced, 26);
+        assert_eq!(dashboard.last_attempt_at, Some(now - Duration::hours(1)));
+        assert_eq!(
+            dashboard.last_success_at,
+            Some(day(1) + Duration::seconds(10))
+        );
+        assert_eq!(dashboard.state, SyncStatus::Failed);
+        assert_eq!(dashboard.connections.len(), 2);
+        assert_eq!(
+            dashboard.connections[1].last_error.as_deref(),
+            Some("token expired")
+        );
+    }
+
+    #[test]
+    fn empty_history_reports_no_rate_and_tracks_running_guard() {
+        let now = Utc::now();
+
+        let none = ImportRunTotals::default();
+        let idle = build_sync_dashboard(&[], &[], &none, false, now);
+        assert_eq!(idle.state, SyncStatus::Idle);
+        assert_eq!(idle.success_rate, None);
+        assert_eq!(idle.average_duration_ms, None);
+        assert_eq!(idle.total_activities_synced, 0);
+
+        let running = build_sync_dashboard(&[], &[], &none, true, now);
+        assert_eq!(running.state, SyncStatus::Running);
+    }
+}
@@ -0,0 +1,343 @@
+//! Sync metrics as a time series, for dashboards that pull history instead of scraping.
+//!
+//! Built from the persisted sync run history only: every finished run is placed in the bucket
+//! its start falls into. The requested range and bucket count are capped so a single request
+//! cannot walk an unbounded history.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::models::{ImportRun, ImportRunStatus, ImportRunType};
+
+/// Longest range a history request may cover.
+pub const MAX_METRICS_HISTORY_DAYS: i64 = 366;
+
+/// Most buckets a history request may return.
+pub const MAX_METRICS_HISTORY_BUCKETS: i64 = 1_000;
+
+/// Range used when the request does not give a start.
+const DEFAULT_METRICS_HISTORY_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsHistoryMetric {
+    /// Share of finished sync runs that applied or reached review, between 0 and 1.
+    SyncSuccessRate,
+    /// Average duration of finished sync runs, in milliseconds.
+    SyncDuration,
+    /// Records downloaded from the cloud by sync runs.
+    DataUsage,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsBucket {
+    Hour,
+    #[default]
+    Day,
+    /// Weeks start on Monday.
+    Week,
+}
+
+impl MetricsBucket {
+    fn duration(self) -> Duration {
+        match self {
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::weeks(1),
+        }
+    }
+
+    /// Start of the bucket containing `at`.
+    fn floor(self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let day = Utc.from_utc_datetime(&at.date_naive().and_time(NaiveTime::MIN));
+        match self {
+            Self::Hour => day + Duration::hours(i64::from(at.hour())),
+            Self::Day => day,
+            Self::Week => day - Duration::days(i64::from(at.weekday().num_days_from_monday())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MetricsHistoryError {
+    #[error("Invalid date '{0}'; expected RFC 3339 or YYYY-MM-DD")]
+    InvalidDate(String),
+
+    #[error("'from' must be before 'to'")]
+    EmptyRange,
+
+    #[error("Range covers more than {MAX_METRICS_HISTORY_DAYS} days")]
+    RangeTooLong,
+
+    #[error("Range needs more than {MAX_METRICS_HISTORY_BUCKETS} buckets; use a coarser bucket")]
+    TooManyBuckets,
+}
+
+/// A validated history request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsHistoryQuery {
+    pub metric: MetricsHistoryMetric,
+    pub bucket: MetricsBucket,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+fn parse_bound(value: &str) -> Result<DateTime<Utc>, MetricsHistoryError> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN)))
+        .map_err(|_| MetricsHistoryError::InvalidDate(value.to_string()))
+}
+
+impl MetricsHistoryQuery {
+    /// Validates a request. `to` defaults to `now` and `from` to 30 days before `to`.
+    pub fn new(
+        metric: MetricsHistoryMetric,
+        from: Option<&str>,
+        to: Option<&str>,
+        bucket: Option<MetricsBucket>,
+        now: DateTime<Utc>,
+    ) -> Result<Self, MetricsHistoryError> {
+        let bucket = bucket.unwrap_or_default();
+        let to = to.map(parse_bound).transpose()?.unwrap_or(now);
+        let from = match from {
+            Some(from) => parse_bound(from)?,
+            None => to - Duration::days(DEFAULT_METRICS_HISTORY_DAYS),
+        };
+        if from >= to {
+            return Err(MetricsHistoryError::EmptyRange);
+        }
+        if to - from > Duration::days(MAX_METRICS_HISTORY_DAYS) {
+            return Err(MetricsHistoryError::RangeTooLong);
+        }
+        let span = to - bucket.floor(from);
+        let buckets = (span.num_seconds() + bucket.duration().num_seconds() - 1)
+            / bucket.duration().num_seconds();
+        if buckets > MAX_METRICS_HISTORY_BUCKETS {
+            return Err(MetricsHistoryError::TooManyBuckets);
+        }
+        Ok(Self {
+            metric,
+            bucket,
+            from,
+            to,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsHistoryPoint {
+    /// Start of the bucket.
+    pub timestamp: DateTime<Utc>,
+    /// `None` for rates and averages of buckets without finished runs.
+    pub value: Option<f64>,
+    /// Finished sync runs in the bucket.
+    pub samples: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsHistory {
+    pub metric: MetricsHistoryMetric,
+    pub bucket: MetricsBucket,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub points: Vec<MetricsHistoryPoint>,
+    /// The range held more sync runs than are read at once, so the oldest buckets may be
+    /// missing runs.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Default)]
+struct BucketTotals {
+    finished: u32,
+    succeeded: u32,
+    duration_total_ms: i64,
+    duration_count: i64,
+    fetched: u64,
+}
+
+impl BucketTotals {
+    fn value(&self, metric: MetricsHistoryMetric) -> Option<f64> {
+        match metric {
+            MetricsHistoryMetric::SyncSuccessRate => {
+                (self.finished > 0).then(|| f64::from(self.succeeded) / f64::from(self.finished))
+            }
+            MetricsHistoryMetric::SyncDuration => (self.duration_count > 0)
+                .then(|| self.duration_total_ms as f64 / self.duration_count as f64),
+            MetricsHistoryMetric::DataUsage => Some(self.fetched as f64),
+        }
+    }
+}
+
+/// Aggregates the sync runs started inside the query range into its buckets, including the
+/// empty ones. Running and cancelled runs are not counted.
+pub fn build_metrics_history(runs: &[ImportRun], query: &MetricsHistoryQuery) -> MetricsHistory {
+    let step = query.bucket.duration();
+    let first = query.bucket.floor(query.from);
+    let mut starts = Vec::new();
+    let mut start = first;
+    while start < query.to {
+        starts.push(start);
+        start += step;
+    }
+    let mut totals: Vec<BucketTotals> = starts.iter().map(|_| BucketTotals::default()).collect();
+
+    for run in runs.iter().filter(|run| {
+        run.run_type == ImportRunType::Sync
+            && run.started_at >= query.from
+            && run.started_at < query.to
+    }) {
+        let succeeded = match run.status {
+            ImportRunStatus::Applied | ImportRunStatus::NeedsReview => true,
+            ImportRunStatus::Failed => false,
+            ImportRunStatus::Running | ImportRunStatus::Cancelled => continue,
+        };
+        let index = ((run.started_at - first).num_seconds() / step.num_seconds()) as usize;
+        let Some(bucket) = totals.get_mut(index) else {
+            continue;
+        };
+        bucket.finished += 1;
+        if succeeded {
+            bucket.succeeded += 1;
+        }
+        if let Some(finished_at) = run.finished_at {
+            bucket.duration_total_ms += (finished_at - run.started_at).num_milliseconds().max(0);
+            bucket.duration_count += 1;
+        }
+        if let Some(summary) = &run.summary {
+            bucket.fetched += u64::from(summary.fetched);
+        }
+    }
+
+    MetricsHistory {
+        metric: query.metric,
+        bucket: query.bucket,
+        from: query.from,
+        to: query.to,
+        points: starts
+            .into_iter()
+            .zip(totals)
+            .map(|(timestamp, totals)| MetricsHistoryPoint {
+                timestamp,
+                value: totals.value(query.metric),
+                samples: totals.finished,
+            })
+            .collect(),
+        truncated: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broker_ingest::models::{ImportRunMode, ImportRunSummary, ReviewMode};
+
+    fn run(
+        status: ImportRunStatus,
+        started_at: DateTime<Utc>,
+        secs: i64,
+        fetched: u32,
+    ) -> ImportRun {
+        let mut run = ImportRun::new(
+            "acc-1".to_string(),
+            "SNAPTRADE".to_string(),
+            ImportRunType::Sync,
+            ImportRunMode::Incremental,
+            ReviewMode::Never,
+        );
+        run.status = status;
+        run.started_at = started_at;
+        run.finished_at = Some(started_at + Duration::seconds(secs));
+        run.summary = Some(ImportRunSummary {
+            fetched,
+            ..Default::default()
+        });
+        run
+    }
+
+    fn query(metric: MetricsHistoryMetric) -> MetricsHistoryQuery {
+        MetricsHistoryQuery::new(
+            metric,
+            Some("2026-03-01"),
+            Some("2026-03-04"),
+            Some(MetricsBucket::Day),
+            Utc::now(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn seeded_history_aggregates_into_daily_buckets() {
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+        let runs = vec![
+            run(ImportRunStatus::Applied, at(1, 8), 10, 5),
+            run(ImportRunStatus::Failed, at(1, 20), 30, 0),
+            run(ImportRunStatus::NeedsReview, at(3, 9), 20, 7),
+            run(ImportRunStatus::Cancelled, at(3, 10), 99, 100),
+            // Outside the range.
+            run(ImportRunStatus::Failed, at(5, 0), 1, 50),
+        ];
+
+        let rate = build_metrics_history(&runs, &query(MetricsHistoryMetric::SyncSuccessRate));
+        let values: Vec<_> = rate.points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![Some(0.5), None, Some(1.0)]);
+        assert_eq!(rate.points[0].timestamp, at(1, 0));
+        assert_eq!(
+            rate.points.iter().map(|p| p.samples).collect::<Vec<_>>(),
+            vec![2, 0, 1]
+        );
+
+        let duration = build_metrics_history(&runs, &query(MetricsHistoryMetric::SyncDuration));
+        assert_eq!(duration.points[0].value, Some(20_000.0));
+
+        let usage = build_metrics_history(&runs, &query(MetricsHistoryMetric::DataUsage));
+        let values: Vec<_> = usage.points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![Some(5.0), Some(0.0), Some(7.0)]);
+    }
+
+    #[test]
+    fn range_and_granularity_are_capped() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 31, 0, 0, 0).unwrap();
+        let metric = MetricsHistoryMetric::SyncSuccessRate;
+
+        assert_eq!(
+            MetricsHistoryQuery::new(metric, Some("2024-01-01"), None, None, now),
+            Err(MetricsHistoryError::RangeTooLong)
+        );
+        assert_eq!(
+            MetricsHistoryQuery::new(
+                metric,
+                Some("2026-01-01"),
+                None,
+                Some(MetricsBucket::Hour),
+                now
+            ),
+            Err(MetricsHistoryError::TooManyBuckets)
+        );
+        assert_eq!(
+            MetricsHistoryQuery::new(metric, Some("2026-04-01"), None, None, now),
+            Err(MetricsHistoryError::EmptyRange)
+        );
+        let weekly = MetricsHistoryQuery::new(
+            metric,
+            Some("2025-04-01"),
+            None,
+            Some(MetricsBucket::Week),
+            now,
+        )
+        .unwrap();
+        // 2025-04-01 is a Tuesday; the first bucket starts on the Monday before.
+        let history = build_metrics_history(&[], &weekly);
+        assert_eq!(
+            history.points[0].timestamp,
+            Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap()
+        );
+    }
+}
@@ -1,11 +1,23 @@
 //! Broker ingest domain contracts (connect-owned API surface).
 
 mod core_adapter;
+mod dashboard;
+mod metrics_history;
 mod models;
 
 pub use core_adapter::CoreImportRunRepositoryAdapter;
+pub use dashboard::{
+    build_sync_dashboard, SyncConnectionHealth, SyncDashboard, SyncSuspension,
+    SYNC_DASHBOARD_WINDOW_DAYS,
+};
+pub use metrics_history::{
+    build_metrics_history, MetricsBucket, MetricsHistory, MetricsHistoryError,
+    MetricsHistoryMetric, MetricsHistoryPoint, MetricsHistoryQuery, MAX_METRICS_HISTORY_BUCKETS,
+    MAX_METRICS_HISTORY_DAYS,
+};
 pub use models::{
     BrokerSyncState, BrokerSyncStateRepositoryTrait, ImportRun, ImportRunMode,
-    ImportRunRepositoryTrait, ImportRunStatus, ImportRunSummary, ImportRunType,
-    PlaidInvestmentsCheckpoint, PlaidSyncCheckpoint, ReviewMode, SnapTradeCheckpoint, SyncStatus,
+    ImportRunRepositoryTrait, ImportRunStatus, ImportRunSummary, ImportRunTotals, ImportRunType,
+    ImportRunWindow, PlaidInvestmentsCheckpoint, PlaidSyncCheckpoint, ReviewMode,
+    SnapTradeCheckpoint, SyncStatus,
 };
@@ -224,6 +224,24 @@ impl ImportRun {
     }
 }
 
+/// Totals over every run of one type, computed by the repository without loading the runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportRunTotals {
+    /// Activities inserted or updated, from the run summaries.
+    pub activities_synced: u64,
+    pub last_started_at: Option<DateTime<Utc>>,
+    /// When the most recent applied run finished.
+    pub last_applied_at: Option<DateTime<Utc>>,
+}
+
+/// Runs started inside a time window, newest first, capped at a read limit.
+#[derive(Debug, Clone, Default)]
+pub struct ImportRunWindow {
+    pub runs: Vec<ImportRun>,
+    /// The window held more runs than the limit; the oldest ones were left out.
+    pub truncated: bool,
+}
+
 #[async_trait]
 pub trait ImportRunRepositoryTrait: Send + Sync {
     async fn create(&self, import_run: ImportRun) -> Result<ImportRun>;
@@ -232,6 +250,15 @@ pub trait ImportRunRepositoryTrait: Send + Sync {
     fn get_recent_for_account(&self, account_id: &str, limit: i64) -> Result<Vec<ImportRun>>;
     fn get_all(&self, limit: i64, offset: i64) -> Result<Vec<ImportRun>>;
     fn get_by_run_type(&self, run_type: &str, limit: i64, offset: i64) -> Result<Vec<ImportRun>>;
+    /// Runs of `run_type` started in `[from, to)`, newest first, at most `limit`.
+    fn get_by_run_type_started_between(
+        &self,
+        run_type: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<ImportRunWindow>;
+    fn get_totals_by_run_type(&self, run_type: &str) -> Result<ImportRunTotals>;
 }
 
 #[async_trait]
@@ -4,30 +4,95 @@
 //! Wealthfolio Connect cloud service. Both Tauri and server implementations
 //! should use this client to ensure consistency.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
-use log::{debug, info};
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use log::{debug, info, warn};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
 use serde::de::DeserializeOwned;
-use std::time::Duration;
 
 use crate::broker::{
-    BrokerAccount, BrokerBrokerage, BrokerConnection, BrokerConnectionBrokerage,
-    BrokerHoldingsResponse, PaginatedUniversalActivity, PlansResponse, UserInfo, UserTeam,
+    broker_sync_status, BrokerAccount, BrokerBrokerage, BrokerConnection,
+    BrokerConnectionBrokerage, BrokerHoldingsResponse, PaginatedUniversalActivity, PlansResponse,
+    SubscriptionStatus, UserInfo, UserTeam,
 };
 use crate::request_metadata::{
     header_value, log_failed_cloud_request, request_metadata_suffix, server_request_id,
     CloudRequestContext, CLIENT_REQUEST_ID_HEADER,
 };
-use wealthfolio_core::errors::{Error, Result};
+use wealthfolio_core::errors::{CloudApiError, Error, Result};
+use wealthfolio_core::utils::http_retry::{HttpRetryPolicy, HttpTimeouts};
 
 use super::broker::BrokerApiClient;
 
-/// Default timeout for API requests.
-const DEFAULT_TIMEOUT_SECS: u64 = 30;
-
 /// Default base URL for Wealthfolio Connect cloud service.
 pub const DEFAULT_CLOUD_API_URL: &str = "https://api.wealthfolio.app";
 
+/// Seconds an idle pooled connection is kept open for reuse.
+pub const DEFAULT_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+/// User-agent sent to the cloud API: `wealthfolio/<version> (<os>/<arch>)`. Lets the service
+/// tell app versions apart when tracing requests.
+pub fn connect_user_agent(version: &str) -> String {
+    format!(
+        "wealthfolio/{} ({}/{})",
+        version,
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+/// HTTP settings for [`ConnectApiClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectClientConfig {
+    /// Connect and read timeouts.
+    pub timeouts: HttpTimeouts,
+    /// Upper bound for a whole request, including reading the body. Keeps a long broker sync
+    /// from stalling on a connection that trickles data.
+    pub request_timeout: Duration,
+    /// How long an idle pooled connection is kept open for reuse.
+    pub pool_idle_timeout: Duration,
+}
+
+impl Default for ConnectClientConfig {
+    fn default() -> Self {
+        Self::with_timeouts(HttpTimeouts::default())
+    }
+}
+
+impl ConnectClientConfig {
+    /// Connect and read timeouts from the environment (see [`HttpTimeouts::from_env`]), with
+    /// requests bounded by their sum.
+    pub fn from_env() -> Self {
+        Self::with_timeouts(HttpTimeouts::from_env())
+    }
+
+    fn with_timeouts(timeouts: HttpTimeouts) -> Self {
+        Self {
+            timeouts,
+            request_timeout: timeouts.connect + timeouts.read,
+            pool_idle_timeout: Duration::from_secs(DEFAULT_POOL_IDLE_TIMEOUT_SECS),
+        }
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub fn with_pool_idle_timeout(mut self, pool_idle_timeout: Duration) -> Self {
+        self.pool_idle_timeout = pool_idle_timeout;
+        self
+    }
+
+    fn client_builder(&self) -> reqwest::ClientBuilder {
+        self.timeouts
+            .apply(reqwest::Client::builder())
+            .timeout(self.request_timeout)
+            .pool_idle_timeout(self.pool_idle_timeout)
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // API Response Types (internal, for parsing cloud API responses)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -51,6 +116,7 @@ struct ApiConnection {
     updated_at: Option<String>,
     name: Option<String>,
     status: Option<String>,
+    consent_expires_at: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -141,7 +207,11 @@ struct ApiErrorResponse {
 pub struct ConnectApiClient {
     client: reqwest::Client,
     base_url: String,
+    /// Read replica GETs retry against when the primary answers 5xx or cannot be reached.
+    fallback_base_url: Option<String>,
     auth_header: HeaderValue,
+    user_agent: HeaderValue,
+    retry: HttpRetryPolicy,
 }
 
 impl ConnectApiClient {
@@ -157,26 +227,63 @@ impl ConnectApiClient {
     /// Returns an error if the access token format is invalid or the HTTP client
     /// cannot be initialized.
     pub fn new(base_url: &str, access_token: &str) -> Result<Self> {
+        Self::with_config(base_url, access_token, ConnectClientConfig::from_env())
+    }
+
+    /// Create a client with explicit timeout and connection pool settings.
+    pub fn with_config(
+        base_url: &str,
+        access_token: &str,
+        config: ConnectClientConfig,
+    ) -> Result<Self> {
         let auth_header = HeaderValue::from_str(&format!("Bearer {}", access_token))
             .map_err(|e| Error::Unexpected(format!("Invalid access token format: {}", e)))?;
 
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+        let client = config
+            .client_builder()
             .build()
             .map_err(|e| Error::Unexpected(format!("Failed to initialize HTTP client: {}", e)))?;
 
+        let user_agent = HeaderValue::from_str(&connect_user_agent(env!("CARGO_PKG_VERSION")))
+            .map_err(|e| Error::Unexpected(format!("Invalid user-agent: {}", e)))?;
+
         Ok(Self {
             client,
             base_url: base_url.trim_end_matches('/').to_string(),
+            fallback_base_url: None,
             auth_header,
+            user_agent,
+            retry: HttpRetryPolicy::from_env(),
         })
     }
 
+    /// Replace the user-agent (see [`connect_user_agent`], built from this crate's version by
+    /// default).
+    pub fn with_user_agent(mut self, user_agent: &str) -> Result<Self> {
+        self.user_agent = HeaderValue::from_str(user_agent)
+            .map_err(|e| Error::Unexpected(format!("Invalid user-agent: {}", e)))?;
+        Ok(self)
+    }
+
+    /// Send GETs to `fallback_base_url` when the primary answers 5xx, times out or refuses the
+    /// connection. Only GETs fail over: the fallback is a read replica.
+    pub fn with_fallback_url(mut self, fallback_base_url: Option<&str>) -> Self {
+        self.fallback_base_url = fallback_base_url.map(|url| url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Replace the retry policy for GET requests (read from the environment by default).
+    pub fn with_http_retry(mut self, retry: HttpRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Create default headers for API requests.
     fn headers(&self, client_request_id: &str) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
         headers.insert(AUTHORIZATION, self.auth_header.clone());
+        headers.insert(USER_AGENT, self.user_agent.clone());
         headers.insert(
             CLIENT_REQUEST_ID_HEADER,
             header_value(client_request_id).map_err(Error::Unexpected)?,
@@ -184,22 +291,41 @@ impl ConnectApiClient {
         Ok(headers)
     }
 
-    /// Make a GET request and parse the response.
+    /// Make a GET request and parse the response. Fails over to the fallback URL, if any, once
+    /// the primary's retries are spent.
     async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
         let context = CloudRequestContext::new("GET", path, None);
-        let url = format!("{}{}", self.base_url, path);
-
-        let response = self
-            .client
-            .get(&url)
-            .headers(self.headers(&context.client_request_id)?)
-            .send()
-            .await
-            .map_err(|e| self.request_transport_error(&context, e))?;
+        let headers = self.headers(&context.client_request_id)?;
+
+        let mut outcome = self.send_get(&self.base_url, path, headers.clone()).await;
+        if let Some(fallback_base_url) = &self.fallback_base_url {
+            if should_fail_over(&outcome) {
+                warn!(
+                    "[ConnectApi] GET {} failed on the primary API, retrying against the fallback",
+                    path
+                );
+                outcome = self.send_get(fallback_base_url, path, headers).await;
+            }
+        }
+        let response = outcome.map_err(|e| self.request_transport_error(&context, e))?;
 
         self.parse_response(response, &context).await
     }
 
+    async fn send_get(
+        &self,
+        base_url: &str,
+        path: &str,
+        headers: HeaderMap,
+    ) -> reqwest::Result<reqwest::Response> {
+        let request = self
+            .client
+            .get(format!("{}{}", base_url, path))
+            .headers(headers)
+            .build()?;
+        self.retry.execute(&self.client, request).await
+    }
+
     /// Parse an HTTP response, handling errors appropriately.
     async fn parse_response<T: DeserializeOwned>(
         &self,
@@ -210,43 +336,44 @@ impl ConnectApiClient {
         let request_id = server_request_id(response.headers());
         let body = response.text().await.map_err(|e| {
             log_failed_cloud_request("ConnectApi", context, Some(status), request_id.as_deref());
-            Error::Unexpected(format!(
-                "Failed to read response: {} ({})",
-                e,
-                request_metadata_suffix(context, request_id.as_deref())
-            ))
+            CloudApiError::Transport {
+                message: format!("failed to read response: {}", e),
+                request: request_metadata_suffix(context, request_id.as_deref()),
+            }
         })?;
 
         if !status.is_success() {
             log_failed_cloud_request("ConnectApi", context, Some(status), request_id.as_deref());
 
-            // Try to parse error response for a better message
-            if let Ok(err) = serde_json::from_str::<ApiErrorResponse>(&body) {
-                let msg = err
-                    .message
-                    .or(err.error)
-                    .unwrap_or_else(|| format!("HTTP {}", status));
-                return Err(Error::Unexpected(format!(
-                    "API error {}: {} ({})",
-                    status.as_u16(),
-                    msg,
-                    request_metadata_suffix(context, request_id.as_deref())
-                )));
+            // Try to parse error response for a better message, keeping the machine-readable
+            // code so callers can tell e.g. an expired subscription from other refusals.
+            let (code, message) = match serde_json::from_str::<ApiErrorResponse>(&body) {
+                Ok(err) => (
+                    err.code,
+                    Some(
+                        err.message
+                            .or(err.error)
+                            .unwrap_or_else(|| format!("HTTP {}", status)),
+                    ),
+                ),
+                Err(_) => (None, None),
+            };
+            return Err(CloudApiError::Status {
+                status: status.as_u16(),
+                code,
+                message,
+                request: request_metadata_suffix(context, request_id.as_deref()),
             }
-            return Err(Error::Unexpected(format!(
-                "API error {} ({})",
-                status.as_u16(),
-                request_metadata_suffix(context, request_id.as_deref())
-            )));
+            .into());
         }
 
         serde_json::from_str(&body).map_err(|e| {
             log_failed_cloud_request("ConnectApi", context, Some(status), request_id.as_deref());
-            Error::Unexpected(format!(
-                "Failed to parse response: {} ({})",
-                e,
-                request_metadata_suffix(context, request_id.as_deref())
-            ))
+            CloudApiError::InvalidResponse {
+                message: e.to_string(),
+                request: request_metadata_suffix(context, request_id.as_deref()),
+            }
+            .into()
         })
     }
 
@@ -256,11 +383,13 @@ impl ConnectApiClient {
         error: reqwest::Error,
     ) -> Error {
         log_failed_cloud_request("ConnectApi", context, None, None);
-        Error::Unexpected(format!(
-            "Request failed: {} ({})",
-            error,
-            request_metadata_suffix(context, None)
-        ))
+        let message = error.to_string();
+        let request = request_metadata_suffix(context, None);
+        if error.is_timeout() {
+            CloudApiError::Timeout { message, request }.into()
+        } else {
+            CloudApiError::Transport { message, request }.into()
+        }
     }
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -357,6 +486,7 @@ impl ConnectApiClient {
                 canceled_at: t.canceled_at,
                 country_code: t.country_code,
                 created_at: t.created_at,
+                simulated_subscription: None,
             }),
         })
     }
@@ -372,38 +502,17 @@ impl ConnectApiClient {
     /// is not "basic" (basic plan only includes device sync).
     pub async fn has_broker_sync(&self) -> Result<bool> {
         let user_info = self.get_user_info().await?;
+        Ok(broker_sync_status(&user_info) == SubscriptionStatus::Active)
+    }
 
-        let team = match &user_info.team {
-            Some(t) => t,
-            None => {
-                debug!("[ConnectApi] No team info, broker sync not available");
-                return Ok(false);
-            }
-        };
-
-        let is_active = matches!(
-            team.subscription_status.as_deref(),
-            Some("active") | Some("trialing")
-        );
-        if !is_active {
-            debug!("[ConnectApi] No active subscription, broker sync not available");
-            return Ok(false);
-        }
-
-        let plan = match team.plan.as_deref() {
-            Some(p) => p,
-            None => {
-                debug!("[ConnectApi] No plan metadata, broker sync not available");
-                return Ok(false);
-            }
-        };
-        if plan == "basic" {
-            debug!("[ConnectApi] Basic plan, broker sync not available");
-            return Ok(false);
+    /// Like [`Self::has_broker_sync`], but reports a failed check as
+    /// [`SubscriptionStatus::Unknown`] instead of an error, so callers can tell it apart
+    /// from a missing subscription.
+    pub async fn subscription_status(&self) -> SubscriptionStatus {
+        match self.get_user_info().await {
+            Ok(user_info) => broker_sync_status(&user_info),
+            Err(e) => SubscriptionStatus::Unknown(e.to_string()),
         }
-
-        debug!("[ConnectApi] Broker sync is available");
-        Ok(true)
     }
 }
 
@@ -456,6 +565,9 @@ impl BrokerApiClient for ConnectApiClient {
                     disabled_date: None,
                     updated_at: c.updated_at,
                     name: c.name,
+                    consent_expires_at: c.consent_expires_at,
+                    custom_name: None,
+                    health: None,
                 }
             })
             .collect();
@@ -505,6 +617,14 @@ impl BrokerApiClient for ConnectApiClient {
     }
 }
 
+/// A 5xx, a timeout or a refused connection. Other errors would fail on the replica too.
+fn should_fail_over(outcome: &reqwest::Result<reqwest::Response>) -> bool {
+    match outcome {
+        Ok(response) => response.status().is_server_error(),
+        Err(err) => err.is_timeout() || err.is_connect(),
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Public (Unauthenticated) API Functions
 // ─────────────────────────────────────────────────────────────────────────────
@@ -524,8 +644,8 @@ impl BrokerApiClient for ConnectApiClient {
 /// let plans = fetch_subscription_plans_public("https://api.wealthfolio.app").await?;
 /// ```
 pub async fn fetch_subscription_plans_public(base_url: &str) -> Result<PlansResponse> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+    let client = HttpTimeouts::from_env()
+        .apply(reqwest::Client::builder())
         .build()
         .map_err(|e| Error::Unexpected(format!("Failed to initialize HTTP client: {}", e)))?;
 
@@ -534,20 +654,24 @@ pub async fn fetch_subscription_plans_public(base_url: &str) -> Result<PlansResp
     let url = format!("{}{}", base_url, path);
     let context = CloudRequestContext::new("GET", path, None);
 
-    let response = client
-        .get(&url)
-        .header(CONTENT_TYPE, "application/json")
-        .header(CLIENT_REQUEST_ID_HEADER, context.client_request_id.as_str())
-        .send()
-        .await
-        .map_err(|e| {
-            log_failed_cloud_request("ConnectApi", &context, None, None);
-            Error::Unexpected(format!(
-                "Request failed: {} ({})",
-                e,
-                request_metadata_suffix(&context, None)
-            ))
-        })?;
+    let response = async {
+        let request = client
+            .get(&url)
+            .header(CONTENT_TYPE, "application/json")
+            .header(USER_AGENT, connect_user_agent(env!("CARGO_PKG_VERSION")))
+            .header(CLIENT_REQUEST_ID_HEADER, context.client_request_id.as_str())
+            .build()?;
+        HttpRetryPolicy::from_env().execute(&client, request).await
+    }
+    .await
+    .map_err(|e| {
+        log_failed_cloud_request("ConnectApi", &context, None, None);
+        Error::Unexpected(format!(
+            "Request failed: {} ({})",
+            e,
+            request_metadata_suffix(&context, None)
+        ))
+    })?;
 
     let status = response.status();
     let request_id = server_request_id(response.headers());
@@ -653,6 +777,78 @@ mod tests {
         handle.join().expect("server thread");
     }
 
+    #[tokio::test]
+    async fn request_sends_the_user_agent() {
+        let (base_url, captured, handle) = start_one_request_server(200, r#"{"plans":[]}"#, None);
+        let client = ConnectApiClient::new(&base_url, "test-token").unwrap();
+        assert_eq!(
+            client.headers("app:test").unwrap().get(USER_AGENT),
+            Some(&HeaderValue::from_str(&connect_user_agent(env!("CARGO_PKG_VERSION"))).unwrap())
+        );
+        let client = client
+            .with_user_agent("wealthfolio/9.9.9 (test/arch)")
+            .unwrap();
+
+        client.get_subscription_plans().await.unwrap();
+
+        let headers = captured.lock().unwrap().clone().expect("captured request");
+        assert_eq!(
+            headers.get("user-agent").map(String::as_str),
+            Some("wealthfolio/9.9.9 (test/arch)")
+        );
+        handle.join().expect("server thread");
+    }
+
+    #[tokio::test]
+    async fn get_fails_over_to_the_fallback_when_the_primary_is_unavailable() {
+        let (primary_url, primary_captured, primary) =
+            start_one_request_server(503, r#"{"error":"unavailable"}"#, None);
+        let (fallback_url, fallback_captured, fallback) =
+            start_one_request_server(200, r#"{"plans":[]}"#, None);
+        let client = ConnectApiClient::new(&primary_url, "test-token")
+            .unwrap()
+            .with_http_retry(HttpRetryPolicy::disabled())
+            .with_fallback_url(Some(&fallback_url));
+
+        let response = client.get_subscription_plans().await.unwrap();
+
+        assert!(response.plans.is_empty());
+        let primary_headers = primary_captured.lock().unwrap().clone().expect("primary");
+        let fallback_headers = fallback_captured.lock().unwrap().clone().expect("fallback");
+        // Both attempts are one logical request.
+        assert_eq!(
+            primary_headers.get(CLIENT_REQUEST_ID_HEADER),
+            fallback_headers.get(CLIENT_REQUEST_ID_HEADER)
+        );
+        assert_eq!(
+            fallback_headers.get("authorization").map(String::as_str),
+            Some("Bearer test-token")
+        );
+        primary.join().expect("primary thread");
+        fallback.join().expect("fallback thread");
+    }
+
+    #[tokio::test]
+    async fn get_does_not_fail_over_on_client_errors() {
+        let (primary_url, _, primary) = start_one_request_server(
+            404,
+            r#"{"error":"not_found","message":"no such plan"}"#,
+            None,
+        );
+        // Bound but never answered: failing over would end in a timeout instead of the 404.
+        let fallback_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let fallback_url = format!("http://{}", fallback_listener.local_addr().unwrap());
+        let client = ConnectApiClient::new(&primary_url, "test-token")
+            .unwrap()
+            .with_http_retry(HttpRetryPolicy::disabled())
+            .with_fallback_url(Some(&fallback_url));
+
+        let error = client.get_subscription_plans().await.unwrap_err();
+
+        assert!(error.to_string().contains("API error 404"));
+        primary.join().expect("primary thread");
+    }
+
     #[tokio::test]
     async fn failed_request_error_includes_client_and_server_request_ids() {
         let (base_url, captured, handle) = start_one_request_server(
@@ -660,7 +856,10 @@ mod tests {
             r#"{"error":"server_error","message":"temporary failure"}"#,
             Some("server-req-123"),
         );
-        let client = ConnectApiClient::new(&base_url, "test-token").unwrap();
+        // The test server answers a single request; a retry would hit a closed socket.
+        let client = ConnectApiClient::new(&base_url, "test-token")
+            .unwrap()
+            .with_http_retry(HttpRetryPolicy::disabled());
 
         let error = client
             .get_subscription_plans()
@@ -678,6 +877,66 @@ mod tests {
         handle.join().expect("server thread");
     }
 
+    #[tokio::test]
+    async fn failed_request_error_keeps_the_error_code() {
+        let (base_url, _, handle) = start_one_request_server(
+            403,
+            r#"{"code":"SYNC_SUBSCRIPTION_REQUIRED","message":"Subscription expired"}"#,
+            None,
+        );
+        let client = ConnectApiClient::new(&base_url, "test-token")
+            .unwrap()
+            .with_http_retry(HttpRetryPolicy::disabled());
+
+        let error = client
+            .get_subscription_plans()
+            .await
+            .expect_err("request should fail")
+            .to_string();
+
+        assert!(
+            error.starts_with("API error 403: SYNC_SUBSCRIPTION_REQUIRED: Subscription expired"),
+            "{}",
+            error
+        );
+        handle.join().expect("server thread");
+    }
+
+    #[tokio::test]
+    async fn hung_request_fails_with_a_transient_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        // Accepts the request and never answers.
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("accept request");
+            thread::sleep(std::time::Duration::from_millis(500));
+            drop(stream);
+        });
+        let config = ConnectClientConfig::default()
+            .with_request_timeout(std::time::Duration::from_millis(100));
+        let client =
+            ConnectApiClient::with_config(&format!("http://{}", addr), "test-token", config)
+                .unwrap()
+                .with_http_retry(HttpRetryPolicy::disabled());
+
+        let error = client
+            .get_subscription_plans()
+            .await
+            .expect_err("request should time out");
+
+        let Error::CloudApi(error) = error else {
+            panic!("expected a cloud API error, got {}", error);
+        };
+        assert!(
+            matches!(error, CloudApiError::Timeout { .. }),
+            "{:?}",
+            error
+        );
+        assert!(!error.is_permanent());
+        assert!(!crate::BrokerSyncError::Cloud(error).is_permanent());
+        handle.join().expect("server thread");
+    }
+
     fn start_one_request_server(
         status: u16,
         body: &'static str,
@@ -0,0 +1,389 @@
+//! OAuth device authorization grant (RFC 8628) for signing in to Connect without a browser on
+//! the same machine, e.g. a headless server.
+//!
+//! [`start_device_login`] asks the auth service for a device code and returns the verification
+//! URL and short user code to enter on any device with a browser. [`poll_device_login`] then
+//! asks for the token no more often than the `interval` the auth service requested, and gives
+//! up once the code's `expires_in` has passed. On approval the refresh token is stored like any
+//! other cloud session. The device code itself stays on this side.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use wealthfolio_core::secrets::SecretStore;
+
+use crate::request_metadata::{
+    log_failed_cloud_request, request_metadata_suffix, server_request_id, CloudRequestContext,
+    CLIENT_REQUEST_ID_HEADER,
+};
+use crate::token_lifecycle::{
+    store_cloud_session, TokenLifecycleConfig, TokenLifecycleError, TokenLifecycleState,
+};
+
+const DEVICE_CODE_PATH: &str = "/auth/v1/device/code";
+const DEVICE_TOKEN_PATH: &str =
+    "/auth/v1/token?grant_type=urn:ietf:params:oauth:grant-type:device_code";
+
+/// Poll interval when the device code response does not name one (RFC 8628 §3.2).
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 5;
+/// Added to the poll interval each time the auth service answers `slow_down` (RFC 8628 §3.5).
+const SLOW_DOWN_STEP_SECS: u64 = 5;
+
+/// What the user needs to approve a device login.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLoginStart {
+    pub verification_uri: String,
+    /// The verification URL with the user code filled in, when the auth service offers one.
+    pub verification_uri_complete: Option<String>,
+    pub user_code: String,
+    pub expires_in_secs: u64,
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceLoginStatus {
+    /// The user has not approved yet; poll again after `retry_after_secs`.
+    Pending,
+    /// Approved; the cloud session is stored.
+    Complete,
+    /// The code expired before it was approved.
+    Expired,
+    /// The user declined.
+    Denied,
+    /// No device login is in progress.
+    NotStarted,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLoginPoll {
+    pub status: DeviceLoginStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+}
+
+impl DeviceLoginPoll {
+    fn pending(retry_after: Duration) -> Self {
+        Self {
+            status: DeviceLoginStatus::Pending,
+            retry_after_secs: Some(retry_after.as_secs().max(1)),
+        }
+    }
+
+    fn done(status: DeviceLoginStatus) -> Self {
+        Self {
+            status,
+            retry_after_secs: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingDeviceLogin {
+    device_code: String,
+    interval: Duration,
+    expires_at: Instant,
+    next_poll_at: Instant,
+}
+
+/// The device login in progress, if any. Starting a new one replaces it.
+#[derive(Debug, Default)]
+pub struct DeviceLoginState {
+    pending: Mutex<Option<PendingDeviceLogin>>,
+}
+
+impl DeviceLoginState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    interval: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceTokenResponse {
+    refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceTokenErrorResponse {
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// How the token endpoint answered a poll that did not return a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PollError {
+    AuthorizationPending,
+    SlowDown,
+    Expired,
+    Denied,
+    Other,
+}
+
+fn classify_poll_error(error_code: &str) -> PollError {
+    match error_code {
+        "authorization_pending" => PollError::AuthorizationPending,
+        "slow_down" => PollError::SlowDown,
+        "expired_token" => PollError::Expired,
+        "access_denied" => PollError::Denied,
+        _ => PollError::Other,
+    }
+}
+
+/// Requests a device code and remembers it for [`poll_device_login`].
+pub async fn start_device_login(
+    state: &DeviceLoginState,
+    config: Option<&TokenLifecycleConfig>,
+) -> Result<DeviceLoginStart, TokenLifecycleError> {
+    let config = ensure_configured(config)?;
+    let context = CloudRequestContext::new("POST", DEVICE_CODE_PATH, None);
+    let (status, request_id, body) = post_auth(config, &context, serde_json::json!({})).await?;
+    if !status.is_success() {
+        log_failed_cloud_request("ConnectAuth", &context, Some(status), request_id.as_deref());
+        return Err(TokenLifecycleError::Internal(format!(
+            "Failed to start device login: HTTP {} ({})",
+            status.as_u16(),
+            request_metadata_suffix(&context, request_id.as_deref())
+        )));
+    }
+    let response = serde_json::from_str::<DeviceCodeResponse>(&body).map_err(|e| {
+        TokenLifecycleError::Internal(format!(
+            "Failed to parse device code response: {} ({})",
+            e,
+            request_metadata_suffix(&context, request_id.as_deref())
+        ))
+    })?;
+
+    let interval = Duration::from_secs(
+        response
+            .interval
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS),
+    );
+    let now = Instant::now();
+    *state.pending.lock().await = Some(PendingDeviceLogin {
+        device_code: response.device_code,
+        interval,
+        expires_at: now + Duration::from_secs(response.expires_in),
+        next_poll_at: now + interval,
+    });
+
+    Ok(DeviceLoginStart {
+        verification_uri: response.verification_uri,
+        verification_uri_complete: response.verification_uri_complete,
+        user_code: response.user_code,
+        expires_in_secs: response.expires_in,
+        interval_secs: interval.as_secs(),
+    })
+}
+
+/// Checks once whether the user approved the device login. Calls made before the poll interval
+/// has passed return `Pending` without asking the auth service. On approval the refresh token
+/// is stored and the cached access token dropped.
+pub async fn poll_device_login(
+    state: &DeviceLoginState,
+    secret_store: &dyn SecretStore,
+    tokens: &TokenLifecycleState,
+    config: Option<&TokenLifecycleConfig>,
+) -> Result<DeviceLoginPoll, TokenLifecycleError> {
+    // Held across the request so concurrent polls cannot outpace the interval.
+    let mut guard = state.pending.lock().await;
+    let Some(pending) = guard.as_mut() else {
+        return Ok(DeviceLoginPoll::done(DeviceLoginStatus::NotStarted));
+    };
+    let now = Instant::now();
+    if now >= pending.expires_at {
+        *guard = None;
+        return Ok(DeviceLoginPoll::done(DeviceLoginStatus::Expired));
+    }
+    if now < pending.next_poll_at {
+        return Ok(DeviceLoginPoll::pending(pending.next_poll_at - now));
+    }
+    let config = ensure_configured(config)?;
+
+    let context = CloudRequestContext::new("POST", DEVICE_TOKEN_PATH, None);
+    let result = post_auth(
+        config,
+        &context,
+        serde_json::json!({ "device_code": pending.device_code }),
+    )
+    .await;
+    pending.next_poll_at = Instant::now() + pending.interval;
+    let (status, request_id, body) = result?;
+
+    if status.is_success() {
+        let response = serde_json::from_str::<DeviceTokenResponse>(&body).map_err(|e| {
+            TokenLifecycleError::Internal(format!(
+                "Failed to parse device login token response: {} ({})",
+                e,
+                request_metadata_suffix(&context, request_id.as_deref())
+            ))
+        })?;
+        store_cloud_session(secret_store, &response.refresh_token)?;
+        tokens.clear_cache().await;
+        *guard = None;
+        return Ok(DeviceLoginPoll::done(DeviceLoginStatus::Complete));
+    }
+
+    let parsed = serde_json::from_str::<DeviceTokenErrorResponse>(&body).ok();
+    let error_code = parsed
+        .as_ref()
+        .and_then(|value| value.error.as_deref())
+        .unwrap_or_default();
+    match classify_poll_error(error_code) {
+        PollError::AuthorizationPending => Ok(DeviceLoginPoll::pending(pending.interval)),
+        PollError::SlowDown => {
+            pending.interval += Duration::from_secs(SLOW_DOWN_STEP_SECS);
+            pending.next_poll_at = Instant::now() + pending.interval;
+            Ok(DeviceLoginPoll::pending(pending.interval))
+        }
+        PollError::Expired => {
+            *guard = None;
+            Ok(DeviceLoginPoll::done(DeviceLoginStatus::Expired))
+        }
+        PollError::Denied => {
+            *guard = None;
+            Ok(DeviceLoginPoll::done(DeviceLoginStatus::Denied))
+        }
+        PollError::Other => {
+            log_failed_cloud_request("ConnectAuth", &context, Some(status), request_id.as_deref());
+            let message = parsed
+                .and_then(|value| value.error_description.or(value.error))
+                .unwrap_or_else(|| format!("HTTP {}", status.as_u16()));
+            Err(TokenLifecycleError::RefreshFailed(format!(
+                "Device login failed: {} ({})",
+                message,
+                request_metadata_suffix(&context, request_id.as_deref())
+            )))
+        }
+    }
+}
+
+fn ensure_configured(
+    config: Option<&TokenLifecycleConfig>,
+) -> Result<&TokenLifecycleConfig, TokenLifecycleError> {
+    match config {
+        Some(config) if config.is_configured() => Ok(config),
+        Some(_) => Err(TokenLifecycleError::NotConfigured(
+            "CONNECT_AUTH_URL or CONNECT_AUTH_PUBLISHABLE_KEY is not configured".to_string(),
+        )),
+        None => Err(TokenLifecycleError::NotConfigured(
+            "Auth refresh configuration is missing".to_string(),
+        )),
+    }
+}
+
+async fn post_auth(
+    config: &TokenLifecycleConfig,
+    context: &CloudRequestContext,
+    body: serde_json::Value,
+) -> Result<(reqwest::StatusCode, Option<String>, String), TokenLifecycleError> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.refresh_timeout_secs))
+        .build()
+        .map_err(|e| {
+            TokenLifecycleError::Internal(format!("Failed to create HTTP client: {}", e))
+        })?;
+    let response = client
+        .post(format!("{}{}", config.auth_url, context.path))
+        .header("apikey", &config.publishable_key)
+        .header("Content-Type", "application/json")
+        .header(CLIENT_REQUEST_ID_HEADER, context.client_request_id.as_str())
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| {
+            log_failed_cloud_request("ConnectAuth", context, None, None);
+            TokenLifecycleError::RefreshFailed(format!(
+                "Device login request failed: {} ({})",
+                e,
+                request_metadata_suffix(context, None)
+            ))
+        })?;
+    let status = response.status();
+    let request_id = server_request_id(response.headers());
+    let body = response.text().await.map_err(|e| {
+        log_failed_cloud_request("ConnectAuth", context, Some(status), request_id.as_deref());
+        TokenLifecycleError::RefreshFailed(format!(
+            "Failed to read response: {} ({})",
+            e,
+            request_metadata_suffix(context, request_id.as_deref())
+        ))
+    })?;
+    Ok((status, request_id, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wealthfolio_core::secrets::MemorySecretStore;
+
+    fn pending_state(expires_in: Duration, next_poll_in: Duration) -> DeviceLoginState {
+        let now = Instant::now();
+        DeviceLoginState {
+            pending: Mutex::new(Some(PendingDeviceLogin {
+                device_code: "device-code".to_string(),
+                interval: Duration::from_secs(5),
+                expires_at: now + expires_in,
+                next_poll_at: now + next_poll_in,
+            })),
+        }
+    }
+
+    #[tokio::test]
+    async fn polling_before_the_interval_stays_pending_without_a_request() {
+        let state = pending_state(Duration::from_secs(600), Duration::from_secs(4));
+
+        // No auth config: the poll must be answered without a request.
+        let secrets = MemorySecretStore::default();
+        let poll = poll_device_login(&state, &secrets, &TokenLifecycleState::new(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(poll.status, DeviceLoginStatus::Pending);
+        assert!((1..=4).contains(&poll.retry_after_secs.unwrap()));
+        assert!(secrets.keys().is_empty());
+    }
+
+    #[tokio::test]
+    async fn an_expired_code_ends_the_login() {
+        let state = pending_state(Duration::ZERO, Duration::ZERO);
+        let tokens = TokenLifecycleState::new();
+        let secrets = MemorySecretStore::default();
+
+        let poll = poll_device_login(&state, &secrets, &tokens, None)
+            .await
+            .unwrap();
+        assert_eq!(poll, DeviceLoginPoll::done(DeviceLoginStatus::Expired));
+
+        let poll = poll_device_login(&state, &secrets, &tokens, None)
+            .await
+            .unwrap();
+        assert_eq!(poll.status, DeviceLoginStatus::NotStarted);
+        assert!(secrets.keys().is_empty());
+    }
+
+    #[test]
+    fn token_errors_are_classified_per_rfc_8628() {
+        assert_eq!(
+            classify_poll_error("authorization_pending"),
+            PollError::AuthorizationPending
+        );
+        assert_eq!(classify_poll_error("slow_down"), PollError::SlowDown);
+        assert_eq!(classify_poll_error("expired_token"), PollError::Expired);
+        assert_eq!(classify_poll_error("access_denied"), PollError::Denied);
+        assert_eq!(classify_poll_error("invalid_grant"), PollError::Other);
+    }
+}
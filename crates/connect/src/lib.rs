@@ -3,34 +3,64 @@
 //! This crate provides integration with Wealthfolio Connect cloud services
 //! for syncing broker accounts and activities.
 
+pub mod api_url;
 #[cfg(feature = "broker")]
 pub mod broker;
 pub mod broker_ingest;
 pub mod client;
+pub mod device_login;
+pub mod diagnostics;
 pub mod platform;
 pub mod post_login_bootstrap;
 mod request_metadata;
+pub mod sign_out;
 pub mod token_lifecycle;
 
 // Re-export commonly used types
 #[cfg(feature = "broker")]
+pub use broker::{resolve_broker_sync_subscription, subscription_grace_period_from_env};
+#[cfg(feature = "broker")]
 pub use broker::{
-    AccountUniversalActivity, BrokerAccount, BrokerApiClient, BrokerBrokerage, BrokerConnection,
-    BrokerSyncService, BrokerSyncServiceTrait, NoOpProgressReporter, PaginatedUniversalActivity,
-    PlanLimitValue, PlanLimits, PlanPricing, PlansResponse, PlatformRepositoryTrait,
-    SubscriptionPlan, SyncAccountsResponse, SyncActivitiesResponse, SyncConfig,
-    SyncConnectionsResponse, SyncOrchestrator, SyncProgressPayload, SyncProgressReporter,
-    SyncResult, SyncStatus, UserInfo, UserTeam,
+    AccountUniversalActivity, ActivityResumeService, AnomalyThresholds, BrokerAccount,
+    BrokerApiClient, BrokerBrokerage, BrokerConnection, BrokerSyncError, BrokerSyncService,
+    BrokerSyncServiceTrait, BrokerSyncSummary, BrokerSyncTrigger, ConnectionExpiry,
+    ConnectionHealth, ConnectionHealthService, ConnectionNameService, ConnectionSummary,
+    FakeSubscriptionState, HistoryBackfillJob, HistoryBackfillService, HistoryBackfillStatus,
+    NoOpProgressReporter, PaginatedUniversalActivity, PlanLimitValue, PlanLimits, PlanPricing,
+    PlansResponse, PlatformRepositoryTrait, SkipReason, SubscriptionDecision, SubscriptionOverride,
+    SubscriptionOverrideError, SubscriptionPlan, SubscriptionStatus, SubscriptionStatusService,
+    SyncAccountsResponse, SyncActivitiesResponse, SyncAnomaly, SyncConfig, SyncConnectionsResponse,
+    SyncOrchestrator, SyncProgressPayload, SyncProgressReporter, SyncResult, SyncStatus,
+    SyncSuspensionService, UserInfo, UserTeam, DEFAULT_PERMANENT_FAILURE_THRESHOLD,
+    DEFAULT_SUBSCRIPTION_GRACE_HOURS,
 };
 
 // Re-export the HTTP client and public functions
-pub use client::{fetch_subscription_plans_public, ConnectApiClient, DEFAULT_CLOUD_API_URL};
+pub use api_url::{
+    normalize_cloud_api_url, parse_require_https, require_https_from_env, resolve_cloud_api_url,
+    resolve_cloud_api_urls, validate_cloud_api_url, CloudApiUrlError, CloudApiUrls,
+    REQUIRE_HTTPS_ENV,
+};
+pub use client::{
+    connect_user_agent, fetch_subscription_plans_public, ConnectApiClient, ConnectClientConfig,
+    DEFAULT_CLOUD_API_URL,
+};
+pub use device_login::{
+    poll_device_login, start_device_login, DeviceLoginPoll, DeviceLoginStart, DeviceLoginState,
+    DeviceLoginStatus,
+};
+pub use diagnostics::{
+    redact_secrets, DeviceSyncDiagnostics, DiagnosticsSources, FeatureDiagnostics, SyncDiagnostics,
+    SyncRunDiagnostics, DIAGNOSTICS_SCHEMA_VERSION,
+};
 pub use post_login_bootstrap::{
     acquire_broker_sync_guard, BrokerSyncRunGuard, PostLoginBootstrapReason,
     PostLoginBootstrapResult, PostLoginBootstrapStatus, PostLoginBootstrapSyncResult,
 };
+pub use sign_out::{sign_out, SignOutResult, CLOUD_SECRET_KEYS};
 pub use token_lifecycle::{
-    ensure_valid_access_token, TokenLifecycleConfig, TokenLifecycleError, TokenLifecycleState,
+    access_token_expiry, ensure_valid_access_token, force_refresh_access_token,
+    store_cloud_session, TokenLifecycleConfig, TokenLifecycleError, TokenLifecycleState,
     CLOUD_ACCESS_TOKEN_KEY, CLOUD_REFRESH_TOKEN_KEY,
 };
 
@@ -41,8 +71,10 @@ pub use post_login_bootstrap::{
 };
 
 pub use broker_ingest::{
-    BrokerSyncState, BrokerSyncStateRepositoryTrait, CoreImportRunRepositoryAdapter, ImportRun,
-    ImportRunMode, ImportRunRepositoryTrait, ImportRunStatus, ImportRunSummary, ImportRunType,
-    ReviewMode,
+    build_metrics_history, build_sync_dashboard, BrokerSyncState, BrokerSyncStateRepositoryTrait,
+    CoreImportRunRepositoryAdapter, ImportRun, ImportRunMode, ImportRunRepositoryTrait,
+    ImportRunStatus, ImportRunSummary, ImportRunTotals, ImportRunType, ImportRunWindow,
+    MetricsBucket, MetricsHistory, MetricsHistoryError, MetricsHistoryMetric, MetricsHistoryPoint,
+    MetricsHistoryQuery, ReviewMode, SyncConnectionHealth, SyncDashboard, SyncSuspension,
 };
 pub use platform::Platform;
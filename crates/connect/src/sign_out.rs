@@ -0,0 +1,229 @@
+//! Signing out of Connect on every device.
+//!
+//! [`sign_out`] asks the auth service to revoke the session everywhere, then deletes every cloud
+//! secret this app stores: the session tokens and the device sync enrollment. The revoke is best
+//! effort; when it fails (no network, session already expired) the local secrets are deleted all
+//! the same and the failure is only logged, so signing out never leaves credentials behind.
+
+use std::time::Duration;
+
+use log::warn;
+use serde::Serialize;
+use wealthfolio_core::secrets::SecretStore;
+
+use crate::request_metadata::{
+    log_failed_cloud_request, request_metadata_suffix, server_request_id, CloudRequestContext,
+    CLIENT_REQUEST_ID_HEADER,
+};
+use crate::token_lifecycle::{
+    ensure_valid_access_token, TokenLifecycleConfig, TokenLifecycleError, TokenLifecycleState,
+    CLOUD_ACCESS_TOKEN_KEY, CLOUD_REFRESH_TOKEN_KEY,
+};
+
+/// Revokes every session of the user, not just this one.
+const LOGOUT_PATH: &str = "/auth/v1/logout?scope=global";
+
+/// Device sync enrollment: identity and root key, and the registered device ID.
+pub(crate) const SYNC_IDENTITY_KEY: &str = "sync_identity";
+pub(crate) const SYNC_DEVICE_ID_KEY: &str = "sync_device_id";
+
+/// Every cloud secret, as named in the [`SecretStore`] (which adds the `wealthfolio_` prefix).
+/// Other secrets, such as AI provider keys, are not touched by signing out.
+pub const CLOUD_SECRET_KEYS: [&str; 4] = [
+    CLOUD_REFRESH_TOKEN_KEY,
+    CLOUD_ACCESS_TOKEN_KEY,
+    SYNC_IDENTITY_KEY,
+    SYNC_DEVICE_ID_KEY,
+];
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignOutResult {
+    /// The auth service revoked the session. `false` when it could not be reached or there was
+    /// no session; the local secrets are gone either way.
+    pub remote_revoked: bool,
+}
+
+/// Revokes the cloud session, then deletes all cloud secrets and the cached access token.
+/// Fails only when a secret could not be deleted.
+pub async fn sign_out(
+    secret_store: &dyn SecretStore,
+    tokens: &TokenLifecycleState,
+    config: Option<&TokenLifecycleConfig>,
+) -> Result<SignOutResult, TokenLifecycleError> {
+    let remote_revoked = match revoke_session(secret_store, tokens, config).await {
+        Ok(revoked) => revoked,
+        Err(err) => {
+            warn!(
+                "[Connect] Could not revoke the cloud session, signing out locally only: {}",
+                err
+            );
+            false
+        }
+    };
+
+    tokens.clear_cache().await;
+    let failed: Vec<String> = CLOUD_SECRET_KEYS
+        .iter()
+        .filter_map(|key| {
+            secret_store
+                .delete_secret(key)
+                .err()
+                .map(|e| format!("{}: {}", key, e))
+        })
+        .collect();
+    if !failed.is_empty() {
+        return Err(TokenLifecycleError::Internal(format!(
+            "Failed to delete cloud secrets: {}",
+            failed.join(", ")
+        )));
+    }
+    Ok(SignOutResult { remote_revoked })
+}
+
+/// `Ok(false)` when there is no session to revoke.
+async fn revoke_session(
+    secret_store: &dyn SecretStore,
+    tokens: &TokenLifecycleState,
+    config: Option<&TokenLifecycleConfig>,
+) -> Result<bool, TokenLifecycleError> {
+    let signed_in = secret_store
+        .get_secret(CLOUD_REFRESH_TOKEN_KEY)
+        .map_err(|e| TokenLifecycleError::Internal(format!("Failed to read refresh token: {}", e)))?
+        .is_some();
+    if !signed_in {
+        return Ok(false);
+    }
+    let access_token = ensure_valid_access_token(secret_store, tokens, config).await?;
+    // `ensure_valid_access_token` only succeeds with a configuration.
+    let Some(config) = config else {
+        return Ok(false);
+    };
+
+    let context = CloudRequestContext::new("POST", LOGOUT_PATH, None);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.refresh_timeout_secs))
+        .build()
+        .map_err(|e| {
+            TokenLifecycleError::Internal(format!("Failed to create HTTP client: {}", e))
+        })?;
+    let response = client
+        .post(format!("{}{}", config.auth_url, LOGOUT_PATH))
+        .header("apikey", &config.publishable_key)
+        .bearer_auth(&access_token)
+        .header(CLIENT_REQUEST_ID_HEADER, context.client_request_id.as_str())
+        .send()
+        .await
+        .map_err(|e| {
+            log_failed_cloud_request("ConnectAuth", &context, None, None);
+            TokenLifecycleError::RefreshFailed(format!(
+                "Sign-out request failed: {} ({})",
+                e,
+                request_metadata_suffix(&context, None)
+            ))
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let request_id = server_request_id(response.headers());
+        log_failed_cloud_request("ConnectAuth", &context, Some(status), request_id.as_deref());
+        return Err(TokenLifecycleError::RefreshFailed(format!(
+            "Sign-out was rejected with HTTP {} ({})",
+            status.as_u16(),
+            request_metadata_suffix(&context, request_id.as_deref())
+        )));
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use wealthfolio_core::secrets::MemorySecretStore;
+
+    fn signed_in_store() -> MemorySecretStore {
+        let store = MemorySecretStore::with(&[("openai", "sk-test")]);
+        for key in CLOUD_SECRET_KEYS {
+            store.set_secret(key, "secret").unwrap();
+        }
+        store
+    }
+
+    /// Serves one token refresh, then one logout, and records the request lines.
+    fn start_auth_server(logout_status: u16) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            let responses = [
+                (200, r#"{"access_token":"access","expires_in":3600}"#),
+                (logout_status, ""),
+            ];
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0_u8; 4096];
+                let read = stream.read(&mut buffer).unwrap();
+                let request = String::from_utf8_lossy(&buffer[..read]);
+                seen.lock()
+                    .unwrap()
+                    .push(request.lines().next().unwrap_or_default().to_string());
+                let response = format!(
+                    "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn sign_out_revokes_the_session_and_removes_all_cloud_secrets() {
+        let (auth_url, requests) = start_auth_server(204);
+        let config = TokenLifecycleConfig::new(auth_url, "publishable".to_string());
+        let store = signed_in_store();
+
+        let result = sign_out(&store, &TokenLifecycleState::new(), Some(&config))
+            .await
+            .unwrap();
+
+        assert!(result.remote_revoked);
+        assert_eq!(
+            requests.lock().unwrap().last().map(String::as_str),
+            Some("POST /auth/v1/logout?scope=global HTTP/1.1")
+        );
+        assert_eq!(store.keys(), vec!["openai".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn sign_out_removes_all_cloud_secrets_when_the_revoke_fails() {
+        let (auth_url, _) = start_auth_server(500);
+        let config = TokenLifecycleConfig::new(auth_url, "publishable".to_string());
+        let store = signed_in_store();
+
+        let result = sign_out(&store, &TokenLifecycleState::new(), Some(&config))
+            .await
+            .unwrap();
+
+        assert!(!result.remote_revoked);
+        assert_eq!(store.keys(), vec!["openai".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn sign_out_without_auth_configuration_still_signs_out_locally() {
+        let store = signed_in_store();
+
+        let result = sign_out(&store, &TokenLifecycleState::new(), None)
+            .await
+            .unwrap();
+
+        assert!(!result.remote_revoked);
+        assert_eq!(store.keys(), vec!["openai".to_string()]);
+    }
+}
@@ -81,6 +81,7 @@ pub enum TokenLifecycleError {
 struct CachedAccessToken {
     token: String,
     expires_at: Instant,
+    refreshed_at: Instant,
 }
 
 #[derive(Debug, Deserialize)]
@@ -102,6 +103,27 @@ struct JwtClaims {
     exp: Option<i64>,
 }
 
+/// Stores a refreshed cloud session after reauth.
+///
+/// Only the cloud session secrets change. Device sync identity, cursor, enrollment and local
+/// data are deliberately left alone so an expired cloud token never forces a re-pair or
+/// bootstrap. Callers also drop their cached access token.
+pub fn store_cloud_session(
+    secret_store: &dyn SecretStore,
+    refresh_token: &str,
+) -> Result<(), TokenLifecycleError> {
+    secret_store
+        .set_secret(CLOUD_REFRESH_TOKEN_KEY, refresh_token)
+        .map_err(|e| {
+            TokenLifecycleError::Internal(format!("Failed to store refresh token: {}", e))
+        })?;
+    // Best-effort cleanup for legacy versions that stored access tokens at rest.
+    let _ = secret_store.delete_secret(CLOUD_ACCESS_TOKEN_KEY);
+    Ok(())
+}
+
+/// Returns the cached access token, or refreshes it. Callers that find the cache empty at the
+/// same time share one refresh.
 pub async fn ensure_valid_access_token(
     secret_store: &dyn SecretStore,
     state: &TokenLifecycleState,
@@ -117,6 +139,34 @@ pub async fn ensure_valid_access_token(
         return Ok(token);
     }
 
+    refresh_locked(secret_store, state, config).await
+}
+
+/// Refreshes the access token even when the cached one still looks valid, e.g. so it does not
+/// lapse halfway through a long operation. Concurrent callers share one refresh: whoever was
+/// waiting for the lock while another caller refreshed gets that token instead of refreshing
+/// (and rotating the refresh token) again.
+pub async fn force_refresh_access_token(
+    secret_store: &dyn SecretStore,
+    state: &TokenLifecycleState,
+    config: Option<&TokenLifecycleConfig>,
+) -> Result<String, TokenLifecycleError> {
+    let requested_at = Instant::now();
+    let _refresh_guard = state.refresh_lock.lock().await;
+
+    if let Some(token) = read_token_refreshed_since(state, requested_at).await {
+        return Ok(token);
+    }
+
+    refresh_locked(secret_store, state, config).await
+}
+
+/// Exchanges the stored refresh token for a new access token. Callers hold `refresh_lock`.
+async fn refresh_locked(
+    secret_store: &dyn SecretStore,
+    state: &TokenLifecycleState,
+    config: Option<&TokenLifecycleConfig>,
+) -> Result<String, TokenLifecycleError> {
     let Some(config) = config else {
         return Err(TokenLifecycleError::NotConfigured(
             "Auth refresh configuration is missing".to_string(),
@@ -190,6 +240,13 @@ pub fn is_access_token_fresh(token: &str, now: SystemTime, expiry_buffer_secs: u
     exp > now_secs as i64 + expiry_buffer_secs as i64
 }
 
+/// When a JWT access token expires, from its `exp` claim. The signature is not checked; this
+/// only tells callers when to refresh. Opaque tokens give `None`.
+pub fn access_token_expiry(token: &str) -> Option<SystemTime> {
+    let exp = u64::try_from(parse_jwt_exp(token)?).ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(exp))
+}
+
 async fn read_cached_token(state: &TokenLifecycleState) -> Option<String> {
     let cache = state.cache.read().await;
     cache
@@ -204,9 +261,28 @@ async fn read_cached_token(state: &TokenLifecycleState) -> Option<String> {
         .map(|value| value.token.clone())
 }
 
+/// The cached token, if it is still fresh and was refreshed at or after `since`.
+async fn read_token_refreshed_since(state: &TokenLifecycleState, since: Instant) -> Option<String> {
+    let refreshed_since = state
+        .cache
+        .read()
+        .await
+        .as_ref()
+        .is_some_and(|value| value.refreshed_at >= since);
+    if refreshed_since {
+        read_cached_token(state).await
+    } else {
+        None
+    }
+}
+
 async fn write_cache(state: &TokenLifecycleState, token: String, expires_at: Instant) {
     let mut cache = state.cache.write().await;
-    *cache = Some(CachedAccessToken { token, expires_at });
+    *cache = Some(CachedAccessToken {
+        token,
+        expires_at,
+        refreshed_at: Instant::now(),
+    });
 }
 
 fn compute_expires_at(token: &str, expires_in: Option<i64>, buffer_secs: u64) -> Instant {
@@ -374,6 +450,8 @@ impl RefreshRequestError {
 mod tests {
     use super::*;
     use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use std::sync::Arc;
+    use wealthfolio_core::secrets::MemorySecretStore;
 
     fn fake_jwt_with_exp(exp: i64) -> String {
         let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
@@ -401,6 +479,16 @@ mod tests {
         assert!(!is_access_token_fresh(&token, SystemTime::now(), 60));
     }
 
+    #[test]
+    fn expiry_is_read_from_jwt_tokens_only() {
+        assert_eq!(
+            access_token_expiry(&fake_jwt_with_exp(1_900_000_000)),
+            Some(UNIX_EPOCH + Duration::from_secs(1_900_000_000))
+        );
+        assert_eq!(access_token_expiry("sk_opaque_access_token"), None);
+        assert_eq!(access_token_expiry(&fake_jwt_with_exp(-5)), None);
+    }
+
     #[test]
     fn malformed_token_is_stale() {
         assert!(!is_access_token_fresh(
@@ -420,6 +508,40 @@ mod tests {
         assert!(is_session_invalid(401, "", "unauthorized"));
     }
 
+    #[test]
+    fn store_cloud_session_leaves_device_sync_secrets_untouched() {
+        let store = MemorySecretStore::default();
+        store
+            .set_secret("sync_identity", r#"{"deviceId":"dev-1"}"#)
+            .unwrap();
+        store.set_secret("sync_device_id", "dev-1").unwrap();
+        store
+            .set_secret(CLOUD_REFRESH_TOKEN_KEY, "old-refresh")
+            .unwrap();
+        store
+            .set_secret(CLOUD_ACCESS_TOKEN_KEY, "legacy-access")
+            .unwrap();
+
+        store_cloud_session(&store, "new-refresh").unwrap();
+
+        assert_eq!(
+            store
+                .get_secret(CLOUD_REFRESH_TOKEN_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("new-refresh")
+        );
+        assert_eq!(store.get_secret(CLOUD_ACCESS_TOKEN_KEY).unwrap(), None);
+        assert_eq!(
+            store.get_secret("sync_identity").unwrap().as_deref(),
+            Some(r#"{"deviceId":"dev-1"}"#)
+        );
+        assert_eq!(
+            store.get_secret("sync_device_id").unwrap().as_deref(),
+            Some("dev-1")
+        );
+    }
+
     #[test]
     fn fallback_refresh_error_preserves_invalid_session_body() {
         let message =
@@ -427,4 +549,122 @@ mod tests {
 
         assert!(is_session_invalid(400, "", &message));
     }
+
+    /// Answers every token request with a fresh access token and counts the requests.
+    fn start_counting_auth_server(
+        access_token: String,
+    ) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut buffer = [0_u8; 8192];
+                let _ = stream.read(&mut buffer);
+                counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let body = format!(
+                    r#"{{"access_token":"{}","refresh_token":"rotated","expires_in":3600}}"#,
+                    access_token
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    fn fresh_jwt() -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("current time after epoch")
+            .as_secs() as i64;
+        fake_jwt_with_exp(now + 3600)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_callers_after_expiry_share_one_refresh() {
+        let token = fresh_jwt();
+        let (auth_url, requests) = start_counting_auth_server(token.clone());
+        let config = Arc::new(TokenLifecycleConfig::new(auth_url, "key".to_string()));
+        let store = Arc::new(MemorySecretStore::default());
+        store
+            .set_secret(CLOUD_REFRESH_TOKEN_KEY, "refresh")
+            .unwrap();
+        // Nothing cached, as after the previous token expired.
+        let state = Arc::new(TokenLifecycleState::new());
+
+        let callers: Vec<_> = (0..8)
+            .map(|_| {
+                let (store, state, config) = (store.clone(), state.clone(), config.clone());
+                tokio::spawn(async move {
+                    ensure_valid_access_token(store.as_ref(), &state, Some(config.as_ref())).await
+                })
+            })
+            .collect();
+        for caller in callers {
+            assert_eq!(caller.await.unwrap().unwrap(), token);
+        }
+
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(
+            store
+                .get_secret(CLOUD_REFRESH_TOKEN_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("rotated")
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_forced_refreshes_share_one_refresh() {
+        let token = fresh_jwt();
+        let (auth_url, requests) = start_counting_auth_server(token.clone());
+        let config = Arc::new(TokenLifecycleConfig::new(auth_url, "key".to_string()));
+        let store = Arc::new(MemorySecretStore::default());
+        store
+            .set_secret(CLOUD_REFRESH_TOKEN_KEY, "refresh")
+            .unwrap();
+        let state = Arc::new(TokenLifecycleState::new());
+        write_cache(
+            &state,
+            fresh_jwt(),
+            Instant::now() + Duration::from_secs(60),
+        )
+        .await;
+
+        // Hold the lock until every caller has asked, so none asks after the refresh is done.
+        let held = state.refresh_lock.lock().await;
+        let callers: Vec<_> = (0..8)
+            .map(|_| {
+                let (store, state, config) = (store.clone(), state.clone(), config.clone());
+                tokio::spawn(async move {
+                    force_refresh_access_token(store.as_ref(), &state, Some(config.as_ref())).await
+                })
+            })
+            .collect();
+        // Advancing the paused clock yields, so every caller runs until it waits on the lock.
+        tokio::time::pause();
+        tokio::time::advance(Duration::from_millis(50)).await;
+        tokio::time::resume();
+        drop(held);
+        for caller in callers {
+            assert!(caller.await.unwrap().is_ok());
+        }
+
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // A refresh asked for after the last one finished does go out.
+        force_refresh_access_token(store.as_ref(), &state, Some(config.as_ref()))
+            .await
+            .unwrap();
+        assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
 }
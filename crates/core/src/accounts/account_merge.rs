@@ -0,0 +1,278 @@
+//! Merging a duplicate account into another one.
+//!
+//! Re-linking a broker can leave the same brokerage account twice, with activity split between
+//! the two. Merging moves every activity from the source account into the target, drops source
+//! activities the target already has, and deletes the emptied source account.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::accounts_model::Account;
+use crate::activities::{compute_activity_idempotency_key, compute_idempotency_key, Activity};
+use crate::errors::{Error, Result, ValidationError};
+
+/// A source activity that moves to the target account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMergeMove {
+    pub activity_id: String,
+    /// Idempotency key rebuilt for the target account, so future imports still dedupe.
+    pub idempotency_key: Option<String>,
+}
+
+/// What a merge does, computed before anything is written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMergePlan {
+    pub source_account_id: String,
+    pub target_account_id: String,
+    pub moves: Vec<AccountMergeMove>,
+    /// Source activities that duplicate a target activity; deleted instead of moved.
+    pub duplicate_activity_ids: Vec<String>,
+}
+
+/// Outcome of a merge, or its preview when `dry_run` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMergeResult {
+    pub source_account_id: String,
+    pub source_account_name: String,
+    pub target_account_id: String,
+    pub target_account_name: String,
+    pub moved_activity_ids: Vec<String>,
+    pub duplicate_activity_ids: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// Identifies the same economic event across accounts. Provider references and notes are left
+/// out: they are what tends to differ between two links of the same brokerage account.
+fn merge_fingerprint(activity: &Activity, target_account_id: &str) -> String {
+    compute_idempotency_key(
+        target_account_id,
+        activity.effective_type(),
+        &activity.activity_date,
+        activity.asset_id.as_deref(),
+        activity.quantity,
+        activity.unit_price,
+        activity.amount,
+        activity.fee,
+        &activity.currency,
+        None,
+        None,
+    )
+}
+
+/// Key the moved activity gets in the target account. Manual keys are random and stay as-is.
+fn rekeyed_idempotency_key(activity: &Activity, target_account_id: &str) -> Option<String> {
+    let key = activity.idempotency_key.as_deref()?;
+    if key.starts_with("manual:") {
+        return Some(key.to_string());
+    }
+    let mut moved = activity.clone();
+    moved.account_id = target_account_id.to_string();
+    Some(compute_activity_idempotency_key(&moved))
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message.into()))
+}
+
+/// Plans merging `source` into `target`. Each target activity absorbs at most one matching source
+/// activity, so genuinely repeated trades on the same day are kept.
+pub fn plan_account_merge(
+    source: &Account,
+    target: &Account,
+    source_activities: &[Activity],
+    target_activities: &[Activity],
+) -> Result<AccountMergePlan> {
+    if source.id == target.id {
+        return Err(invalid("An account cannot be merged into itself"));
+    }
+    if source.is_archived || target.is_archived {
+        return Err(invalid("Unarchive both accounts before merging them"));
+    }
+    if source.currency != target.currency {
+        return Err(invalid(format!(
+            "Cannot merge a {} account into a {} account",
+            source.currency, target.currency
+        )));
+    }
+    if source.tracking_mode != target.tracking_mode {
+        return Err(invalid(
+            "Accounts with different tracking modes cannot be merged",
+        ));
+    }
+
+    let mut unmatched: HashMap<String, usize> = HashMap::new();
+    let mut target_keys: HashSet<&str> = HashSet::new();
+    for activity in target_activities {
+        *unmatched
+            .entry(merge_fingerprint(activity, &target.id))
+            .or_default() += 1;
+        if let Some(key) = activity.idempotency_key.as_deref() {
+            target_keys.insert(key);
+        }
+    }
+
+    let mut ordered: Vec<&Activity> = source_activities.iter().collect();
+    ordered.sort_by_key(|activity| (activity.activity_date, activity.created_at));
+
+    let mut moves = Vec::new();
+    let mut duplicate_activity_ids = Vec::new();
+    let mut moved_keys: HashSet<String> = HashSet::new();
+    for activity in ordered {
+        let fingerprint = merge_fingerprint(activity, &target.id);
+        if let Some(remaining) = unmatched.get_mut(&fingerprint).filter(|count| **count > 0) {
+            *remaining -= 1;
+            duplicate_activity_ids.push(activity.id.clone());
+            continue;
+        }
+
+        // Keep the old key if the rebuilt one is already taken; keys must stay unique.
+        let idempotency_key = rekeyed_idempotency_key(activity, &target.id)
+            .filter(|key| !target_keys.contains(key.as_str()) && !moved_keys.contains(key))
+            .or_else(|| activity.idempotency_key.clone());
+        if let Some(key) = &idempotency_key {
+            moved_keys.insert(key.clone());
+        }
+        moves.push(AccountMergeMove {
+            activity_id: activity.id.clone(),
+            idempotency_key,
+        });
+    }
+
+    Ok(AccountMergePlan {
+        source_account_id: source.id.clone(),
+        target_account_id: target.id.clone(),
+        moves,
+        duplicate_activity_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::TrackingMode;
+    use crate::activities::{ActivityStatus, ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_DEPOSIT};
+    use chrono::{DateTime, Utc};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn account(id: &str) -> Account {
+        Account {
+            id: id.to_string(),
+            name: id.to_string(),
+            currency: "USD".to_string(),
+            tracking_mode: TrackingMode::Transactions,
+            ..Default::default()
+        }
+    }
+
+    fn activity(id: &str, account_id: &str, date: &str, amount: Decimal) -> Activity {
+        let activity_date = DateTime::parse_from_rfc3339(&format!("{}T00:00:00Z", date))
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut activity = Activity {
+            id: id.to_string(),
+            account_id: account_id.to_string(),
+            asset_id: None,
+            activity_type: ACTIVITY_TYPE_DEPOSIT.to_string(),
+            activity_type_override: None,
+            source_type: None,
+            subtype: None,
+            status: ActivityStatus::Posted,
+            activity_date,
+            settlement_date: None,
+            quantity: None,
+            unit_price: None,
+            amount: Some(amount),
+            fee: None,
+            tax: None,
+            currency: "USD".to_string(),
+            fx_rate: None,
+            notes: None,
+            metadata: None,
+            source_system: None,
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+            is_user_modified: false,
+            needs_review: false,
+            created_at: activity_date,
+            updated_at: activity_date,
+        };
+        activity.idempotency_key = Some(compute_activity_idempotency_key(&activity));
+        activity
+    }
+
+    #[test]
+    fn overlapping_activities_collapse_and_the_rest_move_with_new_keys() {
+        let mut relinked = activity("s1", "old", "2024-01-02", dec!(100));
+        relinked.source_record_id = Some("old-provider-ref".to_string());
+        let source = vec![relinked, activity("s2", "old", "2024-02-01", dec!(50))];
+        let target = vec![activity("t1", "new", "2024-01-02", dec!(100))];
+
+        let plan = plan_account_merge(&account("old"), &account("new"), &source, &target).unwrap();
+
+        assert_eq!(plan.duplicate_activity_ids, vec!["s1".to_string()]);
+        assert_eq!(plan.moves.len(), 1);
+        assert_eq!(plan.moves[0].activity_id, "s2");
+        let mut moved = source[1].clone();
+        moved.account_id = "new".to_string();
+        assert_eq!(
+            plan.moves[0].idempotency_key,
+            Some(compute_activity_idempotency_key(&moved))
+        );
+    }
+
+    #[test]
+    fn each_target_activity_absorbs_only_one_duplicate() {
+        let mut again = activity("s2", "old", "2024-01-02", dec!(100));
+        again.notes = Some("second deposit".to_string());
+        again.idempotency_key = Some(compute_activity_idempotency_key(&again));
+        let source = vec![activity("s1", "old", "2024-01-02", dec!(100)), again];
+        let target = vec![activity("t1", "new", "2024-01-02", dec!(100))];
+
+        let plan = plan_account_merge(&account("old"), &account("new"), &source, &target).unwrap();
+
+        assert_eq!(plan.duplicate_activity_ids.len(), 1);
+        assert_eq!(plan.moves.len(), 1);
+    }
+
+    #[test]
+    fn incompatible_accounts_are_rejected() {
+        let mut cad = account("old");
+        cad.currency = "CAD".to_string();
+        assert!(plan_account_merge(&cad, &account("new"), &[], &[]).is_err());
+
+        let mut holdings = account("old");
+        holdings.tracking_mode = TrackingMode::Holdings;
+        assert!(plan_account_merge(&holdings, &account("new"), &[], &[]).is_err());
+
+        let mut archived = account("old");
+        archived.is_archived = true;
+        assert!(plan_account_merge(&archived, &account("new"), &[], &[]).is_err());
+
+        assert!(plan_account_merge(&account("new"), &account("new"), &[], &[]).is_err());
+    }
+
+    #[test]
+    fn trades_match_on_asset_and_quantity() {
+        let mut buy = activity("s1", "old", "2024-01-02", dec!(1000));
+        buy.activity_type = ACTIVITY_TYPE_BUY.to_string();
+        buy.asset_id = Some("SEC:AAPL:XNAS".to_string());
+        buy.quantity = Some(dec!(10));
+        let mut other_asset = buy.clone();
+        other_asset.id = "t1".to_string();
+        other_asset.account_id = "new".to_string();
+        other_asset.asset_id = Some("SEC:MSFT:XNAS".to_string());
+
+        let plan =
+            plan_account_merge(&account("old"), &account("new"), &[buy], &[other_asset]).unwrap();
+
+        assert!(plan.duplicate_activity_ids.is_empty());
+        assert_eq!(plan.moves.len(), 1);
+    }
+}
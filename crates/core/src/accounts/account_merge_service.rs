@@ -0,0 +1,106 @@
+//! Account merge service implementation.
+
+use log::info;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use super::account_merge::{plan_account_merge, AccountMergeResult};
+use super::accounts_traits::{
+    AccountMergeRepositoryTrait, AccountMergeServiceTrait, AccountRepositoryTrait,
+};
+use crate::activities::ActivityRepositoryTrait;
+use crate::errors::{Error, Result, ValidationError};
+use crate::events::{DomainEvent, DomainEventSink};
+
+/// Service for merging duplicate accounts.
+pub struct AccountMergeService {
+    account_repository: Arc<dyn AccountRepositoryTrait>,
+    activity_repository: Arc<dyn ActivityRepositoryTrait>,
+    merge_repository: Arc<dyn AccountMergeRepositoryTrait>,
+    event_sink: Arc<dyn DomainEventSink>,
+}
+
+impl AccountMergeService {
+    pub fn new(
+        account_repository: Arc<dyn AccountRepositoryTrait>,
+        activity_repository: Arc<dyn ActivityRepositoryTrait>,
+        merge_repository: Arc<dyn AccountMergeRepositoryTrait>,
+        event_sink: Arc<dyn DomainEventSink>,
+    ) -> Self {
+        Self {
+            account_repository,
+            activity_repository,
+            merge_repository,
+            event_sink,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AccountMergeServiceTrait for AccountMergeService {
+    async fn merge_accounts(
+        &self,
+        source_account_id: &str,
+        target_account_id: &str,
+        dry_run: bool,
+        confirm: bool,
+    ) -> Result<AccountMergeResult> {
+        let source = self.account_repository.get_by_id(source_account_id)?;
+        let target = self.account_repository.get_by_id(target_account_id)?;
+        let source_activities = self
+            .activity_repository
+            .get_activities_by_account_id(&source.id)?;
+        let target_activities = self
+            .activity_repository
+            .get_activities_by_account_id(&target.id)?;
+
+        let plan = plan_account_merge(&source, &target, &source_activities, &target_activities)?;
+        let result = AccountMergeResult {
+            source_account_id: source.id.clone(),
+            source_account_name: source.name.clone(),
+            target_account_id: target.id.clone(),
+            target_account_name: target.name.clone(),
+            moved_activity_ids: plan.moves.iter().map(|m| m.activity_id.clone()).collect(),
+            duplicate_activity_ids: plan.duplicate_activity_ids.clone(),
+            dry_run,
+        };
+        if dry_run {
+            return Ok(result);
+        }
+        if !confirm {
+            return Err(Error::Validation(ValidationError::InvalidInput(
+                "Merging accounts deletes the source account; confirm the merge to proceed"
+                    .to_string(),
+            )));
+        }
+
+        self.merge_repository.apply_account_merge(&plan).await?;
+        info!(
+            "Merged account {} into {}: {} activities moved, {} duplicates removed",
+            source.id,
+            target.id,
+            result.moved_activity_ids.len(),
+            result.duplicate_activity_ids.len()
+        );
+
+        let moved: Vec<_> = source_activities
+            .iter()
+            .filter(|a| result.moved_activity_ids.contains(&a.id))
+            .collect();
+        let asset_ids: BTreeSet<String> = moved.iter().filter_map(|a| a.asset_id.clone()).collect();
+        let currencies: BTreeSet<String> = moved.iter().map(|a| a.currency.clone()).collect();
+        let earliest = moved.iter().map(|a| a.activity_date).min();
+        self.event_sink.emit(DomainEvent::accounts_changed(
+            vec![source.id.clone()],
+            vec![],
+        ));
+        self.event_sink.emit(DomainEvent::activities_changed(
+            vec![target.id.clone()],
+            asset_ids.into_iter().collect(),
+            currencies.into_iter().collect(),
+            earliest,
+        ));
+
+        Ok(result)
+    }
+}
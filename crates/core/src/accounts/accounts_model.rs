@@ -1,8 +1,10 @@
 //! Account domain models.
 
 use chrono::NaiveDateTime;
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::time_utils::parse_user_timezone;
 use crate::{errors::ValidationError, Error, Result};
 
 use super::accounts_constants::account_types;
@@ -264,6 +266,36 @@ impl Account {
             .filter(|s| !s.is_empty())
             .map(String::from)
     }
+
+    /// Timezone that activity dates without an offset are read in, if one is configured.
+    pub fn activity_timezone(&self) -> Option<Tz> {
+        activity_timezone_in_meta(self.meta.as_deref()).and_then(|tz| parse_user_timezone(&tz).ok())
+    }
+}
+
+/// Account meta key holding the IANA timezone used to normalize imported and synced
+/// activity dates.
+pub const ACTIVITY_TIMEZONE_META_KEY: &str = "activityTimezone";
+
+fn activity_timezone_in_meta(meta: Option<&str>) -> Option<String> {
+    let meta = meta?.trim();
+    if meta.is_empty() {
+        return None;
+    }
+    let parsed: serde_json::Value = serde_json::from_str(meta).ok()?;
+    parsed
+        .get(ACTIVITY_TIMEZONE_META_KEY)?
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+fn validate_activity_timezone_meta(meta: Option<&str>) -> Result<()> {
+    match activity_timezone_in_meta(meta) {
+        Some(tz) => parse_user_timezone(&tz).map(|_| ()),
+        None => Ok(()),
+    }
 }
 
 /// Input model for creating a new account.
@@ -309,7 +341,7 @@ impl NewAccount {
                 "Credit card accounts cannot use HOLDINGS tracking mode".to_string(),
             )));
         }
-        Ok(())
+        validate_activity_timezone_meta(self.meta.as_deref())
     }
 }
 
@@ -352,6 +384,6 @@ impl AccountUpdate {
                 "Credit card accounts cannot use HOLDINGS tracking mode".to_string(),
             )));
         }
-        Ok(())
+        validate_activity_timezone_meta(self.meta.as_deref())
     }
 }
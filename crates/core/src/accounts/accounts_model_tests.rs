@@ -140,6 +140,40 @@ mod tests {
             .contains("Credit card accounts cannot use HOLDINGS tracking mode"));
     }
 
+    #[test]
+    fn activity_timezone_parses_meta_and_rejects_unknown_zones() {
+        let account = Account {
+            meta: Some(r#"{"activityTimezone":"America/New_York"}"#.to_string()),
+            ..Account::default()
+        };
+        assert_eq!(
+            account.activity_timezone(),
+            Some(chrono_tz::America::New_York)
+        );
+        assert_eq!(Account::default().activity_timezone(), None);
+
+        let mut new_account = NewAccount {
+            id: None,
+            name: "Brokerage".to_string(),
+            account_type: "SECURITIES".to_string(),
+            group: None,
+            currency: "USD".to_string(),
+            is_default: false,
+            is_active: true,
+            platform_id: None,
+            account_number: None,
+            meta: Some(r#"{"activityTimezone":"Mars/Olympus"}"#.to_string()),
+            provider: None,
+            provider_account_id: None,
+            is_archived: false,
+            tracking_mode: TrackingMode::Transactions,
+        };
+        assert!(new_account.validate().is_err());
+
+        new_account.meta = account.meta.clone();
+        assert!(new_account.validate().is_ok());
+    }
+
     // ==================== Helper Functions ====================
 
     fn create_test_account(tracking_mode: TrackingMode) -> Account {
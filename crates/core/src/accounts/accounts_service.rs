@@ -1,6 +1,7 @@
 //! Account service implementation.
 
 use log::{debug, info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 use super::accounts_constants::account_types;
@@ -20,6 +21,7 @@ pub struct AccountService {
     event_sink: Arc<dyn DomainEventSink>,
     asset_repository: Arc<dyn AssetRepositoryTrait>,
     sync_state_store: Arc<dyn SyncStateStore>,
+    data_version: AtomicU64,
 }
 
 impl AccountService {
@@ -39,6 +41,7 @@ impl AccountService {
             event_sink,
             asset_repository,
             sync_state_store,
+            data_version: AtomicU64::new(0),
         }
     }
 
@@ -80,6 +83,7 @@ impl AccountServiceTrait for AccountService {
 
         // Repository handles transaction internally
         let result = self.repository.create(new_account).await?;
+        self.data_version.fetch_add(1, Ordering::SeqCst);
 
         // Emit AccountsChanged event with currency info for FX sync planning
         let currency_changes = vec![CurrencyChange {
@@ -111,6 +115,7 @@ impl AccountServiceTrait for AccountService {
         )?;
 
         let result = self.repository.update(account_update).await?;
+        self.data_version.fetch_add(1, Ordering::SeqCst);
 
         // Detect currency changes and register FX pair if needed
         let currency_changes = if existing.currency != result.currency {
@@ -199,9 +204,14 @@ impl AccountServiceTrait for AccountService {
         }
     }
 
+    fn data_version(&self) -> u64 {
+        self.data_version.load(Ordering::SeqCst)
+    }
+
     /// Deletes an account by its ID.
     async fn delete_account(&self, account_id: &str) -> Result<()> {
         self.repository.delete(account_id).await?;
+        self.data_version.fetch_add(1, Ordering::SeqCst);
 
         // Clean up orphaned assets (activities are already CASCADE-deleted)
         match self
@@ -6,6 +6,7 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
 
+use super::account_merge::{AccountMergePlan, AccountMergeResult};
 use super::accounts_model::{Account, AccountAccountingSettings, AccountUpdate, NewAccount};
 use crate::errors::Result;
 
@@ -108,4 +109,32 @@ pub trait AccountServiceTrait: Send + Sync {
 
     /// Returns the configured base currency if available.
     fn get_base_currency(&self) -> Option<String>;
+
+    /// Counter that goes up after every account change made through this service, so derived
+    /// caches can tell they are stale.
+    fn data_version(&self) -> u64 {
+        0
+    }
+}
+
+/// Persists account merges planned by [`super::plan_account_merge`].
+#[async_trait]
+pub trait AccountMergeRepositoryTrait: Send + Sync {
+    /// Applies the plan in a single transaction: deletes the duplicate activities, moves the
+    /// rest to the target account, and deletes the source account with its derived data.
+    async fn apply_account_merge(&self, plan: &AccountMergePlan) -> Result<()>;
+}
+
+/// Merges a duplicate account into another one.
+#[async_trait]
+pub trait AccountMergeServiceTrait: Send + Sync {
+    /// Merges `source_account_id` into `target_account_id`. With `dry_run` the merge is only
+    /// previewed; otherwise `confirm` must be set, as the source account is deleted.
+    async fn merge_accounts(
+        &self,
+        source_account_id: &str,
+        target_account_id: &str,
+        dry_run: bool,
+        confirm: bool,
+    ) -> Result<AccountMergeResult>;
 }
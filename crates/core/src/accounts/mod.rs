@@ -1,18 +1,27 @@
 //! Accounts module - domain models, services, and traits.
 
+mod account_merge;
+mod account_merge_service;
 mod accounts_constants;
 mod accounts_model;
 mod accounts_service;
 mod accounts_traits;
 
 // Re-export the public interface
+pub use account_merge::{
+    plan_account_merge, AccountMergeMove, AccountMergePlan, AccountMergeResult,
+};
+pub use account_merge_service::AccountMergeService;
 pub use accounts_constants::*;
 pub use accounts_model::{
     Account, AccountAccountingSettings, AccountUpdate, CostBasisMethod, CostBasisProfile,
-    LotSelectionStrategy, NewAccount, PoolingScope, TrackingMode,
+    LotSelectionStrategy, NewAccount, PoolingScope, TrackingMode, ACTIVITY_TIMEZONE_META_KEY,
 };
 pub use accounts_service::AccountService;
-pub use accounts_traits::{AccountRepositoryTrait, AccountServiceTrait};
+pub use accounts_traits::{
+    AccountMergeRepositoryTrait, AccountMergeServiceTrait, AccountRepositoryTrait,
+    AccountServiceTrait,
+};
 
 /// Returns true when an account belongs in portfolio totals/history.
 pub fn account_in_portfolio_scope(account: &Account) -> bool {
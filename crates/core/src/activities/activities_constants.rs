@@ -67,6 +67,18 @@ pub const INCOME_ACTIVITY_TYPES: [&str; 2] = [ACTIVITY_TYPE_DIVIDEND, ACTIVITY_T
 /// value from quotes instead.
 pub const PRICE_BEARING_ACTIVITY_TYPES: [&str; 2] = [ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_SELL];
 
+/// Default number of activities returned by the recent activity feed.
+pub const DEFAULT_RECENT_ACTIVITIES_LIMIT: i64 = 20;
+
+/// Largest page the recent activity feed returns.
+pub const MAX_RECENT_ACTIVITIES_LIMIT: i64 = 100;
+
+/// Default size of a keyset (cursor) activity page.
+pub const DEFAULT_ACTIVITY_PAGE_SIZE: i64 = 200;
+
+/// Largest keyset activity page.
+pub const MAX_ACTIVITY_PAGE_SIZE: i64 = 1000;
+
 /// Activity types that always require a symbol/asset.
 /// Everything else: symbol is optional (cash-only or dual-use like TRANSFER_IN).
 /// Note: DIVIDEND and ADJUSTMENT are handled separately in classify_import_activity (symbol optional during import).
@@ -11,6 +11,7 @@ use crate::activities::activities_constants::{
     ACTIVITY_TYPE_WITHDRAWAL,
 };
 use crate::activities::csv_parser::ParseConfig;
+use crate::activities::currency_mismatch::CurrencyMismatch;
 use crate::assets::NewAsset;
 use crate::Result;
 use crate::{activities::activities_errors::ActivityError, QuoteMode};
@@ -884,6 +885,83 @@ pub struct ActivitySearchResponse {
     pub meta: ActivitySearchResponseMeta,
 }
 
+/// Position after the last activity of a keyset page. Keyset pages are ordered newest first by
+/// `(date, id)`, which is unique, so a page never repeats or skips rows when activities are
+/// inserted between requests. Clients see it as an opaque string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityCursor {
+    pub date: String,
+    pub id: String,
+}
+
+impl ActivityCursor {
+    pub fn after(activity: &ActivityDetails) -> Self {
+        Self {
+            date: activity.date.clone(),
+            id: activity.id.clone(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        hex::encode(format!("{}\n{}", self.date, self.id))
+    }
+
+    pub fn decode(raw: &str) -> Result<Self> {
+        let invalid = || ActivityError::InvalidData("Invalid activity page cursor".to_string());
+        let bytes = hex::decode(raw.trim()).map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (date, id) = text.split_once('\n').ok_or_else(invalid)?;
+        if date.is_empty() || id.is_empty() {
+            return Err(invalid().into());
+        }
+        Ok(Self {
+            date: date.to_string(),
+            id: id.to_string(),
+        })
+    }
+
+    /// Whether `activity` comes after this cursor in keyset order.
+    pub fn precedes(&self, activity: &ActivityDetails) -> bool {
+        (activity.date.as_str(), activity.id.as_str()) < (self.date.as_str(), self.id.as_str())
+    }
+}
+
+/// One keyset page of activities. `next_cursor` is `None` on the last page.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPage {
+    pub data: Vec<ActivityDetails>,
+    pub next_cursor: Option<String>,
+}
+
+impl ActivityPage {
+    /// Builds a page from up to `limit + 1` rows in keyset order; the extra row only signals
+    /// that another page follows.
+    pub fn from_rows(mut rows: Vec<ActivityDetails>, limit: i64) -> Self {
+        let limit = usize::try_from(limit.max(0)).unwrap_or(usize::MAX);
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(|last| ActivityCursor::after(last).encode())
+        } else {
+            None
+        };
+        Self {
+            data: rows,
+            next_cursor,
+        }
+    }
+}
+
+/// Activity in the cross-account recent feed, with the broker it belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentActivity {
+    #[serde(flatten)]
+    pub activity: ActivityDetails,
+    /// Display name of the account's platform, falling back to the platform id.
+    pub broker_name: Option<String>,
+}
+
 /// Model for importing activities
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -1708,8 +1786,14 @@ mod optional_decimal_format {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IncomeData {
+    /// Month of the activity (`YYYY-MM`).
     pub date: String,
+    /// Day of the activity (`YYYY-MM-DD`).
+    #[serde(default)]
+    pub activity_date: String,
     pub income_type: String,
+    #[serde(default)]
+    pub subtype: Option<String>,
     pub asset_id: String,
     pub asset_kind: String,
     pub symbol: String,
@@ -1730,6 +1814,10 @@ pub struct ImportActivitiesResult {
     pub import_run_id: String,
     /// Summary statistics for the import
     pub summary: ImportActivitiesSummary,
+    /// Activities whose currency differs from their account currency, when a
+    /// mismatch policy is configured
+    #[serde(default)]
+    pub currency_mismatches: Vec<CurrencyMismatch>,
 }
 
 /// Summary statistics for an activity import
@@ -1796,6 +1884,29 @@ pub struct BulkUpsertResult {
     pub skipped: usize,
 }
 
+/// Which of an account's activities a reconciliation deletes, and which it keeps although the
+/// source no longer returns them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleActivityPlan {
+    /// Activities the source no longer returns.
+    pub remove_ids: Vec<String>,
+    /// Activities the source no longer returns that were edited locally; left in place.
+    pub preserved_ids: Vec<String>,
+}
+
+/// Chooses the stale activities of an account from its activities as stored after an upsert.
+pub type StaleActivitySelector = Box<dyn FnOnce(&[Activity]) -> StaleActivityPlan + Send>;
+
+/// Result of upserting an account's activities and deleting its stale ones together.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityReconcileResult {
+    pub upserted: BulkUpsertResult,
+    pub plan: StaleActivityPlan,
+    /// The deleted activities, including transfer counterparts removed with them.
+    pub removed: Vec<Activity>,
+}
+
 /// Activity ready for persistence
 #[derive(Debug, Clone)]
 pub struct PreparedActivity {
@@ -1811,6 +1922,7 @@ pub struct PrepareActivitiesResult {
     pub errors: Vec<(usize, String)>, // (index, error_message)
     pub assets_created: u32,
     pub created_asset_ids: Vec<String>,
+    pub currency_mismatches: Vec<CurrencyMismatch>,
 }
 
 impl From<ActivityImport> for NewActivity {
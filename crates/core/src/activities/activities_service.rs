@@ -1,4 +1,5 @@
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
 use log::debug;
 use rust_decimal::Decimal;
 use std::collections::{HashMap, HashSet};
@@ -9,23 +10,29 @@ use crate::activities::activities_constants::{
     classify_import_activity, is_cash_symbol, is_garbage_symbol, requires_symbol,
     ImportSymbolDisposition, ACTIVITY_TYPE_CREDIT, ACTIVITY_TYPE_FEE, ACTIVITY_TYPE_INTEREST,
     ACTIVITY_TYPE_SPLIT, ACTIVITY_TYPE_TRANSFER_IN, ACTIVITY_TYPE_TRANSFER_OUT,
-    ACTIVITY_TYPE_WITHDRAWAL, PRICE_BEARING_ACTIVITY_TYPES,
+    ACTIVITY_TYPE_WITHDRAWAL, DEFAULT_ACTIVITY_PAGE_SIZE, DEFAULT_RECENT_ACTIVITIES_LIMIT,
+    MAX_ACTIVITY_PAGE_SIZE, MAX_RECENT_ACTIVITIES_LIMIT, PRICE_BEARING_ACTIVITY_TYPES,
 };
 use crate::activities::activities_errors::ActivityError;
 use crate::activities::activities_model::*;
 use crate::activities::csv_parser::{self, ParseConfig, ParsedCsvResult};
+use crate::activities::currency_mismatch::{
+    apply_currency_mismatch_policy, CurrencyMismatch, CurrencyMismatchPolicy,
+};
 use crate::activities::idempotency::compute_idempotency_key;
 use crate::activities::{
-    ActivityRepositoryTrait, ActivityServiceTrait, TransferPair, TransferPairResolution,
+    normalize_new_activity_date, ActivityRepositoryTrait, ActivityServiceTrait, TransferPair,
+    TransferPairResolution,
 };
 use crate::activities::{
     ImportRun, ImportRunMode, ImportRunRepositoryTrait, ImportRunSummary, ImportRunType, ReviewMode,
 };
 use crate::assets::{
-    canonicalize_market_identity, normalize_quote_ccy_code, parse_crypto_pair_symbol,
-    parse_symbol_with_exchange_suffix, resolve_quote_ccy_precedence, AssetKind,
-    AssetResolutionInput as ImportAssetResolutionInput, AssetServiceTrait, InstrumentType,
-    QuoteCcyResolutionSource, QuoteMode,
+    canonicalize_market_identity, normalize_quote_ccy_code, normalize_share_class_separator,
+    parse_crypto_pair_symbol, parse_symbol_with_exchange_suffix, resolve_quote_ccy_precedence,
+    AssetKind, AssetResolutionInput as ImportAssetResolutionInput, AssetResolutionOutput,
+    AssetServiceTrait, InstrumentType, QuoteCcyResolutionSource, QuoteMode, SymbolMatchStatus,
+    SymbolNormalization, SymbolNormalizationService, SymbolNormalizer,
 };
 use crate::errors::{DatabaseError, Error};
 use crate::events::{DomainEvent, DomainEventSink, NoOpDomainEventSink};
@@ -33,6 +40,7 @@ use crate::fx::currency::{get_normalization_rule, normalize_amount, resolve_curr
 use crate::fx::FxServiceTrait;
 use crate::quotes::constants::DATA_SOURCE_MANUAL;
 use crate::quotes::{Quote, QuoteServiceTrait};
+use crate::settings::SettingsServiceTrait;
 use crate::Result;
 use log::warn;
 
@@ -109,6 +117,8 @@ pub struct ActivityService {
     quote_service: Arc<dyn QuoteServiceTrait>,
     import_run_repository: Option<Arc<dyn ImportRunRepositoryTrait>>,
     event_sink: Arc<dyn DomainEventSink>,
+    currency_mismatch_policy: Option<CurrencyMismatchPolicy>,
+    symbol_normalization: Option<SymbolNormalizationService>,
 }
 
 #[derive(Clone, Copy)]
@@ -126,6 +136,18 @@ impl PreparationMode {
     fn is_sync(self) -> bool {
         matches!(self, Self::Sync)
     }
+
+    /// Imported and synced symbols are matched against the asset catalog; symbols entered by
+    /// hand were picked from search results.
+    fn normalizes_symbols(self) -> bool {
+        matches!(self, Self::ImportApply | Self::Sync)
+    }
+
+    /// Imported and synced dates are normalized to the account's activity timezone;
+    /// dates entered by hand already come from the user's local date picker.
+    fn normalizes_activity_dates(self) -> bool {
+        matches!(self, Self::ImportApply | Self::Sync)
+    }
 }
 
 impl ActivityService {
@@ -554,6 +576,8 @@ impl ActivityService {
             quote_service,
             import_run_repository: None,
             event_sink: Arc::new(NoOpDomainEventSink),
+            currency_mismatch_policy: None,
+            symbol_normalization: None,
         }
     }
 
@@ -574,6 +598,8 @@ impl ActivityService {
             quote_service,
             import_run_repository: Some(import_run_repository),
             event_sink: Arc::new(NoOpDomainEventSink),
+            currency_mismatch_policy: None,
+            symbol_normalization: None,
         }
     }
 
@@ -637,6 +663,197 @@ impl ActivityService {
         self
     }
 
+    /// Sets how sync and import handle activities whose currency differs from their
+    /// account currency. `None` (the default) leaves them untouched.
+    pub fn with_currency_mismatch_policy(mut self, policy: Option<CurrencyMismatchPolicy>) -> Self {
+        self.currency_mismatch_policy = policy;
+        self
+    }
+
+    /// Normalizes imported and synced symbols against the asset catalog and the manual symbol
+    /// mappings stored in settings.
+    pub fn with_symbol_normalization(
+        mut self,
+        settings_service: Arc<dyn SettingsServiceTrait>,
+    ) -> Self {
+        self.symbol_normalization = Some(SymbolNormalizationService::new(
+            self.asset_service.clone(),
+            settings_service,
+        ));
+        self
+    }
+
+    /// The catalog and manual mappings symbols are normalized against, when configured.
+    fn symbol_normalizer(&self) -> Option<SymbolNormalizer> {
+        let service = self.symbol_normalization.as_ref()?;
+        match service.normalizer() {
+            Ok(normalizer) => Some(normalizer),
+            Err(e) => {
+                warn!("Skipping symbol normalization: {}", e);
+                None
+            }
+        }
+    }
+
+    /// The symbol and exchange to store: the catalog listing the symbol matches or is mapped to,
+    /// otherwise the symbol with its share-class separator normalized.
+    fn normalized_symbol(normalization: &SymbolNormalization) -> (String, Option<String>) {
+        // Crypto pairs carry their quote currency in the symbol; canonicalization keeps it.
+        if !normalization.needs_mapping()
+            && normalization.instrument_type != Some(InstrumentType::Crypto)
+        {
+            if let Some(canonical) = &normalization.canonical_symbol {
+                return (canonical.clone(), normalization.exchange_mic.clone());
+            }
+        }
+        (
+            normalize_share_class_separator(&normalization.raw_symbol),
+            None,
+        )
+    }
+
+    /// Rewrites symbol variants (`BRK-B`, `brk/b`) to the catalog listing they match, or to the
+    /// dotted share class when the catalog has no match yet, so they resolve to one asset.
+    fn normalize_activity_symbols(&self, activities: &mut [NewActivity]) {
+        let Some(normalizer) = self.symbol_normalizer() else {
+            return;
+        };
+        for activity in activities.iter_mut() {
+            let Some(asset) = activity.asset.as_mut() else {
+                continue;
+            };
+            if asset.id.is_some()
+                || matches!(
+                    Self::parse_instrument_type(asset.instrument_type.as_deref()),
+                    Some(InstrumentType::Fx)
+                )
+            {
+                continue;
+            }
+            let Some(symbol) = asset.symbol.as_deref().filter(|s| !s.starts_with("CASH:")) else {
+                continue;
+            };
+            let (symbol, exchange_mic) = Self::normalized_symbol(&normalizer.normalize(symbol));
+            asset.symbol = Some(symbol);
+            if asset.exchange_mic.is_none() {
+                asset.exchange_mic = exchange_mic;
+            }
+        }
+    }
+
+    /// Normalizes the symbols of import rows that resolve to an asset, before resolution, so
+    /// variants in one file resolve as one asset. Returns, per row, the match of a symbol the
+    /// catalog could not place.
+    fn normalize_import_symbols(
+        &self,
+        activities: &mut [ActivityImport],
+    ) -> Vec<Option<SymbolNormalization>> {
+        let Some(normalizer) = self.symbol_normalizer() else {
+            return vec![None; activities.len()];
+        };
+        activities
+            .iter_mut()
+            .map(|activity| {
+                let symbol = activity.symbol.trim();
+                if symbol.is_empty()
+                    || symbol.starts_with("CASH:")
+                    || is_garbage_symbol(symbol)
+                    || activity
+                        .asset_id
+                        .as_deref()
+                        .is_some_and(|id| !id.trim().is_empty())
+                    || matches!(
+                        Self::parse_instrument_type(activity.instrument_type.as_deref()),
+                        Some(InstrumentType::Fx)
+                    )
+                    || !matches!(
+                        Self::classify_import_symbol_disposition(
+                            &activity.activity_type,
+                            activity.subtype.as_deref(),
+                            symbol,
+                            activity.quantity,
+                            activity.unit_price,
+                        ),
+                        ImportSymbolDisposition::ResolveAsset
+                    )
+                {
+                    return None;
+                }
+                let normalization = normalizer.normalize(symbol);
+                let (symbol, exchange_mic) = Self::normalized_symbol(&normalization);
+                activity.symbol = symbol;
+                if activity.exchange_mic.is_none() {
+                    activity.exchange_mic = exchange_mic;
+                }
+                normalization.needs_mapping().then_some(normalization)
+            })
+            .collect()
+    }
+
+    /// Why an import row's symbol needs a manual mapping: it matches several catalog listings,
+    /// or neither the catalog nor the market data providers know it.
+    fn symbol_mapping_warning(
+        normalization: &SymbolNormalization,
+        resolution: Option<&AssetResolutionOutput>,
+    ) -> Option<String> {
+        match normalization.status {
+            SymbolMatchStatus::Ambiguous => {
+                let listings: Vec<String> = normalization
+                    .candidates
+                    .iter()
+                    .map(|candidate| match &candidate.exchange_mic {
+                        Some(mic) => format!("{} on {}", candidate.symbol, mic),
+                        None => candidate.symbol.clone(),
+                    })
+                    .collect();
+                Some(format!(
+                    "'{}' matches several listings ({}). Add an exchange suffix or a symbol mapping.",
+                    normalization.raw_symbol,
+                    listings.join(", ")
+                ))
+            }
+            SymbolMatchStatus::Unmatched
+                if resolution.is_none_or(|output| {
+                    output.existing_asset_id.is_none() && output.exchange_mic.is_none()
+                }) =>
+            {
+                Some(format!(
+                    "'{}' matches no known listing. Add a symbol mapping if the ticker changed or is spelled differently.",
+                    normalization.raw_symbol
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Applies the configured currency mismatch policy to `activity`, looking up the
+    /// historical rate for its date.
+    fn resolve_currency_mismatch(
+        &self,
+        activity: &mut NewActivity,
+        account_currency: &str,
+    ) -> Option<CurrencyMismatch> {
+        let policy = self.currency_mismatch_policy?;
+        let mismatch = apply_currency_mismatch_policy(
+            activity,
+            account_currency,
+            policy,
+            |from, to, date| {
+                self.fx_service
+                    .get_exchange_rate_for_date(from, to, date)
+                    .ok()
+            },
+        )?;
+        if let Some(reason) = &mismatch.reason {
+            warn!(
+                "Activity {} flagged for review: {}",
+                mismatch.activity_id.as_deref().unwrap_or("(new)"),
+                reason
+            );
+        }
+        Some(mismatch)
+    }
+
     fn invalid_activity_data(message: impl Into<String>) -> Error {
         ActivityError::InvalidData(message.into()).into()
     }
@@ -3104,11 +3321,12 @@ impl ActivityService {
     async fn check_activities_import_for_account(
         &self,
         account_id: String,
-        activities: Vec<ActivityImport>,
+        mut activities: Vec<ActivityImport>,
     ) -> Result<Vec<ActivityImport>> {
         let account: Account = self.account_service.get_account(&account_id)?;
         let base_ccy = self.account_service.get_base_currency().unwrap_or_default();
         let account_currency = resolve_currency(&[&account.currency, &base_ccy]);
+        let unplaced_symbols = self.normalize_import_symbols(&mut activities);
 
         let asset_resolution_inputs: Vec<ImportAssetResolutionInput> = activities
             .iter()
@@ -3170,7 +3388,7 @@ impl ActivityService {
         let mut quote_ccy_cache: QuoteCcyCache = HashMap::new();
         let mut activities_with_status: Vec<ActivityImport> = Vec::new();
 
-        for mut activity in activities {
+        for (mut activity, unplaced_symbol) in activities.into_iter().zip(unplaced_symbols) {
             activity.id = Some(Uuid::new_v4().to_string());
             if activity.account_name.is_none() {
                 activity.account_name = Some(account.name.clone());
@@ -3268,6 +3486,11 @@ impl ActivityService {
             };
             let resolution_key = import_asset_resolution_key(&activity, &resolve_ccy);
             let asset_resolution = asset_resolution_cache.get(&resolution_key);
+            if let Some(message) = unplaced_symbol.as_ref().and_then(|normalization| {
+                Self::symbol_mapping_warning(normalization, asset_resolution)
+            }) {
+                Self::add_activity_warning(&mut activity, "symbol", &message);
+            }
             let resolution_quote_ccy = asset_resolution
                 .and_then(|output| output.quote_ccy.clone())
                 .filter(|currency| !currency.trim().is_empty());
@@ -3712,6 +3935,47 @@ impl ActivityServiceTrait for ActivityService {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn search_activities_page(
+        &self,
+        cursor: Option<String>,
+        limit: Option<i64>,
+        account_id_filter: Option<Vec<String>>,
+        activity_type_filter: Option<Vec<String>>,
+        asset_id_keyword: Option<String>,
+        needs_review_filter: Option<bool>,
+        date_from_utc: Option<DateTime<Utc>>,
+        date_to_utc_exclusive: Option<DateTime<Utc>>,
+        instrument_type_filter: Option<Vec<String>>,
+    ) -> Result<ActivityPage> {
+        let cursor = cursor
+            .filter(|raw| !raw.trim().is_empty())
+            .map(|raw| ActivityCursor::decode(&raw))
+            .transpose()?;
+        let limit = limit
+            .unwrap_or(DEFAULT_ACTIVITY_PAGE_SIZE)
+            .clamp(1, MAX_ACTIVITY_PAGE_SIZE);
+        self.activity_repository.search_activities_page(
+            cursor.as_ref(),
+            limit,
+            account_id_filter,
+            activity_type_filter,
+            asset_id_keyword,
+            needs_review_filter,
+            date_from_utc,
+            date_to_utc_exclusive,
+            instrument_type_filter,
+        )
+    }
+
+    /// Retrieves the most recent activities across all accounts
+    fn get_recent_activities(&self, limit: Option<i64>) -> Result<Vec<RecentActivity>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_RECENT_ACTIVITIES_LIMIT)
+            .clamp(1, MAX_RECENT_ACTIVITIES_LIMIT);
+        self.activity_repository.get_recent_activities(limit)
+    }
+
     /// Creates a new activity
     async fn create_activity(&self, activity: NewActivity) -> Result<Activity> {
         let prepared = self.prepare_new_activity(activity).await?;
@@ -4686,6 +4950,7 @@ impl ActivityServiceTrait for ActivityService {
                     success: false,
                     error_message: Some("Account is required for all activities.".to_string()),
                 },
+                currency_mismatches: Vec::new(),
             });
         }
 
@@ -4698,11 +4963,15 @@ impl ActivityServiceTrait for ActivityService {
 
         let mut account_currencies: HashMap<String, String> =
             HashMap::with_capacity(unique_account_ids.len());
+        let mut account_timezones: HashMap<String, Tz> = HashMap::new();
 
         for account_id in &unique_account_ids {
             let account = self.account_service.get_account(account_id)?;
             let currency = resolve_currency(&[&account.currency, &base_ccy]);
             account_currencies.insert(account_id.clone(), currency);
+            if let Some(tz) = account.activity_timezone() {
+                account_timezones.insert(account_id.clone(), tz);
+            }
         }
 
         // ── 3. Normalize + convert each activity ─────────────────────────────
@@ -4835,6 +5104,7 @@ impl ActivityServiceTrait for ActivityService {
                     success: false,
                     error_message: Some("Validation errors found in activities.".to_string()),
                 },
+                currency_mismatches: Vec::new(),
             });
         }
 
@@ -4859,6 +5129,9 @@ impl ActivityServiceTrait for ActivityService {
             );
             Self::normalize_new_activity_economic_signs(new_act);
             new_act.idempotency_key = Self::build_import_idempotency_key(src, &new_act.account_id);
+            if let Some(tz) = account_timezones.get(&new_act.account_id) {
+                normalize_new_activity_date(new_act, *tz);
+            }
         }
 
         // ── 5. Partition hard duplicates before insert ───────────────────────
@@ -5000,6 +5273,30 @@ impl ActivityServiceTrait for ActivityService {
                 .await?;
         }
 
+        // ── 6.5 Apply the currency mismatch policy ───────────────────────────
+        let mut currency_mismatches = Vec::new();
+        for (new_act, (source_idx, src)) in insertable_new_activities
+            .iter_mut()
+            .zip(insertable_sources.iter())
+        {
+            let account_id = src.account_id.as_deref().unwrap_or("");
+            let account_currency = account_currencies
+                .get(account_id)
+                .map(String::as_str)
+                .unwrap_or(&base_ccy);
+            if let Some(mismatch) = self.resolve_currency_mismatch(new_act, account_currency) {
+                if let Some(reason) = &mismatch.reason {
+                    if let Some((_, import_activity)) = import_activities_indexed
+                        .iter_mut()
+                        .find(|(idx, _)| idx == source_idx)
+                    {
+                        Self::add_activity_warning(import_activity, "currency", reason);
+                    }
+                }
+                currency_mismatches.push(mismatch);
+            }
+        }
+
         // ── 7. Create ImportRun ───────────────────────────────────────────────
         let first_account_id = import_activities_indexed
             .first()
@@ -5119,6 +5416,7 @@ impl ActivityServiceTrait for ActivityService {
                 success: true,
                 error_message: None,
             },
+            currency_mismatches,
         })
     }
 
@@ -5405,6 +5703,47 @@ impl ActivityServiceTrait for ActivityService {
         Ok(result)
     }
 
+    async fn upsert_activities_bulk_removing_stale(
+        &self,
+        account_id: &str,
+        activities: Vec<ActivityUpsert>,
+        select_stale: StaleActivitySelector,
+    ) -> Result<ActivityReconcileResult> {
+        let earliest_upserted_at_utc = Self::earliest_upsert_activity_at_utc(&activities);
+        let mut asset_ids: HashSet<String> = activities
+            .iter()
+            .filter_map(|a| a.asset_id.clone())
+            .collect();
+        let mut currencies: HashSet<String> =
+            activities.iter().map(|a| a.currency.clone()).collect();
+
+        let result = self
+            .activity_repository
+            .bulk_upsert_removing_stale(account_id, activities, select_stale)
+            .await?;
+
+        if result.upserted.upserted > 0 || !result.removed.is_empty() {
+            let mut account_ids: HashSet<String> = HashSet::from([account_id.to_string()]);
+            for removed in &result.removed {
+                account_ids.insert(removed.account_id.clone());
+                asset_ids.extend(removed.asset_id.clone());
+                currencies.insert(removed.currency.clone());
+            }
+            let earliest_activity_at_utc = earliest_upserted_at_utc
+                .into_iter()
+                .chain(Self::earliest_activity_at_utc(&result.removed))
+                .min();
+            self.emit_activities_changed(
+                account_ids.into_iter().collect(),
+                asset_ids.into_iter().collect(),
+                currencies.into_iter().collect(),
+                earliest_activity_at_utc,
+            );
+        }
+
+        Ok(result)
+    }
+
     async fn prepare_activities_for_save(
         &self,
         activities: Vec<NewActivity>,
@@ -5447,10 +5786,22 @@ impl ActivityService {
             return Ok(PrepareActivitiesResult::default());
         }
 
-        let activities: Vec<NewActivity> = activities
+        let activity_timezone = account
+            .activity_timezone()
+            .filter(|_| mode.normalizes_activity_dates());
+        let mut activities: Vec<NewActivity> = activities
             .into_iter()
             .map(Self::normalize_activity_for_preparation)
+            .map(|mut activity| {
+                if let Some(tz) = activity_timezone {
+                    normalize_new_activity_date(&mut activity, tz);
+                }
+                activity
+            })
             .collect();
+        if mode.normalizes_symbols() {
+            self.normalize_activity_symbols(&mut activities);
+        }
 
         let mut result = PrepareActivitiesResult::default();
         let base_ccy = self.account_service.get_base_currency().unwrap_or_default();
@@ -5808,6 +6159,17 @@ impl ActivityService {
                 }
             }
 
+            if !matches!(mode, PreparationMode::Save) {
+                if let Some(mismatch) =
+                    self.resolve_currency_mismatch(&mut activity, &account_currency)
+                {
+                    if mode.is_sync() && mismatch.reason.is_some() {
+                        activity.status = Some(ActivityStatus::Draft);
+                    }
+                    result.currency_mismatches.push(mismatch);
+                }
+            }
+
             let explicit_idempotency_key = activity
                 .idempotency_key
                 .as_deref()
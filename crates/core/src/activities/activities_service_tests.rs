@@ -1675,6 +1675,15 @@ mod tests {
             unimplemented!()
         }
 
+        async fn bulk_upsert_removing_stale(
+            &self,
+            _account_id: &str,
+            _activities: Vec<crate::activities::ActivityUpsert>,
+            _select_stale: crate::activities::StaleActivitySelector,
+        ) -> Result<crate::activities::ActivityReconcileResult> {
+            unimplemented!()
+        }
+
         async fn reassign_asset(&self, _old_asset_id: &str, _new_asset_id: &str) -> Result<u32> {
             Ok(0)
         }
@@ -3867,6 +3876,79 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_sync_prepare_normalizes_late_evening_date_to_account_timezone() {
+        let account_service = Arc::new(MockAccountService::new());
+        let asset_service = Arc::new(MockAssetService::new());
+        let fx_service = Arc::new(MockFxService::new());
+        let activity_repository = Arc::new(MockActivityRepository::new());
+
+        let mut account = create_test_account("acc-1", "USD");
+        account.meta = Some(r#"{"activityTimezone":"America/Toronto"}"#.to_string());
+        account_service.add_account(account.clone());
+        asset_service.add_asset(create_test_asset("AAPL", "USD"));
+
+        let activity_service = ActivityService::new(
+            activity_repository,
+            account_service,
+            asset_service,
+            fx_service,
+            Arc::new(MockQuoteService),
+        );
+
+        let result = activity_service
+            .prepare_activities_for_sync(
+                vec![NewActivity {
+                    id: Some("late-buy".to_string()),
+                    account_id: "acc-1".to_string(),
+                    asset: Some(AssetResolutionInput {
+                        id: Some("AAPL".to_string()),
+                        ..Default::default()
+                    }),
+                    activity_type: "BUY".to_string(),
+                    subtype: None,
+                    activity_date: "2024-01-15T23:30:00".to_string(),
+                    quantity: Some(dec!(1)),
+                    unit_price: Some(dec!(100)),
+                    currency: "USD".to_string(),
+                    fee: Some(dec!(0)),
+                    tax: None,
+                    amount: Some(dec!(100)),
+                    status: None,
+                    notes: None,
+                    fx_rate: None,
+                    metadata: None,
+                    needs_review: None,
+                    source_system: Some("SNAPTRADE".to_string()),
+                    source_record_id: Some("late-buy".to_string()),
+                    source_group_id: None,
+                    idempotency_key: None,
+                    import_run_id: None,
+                }],
+                &account,
+            )
+            .await
+            .expect("sync preparation should succeed");
+
+        assert!(result.errors.is_empty());
+        let prepared = &result.prepared[0].activity;
+        // 23:30 EST on Jan 15 is 04:30 UTC on Jan 16, and still Jan 15 in Toronto.
+        assert_eq!(prepared.activity_date, "2024-01-16T04:30:00+00:00");
+        let instant = DateTime::parse_from_rfc3339(&prepared.activity_date)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            crate::utils::time_utils::activity_date_in_tz(instant, chrono_tz::America::Toronto),
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+        let metadata: serde_json::Value =
+            serde_json::from_str(prepared.metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            metadata[crate::activities::ORIGINAL_ACTIVITY_DATE_METADATA_KEY],
+            "2024-01-15T23:30:00"
+        );
+    }
+
     #[tokio::test]
     async fn test_import_prepare_normalizes_minor_currency_tax() {
         let account_service = Arc::new(MockAccountService::new());
@@ -6099,6 +6181,91 @@ mod tests {
         assert_eq!(checked.quote_ccy.as_deref(), Some("USD"));
     }
 
+    #[tokio::test]
+    async fn test_check_import_normalizes_share_classes_and_flags_ambiguous_symbols() {
+        let account_service = Arc::new(MockAccountService::new());
+        let asset_service = Arc::new(MockAssetService::new());
+        account_service.add_account(create_test_account("acc-1", "USD"));
+        for (id, mic) in [("shop-tsx", "XTSE"), ("shop-nyse", "XNYS")] {
+            asset_service.add_asset(create_test_asset_with_instrument(
+                id,
+                "SHOP",
+                Some(mic),
+                Some(InstrumentType::Equity),
+                "USD",
+            ));
+        }
+        let activity_service = ActivityService::new(
+            Arc::new(MockActivityRepository::new()),
+            account_service,
+            asset_service,
+            Arc::new(MockFxService::new()),
+            Arc::new(MockQuoteService),
+        )
+        .with_symbol_normalization(Arc::new(crate::settings::MemorySettingsService::default()));
+
+        let import = |symbol: &str, line_number| ActivityImport {
+            id: None,
+            date: "2024-01-15".to_string(),
+            symbol: symbol.to_string(),
+            activity_type: "BUY".to_string(),
+            quantity: Some(dec!(1)),
+            unit_price: Some(dec!(100)),
+            currency: "USD".to_string(),
+            fee: Some(dec!(0)),
+            tax: None,
+            amount: Some(dec!(100)),
+            comment: None,
+            account_id: Some("acc-1".to_string()),
+            account_name: None,
+            symbol_name: None,
+            exchange_mic: None,
+            quote_ccy: None,
+            instrument_type: None,
+            quote_mode: None,
+            provider_id: None,
+            provider_symbol: None,
+            errors: None,
+            warnings: None,
+            duplicate_of_id: None,
+            duplicate_of_line_number: None,
+            is_draft: false,
+            is_valid: true,
+            line_number: Some(line_number),
+            fx_rate: None,
+            subtype: None,
+            asset_id: None,
+            isin: None,
+            force_import: false,
+            is_external: None,
+        };
+
+        let result = activity_service
+            .check_activities_import(vec![
+                import("brk-b", 1),
+                import("BRK.B", 2),
+                import("SHOP", 3),
+            ])
+            .await
+            .expect("import check should succeed");
+
+        // Neither spelling is in the catalog; both still resolve as the same listing.
+        assert_eq!(result[0].symbol, "BRK.B");
+        assert_eq!(result[0].exchange_mic, result[1].exchange_mic);
+        assert!(result[0]
+            .warnings
+            .as_ref()
+            .and_then(|warnings| warnings.get("symbol"))
+            .is_none());
+
+        let shop_warnings = result[2]
+            .warnings
+            .as_ref()
+            .and_then(|warnings| warnings.get("symbol"))
+            .expect("ambiguous symbol is flagged");
+        assert!(shop_warnings[0].contains("matches several listings"));
+    }
+
     #[tokio::test]
     async fn test_check_import_preserves_explicit_requested_quote_ccy() {
         let account_service = Arc::new(MockAccountService::new());
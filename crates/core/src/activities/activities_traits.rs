@@ -170,6 +170,70 @@ pub trait ActivityRepositoryTrait: Send + Sync {
             instrument_type_filter,
         )
     }
+    /// Keyset page of activities after `cursor`, newest first by `(date, id)`. The default
+    /// loads every match; storage backends override it with a bounded query.
+    #[allow(clippy::too_many_arguments)]
+    fn search_activities_page(
+        &self,
+        cursor: Option<&ActivityCursor>,
+        limit: i64,
+        account_id_filter: Option<Vec<String>>,
+        activity_type_filter: Option<Vec<String>>,
+        asset_id_keyword: Option<String>,
+        needs_review_filter: Option<bool>,
+        date_from_utc: Option<DateTime<Utc>>,
+        date_to_utc_exclusive: Option<DateTime<Utc>>,
+        instrument_type_filter: Option<Vec<String>>,
+    ) -> Result<ActivityPage> {
+        let mut rows = self
+            .search_activities_in_utc_range(
+                0,
+                i64::MAX,
+                account_id_filter,
+                activity_type_filter,
+                asset_id_keyword,
+                None,
+                needs_review_filter,
+                date_from_utc,
+                date_to_utc_exclusive,
+                instrument_type_filter,
+            )?
+            .data;
+        rows.retain(|activity| cursor.is_none_or(|cursor| cursor.precedes(activity)));
+        rows.sort_by(|a, b| (&b.date, &b.id).cmp(&(&a.date, &a.id)));
+        rows.truncate(
+            usize::try_from(limit.max(0))
+                .unwrap_or(usize::MAX)
+                .saturating_add(1),
+        );
+        Ok(ActivityPage::from_rows(rows, limit))
+    }
+    /// Most recent activities across all (non-archived) accounts, newest first.
+    fn get_recent_activities(&self, limit: i64) -> Result<Vec<RecentActivity>> {
+        let response = self.search_activities(
+            0,
+            limit,
+            None,
+            None,
+            None,
+            Some(Sort {
+                id: "date".to_string(),
+                desc: true,
+            }),
+            None,
+            None,
+            None,
+            None,
+        )?;
+        Ok(response
+            .data
+            .into_iter()
+            .map(|activity| RecentActivity {
+                activity,
+                broker_name: None,
+            })
+            .collect())
+    }
     async fn create_activity(&self, new_activity: NewActivity) -> Result<Activity>;
     async fn update_activity(&self, activity_update: ActivityUpdate) -> Result<Activity>;
     async fn delete_activity(&self, activity_id: String) -> Result<Activity>;
@@ -276,6 +340,17 @@ pub trait ActivityRepositoryTrait: Send + Sync {
         activities: Vec<super::ActivityUpsert>,
     ) -> Result<super::BulkUpsertResult>;
 
+    /// Upserts `activities` like [`bulk_upsert`](Self::bulk_upsert) and deletes the stale
+    /// activities of `account_id` in the same transaction. `select_stale` sees the account's
+    /// activities as stored after the upsert, so an edit committed before the transaction is
+    /// never deleted.
+    async fn bulk_upsert_removing_stale(
+        &self,
+        account_id: &str,
+        activities: Vec<super::ActivityUpsert>,
+        select_stale: StaleActivitySelector,
+    ) -> Result<ActivityReconcileResult>;
+
     /// Reassigns all activities from one asset to another.
     /// Used when merging UNKNOWN assets into resolved ones.
     /// Returns the number of activities updated.
@@ -343,6 +418,25 @@ pub trait ActivityServiceTrait: Send + Sync {
             instrument_type_filter,
         )
     }
+    /// Keyset page of activities, newest first. `cursor` is the `next_cursor` of the previous
+    /// page; `limit` defaults to [`DEFAULT_ACTIVITY_PAGE_SIZE`] and is capped at
+    /// [`MAX_ACTIVITY_PAGE_SIZE`].
+    #[allow(clippy::too_many_arguments)]
+    fn search_activities_page(
+        &self,
+        cursor: Option<String>,
+        limit: Option<i64>,
+        account_id_filter: Option<Vec<String>>,
+        activity_type_filter: Option<Vec<String>>,
+        asset_id_keyword: Option<String>,
+        needs_review_filter: Option<bool>,
+        date_from_utc: Option<DateTime<Utc>>,
+        date_to_utc_exclusive: Option<DateTime<Utc>>,
+        instrument_type_filter: Option<Vec<String>>,
+    ) -> Result<ActivityPage>;
+    /// Most recent activities across all accounts, newest first. `limit` defaults to
+    /// [`DEFAULT_RECENT_ACTIVITIES_LIMIT`] and is capped at [`MAX_RECENT_ACTIVITIES_LIMIT`].
+    fn get_recent_activities(&self, limit: Option<i64>) -> Result<Vec<RecentActivity>>;
     fn get_first_activity_date(
         &self,
         account_ids: Option<&[String]>,
@@ -434,6 +528,16 @@ pub trait ActivityServiceTrait: Send + Sync {
         activities: Vec<super::ActivityUpsert>,
     ) -> Result<super::BulkUpsertResult>;
 
+    /// Upserts an account's synced activities and deletes its stale ones in one transaction.
+    /// See [`ActivityRepositoryTrait::bulk_upsert_removing_stale`]. Emits a single aggregated
+    /// ActivitiesChanged event covering both.
+    async fn upsert_activities_bulk_removing_stale(
+        &self,
+        account_id: &str,
+        activities: Vec<super::ActivityUpsert>,
+        select_stale: StaleActivitySelector,
+    ) -> Result<ActivityReconcileResult>;
+
     /// Prepares activities for normal save/create flows.
     /// Uses only payload metadata (no live symbol/provider resolution).
     ///
@@ -0,0 +1,171 @@
+//! Activity date normalization.
+//!
+//! Brokers and CSV files often send trade dates without a usable offset: a bare
+//! `2024-03-01`, a naive `2024-03-01T21:30:00`, or a bare date encoded as UTC midnight.
+//! Stored as-is these land on the previous day for anyone west of UTC. When an account has
+//! an activity timezone, such dates are read as wall-clock times in that zone and stored as
+//! the matching UTC instant; the value the source sent is kept in the activity metadata.
+//! Timestamps that carry a real time of day and offset are already unambiguous and are left
+//! untouched.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::{Map, Value};
+
+use super::NewActivity;
+
+/// Metadata key holding the activity date as received, before normalization.
+pub const ORIGINAL_ACTIVITY_DATE_METADATA_KEY: &str = "originalActivityDate";
+
+const NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Reads an ambiguous activity date as local time in `tz`.
+///
+/// Returns `None` when the value already names an instant (or cannot be parsed), in which
+/// case it should be stored unchanged.
+pub fn normalize_activity_date(raw: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        // A UTC-midnight timestamp is how most brokers encode a bare trade date.
+        if dt.offset().local_minus_utc() != 0 || dt.time() != NaiveTime::MIN {
+            return None;
+        }
+        return local_to_utc(dt.date_naive().and_time(NaiveTime::MIN), tz);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        return local_to_utc(date.and_time(NaiveTime::MIN), tz);
+    }
+
+    NAIVE_DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        .and_then(|local| local_to_utc(local, tz))
+}
+
+/// Rewrites `activity.activity_date` as an RFC 3339 UTC instant when it is ambiguous,
+/// recording the original value in the metadata. Returns whether the date changed.
+pub fn normalize_new_activity_date(activity: &mut NewActivity, tz: Tz) -> bool {
+    let Some(instant) = normalize_activity_date(&activity.activity_date, tz) else {
+        return false;
+    };
+    let normalized = instant.to_rfc3339();
+    if normalized == activity.activity_date {
+        return false;
+    }
+
+    let original = std::mem::replace(&mut activity.activity_date, normalized);
+    let mut metadata = activity
+        .metadata
+        .as_deref()
+        .and_then(|raw| serde_json::from_str::<Map<String, Value>>(raw).ok())
+        .unwrap_or_default();
+    // Keep the first value received if the activity is normalized again on re-sync.
+    metadata
+        .entry(ORIGINAL_ACTIVITY_DATE_METADATA_KEY)
+        .or_insert(Value::String(original));
+    activity.metadata = Some(Value::Object(metadata).to_string());
+    true
+}
+
+fn local_to_utc(local: NaiveDateTime, tz: Tz) -> Option<DateTime<Utc>> {
+    // Ambiguous (DST fold) times take the first occurrence; times in a DST gap move past it.
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::time_utils::activity_date_in_tz;
+
+    fn new_activity(activity_date: &str) -> NewActivity {
+        NewActivity {
+            id: None,
+            account_id: "acc-1".to_string(),
+            asset: None,
+            activity_type: "DEPOSIT".to_string(),
+            subtype: None,
+            activity_date: activity_date.to_string(),
+            quantity: None,
+            unit_price: None,
+            currency: "EUR".to_string(),
+            fee: None,
+            tax: None,
+            amount: None,
+            status: None,
+            notes: None,
+            fx_rate: None,
+            metadata: None,
+            needs_review: None,
+            source_system: None,
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn late_evening_trade_stays_on_its_local_day() {
+        let tz: Tz = "America/New_York".parse().unwrap();
+
+        // 23:30 in New York is already the next day in UTC.
+        let instant = normalize_activity_date("2024-03-01T23:30:00", tz).unwrap();
+        assert_eq!(instant.to_rfc3339(), "2024-03-02T04:30:00+00:00");
+        assert_eq!(activity_date_in_tz(instant, tz), date("2024-03-01"));
+
+        // A bare date stored as UTC midnight would read as Feb 29 in New York.
+        for raw in ["2024-03-01", "2024-03-01T00:00:00Z"] {
+            let instant = normalize_activity_date(raw, tz).unwrap();
+            assert_eq!(activity_date_in_tz(instant, tz), date("2024-03-01"));
+        }
+    }
+
+    #[test]
+    fn timestamps_with_a_real_offset_are_left_alone() {
+        let tz: Tz = "Asia/Tokyo".parse().unwrap();
+
+        assert_eq!(
+            normalize_activity_date("2024-03-01T23:30:00-05:00", tz),
+            None
+        );
+        assert_eq!(normalize_activity_date("2024-03-01T15:00:00Z", tz), None);
+        assert_eq!(normalize_activity_date("not a date", tz), None);
+    }
+
+    #[test]
+    fn normalized_activity_keeps_the_original_date_in_metadata() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let mut activity = new_activity("2024-06-30 23:45:00");
+        activity.metadata = Some(r#"{"flow":{"is_external":true}}"#.to_string());
+
+        assert!(normalize_new_activity_date(&mut activity, tz));
+        assert_eq!(activity.activity_date, "2024-06-30T21:45:00+00:00");
+
+        let metadata: Value = serde_json::from_str(activity.metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            metadata[ORIGINAL_ACTIVITY_DATE_METADATA_KEY],
+            "2024-06-30 23:45:00"
+        );
+        assert_eq!(metadata["flow"]["is_external"], true);
+
+        // Already an unambiguous instant: nothing to do.
+        assert!(!normalize_new_activity_date(&mut activity, tz));
+    }
+}
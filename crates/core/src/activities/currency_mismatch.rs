@@ -0,0 +1,246 @@
+//! Detection of activities whose currency differs from their account currency.
+//!
+//! Without an FX rate such activities are valued with whatever rate is current when the
+//! portfolio is calculated, which silently skews cost basis. When a policy is configured,
+//! sync and import either record the historical rate on the activity (keeping the original
+//! currency and amounts) or flag it for review. A missing rate always flags.
+
+use chrono::{DateTime, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::NewActivity;
+
+/// Settings key holding the desktop app's policy, `convert` or `flag`. Unset disables it.
+pub const CURRENCY_MISMATCH_POLICY_SETTING_KEY: &str = "activity_currency_mismatch_policy";
+
+/// What to do with an activity whose currency differs from its account currency.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CurrencyMismatchPolicy {
+    /// Store the rate to the account currency for the activity date.
+    Convert,
+    /// Mark the activity as needing review.
+    Flag,
+}
+
+impl CurrencyMismatchPolicy {
+    /// Parses `convert` or `flag`, case-insensitively.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "convert" => Some(Self::Convert),
+            "flag" => Some(Self::Flag),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CurrencyMismatchResolution {
+    Converted,
+    Flagged,
+}
+
+/// A currency-mismatched activity and how it was handled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyMismatch {
+    pub activity_id: Option<String>,
+    pub account_id: String,
+    pub activity_date: String,
+    pub activity_currency: String,
+    pub account_currency: String,
+    pub resolution: CurrencyMismatchResolution,
+    /// Rate from the activity currency to the account currency, when converted.
+    pub fx_rate: Option<Decimal>,
+    /// Why the activity was flagged.
+    pub reason: Option<String>,
+}
+
+/// Applies `policy` to `activity` when its currency differs from `account_currency` and it
+/// carries no FX rate yet. `rate_for_date` returns the `from -> to` rate on a date, if known.
+///
+/// Returns `None` when the activity needs no handling.
+pub fn apply_currency_mismatch_policy(
+    activity: &mut NewActivity,
+    account_currency: &str,
+    policy: CurrencyMismatchPolicy,
+    rate_for_date: impl FnOnce(&str, &str, NaiveDate) -> Option<Decimal>,
+) -> Option<CurrencyMismatch> {
+    let activity_currency = activity.currency.trim();
+    if activity_currency.is_empty()
+        || account_currency.trim().is_empty()
+        || activity_currency.eq_ignore_ascii_case(account_currency.trim())
+        || activity.fx_rate.is_some_and(|rate| rate > Decimal::ZERO)
+    {
+        return None;
+    }
+
+    let mut mismatch = CurrencyMismatch {
+        activity_id: activity.id.clone(),
+        account_id: activity.account_id.clone(),
+        activity_date: activity.activity_date.clone(),
+        activity_currency: activity_currency.to_string(),
+        account_currency: account_currency.trim().to_string(),
+        resolution: CurrencyMismatchResolution::Flagged,
+        fx_rate: None,
+        reason: None,
+    };
+
+    let reason = match policy {
+        CurrencyMismatchPolicy::Flag => format!(
+            "Activity currency {} differs from account currency {}",
+            mismatch.activity_currency, mismatch.account_currency
+        ),
+        CurrencyMismatchPolicy::Convert => match parse_activity_date(&activity.activity_date) {
+            None => format!("Invalid activity date '{}'", activity.activity_date),
+            Some(date) => match rate_for_date(
+                &mismatch.activity_currency,
+                &mismatch.account_currency,
+                date,
+            )
+            .filter(|rate| *rate > Decimal::ZERO)
+            {
+                Some(rate) => {
+                    activity.fx_rate = Some(rate);
+                    mismatch.resolution = CurrencyMismatchResolution::Converted;
+                    mismatch.fx_rate = Some(rate);
+                    return Some(mismatch);
+                }
+                None => format!(
+                    "No {}/{} exchange rate for {}",
+                    mismatch.activity_currency, mismatch.account_currency, date
+                ),
+            },
+        },
+    };
+
+    activity.needs_review = Some(true);
+    mismatch.reason = Some(reason);
+    Some(mismatch)
+}
+
+fn parse_activity_date(value: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.date_naive())
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn eur_activity() -> NewActivity {
+        NewActivity {
+            id: Some("act-1".to_string()),
+            account_id: "acc-usd".to_string(),
+            asset: None,
+            activity_type: "BUY".to_string(),
+            subtype: None,
+            activity_date: "2024-03-15T00:00:00Z".to_string(),
+            quantity: Some(dec!(10)),
+            unit_price: Some(dec!(50)),
+            currency: "EUR".to_string(),
+            fee: None,
+            tax: None,
+            amount: None,
+            status: None,
+            notes: None,
+            fx_rate: None,
+            metadata: None,
+            needs_review: None,
+            source_system: None,
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+        }
+    }
+
+    #[test]
+    fn convert_policy_stores_rate_and_keeps_original_amounts() {
+        let mut activity = eur_activity();
+
+        let mismatch = apply_currency_mismatch_policy(
+            &mut activity,
+            "USD",
+            CurrencyMismatchPolicy::Convert,
+            |from, to, date| {
+                assert_eq!((from, to), ("EUR", "USD"));
+                assert_eq!(date, NaiveDate::from_ymd_opt(2024, 3, 15).unwrap());
+                Some(dec!(1.09))
+            },
+        )
+        .unwrap();
+
+        assert_eq!(mismatch.resolution, CurrencyMismatchResolution::Converted);
+        assert_eq!(mismatch.fx_rate, Some(dec!(1.09)));
+        assert_eq!(activity.fx_rate, Some(dec!(1.09)));
+        assert_eq!(activity.currency, "EUR");
+        assert_eq!(activity.unit_price, Some(dec!(50)));
+        assert_eq!(activity.needs_review, None);
+    }
+
+    #[test]
+    fn missing_rate_flags_even_under_convert_policy() {
+        let mut activity = eur_activity();
+
+        let mismatch = apply_currency_mismatch_policy(
+            &mut activity,
+            "USD",
+            CurrencyMismatchPolicy::Convert,
+            |_, _, _| None,
+        )
+        .unwrap();
+
+        assert_eq!(mismatch.resolution, CurrencyMismatchResolution::Flagged);
+        assert!(mismatch
+            .reason
+            .unwrap()
+            .contains("No EUR/USD exchange rate"));
+        assert_eq!(activity.fx_rate, None);
+        assert_eq!(activity.needs_review, Some(true));
+    }
+
+    #[test]
+    fn flag_policy_flags_without_looking_up_a_rate() {
+        let mut activity = eur_activity();
+
+        let mismatch = apply_currency_mismatch_policy(
+            &mut activity,
+            "USD",
+            CurrencyMismatchPolicy::Flag,
+            |_, _, _| panic!("rate should not be looked up"),
+        )
+        .unwrap();
+
+        assert_eq!(mismatch.resolution, CurrencyMismatchResolution::Flagged);
+        assert_eq!(activity.needs_review, Some(true));
+    }
+
+    #[test]
+    fn matching_currency_or_existing_rate_is_left_alone() {
+        let mut same = eur_activity();
+        assert!(apply_currency_mismatch_policy(
+            &mut same,
+            "eur",
+            CurrencyMismatchPolicy::Flag,
+            |_, _, _| None
+        )
+        .is_none());
+
+        let mut with_rate = eur_activity();
+        with_rate.fx_rate = Some(dec!(1.1));
+        assert!(apply_currency_mismatch_policy(
+            &mut with_rate,
+            "USD",
+            CurrencyMismatchPolicy::Flag,
+            |_, _, _| None
+        )
+        .is_none());
+        assert_eq!(with_rate.needs_review, None);
+    }
+}
@@ -5,8 +5,10 @@ mod activities_errors;
 mod activities_model;
 mod activities_service;
 mod activities_traits;
+mod activity_dates;
 mod compiler;
 mod csv_parser;
+mod currency_mismatch;
 mod idempotency;
 mod import_run_model;
 mod transfer_pairs;
@@ -23,20 +25,29 @@ pub use activities_model::import_type;
 pub use activities_model::{
     into_field_mapping_values, normalize_context_kind_value, parse_decimal_string_tolerant,
     Activity, ActivityBulkIdentifierMapping, ActivityBulkMutationError,
-    ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityDetails, ActivityImport,
-    ActivitySearchResponse, ActivitySearchResponseMeta, ActivityStatus, ActivityType,
-    ActivityUpdate, ActivityUpsert, AssetResolutionInput, BrokerActivityProfileConfig,
-    BrokerProfileScope, BrokerSyncProfileData, BulkUpsertResult, FieldMappingValue,
-    ImportActivitiesResult, ImportActivitiesSummary, ImportAssetCandidate, ImportAssetPreviewItem,
-    ImportAssetPreviewStatus, ImportMapping, ImportMappingData, ImportTemplate, ImportTemplateData,
-    ImportTemplateScope, IncomeData, InternalTransferPairRequest, InternalTransferPairResponse,
-    NewActivity, PrepareActivitiesResult, SaveBrokerSyncProfileRulesRequest, Sort, TemplateKind,
-    TransferMatchCandidate, TransferMatchCandidateRequest,
+    ActivityBulkMutationRequest, ActivityBulkMutationResult, ActivityCursor, ActivityDetails,
+    ActivityImport, ActivityPage, ActivityReconcileResult, ActivitySearchResponse,
+    ActivitySearchResponseMeta, ActivityStatus, ActivityType, ActivityUpdate, ActivityUpsert,
+    AssetResolutionInput, BrokerActivityProfileConfig, BrokerProfileScope, BrokerSyncProfileData,
+    BulkUpsertResult, FieldMappingValue, ImportActivitiesResult, ImportActivitiesSummary,
+    ImportAssetCandidate, ImportAssetPreviewItem, ImportAssetPreviewStatus, ImportMapping,
+    ImportMappingData, ImportTemplate, ImportTemplateData, ImportTemplateScope, IncomeData,
+    InternalTransferPairRequest, InternalTransferPairResponse, NewActivity,
+    PrepareActivitiesResult, RecentActivity, SaveBrokerSyncProfileRulesRequest, Sort,
+    StaleActivityPlan, StaleActivitySelector, TemplateKind, TransferMatchCandidate,
+    TransferMatchCandidateRequest,
 };
 pub use activities_service::ActivityService;
 pub use activities_traits::{ActivityRepositoryTrait, ActivityServiceTrait};
+pub use activity_dates::{
+    normalize_activity_date, normalize_new_activity_date, ORIGINAL_ACTIVITY_DATE_METADATA_KEY,
+};
 pub use compiler::{ActivityCompiler, DefaultActivityCompiler};
 pub use csv_parser::{parse_csv, ParseConfig, ParseError, ParsedCsvResult};
+pub use currency_mismatch::{
+    apply_currency_mismatch_policy, CurrencyMismatch, CurrencyMismatchPolicy,
+    CurrencyMismatchResolution, CURRENCY_MISMATCH_POLICY_SETTING_KEY,
+};
 pub use idempotency::{
     compute_activity_idempotency_key, compute_idempotency_key, generate_manual_idempotency_key,
 };
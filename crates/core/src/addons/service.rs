@@ -6,6 +6,7 @@ use async_trait::async_trait;
 
 use super::addon_traits::AddonServiceTrait;
 use super::models::*;
+use crate::utils::http_retry::HttpRetryPolicy;
 
 // Constants
 pub const ADDON_STORE_API_BASE_URL: &str = "https://wealthfolio.app/api/addons";
@@ -41,6 +42,17 @@ fn create_request_with_headers(
     request
 }
 
+/// Sends a store GET, retrying transient failures (see [`HttpRetryPolicy`]).
+async fn send_get_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    instance_id: Option<&str>,
+) -> reqwest::Result<reqwest::Response> {
+    let request =
+        create_request_with_headers(client, reqwest::Method::GET, url, instance_id).build()?;
+    HttpRetryPolicy::from_env().execute(client, request).await
+}
+
 /// Helper function to handle API response and parse JSON
 async fn handle_api_response<T>(response: reqwest::Response, operation: &str) -> Result<T, String>
 where
@@ -865,14 +877,12 @@ pub async fn check_addon_update_from_api(
     );
 
     let client = reqwest::Client::new();
-    let response =
-        create_request_with_headers(&client, reqwest::Method::GET, &api_url, instance_id)
-            .send()
-            .await
-            .map_err(|e| {
-                log::error!("Failed to fetch addon info from API: {}", e);
-                format!("Failed to fetch addon info from API: {}", e)
-            })?;
+    let response = send_get_with_retry(&client, &api_url, instance_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to fetch addon info from API: {}", e);
+            format!("Failed to fetch addon info from API: {}", e)
+        })?;
 
     handle_api_response(response, "Update check").await
 }
@@ -1252,14 +1262,12 @@ pub async fn fetch_addon_store_listings(
     let api_url = ADDON_STORE_API_BASE_URL.to_string();
 
     let client = reqwest::Client::new();
-    let response =
-        create_request_with_headers(&client, reqwest::Method::GET, &api_url, instance_id)
-            .send()
-            .await
-            .map_err(|e| {
-                log::error!("Failed to fetch addon store listings: {}", e);
-                format!("Failed to fetch addon store listings: {}", e)
-            })?;
+    let response = send_get_with_retry(&client, &api_url, instance_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to fetch addon store listings: {}", e);
+            format!("Failed to fetch addon store listings: {}", e)
+        })?;
 
     let status = response.status();
     if !status.is_success() {
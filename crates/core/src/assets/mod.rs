@@ -11,6 +11,7 @@ mod assets_service;
 mod assets_traits;
 mod auto_classification;
 mod classification_service;
+mod symbol_normalization;
 
 #[cfg(test)]
 mod assets_model_tests;
@@ -45,3 +46,7 @@ pub use auto_classification::{
 pub use classification_service::{
     AssetClassificationService, AssetClassifications, CategoryWithWeight,
 };
+pub use symbol_normalization::{
+    normalize_share_class_separator, symbol_variant_key, SymbolMatchStatus, SymbolNormalization,
+    SymbolNormalizationService, SymbolNormalizer, SymbolReference, SYMBOL_OVERRIDES_SETTING_KEY,
+};
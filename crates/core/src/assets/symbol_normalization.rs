@@ -0,0 +1,521 @@
+//! Normalizing raw symbols against the asset catalog.
+//!
+//! Brokers, CSV exports and providers spell the same listing differently: `BRK.B`, `BRK-B`,
+//! `brk/b`, `BRK-B.TO`. Left alone, each spelling becomes its own asset. Share-class separators
+//! are rewritten to the dotted form the catalog and the market data providers use, whether or not
+//! the listing is in the catalog yet, so two spellings in one import still become one asset.
+//! Symbols are then matched against the assets already in the catalog by variant key (uppercase,
+//! dotted share class, known exchange suffix turned into a MIC). Symbols that match nothing, or
+//! more than one listing, are flagged so the user can map them. Manual mappings are stored as
+//! JSON in the settings table and win over automatic matching.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use super::{
+    parse_crypto_pair_symbol, parse_symbol_with_exchange_suffix, Asset, AssetServiceTrait,
+    InstrumentType,
+};
+use crate::errors::{Error, Result, ValidationError};
+use crate::settings::SettingsServiceTrait;
+
+/// Settings key holding the manual symbol mappings JSON (raw symbol -> target symbol).
+pub const SYMBOL_OVERRIDES_SETTING_KEY: &str = "symbol_normalization_overrides";
+
+/// Serializes the read-modify-write of the stored mappings. Services are constructed per
+/// call, so the lock is process-wide rather than per instance.
+static OVERRIDES_WRITE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Separators brokers write in place of the dotted share class: `BRK-B`, `BRK/B`.
+const SHARE_CLASS_SEPARATORS: [char; 2] = ['-', '/'];
+
+/// Longest ticker a share-class suffix is recognized on.
+const MAX_SHARE_CLASS_TICKER_LEN: usize = 5;
+
+/// Rewrites a share-class separator to the dotted form: `brk-b` and `BRK/B` become `BRK.B`, and
+/// `BRK-B.TO` becomes `BRK.B.TO`. Only one letter after a ticker of at most five letters is a
+/// share class, and never when the dotted form would read as an exchange suffix: `ABC-L` stays
+/// as it is because `ABC.L` is a London listing. Anything else is only uppercased.
+pub fn normalize_share_class_separator(symbol: &str) -> String {
+    let upper = symbol.trim().to_uppercase();
+    let (base, _) = parse_symbol_with_exchange_suffix(&upper);
+    let suffix = &upper[base.len()..];
+    let Some((ticker, class)) = base.rsplit_once(SHARE_CLASS_SEPARATORS) else {
+        return upper;
+    };
+    let is_share_class = (1..=MAX_SHARE_CLASS_TICKER_LEN).contains(&ticker.len())
+        && ticker.chars().all(|c| c.is_ascii_alphabetic())
+        && class.len() == 1
+        && class.chars().all(|c| c.is_ascii_alphabetic());
+    if !is_share_class {
+        return upper;
+    }
+    let dotted = format!("{}.{}", ticker, class);
+    if parse_symbol_with_exchange_suffix(&dotted).1.is_some() {
+        return upper;
+    }
+    format!("{}{}", dotted, suffix)
+}
+
+/// Folds a raw symbol into `(base, mic)`: uppercased, share class written as `BRK.B`, and a
+/// known exchange suffix (`.TO`, `.L`) split off into its MIC.
+pub fn symbol_variant_key(raw: &str) -> (String, Option<&'static str>) {
+    let normalized = normalize_share_class_separator(raw);
+    let (base, mic) = parse_symbol_with_exchange_suffix(&normalized);
+    (base.to_string(), mic)
+}
+
+/// How a raw symbol was matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SymbolMatchStatus {
+    /// Matched exactly one listing in the catalog.
+    Matched,
+    /// Resolved through a manual mapping.
+    Overridden,
+    /// Matches several listings; needs a mapping or an exchange suffix.
+    Ambiguous,
+    /// Matches nothing in the catalog.
+    Unmatched,
+}
+
+/// A catalog listing a symbol can normalize to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolReference {
+    pub asset_id: String,
+    pub symbol: String,
+    pub exchange_mic: Option<String>,
+    pub name: Option<String>,
+    pub instrument_type: Option<InstrumentType>,
+}
+
+/// The outcome of normalizing one raw symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolNormalization {
+    pub raw_symbol: String,
+    pub status: SymbolMatchStatus,
+    /// Canonical symbol to store, when matched or mapped.
+    pub canonical_symbol: Option<String>,
+    pub exchange_mic: Option<String>,
+    pub asset_id: Option<String>,
+    pub instrument_type: Option<InstrumentType>,
+    /// Listings an ambiguous symbol could mean.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<SymbolReference>,
+}
+
+impl SymbolNormalization {
+    /// Whether the symbol needs a manual mapping before it can be trusted.
+    pub fn needs_mapping(&self) -> bool {
+        matches!(
+            self.status,
+            SymbolMatchStatus::Ambiguous | SymbolMatchStatus::Unmatched
+        )
+    }
+}
+
+/// Matches raw symbols against a fixed set of listings and manual mappings.
+pub struct SymbolNormalizer {
+    securities: HashMap<String, Vec<SymbolReference>>,
+    crypto: HashMap<String, SymbolReference>,
+    overrides: HashMap<String, String>,
+}
+
+impl SymbolNormalizer {
+    pub fn new(references: &[Asset], overrides: &BTreeMap<String, String>) -> Self {
+        let mut securities: HashMap<String, Vec<SymbolReference>> = HashMap::new();
+        let mut crypto = HashMap::new();
+        for asset in references {
+            let Some(symbol) = asset.instrument_symbol.as_deref() else {
+                continue;
+            };
+            let reference = SymbolReference {
+                asset_id: asset.id.clone(),
+                symbol: symbol.to_string(),
+                exchange_mic: asset.instrument_exchange_mic.clone(),
+                name: asset.name.clone(),
+                instrument_type: asset.instrument_type.clone(),
+            };
+            match asset.instrument_type {
+                Some(InstrumentType::Equity | InstrumentType::Option | InstrumentType::Metal) => {
+                    let (key, _) = symbol_variant_key(symbol);
+                    securities.entry(key).or_default().push(reference);
+                }
+                Some(InstrumentType::Crypto) => {
+                    crypto.insert(symbol.trim().to_uppercase(), reference);
+                }
+                _ => {}
+            }
+        }
+        Self {
+            securities,
+            crypto,
+            overrides: overrides
+                .iter()
+                .map(|(raw, target)| (raw.trim().to_uppercase(), target.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn normalize(&self, raw: &str) -> SymbolNormalization {
+        if let Some(target) = self.overrides.get(&raw.trim().to_uppercase()) {
+            let mut mapped = self.match_reference(raw, target);
+            if mapped.status != SymbolMatchStatus::Matched {
+                let (key, mic) = symbol_variant_key(target);
+                mapped.canonical_symbol = Some(key);
+                mapped.exchange_mic = mic.map(str::to_string);
+                mapped.asset_id = None;
+                mapped.instrument_type = None;
+            }
+            mapped.status = SymbolMatchStatus::Overridden;
+            mapped.candidates.clear();
+            return mapped;
+        }
+        self.match_reference(raw, raw)
+    }
+
+    fn match_reference(&self, raw: &str, symbol: &str) -> SymbolNormalization {
+        let (key, mic) = symbol_variant_key(symbol);
+        let mut candidates: Vec<&SymbolReference> = self
+            .securities
+            .get(&key)
+            .map(|refs| {
+                refs.iter()
+                    .filter(|r| mic.is_none() || r.exchange_mic.as_deref() == mic)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if candidates.is_empty() {
+            let crypto_base = parse_crypto_pair_symbol(&key)
+                .map(|(base, _)| base)
+                .unwrap_or_else(|| key.clone());
+            candidates.extend(self.crypto.get(&crypto_base));
+        }
+
+        let mut result = SymbolNormalization {
+            raw_symbol: raw.to_string(),
+            status: SymbolMatchStatus::Unmatched,
+            canonical_symbol: None,
+            exchange_mic: None,
+            asset_id: None,
+            instrument_type: None,
+            candidates: Vec::new(),
+        };
+        match candidates.as_slice() {
+            [] => {}
+            [only] => {
+                result.status = SymbolMatchStatus::Matched;
+                result.canonical_symbol = Some(only.symbol.clone());
+                result.exchange_mic = only.exchange_mic.clone();
+                result.asset_id = Some(only.asset_id.clone());
+                result.instrument_type = only.instrument_type.clone();
+            }
+            several => {
+                result.status = SymbolMatchStatus::Ambiguous;
+                result.candidates = several.iter().map(|r| (*r).clone()).collect();
+            }
+        }
+        result
+    }
+}
+
+pub struct SymbolNormalizationService {
+    asset_service: Arc<dyn AssetServiceTrait>,
+    settings_service: Arc<dyn SettingsServiceTrait>,
+}
+
+impl SymbolNormalizationService {
+    pub fn new(
+        asset_service: Arc<dyn AssetServiceTrait>,
+        settings_service: Arc<dyn SettingsServiceTrait>,
+    ) -> Self {
+        Self {
+            asset_service,
+            settings_service,
+        }
+    }
+
+    /// Manual mappings, keyed by uppercased raw symbol. A stored value that is not a valid map
+    /// is an error, so a later write never silently replaces mappings it could not read.
+    pub fn get_overrides(&self) -> Result<BTreeMap<String, String>> {
+        match self
+            .settings_service
+            .get_setting_value(SYMBOL_OVERRIDES_SETTING_KEY)?
+            .filter(|raw| !raw.trim().is_empty())
+        {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                Error::Unexpected(format!("Stored symbol mappings are invalid: {}", e))
+            }),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// Maps `raw_symbol` to `target_symbol`, or removes its mapping with `None`. Returns the
+    /// updated mappings.
+    pub async fn set_override(
+        &self,
+        raw_symbol: &str,
+        target_symbol: Option<&str>,
+    ) -> Result<BTreeMap<String, String>> {
+        let raw_key = raw_symbol.trim().to_uppercase();
+        if raw_key.is_empty() {
+            return Err(invalid("Symbol to map must not be empty"));
+        }
+        let _guard = OVERRIDES_WRITE_LOCK.lock().await;
+        let mut overrides = self.get_overrides()?;
+        match target_symbol.map(str::trim) {
+            Some("") => return Err(invalid("Mapped symbol must not be empty")),
+            Some(target) => {
+                overrides.insert(raw_key, target.to_uppercase());
+            }
+            None => {
+                overrides.remove(&raw_key);
+            }
+        }
+        let raw =
+            serde_json::to_string(&overrides).map_err(|e| Error::Unexpected(e.to_string()))?;
+        self.settings_service
+            .set_setting_value(SYMBOL_OVERRIDES_SETTING_KEY, &raw)
+            .await?;
+        Ok(overrides)
+    }
+
+    /// A normalizer over the current catalog and mappings.
+    pub fn normalizer(&self) -> Result<SymbolNormalizer> {
+        let assets = self.asset_service.get_assets()?;
+        Ok(SymbolNormalizer::new(&assets, &self.get_overrides()?))
+    }
+
+    pub fn normalize_symbols(&self, symbols: &[String]) -> Result<Vec<SymbolNormalization>> {
+        let normalizer = self.normalizer()?;
+        Ok(symbols.iter().map(|s| normalizer.normalize(s)).collect())
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activities::ActivityRepositoryTrait;
+    use crate::assets::{
+        AssetMetadata, AssetResolutionInput, AssetResolutionOutput, AssetSpec, EnsureAssetsResult,
+        NewAsset, UpdateAssetProfile,
+    };
+    use crate::settings::MemorySettingsService;
+
+    fn asset(id: &str, symbol: &str, mic: Option<&str>, instrument_type: InstrumentType) -> Asset {
+        Asset {
+            id: id.to_string(),
+            instrument_symbol: Some(symbol.to_string()),
+            instrument_exchange_mic: mic.map(str::to_string),
+            instrument_type: Some(instrument_type),
+            ..Default::default()
+        }
+    }
+
+    fn catalog() -> Vec<Asset> {
+        vec![
+            asset("brk", "BRK.B", Some("XNYS"), InstrumentType::Equity),
+            asset("shop-tsx", "SHOP", Some("XTSE"), InstrumentType::Equity),
+            asset("shop-nyse", "SHOP", Some("XNYS"), InstrumentType::Equity),
+            asset("btc", "BTC", None, InstrumentType::Crypto),
+        ]
+    }
+
+    #[test]
+    fn share_class_variants_normalize_to_one_listing() {
+        let normalizer = SymbolNormalizer::new(&catalog(), &BTreeMap::new());
+
+        for raw in ["BRK.B", "BRK-B", "brk/b", " brk-b "] {
+            let result = normalizer.normalize(raw);
+            assert_eq!(result.status, SymbolMatchStatus::Matched, "{raw}");
+            assert_eq!(result.canonical_symbol.as_deref(), Some("BRK.B"));
+            assert_eq!(result.exchange_mic.as_deref(), Some("XNYS"));
+            assert_eq!(result.asset_id.as_deref(), Some("brk"));
+        }
+        assert_eq!(
+            normalizer.normalize("BTC-USD").asset_id.as_deref(),
+            Some("btc")
+        );
+    }
+
+    #[test]
+    fn share_class_separators_are_normalized_without_a_catalog_match() {
+        assert_eq!(normalize_share_class_separator("brk-b"), "BRK.B");
+        assert_eq!(normalize_share_class_separator("BRK/B"), "BRK.B");
+        assert_eq!(normalize_share_class_separator("BRK-B.TO"), "BRK.B.TO");
+        assert_eq!(normalize_share_class_separator("BRK.B"), "BRK.B");
+
+        // Not share classes: crypto pairs, longer suffixes, digits, long tickers.
+        for raw in ["BTC-USD", "ABC-WS", "ABC-1", "A1-B", "ABCDEF-B", "BRK B"] {
+            assert_eq!(normalize_share_class_separator(raw), raw);
+        }
+        // The dotted form would read as a London listing, a different instrument.
+        assert_eq!(normalize_share_class_separator("ABC-L"), "ABC-L");
+        assert_ne!(symbol_variant_key("ABC-L"), symbol_variant_key("ABC.L"));
+    }
+
+    #[test]
+    fn unknown_and_ambiguous_symbols_are_flagged() {
+        let normalizer = SymbolNormalizer::new(&catalog(), &BTreeMap::new());
+
+        let unknown = normalizer.normalize("ZZZZ");
+        assert_eq!(unknown.status, SymbolMatchStatus::Unmatched);
+        assert!(unknown.needs_mapping());
+
+        let listed_twice = normalizer.normalize("SHOP");
+        assert_eq!(listed_twice.status, SymbolMatchStatus::Ambiguous);
+        assert_eq!(listed_twice.candidates.len(), 2);
+
+        // An exchange suffix picks one listing.
+        let tsx = normalizer.normalize("SHOP.TO");
+        assert_eq!(tsx.status, SymbolMatchStatus::Matched);
+        assert_eq!(tsx.asset_id.as_deref(), Some("shop-tsx"));
+    }
+
+    #[test]
+    fn manual_mappings_take_precedence() {
+        let overrides = BTreeMap::from([
+            ("BRKB".to_string(), "BRK.B".to_string()),
+            ("SHOP".to_string(), "SHOP.TO".to_string()),
+            ("OLDCO".to_string(), "NEWCO".to_string()),
+        ]);
+        let normalizer = SymbolNormalizer::new(&catalog(), &overrides);
+
+        let brk = normalizer.normalize("brkb");
+        assert_eq!(brk.status, SymbolMatchStatus::Overridden);
+        assert_eq!(brk.asset_id.as_deref(), Some("brk"));
+
+        let shop = normalizer.normalize("SHOP");
+        assert_eq!(shop.status, SymbolMatchStatus::Overridden);
+        assert_eq!(shop.asset_id.as_deref(), Some("shop-tsx"));
+        assert!(shop.candidates.is_empty());
+
+        // A mapping to a symbol not yet in the catalog still rewrites it.
+        let renamed = normalizer.normalize("OLDCO");
+        assert_eq!(renamed.canonical_symbol.as_deref(), Some("NEWCO"));
+        assert_eq!(renamed.asset_id, None);
+        assert!(!renamed.needs_mapping());
+    }
+
+    /// The override methods never read the catalog.
+    struct NoAssets;
+
+    #[async_trait::async_trait]
+    impl AssetServiceTrait for NoAssets {
+        fn get_assets(&self) -> Result<Vec<Asset>> {
+            Ok(Vec::new())
+        }
+        fn get_asset_by_id(&self, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn delete_asset(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn update_asset_profile(&self, _: &str, _: UpdateAssetProfile) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn create_asset(&self, _: NewAsset) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn get_or_create_minimal_asset(
+            &self,
+            _: &str,
+            _: Option<String>,
+            _: Option<AssetMetadata>,
+            _: Option<String>,
+        ) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn update_quote_mode(&self, _: &str, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn get_assets_by_asset_ids(&self, _: &[String]) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        async fn enrich_asset_profile(&self, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn enrich_assets(&self, _: Vec<String>) -> Result<(usize, usize, usize)> {
+            unimplemented!()
+        }
+        async fn cleanup_legacy_metadata(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn merge_unknown_asset(
+            &self,
+            _: &str,
+            _: &str,
+            _: &dyn ActivityRepositoryTrait,
+        ) -> Result<u32> {
+            unimplemented!()
+        }
+        async fn ensure_assets(
+            &self,
+            _: Vec<AssetSpec>,
+            _: &dyn ActivityRepositoryTrait,
+        ) -> Result<EnsureAssetsResult> {
+            unimplemented!()
+        }
+        async fn resolve_import_asset_inputs(
+            &self,
+            _: Vec<AssetResolutionInput>,
+        ) -> Result<Vec<AssetResolutionOutput>> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn corrupt_stored_mappings_are_not_overwritten() {
+        let settings = Arc::new(MemorySettingsService::default());
+        settings
+            .set_setting_value(SYMBOL_OVERRIDES_SETTING_KEY, "{not json")
+            .await
+            .unwrap();
+        let service = SymbolNormalizationService::new(Arc::new(NoAssets), settings.clone());
+
+        assert!(matches!(service.get_overrides(), Err(Error::Unexpected(_))));
+        let result = service.set_override("BRKB", Some("BRK.B")).await;
+
+        assert!(matches!(result, Err(Error::Unexpected(_))));
+        assert_eq!(
+            settings
+                .get_setting_value(SYMBOL_OVERRIDES_SETTING_KEY)
+                .unwrap()
+                .as_deref(),
+            Some("{not json")
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_mappings_of_different_symbols_are_all_kept() {
+        let settings: Arc<dyn SettingsServiceTrait> = Arc::new(MemorySettingsService::default());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let settings = settings.clone();
+                tokio::spawn(async move {
+                    SymbolNormalizationService::new(Arc::new(NoAssets), settings)
+                        .set_override(&format!("RAW{}", i), Some("TARGET"))
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let overrides = SymbolNormalizationService::new(Arc::new(NoAssets), settings)
+            .get_overrides()
+            .unwrap();
+        assert_eq!(overrides.len(), 8);
+    }
+}
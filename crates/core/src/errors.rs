@@ -71,6 +71,118 @@ pub enum Error {
 
     #[error("Fx error: {0}")]
     Fx(#[from] FxError),
+
+    #[error("Cloud access is disabled")]
+    CloudDisabled,
+
+    #[error("{0}")]
+    CloudApi(#[from] CloudApiError),
+}
+
+/// The plan no longer includes sync (subscription expired or over quota). Sent with a `403`;
+/// a `402` means the same without a code.
+pub const SYNC_SUBSCRIPTION_REQUIRED: &str = "SYNC_SUBSCRIPTION_REQUIRED";
+
+/// Whether a cloud response refused the request because the plan does not cover it: a `402`,
+/// or a `403` carrying [`SYNC_SUBSCRIPTION_REQUIRED`] (a plain `403` is an auth problem).
+pub fn is_subscription_required_response(status: u16, code: Option<&str>) -> bool {
+    match status {
+        402 => true,
+        403 => code == Some(SYNC_SUBSCRIPTION_REQUIRED),
+        _ => false,
+    }
+}
+
+/// Client errors that can clear up on their own: auth refreshes, conflicts, locks and rate
+/// limits.
+const RETRYABLE_CLIENT_STATUSES: [u16; 7] = [401, 403, 408, 409, 423, 425, 429];
+
+/// A failed request to the Wealthfolio cloud API. `request` carries the client and server
+/// request ids for support; it differs on every call.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CloudApiError {
+    /// The cloud answered with an error status.
+    #[error("API error {status}{} ({request})", status_detail(.code, .message))]
+    Status {
+        status: u16,
+        /// Machine-readable error code from the response body, if any.
+        code: Option<String>,
+        message: Option<String>,
+        request: String,
+    },
+
+    /// No response within the client's connect, read or request timeout.
+    #[error("Request timed out: {message} ({request})")]
+    Timeout { message: String, request: String },
+
+    /// The request or response could not be sent or read.
+    #[error("Request failed: {message} ({request})")]
+    Transport { message: String, request: String },
+
+    /// The response did not have the expected shape.
+    #[error("Failed to parse response: {message} ({request})")]
+    InvalidResponse { message: String, request: String },
+}
+
+fn status_detail(code: &Option<String>, message: &Option<String>) -> String {
+    match (code, message) {
+        (Some(code), Some(message)) => format!(": {}: {}", code, message),
+        (None, Some(message)) => format!(": {}", message),
+        (Some(code), None) => format!(": {}", code),
+        (None, None) => String::new(),
+    }
+}
+
+impl CloudApiError {
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Status { code, .. } => code.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the cloud refused the request because the subscription expired or does not
+    /// cover it.
+    pub fn is_subscription_required(&self) -> bool {
+        self.status()
+            .is_some_and(|status| is_subscription_required_response(status, self.code()))
+    }
+
+    /// Whether retrying the same request will keep failing: a client error other than the
+    /// retryable ones, or a response the client cannot parse.
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            Self::Status { status, .. } => {
+                (400..500).contains(status) && !RETRYABLE_CLIENT_STATUSES.contains(status)
+            }
+            Self::InvalidResponse { .. } => true,
+            Self::Timeout { .. } | Self::Transport { .. } => false,
+        }
+    }
+
+    /// The error without its request ids, so the same failure compares equal across calls.
+    pub fn signature(&self) -> String {
+        match self {
+            Self::Status {
+                status,
+                code,
+                message,
+                ..
+            } => format!("API error {}{}", status, status_detail(code, message)),
+            Self::Timeout { message, .. } => format!("Request timed out: {}", message),
+            Self::Transport { message, .. } => format!("Request failed: {}", message),
+            Self::InvalidResponse { message, .. } => {
+                format!("Failed to parse response: {}", message)
+            }
+        }
+    }
 }
 
 /// Database-agnostic error type for storage operations.
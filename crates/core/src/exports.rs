@@ -1,14 +1,15 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
 use rust_decimal::Decimal;
 use serde::de::{MapAccess, Visitor};
 use serde::Deserializer;
 use serde::Serialize;
 use serde_json::Value;
+use std::collections::HashSet;
 use std::fmt;
 
 use crate::assets::AssetKind;
 use crate::errors::{Error, Result, ValidationError};
-use crate::portfolio::holdings::{HoldingListItem, HoldingType, MonetaryValue};
+use crate::portfolio::holdings::{Holding, HoldingListItem, HoldingType, MonetaryValue};
 use crate::utils::occ_symbol::parse_occ_symbol;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -304,6 +305,118 @@ pub fn format_holding_list_records(
     }
 }
 
+const HOLDINGS_SNAPSHOT_CSV_HEADERS: [&str; 9] = [
+    "symbol",
+    "name",
+    "accountId",
+    "quantity",
+    "costBasisBase",
+    "marketValueBase",
+    "baseCurrency",
+    "asOfDate",
+    "priceFlag",
+];
+
+/// Why a holdings snapshot row has no market value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HoldingPriceFlag {
+    MissingPrice,
+    StalePrice,
+}
+
+impl HoldingPriceFlag {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingPrice => "MISSING_PRICE",
+            Self::StalePrice => "STALE_PRICE",
+        }
+    }
+}
+
+fn holding_price_flag(
+    holding: &Holding,
+    stale_asset_ids: &HashSet<String>,
+) -> Option<HoldingPriceFlag> {
+    if holding.holding_type == HoldingType::Cash {
+        return None;
+    }
+    if holding.price.is_none() {
+        return Some(HoldingPriceFlag::MissingPrice);
+    }
+    holding
+        .instrument
+        .as_ref()
+        .filter(|instrument| stale_asset_ids.contains(&instrument.id))
+        .map(|_| HoldingPriceFlag::StalePrice)
+}
+
+/// Renders current positions as a plain CSV for sharing outside the app.
+///
+/// The first line is a `#` comment carrying the valuation timestamp. Holdings whose price
+/// is missing or listed in `stale_asset_ids` keep their quantity and cost basis but leave
+/// the market value blank and set `priceFlag`.
+pub fn holdings_snapshot_csv(
+    holdings: &[Holding],
+    stale_asset_ids: &HashSet<String>,
+    valued_at: DateTime<Utc>,
+) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(HOLDINGS_SNAPSHOT_CSV_HEADERS)
+        .map_err(|e| Error::Unexpected(format!("Failed to write holdings CSV: {}", e)))?;
+
+    for holding in holdings {
+        let flag = holding_price_flag(holding, stale_asset_ids);
+        let symbol = holding
+            .instrument
+            .as_ref()
+            .map(|instrument| instrument.symbol.clone())
+            .unwrap_or_else(|| holding.local_currency.clone());
+        let name = holding
+            .instrument
+            .as_ref()
+            .and_then(|instrument| instrument.name.clone())
+            .unwrap_or_default();
+        let cost_basis = holding
+            .cost_basis
+            .as_ref()
+            .map(|value| value.base.to_string())
+            .unwrap_or_default();
+        let market_value = if flag.is_some() {
+            String::new()
+        } else {
+            holding.market_value.base.to_string()
+        };
+
+        writer
+            .write_record([
+                symbol,
+                name,
+                holding.account_id.clone(),
+                holding.quantity.to_string(),
+                cost_basis,
+                market_value,
+                holding.base_currency.clone(),
+                holding.as_of_date.format("%Y-%m-%d").to_string(),
+                flag.map(|flag| flag.as_str().to_string())
+                    .unwrap_or_default(),
+            ])
+            .map_err(|e| Error::Unexpected(format!("Failed to write holdings CSV: {}", e)))?;
+    }
+
+    let rows = writer
+        .into_inner()
+        .map_err(|e| Error::Unexpected(format!("Failed to write holdings CSV: {}", e)))?;
+    let rows = String::from_utf8(rows)
+        .map_err(|e| Error::Unexpected(format!("Holdings CSV is not valid UTF-8: {}", e)))?;
+
+    Ok(format!(
+        "# valuedAt={}\n{}",
+        valued_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        rows
+    ))
+}
+
 fn records_to_csv<T: Serialize>(records: &[T]) -> Result<String> {
     let rows = records_to_object_rows(records)?;
     if rows.is_empty() {
@@ -495,6 +608,87 @@ mod tests {
         }
     }
 
+    fn sample_holding(symbol: &str, price: Option<Decimal>) -> Holding {
+        Holding {
+            id: format!("account-1-{}", symbol),
+            account_id: "account-1".to_string(),
+            holding_type: HoldingType::Security,
+            instrument: Some(crate::portfolio::holdings::Instrument {
+                id: format!("asset-{}", symbol.to_ascii_lowercase()),
+                symbol: symbol.to_string(),
+                name: Some(format!("{} Corp", symbol)),
+                currency: "USD".to_string(),
+                notes: None,
+                pricing_mode: "MARKET".to_string(),
+                preferred_provider: None,
+                exchange_mic: None,
+                classifications: None,
+            }),
+            asset_kind: Some(AssetKind::Investment),
+            quantity: dec!(10),
+            open_date: None,
+            lots: None,
+            contract_multiplier: dec!(1),
+            local_currency: "USD".to_string(),
+            base_currency: "CAD".to_string(),
+            fx_rate: Some(dec!(1.25)),
+            market_value: MonetaryValue {
+                local: dec!(2000),
+                base: dec!(2500),
+            },
+            cost_basis: Some(MonetaryValue {
+                local: dec!(1000),
+                base: dec!(1250),
+            }),
+            price,
+            purchase_price: None,
+            unrealized_gain: None,
+            unrealized_gain_pct: None,
+            realized_gain: None,
+            realized_gain_pct: None,
+            total_gain: None,
+            total_gain_pct: None,
+            income: None,
+            total_return: None,
+            total_return_pct: None,
+            return_basis: None,
+            day_change: None,
+            day_change_pct: None,
+            prev_close_value: None,
+            weight: dec!(0.5),
+            as_of_date: NaiveDate::from_ymd_opt(2026, 6, 25).unwrap(),
+            metadata: None,
+            source_account_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn holdings_snapshot_csv_lists_positions_and_flags_missing_prices() {
+        let holdings = vec![
+            sample_holding("AAPL", Some(dec!(200))),
+            sample_holding("XYZ", None),
+            sample_holding("MSFT", Some(dec!(400))),
+        ];
+        let stale = HashSet::from(["asset-msft".to_string()]);
+        let valued_at = DateTime::parse_from_rfc3339("2026-06-25T21:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let csv = holdings_snapshot_csv(&holdings, &stale, valued_at).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "# valuedAt=2026-06-25T21:30:00Z",
+                "symbol,name,accountId,quantity,costBasisBase,marketValueBase,baseCurrency,asOfDate,priceFlag",
+                "AAPL,AAPL Corp,account-1,10,1250,2500,CAD,2026-06-25,",
+                "XYZ,XYZ Corp,account-1,10,1250,,CAD,2026-06-25,MISSING_PRICE",
+                "MSFT,MSFT Corp,account-1,10,1250,,CAD,2026-06-25,STALE_PRICE",
+            ]
+        );
+    }
+
     #[test]
     fn holdings_export_type_parses_and_names_file() {
         let data_type = ExportDataType::parse("holdings").unwrap();
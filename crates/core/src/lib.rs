@@ -19,11 +19,13 @@ pub mod health;
 pub mod limits;
 pub mod lots;
 pub mod planning;
+pub mod portability;
 pub mod portfolio;
 pub mod portfolios;
 pub mod quotes;
 pub mod secrets;
 pub mod settings;
+pub mod share_snapshot;
 pub mod sync;
 pub mod taxonomies;
 pub mod utils;
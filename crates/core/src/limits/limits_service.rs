@@ -467,6 +467,15 @@ mod tests {
             unimplemented!()
         }
 
+        async fn bulk_upsert_removing_stale(
+            &self,
+            _account_id: &str,
+            _activities: Vec<crate::activities::ActivityUpsert>,
+            _select_stale: crate::activities::StaleActivitySelector,
+        ) -> Result<crate::activities::ActivityReconcileResult> {
+            unimplemented!()
+        }
+
         async fn reassign_asset(&self, _old_asset_id: &str, _new_asset_id: &str) -> Result<u32> {
             Ok(0)
         }
@@ -0,0 +1,731 @@
+//! Versioned, integrity-checked data portability archives.
+//!
+//! An archive is a single JSON document with two top-level keys:
+//!
+//! - `manifest`: format name, version, creation time and one entry per section with its record
+//!   count and `sha256:<hex>` checksum, plus a checksum over the manifest itself.
+//! - `sections`: the exported data, keyed by section name (e.g. `accounts`, `activities`).
+//!
+//! Checksums use the same `sha256:<hex>` convention as device sync snapshots.
+//! [`verify_archive`] must succeed before any restore: it rejects archives with a
+//! missing, extra, truncated or modified section.
+//!
+//! [`diff_archives`] compares two verified archives record by record, matching array records
+//! on their `id` field.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::accounts::{Account, AccountServiceTrait, NewAccount};
+use crate::activities::{
+    Activity, ActivityBulkMutationRequest, ActivityServiceTrait, AssetResolutionInput, NewActivity,
+};
+use crate::errors::{Error, Result, ValidationError};
+use crate::goals::{Goal, GoalFundingRule, GoalFundingRuleInput, GoalServiceTrait, NewGoal};
+
+/// Format identifier written into every archive manifest.
+pub const ARCHIVE_FORMAT: &str = "wealthfolio-portability";
+
+/// Current archive format version.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+pub const ACCOUNTS_SECTION: &str = "accounts";
+pub const ACTIVITIES_SECTION: &str = "activities";
+pub const GOALS_SECTION: &str = "goals";
+/// Goal funding rules, which link goals to accounts. Absent from archives written before it
+/// was added; those restore goals without funding.
+pub const GOAL_FUNDING_SECTION: &str = "goalFunding";
+
+/// Manifest entry describing a single archive section.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSectionEntry {
+    pub name: String,
+    /// Number of records when the section is an array, otherwise 1.
+    pub records: usize,
+    pub checksum: String,
+}
+
+/// Archive manifest listing every section and its checksum.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub app_version: Option<String>,
+    pub sections: Vec<ArchiveSectionEntry>,
+    /// Checksum over the manifest fields above, so entries can't be edited to match tampered data.
+    pub checksum: String,
+}
+
+/// A complete data portability archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortabilityArchive {
+    pub manifest: ArchiveManifest,
+    pub sections: BTreeMap<String, Value>,
+}
+
+impl PortabilityArchive {
+    /// Builds an archive from named sections, computing each section's checksum.
+    pub fn new(
+        sections: BTreeMap<String, Value>,
+        created_at: DateTime<Utc>,
+        app_version: Option<String>,
+    ) -> Result<Self> {
+        let mut entries = Vec::with_capacity(sections.len());
+        for (name, value) in &sections {
+            entries.push(ArchiveSectionEntry {
+                name: name.clone(),
+                records: record_count(value),
+                checksum: section_checksum(value)?,
+            });
+        }
+
+        let mut manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            created_at,
+            app_version,
+            sections: entries,
+            checksum: String::new(),
+        };
+        manifest.checksum = manifest_checksum(&manifest);
+
+        Ok(Self { manifest, sections })
+    }
+
+    /// Serializes the archive to pretty-printed JSON.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec_pretty(self).map_err(|e| Error::Unexpected(e.to_string()))
+    }
+
+    /// Returns a verified section deserialized into `T`, or `None` if the section is absent.
+    pub fn section<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<Option<T>> {
+        self.sections
+            .get(name)
+            .map(|value| {
+                serde_json::from_value(value.clone())
+                    .map_err(|e| invalid(format!("Archive section '{}' is malformed: {}", name, e)))
+            })
+            .transpose()
+    }
+}
+
+/// Outcome of restoring a verified archive.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveRestoreResult {
+    pub sections: Vec<ArchiveSectionEntry>,
+    pub accounts_restored: usize,
+    pub accounts_skipped: usize,
+    pub activities_restored: usize,
+    pub goals_restored: usize,
+    pub goals_skipped: usize,
+}
+
+/// Builds a full archive of accounts, activities, goals and goal funding.
+pub fn build_archive(
+    account_service: &dyn AccountServiceTrait,
+    activity_service: &dyn ActivityServiceTrait,
+    goal_service: &dyn GoalServiceTrait,
+    app_version: Option<String>,
+) -> Result<PortabilityArchive> {
+    let mut sections = BTreeMap::new();
+    sections.insert(
+        ACCOUNTS_SECTION.to_string(),
+        to_section(&account_service.get_all_accounts()?)?,
+    );
+    sections.insert(
+        ACTIVITIES_SECTION.to_string(),
+        to_section(&activity_service.get_activities()?)?,
+    );
+    let goals = goal_service.get_goals()?;
+    let mut funding = Vec::new();
+    for goal in &goals {
+        funding.extend(goal_service.get_goal_funding(&goal.id)?);
+    }
+    sections.insert(GOALS_SECTION.to_string(), to_section(&goals)?);
+    sections.insert(GOAL_FUNDING_SECTION.to_string(), to_section(&funding)?);
+    PortabilityArchive::new(sections, Utc::now(), app_version)
+}
+
+/// Verifies an archive and restores it.
+///
+/// Nothing is written unless every checksum matches. Accounts and goals that already exist are
+/// left untouched. Restored accounts and goals get new IDs, so every activity and funding rule is
+/// rewritten to the new account and goal IDs; activities are only restored for accounts created
+/// by this restore.
+pub async fn restore_archive(
+    bytes: &[u8],
+    account_service: &dyn AccountServiceTrait,
+    activity_service: &dyn ActivityServiceTrait,
+    goal_service: &dyn GoalServiceTrait,
+) -> Result<ArchiveRestoreResult> {
+    let archive = verify_archive(bytes)?;
+    let accounts: Vec<Account> = archive.section(ACCOUNTS_SECTION)?.unwrap_or_default();
+    let activities: Vec<Activity> = archive.section(ACTIVITIES_SECTION)?.unwrap_or_default();
+    let goals: Vec<Goal> = archive.section(GOALS_SECTION)?.unwrap_or_default();
+    let funding: Vec<GoalFundingRule> = archive.section(GOAL_FUNDING_SECTION)?.unwrap_or_default();
+
+    let existing_ids: HashSet<String> = account_service
+        .get_all_accounts()?
+        .into_iter()
+        .map(|account| account.id)
+        .collect();
+
+    // Archived account ID -> ID in this database, for skipped and restored accounts alike.
+    let mut account_ids: HashMap<String, String> = HashMap::new();
+    let mut restored_account_ids: HashSet<String> = HashSet::new();
+    let mut accounts_skipped = 0;
+    for account in accounts {
+        if existing_ids.contains(&account.id) {
+            accounts_skipped += 1;
+            account_ids.insert(account.id.clone(), account.id);
+            continue;
+        }
+        let value = serde_json::to_value(&account).map_err(|e| Error::Unexpected(e.to_string()))?;
+        let new_account: NewAccount = serde_json::from_value(value)
+            .map_err(|e| invalid(format!("Invalid archived account: {}", e)))?;
+        let created = account_service.create_account(new_account).await?;
+        restored_account_ids.insert(account.id.clone());
+        account_ids.insert(account.id, created.id);
+    }
+
+    let creates: Vec<NewActivity> = activities
+        .into_iter()
+        .filter(|activity| restored_account_ids.contains(&activity.account_id))
+        .map(|mut activity| {
+            activity.account_id = account_ids[&activity.account_id].clone();
+            archived_activity_to_new(activity)
+        })
+        .collect();
+    let activities_restored = if creates.is_empty() {
+        0
+    } else {
+        let result = activity_service
+            .bulk_mutate_activities(ActivityBulkMutationRequest {
+                creates,
+                updates: Vec::new(),
+                delete_ids: Vec::new(),
+            })
+            .await?;
+        if let Some(error) = result.errors.first() {
+            return Err(invalid(format!(
+                "Failed to restore activities: {}",
+                error.message
+            )));
+        }
+        result.created.len()
+    };
+
+    let existing_goal_ids: HashSet<String> = goal_service
+        .get_goals()?
+        .into_iter()
+        .map(|goal| goal.id)
+        .collect();
+    let mut goals_restored = 0;
+    let mut goals_skipped = 0;
+    for goal in goals {
+        if existing_goal_ids.contains(&goal.id) {
+            goals_skipped += 1;
+            continue;
+        }
+        let archived_id = goal.id.clone();
+        let created = goal_service.create_goal(archived_goal_to_new(goal)).await?;
+        // Rules for accounts missing from the archive have nothing to point at.
+        let rules: Vec<GoalFundingRuleInput> = funding
+            .iter()
+            .filter(|rule| rule.goal_id == archived_id)
+            .filter_map(|rule| {
+                Some(GoalFundingRuleInput {
+                    account_id: account_ids.get(&rule.account_id)?.clone(),
+                    share_percent: rule.share_percent,
+                    tax_bucket: rule.tax_bucket.clone(),
+                })
+            })
+            .collect();
+        if !rules.is_empty() {
+            goal_service.save_goal_funding(&created.id, rules).await?;
+        }
+        goals_restored += 1;
+    }
+
+    Ok(ArchiveRestoreResult {
+        sections: archive.manifest.sections,
+        accounts_restored: restored_account_ids.len(),
+        accounts_skipped,
+        activities_restored,
+        goals_restored,
+        goals_skipped,
+    })
+}
+
+/// Parses an archive and verifies its manifest and every section checksum.
+pub fn verify_archive(bytes: &[u8]) -> Result<PortabilityArchive> {
+    let archive: PortabilityArchive = serde_json::from_slice(bytes)
+        .map_err(|e| invalid(format!("Archive is not valid JSON: {}", e)))?;
+    let manifest = &archive.manifest;
+
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(invalid(format!(
+            "Unsupported archive format '{}'",
+            manifest.format
+        )));
+    }
+    if manifest.version == 0 || manifest.version > ARCHIVE_VERSION {
+        return Err(invalid(format!(
+            "Unsupported archive version {}",
+            manifest.version
+        )));
+    }
+    if manifest_checksum(manifest) != manifest.checksum {
+        return Err(invalid("Archive manifest checksum mismatch".to_string()));
+    }
+
+    for entry in &manifest.sections {
+        let value = archive
+            .sections
+            .get(&entry.name)
+            .ok_or_else(|| invalid(format!("Archive section '{}' is missing", entry.name)))?;
+        if record_count(value) != entry.records {
+            return Err(invalid(format!(
+                "Archive section '{}' has {} records, manifest lists {}",
+                entry.name,
+                record_count(value),
+                entry.records
+            )));
+        }
+        if section_checksum(value)? != entry.checksum {
+            return Err(invalid(format!(
+                "Archive section '{}' checksum mismatch",
+                entry.name
+            )));
+        }
+    }
+
+    if let Some(extra) = archive
+        .sections
+        .keys()
+        .find(|name| !manifest.sections.iter().any(|entry| &entry.name == *name))
+    {
+        return Err(invalid(format!(
+            "Archive section '{}' is not listed in the manifest",
+            extra
+        )));
+    }
+
+    Ok(archive)
+}
+
+/// How a record differs between two archives.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ArchiveRecordChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A top-level field whose value differs between two versions of a record.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveRecordChange {
+    pub id: String,
+    pub kind: ArchiveRecordChangeKind,
+    /// Changed fields; empty for added and removed records.
+    pub fields: Vec<ArchiveFieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveSectionDiff {
+    pub name: String,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    pub records: Vec<ArchiveRecordChange>,
+}
+
+/// Record-level differences from the `left` archive to the `right` one.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveDiff {
+    pub left_created_at: DateTime<Utc>,
+    pub right_created_at: DateTime<Utc>,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub sections: Vec<ArchiveSectionDiff>,
+}
+
+/// Verifies both archives and reports the records added, removed or changed from `left` to
+/// `right`, per section. An archive in a format version this build cannot read fails
+/// verification, like any other invalid archive.
+pub fn diff_archives(left: &[u8], right: &[u8]) -> Result<ArchiveDiff> {
+    let left = verify_archive(left)?;
+    let right = verify_archive(right)?;
+
+    let names: BTreeSet<&String> = left.sections.keys().chain(right.sections.keys()).collect();
+    let sections: Vec<ArchiveSectionDiff> = names
+        .into_iter()
+        .map(|name| {
+            diff_section(
+                name,
+                left.sections.get(name).unwrap_or(&Value::Null),
+                right.sections.get(name).unwrap_or(&Value::Null),
+            )
+        })
+        .collect();
+
+    Ok(ArchiveDiff {
+        left_created_at: left.manifest.created_at,
+        right_created_at: right.manifest.created_at,
+        added: sections.iter().map(|section| section.added).sum(),
+        removed: sections.iter().map(|section| section.removed).sum(),
+        changed: sections.iter().map(|section| section.changed).sum(),
+        sections,
+    })
+}
+
+fn diff_section(name: &str, left: &Value, right: &Value) -> ArchiveSectionDiff {
+    let left_records = keyed_records(name, left);
+    let right_records = keyed_records(name, right);
+
+    let mut diff = ArchiveSectionDiff {
+        name: name.to_string(),
+        added: 0,
+        removed: 0,
+        changed: 0,
+        unchanged: 0,
+        records: Vec::new(),
+    };
+    for (id, before) in &left_records {
+        match right_records.get(id) {
+            None => {
+                diff.removed += 1;
+                diff.records.push(ArchiveRecordChange {
+                    id: id.clone(),
+                    kind: ArchiveRecordChangeKind::Removed,
+                    fields: Vec::new(),
+                });
+            }
+            Some(after) if after == before => diff.unchanged += 1,
+            Some(after) => {
+                diff.changed += 1;
+                diff.records.push(ArchiveRecordChange {
+                    id: id.clone(),
+                    kind: ArchiveRecordChangeKind::Changed,
+                    fields: field_changes(before, after),
+                });
+            }
+        }
+    }
+    for id in right_records.keys() {
+        if !left_records.contains_key(id) {
+            diff.added += 1;
+            diff.records.push(ArchiveRecordChange {
+                id: id.clone(),
+                kind: ArchiveRecordChangeKind::Added,
+                fields: Vec::new(),
+            });
+        }
+    }
+    diff
+}
+
+/// Array records keyed by their `id` (or position when missing); any other section value is
+/// a single record keyed by the section name.
+fn keyed_records<'a>(name: &str, value: &'a Value) -> BTreeMap<String, &'a Value> {
+    match value {
+        Value::Null => BTreeMap::new(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let id = match item.get("id") {
+                    Some(Value::String(id)) => id.clone(),
+                    Some(id) if !id.is_null() => id.to_string(),
+                    _ => format!("#{}", index),
+                };
+                (id, item)
+            })
+            .collect(),
+        other => BTreeMap::from([(name.to_string(), other)]),
+    }
+}
+
+fn field_changes(before: &Value, after: &Value) -> Vec<ArchiveFieldChange> {
+    let (Value::Object(before), Value::Object(after)) = (before, after) else {
+        return vec![ArchiveFieldChange {
+            field: String::new(),
+            before: before.clone(),
+            after: after.clone(),
+        }];
+    };
+
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            let new = after.get(field).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| ArchiveFieldChange {
+                field: field.clone(),
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+fn to_section<T: Serialize>(records: &[T]) -> Result<Value> {
+    serde_json::to_value(records).map_err(|e| Error::Unexpected(e.to_string()))
+}
+
+fn archived_activity_to_new(activity: Activity) -> NewActivity {
+    NewActivity {
+        id: Some(activity.id),
+        account_id: activity.account_id,
+        asset: activity.asset_id.map(|asset_id| AssetResolutionInput {
+            id: Some(asset_id),
+            ..Default::default()
+        }),
+        activity_type: activity.activity_type,
+        subtype: activity.subtype,
+        activity_date: activity.activity_date.to_rfc3339(),
+        quantity: activity.quantity,
+        unit_price: activity.unit_price,
+        currency: activity.currency,
+        fee: activity.fee,
+        tax: activity.tax,
+        amount: activity.amount,
+        status: Some(activity.status),
+        notes: activity.notes,
+        fx_rate: activity.fx_rate,
+        metadata: activity.metadata.map(|m| m.to_string()),
+        needs_review: Some(activity.needs_review),
+        source_system: activity.source_system,
+        source_record_id: activity.source_record_id,
+        source_group_id: activity.source_group_id,
+        idempotency_key: activity.idempotency_key,
+        import_run_id: None,
+    }
+}
+
+fn archived_goal_to_new(goal: Goal) -> NewGoal {
+    NewGoal {
+        id: None,
+        goal_type: goal.goal_type,
+        title: goal.title,
+        description: goal.description,
+        target_amount: goal.target_amount,
+        status_lifecycle: Some(goal.status_lifecycle),
+        status_health: Some(goal.status_health),
+        priority: Some(goal.priority),
+        cover_image_key: goal.cover_image_key,
+        currency: goal.currency,
+        start_date: goal.start_date,
+        target_date: goal.target_date,
+        created_at: Some(goal.created_at),
+        updated_at: Some(goal.updated_at),
+    }
+}
+
+fn record_count(value: &Value) -> usize {
+    match value {
+        Value::Array(items) => items.len(),
+        Value::Null => 0,
+        _ => 1,
+    }
+}
+
+fn section_checksum(value: &Value) -> Result<String> {
+    let bytes = serde_json::to_vec(value).map_err(|e| Error::Unexpected(e.to_string()))?;
+    Ok(sha256_checksum(&bytes))
+}
+
+fn manifest_checksum(manifest: &ArchiveManifest) -> String {
+    let mut canonical = format!(
+        "{}\n{}\n{}\n{}\n",
+        manifest.format,
+        manifest.version,
+        manifest.created_at.to_rfc3339(),
+        manifest.app_version.as_deref().unwrap_or_default()
+    );
+    for entry in &manifest.sections {
+        canonical.push_str(&format!(
+            "{}:{}:{}\n",
+            entry.name, entry.records, entry.checksum
+        ));
+    }
+    sha256_checksum(canonical.as_bytes())
+}
+
+fn sha256_checksum(data: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(data))
+}
+
+fn invalid(message: String) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_archive() -> PortabilityArchive {
+        let mut sections = BTreeMap::new();
+        sections.insert(
+            "accounts".to_string(),
+            json!([{ "id": "acc-1", "name": "Brokerage", "currency": "USD" }]),
+        );
+        sections.insert(
+            "activities".to_string(),
+            json!([
+                { "id": "act-1", "accountId": "acc-1", "activityType": "DEPOSIT", "amount": 1000 },
+                { "id": "act-2", "accountId": "acc-1", "activityType": "BUY", "quantity": 5 }
+            ]),
+        );
+        PortabilityArchive::new(sections, Utc::now(), Some("1.0.0".to_string())).unwrap()
+    }
+
+    #[test]
+    fn round_trip_archive_verifies() {
+        let archive = sample_archive();
+        let bytes = archive.to_bytes().unwrap();
+
+        let verified = verify_archive(&bytes).unwrap();
+        assert_eq!(verified.manifest, archive.manifest);
+        assert_eq!(verified.manifest.sections.len(), 2);
+        assert_eq!(verified.manifest.sections[1].records, 2);
+        assert!(verified.manifest.sections[0]
+            .checksum
+            .starts_with("sha256:"));
+    }
+
+    #[test]
+    fn tampered_section_fails_verification() {
+        let mut archive = sample_archive();
+        archive.sections.insert(
+            "accounts".to_string(),
+            json!([{ "id": "acc-1", "name": "Hijacked", "currency": "USD" }]),
+        );
+        let bytes = archive.to_bytes().unwrap();
+
+        let err = verify_archive(&bytes).unwrap_err().to_string();
+        assert!(err.contains("checksum mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn truncated_section_fails_verification() {
+        let mut archive = sample_archive();
+        archive.sections.insert(
+            "activities".to_string(),
+            json!([{ "id": "act-1", "accountId": "acc-1", "activityType": "DEPOSIT", "amount": 1000 }]),
+        );
+        let bytes = archive.to_bytes().unwrap();
+
+        assert!(verify_archive(&bytes).is_err());
+    }
+
+    #[test]
+    fn edited_manifest_fails_verification() {
+        let mut archive = sample_archive();
+        archive.sections.remove("activities");
+        archive
+            .manifest
+            .sections
+            .retain(|entry| entry.name != "activities");
+        let bytes = archive.to_bytes().unwrap();
+
+        let err = verify_archive(&bytes).unwrap_err().to_string();
+        assert!(err.contains("manifest checksum"), "{}", err);
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_records() {
+        let left = sample_archive();
+        let mut sections = left.sections.clone();
+        sections.insert(
+            "accounts".to_string(),
+            json!([
+                { "id": "acc-1", "name": "Brokerage", "currency": "CAD" },
+                { "id": "acc-2", "name": "Savings", "currency": "USD" }
+            ]),
+        );
+        sections.insert(
+            "activities".to_string(),
+            json!([{ "id": "act-2", "accountId": "acc-1", "activityType": "BUY", "quantity": 5 }]),
+        );
+        sections.insert("goals".to_string(), json!([]));
+        let right =
+            PortabilityArchive::new(sections, Utc::now(), Some("1.1.0".to_string())).unwrap();
+
+        let diff = diff_archives(&left.to_bytes().unwrap(), &right.to_bytes().unwrap()).unwrap();
+
+        assert_eq!((diff.added, diff.removed, diff.changed), (1, 1, 1));
+        let accounts = &diff.sections[0];
+        assert_eq!(accounts.name, "accounts");
+        assert_eq!(
+            accounts.records,
+            vec![
+                ArchiveRecordChange {
+                    id: "acc-1".to_string(),
+                    kind: ArchiveRecordChangeKind::Changed,
+                    fields: vec![ArchiveFieldChange {
+                        field: "currency".to_string(),
+                        before: json!("USD"),
+                        after: json!("CAD"),
+                    }],
+                },
+                ArchiveRecordChange {
+                    id: "acc-2".to_string(),
+                    kind: ArchiveRecordChangeKind::Added,
+                    fields: Vec::new(),
+                },
+            ]
+        );
+        let activities = &diff.sections[1];
+        assert_eq!((activities.removed, activities.unchanged), (1, 1));
+        assert_eq!(activities.records[0].id, "act-1");
+        assert_eq!(diff.sections[2].name, "goals");
+        assert!(diff.sections[2].records.is_empty());
+    }
+
+    #[test]
+    fn diff_refuses_an_archive_of_an_unsupported_version() {
+        let left = sample_archive().to_bytes().unwrap();
+        let mut newer = sample_archive();
+        newer.manifest.version = ARCHIVE_VERSION + 1;
+        newer.manifest.checksum = manifest_checksum(&newer.manifest);
+
+        let err = diff_archives(&left, &newer.to_bytes().unwrap()).unwrap_err();
+        assert!(
+            err.to_string().contains("Unsupported archive version"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn truncated_file_fails_verification() {
+        let bytes = sample_archive().to_bytes().unwrap();
+        assert!(verify_archive(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
@@ -145,3 +145,50 @@ pub struct TaxonomyHoldingContributions {
     pub currency: String,
     pub contributions: Vec<HoldingAllocationContribution>,
 }
+
+/// Label used for holdings that have no value along a breakdown dimension.
+pub const UNCLASSIFIED_LABEL: &str = "Unclassified";
+
+/// Dimension for a flat allocation breakdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationDimension {
+    AssetClass,
+    Sector,
+    Currency,
+    Account,
+}
+
+impl AllocationDimension {
+    pub const ALL: [Self; 4] = [
+        Self::AssetClass,
+        Self::Sector,
+        Self::Currency,
+        Self::Account,
+    ];
+
+    pub fn parse(value: &str) -> crate::errors::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "asset_class" | "asset-class" | "assetclass" => Ok(Self::AssetClass),
+            "sector" => Ok(Self::Sector),
+            "currency" => Ok(Self::Currency),
+            "account" => Ok(Self::Account),
+            other => Err(crate::errors::Error::Validation(
+                crate::errors::ValidationError::InvalidInput(format!(
+                    "Unsupported allocation dimension: {}",
+                    other
+                )),
+            )),
+        }
+    }
+}
+
+/// One slice of a flat allocation breakdown, valued in base currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationSlice {
+    pub label: String,
+    pub value: Decimal,
+    /// Share of the breakdown total (0-100). Slices always sum to exactly 100.
+    pub percent: Decimal,
+}
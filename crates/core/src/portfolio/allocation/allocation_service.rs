@@ -1,7 +1,7 @@
 //! Service for computing portfolio allocations by taxonomy.
 
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
 use rust_decimal::Decimal;
@@ -13,12 +13,27 @@ use crate::portfolio::holdings::{Holding, HoldingSummary, HoldingType, HoldingsS
 use crate::taxonomies::{AssetTaxonomyAssignment, Category, TaxonomyServiceTrait};
 
 use super::{
-    AllocationHoldings, CategoryAllocation, HoldingAllocationContribution, PortfolioAllocations,
-    TaxonomyAllocation, TaxonomyHoldingContributions,
+    AllocationDimension, AllocationHoldings, AllocationSlice, CategoryAllocation,
+    HoldingAllocationContribution, PortfolioAllocations, TaxonomyAllocation,
+    TaxonomyHoldingContributions, UNCLASSIFIED_LABEL,
 };
 
 const CUSTOM_GROUPS_TAXONOMY_ID: &str = "custom_groups";
 
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct BreakdownCacheKey {
+    dimension: AllocationDimension,
+    account_ids: Vec<String>,
+    base_currency: String,
+}
+
+/// Breakdowns computed from taxonomy and account data at `data_version`.
+#[derive(Default)]
+struct BreakdownCache {
+    data_version: (u64, u64),
+    entries: HashMap<BreakdownCacheKey, Vec<AllocationSlice>>,
+}
+
 #[derive(Debug, Clone)]
 struct HoldingTaxonomyShare {
     category_id: String,
@@ -72,6 +87,20 @@ pub trait AllocationServiceTrait: Send + Sync {
         taxonomy_id: &str,
         aggregated_account_id: &str,
     ) -> Result<TaxonomyHoldingContributions>;
+
+    /// Returns a flat allocation breakdown along one dimension, valued in base currency.
+    /// Holdings without a classification are grouped under "Unclassified".
+    async fn get_allocation_breakdown(
+        &self,
+        account_ids: &[String],
+        base_currency: &str,
+        aggregated_account_id: &str,
+        dimension: AllocationDimension,
+    ) -> Result<Vec<AllocationSlice>>;
+
+    /// Drops cached breakdowns. Call after holdings change; taxonomy and account edits made
+    /// through their services are picked up without it.
+    fn invalidate_cache(&self) {}
 }
 
 /// Service for computing taxonomy-based portfolio allocations.
@@ -79,6 +108,7 @@ pub struct AllocationService {
     holdings_service: Arc<dyn HoldingsServiceTrait>,
     taxonomy_service: Arc<dyn TaxonomyServiceTrait>,
     account_service: Option<Arc<dyn AccountServiceTrait>>,
+    breakdown_cache: RwLock<BreakdownCache>,
 }
 
 impl AllocationService {
@@ -90,6 +120,7 @@ impl AllocationService {
             holdings_service,
             taxonomy_service,
             account_service: None,
+            breakdown_cache: RwLock::new(BreakdownCache::default()),
         }
     }
 
@@ -98,6 +129,16 @@ impl AllocationService {
         self
     }
 
+    /// Versions of the taxonomy and account data the breakdowns are computed from.
+    fn data_version(&self) -> (u64, u64) {
+        (
+            self.taxonomy_service.data_version(),
+            self.account_service
+                .as_ref()
+                .map_or(0, |account_service| account_service.data_version()),
+        )
+    }
+
     fn load_cash_overrides(&self, account_ids: &[String]) -> HashMap<String, String> {
         let Some(account_service) = &self.account_service else {
             return HashMap::new();
@@ -1067,6 +1108,110 @@ impl AllocationService {
             currency: base_currency.to_string(),
         })
     }
+
+    async fn compute_allocation_breakdown(
+        &self,
+        account_ids: &[String],
+        base_currency: &str,
+        aggregated_account_id: &str,
+        dimension: AllocationDimension,
+    ) -> Result<Vec<AllocationSlice>> {
+        let values: Vec<(String, Decimal)> = match dimension {
+            AllocationDimension::AssetClass | AllocationDimension::Sector => {
+                let allocations = if account_ids.len() == 1 {
+                    self.get_portfolio_allocations(&account_ids[0], base_currency)
+                        .await?
+                } else {
+                    self.get_portfolio_allocations_for_accounts(
+                        account_ids,
+                        base_currency,
+                        aggregated_account_id,
+                    )
+                    .await?
+                };
+                let taxonomy = if dimension == AllocationDimension::AssetClass {
+                    allocations.asset_classes
+                } else {
+                    allocations.sectors
+                };
+                taxonomy
+                    .categories
+                    .into_iter()
+                    .map(|category| {
+                        let label = if category.category_id == "__UNKNOWN__" {
+                            UNCLASSIFIED_LABEL.to_string()
+                        } else {
+                            category.category_name
+                        };
+                        (label, category.value)
+                    })
+                    .collect()
+            }
+            AllocationDimension::Currency | AllocationDimension::Account => {
+                // Per-account holdings so account totals are not blurred by merged rows.
+                let holdings = self
+                    .get_unmerged_holdings_for_accounts(account_ids, base_currency)
+                    .await?;
+                let account_names = if dimension == AllocationDimension::Account {
+                    self.load_account_names(account_ids)
+                } else {
+                    HashMap::new()
+                };
+                holdings
+                    .iter()
+                    .map(|holding| {
+                        let label = if dimension == AllocationDimension::Currency {
+                            holding.local_currency.trim().to_string()
+                        } else {
+                            account_names
+                                .get(&holding.account_id)
+                                .cloned()
+                                .unwrap_or_else(|| holding.account_id.clone())
+                        };
+                        let label = if label.is_empty() {
+                            UNCLASSIFIED_LABEL.to_string()
+                        } else {
+                            label
+                        };
+                        (label, holding.market_value.base)
+                    })
+                    .collect()
+            }
+        };
+        Ok(Self::build_slices(values))
+    }
+
+    /// Sums values per label and assigns percentages of the overall total. Rounding drift
+    /// goes to the largest slice so the percentages always add up to exactly 100.
+    fn build_slices(values: impl IntoIterator<Item = (String, Decimal)>) -> Vec<AllocationSlice> {
+        let mut totals: HashMap<String, Decimal> = HashMap::new();
+        for (label, value) in values {
+            *totals.entry(label).or_insert(Decimal::ZERO) += value;
+        }
+
+        let total: Decimal = totals.values().copied().sum();
+        let mut slices: Vec<AllocationSlice> = totals
+            .into_iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(label, value)| AllocationSlice {
+                label,
+                value,
+                percent: Decimal::ZERO,
+            })
+            .collect();
+        slices.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+
+        if total > Decimal::ZERO {
+            for slice in &mut slices {
+                slice.percent = (slice.value / total * dec!(100)).round_dp(2);
+            }
+            let drift = dec!(100) - slices.iter().map(|slice| slice.percent).sum::<Decimal>();
+            if let Some(largest) = slices.first_mut() {
+                largest.percent += drift;
+            }
+        }
+        slices
+    }
 }
 
 #[async_trait]
@@ -1180,13 +1325,61 @@ impl AllocationServiceTrait for AllocationService {
         )
         .await
     }
+
+    async fn get_allocation_breakdown(
+        &self,
+        account_ids: &[String],
+        base_currency: &str,
+        aggregated_account_id: &str,
+        dimension: AllocationDimension,
+    ) -> Result<Vec<AllocationSlice>> {
+        let mut sorted_account_ids = account_ids.to_vec();
+        sorted_account_ids.sort();
+        let cache_key = BreakdownCacheKey {
+            dimension,
+            account_ids: sorted_account_ids,
+            base_currency: base_currency.to_string(),
+        };
+        // Read before computing, so an edit made meanwhile leaves the new entry stale.
+        let data_version = self.data_version();
+        {
+            let cache = self.breakdown_cache.read().unwrap();
+            if cache.data_version == data_version {
+                if let Some(cached) = cache.entries.get(&cache_key) {
+                    return Ok(cached.clone());
+                }
+            }
+        }
+
+        let slices = self
+            .compute_allocation_breakdown(
+                account_ids,
+                base_currency,
+                aggregated_account_id,
+                dimension,
+            )
+            .await?;
+        let mut cache = self.breakdown_cache.write().unwrap();
+        if cache.data_version != data_version {
+            cache.entries.clear();
+            cache.data_version = data_version;
+        }
+        cache.entries.insert(cache_key, slices.clone());
+        Ok(slices)
+    }
+
+    fn invalidate_cache(&self) {
+        self.breakdown_cache.write().unwrap().entries.clear();
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::accounts::{Account, AccountUpdate, NewAccount};
+    use crate::accounts::{Account, AccountPurpose, AccountUpdate, NewAccount};
     use crate::portfolio::holdings::holdings_model::{Instrument, MonetaryValue};
+    use crate::portfolios::portfolios_test_support::StaticPortfolios;
+    use crate::portfolios::{AccountScope, PortfolioServiceTrait};
     use crate::taxonomies::{
         AssetTaxonomyAssignment, Category, NewAssetTaxonomyAssignment, NewCategory, NewTaxonomy,
         Taxonomy, TaxonomyWithCategories,
@@ -1460,6 +1653,115 @@ mod tests {
         }
     }
 
+    /// Taxonomies whose assignments can be edited, bumping the data version like the real service.
+    struct EditableTaxonomies {
+        taxonomies: Vec<TaxonomyWithCategories>,
+        assignments_by_asset: std::sync::Mutex<HashMap<String, Vec<AssetTaxonomyAssignment>>>,
+        data_version: std::sync::atomic::AtomicU64,
+    }
+
+    #[async_trait]
+    impl TaxonomyServiceTrait for EditableTaxonomies {
+        fn get_taxonomies(&self) -> Result<Vec<Taxonomy>> {
+            Ok(self
+                .taxonomies
+                .iter()
+                .map(|entry| entry.taxonomy.clone())
+                .collect())
+        }
+        fn get_taxonomy(&self, id: &str) -> Result<Option<TaxonomyWithCategories>> {
+            Ok(self
+                .taxonomies
+                .iter()
+                .find(|entry| entry.taxonomy.id == id)
+                .cloned())
+        }
+        fn get_taxonomies_with_categories(&self) -> Result<Vec<TaxonomyWithCategories>> {
+            Ok(self.taxonomies.clone())
+        }
+        async fn create_taxonomy(&self, _: NewTaxonomy) -> Result<Taxonomy> {
+            unimplemented!()
+        }
+        async fn update_taxonomy(&self, _: Taxonomy) -> Result<Taxonomy> {
+            unimplemented!()
+        }
+        async fn delete_taxonomy(&self, _: &str) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn create_category(&self, _: NewCategory) -> Result<Category> {
+            unimplemented!()
+        }
+        async fn update_category(&self, _: Category) -> Result<Category> {
+            unimplemented!()
+        }
+        async fn delete_category(&self, _: &str, _: &str) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn move_category(
+            &self,
+            _: &str,
+            _: &str,
+            _: Option<String>,
+            _: i32,
+        ) -> Result<Category> {
+            unimplemented!()
+        }
+        async fn import_taxonomy_json(&self, _: &str) -> Result<Taxonomy> {
+            unimplemented!()
+        }
+        fn export_taxonomy_json(&self, _: &str) -> Result<String> {
+            unimplemented!()
+        }
+        fn get_asset_assignments(&self, asset_id: &str) -> Result<Vec<AssetTaxonomyAssignment>> {
+            Ok(self
+                .assignments_by_asset
+                .lock()
+                .unwrap()
+                .get(asset_id)
+                .cloned()
+                .unwrap_or_default())
+        }
+        fn get_category_assignments(
+            &self,
+            _: &str,
+            _: &str,
+        ) -> Result<Vec<AssetTaxonomyAssignment>> {
+            unimplemented!()
+        }
+        async fn assign_asset_to_category(
+            &self,
+            assignment: NewAssetTaxonomyAssignment,
+        ) -> Result<AssetTaxonomyAssignment> {
+            let assigned = make_assignment(
+                &assignment.asset_id,
+                &assignment.taxonomy_id,
+                &assignment.category_id,
+                assignment.weight,
+            );
+            self.assignments_by_asset
+                .lock()
+                .unwrap()
+                .insert(assignment.asset_id, vec![assigned.clone()]);
+            self.data_version
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(assigned)
+        }
+        async fn replace_asset_taxonomy_assignments(
+            &self,
+            _: &str,
+            _: &str,
+            _: Vec<NewAssetTaxonomyAssignment>,
+        ) -> Result<Vec<AssetTaxonomyAssignment>> {
+            unimplemented!()
+        }
+        async fn remove_asset_assignment(&self, _: &str) -> Result<usize> {
+            unimplemented!()
+        }
+        fn data_version(&self) -> u64 {
+            self.data_version.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
     fn svc() -> AllocationService {
         AllocationService::new(Arc::new(NoopHoldings), Arc::new(NoopTaxonomies))
     }
@@ -2626,4 +2928,252 @@ mod tests {
         assert!(fi.is_some(), "all sources agree → FIXED_INCOME");
         assert_eq!(fi.unwrap().value, dec!(10000));
     }
+
+    // ── Allocation breakdown tests ─────────────────────────────────────────
+
+    struct StaticHoldings {
+        holdings: Vec<Holding>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl StaticHoldings {
+        fn new(holdings: Vec<Holding>) -> Self {
+            Self {
+                holdings,
+                calls: std::sync::atomic::AtomicUsize::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl HoldingsServiceTrait for StaticHoldings {
+        async fn get_holdings(&self, account_id: &str, _: &str) -> Result<Vec<Holding>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(self
+                .holdings
+                .iter()
+                .filter(|holding| holding.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+        async fn get_holdings_for_accounts(
+            &self,
+            _: &[String],
+            _: &str,
+            _: &str,
+        ) -> Result<Vec<Holding>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(self.holdings.clone())
+        }
+        async fn get_holding(&self, _: &str, _: &str, _: &str) -> Result<Option<Holding>> {
+            unimplemented!()
+        }
+        async fn holdings_from_snapshot(
+            &self,
+            _: &crate::portfolio::snapshot::AccountStateSnapshot,
+            _: &str,
+        ) -> Result<Vec<Holding>> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn allocation_breakdown_percentages_sum_to_100() {
+        let holdings = vec![
+            make_cash_holding("USD", dec!(100)),
+            make_cash_holding("EUR", dec!(100)),
+            make_cash_holding("CAD", dec!(100)),
+        ];
+        let svc = AllocationService::new(
+            Arc::new(StaticHoldings::new(holdings)),
+            Arc::new(NoopTaxonomies),
+        );
+
+        let slices = svc
+            .get_allocation_breakdown(
+                &["acc".to_string()],
+                "USD",
+                "acc",
+                AllocationDimension::Currency,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(slices.len(), 3);
+        assert_eq!(
+            slices.iter().map(|slice| slice.percent).sum::<Decimal>(),
+            dec!(100)
+        );
+        assert!(slices
+            .iter()
+            .all(|slice| slice.percent == dec!(33.33) || slice.percent == dec!(33.34)));
+    }
+
+    #[tokio::test]
+    async fn allocation_breakdown_buckets_unclassified_holdings() {
+        let holdings = vec![
+            make_holding("AAPL", dec!(750)),
+            make_holding("MYSTERY", dec!(250)),
+        ];
+        let holdings_service = Arc::new(StaticHoldings::new(holdings));
+        let taxonomies = StaticTaxonomies {
+            taxonomies: vec![TaxonomyWithCategories {
+                taxonomy: make_taxonomy("asset_classes", "Asset Classes", true),
+                categories: vec![make_category_for_taxonomy("asset_classes", "EQUITY", None)],
+            }],
+            assignments_by_asset: HashMap::from([(
+                "AAPL".to_string(),
+                vec![make_assignment("AAPL", "asset_classes", "EQUITY", 10000)],
+            )]),
+        };
+        let svc = AllocationService::new(holdings_service.clone(), Arc::new(taxonomies));
+        let account_ids = ["acc".to_string()];
+
+        let slices = svc
+            .get_allocation_breakdown(&account_ids, "USD", "acc", AllocationDimension::AssetClass)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            slices,
+            vec![
+                AllocationSlice {
+                    label: "EQUITY".to_string(),
+                    value: dec!(750),
+                    percent: dec!(75),
+                },
+                AllocationSlice {
+                    label: UNCLASSIFIED_LABEL.to_string(),
+                    value: dec!(250),
+                    percent: dec!(25),
+                },
+            ]
+        );
+
+        // Cached until invalidated.
+        svc.get_allocation_breakdown(&account_ids, "USD", "acc", AllocationDimension::AssetClass)
+            .await
+            .unwrap();
+        assert_eq!(holdings_service.calls(), 1);
+        svc.invalidate_cache();
+        svc.get_allocation_breakdown(&account_ids, "USD", "acc", AllocationDimension::AssetClass)
+            .await
+            .unwrap();
+        assert_eq!(holdings_service.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn allocation_breakdown_follows_an_edited_taxonomy_assignment() {
+        let holdings_service = Arc::new(StaticHoldings::new(vec![make_holding("AAPL", dec!(100))]));
+        let taxonomies = Arc::new(EditableTaxonomies {
+            taxonomies: vec![TaxonomyWithCategories {
+                taxonomy: make_taxonomy("asset_classes", "Asset Classes", true),
+                categories: vec![
+                    make_category_for_taxonomy("asset_classes", "EQUITY", None),
+                    make_category_for_taxonomy("asset_classes", "FIXED_INCOME", None),
+                ],
+            }],
+            assignments_by_asset: std::sync::Mutex::new(HashMap::from([(
+                "AAPL".to_string(),
+                vec![make_assignment("AAPL", "asset_classes", "EQUITY", 10000)],
+            )])),
+            data_version: std::sync::atomic::AtomicU64::new(0),
+        });
+        let svc = AllocationService::new(holdings_service.clone(), taxonomies.clone());
+        let account_ids = ["acc".to_string()];
+        let labels = |slices: Vec<AllocationSlice>| -> Vec<String> {
+            slices.into_iter().map(|slice| slice.label).collect()
+        };
+
+        let before = svc
+            .get_allocation_breakdown(&account_ids, "USD", "acc", AllocationDimension::AssetClass)
+            .await
+            .unwrap();
+        assert_eq!(labels(before), vec!["EQUITY"]);
+
+        taxonomies
+            .assign_asset_to_category(NewAssetTaxonomyAssignment {
+                asset_id: "AAPL".to_string(),
+                taxonomy_id: "asset_classes".to_string(),
+                category_id: "FIXED_INCOME".to_string(),
+                weight: 10000,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        // No portfolio job ran, yet the cached breakdown is not served.
+        let after = svc
+            .get_allocation_breakdown(&account_ids, "USD", "acc", AllocationDimension::AssetClass)
+            .await
+            .unwrap();
+        assert_eq!(labels(after), vec!["FIXED_INCOME"]);
+        assert_eq!(holdings_service.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn allocation_breakdown_by_portfolio_only_includes_member_accounts() {
+        let holdings = vec![
+            make_cash_holding_for_account("USD", dec!(600), "ira"),
+            make_cash_holding_for_account("USD", dec!(400), "roth"),
+            make_cash_holding_for_account("USD", dec!(5000), "taxable"),
+        ];
+        let portfolios = StaticPortfolios::with_portfolio("retirement", &["roth", "ira"]);
+        let svc = AllocationService::new(
+            Arc::new(StaticHoldings::new(holdings)),
+            Arc::new(NoopTaxonomies),
+        );
+
+        let scope = portfolios
+            .resolve_account_scope_for_purpose(
+                &AccountScope::Portfolio {
+                    portfolio_id: "retirement".to_string(),
+                },
+                "USD",
+                AccountPurpose::Holdings,
+            )
+            .unwrap();
+        let slices = svc
+            .get_allocation_breakdown(
+                &scope.account_ids,
+                "USD",
+                &scope.scope_id,
+                AllocationDimension::Account,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            slices,
+            vec![
+                AllocationSlice {
+                    label: "ira".to_string(),
+                    value: dec!(600),
+                    percent: dec!(60),
+                },
+                AllocationSlice {
+                    label: "roth".to_string(),
+                    value: dec!(400),
+                    percent: dec!(40),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn allocation_dimension_parses_supported_values() {
+        assert_eq!(
+            AllocationDimension::parse("asset_class").unwrap(),
+            AllocationDimension::AssetClass
+        );
+        assert_eq!(
+            AllocationDimension::parse("Sector").unwrap(),
+            AllocationDimension::Sector
+        );
+        assert!(AllocationDimension::parse("planet").is_err());
+    }
 }
@@ -457,8 +457,9 @@ mod tests {
     use super::*;
     use crate::errors::Result as CoreResult;
     use crate::portfolio::allocation::{
-        AllocationHoldings, CategoryAllocation, HoldingAllocationContribution,
-        PortfolioAllocations, TaxonomyAllocation, TaxonomyHoldingContributions,
+        AllocationDimension, AllocationHoldings, AllocationSlice, CategoryAllocation,
+        HoldingAllocationContribution, PortfolioAllocations, TaxonomyAllocation,
+        TaxonomyHoldingContributions,
     };
     use crate::portfolio::allocation_targets::model::{
         AllocationTarget, AllocationTargetWeight, BandType, NewAllocationTarget,
@@ -802,6 +803,15 @@ mod tests {
         ) -> CoreResult<TaxonomyHoldingContributions> {
             Ok(self.contributions.clone())
         }
+        async fn get_allocation_breakdown(
+            &self,
+            _account_ids: &[String],
+            _base_currency: &str,
+            _aggregated_account_id: &str,
+            _dimension: AllocationDimension,
+        ) -> CoreResult<Vec<AllocationSlice>> {
+            unimplemented!()
+        }
     }
 
     struct MockHoldingsService {
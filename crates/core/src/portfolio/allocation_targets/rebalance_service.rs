@@ -485,8 +485,8 @@ impl RebalanceServiceTrait for RebalanceService {
 mod tests {
     use super::*;
     use crate::portfolio::allocation::{
-        AllocationHoldings, HoldingAllocationContribution, PortfolioAllocations,
-        TaxonomyHoldingContributions,
+        AllocationDimension, AllocationHoldings, AllocationSlice, HoldingAllocationContribution,
+        PortfolioAllocations, TaxonomyHoldingContributions,
     };
     use crate::portfolio::allocation_targets::{
         AllocationTarget, AllocationTargetWeight, BandType, DriftReport, DriftRow, DriftStatus,
@@ -848,6 +848,15 @@ mod tests {
         ) -> CoreResult<TaxonomyHoldingContributions> {
             Ok(self.contributions.clone())
         }
+        async fn get_allocation_breakdown(
+            &self,
+            _: &[String],
+            _: &str,
+            _: &str,
+            _: AllocationDimension,
+        ) -> CoreResult<Vec<AllocationSlice>> {
+            unimplemented!()
+        }
     }
 
     struct MockHoldingsService {
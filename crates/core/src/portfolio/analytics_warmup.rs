@@ -0,0 +1,510 @@
+//! Background warmup of the portfolio analytics caches.
+//!
+//! After a sync or recalculation every analytics cache is cold, so the first dashboard load pays
+//! for allocation breakdowns, gains and value history at once. Warmup computes them ahead of time
+//! for the all-accounts scope, using exactly the arguments the read endpoints use, so those reads
+//! are served from cache.
+//!
+//! A warmup runs under a ticket. Starting a new warmup or calling [`AnalyticsWarmupService::cancel`]
+//! invalidates every earlier ticket, and a running warmup stops at its next step.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::accounts::AccountPurpose;
+use crate::errors::Result;
+use crate::portfolio::allocation::{AllocationDimension, AllocationServiceTrait};
+use crate::portfolio::gains::GainsServiceTrait;
+use crate::portfolio::valuation::ValuationServiceTrait;
+use crate::portfolios::{AccountScope, PortfolioServiceTrait, ResolvedAccountScope};
+
+/// Identifies one warmup run; stale once a newer run starts or warmup is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmupTicket(u64);
+
+/// What a warmup run computed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsWarmupReport {
+    pub account_count: usize,
+    pub allocation_slices: usize,
+    pub gains_holdings: usize,
+    pub valuation_points: usize,
+    /// Set when the run was superseded or cancelled before it finished.
+    pub cancelled: bool,
+}
+
+pub struct AnalyticsWarmupService {
+    portfolio_service: Arc<dyn PortfolioServiceTrait>,
+    allocation_service: Arc<dyn AllocationServiceTrait>,
+    gains_service: Arc<dyn GainsServiceTrait>,
+    valuation_service: Arc<dyn ValuationServiceTrait>,
+    generation: AtomicU64,
+}
+
+impl AnalyticsWarmupService {
+    pub fn new(
+        portfolio_service: Arc<dyn PortfolioServiceTrait>,
+        allocation_service: Arc<dyn AllocationServiceTrait>,
+        gains_service: Arc<dyn GainsServiceTrait>,
+        valuation_service: Arc<dyn ValuationServiceTrait>,
+    ) -> Self {
+        Self {
+            portfolio_service,
+            allocation_service,
+            gains_service,
+            valuation_service,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts a new run, cancelling any run still in progress.
+    pub fn begin(&self) -> WarmupTicket {
+        WarmupTicket(self.generation.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    /// Cancels the run in progress, if any.
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn is_current(&self, ticket: WarmupTicket) -> bool {
+        self.generation.load(Ordering::SeqCst) == ticket.0
+    }
+
+    /// Computes allocation breakdowns, gains and value history for all holdings accounts.
+    /// Returns early with `cancelled` set once `ticket` goes stale.
+    pub async fn run(
+        &self,
+        ticket: WarmupTicket,
+        base_currency: &str,
+    ) -> Result<AnalyticsWarmupReport> {
+        let cancelled = AnalyticsWarmupReport {
+            cancelled: true,
+            ..Default::default()
+        };
+        if !self.is_current(ticket) {
+            return Ok(cancelled);
+        }
+
+        let scope = self.portfolio_service.resolve_account_scope_for_purpose(
+            &AccountScope::All,
+            base_currency,
+            AccountPurpose::Holdings,
+        )?;
+        let mut report = AnalyticsWarmupReport {
+            account_count: scope.account_ids.len(),
+            ..Default::default()
+        };
+        if scope.account_ids.is_empty() {
+            return Ok(report);
+        }
+
+        for dimension in AllocationDimension::ALL {
+            if !self.is_current(ticket) {
+                return Ok(cancelled);
+            }
+            report.allocation_slices += self
+                .allocation_service
+                .get_allocation_breakdown(
+                    &scope.account_ids,
+                    base_currency,
+                    &scope.scope_id,
+                    dimension,
+                )
+                .await?
+                .len();
+        }
+
+        if !self.is_current(ticket) {
+            return Ok(cancelled);
+        }
+        report.gains_holdings = self.gains_service.get_gains(None).await?.len();
+
+        if !self.is_current(ticket) {
+            return Ok(cancelled);
+        }
+        report.valuation_points = self.warm_value_history(&scope)?;
+
+        debug!(
+            "Analytics warmup finished for {} accounts",
+            report.account_count
+        );
+        Ok(report)
+    }
+
+    /// Begins a run and drives it to completion.
+    pub async fn warm(&self, base_currency: &str) -> Result<AnalyticsWarmupReport> {
+        let ticket = self.begin();
+        self.run(ticket, base_currency).await
+    }
+
+    /// Single-account history is read straight from storage and has no cache to warm.
+    fn warm_value_history(&self, scope: &ResolvedAccountScope) -> Result<usize> {
+        if scope.account_ids.len() < 2 {
+            return Ok(0);
+        }
+        Ok(self
+            .valuation_service
+            .get_historical_valuation_totals_for_accounts(
+                &scope.scope_id,
+                &scope.account_ids,
+                &scope.base_currency,
+                None,
+                None,
+            )?
+            .len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::{Account, AccountServiceTrait, AccountUpdate, NewAccount};
+    use crate::portfolio::allocation::AllocationService;
+    use crate::portfolio::gains::GainsService;
+    use crate::portfolio::holdings::{Holding, HoldingsServiceTrait};
+    use crate::portfolio::snapshot::AccountStateSnapshot;
+    use crate::portfolio::valuation::{
+        DailyAccountValuation, NegativeBalanceInfo, ValuationRecalcMode,
+    };
+    use crate::portfolios::portfolios_test_support::StaticPortfolios;
+    use crate::taxonomies::{
+        AssetTaxonomyAssignment, Category, NewAssetTaxonomyAssignment, NewCategory, NewTaxonomy,
+        Taxonomy, TaxonomyServiceTrait, TaxonomyWithCategories,
+    };
+    use async_trait::async_trait;
+    use chrono::NaiveDate;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::{Mutex, RwLock};
+
+    /// The storage behind the allocation and gains caches; counts every load.
+    #[derive(Default)]
+    struct CountingHoldings {
+        loads: AtomicUsize,
+    }
+
+    impl CountingHoldings {
+        fn loads(&self) -> usize {
+            self.loads.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl HoldingsServiceTrait for CountingHoldings {
+        async fn get_holdings(&self, _: &str, _: &str) -> Result<Vec<Holding>> {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+        async fn get_holdings_for_accounts(
+            &self,
+            _: &[String],
+            _: &str,
+            _: &str,
+        ) -> Result<Vec<Holding>> {
+            self.loads.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+        async fn get_holding(&self, _: &str, _: &str, _: &str) -> Result<Option<Holding>> {
+            unimplemented!()
+        }
+        async fn holdings_from_snapshot(
+            &self,
+            _: &AccountStateSnapshot,
+            _: &str,
+        ) -> Result<Vec<Holding>> {
+            unimplemented!()
+        }
+    }
+
+    struct StaticAccounts(Vec<Account>);
+
+    #[async_trait]
+    impl AccountServiceTrait for StaticAccounts {
+        async fn create_account(&self, _: NewAccount) -> Result<Account> {
+            unimplemented!()
+        }
+        async fn update_account(&self, _: AccountUpdate) -> Result<Account> {
+            unimplemented!()
+        }
+        async fn delete_account(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        fn get_account(&self, _: &str) -> Result<Account> {
+            unimplemented!()
+        }
+        fn list_accounts(
+            &self,
+            _: Option<bool>,
+            _: Option<bool>,
+            _: Option<&[String]>,
+        ) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_all_accounts(&self) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_active_accounts(&self) -> Result<Vec<Account>> {
+            Ok(self.0.clone())
+        }
+        fn get_accounts_by_ids(&self, _: &[String]) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_non_archived_accounts(&self) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_active_non_archived_accounts(&self) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_base_currency(&self) -> Option<String> {
+            None
+        }
+    }
+
+    // Holdings are empty, so allocations never look up a taxonomy.
+    struct NoopTaxonomies;
+
+    #[async_trait]
+    impl TaxonomyServiceTrait for NoopTaxonomies {
+        fn get_taxonomies(&self) -> Result<Vec<Taxonomy>> {
+            unimplemented!()
+        }
+        fn get_taxonomy(&self, _: &str) -> Result<Option<TaxonomyWithCategories>> {
+            unimplemented!()
+        }
+        fn get_taxonomies_with_categories(&self) -> Result<Vec<TaxonomyWithCategories>> {
+            unimplemented!()
+        }
+        async fn create_taxonomy(&self, _: NewTaxonomy) -> Result<Taxonomy> {
+            unimplemented!()
+        }
+        async fn update_taxonomy(&self, _: Taxonomy) -> Result<Taxonomy> {
+            unimplemented!()
+        }
+        async fn delete_taxonomy(&self, _: &str) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn create_category(&self, _: NewCategory) -> Result<Category> {
+            unimplemented!()
+        }
+        async fn update_category(&self, _: Category) -> Result<Category> {
+            unimplemented!()
+        }
+        async fn delete_category(&self, _: &str, _: &str) -> Result<usize> {
+            unimplemented!()
+        }
+        async fn move_category(
+            &self,
+            _: &str,
+            _: &str,
+            _: Option<String>,
+            _: i32,
+        ) -> Result<Category> {
+            unimplemented!()
+        }
+        async fn import_taxonomy_json(&self, _: &str) -> Result<Taxonomy> {
+            unimplemented!()
+        }
+        fn export_taxonomy_json(&self, _: &str) -> Result<String> {
+            unimplemented!()
+        }
+        fn get_asset_assignments(&self, _: &str) -> Result<Vec<AssetTaxonomyAssignment>> {
+            unimplemented!()
+        }
+        fn get_category_assignments(
+            &self,
+            _: &str,
+            _: &str,
+        ) -> Result<Vec<AssetTaxonomyAssignment>> {
+            unimplemented!()
+        }
+        async fn assign_asset_to_category(
+            &self,
+            _: NewAssetTaxonomyAssignment,
+        ) -> Result<AssetTaxonomyAssignment> {
+            unimplemented!()
+        }
+        async fn replace_asset_taxonomy_assignments(
+            &self,
+            _: &str,
+            _: &str,
+            _: Vec<NewAssetTaxonomyAssignment>,
+        ) -> Result<Vec<AssetTaxonomyAssignment>> {
+            unimplemented!()
+        }
+        async fn remove_asset_assignment(&self, _: &str) -> Result<usize> {
+            unimplemented!()
+        }
+    }
+
+    /// Records the value history requests; the scoped history cache is keyed by exactly these
+    /// arguments.
+    #[derive(Default)]
+    struct RecordingValuations {
+        requests: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ValuationServiceTrait for RecordingValuations {
+        async fn calculate_valuation_history(
+            &self,
+            _account_id: &str,
+            _mode: ValuationRecalcMode,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+        fn get_historical_valuations(
+            &self,
+            _account_id: &str,
+            _start_date_opt: Option<NaiveDate>,
+            _end_date_opt: Option<NaiveDate>,
+        ) -> Result<Vec<DailyAccountValuation>> {
+            unimplemented!()
+        }
+        fn get_historical_valuations_for_accounts(
+            &self,
+            scope_id: &str,
+            account_ids: &[String],
+            base_currency: &str,
+            start_date_opt: Option<NaiveDate>,
+            end_date_opt: Option<NaiveDate>,
+        ) -> Result<Vec<DailyAccountValuation>> {
+            self.requests.lock().unwrap().push(format!(
+                "{}|{}|{}|{:?}|{:?}",
+                scope_id,
+                account_ids.join(","),
+                base_currency,
+                start_date_opt,
+                end_date_opt
+            ));
+            Ok(Vec::new())
+        }
+        fn get_latest_valuations(
+            &self,
+            _account_ids: &[String],
+        ) -> Result<Vec<DailyAccountValuation>> {
+            unimplemented!()
+        }
+        fn get_valuations_on_date(
+            &self,
+            _account_ids: &[String],
+            _date: NaiveDate,
+        ) -> Result<Vec<DailyAccountValuation>> {
+            unimplemented!()
+        }
+        fn get_accounts_with_negative_balance(
+            &self,
+            _account_ids: &[String],
+        ) -> Result<Vec<NegativeBalanceInfo>> {
+            unimplemented!()
+        }
+    }
+
+    struct Fixture {
+        portfolios: Arc<StaticPortfolios>,
+        holdings: Arc<CountingHoldings>,
+        allocations: Arc<AllocationService>,
+        gains: Arc<GainsService>,
+        valuations: Arc<RecordingValuations>,
+        warmup: AnalyticsWarmupService,
+    }
+
+    fn fixture() -> Fixture {
+        let portfolios = Arc::new(StaticPortfolios::with_portfolio(
+            "portfolio",
+            &["acc-b", "acc-a"],
+        ));
+        let holdings = Arc::new(CountingHoldings::default());
+        let accounts = Arc::new(StaticAccounts(
+            ["acc-b", "acc-a"]
+                .into_iter()
+                .map(|id| Account {
+                    id: id.to_string(),
+                    account_type: "SECURITIES".to_string(),
+                    ..Account::default()
+                })
+                .collect(),
+        ));
+        let allocations = Arc::new(AllocationService::new(
+            holdings.clone(),
+            Arc::new(NoopTaxonomies),
+        ));
+        let gains = Arc::new(GainsService::new(
+            accounts,
+            holdings.clone(),
+            Arc::new(RwLock::new("USD".to_string())),
+        ));
+        let valuations = Arc::new(RecordingValuations::default());
+        let warmup = AnalyticsWarmupService::new(
+            portfolios.clone(),
+            allocations.clone(),
+            gains.clone(),
+            valuations.clone(),
+        );
+        Fixture {
+            portfolios,
+            holdings,
+            allocations,
+            gains,
+            valuations,
+            warmup,
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_after_warmup_are_served_from_cache() {
+        let f = fixture();
+        let report = f.warmup.warm("USD").await.unwrap();
+        assert!(!report.cancelled);
+        assert_eq!(report.account_count, 2);
+        let loads = f.holdings.loads();
+        assert!(loads > 0);
+
+        // Same calls the dashboard endpoints make for the all-accounts scope.
+        let scope = f
+            .portfolios
+            .resolve_account_scope(&AccountScope::All, "USD")
+            .unwrap();
+        for dimension in AllocationDimension::ALL {
+            f.allocations
+                .get_allocation_breakdown(&scope.account_ids, "USD", &scope.scope_id, dimension)
+                .await
+                .unwrap();
+        }
+        f.gains.get_gains(None).await.unwrap();
+        f.valuations
+            .get_historical_valuation_totals_for_accounts(
+                &scope.scope_id,
+                &scope.account_ids,
+                &scope.base_currency,
+                None,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(f.holdings.loads(), loads);
+        let requests = f.valuations.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0], requests[1]);
+    }
+
+    #[tokio::test]
+    async fn a_stale_ticket_computes_nothing() {
+        let f = fixture();
+
+        let ticket = f.warmup.begin();
+        f.warmup.cancel();
+        let report = f.warmup.run(ticket, "USD").await.unwrap();
+        assert!(report.cancelled);
+
+        let superseded = f.warmup.begin();
+        let _newer = f.warmup.begin();
+        assert!(f.warmup.run(superseded, "USD").await.unwrap().cancelled);
+
+        assert_eq!(f.holdings.loads(), 0);
+        assert!(f.valuations.requests.lock().unwrap().is_empty());
+    }
+}
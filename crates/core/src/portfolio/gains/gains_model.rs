@@ -0,0 +1,28 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Realized and unrealized gains for one holding, in base currency.
+///
+/// Values come from the holdings calculation, so lot matching follows each account's
+/// cost basis method and short positions keep their sign.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HoldingGains {
+    pub asset_id: String,
+    pub symbol: String,
+    /// Open quantity; negative for a short position.
+    pub quantity: Decimal,
+    /// Cost of the open lots.
+    pub cost_basis: Decimal,
+    /// `None` when the holding has no current price.
+    pub market_value: Option<Decimal>,
+    /// Realized P&L of the lots disposed so far.
+    pub realized: Decimal,
+    /// Market value minus cost basis of the open lots.
+    pub unrealized: Option<Decimal>,
+    /// `(realized + unrealized) / cost of all lots` as a fraction.
+    pub total_return_pct: Option<Decimal>,
+    pub currency: String,
+    /// Set when the open lots have no cost, or their cost could not be converted to base currency.
+    pub incomplete_cost_basis: bool,
+}
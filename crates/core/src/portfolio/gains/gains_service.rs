@@ -0,0 +1,841 @@
+//! Service for realized and unrealized gains per holding.
+//!
+//! Gains of open positions are read from the holdings calculation rather than recomputed: the
+//! holdings calculator matches lots with each account's cost basis method, converts every lot at
+//! its stored FX rate, and records disposals for both long and short positions. Holdings skip
+//! fully closed positions, so their realized gains are summed from the recorded lot disposals.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+
+use crate::accounts::{account_supports_portfolio_scope, AccountPurpose, AccountServiceTrait};
+use crate::assets::AssetServiceTrait;
+use crate::constants::DECIMAL_PRECISION;
+use crate::errors::Result;
+use crate::lots::LotRepositoryTrait;
+use crate::portfolio::holdings::{Holding, HoldingType, HoldingsServiceTrait};
+
+use super::HoldingGains;
+
+#[async_trait]
+pub trait GainsServiceTrait: Send + Sync {
+    /// Gains per holding for one account, or across all accounts when `account_id` is `None`.
+    async fn get_gains(&self, account_id: Option<&str>) -> Result<Vec<HoldingGains>>;
+
+    /// Drops cached gains. Call after activities or quotes change.
+    fn invalidate_cache(&self);
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct GainsCacheKey {
+    account_id: Option<String>,
+    base_currency: String,
+}
+
+/// Realized results of lot disposals not already counted in an open holding, in base currency.
+#[derive(Debug, Default)]
+struct DisposalTotals {
+    realized: Decimal,
+    cost_basis: Decimal,
+    /// Set when a disposal was valued in another base currency and had to be left out.
+    incomplete: bool,
+}
+
+pub struct GainsService {
+    account_service: Arc<dyn AccountServiceTrait>,
+    holdings_service: Arc<dyn HoldingsServiceTrait>,
+    lot_repository: Option<Arc<dyn LotRepositoryTrait>>,
+    asset_service: Option<Arc<dyn AssetServiceTrait>>,
+    base_currency: Arc<RwLock<String>>,
+    cache: RwLock<HashMap<GainsCacheKey, Vec<HoldingGains>>>,
+}
+
+impl GainsService {
+    pub fn new(
+        account_service: Arc<dyn AccountServiceTrait>,
+        holdings_service: Arc<dyn HoldingsServiceTrait>,
+        base_currency: Arc<RwLock<String>>,
+    ) -> Self {
+        Self {
+            account_service,
+            holdings_service,
+            lot_repository: None,
+            asset_service: None,
+            base_currency,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Reports realized gains of fully closed positions from their lot disposals.
+    pub fn with_lot_repository(mut self, lot_repository: Arc<dyn LotRepositoryTrait>) -> Self {
+        self.lot_repository = Some(lot_repository);
+        self
+    }
+
+    /// Resolves symbols of closed positions, which have no holding to read them from.
+    pub fn with_asset_service(mut self, asset_service: Arc<dyn AssetServiceTrait>) -> Self {
+        self.asset_service = Some(asset_service);
+        self
+    }
+
+    /// Accounts the gains cover: the requested one, or every active account with holdings.
+    fn scoped_account_ids(&self, account_id: Option<&str>) -> Result<Vec<String>> {
+        if let Some(account_id) = account_id {
+            return Ok(vec![account_id.to_string()]);
+        }
+        Ok(self
+            .account_service
+            .get_active_accounts()?
+            .into_iter()
+            .filter(|account| account_supports_portfolio_scope(account, AccountPurpose::Holdings))
+            .map(|account| account.id)
+            .collect())
+    }
+
+    async fn load_holdings(
+        &self,
+        account_id: Option<&str>,
+        account_ids: &[String],
+        base_currency: &str,
+    ) -> Result<Vec<Holding>> {
+        if let Some(account_id) = account_id {
+            return self
+                .holdings_service
+                .get_holdings(account_id, base_currency)
+                .await;
+        }
+        if account_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.holdings_service
+            .get_holdings_for_accounts(account_ids, base_currency, "")
+            .await
+    }
+
+    /// Sums the lot disposals of each asset that no open holding already accounts for: assets
+    /// sold out entirely, or sold out in some of the accounts an aggregated holding spans.
+    async fn uncounted_disposals(
+        &self,
+        account_ids: &[String],
+        holdings: &[Holding],
+        base_currency: &str,
+    ) -> Result<BTreeMap<String, DisposalTotals>> {
+        let mut totals: BTreeMap<String, DisposalTotals> = BTreeMap::new();
+        let Some(lot_repository) = &self.lot_repository else {
+            return Ok(totals);
+        };
+
+        for account_id in account_ids {
+            for disposal in lot_repository
+                .get_lot_disposals_for_account(account_id)
+                .await?
+            {
+                let counted = holdings.iter().any(|holding| {
+                    holding
+                        .instrument
+                        .as_ref()
+                        .is_some_and(|instrument| instrument.id == disposal.asset_id)
+                        && holding_covers_account(holding, account_id)
+                });
+                if counted {
+                    continue;
+                }
+                let entry = totals.entry(disposal.asset_id.clone()).or_default();
+                if !disposal.base_currency.eq_ignore_ascii_case(base_currency) {
+                    entry.incomplete = true;
+                    continue;
+                }
+                entry.realized += parse_decimal_lossy(&disposal.realized_pnl_base);
+                entry.cost_basis += parse_decimal_lossy(&disposal.cost_basis_base);
+            }
+        }
+        Ok(totals)
+    }
+
+    async fn symbols(&self, asset_ids: &[String]) -> Result<HashMap<String, String>> {
+        let Some(asset_service) = &self.asset_service else {
+            return Ok(HashMap::new());
+        };
+        if asset_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        Ok(asset_service
+            .get_assets_by_asset_ids(asset_ids)
+            .await?
+            .into_iter()
+            .filter_map(|asset| {
+                let symbol = asset
+                    .display_code
+                    .clone()
+                    .or_else(|| asset.instrument_symbol.clone())?;
+                Some((asset.id, symbol))
+            })
+            .collect())
+    }
+}
+
+/// Whether an open holding's realized gain already includes `account_id`'s disposals.
+fn holding_covers_account(holding: &Holding, account_id: &str) -> bool {
+    if holding.source_account_ids.is_empty() {
+        holding.account_id == account_id
+    } else {
+        holding.source_account_ids.iter().any(|id| id == account_id)
+    }
+}
+
+fn parse_decimal_lossy(value: &str) -> Decimal {
+    value.parse::<Decimal>().unwrap_or(Decimal::ZERO)
+}
+
+fn return_pct(gain: Decimal, basis: Decimal) -> Option<Decimal> {
+    let basis = basis.abs();
+    (basis > Decimal::ZERO).then(|| (gain / basis).round_dp(DECIMAL_PRECISION))
+}
+
+/// Maps a calculated holding to its gains, or `None` for cash.
+fn holding_gains(holding: &Holding, base_currency: &str) -> Option<HoldingGains> {
+    if !matches!(
+        holding.holding_type,
+        HoldingType::Security | HoldingType::AlternativeAsset
+    ) {
+        return None;
+    }
+    let instrument = holding.instrument.as_ref()?;
+
+    let missing_fx = !holding
+        .local_currency
+        .eq_ignore_ascii_case(&holding.base_currency)
+        && holding.fx_rate.is_none();
+    let lot_without_cost = holding.lots.as_ref().is_some_and(|lots| {
+        lots.iter()
+            .any(|lot| !lot.quantity.is_zero() && lot.cost_basis.is_zero())
+    });
+    let incomplete_cost_basis = !holding.quantity.is_zero()
+        && (holding.cost_basis.is_none() || missing_fx || lot_without_cost);
+
+    Some(HoldingGains {
+        asset_id: instrument.id.clone(),
+        symbol: instrument.symbol.clone(),
+        quantity: holding.quantity,
+        cost_basis: holding
+            .cost_basis
+            .as_ref()
+            .map_or(Decimal::ZERO, |cost_basis| cost_basis.base),
+        market_value: holding.price.map(|_| holding.market_value.base),
+        realized: holding
+            .realized_gain
+            .as_ref()
+            .map_or(Decimal::ZERO, |realized| realized.base),
+        unrealized: holding
+            .unrealized_gain
+            .as_ref()
+            .map(|unrealized| unrealized.base),
+        total_return_pct: holding.total_gain_pct.or(holding.unrealized_gain_pct),
+        currency: base_currency.to_string(),
+        incomplete_cost_basis,
+    })
+}
+
+#[async_trait]
+impl GainsServiceTrait for GainsService {
+    async fn get_gains(&self, account_id: Option<&str>) -> Result<Vec<HoldingGains>> {
+        let key = GainsCacheKey {
+            account_id: account_id.map(str::to_string),
+            base_currency: self.base_currency.read().unwrap().clone(),
+        };
+        if let Some(cached) = self.cache.read().unwrap().get(&key).cloned() {
+            return Ok(cached);
+        }
+
+        let account_ids = self.scoped_account_ids(account_id)?;
+        let holdings = self
+            .load_holdings(account_id, &account_ids, &key.base_currency)
+            .await?;
+        let mut disposals = self
+            .uncounted_disposals(&account_ids, &holdings, &key.base_currency)
+            .await?;
+
+        let mut gains = Vec::new();
+        for holding in &holdings {
+            let Some(mut gain) = holding_gains(holding, &key.base_currency) else {
+                continue;
+            };
+            if let Some(extra) = disposals.remove(&gain.asset_id) {
+                // Sold out in another account of the aggregate: add that realized result and
+                // its cost to the holding's return.
+                let return_basis = holding
+                    .return_basis
+                    .as_ref()
+                    .map_or(gain.cost_basis, |basis| basis.base);
+                gain.realized += extra.realized;
+                gain.total_return_pct = gain.unrealized.and_then(|unrealized| {
+                    return_pct(gain.realized + unrealized, return_basis + extra.cost_basis)
+                });
+                gain.incomplete_cost_basis |= extra.incomplete;
+            }
+            gains.push(gain);
+        }
+
+        let closed_ids: Vec<String> = disposals.keys().cloned().collect();
+        let symbols = self.symbols(&closed_ids).await?;
+        for (asset_id, totals) in disposals {
+            gains.push(HoldingGains {
+                symbol: symbols
+                    .get(&asset_id)
+                    .cloned()
+                    .unwrap_or_else(|| asset_id.clone()),
+                asset_id,
+                quantity: Decimal::ZERO,
+                cost_basis: Decimal::ZERO,
+                market_value: Some(Decimal::ZERO),
+                realized: totals.realized,
+                unrealized: Some(Decimal::ZERO),
+                total_return_pct: return_pct(totals.realized, totals.cost_basis),
+                currency: key.base_currency.clone(),
+                incomplete_cost_basis: totals.incomplete,
+            });
+        }
+        gains.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+
+        self.cache.write().unwrap().insert(key, gains.clone());
+        Ok(gains)
+    }
+
+    fn invalidate_cache(&self) {
+        self.cache.write().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::{Account, AccountUpdate, NewAccount};
+    use crate::activities::ActivityRepositoryTrait;
+    use crate::assets::{
+        Asset, AssetMetadata, AssetResolutionInput, AssetResolutionOutput, AssetSpec,
+        EnsureAssetsResult, NewAsset, UpdateAssetProfile,
+    };
+    use crate::lots::{AssetLotView, LotClosure, LotDisposal, LotRecord};
+    use crate::portfolio::holdings::{Instrument, MonetaryValue};
+    use crate::portfolio::snapshot::AccountStateSnapshot;
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct StaticAccounts(Vec<Account>);
+
+    #[async_trait]
+    impl AccountServiceTrait for StaticAccounts {
+        async fn create_account(&self, _: NewAccount) -> Result<Account> {
+            unimplemented!()
+        }
+        async fn update_account(&self, _: AccountUpdate) -> Result<Account> {
+            unimplemented!()
+        }
+        async fn delete_account(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        fn get_account(&self, _: &str) -> Result<Account> {
+            unimplemented!()
+        }
+        fn list_accounts(
+            &self,
+            _: Option<bool>,
+            _: Option<bool>,
+            _: Option<&[String]>,
+        ) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_all_accounts(&self) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_active_accounts(&self) -> Result<Vec<Account>> {
+            Ok(self.0.clone())
+        }
+        fn get_accounts_by_ids(&self, _: &[String]) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_non_archived_accounts(&self) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_active_non_archived_accounts(&self) -> Result<Vec<Account>> {
+            unimplemented!()
+        }
+        fn get_base_currency(&self) -> Option<String> {
+            None
+        }
+    }
+
+    /// Returns fixed holdings and counts how often they were requested.
+    struct StaticHoldings {
+        holdings: Vec<Holding>,
+        calls: AtomicUsize,
+        requested_accounts: RwLock<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl HoldingsServiceTrait for StaticHoldings {
+        async fn get_holdings(&self, account_id: &str, _: &str) -> Result<Vec<Holding>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.requested_accounts
+                .write()
+                .unwrap()
+                .push(account_id.to_string());
+            Ok(self.holdings.clone())
+        }
+        async fn get_holdings_for_accounts(
+            &self,
+            account_ids: &[String],
+            _: &str,
+            _: &str,
+        ) -> Result<Vec<Holding>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.requested_accounts
+                .write()
+                .unwrap()
+                .extend(account_ids.iter().cloned());
+            Ok(self.holdings.clone())
+        }
+        async fn get_holding(&self, _: &str, _: &str, _: &str) -> Result<Option<Holding>> {
+            unimplemented!()
+        }
+        async fn holdings_from_snapshot(
+            &self,
+            _: &AccountStateSnapshot,
+            _: &str,
+        ) -> Result<Vec<Holding>> {
+            unimplemented!()
+        }
+    }
+
+    /// Serves fixed lot disposals; nothing else is read by the gains service.
+    struct StaticLots(Vec<LotDisposal>);
+
+    #[async_trait]
+    impl LotRepositoryTrait for StaticLots {
+        async fn replace_lots_for_account(&self, _: &str, _: &[LotRecord]) -> Result<()> {
+            unimplemented!()
+        }
+        async fn get_open_lots_for_account(&self, _: &str) -> Result<Vec<LotRecord>> {
+            unimplemented!()
+        }
+        async fn get_all_open_lots(&self) -> Result<Vec<LotRecord>> {
+            unimplemented!()
+        }
+        async fn get_lots_as_of_date(&self, _: &[String], _: NaiveDate) -> Result<Vec<LotRecord>> {
+            unimplemented!()
+        }
+        async fn get_all_lots_for_account(&self, _: &str) -> Result<Vec<LotRecord>> {
+            unimplemented!()
+        }
+        async fn get_lots_for_asset(&self, _: &str) -> Result<Vec<LotRecord>> {
+            unimplemented!()
+        }
+        async fn get_asset_lot_view(&self, _: &str, _: bool) -> Result<Vec<AssetLotView>> {
+            unimplemented!()
+        }
+        async fn get_all_lots(&self) -> Result<Vec<LotRecord>> {
+            unimplemented!()
+        }
+        async fn sync_lots_for_account(
+            &self,
+            _: &str,
+            _: &[LotRecord],
+            _: &[LotClosure],
+        ) -> Result<()> {
+            unimplemented!()
+        }
+        async fn get_lot_disposals_for_account(
+            &self,
+            account_id: &str,
+        ) -> Result<Vec<LotDisposal>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|disposal| disposal.account_id == account_id)
+                .cloned()
+                .collect())
+        }
+        fn get_lot_disposals_for_accounts_in_date_range_sync(
+            &self,
+            _: &[String],
+            _: NaiveDate,
+            _: NaiveDate,
+        ) -> Result<Vec<LotDisposal>> {
+            unimplemented!()
+        }
+        async fn get_open_position_quantities(&self) -> Result<HashMap<String, Decimal>> {
+            unimplemented!()
+        }
+        fn count_lots(&self) -> Result<i64> {
+            unimplemented!()
+        }
+    }
+
+    /// Resolves display codes for closed positions.
+    struct StaticAssets;
+
+    #[async_trait]
+    impl AssetServiceTrait for StaticAssets {
+        fn get_assets(&self) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        fn get_asset_by_id(&self, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn delete_asset(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn update_asset_profile(&self, _: &str, _: UpdateAssetProfile) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn create_asset(&self, _: NewAsset) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn get_or_create_minimal_asset(
+            &self,
+            _: &str,
+            _: Option<String>,
+            _: Option<AssetMetadata>,
+            _: Option<String>,
+        ) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn update_quote_mode(&self, _: &str, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn get_assets_by_asset_ids(&self, asset_ids: &[String]) -> Result<Vec<Asset>> {
+            Ok(asset_ids
+                .iter()
+                .map(|id| Asset {
+                    id: id.clone(),
+                    display_code: id.strip_prefix("SEC:").map(str::to_string),
+                    ..Asset::default()
+                })
+                .collect())
+        }
+        async fn enrich_asset_profile(&self, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn enrich_assets(&self, _: Vec<String>) -> Result<(usize, usize, usize)> {
+            unimplemented!()
+        }
+        async fn cleanup_legacy_metadata(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn merge_unknown_asset(
+            &self,
+            _: &str,
+            _: &str,
+            _: &dyn ActivityRepositoryTrait,
+        ) -> Result<u32> {
+            unimplemented!()
+        }
+        async fn ensure_assets(
+            &self,
+            _: Vec<AssetSpec>,
+            _: &dyn ActivityRepositoryTrait,
+        ) -> Result<EnsureAssetsResult> {
+            unimplemented!()
+        }
+        async fn resolve_import_asset_inputs(
+            &self,
+            _: Vec<AssetResolutionInput>,
+        ) -> Result<Vec<AssetResolutionOutput>> {
+            unimplemented!()
+        }
+    }
+
+    fn money(base: Decimal) -> MonetaryValue {
+        MonetaryValue { local: base, base }
+    }
+
+    fn security(symbol: &str, local_currency: &str, quantity: Decimal) -> Holding {
+        Holding {
+            id: format!("acc-1-{symbol}"),
+            account_id: "acc-1".to_string(),
+            holding_type: HoldingType::Security,
+            instrument: Some(Instrument {
+                id: format!("SEC:{symbol}"),
+                symbol: symbol.to_string(),
+                name: None,
+                currency: local_currency.to_string(),
+                notes: None,
+                pricing_mode: "MARKET".to_string(),
+                preferred_provider: None,
+                exchange_mic: None,
+                classifications: None,
+            }),
+            asset_kind: None,
+            quantity,
+            open_date: None,
+            lots: None,
+            contract_multiplier: Decimal::ONE,
+            local_currency: local_currency.to_string(),
+            base_currency: "CAD".to_string(),
+            fx_rate: None,
+            market_value: MonetaryValue::zero(),
+            cost_basis: None,
+            price: None,
+            purchase_price: None,
+            unrealized_gain: None,
+            unrealized_gain_pct: None,
+            realized_gain: None,
+            realized_gain_pct: None,
+            total_gain: None,
+            total_gain_pct: None,
+            income: None,
+            total_return: None,
+            total_return_pct: None,
+            return_basis: None,
+            day_change: None,
+            day_change_pct: None,
+            prev_close_value: None,
+            weight: Decimal::ZERO,
+            as_of_date: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            metadata: None,
+            source_account_ids: vec![],
+        }
+    }
+
+    fn service(holdings: Vec<Holding>) -> (GainsService, Arc<StaticHoldings>) {
+        let holdings = Arc::new(StaticHoldings {
+            holdings,
+            calls: AtomicUsize::new(0),
+            requested_accounts: RwLock::new(Vec::new()),
+        });
+        let accounts = StaticAccounts(vec![
+            Account {
+                id: "acc-1".to_string(),
+                account_type: "SECURITIES".to_string(),
+                ..Account::default()
+            },
+            Account {
+                id: "card".to_string(),
+                account_type: "CREDIT_CARD".to_string(),
+                ..Account::default()
+            },
+        ]);
+        let service = GainsService::new(
+            Arc::new(accounts),
+            holdings.clone(),
+            Arc::new(RwLock::new("CAD".to_string())),
+        );
+        (service, holdings)
+    }
+
+    fn securities_account(id: &str) -> Account {
+        Account {
+            id: id.to_string(),
+            account_type: "SECURITIES".to_string(),
+            ..Account::default()
+        }
+    }
+
+    /// A FIFO disposal slice as the holdings calculator records it, in CAD.
+    fn disposal(
+        account_id: &str,
+        activity_id: &str,
+        cost: Decimal,
+        proceeds: Decimal,
+    ) -> LotDisposal {
+        LotDisposal {
+            id: format!("{activity_id}:{cost}"),
+            lot_id: "lot".to_string(),
+            account_id: account_id.to_string(),
+            asset_id: "SEC:AAPL".to_string(),
+            disposal_activity_id: activity_id.to_string(),
+            disposal_date: "2024-03-01".to_string(),
+            quantity: "0".to_string(),
+            proceeds: proceeds.to_string(),
+            cost_basis: cost.to_string(),
+            realized_pnl: (proceeds - cost).to_string(),
+            proceeds_base: proceeds.to_string(),
+            cost_basis_base: cost.to_string(),
+            realized_pnl_base: (proceeds - cost).to_string(),
+            currency: "CAD".to_string(),
+            base_currency: "CAD".to_string(),
+            fx_rate_to_base: "1".to_string(),
+            cost_basis_method: "FIFO".to_string(),
+            created_at: "2024-03-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn service_with_lots(
+        accounts: Vec<Account>,
+        holdings: Vec<Holding>,
+        disposals: Vec<LotDisposal>,
+    ) -> GainsService {
+        let holdings = Arc::new(StaticHoldings {
+            holdings,
+            calls: AtomicUsize::new(0),
+            requested_accounts: RwLock::new(Vec::new()),
+        });
+        GainsService::new(
+            Arc::new(StaticAccounts(accounts)),
+            holdings,
+            Arc::new(RwLock::new("CAD".to_string())),
+        )
+        .with_lot_repository(Arc::new(StaticLots(disposals)))
+        .with_asset_service(Arc::new(StaticAssets))
+    }
+
+    #[tokio::test]
+    async fn gains_use_base_values_of_the_calculated_holdings() {
+        // Bought 10 USD shares for 1,300 CAD, sold 4 for a 52 CAD gain; 6 remain.
+        let mut long = security("AAPL", "USD", dec!(6));
+        long.fx_rate = Some(dec!(1.35));
+        long.price = Some(dec!(110));
+        long.market_value = MonetaryValue {
+            local: dec!(660),
+            base: dec!(891),
+        };
+        long.cost_basis = Some(MonetaryValue {
+            local: dec!(600),
+            base: dec!(780),
+        });
+        long.unrealized_gain = Some(MonetaryValue {
+            local: dec!(60),
+            base: dec!(111),
+        });
+        long.realized_gain = Some(MonetaryValue {
+            local: dec!(40),
+            base: dec!(52),
+        });
+        long.total_gain_pct = Some(dec!(0.1254));
+
+        // Short 5 shares opened at 500 CAD now worth 450 CAD.
+        let mut short = security("TSLA", "CAD", dec!(-5));
+        short.price = Some(dec!(90));
+        short.market_value = money(dec!(-450));
+        short.cost_basis = Some(money(dec!(-500)));
+        short.unrealized_gain = Some(money(dec!(50)));
+        short.unrealized_gain_pct = Some(dec!(0.1));
+
+        let mut cash = security("CAD", "CAD", dec!(100));
+        cash.holding_type = HoldingType::Cash;
+
+        let (service, _) = service(vec![short, cash, long]);
+        let gains = service.get_gains(Some("acc-1")).await.unwrap();
+
+        assert_eq!(gains.len(), 2);
+        assert_eq!(gains[0].symbol, "AAPL");
+        assert_eq!(gains[0].quantity, dec!(6));
+        assert_eq!(gains[0].cost_basis, dec!(780));
+        assert_eq!(gains[0].market_value, Some(dec!(891)));
+        assert_eq!(gains[0].realized, dec!(52));
+        assert_eq!(gains[0].unrealized, Some(dec!(111)));
+        assert_eq!(gains[0].total_return_pct, Some(dec!(0.1254)));
+        assert_eq!(gains[0].currency, "CAD");
+        assert!(!gains[0].incomplete_cost_basis);
+
+        assert_eq!(gains[1].symbol, "TSLA");
+        assert_eq!(gains[1].quantity, dec!(-5));
+        assert_eq!(gains[1].unrealized, Some(dec!(50)));
+        assert_eq!(gains[1].total_return_pct, Some(dec!(0.1)));
+        assert!(!gains[1].incomplete_cost_basis);
+    }
+
+    #[tokio::test]
+    async fn holdings_without_cost_or_fx_are_flagged_incomplete() {
+        // Transferred in with no cost and no price.
+        let no_cost = security("GIFT", "CAD", dec!(3));
+
+        // Cost is known in USD but there is no rate to CAD.
+        let mut no_fx = security("MSFT", "USD", dec!(2));
+        no_fx.cost_basis = Some(MonetaryValue {
+            local: dec!(200),
+            base: Decimal::ZERO,
+        });
+
+        let (service, _) = service(vec![no_cost, no_fx]);
+        let gains = service.get_gains(Some("acc-1")).await.unwrap();
+
+        assert!(gains.iter().all(|gain| gain.incomplete_cost_basis));
+        let gift = gains.iter().find(|gain| gain.symbol == "GIFT").unwrap();
+        assert_eq!(gift.market_value, None);
+        assert_eq!(gift.unrealized, None);
+        assert_eq!(gift.realized, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn gains_are_cached_until_invalidated_and_span_holdings_accounts() {
+        let (service, holdings) = service(vec![security("AAPL", "CAD", dec!(1))]);
+
+        service.get_gains(None).await.unwrap();
+        service.get_gains(None).await.unwrap();
+        assert_eq!(holdings.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            *holdings.requested_accounts.read().unwrap(),
+            vec!["acc-1".to_string()]
+        );
+
+        service.invalidate_cache();
+        service.get_gains(None).await.unwrap();
+        assert_eq!(holdings.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn seeded_buys_and_sells_keep_realized_gains_after_the_position_closes() {
+        // Buy 10 @ 100, buy 10 @ 120, sell 15 @ 130, then sell the last 5 @ 150. FIFO matches
+        // the first sell against all of lot 1 and half of lot 2. No holding is left.
+        let disposals = vec![
+            disposal("acc-1", "sell-1", dec!(1000), dec!(1300)),
+            disposal("acc-1", "sell-1", dec!(600), dec!(650)),
+            disposal("acc-1", "sell-2", dec!(600), dec!(750)),
+        ];
+        let service = service_with_lots(vec![securities_account("acc-1")], vec![], disposals);
+
+        for account_id in [Some("acc-1"), None] {
+            let gains = service.get_gains(account_id).await.unwrap();
+
+            assert_eq!(gains.len(), 1);
+            let closed = &gains[0];
+            assert_eq!(closed.asset_id, "SEC:AAPL");
+            assert_eq!(closed.symbol, "AAPL");
+            assert_eq!(closed.quantity, Decimal::ZERO);
+            assert_eq!(closed.cost_basis, Decimal::ZERO);
+            assert_eq!(closed.market_value, Some(Decimal::ZERO));
+            assert_eq!(closed.unrealized, Some(Decimal::ZERO));
+            assert_eq!(closed.realized, dec!(500));
+            // 500 / (1000 + 600 + 600)
+            assert_eq!(closed.total_return_pct, Some(dec!(0.22727273)));
+            assert!(!closed.incomplete_cost_basis);
+        }
+    }
+
+    #[tokio::test]
+    async fn sold_out_accounts_add_to_an_aggregated_open_holding() {
+        // acc-1 still holds 5 shares (its 350 realized is already on the holding); acc-2 sold
+        // its whole position for a 100 gain.
+        let mut open = security("AAPL", "CAD", dec!(5));
+        open.source_account_ids = vec!["acc-1".to_string()];
+        open.price = Some(dec!(150));
+        open.market_value = money(dec!(750));
+        open.cost_basis = Some(money(dec!(600)));
+        open.unrealized_gain = Some(money(dec!(150)));
+        open.realized_gain = Some(money(dec!(350)));
+        open.return_basis = Some(money(dec!(2200)));
+        let disposals = vec![
+            disposal("acc-1", "sell-1", dec!(1600), dec!(1950)),
+            disposal("acc-2", "sell-2", dec!(400), dec!(500)),
+        ];
+        let service = service_with_lots(
+            vec![securities_account("acc-1"), securities_account("acc-2")],
+            vec![open],
+            disposals,
+        );
+
+        let gains = service.get_gains(None).await.unwrap();
+
+        assert_eq!(gains.len(), 1);
+        assert_eq!(gains[0].quantity, dec!(5));
+        assert_eq!(gains[0].realized, dec!(450));
+        assert_eq!(gains[0].unrealized, Some(dec!(150)));
+        // (450 + 150) / (2200 + 400)
+        assert_eq!(gains[0].total_return_pct, Some(dec!(0.23076923)));
+    }
+}
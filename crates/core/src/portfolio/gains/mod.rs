@@ -0,0 +1,5 @@
+pub mod gains_model;
+pub mod gains_service;
+
+pub use gains_model::*;
+pub use gains_service::{GainsService, GainsServiceTrait};
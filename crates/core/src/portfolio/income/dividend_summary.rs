@@ -0,0 +1,327 @@
+//! Dividend totals per symbol, account or month, for income-focused views.
+//!
+//! Built from dividend activities only; interest and other income are left out. Every amount is
+//! converted to the base currency at the rate of the day it was paid. Dividends paid in shares
+//! (DRIP, dividend in kind) are totalled separately from cash dividends.
+
+use std::collections::BTreeMap;
+
+use chrono::{Months, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::activities::{
+    IncomeData, ACTIVITY_SUBTYPE_DIVIDEND_IN_KIND, ACTIVITY_SUBTYPE_DRIP, ACTIVITY_TYPE_DIVIDEND,
+};
+use crate::constants::DISPLAY_DECIMAL_PRECISION;
+
+/// Months of history a projection is based on.
+pub const DIVIDEND_PROJECTION_MONTHS: u32 = 12;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DividendGroupBy {
+    #[default]
+    Symbol,
+    Account,
+    Month,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendSummaryQuery {
+    /// First day included; unbounded when omitted.
+    pub from: Option<NaiveDate>,
+    /// Last day included; unbounded when omitted.
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub group_by: DividendGroupBy,
+    /// Adds forward income projected from the last 12 months.
+    #[serde(default)]
+    pub include_projection: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendGroup {
+    /// Asset id, account id or `YYYY-MM`, depending on the grouping.
+    pub key: String,
+    /// Symbol, account name or `YYYY-MM`.
+    pub label: String,
+    pub cash: Decimal,
+    pub reinvested: Decimal,
+    pub total: Decimal,
+    pub payments: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendProjection {
+    /// Dividends of the last 12 months, expected again over the next 12.
+    pub annual: Decimal,
+    pub monthly: Decimal,
+    /// First day of the history the projection is based on.
+    pub based_on_from: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendSummary {
+    pub currency: String,
+    pub group_by: DividendGroupBy,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Largest total first; months in calendar order.
+    pub groups: Vec<DividendGroup>,
+    pub cash_total: Decimal,
+    pub reinvested_total: Decimal,
+    pub total: Decimal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projection: Option<DividendProjection>,
+}
+
+fn is_reinvested(subtype: Option<&str>) -> bool {
+    subtype.is_some_and(|subtype| {
+        subtype.eq_ignore_ascii_case(ACTIVITY_SUBTYPE_DRIP)
+            || subtype.eq_ignore_ascii_case(ACTIVITY_SUBTYPE_DIVIDEND_IN_KIND)
+    })
+}
+
+fn group_key(income: &IncomeData, group_by: DividendGroupBy) -> (String, String) {
+    match group_by {
+        DividendGroupBy::Symbol => (income.asset_id.clone(), income.symbol.clone()),
+        DividendGroupBy::Account => (income.account_id.clone(), income.account_name.clone()),
+        DividendGroupBy::Month => (income.date.clone(), income.date.clone()),
+    }
+}
+
+/// Summarizes the dividends in `income`. `convert` turns an amount paid on a day into the base
+/// currency. `today` anchors the projection.
+pub fn build_dividend_summary(
+    income: &[IncomeData],
+    query: &DividendSummaryQuery,
+    base_currency: &str,
+    convert: impl Fn(Decimal, &str, NaiveDate) -> Decimal,
+    today: NaiveDate,
+) -> DividendSummary {
+    let dividends: Vec<(NaiveDate, &IncomeData, Decimal)> = income
+        .iter()
+        .filter(|row| row.income_type == ACTIVITY_TYPE_DIVIDEND)
+        .filter_map(|row| {
+            let paid_on = NaiveDate::parse_from_str(&row.activity_date, "%Y-%m-%d").ok()?;
+            Some((paid_on, row, convert(row.amount, &row.currency, paid_on)))
+        })
+        .collect();
+
+    let mut groups: BTreeMap<String, DividendGroup> = BTreeMap::new();
+    let (mut cash_total, mut reinvested_total) = (Decimal::ZERO, Decimal::ZERO);
+    for (paid_on, row, amount) in &dividends {
+        if query.from.is_some_and(|from| *paid_on < from)
+            || query.to.is_some_and(|to| *paid_on > to)
+        {
+            continue;
+        }
+        let (key, label) = group_key(row, query.group_by);
+        let group = groups.entry(key.clone()).or_insert_with(|| DividendGroup {
+            key,
+            label,
+            cash: Decimal::ZERO,
+            reinvested: Decimal::ZERO,
+            total: Decimal::ZERO,
+            payments: 0,
+        });
+        if is_reinvested(row.subtype.as_deref()) {
+            group.reinvested += amount;
+            reinvested_total += amount;
+        } else {
+            group.cash += amount;
+            cash_total += amount;
+        }
+        group.total += amount;
+        group.payments += 1;
+    }
+
+    let mut groups: Vec<DividendGroup> = groups
+        .into_values()
+        .map(|mut group| {
+            group.cash = group.cash.round_dp(DISPLAY_DECIMAL_PRECISION);
+            group.reinvested = group.reinvested.round_dp(DISPLAY_DECIMAL_PRECISION);
+            group.total = group.total.round_dp(DISPLAY_DECIMAL_PRECISION);
+            group
+        })
+        .collect();
+    if query.group_by != DividendGroupBy::Month {
+        groups.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.label.cmp(&b.label)));
+    }
+
+    let projection = query.include_projection.then(|| {
+        // Exactly the 12 months ending today, so the monthly figure divides by the months
+        // actually covered.
+        let based_on_from = today
+            .checked_sub_months(Months::new(DIVIDEND_PROJECTION_MONTHS))
+            .and_then(|day| day.succ_opt())
+            .unwrap_or(today);
+        let annual: Decimal = dividends
+            .iter()
+            .filter(|(paid_on, ..)| *paid_on >= based_on_from && *paid_on <= today)
+            .map(|(.., amount)| *amount)
+            .sum();
+        DividendProjection {
+            annual: annual.round_dp(DISPLAY_DECIMAL_PRECISION),
+            monthly: (annual / Decimal::from(DIVIDEND_PROJECTION_MONTHS))
+                .round_dp(DISPLAY_DECIMAL_PRECISION),
+            based_on_from,
+        }
+    });
+
+    DividendSummary {
+        currency: base_currency.to_string(),
+        group_by: query.group_by,
+        from: query.from,
+        to: query.to,
+        groups,
+        cash_total: cash_total.round_dp(DISPLAY_DECIMAL_PRECISION),
+        reinvested_total: reinvested_total.round_dp(DISPLAY_DECIMAL_PRECISION),
+        total: (cash_total + reinvested_total).round_dp(DISPLAY_DECIMAL_PRECISION),
+        projection,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Datelike;
+    use rust_decimal_macros::dec;
+
+    /// VTI pays in USD, everything else in CAD.
+    fn dividend(
+        day: &str,
+        subtype: Option<&str>,
+        symbol: &str,
+        account: &str,
+        amount: Decimal,
+    ) -> IncomeData {
+        IncomeData {
+            date: day[..7].to_string(),
+            activity_date: day.to_string(),
+            income_type: ACTIVITY_TYPE_DIVIDEND.to_string(),
+            subtype: subtype.map(str::to_string),
+            asset_id: format!("asset-{}", symbol),
+            asset_kind: "INVESTMENT".to_string(),
+            symbol: symbol.to_string(),
+            symbol_name: symbol.to_string(),
+            currency: if symbol == "VTI" { "USD" } else { "CAD" }.to_string(),
+            amount,
+            account_id: format!("acct-{}", account),
+            account_name: account.to_string(),
+        }
+    }
+
+    fn seeded() -> Vec<IncomeData> {
+        let mut interest = dividend("2026-02-11", None, "CASH", "TFSA", dec!(500));
+        interest.income_type = "INTEREST".to_string();
+        vec![
+            dividend("2026-01-15", None, "VTI", "TFSA", dec!(100)),
+            dividend("2026-01-20", Some("DRIP"), "VTI", "RRSP", dec!(40)),
+            dividend("2026-02-10", None, "RY", "TFSA", dec!(30)),
+            interest,
+            dividend("2025-06-01", None, "RY", "TFSA", dec!(25)),
+        ]
+    }
+
+    /// USD is worth 1.25 CAD in January and 1.5 CAD afterwards.
+    fn to_cad(amount: Decimal, currency: &str, day: NaiveDate) -> Decimal {
+        match currency {
+            "USD" if day.month() == 1 => amount * dec!(1.25),
+            "USD" => amount * dec!(1.5),
+            _ => amount,
+        }
+    }
+
+    fn query(group_by: DividendGroupBy) -> DividendSummaryQuery {
+        DividendSummaryQuery {
+            from: NaiveDate::from_ymd_opt(2026, 1, 1),
+            to: NaiveDate::from_ymd_opt(2026, 12, 31),
+            group_by,
+            include_projection: false,
+        }
+    }
+
+    #[test]
+    fn groups_dividends_in_base_currency_and_splits_reinvestments() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let by_symbol = build_dividend_summary(
+            &seeded(),
+            &query(DividendGroupBy::Symbol),
+            "CAD",
+            to_cad,
+            today,
+        );
+
+        assert_eq!(by_symbol.total, dec!(205));
+        assert_eq!(by_symbol.cash_total, dec!(155));
+        assert_eq!(by_symbol.reinvested_total, dec!(50));
+        assert_eq!(by_symbol.groups.len(), 2);
+        assert_eq!(by_symbol.groups[0].label, "VTI");
+        assert_eq!(by_symbol.groups[0].cash, dec!(125));
+        assert_eq!(by_symbol.groups[0].reinvested, dec!(50));
+        assert_eq!(by_symbol.groups[0].payments, 2);
+        assert_eq!(by_symbol.groups[1].total, dec!(30));
+
+        let by_account = build_dividend_summary(
+            &seeded(),
+            &query(DividendGroupBy::Account),
+            "CAD",
+            to_cad,
+            today,
+        );
+        let accounts: Vec<_> = by_account
+            .groups
+            .iter()
+            .map(|g| (g.label.as_str(), g.total))
+            .collect();
+        assert_eq!(accounts, vec![("TFSA", dec!(155)), ("RRSP", dec!(50))]);
+
+        let mut by_month_query = query(DividendGroupBy::Month);
+        by_month_query.include_projection = true;
+        let by_month = build_dividend_summary(&seeded(), &by_month_query, "CAD", to_cad, today);
+        let months: Vec<_> = by_month
+            .groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.total))
+            .collect();
+        assert_eq!(months, vec![("2026-01", dec!(175)), ("2026-02", dec!(30))]);
+        // The projection covers the 12 months before today regardless of the range, so the
+        // June 2025 dividend counts.
+        let projection = by_month.projection.unwrap();
+        assert_eq!(projection.annual, dec!(230));
+        assert_eq!(
+            projection.based_on_from,
+            NaiveDate::from_ymd_opt(2025, 3, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn projection_covers_exactly_the_last_twelve_months() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let rows = vec![
+            dividend("2025-03-05", None, "RY", "TFSA", dec!(999)),
+            dividend("2025-03-20", None, "RY", "TFSA", dec!(999)),
+            dividend("2025-03-21", None, "RY", "TFSA", dec!(60)),
+            dividend("2026-03-20", None, "RY", "TFSA", dec!(60)),
+        ];
+        let mut projection_query = query(DividendGroupBy::Symbol);
+        projection_query.include_projection = true;
+
+        let projection = build_dividend_summary(&rows, &projection_query, "CAD", to_cad, today)
+            .projection
+            .unwrap();
+
+        assert_eq!(
+            projection.based_on_from,
+            NaiveDate::from_ymd_opt(2025, 3, 21).unwrap()
+        );
+        assert_eq!(projection.annual, dec!(120));
+        assert_eq!(projection.monthly, dec!(10));
+    }
+}
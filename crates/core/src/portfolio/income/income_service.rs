@@ -6,7 +6,7 @@ use crate::{
 };
 use chrono::{Datelike, NaiveDate};
 
-use super::IncomeSummary;
+use super::{build_dividend_summary, DividendSummary, DividendSummaryQuery, IncomeSummary};
 use crate::fx::FxServiceTrait;
 use log::{debug, error};
 use num_traits::Zero;
@@ -15,6 +15,12 @@ use std::sync::{Arc, RwLock};
 // Define the trait for the income service
 pub trait IncomeServiceTrait: Send + Sync {
     fn get_income_summary(&self, account_ids: Option<&[String]>) -> Result<Vec<IncomeSummary>>;
+    /// Dividend totals in the base currency; all accounts when `account_ids` is `None`.
+    fn get_dividend_summary(
+        &self,
+        query: &DividendSummaryQuery,
+        account_ids: Option<&[String]>,
+    ) -> Result<DividendSummary>;
 }
 
 pub struct IncomeService {
@@ -161,7 +167,9 @@ impl IncomeServiceTrait for IncomeService {
             // Create a copy of the activity with cloned fields to avoid ownership issues
             let activity_copy = IncomeData {
                 date: activity.date.clone(),
+                activity_date: activity.activity_date.clone(),
                 income_type: activity.income_type.clone(),
+                subtype: activity.subtype.clone(),
                 asset_id: activity.asset_id.clone(),
                 asset_kind: activity.asset_kind.clone(),
                 symbol: activity.symbol.clone(),
@@ -247,4 +255,43 @@ impl IncomeServiceTrait for IncomeService {
         debug!("Income summary calculation and rounding completed successfully");
         Ok(rounded_summaries)
     }
+
+    fn get_dividend_summary(
+        &self,
+        query: &DividendSummaryQuery,
+        account_ids: Option<&[String]>,
+    ) -> Result<DividendSummary> {
+        // An empty scope has no dividends, unlike `None`, which is every account.
+        let income = if account_ids.is_some_and(|ids| ids.is_empty()) {
+            Vec::new()
+        } else {
+            self.activity_repository
+                .get_income_activities_data(account_ids)
+                .map_err(|e| {
+                    error!("Error getting dividend data: {:?}", e);
+                    Error::Activity(ActivityError::InvalidData(e.to_string()))
+                })?
+        };
+        let base_currency = self.base_currency.read().unwrap().clone();
+
+        let convert = |amount: Decimal, currency: &str, paid_on: NaiveDate| {
+            self.fx_service
+                .convert_currency_for_date(amount, currency, &base_currency, paid_on)
+                .or_else(|_| {
+                    self.fx_service
+                        .convert_currency(amount, currency, &base_currency)
+                })
+                .unwrap_or_else(|e| {
+                    error!("Error converting dividend currency: {:?}", e);
+                    amount
+                })
+        };
+        Ok(build_dividend_summary(
+            &income,
+            query,
+            &base_currency,
+            convert,
+            self.today_in_user_timezone(),
+        ))
+    }
 }
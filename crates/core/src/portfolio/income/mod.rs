@@ -1,5 +1,10 @@
+pub mod dividend_summary;
 pub mod income_model;
 pub mod income_service;
 
+pub use dividend_summary::{
+    build_dividend_summary, DividendGroup, DividendGroupBy, DividendProjection, DividendSummary,
+    DividendSummaryQuery,
+};
 pub use income_model::*;
 pub use income_service::{IncomeService, IncomeServiceTrait};
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Kind of inconsistency found while replaying an account's activities.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LedgerIssueKind {
+    /// A sell or transfer out of an asset the account never held before that date.
+    SellWithoutHolding,
+    /// A sell or transfer out larger than the quantity held, leaving the position negative.
+    NegativeQuantity,
+    /// The stored holdings snapshot does not match the quantity the activities add up to.
+    HoldingMismatch,
+    /// The holdings calculator rejected the activity, so it has no effect on holdings.
+    RejectedActivity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerIssue {
+    pub kind: LedgerIssueKind,
+    pub account_id: String,
+    pub asset_id: String,
+    pub message: String,
+    /// Activities that caused the issue, oldest first.
+    pub activity_ids: Vec<String>,
+    /// Quantity implied by the activities.
+    pub expected_quantity: Option<Decimal>,
+    /// Quantity after the offending activity, or the stored snapshot quantity.
+    pub actual_quantity: Option<Decimal>,
+    /// Whether recomputing the account's holdings resolves the issue.
+    pub fixable: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerValidationReport {
+    pub checked_accounts: usize,
+    pub checked_activities: usize,
+    pub issues: Vec<LedgerIssue>,
+    /// Accounts queued for a holdings recompute when the fix was requested.
+    #[serde(default)]
+    pub recalculated_account_ids: Vec<String>,
+}
+
+impl LedgerValidationReport {
+    /// Accounts with issues that a holdings recompute resolves.
+    pub fn fixable_account_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .issues
+            .iter()
+            .filter(|issue| issue.fixable)
+            .map(|issue| issue.account_id.clone())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// Counts of this report, without account or asset identities.
+    pub fn summary(&self, checked_at: DateTime<Utc>) -> LedgerValidationSummary {
+        let mut mismatched: Vec<&str> = self
+            .issues
+            .iter()
+            .map(|issue| issue.account_id.as_str())
+            .collect();
+        mismatched.sort_unstable();
+        mismatched.dedup();
+        LedgerValidationSummary {
+            checked_at,
+            checked_accounts: self.checked_accounts,
+            mismatched_accounts: mismatched.len(),
+        }
+    }
+}
+
+/// Outcome of the last ledger validation, as reported in sync diagnostics.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerValidationSummary {
+    pub checked_at: DateTime<Utc>,
+    pub checked_accounts: usize,
+    /// Accounts with at least one issue.
+    pub mismatched_accounts: usize,
+}
@@ -0,0 +1,92 @@
+//! Service that validates the whole activity ledger.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::accounts::AccountServiceTrait;
+use crate::activities::ActivityRepositoryTrait;
+use crate::errors::Result;
+use crate::portfolio::snapshot::{HoldingsCalculator, SnapshotServiceTrait};
+
+use super::{validate_ledger, LedgerValidationReport, LedgerValidationSummary};
+
+#[async_trait]
+pub trait LedgerValidationServiceTrait: Send + Sync {
+    /// Checks every active account's activities for internal consistency. Read-only; callers
+    /// that want the fix queue a holdings recompute for
+    /// [`LedgerValidationReport::fixable_account_ids`].
+    async fn validate_ledger(&self) -> Result<LedgerValidationReport>;
+
+    /// Summary of the most recent [`validate_ledger`](Self::validate_ledger) run since startup.
+    fn last_summary(&self) -> Option<LedgerValidationSummary>;
+}
+
+pub struct LedgerValidationService {
+    account_service: Arc<dyn AccountServiceTrait>,
+    activity_repository: Arc<dyn ActivityRepositoryTrait>,
+    snapshot_service: Arc<dyn SnapshotServiceTrait>,
+    holdings_calculator: HoldingsCalculator,
+    last_summary: RwLock<Option<LedgerValidationSummary>>,
+}
+
+impl LedgerValidationService {
+    pub fn new(
+        account_service: Arc<dyn AccountServiceTrait>,
+        activity_repository: Arc<dyn ActivityRepositoryTrait>,
+        snapshot_service: Arc<dyn SnapshotServiceTrait>,
+        holdings_calculator: HoldingsCalculator,
+    ) -> Self {
+        Self {
+            account_service,
+            activity_repository,
+            snapshot_service,
+            holdings_calculator,
+            last_summary: RwLock::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl LedgerValidationServiceTrait for LedgerValidationService {
+    async fn validate_ledger(&self) -> Result<LedgerValidationReport> {
+        let accounts = self.account_service.get_active_accounts()?;
+        let activities = self.activity_repository.get_activities()?;
+
+        let mut latest_snapshots = HashMap::new();
+        for account in &accounts {
+            if let Some(snapshot) = self
+                .snapshot_service
+                .get_latest_holdings_snapshot(&account.id)?
+            {
+                latest_snapshots.insert(account.id.clone(), snapshot);
+            }
+        }
+
+        let checked_activities = activities
+            .iter()
+            .filter(|activity| accounts.iter().any(|a| a.id == activity.account_id))
+            .count();
+        let issues = validate_ledger(
+            &self.holdings_calculator,
+            &accounts,
+            &activities,
+            &latest_snapshots,
+        );
+
+        let report = LedgerValidationReport {
+            checked_accounts: accounts.len(),
+            checked_activities,
+            issues,
+            recalculated_account_ids: Vec::new(),
+        };
+        *self.last_summary.write().unwrap() = Some(report.summary(Utc::now()));
+        Ok(report)
+    }
+
+    fn last_summary(&self) -> Option<LedgerValidationSummary> {
+        *self.last_summary.read().unwrap()
+    }
+}
@@ -0,0 +1,563 @@
+//! Replays the activity ledger through the holdings calculator and reports states that
+//! cannot happen.
+//!
+//! Each account's posted activities are fed to [`HoldingsCalculator`] one at a time, so
+//! quantities, splits, adjustments and short positions follow exactly the rules used to build
+//! holdings. An activity the calculator rejects is reported, as is a sell or transfer out the
+//! calculator could only apply in part because the position did not hold enough units. For
+//! transaction-tracked accounts the replayed quantities are also compared with the latest stored
+//! holdings snapshot; a mismatch there means the snapshot is stale and recomputing holdings fixes
+//! it.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use rust_decimal::Decimal;
+
+use crate::accounts::{Account, TrackingMode};
+use crate::activities::{Activity, ACTIVITY_TYPE_SELL, ACTIVITY_TYPE_TRANSFER_OUT};
+use crate::portfolio::snapshot::{AccountStateSnapshot, HoldingsCalculator, ProjectionRun};
+
+use super::{LedgerIssue, LedgerIssueKind};
+
+/// Quantity differences below this are rounding noise, not a mismatch.
+fn quantity_tolerance() -> Decimal {
+    Decimal::new(1, 6)
+}
+
+fn position_quantity(state: &AccountStateSnapshot, asset_id: &str) -> Decimal {
+    state
+        .positions
+        .get(asset_id)
+        .map_or(Decimal::ZERO, |position| position.quantity)
+}
+
+/// Checks `activities` against each other and against `latest_snapshots` (account id to the
+/// latest holdings snapshot). Only the given accounts are checked.
+pub fn validate_ledger(
+    calculator: &HoldingsCalculator,
+    accounts: &[Account],
+    activities: &[Activity],
+    latest_snapshots: &HashMap<String, AccountStateSnapshot>,
+) -> Vec<LedgerIssue> {
+    let mut by_account: HashMap<&str, Vec<&Activity>> = HashMap::new();
+    for activity in activities.iter().filter(|activity| activity.is_posted()) {
+        by_account
+            .entry(activity.account_id.as_str())
+            .or_default()
+            .push(activity);
+    }
+
+    // One run for the whole ledger, so paired transfers move lots between accounts.
+    let mut run = ProjectionRun::new();
+    let mut issues = Vec::new();
+    for account in accounts {
+        let mut ordered = by_account.remove(account.id.as_str()).unwrap_or_default();
+        ordered.sort_by_key(|activity| (activity.activity_date, activity.created_at));
+
+        let replay = replay_account(calculator, &mut run, account, &ordered);
+        issues.extend(replay.issues);
+
+        if account.tracking_mode != TrackingMode::Transactions {
+            continue;
+        }
+        let Some(snapshot) = latest_snapshots.get(&account.id) else {
+            continue;
+        };
+        let asset_ids: BTreeSet<&str> = replay
+            .state
+            .positions
+            .keys()
+            .chain(snapshot.positions.keys())
+            .map(String::as_str)
+            .collect();
+        for asset_id in asset_ids {
+            // The activities themselves are inconsistent; a recompute would not settle it.
+            if replay.inconsistent_assets.contains(asset_id) {
+                continue;
+            }
+            let expected = position_quantity(&replay.state, asset_id);
+            let actual = position_quantity(snapshot, asset_id);
+            if (expected - actual).abs() <= quantity_tolerance() {
+                continue;
+            }
+            issues.push(LedgerIssue {
+                kind: LedgerIssueKind::HoldingMismatch,
+                account_id: account.id.clone(),
+                asset_id: asset_id.to_string(),
+                message: format!(
+                    "Holdings show {} units of {} but activities add up to {}",
+                    actual, asset_id, expected
+                ),
+                activity_ids: Vec::new(),
+                expected_quantity: Some(expected),
+                actual_quantity: Some(actual),
+                fixable: true,
+            });
+        }
+    }
+
+    issues
+}
+
+struct AccountReplay {
+    state: AccountStateSnapshot,
+    issues: Vec<LedgerIssue>,
+    inconsistent_assets: HashSet<String>,
+}
+
+fn replay_account(
+    calculator: &HoldingsCalculator,
+    run: &mut ProjectionRun,
+    account: &Account,
+    ordered: &[&Activity],
+) -> AccountReplay {
+    let mut state = AccountStateSnapshot {
+        account_id: account.id.clone(),
+        currency: account.currency.clone(),
+        ..Default::default()
+    };
+    let mut issues = Vec::new();
+    // Overdraws are reported once per asset, listing every offending activity.
+    let mut overdraws: BTreeMap<String, LedgerIssue> = BTreeMap::new();
+    let mut inconsistent_assets = HashSet::new();
+
+    for activity in ordered {
+        let asset_id = activity.asset_id.clone().filter(|id| !id.is_empty());
+        let before = asset_id
+            .as_deref()
+            .map_or(Decimal::ZERO, |id| position_quantity(&state, id));
+
+        let result = match calculator.calculate_next_holdings_for_account_type(
+            run,
+            &state,
+            std::slice::from_ref(*activity),
+            calculator.activity_local_date(activity),
+            Some(&account.account_type),
+        ) {
+            Ok(result) => result,
+            Err(e) => {
+                issues.push(rejected_activity(account, activity, e.to_string()));
+                inconsistent_assets.extend(asset_id);
+                continue;
+            }
+        };
+        let rejected = !result.warnings.is_empty();
+        for warning in result.warnings {
+            issues.push(rejected_activity(account, activity, warning.message));
+            inconsistent_assets.extend(asset_id.clone());
+        }
+        state = result.snapshot;
+        // A rejected activity left the position as it was; that is not an overdraw.
+        if rejected {
+            continue;
+        }
+
+        let Some(asset_id) = asset_id else {
+            continue;
+        };
+        if !matches!(
+            activity.effective_type(),
+            ACTIVITY_TYPE_SELL | ACTIVITY_TYPE_TRANSFER_OUT
+        ) {
+            continue;
+        }
+        let requested = before - activity.qty();
+        if position_quantity(&state, &asset_id) - requested <= quantity_tolerance() {
+            continue;
+        }
+
+        // The calculator only disposed of what the position held.
+        inconsistent_assets.insert(asset_id.clone());
+        overdraws
+            .entry(asset_id.clone())
+            .and_modify(|issue| issue.activity_ids.push(activity.id.clone()))
+            .or_insert_with(|| {
+                let (kind, message) = if before <= quantity_tolerance() {
+                    (
+                        LedgerIssueKind::SellWithoutHolding,
+                        format!(
+                            "{} is sold or transferred out without being held first",
+                            asset_id
+                        ),
+                    )
+                } else {
+                    (
+                        LedgerIssueKind::NegativeQuantity,
+                        format!(
+                            "{} is sold or transferred out beyond the {} units held",
+                            asset_id, before
+                        ),
+                    )
+                };
+                LedgerIssue {
+                    kind,
+                    account_id: account.id.clone(),
+                    asset_id: asset_id.clone(),
+                    message,
+                    activity_ids: vec![activity.id.clone()],
+                    expected_quantity: Some(before),
+                    actual_quantity: Some(requested),
+                    fixable: false,
+                }
+            });
+    }
+
+    issues.extend(overdraws.into_values());
+    AccountReplay {
+        state,
+        issues,
+        inconsistent_assets,
+    }
+}
+
+fn rejected_activity(account: &Account, activity: &Activity, reason: String) -> LedgerIssue {
+    LedgerIssue {
+        kind: LedgerIssueKind::RejectedActivity,
+        account_id: account.id.clone(),
+        asset_id: activity.asset_id.clone().unwrap_or_default(),
+        message: reason,
+        activity_ids: vec![activity.id.clone()],
+        expected_quantity: None,
+        actual_quantity: None,
+        fixable: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activities::{
+        ActivityStatus, ACTIVITY_SUBTYPE_POSITION_OPEN, ACTIVITY_TYPE_BUY, ACTIVITY_TYPE_SPLIT,
+    };
+    use crate::assets::{
+        Asset, AssetKind, AssetRepositoryTrait, NewAsset, QuoteMode, UpdateAssetProfile,
+    };
+    use crate::errors::Result;
+    use crate::fx::{ExchangeRate, FxServiceTrait, NewExchangeRate};
+    use crate::portfolio::ledger::LedgerValidationReport;
+    use crate::portfolio::snapshot::Position;
+    use async_trait::async_trait;
+    use chrono::{DateTime, NaiveDate, Utc};
+    use rust_decimal_macros::dec;
+    use std::sync::{Arc, RwLock};
+
+    const AAPL: &str = "SEC:AAPL:XNAS";
+
+    struct SingleAssetRepository;
+
+    #[async_trait]
+    impl AssetRepositoryTrait for SingleAssetRepository {
+        async fn create(&self, _: NewAsset) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn create_batch(&self, _: Vec<NewAsset>) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        async fn update_profile(&self, _: &str, _: UpdateAssetProfile) -> Result<Asset> {
+            unimplemented!()
+        }
+        async fn update_quote_mode(&self, _: &str, _: &str) -> Result<Asset> {
+            unimplemented!()
+        }
+        fn get_by_id(&self, asset_id: &str) -> Result<Asset> {
+            Ok(Asset {
+                id: asset_id.to_string(),
+                kind: AssetKind::Investment,
+                quote_ccy: "USD".to_string(),
+                quote_mode: QuoteMode::Market,
+                ..Default::default()
+            })
+        }
+        fn list(&self) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        fn list_by_asset_ids(&self, asset_ids: &[String]) -> Result<Vec<Asset>> {
+            asset_ids.iter().map(|id| self.get_by_id(id)).collect()
+        }
+        async fn delete(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        fn search_by_symbol(&self, _: &str) -> Result<Vec<Asset>> {
+            unimplemented!()
+        }
+        fn find_by_instrument_key(&self, _: &str) -> Result<Option<Asset>> {
+            Ok(None)
+        }
+        async fn cleanup_legacy_metadata(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn deactivate(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn reactivate(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn copy_user_metadata(&self, _: &str, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn deactivate_orphaned_investments(&self) -> Result<Vec<String>> {
+            unimplemented!()
+        }
+    }
+
+    /// Every test uses a single currency, so every rate is one.
+    struct UnitFxService;
+
+    #[async_trait]
+    impl FxServiceTrait for UnitFxService {
+        fn initialize(&self) -> Result<()> {
+            Ok(())
+        }
+        fn get_historical_rates(&self, _: &str, _: &str, _: i64) -> Result<Vec<ExchangeRate>> {
+            unimplemented!()
+        }
+        fn get_latest_exchange_rate(&self, _: &str, _: &str) -> Result<Decimal> {
+            Ok(Decimal::ONE)
+        }
+        fn get_exchange_rate_for_date(&self, _: &str, _: &str, _: NaiveDate) -> Result<Decimal> {
+            Ok(Decimal::ONE)
+        }
+        fn convert_currency(&self, amount: Decimal, _: &str, _: &str) -> Result<Decimal> {
+            Ok(amount)
+        }
+        fn convert_currency_for_date(
+            &self,
+            amount: Decimal,
+            _: &str,
+            _: &str,
+            _: NaiveDate,
+        ) -> Result<Decimal> {
+            Ok(amount)
+        }
+        fn get_latest_exchange_rates(&self) -> Result<Vec<ExchangeRate>> {
+            unimplemented!()
+        }
+        async fn add_exchange_rate(&self, _: NewExchangeRate) -> Result<ExchangeRate> {
+            unimplemented!()
+        }
+        async fn update_exchange_rate(&self, _: &str, _: &str, _: Decimal) -> Result<ExchangeRate> {
+            unimplemented!()
+        }
+        async fn delete_exchange_rate(&self, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn register_currency_pair(&self, _: &str, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn register_currency_pair_manual(&self, _: &str, _: &str) -> Result<()> {
+            unimplemented!()
+        }
+        async fn ensure_fx_pairs(&self, _: Vec<(String, String)>) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    fn calculator() -> HoldingsCalculator {
+        HoldingsCalculator::new(
+            Arc::new(UnitFxService),
+            Arc::new(RwLock::new("USD".to_string())),
+            Arc::new(SingleAssetRepository),
+        )
+    }
+
+    fn account(id: &str) -> Account {
+        Account {
+            id: id.to_string(),
+            name: id.to_string(),
+            currency: "USD".to_string(),
+            tracking_mode: TrackingMode::Transactions,
+            ..Default::default()
+        }
+    }
+
+    fn activity(id: &str, activity_type: &str, date: &str, quantity: Decimal) -> Activity {
+        let activity_date = DateTime::parse_from_rfc3339(&format!("{}T00:00:00Z", date))
+            .unwrap()
+            .with_timezone(&Utc);
+        Activity {
+            id: id.to_string(),
+            account_id: "acc-1".to_string(),
+            asset_id: Some(AAPL.to_string()),
+            activity_type: activity_type.to_string(),
+            activity_type_override: None,
+            source_type: None,
+            subtype: None,
+            status: ActivityStatus::Posted,
+            activity_date,
+            settlement_date: None,
+            quantity: Some(quantity),
+            unit_price: Some(dec!(100)),
+            amount: None,
+            fee: None,
+            tax: None,
+            currency: "USD".to_string(),
+            fx_rate: None,
+            notes: None,
+            metadata: None,
+            source_system: None,
+            source_record_id: None,
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+            is_user_modified: false,
+            needs_review: false,
+            created_at: activity_date,
+            updated_at: activity_date,
+        }
+    }
+
+    fn snapshot(account_id: &str, quantity: Decimal) -> AccountStateSnapshot {
+        let mut positions = HashMap::new();
+        positions.insert(
+            AAPL.to_string(),
+            Position {
+                asset_id: AAPL.to_string(),
+                quantity,
+                ..Default::default()
+            },
+        );
+        AccountStateSnapshot {
+            account_id: account_id.to_string(),
+            snapshot_date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            positions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sell_exceeding_holdings_is_reported_with_the_offending_activity() {
+        let activities = vec![
+            activity("a1", ACTIVITY_TYPE_BUY, "2024-01-02", dec!(10)),
+            activity("a2", ACTIVITY_TYPE_SELL, "2024-02-01", dec!(15)),
+        ];
+
+        let issues = validate_ledger(
+            &calculator(),
+            &[account("acc-1")],
+            &activities,
+            &HashMap::new(),
+        );
+
+        assert_eq!(issues.len(), 1);
+        let issue = &issues[0];
+        assert_eq!(issue.kind, LedgerIssueKind::NegativeQuantity);
+        assert_eq!(issue.asset_id, AAPL);
+        assert_eq!(issue.activity_ids, vec!["a2".to_string()]);
+        assert_eq!(issue.expected_quantity, Some(dec!(10)));
+        assert_eq!(issue.actual_quantity, Some(dec!(-5)));
+        assert!(!issue.fixable);
+    }
+
+    #[test]
+    fn sell_before_any_buy_is_a_sell_without_holding() {
+        // Dates, not insertion order, decide the replay order.
+        let activities = vec![
+            activity("a1", ACTIVITY_TYPE_BUY, "2024-03-01", dec!(10)),
+            activity("a2", ACTIVITY_TYPE_SELL, "2024-02-01", dec!(5)),
+        ];
+
+        let issues = validate_ledger(
+            &calculator(),
+            &[account("acc-1")],
+            &activities,
+            &HashMap::new(),
+        );
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, LedgerIssueKind::SellWithoutHolding);
+        assert_eq!(issues[0].activity_ids, vec!["a2".to_string()]);
+    }
+
+    #[test]
+    fn explicit_short_open_is_not_an_issue() {
+        let mut short = activity("a1", ACTIVITY_TYPE_SELL, "2024-01-02", dec!(5));
+        short.subtype = Some(ACTIVITY_SUBTYPE_POSITION_OPEN.to_string());
+
+        let issues = validate_ledger(
+            &calculator(),
+            &[account("acc-1")],
+            &[short],
+            &HashMap::new(),
+        );
+
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn activity_the_calculator_rejects_is_reported() {
+        // Opening a short while long is rejected and leaves the position untouched.
+        let mut short = activity("a2", ACTIVITY_TYPE_SELL, "2024-02-01", dec!(5));
+        short.subtype = Some(ACTIVITY_SUBTYPE_POSITION_OPEN.to_string());
+        let activities = vec![
+            activity("a1", ACTIVITY_TYPE_BUY, "2024-01-02", dec!(10)),
+            short,
+        ];
+        let mut snapshots = HashMap::new();
+        snapshots.insert("acc-1".to_string(), snapshot("acc-1", dec!(5)));
+
+        let issues = validate_ledger(&calculator(), &[account("acc-1")], &activities, &snapshots);
+
+        // The asset is already reported, so the stale snapshot adds no mismatch on top.
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, LedgerIssueKind::RejectedActivity);
+        assert_eq!(issues[0].activity_ids, vec!["a2".to_string()]);
+        assert!(!issues[0].fixable);
+    }
+
+    #[test]
+    fn stale_snapshot_is_a_fixable_mismatch() {
+        let mut split = activity("a3", ACTIVITY_TYPE_SPLIT, "2024-03-01", dec!(0));
+        split.amount = Some(dec!(2));
+        let activities = vec![
+            activity("a1", ACTIVITY_TYPE_BUY, "2024-01-02", dec!(10)),
+            activity("a2", ACTIVITY_TYPE_SELL, "2024-02-01", dec!(4)),
+            split,
+        ];
+        let accounts = [account("acc-1")];
+
+        let mut snapshots = HashMap::new();
+        snapshots.insert("acc-1".to_string(), snapshot("acc-1", dec!(12)));
+        assert!(validate_ledger(&calculator(), &accounts, &activities, &snapshots).is_empty());
+
+        snapshots.insert("acc-1".to_string(), snapshot("acc-1", dec!(6)));
+        let issues = validate_ledger(&calculator(), &accounts, &activities, &snapshots);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, LedgerIssueKind::HoldingMismatch);
+        assert_eq!(issues[0].expected_quantity, Some(dec!(12)));
+        assert_eq!(issues[0].actual_quantity, Some(dec!(6)));
+        assert!(issues[0].fixable);
+
+        // Holdings-tracked accounts keep their own snapshots; there is nothing to reconcile.
+        let mut holdings_account = account("acc-1");
+        holdings_account.tracking_mode = TrackingMode::Holdings;
+        assert!(
+            validate_ledger(&calculator(), &[holdings_account], &activities, &snapshots).is_empty()
+        );
+    }
+
+    #[test]
+    fn summary_counts_each_mismatched_account_once() {
+        let issue = |activity_id: &str| LedgerIssue {
+            kind: LedgerIssueKind::SellWithoutHolding,
+            account_id: "acc-1".to_string(),
+            asset_id: AAPL.to_string(),
+            message: "Sell without holding".to_string(),
+            activity_ids: vec![activity_id.to_string()],
+            expected_quantity: None,
+            actual_quantity: None,
+            fixable: false,
+        };
+        let report = LedgerValidationReport {
+            checked_accounts: 2,
+            checked_activities: 3,
+            issues: vec![issue("a1"), issue("a2")],
+            recalculated_account_ids: Vec::new(),
+        };
+
+        let checked_at = Utc::now();
+        let summary = report.summary(checked_at);
+        assert_eq!(summary.checked_at, checked_at);
+        assert_eq!(summary.checked_accounts, 2);
+        assert_eq!(summary.mismatched_accounts, 1);
+    }
+}
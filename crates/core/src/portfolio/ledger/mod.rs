@@ -0,0 +1,7 @@
+pub mod ledger_model;
+pub mod ledger_service;
+pub mod ledger_validator;
+
+pub use ledger_model::*;
+pub use ledger_service::{LedgerValidationService, LedgerValidationServiceTrait};
+pub use ledger_validator::validate_ledger;
@@ -1,9 +1,12 @@
 pub mod allocation;
 pub mod allocation_targets;
+pub mod analytics_warmup;
 pub mod economic_events;
 pub mod fire;
+pub mod gains;
 pub mod holdings;
 pub mod income;
+pub mod ledger;
 pub mod net_worth;
 pub mod performance;
 pub mod snapshot;
@@ -6199,6 +6199,18 @@ mod tests {
             ))
         }
 
+        async fn bulk_upsert_removing_stale(
+            &self,
+            _account_id: &str,
+            _activities: Vec<crate::activities::ActivityUpsert>,
+            _select_stale: crate::activities::StaleActivitySelector,
+        ) -> Result<crate::activities::ActivityReconcileResult> {
+            Err(errors::Error::Unexpected(
+                "TestActivityRepository::bulk_upsert_removing_stale should not be called"
+                    .to_string(),
+            ))
+        }
+
         async fn reassign_asset(&self, _old_asset_id: &str, _new_asset_id: &str) -> Result<u32> {
             Err(errors::Error::Unexpected(
                 "TestActivityRepository::reassign_asset should not be called".to_string(),
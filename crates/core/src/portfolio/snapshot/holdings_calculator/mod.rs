@@ -277,7 +277,7 @@ impl HoldingsCalculator {
             .unwrap_or_else(|| self.fx_rate_to_base(from_currency, base_currency, date))
     }
 
-    fn activity_local_date(&self, activity: &Activity) -> NaiveDate {
+    pub(crate) fn activity_local_date(&self, activity: &Activity) -> NaiveDate {
         self.activity_local_date_from_utc(activity.activity_date)
     }
 
@@ -565,6 +565,15 @@ mod tests {
             unimplemented!()
         }
 
+        async fn bulk_upsert_removing_stale(
+            &self,
+            _account_id: &str,
+            _activities: Vec<crate::activities::ActivityUpsert>,
+            _select_stale: crate::activities::StaleActivitySelector,
+        ) -> AppResult<crate::activities::ActivityReconcileResult> {
+            unimplemented!()
+        }
+
         async fn reassign_asset(&self, _old_asset_id: &str, _new_asset_id: &str) -> AppResult<u32> {
             Ok(0)
         }
@@ -802,6 +811,15 @@ mod tests {
             unimplemented!()
         }
 
+        async fn bulk_upsert_removing_stale(
+            &self,
+            _account_id: &str,
+            _activities: Vec<crate::activities::ActivityUpsert>,
+            _select_stale: crate::activities::StaleActivitySelector,
+        ) -> AppResult<crate::activities::ActivityReconcileResult> {
+            unimplemented!()
+        }
+
         async fn reassign_asset(&self, _old_asset_id: &str, _new_asset_id: &str) -> AppResult<u32> {
             Ok(0)
         }
@@ -1,4 +1,4 @@
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use log::warn;
 use rust_decimal::Decimal;
 use std::collections::{HashMap, HashSet};
@@ -25,6 +25,36 @@ const MISSING_QUOTE_WARNING: &str =
     "Some market prices are missing, so this value may be incomplete.";
 const MISSING_FX_WARNING: &str =
     "Some exchange rates are missing, so this value may be approximate.";
+const STALE_QUOTE_WARNING: &str =
+    "Some market prices are out of date, so this value may not reflect current prices.";
+
+/// Default age after which a cached quote is considered stale. Long enough to cover a
+/// weekend plus a market holiday without flagging last-close prices.
+pub const DEFAULT_STALE_QUOTE_MAX_AGE_HOURS: i64 = 96;
+
+/// Controls when cached quotes used in a current valuation are flagged as stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleQuotePolicy {
+    pub max_quote_age: Duration,
+}
+
+impl StaleQuotePolicy {
+    pub fn from_hours(hours: i64) -> Self {
+        Self {
+            max_quote_age: Duration::hours(hours),
+        }
+    }
+
+    fn is_stale(&self, quote_age: Duration) -> bool {
+        quote_age > self.max_quote_age
+    }
+}
+
+impl Default for StaleQuotePolicy {
+    fn default() -> Self {
+        Self::from_hours(DEFAULT_STALE_QUOTE_MAX_AGE_HOURS)
+    }
+}
 
 pub struct CurrentAccountValuationService<'a> {
     account_service: &'a dyn AccountServiceTrait,
@@ -32,6 +62,7 @@ pub struct CurrentAccountValuationService<'a> {
     asset_service: &'a dyn AssetServiceTrait,
     quote_service: &'a dyn QuoteServiceTrait,
     fx_service: &'a dyn FxServiceTrait,
+    stale_quote_policy: StaleQuotePolicy,
 }
 
 impl<'a> CurrentAccountValuationService<'a> {
@@ -48,9 +79,15 @@ impl<'a> CurrentAccountValuationService<'a> {
             asset_service,
             quote_service,
             fx_service,
+            stale_quote_policy: StaleQuotePolicy::default(),
         }
     }
 
+    pub fn with_stale_quote_policy(mut self, policy: StaleQuotePolicy) -> Self {
+        self.stale_quote_policy = policy;
+        self
+    }
+
     pub async fn get_current_valuation_for_scope(
         &self,
         scope_id: &str,
@@ -96,7 +133,7 @@ impl<'a> CurrentAccountValuationService<'a> {
             self.load_latest_quotes(&snapshots, &assets_by_id, latest_snapshot_cutoff)?;
         let mut fx_cache = FxRateCache::new(self.fx_service);
 
-        Ok(calculate_current_valuation_response_from_snapshots(
+        Ok(calculate_current_valuation_response_with_policy(
             scope_id,
             &accounts,
             &snapshots,
@@ -105,6 +142,7 @@ impl<'a> CurrentAccountValuationService<'a> {
             base_currency,
             calculated_at,
             include_accounts,
+            &self.stale_quote_policy,
             |from, to| fx_cache.get(from, to),
         ))
     }
@@ -222,6 +260,37 @@ pub fn calculate_current_valuation_response_from_snapshots<F, R>(
     base_currency: &str,
     calculated_at: DateTime<Utc>,
     include_accounts: bool,
+    latest_rate: F,
+) -> CurrentValuationResponse
+where
+    F: FnMut(&str, &str) -> R,
+    R: Into<CurrentValuationRate>,
+{
+    calculate_current_valuation_response_with_policy(
+        scope_id,
+        accounts,
+        snapshots,
+        assets_by_id,
+        latest_quote_pairs,
+        base_currency,
+        calculated_at,
+        include_accounts,
+        &StaleQuotePolicy::default(),
+        latest_rate,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_current_valuation_response_with_policy<F, R>(
+    scope_id: &str,
+    accounts: &[Account],
+    snapshots: &HashMap<String, AccountStateSnapshot>,
+    assets_by_id: &HashMap<String, Asset>,
+    latest_quote_pairs: &HashMap<String, LatestQuotePair>,
+    base_currency: &str,
+    calculated_at: DateTime<Utc>,
+    include_accounts: bool,
+    stale_quote_policy: &StaleQuotePolicy,
     mut latest_rate: F,
 ) -> CurrentValuationResponse
 where
@@ -241,6 +310,8 @@ where
         source_data_as_of: None,
         calculated_at,
         warnings: Vec::new(),
+        stale: false,
+        oldest_quote_age_seconds: None,
     };
     let mut currency_base_totals: HashMap<String, Decimal> = HashMap::new();
     let mut cash_base_totals: HashMap<String, Decimal> = HashMap::new();
@@ -256,6 +327,7 @@ where
                 latest_quote_pairs,
                 base_currency,
                 calculated_at,
+                stale_quote_policy,
                 &mut latest_rate,
             ),
             None => AccountValuationComputation {
@@ -276,6 +348,11 @@ where
             summary.source_data_as_of,
             computation.valuation.source_data_as_of,
         );
+        summary.stale |= computation.valuation.stale;
+        summary.oldest_quote_age_seconds = max_age(
+            summary.oldest_quote_age_seconds,
+            computation.valuation.oldest_quote_age_seconds,
+        );
         merge_totals(&mut currency_base_totals, computation.currency_base_totals);
         merge_totals(&mut cash_base_totals, computation.cash_base_totals);
         merge_totals(&mut cash_local_totals, computation.cash_local_totals);
@@ -347,6 +424,7 @@ struct AccountValuationComputation {
     summary_warnings: Vec<String>,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn calculate_account_snapshot_valuation_with_contributions<F, R>(
     account: &Account,
     snapshot: &AccountStateSnapshot,
@@ -354,6 +432,7 @@ fn calculate_account_snapshot_valuation_with_contributions<F, R>(
     latest_quote_pairs: &HashMap<String, LatestQuotePair>,
     base_currency: &str,
     calculated_at: DateTime<Utc>,
+    stale_quote_policy: &StaleQuotePolicy,
     latest_rate: &mut F,
 ) -> AccountValuationComputation
 where
@@ -440,6 +519,16 @@ where
             valuation.source_data_as_of,
             Some(quote_pair.latest.timestamp),
         );
+        let quote_age = calculated_at - quote_pair.latest.timestamp;
+        valuation.oldest_quote_age_seconds = max_age(
+            valuation.oldest_quote_age_seconds,
+            Some(quote_age.num_seconds()),
+        );
+        if stale_quote_policy.is_stale(quote_age) {
+            valuation.stale = true;
+            summary_warnings.push(STALE_QUOTE_WARNING.to_string());
+            account_warnings.push(STALE_QUOTE_WARNING.to_string());
+        }
 
         let (normalized_price, normalized_quote_currency) =
             normalize_amount(quote_pair.latest.close, &quote_pair.latest.currency);
@@ -517,6 +606,8 @@ fn zero_current_account_valuation(
         source_data_as_of: None,
         calculated_at,
         warnings: Vec::new(),
+        stale: false,
+        oldest_quote_age_seconds: None,
     }
 }
 
@@ -573,6 +664,8 @@ fn empty_current_valuation_response(
             source_data_as_of: None,
             calculated_at,
             warnings: Vec::new(),
+            stale: false,
+            oldest_quote_age_seconds: None,
         },
         accounts: Vec::new(),
     }
@@ -662,6 +755,13 @@ fn latest_datetime(
     }
 }
 
+fn max_age(current: Option<i64>, candidate: Option<i64>) -> Option<i64> {
+    match (current, candidate) {
+        (Some(current), Some(candidate)) => Some(current.max(candidate)),
+        (current, candidate) => current.or(candidate),
+    }
+}
+
 fn snapshot_source_data_as_of(snapshot: &AccountStateSnapshot) -> DateTime<Utc> {
     DateTime::<Utc>::from_naive_utc_and_offset(snapshot.calculated_at, Utc)
 }
@@ -10,9 +10,10 @@ use crate::{
         economic_events::BasisStatus,
         snapshot::{AccountStateSnapshot, Position, SnapshotSource},
         valuation::{
-            calculate_current_valuation_response_from_snapshots, filter_current_valuation_accounts,
+            calculate_current_valuation_response_from_snapshots,
+            calculate_current_valuation_response_with_policy, filter_current_valuation_accounts,
             unique_account_ids, CurrentValuationRate, DailyAccountValuation, ExternalFlowSource,
-            ValuationStatus,
+            StaleQuotePolicy, ValuationStatus,
         },
     },
     quotes::{LatestQuotePair, Quote},
@@ -729,3 +730,59 @@ fn current_valuation_response_summarizes_scope_and_optionally_returns_accounts()
         Some(DateTime::<Utc>::from_timestamp(1_776_480_000, 0).unwrap())
     );
 }
+
+#[test]
+fn current_valuation_flags_quotes_older_than_stale_threshold() {
+    let account = account("acc-1", "USD");
+    let snapshots = HashMap::from([(
+        "acc-1".to_string(),
+        snapshot(
+            "acc-1",
+            "USD",
+            HashMap::from([(
+                "AAPL".to_string(),
+                position("acc-1", "AAPL", dec!(2), "USD", false),
+            )]),
+            HashMap::new(),
+        ),
+    )]);
+    let assets = HashMap::from([("AAPL".to_string(), asset("AAPL", AssetKind::Investment))]);
+    let quotes = HashMap::from([("AAPL".to_string(), quote_pair("AAPL", dec!(50), "USD"))]);
+    let policy = StaleQuotePolicy::from_hours(24);
+    let quote_as_of = DateTime::<Utc>::from_timestamp(1_776_480_000, 0).unwrap();
+
+    let fresh = calculate_current_valuation_response_with_policy(
+        "account:acc-1",
+        std::slice::from_ref(&account),
+        &snapshots,
+        &assets,
+        &quotes,
+        "USD",
+        quote_as_of + chrono::Duration::hours(2),
+        true,
+        &policy,
+        latest_rate,
+    );
+    assert!(!fresh.summary.stale);
+    assert!(!fresh.accounts[0].stale);
+    assert_eq!(fresh.accounts[0].oldest_quote_age_seconds, Some(2 * 3600));
+
+    let stale = calculate_current_valuation_response_with_policy(
+        "account:acc-1",
+        &[account],
+        &snapshots,
+        &assets,
+        &quotes,
+        "USD",
+        quote_as_of + chrono::Duration::hours(30),
+        true,
+        &policy,
+        latest_rate,
+    );
+    assert!(stale.summary.stale);
+    assert!(stale.accounts[0].stale);
+    assert_eq!(stale.summary.oldest_quote_age_seconds, Some(30 * 3600));
+    assert_eq!(stale.summary.investment_market_value_base, dec!(100));
+    assert!(stale.accounts[0].warnings.iter().any(|warning| warning
+        == "Some market prices are out of date, so this value may not reflect current prices."));
+}
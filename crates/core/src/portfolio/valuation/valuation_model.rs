@@ -380,6 +380,12 @@ pub struct CurrentAccountValuation {
     pub source_data_as_of: Option<DateTime<Utc>>,
     pub calculated_at: DateTime<Utc>,
     pub warnings: Vec<String>,
+    /// True when at least one quote used is older than the stale-quote threshold.
+    #[serde(default)]
+    pub stale: bool,
+    /// Age in seconds of the oldest quote used, measured from `calculated_at`.
+    #[serde(default)]
+    pub oldest_quote_age_seconds: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -406,6 +412,12 @@ pub struct CurrentValuationSummary {
     pub source_data_as_of: Option<DateTime<Utc>>,
     pub calculated_at: DateTime<Utc>,
     pub warnings: Vec<String>,
+    /// True when at least one quote used is older than the stale-quote threshold.
+    #[serde(default)]
+    pub stale: bool,
+    /// Age in seconds of the oldest quote used, measured from `calculated_at`.
+    #[serde(default)]
+    pub oldest_quote_age_seconds: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1,6 +1,8 @@
 pub mod portfolios_model;
 pub mod portfolios_service;
 pub mod portfolios_service_tests;
+#[cfg(test)]
+pub(crate) mod portfolios_test_support;
 pub mod portfolios_traits;
 
 pub use portfolios_model::{
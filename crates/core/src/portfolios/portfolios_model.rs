@@ -133,4 +133,6 @@ pub enum AccountScope {
     /// Ad-hoc list of account IDs (e.g. activity page multi-select).
     #[serde(rename_all = "camelCase")]
     Accounts { account_ids: Vec<String> },
+    /// The default group: non-archived accounts that belong to no saved portfolio.
+    Unassigned,
 }
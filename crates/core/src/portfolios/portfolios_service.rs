@@ -86,6 +86,42 @@ impl PortfolioService {
         ids.retain(|account_id| eligible_ids.contains(account_id));
         Ok(())
     }
+
+    /// An account joins at most one portfolio. Only accounts being added are checked, so
+    /// portfolios that shared accounts before the rule existed keep their members when edited.
+    fn validate_exclusive_membership(
+        &self,
+        current: Option<&PortfolioWithAccounts>,
+        ids: &[String],
+    ) -> Result<()> {
+        let kept: HashSet<&str> = current
+            .map(|portfolio| portfolio.account_ids.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+        let added: HashSet<&str> = ids
+            .iter()
+            .map(String::as_str)
+            .filter(|id| !kept.contains(id))
+            .collect();
+        if added.is_empty() {
+            return Ok(());
+        }
+        for portfolio in self.repository.list()? {
+            if current.is_some_and(|current| current.id == portfolio.id) {
+                continue;
+            }
+            if let Some(id) = portfolio
+                .account_ids
+                .iter()
+                .find(|id| added.contains(id.as_str()))
+            {
+                return Err(Error::Validation(ValidationError::InvalidInput(format!(
+                    "Account '{}' already belongs to portfolio '{}'",
+                    id, portfolio.name
+                ))));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -94,6 +130,7 @@ impl PortfolioServiceTrait for PortfolioService {
         new.name = new.name.trim().to_string();
         new.validate()?;
         self.validate_account_ids_exist(&new.account_ids)?;
+        self.validate_exclusive_membership(None, &new.account_ids)?;
         self.repository
             .create(new)
             .await
@@ -104,6 +141,8 @@ impl PortfolioServiceTrait for PortfolioService {
         update.name = update.name.trim().to_string();
         update.validate()?;
         self.validate_account_ids_exist(&update.account_ids)?;
+        let current = self.repository.get_by_id(&update.id)?;
+        self.validate_exclusive_membership(Some(&current), &update.account_ids)?;
         self.repository
             .update(update)
             .await
@@ -134,9 +173,10 @@ impl PortfolioServiceTrait for PortfolioService {
     ) -> Result<ResolvedAccountScope> {
         let mut ids = match filter {
             AccountScope::Account { account_id } => vec![account_id.clone()],
-            AccountScope::All | AccountScope::Portfolio { .. } | AccountScope::Accounts { .. } => {
-                self.resolve_account_filter(filter)?
-            }
+            AccountScope::All
+            | AccountScope::Portfolio { .. }
+            | AccountScope::Accounts { .. }
+            | AccountScope::Unassigned => self.resolve_account_filter(filter)?,
         };
         ids.sort();
         ids.dedup();
@@ -145,6 +185,7 @@ impl PortfolioServiceTrait for PortfolioService {
 
         let scope_id = match filter {
             AccountScope::All => "all".to_string(),
+            AccountScope::Unassigned => "unassigned".to_string(),
             AccountScope::Account { account_id } => format!("account:{}", account_id),
             AccountScope::Portfolio { portfolio_id } => format!("portfolio:{}", portfolio_id),
             AccountScope::Accounts { .. } => {
@@ -29,6 +29,25 @@ mod tests {
                 ..Default::default()
             }
         }
+
+        fn with_portfolios(portfolios: Vec<PortfolioWithAccounts>) -> Self {
+            Self {
+                portfolios: Arc::new(Mutex::new(portfolios)),
+                ..Default::default()
+            }
+        }
+    }
+
+    fn stored_portfolio(id: &str, name: &str, account_ids: &[&str]) -> PortfolioWithAccounts {
+        PortfolioWithAccounts {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: None,
+            sort_order: 0,
+            account_ids: account_ids.iter().map(|id| id.to_string()).collect(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        }
     }
 
     #[async_trait]
@@ -67,6 +86,11 @@ mod tests {
                 created_at: "2024-01-01T00:00:00Z".to_string(),
                 updated_at: "2024-01-01T00:00:00Z".to_string(),
             };
+            let mut portfolios = self.portfolios.lock().unwrap();
+            match portfolios.iter_mut().find(|existing| existing.id == p.id) {
+                Some(existing) => *existing = p.clone(),
+                None => portfolios.push(p.clone()),
+            }
             Ok(p)
         }
 
@@ -97,6 +121,20 @@ mod tests {
                     Ok(vec!["a1".to_string(), "a2".to_string()])
                 }
                 AccountScope::Accounts { account_ids } => Ok(account_ids.clone()),
+                AccountScope::Unassigned => {
+                    let assigned: Vec<String> = self
+                        .portfolios
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .flat_map(|p| p.account_ids.clone())
+                        .collect();
+                    Ok(["a1", "a2"]
+                        .into_iter()
+                        .map(String::from)
+                        .filter(|id| !assigned.contains(id))
+                        .collect())
+                }
             }
         }
     }
@@ -272,7 +310,12 @@ mod tests {
 
     #[tokio::test]
     async fn update_maps_unique_violation_to_friendly_error() {
-        let svc = make_service_with(MockPortfolioRepo::with_violation(), &["a1"]);
+        let repo = MockPortfolioRepo::with_violation();
+        repo.portfolios
+            .lock()
+            .unwrap()
+            .push(stored_portfolio("p1", "P", &["a1"]));
+        let svc = make_service_with(repo, &["a1"]);
         let update = PortfolioUpdate {
             id: "p1".to_string(),
             name: "P".to_string(),
@@ -285,6 +328,94 @@ mod tests {
         assert!(err.to_string().contains("already exists"));
     }
 
+    #[tokio::test]
+    async fn create_rejects_account_already_in_another_portfolio() {
+        let svc = make_service_with(MockPortfolioRepo::default(), &["a1", "a2"]);
+        svc.create_portfolio(valid_new("Retirement", vec!["a1".to_string()]))
+            .await
+            .unwrap();
+
+        let err = svc
+            .create_portfolio(valid_new(
+                "Trading",
+                vec!["a2".to_string(), "a1".to_string()],
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+        assert!(err
+            .to_string()
+            .contains("already belongs to portfolio 'Retirement'"));
+    }
+
+    #[tokio::test]
+    async fn update_may_keep_its_own_accounts() {
+        let svc = make_service_with(MockPortfolioRepo::default(), &["a1", "a2"]);
+        let created = svc
+            .create_portfolio(valid_new("Retirement", vec!["a1".to_string()]))
+            .await
+            .unwrap();
+
+        let update = PortfolioUpdate {
+            id: created.id,
+            name: "Retirement".to_string(),
+            description: None,
+            sort_order: 0,
+            account_ids: vec!["a1".to_string(), "a2".to_string()],
+        };
+        let updated = svc.update_portfolio(update).await.unwrap();
+        assert_eq!(updated.account_ids, vec!["a1", "a2"]);
+    }
+
+    #[tokio::test]
+    async fn update_keeps_accounts_shared_before_the_rule_but_rejects_new_overlaps() {
+        let repo = MockPortfolioRepo::with_portfolios(vec![
+            stored_portfolio("retirement", "Retirement", &["a1"]),
+            stored_portfolio("trading", "Trading", &["a1", "a2"]),
+        ]);
+        let svc = make_service_with(repo, &["a1", "a2"]);
+
+        let renamed = svc
+            .update_portfolio(PortfolioUpdate {
+                id: "retirement".to_string(),
+                name: "Retirement (legacy)".to_string(),
+                description: None,
+                sort_order: 0,
+                account_ids: vec!["a1".to_string()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(renamed.account_ids, vec!["a1"]);
+
+        let err = svc
+            .update_portfolio(PortfolioUpdate {
+                id: "retirement".to_string(),
+                name: "Retirement".to_string(),
+                description: None,
+                sort_order: 0,
+                account_ids: vec!["a1".to_string(), "a2".to_string()],
+            })
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Account 'a2' already belongs to portfolio 'Trading'"));
+    }
+
+    #[tokio::test]
+    async fn resolve_account_scope_unassigned_skips_portfolio_members() {
+        let svc = make_service_with(MockPortfolioRepo::default(), &["a1", "a2"]);
+        svc.create_portfolio(valid_new("Retirement", vec!["a1".to_string()]))
+            .await
+            .unwrap();
+
+        let scope = svc
+            .resolve_account_scope(&AccountScope::Unassigned, "USD")
+            .unwrap();
+        assert_eq!(scope.scope_id, "unassigned");
+        assert_eq!(scope.account_ids, vec!["a2"]);
+    }
+
     #[tokio::test]
     async fn resolve_account_filter_all() {
         let svc = make_service_with(MockPortfolioRepo::default(), &[]);
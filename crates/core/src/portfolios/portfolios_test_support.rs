@@ -0,0 +1,87 @@
+//! Shared portfolio test double for services that only read portfolio scopes.
+
+use async_trait::async_trait;
+
+use super::portfolios_model::{
+    AccountScope, NewPortfolio, PortfolioUpdate, PortfolioWithAccounts, ResolvedAccountScope,
+};
+use super::portfolios_traits::PortfolioServiceTrait;
+use crate::accounts::AccountPurpose;
+use crate::errors::Result;
+
+/// Read-only portfolio service over a fixed list of portfolios. `All` resolves to every member
+/// account, in first-seen order.
+pub(crate) struct StaticPortfolios {
+    portfolios: Vec<PortfolioWithAccounts>,
+}
+
+impl StaticPortfolios {
+    pub(crate) fn new(portfolios: Vec<PortfolioWithAccounts>) -> Self {
+        Self { portfolios }
+    }
+
+    /// A single portfolio with the given id and member accounts.
+    pub(crate) fn with_portfolio(id: &str, account_ids: &[&str]) -> Self {
+        Self::new(vec![PortfolioWithAccounts {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            sort_order: 0,
+            account_ids: account_ids.iter().map(|id| id.to_string()).collect(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        }])
+    }
+}
+
+#[async_trait]
+impl PortfolioServiceTrait for StaticPortfolios {
+    async fn create_portfolio(&self, _new: NewPortfolio) -> Result<PortfolioWithAccounts> {
+        unimplemented!()
+    }
+
+    async fn update_portfolio(&self, _update: PortfolioUpdate) -> Result<PortfolioWithAccounts> {
+        unimplemented!()
+    }
+
+    async fn delete_portfolio(&self, _id: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn get_portfolio(&self, _id: &str) -> Result<PortfolioWithAccounts> {
+        unimplemented!()
+    }
+
+    fn list_portfolios(&self) -> Result<Vec<PortfolioWithAccounts>> {
+        Ok(self.portfolios.clone())
+    }
+
+    fn resolve_account_filter(&self, filter: &AccountScope) -> Result<Vec<String>> {
+        let mut ids: Vec<String> = Vec::new();
+        for portfolio in &self.portfolios {
+            let included = match filter {
+                AccountScope::All => true,
+                AccountScope::Portfolio { portfolio_id } => &portfolio.id == portfolio_id,
+                _ => unimplemented!(),
+            };
+            if !included {
+                continue;
+            }
+            for id in &portfolio.account_ids {
+                if !ids.contains(id) {
+                    ids.push(id.clone());
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    fn resolve_account_scope_for_purpose(
+        &self,
+        filter: &AccountScope,
+        base_currency: &str,
+        _purpose: AccountPurpose,
+    ) -> Result<ResolvedAccountScope> {
+        self.resolve_account_scope(filter, base_currency)
+    }
+}
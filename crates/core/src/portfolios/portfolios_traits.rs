@@ -35,15 +35,17 @@ pub trait PortfolioServiceTrait: Send + Sync {
     ) -> Result<ResolvedAccountScope> {
         let mut ids = match filter {
             AccountScope::Account { account_id } => vec![account_id.clone()],
-            AccountScope::All | AccountScope::Portfolio { .. } | AccountScope::Accounts { .. } => {
-                self.resolve_account_filter(filter)?
-            }
+            AccountScope::All
+            | AccountScope::Portfolio { .. }
+            | AccountScope::Accounts { .. }
+            | AccountScope::Unassigned => self.resolve_account_filter(filter)?,
         };
         ids.sort();
         ids.dedup();
 
         let scope_id = match filter {
             AccountScope::All => "all".to_string(),
+            AccountScope::Unassigned => "unassigned".to_string(),
             AccountScope::Account { account_id } => format!("account:{}", account_id),
             AccountScope::Portfolio { portfolio_id } => format!("portfolio:{}", portfolio_id),
             AccountScope::Accounts { .. } => {
@@ -0,0 +1,354 @@
+//! Secrets kept in one JSON file, shared by the self-hosted server and the desktop fallback.
+//!
+//! The file is plain JSON without a key, or ChaCha20-Poly1305 encrypted with either a raw
+//! 32-byte key (the server's `WF_SECRET_KEY`) or a key derived from a passphrase with Argon2id
+//! and a random salt stored in the file. Writes go to a temporary file with mode `0600` that
+//! is then renamed over the old one, so a crash never leaves a truncated store.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use super::{format_service_id, SecretStore};
+use crate::errors::{Error, Result};
+
+const CURRENT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+
+#[derive(Debug)]
+pub struct FileSecretStore {
+    path: PathBuf,
+    encryption_key: Option<[u8; 32]>,
+    /// Salt the key was derived from; only set for passphrase stores.
+    salt: Option<[u8; SALT_LEN]>,
+    lock: Mutex<()>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct PlainSecrets {
+    version: u32,
+    secrets: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedSecrets {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl FileSecretStore {
+    /// A store encrypted with `encryption_key`, or plain JSON without one.
+    pub fn new(path: PathBuf, encryption_key: Option<[u8; 32]>) -> Self {
+        Self {
+            path,
+            encryption_key,
+            salt: None,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// A store encrypted under a key derived from `passphrase`. The salt comes from the
+    /// existing file, or is generated for a new one.
+    pub fn with_passphrase(path: PathBuf, passphrase: &str) -> Result<Self> {
+        if passphrase.trim().is_empty() {
+            return Err(Error::Secret(
+                "The secrets file passphrase must not be empty".into(),
+            ));
+        }
+        let salt = match read_encrypted(&path)? {
+            Some(file) => {
+                let encoded = file.salt.ok_or_else(|| {
+                    Error::Secret("The secrets file was not written with a passphrase".into())
+                })?;
+                decode_field("salt", &encoded)?
+                    .try_into()
+                    .map_err(|_| Error::Secret("Secrets file salt has the wrong length".into()))?
+            }
+            None => {
+                let mut salt = [0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                salt
+            }
+        };
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| Error::Secret(format!("Failed to derive the secrets file key: {e}")))?;
+        Ok(Self {
+            path,
+            encryption_key: Some(key),
+            salt: Some(salt),
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Every stored secret, keyed by formatted service id.
+    pub fn read_all(&self) -> Result<HashMap<String, String>> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| Error::Secret("Secret store lock poisoned".into()))?;
+        self.load_store_locked()
+    }
+
+    /// Replaces the stored secrets, re-encrypting them with this store's key (used during key
+    /// migration).
+    pub fn replace_all(&self, secrets: &HashMap<String, String>) -> Result<()> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| Error::Secret("Secret store lock poisoned".into()))?;
+        self.persist_store_locked(secrets)
+    }
+
+    fn with_store<F>(&self, mut op: F) -> Result<()>
+    where
+        F: FnMut(&mut HashMap<String, String>) -> Result<()>,
+    {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| Error::Secret("Secret store lock poisoned".into()))?;
+        let mut store = self.load_store_locked()?;
+        op(&mut store)?;
+        self.persist_store_locked(&store)
+    }
+
+    #[allow(deprecated)]
+    fn load_store_locked(&self) -> Result<HashMap<String, String>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let raw = fs::read(&self.path)?;
+        if raw.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let value: serde_json::Value = serde_json::from_slice(&raw)?;
+
+        if value.get("ciphertext").is_some() {
+            let key = self.encryption_key.ok_or_else(|| {
+                Error::Secret("An encryption key is required to decrypt the secrets file".into())
+            })?;
+            let enc: EncryptedSecrets = serde_json::from_value(value)?;
+            let nonce_bytes = decode_field("nonce", &enc.nonce)?;
+            if nonce_bytes.len() != 12 {
+                return Err(Error::Secret(
+                    "Secrets file nonce has the wrong length".into(),
+                ));
+            }
+            let cipher_bytes = decode_field("ciphertext", &enc.ciphertext)?;
+
+            let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+            let nonce = Nonce::from_slice(&nonce_bytes);
+            let plaintext = cipher
+                .decrypt(nonce, cipher_bytes.as_ref())
+                .map_err(|_| Error::Secret("Failed to decrypt secrets file".into()))?;
+            let plain: PlainSecrets = serde_json::from_slice(&plaintext)?;
+            Ok(plain.secrets)
+        } else {
+            let plain: PlainSecrets = serde_json::from_value(value)?;
+            Ok(plain.secrets)
+        }
+    }
+
+    #[allow(deprecated)]
+    fn persist_store_locked(&self, store: &HashMap<String, String>) -> Result<()> {
+        let plain = PlainSecrets {
+            version: CURRENT_VERSION,
+            secrets: store.clone(),
+        };
+
+        let json = if let Some(key) = self.encryption_key {
+            let serialized = serde_json::to_vec(&plain)?;
+            let mut nonce_bytes = [0u8; 12];
+            OsRng.fill_bytes(&mut nonce_bytes);
+            let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+            let nonce = Nonce::from_slice(&nonce_bytes);
+            let ciphertext = cipher
+                .encrypt(nonce, serialized.as_ref())
+                .map_err(|_| Error::Secret("Failed to encrypt secrets".into()))?;
+            let enc = EncryptedSecrets {
+                version: CURRENT_VERSION,
+                salt: self.salt.map(|salt| BASE64.encode(salt)),
+                nonce: BASE64.encode(nonce_bytes),
+                ciphertext: BASE64.encode(ciphertext),
+            };
+            serde_json::to_string_pretty(&enc)?
+        } else {
+            serde_json::to_string_pretty(&plain)?
+        };
+        write_atomically(&self.path, json.as_bytes())
+    }
+}
+
+impl SecretStore for FileSecretStore {
+    fn set_secret(&self, service: &str, secret: &str) -> Result<()> {
+        let key = format_service_id(service);
+        self.with_store(|store| {
+            store.insert(key.clone(), secret.to_string());
+            Ok(())
+        })
+    }
+
+    fn get_secret(&self, service: &str) -> Result<Option<String>> {
+        let key = format_service_id(service);
+        let store = self.read_all()?;
+        Ok(store.get(&key).cloned())
+    }
+
+    fn delete_secret(&self, service: &str) -> Result<()> {
+        let key = format_service_id(service);
+        self.with_store(|store| {
+            store.remove(&key);
+            Ok(())
+        })
+    }
+}
+
+fn read_encrypted(path: &Path) -> Result<Option<EncryptedSecrets>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read(path)?;
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    let value: serde_json::Value = serde_json::from_slice(&raw)?;
+    if value.get("ciphertext").is_none() {
+        return Err(Error::Secret(
+            "The secrets file was not written with a passphrase".into(),
+        ));
+    }
+    Ok(Some(serde_json::from_value(value)?))
+}
+
+fn decode_field(field: &str, value: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| Error::Secret(format!("Failed to decode {field}: {e}")))
+}
+
+/// Writes `contents` to a sibling temporary file readable only by the owner, then renames it
+/// over `path`.
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp_path)?;
+    #[cfg(unix)]
+    {
+        // `mode` only applies on creation; a leftover temp file keeps its old permissions.
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn round_trip_without_encryption() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("secrets.json");
+        let store = FileSecretStore::new(file.clone(), None);
+
+        store.set_secret("alpha", "value").unwrap();
+        assert_eq!(store.get_secret("alpha").unwrap().as_deref(), Some("value"));
+
+        store.delete_secret("alpha").unwrap();
+        assert!(store.get_secret("alpha").unwrap().is_none());
+        assert!(file.exists());
+    }
+
+    #[test]
+    fn round_trip_with_encryption() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("secrets.json");
+        let store = FileSecretStore::new(file.clone(), Some([7u8; 32]));
+
+        store.set_secret("beta", "secret").unwrap();
+        assert_eq!(store.get_secret("beta").unwrap().as_deref(), Some("secret"));
+        assert!(file.exists());
+
+        let raw = fs::read_to_string(file).unwrap();
+        assert!(raw.contains("ciphertext"));
+        assert!(!raw.contains("secret\""));
+    }
+
+    #[test]
+    fn passphrase_store_reopens_with_the_stored_salt() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("secrets.json");
+        let store =
+            FileSecretStore::with_passphrase(path.clone(), "correct horse battery").unwrap();
+
+        store.set_secret("OpenAI", "sk-live-123").unwrap();
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("\"salt\""));
+        assert!(!raw.contains("sk-live-123"));
+        assert!(!raw.contains("wealthfolio_openai"));
+
+        let reopened =
+            FileSecretStore::with_passphrase(path.clone(), "correct horse battery").unwrap();
+        assert_eq!(
+            reopened.get_secret("openai").unwrap().as_deref(),
+            Some("sk-live-123")
+        );
+
+        let wrong = FileSecretStore::with_passphrase(path, "wrong horse battery").unwrap();
+        assert!(wrong.get_secret("openai").is_err());
+        assert!(FileSecretStore::with_passphrase(dir.path().join("new.json"), "  ").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_are_owner_only_and_leave_no_temp_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("secrets.json");
+        let store = FileSecretStore::new(path.clone(), Some([1u8; 32]));
+
+        store.set_secret("alpha", "one").unwrap();
+        store.set_secret("beta", "two").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!dir.path().join("secrets.json.tmp").exists());
+        assert_eq!(store.get_secret("alpha").unwrap().as_deref(), Some("one"));
+    }
+}
@@ -0,0 +1,64 @@
+//! In-memory secret store for tests of code that reads or writes secrets.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::SecretStore;
+use crate::errors::{Error, Result};
+
+/// Secrets held in memory, keyed by service name as given (no prefix is applied).
+#[derive(Default)]
+pub struct MemorySecretStore {
+    secrets: Mutex<HashMap<String, String>>,
+    fail_writes: bool,
+}
+
+impl MemorySecretStore {
+    /// A store that already holds `secrets`.
+    pub fn with(secrets: &[(&str, &str)]) -> Self {
+        let store = Self::default();
+        for (key, value) in secrets {
+            store.set_secret(key, value).unwrap();
+        }
+        store
+    }
+
+    /// Makes every later `set_secret` fail, as a locked keyring would.
+    pub fn fail_writes(mut self) -> Self {
+        self.fail_writes = true;
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.secrets.lock().unwrap().get(key).cloned()
+    }
+
+    /// The stored keys, sorted.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.secrets.lock().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+}
+
+impl SecretStore for MemorySecretStore {
+    fn set_secret(&self, service: &str, secret: &str) -> Result<()> {
+        if self.fail_writes {
+            return Err(Error::Secret("keyring locked".to_string()));
+        }
+        self.secrets
+            .lock()
+            .unwrap()
+            .insert(service.to_string(), secret.to_string());
+        Ok(())
+    }
+
+    fn get_secret(&self, service: &str) -> Result<Option<String>> {
+        Ok(self.get(service))
+    }
+
+    fn delete_secret(&self, service: &str) -> Result<()> {
+        self.secrets.lock().unwrap().remove(service);
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Renaming stored secrets.
+//!
+//! When a secret key is renamed, its value has to move in every existing install or users lose
+//! it on upgrade. Renames are listed in [`SECRET_KEY_MIGRATIONS`] and applied at startup by
+//! [`migrate_secret_keys`]. Keys are the unprefixed names the [`SecretStore`] takes; the store
+//! adds [`super::SERVICE_PREFIX`] itself.
+//!
+//! The migration is idempotent: a rename whose old key is gone is already done, and a rename
+//! whose new key already holds a value is skipped.
+
+use log::{info, warn};
+
+use super::SecretStore;
+use crate::errors::Result;
+
+/// `(old_key, new_key)` renames, applied in order. Append a pair when a key is renamed and keep
+/// it for a few releases so installs that skip versions still migrate.
+pub const SECRET_KEY_MIGRATIONS: &[(&str, &str)] = &[];
+
+/// Applies every rename in `migrations` and returns how many values were moved.
+///
+/// A rename copies the value to the new key, then deletes the old key. When the new key already
+/// holds a value (set by a newer version), the rename is skipped and both keys are left alone.
+/// A failed rename is logged and left for the next start; the others still run.
+pub fn migrate_secret_keys(store: &dyn SecretStore, migrations: &[(&str, &str)]) -> usize {
+    let mut moved = 0;
+    for (old_key, new_key) in migrations {
+        match migrate_secret_key(store, old_key, new_key) {
+            Ok(true) => moved += 1,
+            Ok(false) => {}
+            Err(err) => warn!(
+                "Failed to migrate secret '{}' to '{}': {}",
+                old_key, new_key, err
+            ),
+        }
+    }
+    moved
+}
+
+/// `Ok(true)` when the value was copied to the new key.
+fn migrate_secret_key(store: &dyn SecretStore, old_key: &str, new_key: &str) -> Result<bool> {
+    let Some(value) = store.get_secret(old_key)? else {
+        return Ok(false);
+    };
+    if store.get_secret(new_key)?.is_some() {
+        return Ok(false);
+    }
+    store.set_secret(new_key, &value)?;
+    store.delete_secret(old_key)?;
+    info!("Migrated secret '{}' to '{}'", old_key, new_key);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::MemorySecretStore;
+
+    const RENAMES: &[(&str, &str)] = &[
+        ("old_refresh_token", "sync_refresh_token"),
+        ("old_identity", "sync_identity"),
+    ];
+
+    #[test]
+    fn moves_values_to_the_new_keys_and_removes_the_old_ones() {
+        let store = MemorySecretStore::with(&[
+            ("old_refresh_token", "refresh"),
+            ("old_identity", "identity"),
+            ("openai", "sk-test"),
+        ]);
+
+        assert_eq!(migrate_secret_keys(&store, RENAMES), 2);
+
+        assert_eq!(store.get("sync_refresh_token").as_deref(), Some("refresh"));
+        assert_eq!(store.get("sync_identity").as_deref(), Some("identity"));
+        assert_eq!(store.get("old_refresh_token"), None);
+        assert_eq!(store.get("old_identity"), None);
+        assert_eq!(store.get("openai").as_deref(), Some("sk-test"));
+
+        // A second run has nothing left to do.
+        assert_eq!(migrate_secret_keys(&store, RENAMES), 0);
+        assert_eq!(store.get("sync_refresh_token").as_deref(), Some("refresh"));
+    }
+
+    #[test]
+    fn skips_a_rename_whose_new_key_is_already_set() {
+        let store = MemorySecretStore::with(&[
+            ("old_refresh_token", "stale"),
+            ("sync_refresh_token", "current"),
+        ]);
+
+        assert_eq!(migrate_secret_keys(&store, RENAMES), 0);
+
+        assert_eq!(store.get("sync_refresh_token").as_deref(), Some("current"));
+        assert_eq!(store.get("old_refresh_token").as_deref(), Some("stale"));
+    }
+
+    #[test]
+    fn failed_rename_keeps_the_old_value() {
+        let store = MemorySecretStore::with(&[("old_identity", "identity")]).fail_writes();
+
+        assert_eq!(migrate_secret_keys(&store, RENAMES), 0);
+
+        assert_eq!(store.get("old_identity").as_deref(), Some("identity"));
+        assert_eq!(store.get("sync_identity"), None);
+    }
+}
@@ -1,5 +1,17 @@
 use crate::errors::Result;
 
+#[cfg(feature = "file-secret-store")]
+mod file_store;
+#[cfg(any(test, feature = "test-utils"))]
+mod memory_store;
+mod migration;
+
+#[cfg(feature = "file-secret-store")]
+pub use file_store::FileSecretStore;
+#[cfg(any(test, feature = "test-utils"))]
+pub use memory_store::MemorySecretStore;
+pub use migration::{migrate_secret_keys, SECRET_KEY_MIGRATIONS};
+
 /// Prefix applied to all secret identifiers to avoid collisions with other
 /// applications that may share the same underlying credential store.
 pub const SERVICE_PREFIX: &str = "wealthfolio_";
@@ -10,10 +22,10 @@ pub fn format_service_id(service: &str) -> String {
     format!("{}{}", SERVICE_PREFIX, service.to_lowercase())
 }
 
-/// Platform-agnostic contract for storing provider secrets. Concrete
-/// implementations live in the platform crates (e.g. the Tauri desktop app or
-/// the self-hosted web server) so the core crate remains focused on business
-/// logic.
+/// Platform-agnostic contract for storing provider secrets. The OS keyring
+/// store lives in the Tauri desktop app; the file store shared by the
+/// self-hosted web server and the desktop fallback sits behind the
+/// `file-secret-store` feature.
 pub trait SecretStore: Send + Sync {
     fn set_secret(&self, service: &str, secret: &str) -> Result<()>;
     fn get_secret(&self, service: &str) -> Result<Option<String>>;
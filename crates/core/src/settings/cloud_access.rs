@@ -0,0 +1,101 @@
+//! Cloud kill switch.
+//!
+//! A single persisted flag that takes the app offline from Wealthfolio Cloud. While it is set,
+//! no cloud access token is handed out, so broker sync, subscription checks, device sync and
+//! sync webhooks all stop until it is cleared. Local data stays fully usable.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use super::SettingsServiceTrait;
+use crate::errors::{Error, Result};
+
+/// Settings key holding the kill switch (`"true"` while cloud access is disabled).
+pub const CLOUD_DISABLED_SETTING_KEY: &str = "cloud_disabled";
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudAccessStatus {
+    pub disabled: bool,
+}
+
+pub struct CloudAccessService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+}
+
+impl CloudAccessService {
+    pub fn new(settings_service: Arc<dyn SettingsServiceTrait>) -> Self {
+        Self { settings_service }
+    }
+
+    /// Whether cloud access is switched off. A read error counts as disabled so a broken
+    /// settings store never lets traffic through.
+    pub fn is_disabled(&self) -> bool {
+        match self
+            .settings_service
+            .get_setting_value(CLOUD_DISABLED_SETTING_KEY)
+        {
+            Ok(value) => value.is_some_and(|value| value.trim() == "true"),
+            Err(_) => true,
+        }
+    }
+
+    /// Fails with [`Error::CloudDisabled`] while the kill switch is on.
+    pub fn ensure_enabled(&self) -> Result<()> {
+        if self.is_disabled() {
+            Err(Error::CloudDisabled)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn status(&self) -> CloudAccessStatus {
+        CloudAccessStatus {
+            disabled: self.is_disabled(),
+        }
+    }
+
+    /// Turns the kill switch on or off and returns the stored state.
+    pub async fn set_disabled(&self, disabled: bool) -> Result<CloudAccessStatus> {
+        self.settings_service
+            .set_setting_value(
+                CLOUD_DISABLED_SETTING_KEY,
+                if disabled { "true" } else { "false" },
+            )
+            .await?;
+        Ok(self.status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::MemorySettingsService;
+
+    #[tokio::test]
+    async fn kill_switch_persists_across_service_instances() {
+        let settings: Arc<dyn SettingsServiceTrait> = Arc::new(MemorySettingsService::default());
+        let service = CloudAccessService::new(settings.clone());
+        assert!(service.ensure_enabled().is_ok());
+
+        let status = service.set_disabled(true).await.unwrap();
+        assert!(status.disabled);
+
+        // A fresh instance (as after a restart) reads the stored flag.
+        let restarted = CloudAccessService::new(settings.clone());
+        assert!(matches!(
+            restarted.ensure_enabled(),
+            Err(Error::CloudDisabled)
+        ));
+
+        restarted.set_disabled(false).await.unwrap();
+        assert!(CloudAccessService::new(settings).ensure_enabled().is_ok());
+    }
+
+    #[test]
+    fn unreadable_flag_counts_as_disabled() {
+        let service = CloudAccessService::new(Arc::new(MemorySettingsService::failing_reads()));
+        assert!(service.is_disabled());
+    }
+}
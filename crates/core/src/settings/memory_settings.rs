@@ -0,0 +1,69 @@
+//! In-memory settings store for tests of services that keep their state in key-value settings.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use super::{Settings, SettingsServiceTrait, SettingsUpdate};
+use crate::errors::{Error, Result};
+
+/// Key-value settings held in memory. Only `get_setting_value` and `set_setting_value` are
+/// supported; the typed settings methods panic.
+#[derive(Default)]
+pub struct MemorySettingsService {
+    values: Mutex<HashMap<String, String>>,
+    fail_reads: bool,
+}
+
+impl MemorySettingsService {
+    /// A store whose reads all fail, as an unavailable database would.
+    pub fn failing_reads() -> Self {
+        Self {
+            fail_reads: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[async_trait]
+impl SettingsServiceTrait for MemorySettingsService {
+    fn get_settings(&self) -> Result<Settings> {
+        unimplemented!()
+    }
+
+    async fn update_settings(&self, _new_settings: &SettingsUpdate) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn get_base_currency(&self) -> Result<Option<String>> {
+        unimplemented!()
+    }
+
+    async fn update_base_currency(&self, _new_base_currency: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    fn is_auto_update_check_enabled(&self) -> Result<bool> {
+        unimplemented!()
+    }
+
+    fn is_sync_enabled(&self) -> Result<bool> {
+        unimplemented!()
+    }
+
+    fn get_setting_value(&self, key: &str) -> Result<Option<String>> {
+        if self.fail_reads {
+            return Err(Error::Unexpected("settings store unavailable".to_string()));
+        }
+        Ok(self.values.lock().unwrap().get(key).cloned())
+    }
+
+    async fn set_setting_value(&self, key: &str, value: &str) -> Result<()> {
+        self.values
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
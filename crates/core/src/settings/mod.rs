@@ -1,9 +1,21 @@
 //! Settings module - application settings management.
 
+mod cloud_access;
+#[cfg(any(test, feature = "test-utils"))]
+mod memory_settings;
+mod platform;
+mod quiet_hours;
 mod settings_model;
 mod settings_service;
 mod settings_traits;
 
+pub use cloud_access::{CloudAccessService, CloudAccessStatus, CLOUD_DISABLED_SETTING_KEY};
+#[cfg(any(test, feature = "test-utils"))]
+pub use memory_settings::MemorySettingsService;
+pub use platform::PlatformCapabilities;
+pub use quiet_hours::{
+    SyncQuietHours, SyncQuietHoursService, SyncQuietHoursStatus, SYNC_QUIET_HOURS_SETTING_KEY,
+};
 pub use settings_model::*;
 pub use settings_service::{SettingsService, SettingsServiceTrait};
 pub use settings_traits::SettingsRepositoryTrait;
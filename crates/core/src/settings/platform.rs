@@ -0,0 +1,28 @@
+//! What a build of the app can do, reported to the frontend by both the desktop app and the
+//! server so it can hide features that were compiled out.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PlatformCapabilities {
+    pub connect_sync: bool,
+    pub device_sync: bool,
+    pub cloud_sync: bool,
+    /// Whether scheduled syncs keep running while no window is in the foreground.
+    pub background_sync: bool,
+}
+
+impl PlatformCapabilities {
+    /// Capabilities for a build with the given sync features. `runs_in_background` says
+    /// whether the host keeps running without a foreground window: desktop apps and the
+    /// server do, mobile apps are suspended soon after they leave the foreground.
+    pub fn new(connect_sync: bool, device_sync: bool, runs_in_background: bool) -> Self {
+        let cloud_sync = connect_sync || device_sync;
+        Self {
+            connect_sync,
+            device_sync,
+            cloud_sync,
+            background_sync: runs_in_background && cloud_sync,
+        }
+    }
+}
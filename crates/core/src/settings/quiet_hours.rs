@@ -0,0 +1,240 @@
+//! Quiet hours for automatic sync.
+//!
+//! A daily local-time window during which scheduled and startup syncs are skipped.
+//! Manual syncs are not affected. The window may wrap past midnight (e.g. `22:00`–`07:00`)
+//! and is stored as JSON in the settings table.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use super::SettingsServiceTrait;
+use crate::errors::{Error, Result, ValidationError};
+use crate::utils::time_utils::parse_user_timezone;
+
+/// Settings key holding the quiet hours JSON.
+pub const SYNC_QUIET_HOURS_SETTING_KEY: &str = "sync_quiet_hours";
+
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Daily window, in `HH:MM` local time, during which automatic sync does not run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncQuietHours {
+    pub start: String,
+    pub end: String,
+    /// IANA timezone the window is expressed in.
+    pub timezone: String,
+}
+
+/// Current quiet hours and when automatic sync may next run.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncQuietHoursStatus {
+    pub quiet_hours: Option<SyncQuietHours>,
+    /// Whether `now` falls inside the window.
+    pub active: bool,
+    /// Earliest time an automatic sync may run: `now` outside the window, its end inside.
+    pub next_sync_at: DateTime<Utc>,
+}
+
+struct QuietWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+    tz: Tz,
+}
+
+impl SyncQuietHours {
+    fn window(&self) -> Result<QuietWindow> {
+        let start = parse_time("start", &self.start)?;
+        let end = parse_time("end", &self.end)?;
+        if start == end {
+            return Err(invalid("Quiet hours start and end must differ"));
+        }
+        Ok(QuietWindow {
+            start,
+            end,
+            tz: parse_user_timezone(&self.timezone)?,
+        })
+    }
+
+    /// Validates the window and returns it with canonical `HH:MM` times and timezone name.
+    pub fn normalized(&self) -> Result<Self> {
+        let window = self.window()?;
+        Ok(Self {
+            start: window.start.format(TIME_FORMAT).to_string(),
+            end: window.end.format(TIME_FORMAT).to_string(),
+            timezone: window.tz.name().to_string(),
+        })
+    }
+
+    /// Whether `now` falls inside the window. An invalid window is never active.
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.window().is_ok_and(|window| window.contains(now))
+    }
+
+    /// `now` when outside the window, otherwise the end of the current window.
+    pub fn next_sync_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self.window() {
+            Ok(window) if window.contains(now) => window.end_after(now),
+            _ => now,
+        }
+    }
+}
+
+impl QuietWindow {
+    fn contains(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.tz).time();
+        if self.start < self.end {
+            local >= self.start && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+
+    fn end_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let local = now.with_timezone(&self.tz);
+        let mut date = local.date_naive();
+        if local.time() >= self.end {
+            date += Duration::days(1);
+        }
+        let end = date.and_time(self.end);
+        // A DST gap can swallow the end time; resume at the first valid instant after it.
+        self.tz
+            .from_local_datetime(&end)
+            .earliest()
+            .or_else(|| {
+                self.tz
+                    .from_local_datetime(&(end + Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or(now)
+    }
+}
+
+pub struct SyncQuietHoursService {
+    settings_service: Arc<dyn SettingsServiceTrait>,
+}
+
+impl SyncQuietHoursService {
+    pub fn new(settings_service: Arc<dyn SettingsServiceTrait>) -> Self {
+        Self { settings_service }
+    }
+
+    pub fn get_quiet_hours(&self) -> Result<Option<SyncQuietHours>> {
+        Ok(self
+            .settings_service
+            .get_setting_value(SYNC_QUIET_HOURS_SETTING_KEY)?
+            .filter(|raw| !raw.trim().is_empty())
+            .and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    /// Stores (or clears, with `None`) the quiet hours and returns what was stored.
+    pub async fn set_quiet_hours(
+        &self,
+        quiet_hours: Option<SyncQuietHours>,
+    ) -> Result<Option<SyncQuietHours>> {
+        let normalized = quiet_hours.map(|hours| hours.normalized()).transpose()?;
+        let raw = match &normalized {
+            Some(hours) => {
+                serde_json::to_string(hours).map_err(|e| Error::Unexpected(e.to_string()))?
+            }
+            None => String::new(),
+        };
+        self.settings_service
+            .set_setting_value(SYNC_QUIET_HOURS_SETTING_KEY, &raw)
+            .await?;
+        Ok(normalized)
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> Result<SyncQuietHoursStatus> {
+        let quiet_hours = self.get_quiet_hours()?;
+        let active = quiet_hours
+            .as_ref()
+            .is_some_and(|hours| hours.contains(now));
+        let next_sync_at = quiet_hours
+            .as_ref()
+            .map_or(now, |hours| hours.next_sync_at(now));
+        Ok(SyncQuietHoursStatus {
+            quiet_hours,
+            active,
+            next_sync_at,
+        })
+    }
+
+    /// Whether an automatic sync tick at `now` should run. Read errors never block syncing.
+    pub fn allows_sync_at(&self, now: DateTime<Utc>) -> bool {
+        !matches!(self.get_quiet_hours(), Ok(Some(hours)) if hours.contains(now))
+    }
+}
+
+fn parse_time(field: &str, value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), TIME_FORMAT).map_err(|_| {
+        invalid(format!(
+            "Quiet hours {} must be a HH:MM time, got '{}'",
+            field, value
+        ))
+    })
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Validation(ValidationError::InvalidInput(message.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::MemorySettingsService;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn overnight() -> SyncQuietHours {
+        SyncQuietHours {
+            start: "22:00".to_string(),
+            end: "7:00".to_string(),
+            timezone: "America/Toronto".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn tick_inside_quiet_hours_is_skipped_and_outside_runs() {
+        let service = SyncQuietHoursService::new(Arc::new(MemorySettingsService::default()));
+        let stored = service.set_quiet_hours(Some(overnight())).await.unwrap();
+        assert_eq!(stored.unwrap().end, "07:00");
+
+        // 23:30 and 02:00 in Toronto (EDT, UTC-4) fall inside the overnight window.
+        assert!(!service.allows_sync_at(utc("2024-06-11T03:30:00Z")));
+        assert!(!service.allows_sync_at(utc("2024-06-11T06:00:00Z")));
+        // 12:00 local is outside.
+        assert!(service.allows_sync_at(utc("2024-06-11T16:00:00Z")));
+
+        let status = service.status(utc("2024-06-11T03:30:00Z")).unwrap();
+        assert!(status.active);
+        assert_eq!(status.next_sync_at, utc("2024-06-11T11:00:00Z"));
+
+        service.set_quiet_hours(None).await.unwrap();
+        assert!(service.allows_sync_at(utc("2024-06-11T03:30:00Z")));
+    }
+
+    #[test]
+    fn rejects_invalid_windows() {
+        let mut same = overnight();
+        same.end = "22:00".to_string();
+        assert!(same.normalized().is_err());
+
+        let mut bad_tz = overnight();
+        bad_tz.timezone = "Mars/Olympus".to_string();
+        assert!(bad_tz.normalized().is_err());
+
+        let mut bad_time = overnight();
+        bad_time.start = "25:00".to_string();
+        assert!(bad_time.normalized().is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Read-only portfolio snapshots for sharing with an accountant or advisor.
+//!
+//! A snapshot is self-contained: holdings, allocation and total value at one point in time, all
+//! in the base currency. It is redacted by default. Account details, including account numbers,
+//! appear only when the caller opts in, and fields that commonly carry personal data (asset
+//! notes and metadata, lots, names of alternative assets such as a property address) are never
+//! included.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::accounts::Account;
+use crate::assets::AssetKind;
+use crate::errors::{Error, Result, ValidationError};
+use crate::portfolio::holdings::{Holding, HoldingType};
+
+/// Format version of [`ShareSnapshot`], bumped on breaking changes.
+pub const SHARE_SNAPSHOT_VERSION: u32 = 1;
+
+/// Lifetime of a share link when the request does not give one.
+pub const DEFAULT_SHARE_LINK_TTL_HOURS: i64 = 24 * 7;
+
+/// Longest lifetime a share link may have.
+pub const MAX_SHARE_LINK_TTL_HOURS: i64 = 24 * 30;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareSnapshotOptions {
+    /// Adds account names, types and account numbers.
+    #[serde(default)]
+    pub include_account_details: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedHolding {
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub holding_type: HoldingType,
+    pub asset_kind: Option<AssetKind>,
+    pub quantity: Decimal,
+    /// Market value in the base currency.
+    pub market_value: Decimal,
+    /// Share of the total value, between 0 and 1.
+    pub weight: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedAllocation {
+    /// `CASH`, or the asset kind of the holdings in the slice.
+    pub category: String,
+    pub market_value: Decimal,
+    pub weight: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedAccount {
+    pub name: String,
+    pub account_type: String,
+    pub currency: String,
+    pub account_number: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareSnapshot {
+    pub version: u32,
+    pub generated_at: DateTime<Utc>,
+    pub base_currency: String,
+    pub total_value: Decimal,
+    pub holdings: Vec<SharedHolding>,
+    pub allocation: Vec<SharedAllocation>,
+    /// Present only when account details were opted in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<Vec<SharedAccount>>,
+}
+
+fn allocation_category(holding: &Holding) -> String {
+    if holding.holding_type == HoldingType::Cash {
+        return "CASH".to_string();
+    }
+    holding
+        .asset_kind
+        .as_ref()
+        .unwrap_or(&AssetKind::Investment)
+        .as_db_str()
+        .to_string()
+}
+
+fn share_of(value: Decimal, total: Decimal) -> Decimal {
+    if total.is_zero() {
+        Decimal::ZERO
+    } else {
+        (value / total).round_dp(6)
+    }
+}
+
+/// Builds a snapshot of `holdings`, which must be valued in `base_currency`. `accounts` is only
+/// read when account details are opted in.
+pub fn build_share_snapshot(
+    holdings: &[Holding],
+    accounts: &[Account],
+    base_currency: &str,
+    options: ShareSnapshotOptions,
+    generated_at: DateTime<Utc>,
+) -> ShareSnapshot {
+    let total_value: Decimal = holdings.iter().map(|h| h.market_value.base).sum();
+
+    let mut shared: Vec<SharedHolding> = holdings
+        .iter()
+        .map(|holding| {
+            let instrument = holding.instrument.as_ref();
+            // Alternative assets are named by their owner ("Cottage at 12 Lake Rd"), so only
+            // their kind is shared.
+            let (symbol, name) = if holding.holding_type == HoldingType::AlternativeAsset {
+                (None, None)
+            } else {
+                (
+                    instrument.map(|i| i.symbol.clone()),
+                    instrument.and_then(|i| i.name.clone()),
+                )
+            };
+            SharedHolding {
+                symbol,
+                name,
+                holding_type: holding.holding_type.clone(),
+                asset_kind: holding.asset_kind.clone(),
+                quantity: holding.quantity,
+                market_value: holding.market_value.base,
+                weight: share_of(holding.market_value.base, total_value),
+            }
+        })
+        .collect();
+    shared.sort_by_key(|holding| std::cmp::Reverse(holding.market_value));
+
+    let mut by_category: BTreeMap<String, Decimal> = BTreeMap::new();
+    for holding in holdings {
+        *by_category.entry(allocation_category(holding)).or_default() += holding.market_value.base;
+    }
+    let mut allocation: Vec<SharedAllocation> = by_category
+        .into_iter()
+        .map(|(category, market_value)| SharedAllocation {
+            category,
+            market_value,
+            weight: share_of(market_value, total_value),
+        })
+        .collect();
+    allocation.sort_by_key(|slice| std::cmp::Reverse(slice.market_value));
+
+    let accounts = options.include_account_details.then(|| {
+        accounts
+            .iter()
+            .map(|account| SharedAccount {
+                name: account.name.clone(),
+                account_type: account.account_type.clone(),
+                currency: account.currency.clone(),
+                account_number: account.account_number.clone(),
+            })
+            .collect()
+    });
+
+    ShareSnapshot {
+        version: SHARE_SNAPSHOT_VERSION,
+        generated_at,
+        base_currency: base_currency.to_string(),
+        total_value,
+        holdings: shared,
+        allocation,
+        accounts,
+    }
+}
+
+/// Expiry of a share link created at `now`. `ttl_hours` defaults to
+/// [`DEFAULT_SHARE_LINK_TTL_HOURS`] and may not exceed [`MAX_SHARE_LINK_TTL_HOURS`].
+pub fn share_link_expiry(ttl_hours: Option<i64>, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let hours = ttl_hours.unwrap_or(DEFAULT_SHARE_LINK_TTL_HOURS);
+    if !(1..=MAX_SHARE_LINK_TTL_HOURS).contains(&hours) {
+        return Err(Error::Validation(ValidationError::InvalidInput(format!(
+            "Share link lifetime must be between 1 and {} hours",
+            MAX_SHARE_LINK_TTL_HOURS
+        ))));
+    }
+    Ok(now + Duration::hours(hours))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::holdings::{Instrument, MonetaryValue};
+    use chrono::NaiveDate;
+    use rust_decimal_macros::dec;
+
+    fn holding(
+        holding_type: HoldingType,
+        kind: AssetKind,
+        symbol: &str,
+        name: &str,
+        value: Decimal,
+    ) -> Holding {
+        Holding {
+            id: format!("acct-1-{}", symbol),
+            account_id: "acct-1".to_string(),
+            holding_type,
+            instrument: Some(Instrument {
+                id: format!("asset-{}", symbol),
+                symbol: symbol.to_string(),
+                name: Some(name.to_string()),
+                currency: "USD".to_string(),
+                notes: Some("Bought with Jane's inheritance".to_string()),
+                pricing_mode: "MARKET".to_string(),
+                preferred_provider: None,
+                exchange_mic: None,
+                classifications: None,
+            }),
+            asset_kind: Some(kind),
+            quantity: dec!(1),
+            open_date: None,
+            lots: None,
+            contract_multiplier: dec!(1),
+            local_currency: "USD".to_string(),
+            base_currency: "CAD".to_string(),
+            fx_rate: Some(dec!(1.25)),
+            market_value: MonetaryValue {
+                local: value / dec!(1.25),
+                base: value,
+            },
+            cost_basis: None,
+            price: None,
+            purchase_price: None,
+            unrealized_gain: None,
+            unrealized_gain_pct: None,
+            realized_gain: None,
+            realized_gain_pct: None,
+            total_gain: None,
+            total_gain_pct: None,
+            income: None,
+            total_return: None,
+            total_return_pct: None,
+            return_basis: None,
+            day_change: None,
+            day_change_pct: None,
+            prev_close_value: None,
+            weight: Decimal::ZERO,
+            as_of_date: NaiveDate::from_ymd_opt(2026, 6, 25).unwrap(),
+            metadata: Some(serde_json::json!({ "address": "12 Lake Rd" })),
+            source_account_ids: Vec::new(),
+        }
+    }
+
+    fn sample() -> (Vec<Holding>, Vec<Account>) {
+        let holdings = vec![
+            holding(
+                HoldingType::Security,
+                AssetKind::Investment,
+                "VTI",
+                "Vanguard Total Market",
+                dec!(6000),
+            ),
+            holding(
+                HoldingType::AlternativeAsset,
+                AssetKind::Property,
+                "PROP-1",
+                "Cottage at 12 Lake Rd",
+                dec!(3000),
+            ),
+            holding(
+                HoldingType::Cash,
+                AssetKind::Investment,
+                "$CASH-CAD",
+                "Cash",
+                dec!(1000),
+            ),
+        ];
+        let accounts = vec![Account {
+            id: "acct-1".to_string(),
+            name: "Jane's RRSP".to_string(),
+            account_type: "SECURITIES".to_string(),
+            currency: "CAD".to_string(),
+            account_number: Some("12-3456789".to_string()),
+            ..Default::default()
+        }];
+        (holdings, accounts)
+    }
+
+    #[test]
+    fn snapshot_excludes_personal_data_by_default() {
+        let (holdings, accounts) = sample();
+        let snapshot = build_share_snapshot(
+            &holdings,
+            &accounts,
+            "CAD",
+            ShareSnapshotOptions::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(snapshot.total_value, dec!(10000));
+        assert_eq!(snapshot.holdings[0].symbol.as_deref(), Some("VTI"));
+        assert_eq!(snapshot.holdings[0].weight, dec!(0.6));
+        let categories: Vec<_> = snapshot
+            .allocation
+            .iter()
+            .map(|a| a.category.as_str())
+            .collect();
+        assert_eq!(categories, vec!["INVESTMENT", "PROPERTY", "CASH"]);
+        assert!(snapshot.accounts.is_none());
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        for personal in [
+            "12-3456789",
+            "Jane",
+            "acct-1",
+            "Lake Rd",
+            "PROP-1",
+            "inheritance",
+        ] {
+            assert!(!json.contains(personal), "snapshot leaks '{}'", personal);
+        }
+    }
+
+    #[test]
+    fn account_numbers_are_included_only_when_opted_in() {
+        let (holdings, accounts) = sample();
+        let snapshot = build_share_snapshot(
+            &holdings,
+            &accounts,
+            "CAD",
+            ShareSnapshotOptions {
+                include_account_details: true,
+            },
+            Utc::now(),
+        );
+
+        let accounts = snapshot.accounts.unwrap();
+        assert_eq!(accounts[0].account_number.as_deref(), Some("12-3456789"));
+        assert!(share_link_expiry(Some(0), Utc::now()).is_err());
+        assert!(share_link_expiry(Some(MAX_SHARE_LINK_TTL_HOURS + 1), Utc::now()).is_err());
+    }
+}
@@ -170,6 +170,92 @@ pub struct SyncEntityMetadata {
     pub last_seq: i64,
 }
 
+/// Field-level reason the cloud gave for rejecting a pushed record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFieldError {
+    /// Dotted path of the offending field, relative to the record (e.g. `payload.snapshotId`).
+    pub path: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+/// A local change that will not reach the cloud: a dead-lettered outbox event and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRejection {
+    pub event_id: String,
+    pub entity: SyncEntity,
+    pub entity_id: String,
+    pub op: SyncOperation,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    pub field_errors: Vec<SyncFieldError>,
+    pub suggested_fix: String,
+    pub created_at: String,
+}
+
+/// Which rejections to list: a page, newest first, optionally narrowed to some entities.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRejectionQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    /// Entities to include; empty means all.
+    #[serde(default)]
+    pub entities: Vec<SyncEntity>,
+}
+
+/// One page of rejections and how many match the query in total.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRejectionPage {
+    pub items: Vec<SyncRejection>,
+    pub total_count: i64,
+}
+
+/// Plain-language next step for a rejected record, based on what the cloud complained about.
+pub fn suggest_sync_fix(error_code: Option<&str>, field_errors: &[SyncFieldError]) -> String {
+    let mentions = |needle: &str| {
+        field_errors.iter().any(|e| {
+            e.path.to_ascii_lowercase().contains(needle)
+                || e.message.to_ascii_lowercase().contains(needle)
+        })
+    };
+    match error_code {
+        Some("invalid_entity_id") => {
+            return "This record has an ID other devices cannot accept. Re-create it to give it a \
+                    new ID."
+                .to_string()
+        }
+        Some("key_version_mismatch") => {
+            return "This change was encrypted with an old sync key. Make the change again on \
+                    this device."
+                .to_string()
+        }
+        _ => {}
+    }
+    if mentions("uuid") || field_errors.iter().any(|e| e.path.ends_with("Id")) {
+        return "A linked identifier is malformed. Edit the record, re-select the linked item \
+                and save it again."
+            .to_string();
+    }
+    if mentions("required") {
+        return "A required field is missing. Fill in the listed fields and save the record \
+                again."
+            .to_string();
+    }
+    if !field_errors.is_empty() {
+        return "Correct the listed fields and save the record again; it is pushed on the next \
+                sync."
+            .to_string();
+    }
+    "The cloud refused this change. Edit and save the record again, or contact support if it \
+     keeps failing."
+        .to_string()
+}
+
 /// Lightweight sync engine status.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
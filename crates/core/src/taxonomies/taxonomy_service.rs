@@ -1,7 +1,13 @@
 //! Taxonomy service implementation.
 
 use async_trait::async_trait;
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 use uuid::Uuid;
 
 use crate::errors::{DatabaseError, ValidationError};
@@ -15,11 +21,21 @@ use super::{
 
 pub struct TaxonomyService {
     repository: Arc<dyn TaxonomyRepositoryTrait>,
+    data_version: AtomicU64,
 }
 
 impl TaxonomyService {
     pub fn new(repository: Arc<dyn TaxonomyRepositoryTrait>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            data_version: AtomicU64::new(0),
+        }
+    }
+
+    /// Passes `result` through after a write, bumping the data version whether or not it failed.
+    fn changed<T>(&self, result: Result<T>) -> Result<T> {
+        self.data_version.fetch_add(1, Ordering::SeqCst);
+        result
     }
 
     /// Recursively flatten category JSON into NewCategory records
@@ -201,11 +217,11 @@ impl TaxonomyServiceTrait for TaxonomyService {
     }
 
     async fn create_taxonomy(&self, taxonomy: NewTaxonomy) -> Result<Taxonomy> {
-        self.repository.create_taxonomy(taxonomy).await
+        self.changed(self.repository.create_taxonomy(taxonomy).await)
     }
 
     async fn update_taxonomy(&self, taxonomy: Taxonomy) -> Result<Taxonomy> {
-        self.repository.update_taxonomy(taxonomy).await
+        self.changed(self.repository.update_taxonomy(taxonomy).await)
     }
 
     async fn delete_taxonomy(&self, id: &str) -> Result<usize> {
@@ -218,15 +234,15 @@ impl TaxonomyServiceTrait for TaxonomyService {
                 .into());
             }
         }
-        self.repository.delete_taxonomy(id).await
+        self.changed(self.repository.delete_taxonomy(id).await)
     }
 
     async fn create_category(&self, category: NewCategory) -> Result<Category> {
-        self.repository.create_category(category).await
+        self.changed(self.repository.create_category(category).await)
     }
 
     async fn update_category(&self, category: Category) -> Result<Category> {
-        self.repository.update_category(category).await
+        self.changed(self.repository.update_category(category).await)
     }
 
     async fn delete_category(&self, taxonomy_id: &str, category_id: &str) -> Result<usize> {
@@ -274,9 +290,11 @@ impl TaxonomyServiceTrait for TaxonomyService {
             .into());
         }
 
-        self.repository
+        let result = self
+            .repository
             .delete_category(taxonomy_id, category_id)
-            .await
+            .await;
+        self.changed(result)
     }
 
     async fn move_category(
@@ -297,7 +315,7 @@ impl TaxonomyServiceTrait for TaxonomyService {
             ..category
         };
 
-        self.repository.update_category(updated).await
+        self.changed(self.repository.update_category(updated).await)
     }
 
     async fn import_taxonomy_json(&self, json_str: &str) -> Result<Taxonomy> {
@@ -305,7 +323,7 @@ impl TaxonomyServiceTrait for TaxonomyService {
             .map_err(|e| ValidationError::InvalidInput(format!("Invalid JSON: {}", e)))?;
 
         // Create taxonomy (user-imported taxonomies are never system taxonomies)
-        let taxonomy = self
+        let created = self
             .repository
             .create_taxonomy(NewTaxonomy {
                 id: None,
@@ -317,7 +335,8 @@ impl TaxonomyServiceTrait for TaxonomyService {
                 sort_order: 0,
                 scope: "asset".to_string(),
             })
-            .await?;
+            .await;
+        let taxonomy = self.changed(created)?;
 
         // Flatten and create categories
         let mut sort_order = 0;
@@ -329,7 +348,7 @@ impl TaxonomyServiceTrait for TaxonomyService {
         );
 
         if !categories.is_empty() {
-            self.repository.bulk_create_categories(categories).await?;
+            self.changed(self.repository.bulk_create_categories(categories).await)?;
         }
 
         Ok(taxonomy)
@@ -381,13 +400,15 @@ impl TaxonomyServiceTrait for TaxonomyService {
         if let Some(taxonomy) = self.repository.get_taxonomy(&assignment.taxonomy_id)? {
             if taxonomy.is_single_select {
                 // Delete any existing assignments for this asset+taxonomy before creating new one
-                self.repository
+                let deleted = self
+                    .repository
                     .delete_asset_assignments(&assignment.asset_id, &assignment.taxonomy_id)
-                    .await?;
+                    .await;
+                self.changed(deleted)?;
             }
         }
 
-        self.repository.upsert_assignment(assignment).await
+        self.changed(self.repository.upsert_assignment(assignment).await)
     }
 
     async fn replace_asset_taxonomy_assignments(
@@ -397,12 +418,18 @@ impl TaxonomyServiceTrait for TaxonomyService {
         assignments: Vec<NewAssetTaxonomyAssignment>,
     ) -> Result<Vec<AssetTaxonomyAssignment>> {
         self.validate_asset_assignment_replacement(asset_id, taxonomy_id, &assignments)?;
-        self.repository
+        let result = self
+            .repository
             .replace_asset_assignments(asset_id, taxonomy_id, assignments)
-            .await
+            .await;
+        self.changed(result)
     }
 
     async fn remove_asset_assignment(&self, id: &str) -> Result<usize> {
-        self.repository.delete_assignment(id).await
+        self.changed(self.repository.delete_assignment(id).await)
+    }
+
+    fn data_version(&self) -> u64 {
+        self.data_version.load(Ordering::SeqCst)
     }
 }
@@ -127,4 +127,10 @@ pub trait TaxonomyServiceTrait: Send + Sync {
         assignments: Vec<NewAssetTaxonomyAssignment>,
     ) -> Result<Vec<AssetTaxonomyAssignment>>;
     async fn remove_asset_assignment(&self, id: &str) -> Result<usize>;
+
+    /// Counter that goes up after every taxonomy, category or assignment change made through
+    /// this service, so derived caches can tell they are stale.
+    fn data_version(&self) -> u64 {
+        0
+    }
 }
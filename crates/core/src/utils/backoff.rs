@@ -0,0 +1,65 @@
+//! Exponential backoff shared by every retry loop that talks to a cloud service.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+/// `base` doubled once per failed attempt after the first (counting from 1), never above `cap`.
+/// Attempt `0` is treated like attempt `1`.
+pub fn exponential_backoff(base: Duration, attempt: u32, cap: Duration) -> Duration {
+    let doublings = attempt.saturating_sub(1).min(31);
+    base.checked_mul(1 << doublings).unwrap_or(cap).min(cap)
+}
+
+/// `delay` plus a random extra of up to `max_fraction` of it, so clients that failed at the same
+/// moment do not all retry at the same moment.
+pub fn with_jitter(delay: Duration, max_fraction: f64) -> Duration {
+    if max_fraction.is_nan() || max_fraction <= 0.0 {
+        return delay;
+    }
+    let fraction = rand::thread_rng().gen_range(0.0..=max_fraction.min(1.0));
+    delay.saturating_add(delay.mul_f64(fraction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_double_per_attempt_up_to_the_cap() {
+        let base = Duration::from_millis(250);
+        let cap = Duration::from_secs(2);
+        let delays: Vec<_> = (0..=6)
+            .map(|attempt| exponential_backoff(base, attempt, cap))
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                base,
+                base,
+                Duration::from_millis(500),
+                Duration::from_secs(1),
+                cap,
+                cap,
+                cap,
+            ]
+        );
+        assert_eq!(exponential_backoff(base, u32::MAX, cap), cap);
+        assert_eq!(
+            exponential_backoff(Duration::from_secs(u64::MAX / 2), 3, Duration::MAX),
+            Duration::MAX
+        );
+    }
+
+    #[test]
+    fn jitter_only_ever_adds_up_to_the_fraction() {
+        let delay = Duration::from_secs(10);
+        for _ in 0..100 {
+            let jittered = with_jitter(delay, 0.2);
+            assert!(jittered >= delay && jittered <= Duration::from_secs(12));
+        }
+        assert_eq!(with_jitter(delay, 0.0), delay);
+        assert_eq!(with_jitter(delay, f64::NAN), delay);
+        assert_eq!(with_jitter(Duration::ZERO, 0.2), Duration::ZERO);
+    }
+}
@@ -0,0 +1,444 @@
+//! Transport-level retry for idempotent requests to cloud services.
+//!
+//! GET requests that fail with a connect error, a timeout or a `5xx` response are sent again a
+//! few times with exponential backoff, so momentary network blips never reach the caller.
+//! Every other method is sent exactly once, since retrying a POST could apply the write twice,
+//! unless the request carries an [`IDEMPOTENCY_KEY_HEADER`]: the server applies each key once,
+//! so such a request is retried like a GET, with the same key on every attempt.
+//!
+//! Connect failures are retried eagerly: the server was never reached, so it is most likely a
+//! network blip. A read timeout means the server accepted the request but is slow to answer,
+//! possibly overloaded, so it is retried fewer times with a longer wait.
+
+use std::time::Duration;
+
+use log::debug;
+use reqwest::{Client, ClientBuilder, Method, Request, Response, StatusCode};
+
+use super::backoff::exponential_backoff;
+
+/// Marks a mutating request as safe to retry. Its value must stay the same across the attempts
+/// of one logical operation.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Number of GET retries; `0` disables retrying.
+pub const HTTP_GET_RETRIES_ENV: &str = "WF_CLOUD_HTTP_GET_RETRIES";
+pub const DEFAULT_HTTP_GET_RETRIES: u32 = 2;
+/// Upper bound for the configured retry count.
+pub const MAX_HTTP_GET_RETRIES: u32 = 5;
+
+/// Seconds to wait for a connection to the server.
+pub const HTTP_CONNECT_TIMEOUT_ENV: &str = "WF_CLOUD_HTTP_CONNECT_TIMEOUT_SECS";
+/// Seconds to wait for the server to send data once connected.
+pub const HTTP_READ_TIMEOUT_ENV: &str = "WF_CLOUD_HTTP_READ_TIMEOUT_SECS";
+pub const DEFAULT_HTTP_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_HTTP_READ_TIMEOUT_SECS: u64 = 30;
+/// Upper bound for either configured timeout.
+pub const MAX_HTTP_TIMEOUT_SECS: u64 = 300;
+
+const DEFAULT_BASE_BACKOFF_MS: u64 = 250;
+const DEFAULT_READ_TIMEOUT_RETRIES: u32 = 1;
+const DEFAULT_READ_TIMEOUT_BACKOFF_MS: u64 = 1_000;
+const MAX_BACKOFF_MS: u64 = 4_000;
+
+/// Why a failed GET attempt is worth sending again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransientFailure {
+    /// The connection could not be established, including connect timeouts.
+    Connect,
+    /// Connected, but the server did not answer in time.
+    ReadTimeout,
+    /// The server answered with a `5xx` status.
+    ServerError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpRetryPolicy {
+    /// Extra attempts after the first one.
+    pub max_retries: u32,
+    /// Wait before the first retry after a connect failure or `5xx`; doubled for each further one.
+    pub base_backoff: Duration,
+    /// Extra attempts after read timeouts, within `max_retries`.
+    pub read_timeout_retries: u32,
+    /// Wait before the first retry after a read timeout; doubled for each further one.
+    pub read_timeout_backoff: Duration,
+}
+
+impl Default for HttpRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_HTTP_GET_RETRIES,
+            base_backoff: Duration::from_millis(DEFAULT_BASE_BACKOFF_MS),
+            read_timeout_retries: DEFAULT_READ_TIMEOUT_RETRIES,
+            read_timeout_backoff: Duration::from_millis(DEFAULT_READ_TIMEOUT_BACKOFF_MS),
+        }
+    }
+}
+
+/// Connect and read timeouts for cloud clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    pub connect: Duration,
+    pub read: Duration,
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(DEFAULT_HTTP_CONNECT_TIMEOUT_SECS),
+            read: Duration::from_secs(DEFAULT_HTTP_READ_TIMEOUT_SECS),
+        }
+    }
+}
+
+fn timeout_from_env(key: &str, default_secs: u64) -> Duration {
+    let secs = std::env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(|secs| secs.min(MAX_HTTP_TIMEOUT_SECS))
+        .unwrap_or(default_secs);
+    Duration::from_secs(secs)
+}
+
+impl HttpTimeouts {
+    /// Reads [`HTTP_CONNECT_TIMEOUT_ENV`] and [`HTTP_READ_TIMEOUT_ENV`], falling back to the
+    /// defaults when unset or invalid.
+    pub fn from_env() -> Self {
+        Self {
+            connect: timeout_from_env(HTTP_CONNECT_TIMEOUT_ENV, DEFAULT_HTTP_CONNECT_TIMEOUT_SECS),
+            read: timeout_from_env(HTTP_READ_TIMEOUT_ENV, DEFAULT_HTTP_READ_TIMEOUT_SECS),
+        }
+    }
+
+    /// Applies both timeouts. The whole request is additionally bounded by their sum, so a
+    /// server trickling a response cannot hold it open forever.
+    pub fn apply(&self, builder: ClientBuilder) -> ClientBuilder {
+        builder
+            .connect_timeout(self.connect)
+            .read_timeout(self.read)
+            .timeout(self.connect + self.read)
+    }
+}
+
+impl HttpRetryPolicy {
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Reads the retry count from [`HTTP_GET_RETRIES_ENV`], falling back to the default when it
+    /// is unset or invalid.
+    pub fn from_env() -> Self {
+        let max_retries = std::env::var(HTTP_GET_RETRIES_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .map(|retries| retries.min(MAX_HTTP_GET_RETRIES))
+            .unwrap_or(DEFAULT_HTTP_GET_RETRIES);
+        Self {
+            max_retries,
+            ..Self::default()
+        }
+    }
+
+    /// Wait before retry number `retry` (1-based) after a connect failure or `5xx`.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.backoff_for(TransientFailure::Connect, retry)
+    }
+
+    /// Wait before retry number `retry` (1-based) after `failure`.
+    pub fn backoff_for(&self, failure: TransientFailure, retry: u32) -> Duration {
+        let base = match failure {
+            TransientFailure::Connect | TransientFailure::ServerError => self.base_backoff,
+            TransientFailure::ReadTimeout => self.read_timeout_backoff,
+        };
+        exponential_backoff(base, retry, Duration::from_millis(MAX_BACKOFF_MS))
+    }
+
+    /// Whether another attempt is allowed after `failure`, given the retries already spent on
+    /// any failure and on read timeouts.
+    fn allows_retry(&self, failure: TransientFailure, retries: u32, read_timeouts: u32) -> bool {
+        retries < self.max_retries
+            && (failure != TransientFailure::ReadTimeout
+                || read_timeouts < self.read_timeout_retries)
+    }
+
+    /// Sends `request`, retrying transient failures when it is a GET or carries an
+    /// [`IDEMPOTENCY_KEY_HEADER`]. After the last retry the final `5xx` response or error is
+    /// returned as-is for the caller to handle.
+    pub async fn execute(&self, client: &Client, request: Request) -> reqwest::Result<Response> {
+        if request.method() != Method::GET
+            && !request.headers().contains_key(IDEMPOTENCY_KEY_HEADER)
+        {
+            return client.execute(request).await;
+        }
+
+        let mut retries = 0;
+        let mut read_timeouts = 0;
+        loop {
+            let attempt = match request.try_clone() {
+                Some(attempt) if retries < self.max_retries => attempt,
+                _ => return client.execute(request).await,
+            };
+            let failure = match client.execute(attempt).await {
+                Ok(response) if !is_transient_status(response.status()) => return Ok(response),
+                Ok(response) => {
+                    debug!(
+                        "{} {} returned {}; retry {}/{}",
+                        request.method(),
+                        request.url().path(),
+                        response.status(),
+                        retries + 1,
+                        self.max_retries
+                    );
+                    TransientFailure::ServerError
+                }
+                Err(err) => {
+                    let Some(failure) = classify_transient_error(&err)
+                        .filter(|f| self.allows_retry(*f, retries, read_timeouts))
+                    else {
+                        return Err(err);
+                    };
+                    debug!(
+                        "{} {} failed ({:?}): {}; retry {}/{}",
+                        request.method(),
+                        request.url().path(),
+                        failure,
+                        err,
+                        retries + 1,
+                        self.max_retries
+                    );
+                    failure
+                }
+            };
+            retries += 1;
+            let backoff = if failure == TransientFailure::ReadTimeout {
+                read_timeouts += 1;
+                self.backoff_for(failure, read_timeouts)
+            } else {
+                self.backoff_for(failure, retries)
+            };
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
+
+/// Connect failures and timeouts. A connect timeout is reported as a connect failure: the
+/// request never reached the server.
+pub fn classify_transient_error(err: &reqwest::Error) -> Option<TransientFailure> {
+    if err.is_connect() {
+        Some(TransientFailure::Connect)
+    } else if err.is_timeout() {
+        Some(TransientFailure::ReadTimeout)
+    } else {
+        None
+    }
+}
+
+pub fn is_transient_http_error(err: &reqwest::Error) -> bool {
+    classify_transient_error(err).is_some()
+}
+
+pub fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Answers `503` to the first `failures` requests and `200` afterwards, counting requests.
+    fn flaky_server(failures: usize) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut buffer = [0_u8; 4096];
+                let _ = stream.read(&mut buffer);
+                let seen = counter.fetch_add(1, Ordering::SeqCst);
+                let (status, body) = if seen < failures {
+                    ("503 Service Unavailable", "unavailable")
+                } else {
+                    ("200 OK", "ok")
+                };
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+                let _ = stream.flush();
+            }
+        });
+
+        (format!("http://{}/", addr), requests)
+    }
+
+    fn fast_policy(max_retries: u32) -> HttpRetryPolicy {
+        HttpRetryPolicy {
+            max_retries,
+            base_backoff: Duration::from_millis(1),
+            read_timeout_backoff: Duration::from_millis(2),
+            ..HttpRetryPolicy::default()
+        }
+    }
+
+    /// Accepts connections and reads requests without ever answering, counting requests.
+    fn silent_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+
+        thread::spawn(move || {
+            let mut open = Vec::new();
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut buffer = [0_u8; 4096];
+                let _ = stream.read(&mut buffer);
+                counter.fetch_add(1, Ordering::SeqCst);
+                open.push(stream);
+            }
+        });
+
+        (format!("http://{}/", addr), requests)
+    }
+
+    /// A local address nothing listens on.
+    fn closed_port_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test listener");
+        let addr = listener.local_addr().expect("listener addr");
+        drop(listener);
+        format!("http://{}/", addr)
+    }
+
+    #[tokio::test]
+    async fn get_is_retried_after_a_transient_server_error() {
+        let (url, requests) = flaky_server(2);
+        let client = Client::new();
+
+        let request = client.get(&url).build().unwrap();
+        let response = fast_policy(2).execute(&client, request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn get_returns_the_last_error_when_retries_run_out() {
+        let (url, requests) = flaky_server(usize::MAX);
+        let client = Client::new();
+
+        let request = client.get(&url).build().unwrap();
+        let response = fast_policy(1).execute(&client, request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let request = client.get(&url).build().unwrap();
+        let response = HttpRetryPolicy::disabled()
+            .execute(&client, request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn post_is_never_retried() {
+        let (url, requests) = flaky_server(1);
+        let client = Client::new();
+
+        let request = client.post(&url).body("{}").build().unwrap();
+        let response = fast_policy(3).execute(&client, request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn post_with_an_idempotency_key_is_retried() {
+        let (url, requests) = flaky_server(1);
+        let client = Client::new();
+
+        let request = client
+            .post(&url)
+            .header(
+                IDEMPOTENCY_KEY_HEADER,
+                "5a8f3c2e-0d4b-4e1a-9c7d-2b6f8e1a3c5d",
+            )
+            .body("{}")
+            .build()
+            .unwrap();
+        let response = fast_policy(3).execute(&client, request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn connect_failures_and_read_timeouts_are_retried_differently() {
+        let timeouts = HttpTimeouts {
+            connect: Duration::from_millis(200),
+            read: Duration::from_millis(50),
+        };
+        let client = timeouts.apply(Client::builder()).build().unwrap();
+        let policy = fast_policy(3);
+
+        let request = client.get(closed_port_url()).build().unwrap();
+        let err = client
+            .execute(request.try_clone().unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            classify_transient_error(&err),
+            Some(TransientFailure::Connect)
+        );
+        let err = policy.execute(&client, request).await.unwrap_err();
+        assert!(err.is_connect());
+
+        let (url, requests) = silent_server();
+        let request = client.get(&url).build().unwrap();
+        let err = client
+            .execute(request.try_clone().unwrap())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            classify_transient_error(&err),
+            Some(TransientFailure::ReadTimeout)
+        );
+        requests.store(0, Ordering::SeqCst);
+        let err = policy.execute(&client, request).await.unwrap_err();
+        assert!(err.is_timeout());
+        // One retry after a read timeout although the policy allows three.
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let policy = HttpRetryPolicy::default();
+        assert!(
+            policy.backoff_for(TransientFailure::ReadTimeout, 1)
+                > policy.backoff_for(TransientFailure::Connect, 1)
+        );
+        assert!(policy.allows_retry(TransientFailure::Connect, 1, 0));
+        assert!(!policy.allows_retry(TransientFailure::ReadTimeout, 1, 1));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = HttpRetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(250));
+        assert_eq!(policy.backoff(2), Duration::from_millis(500));
+        assert_eq!(policy.backoff(10), Duration::from_millis(MAX_BACKOFF_MS));
+    }
+}
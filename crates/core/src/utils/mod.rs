@@ -1,6 +1,8 @@
 // This file declares utility modules
+pub mod backoff;
 pub mod cusip;
 pub mod decimal_serde;
+pub mod http_retry;
 pub mod isin;
 pub mod occ_symbol;
 pub mod time_utils;
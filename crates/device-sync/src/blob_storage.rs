@@ -0,0 +1,114 @@
+//! Location for snapshot images staged on disk.
+//!
+//! Snapshot uploads export the sync tables into a SQLite image and restores write the
+//! downloaded image to disk before importing it. Both can be large, so the directory is
+//! configurable through `DEVICE_SYNC_STORAGE_DIR` and defaults to the system temp dir.
+//! Images are removed as soon as the upload or restore finishes, so changing the directory
+//! needs no migration.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Environment variable selecting the snapshot staging directory.
+pub const DEVICE_SYNC_STORAGE_DIR_ENV: &str = "DEVICE_SYNC_STORAGE_DIR";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncBlobStorage {
+    dir: PathBuf,
+}
+
+impl SyncBlobStorage {
+    /// Uses `dir`, creating it if needed. Fails when the directory cannot be written.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        let probe = dir.join(format!(".wf_write_probe_{}", uuid::Uuid::new_v4().simple()));
+        std::fs::write(&probe, b"")?;
+        std::fs::remove_file(&probe)?;
+        Ok(Self { dir })
+    }
+
+    /// Stages images in the system temp directory.
+    pub fn temp() -> Self {
+        Self {
+            dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Reads `DEVICE_SYNC_STORAGE_DIR`, falling back to [`SyncBlobStorage::temp`] when unset.
+    pub fn from_env() -> io::Result<Self> {
+        match std::env::var(DEVICE_SYNC_STORAGE_DIR_ENV)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+        {
+            Some(dir) => Self::new(&dir).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("{DEVICE_SYNC_STORAGE_DIR_ENV} '{dir}' is not writable: {e}"),
+                )
+            }),
+            None => Ok(Self::temp()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// A fresh, unique path for a SQLite image named after `prefix`.
+    pub fn blob_path(&self, prefix: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}_{}.db", prefix, uuid::Uuid::new_v4().simple()))
+    }
+
+    /// Writes `bytes` to a fresh path and returns it. The caller removes the file.
+    pub fn write_blob(&self, prefix: &str, bytes: &[u8]) -> io::Result<PathBuf> {
+        let path = self.blob_path(prefix);
+        std::fs::write(&path, bytes)?;
+        Ok(path)
+    }
+}
+
+impl Default for SyncBlobStorage {
+    fn default() -> Self {
+        Self::temp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("wf_blob_storage_test_{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn blobs_are_written_to_and_read_from_the_configured_dir() {
+        let root = scratch_dir();
+        let storage = SyncBlobStorage::new(root.join("nested/sync")).unwrap();
+        assert!(storage.dir().is_dir());
+
+        let path = storage.write_blob("wf_snapshot", b"sqlite image").unwrap();
+        assert_eq!(path.parent(), Some(storage.dir()));
+        assert_eq!(std::fs::read(&path).unwrap(), b"sqlite image");
+        // Only the blob remains; the writability probe is cleaned up.
+        assert_eq!(std::fs::read_dir(storage.dir()).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn unusable_dir_is_rejected() {
+        let root = scratch_dir();
+        std::fs::create_dir_all(&root).unwrap();
+        let file = root.join("not-a-dir");
+        std::fs::write(&file, b"").unwrap();
+
+        assert!(SyncBlobStorage::new(&file).is_err());
+        assert!(SyncBlobStorage::new(file.join("child")).is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -3,11 +3,13 @@
 //! This client uses the REST API endpoints for device synchronization.
 
 use log::debug;
-use rand::Rng;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
+use reqwest::header::{
+    HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, RETRY_AFTER,
+};
 use reqwest::{Method, RequestBuilder, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
@@ -15,15 +17,26 @@ use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::time::sleep;
 use uuid::Uuid;
-
-use crate::error::{DeviceSyncError, Result};
+use wealthfolio_core::utils::backoff::with_jitter;
+use wealthfolio_core::utils::http_retry::{HttpRetryPolicy, HttpTimeouts, IDEMPOTENCY_KEY_HEADER};
+
+use crate::crypto::{is_valid_checksum, sha256_checksum, verify_checksum};
+use crate::error::{
+    parse_retry_after, truncate_error_message, DeviceSyncError, Result,
+    DEFAULT_MAX_ERROR_MESSAGE_LEN, SYNC_CREDENTIAL_ROTATION_UNSUPPORTED,
+};
+use crate::retry::RetryPolicy;
 use crate::types::*;
 
-/// Default timeout for API requests.
-const DEFAULT_TIMEOUT_SECS: u64 = 30;
 const SNAPSHOT_UPLOAD_MAX_ATTEMPTS: usize = 5;
-const SNAPSHOT_UPLOAD_BASE_BACKOFF_MS: u64 = 250;
-const SNAPSHOT_UPLOAD_MAX_BACKOFF_MS: u64 = 8_000;
+const SNAPSHOT_UPLOAD_RETRY: RetryPolicy = RetryPolicy {
+    base_delay: Duration::from_millis(250),
+    max_delay: Duration::from_secs(8),
+    multiplier: 2.0,
+    max_attempts: SNAPSHOT_UPLOAD_MAX_ATTEMPTS as u32,
+};
+/// Up to this fraction of the backoff is added at random between snapshot upload attempts.
+const SNAPSHOT_UPLOAD_JITTER: f64 = 0.2;
 const CLIENT_REQUEST_ID_HEADER: &str = "x-wf-client-request-id";
 const SERVER_REQUEST_ID_HEADER: &str = "x-request-id";
 
@@ -33,17 +46,6 @@ fn snapshot_upload_in_flight() -> &'static Mutex<HashSet<String>> {
     SNAPSHOT_UPLOAD_IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
 }
 
-fn compute_sha256_checksum(payload: &[u8]) -> String {
-    crate::crypto::sha256_checksum(payload)
-}
-
-fn is_valid_sha256_checksum(checksum: &str) -> bool {
-    let Some(hex) = checksum.strip_prefix("sha256:") else {
-        return false;
-    };
-    hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit())
-}
-
 fn is_retryable_snapshot_status(status: u16) -> bool {
     matches!(status, 408 | 429 | 500..=599)
 }
@@ -62,16 +64,28 @@ fn is_retryable_snapshot_error(status: u16, code: Option<&str>, message: Option<
     false
 }
 
+/// Idempotency key for a push batch. It is derived from the batch's event IDs, so every resend
+/// of the same outbox batch carries the same key, whether the HTTP retry policy resends it or
+/// the next sync cycle does.
+fn push_idempotency_key(req: &SyncPushRequest) -> String {
+    let mut hasher = Sha256::new();
+    for event in &req.events {
+        hasher.update(event.event_id.as_bytes());
+        hasher.update(b"\n");
+    }
+    let digest = hasher.finalize();
+    let mut bytes = [0_u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Uuid::from_bytes(bytes).to_string()
+}
+
 fn is_retryable_transport_error(err: &reqwest::Error) -> bool {
     err.is_timeout() || err.is_connect() || err.is_request() || err.is_body()
 }
 
 fn snapshot_backoff_with_jitter(attempt: usize) -> Duration {
-    let exp = (attempt.saturating_sub(1) as u32).min(8);
-    let backoff = (SNAPSHOT_UPLOAD_BASE_BACKOFF_MS.saturating_mul(1_u64 << exp))
-        .min(SNAPSHOT_UPLOAD_MAX_BACKOFF_MS);
-    let jitter = rand::thread_rng().gen_range(0..=(backoff / 5).max(1));
-    Duration::from_millis(backoff.saturating_add(jitter))
+    let backoff = SNAPSHOT_UPLOAD_RETRY.backoff(u32::try_from(attempt).unwrap_or(u32::MAX));
+    with_jitter(backoff, SNAPSHOT_UPLOAD_JITTER)
 }
 
 #[derive(Debug, Clone)]
@@ -125,6 +139,13 @@ fn server_request_id(headers: &HeaderMap) -> Option<String> {
         .map(str::to_string)
 }
 
+fn retry_after_header(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, chrono::Utc::now()))
+}
+
 fn request_metadata_suffix(
     context: &CloudRequestContext,
     server_request_id: Option<&str>,
@@ -141,15 +162,13 @@ fn request_metadata_suffix(
     }
 }
 
-fn with_request_metadata(
-    message: impl Into<String>,
-    context: &CloudRequestContext,
-    server_request_id: Option<&str>,
-) -> String {
+/// Error message with our request ID; the server's travels in [`DeviceSyncError::Api`] and is
+/// shown by its `Display`.
+fn with_request_metadata(message: impl Into<String>, context: &CloudRequestContext) -> String {
     format!(
-        "{} ({})",
+        "{} (clientRequestId={})",
         message.into(),
-        request_metadata_suffix(context, server_request_id)
+        context.client_request_id
     )
 }
 
@@ -222,6 +241,9 @@ fn log_failed_cloud_request(
 pub struct DeviceSyncClient {
     client: reqwest::Client,
     base_url: String,
+    retry: HttpRetryPolicy,
+    /// Longest error message kept from a failed response; see [`truncate_error_message`].
+    max_error_message_len: usize,
 }
 
 impl DeviceSyncClient {
@@ -344,17 +366,41 @@ impl DeviceSyncClient {
     ///
     /// * `base_url` - The base URL of the cloud API (e.g., "https://api.wealthfolio.app")
     pub fn new(base_url: &str) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+        let client = HttpTimeouts::from_env()
+            .apply(reqwest::Client::builder())
             .build()
             .expect("Failed to build HTTP client");
 
         Self {
             client,
             base_url: base_url.trim_end_matches('/').to_string(),
+            retry: HttpRetryPolicy::from_env(),
+            max_error_message_len: DEFAULT_MAX_ERROR_MESSAGE_LEN,
         }
     }
 
+    /// Replace the retry policy for GET requests (read from the environment by default).
+    pub fn with_http_retry(mut self, retry: HttpRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Replace the longest error message kept from a failed response
+    /// ([`DEFAULT_MAX_ERROR_MESSAGE_LEN`] bytes by default).
+    pub fn with_max_error_message_len(mut self, max_len: usize) -> Self {
+        self.max_error_message_len = max_len;
+        self
+    }
+
+    /// Error message from a response body, truncated, then tagged with the request ID so the
+    /// ID survives the cut.
+    fn api_error_message(&self, message: String, context: &CloudRequestContext) -> String {
+        with_request_metadata(
+            truncate_error_message(message, self.max_error_message_len),
+            context,
+        )
+    }
+
     /// Create headers for an API request with optional device ID.
     fn headers_with_device(
         &self,
@@ -396,7 +442,7 @@ impl DeviceSyncClient {
         let response = self
             .send_request(&context, self.client.request(method, &url).headers(headers))
             .await?;
-        Self::parse_response(response, &context).await
+        self.parse_response(response, &context).await
     }
 
     async fn send_json_body<T: DeserializeOwned, B: Serialize + ?Sized>(
@@ -406,10 +452,32 @@ impl DeviceSyncClient {
         token: &str,
         device_id: Option<&str>,
         body: &B,
+    ) -> Result<T> {
+        self.send_json_body_with_idempotency_key(method, path, token, device_id, body, None)
+            .await
+    }
+
+    /// Like [`Self::send_json_body`]; with an `idempotency_key` the retry policy may resend the
+    /// write and the server still applies it once.
+    async fn send_json_body_with_idempotency_key<T: DeserializeOwned, B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: String,
+        token: &str,
+        device_id: Option<&str>,
+        body: &B,
+        idempotency_key: Option<&str>,
     ) -> Result<T> {
         let context = CloudRequestContext::new(method.as_str(), path.clone(), device_id);
         let url = format!("{}{}", self.base_url, path);
-        let headers = self.headers_with_device(token, device_id, &context)?;
+        let mut headers = self.headers_with_device(token, device_id, &context)?;
+        if let Some(key) = idempotency_key {
+            headers.insert(
+                IDEMPOTENCY_KEY_HEADER,
+                HeaderValue::from_str(key)
+                    .map_err(|_| DeviceSyncError::invalid_request("Invalid idempotency key"))?,
+            );
+        }
         let response = self
             .send_request(
                 &context,
@@ -419,7 +487,7 @@ impl DeviceSyncClient {
                     .json(body),
             )
             .await?;
-        Self::parse_response(response, &context).await
+        self.parse_response(response, &context).await
     }
 
     async fn send_request(
@@ -427,22 +495,36 @@ impl DeviceSyncClient {
         context: &CloudRequestContext,
         request: RequestBuilder,
     ) -> Result<reqwest::Response> {
-        request.send().await.map_err(|err| {
-            log_failed_cloud_request(context, None, None);
-            DeviceSyncError::Http(err)
-        })
+        let request = match request.build() {
+            Ok(request) => request,
+            Err(err) => {
+                log_failed_cloud_request(context, None, None);
+                return Err(DeviceSyncError::from(err));
+            }
+        };
+        // Only GETs and writes carrying an idempotency key are retried; other writes go out
+        // exactly once.
+        self.retry
+            .execute(&self.client, request)
+            .await
+            .map_err(|err| {
+                log_failed_cloud_request(context, None, None);
+                DeviceSyncError::from(err)
+            })
     }
 
     /// Parse a JSON response body.
     async fn parse_response<T: DeserializeOwned>(
+        &self,
         response: reqwest::Response,
         context: &CloudRequestContext,
     ) -> Result<T> {
         let status = response.status();
         let request_id = server_request_id(response.headers());
+        let retry_after = retry_after_header(response.headers());
         let body = response.text().await.map_err(|err| {
             log_failed_cloud_request(context, Some(status), request_id.as_deref());
-            DeviceSyncError::Http(err)
+            DeviceSyncError::from(err)
         })?;
 
         if !status.is_success() {
@@ -456,18 +538,18 @@ impl DeviceSyncClient {
                 return Err(DeviceSyncError::api_structured(
                     status.as_u16(),
                     code,
-                    with_request_metadata(error.message, context, request_id.as_deref()),
+                    self.api_error_message(error.message, context),
                     details_with_request_metadata(error.details, context, request_id.as_deref()),
-                ));
+                    request_id,
+                )
+                .with_retry_after(retry_after));
             }
             return Err(DeviceSyncError::api(
                 status.as_u16(),
-                with_request_metadata(
-                    fallback_api_error_message(&body),
-                    context,
-                    request_id.as_deref(),
-                ),
-            ));
+                self.api_error_message(fallback_api_error_message(&body), context),
+            )
+            .with_request_id(request_id)
+            .with_retry_after(retry_after));
         }
 
         serde_json::from_str(&body).map_err(|e| {
@@ -479,17 +561,15 @@ impl DeviceSyncClient {
             );
             DeviceSyncError::api(
                 status.as_u16(),
-                with_request_metadata(
-                    format!("Failed to parse response: {}", e),
-                    context,
-                    request_id.as_deref(),
-                ),
+                with_request_metadata(format!("Failed to parse response: {}", e), context),
             )
+            .with_request_id(request_id)
         })
     }
 
     /// Parse a binary response body while preserving API error handling.
     async fn parse_binary_response(
+        &self,
         response: reqwest::Response,
         context: &CloudRequestContext,
     ) -> Result<reqwest::Response> {
@@ -499,9 +579,10 @@ impl DeviceSyncClient {
         }
 
         let request_id = server_request_id(response.headers());
+        let retry_after = retry_after_header(response.headers());
         let body = response.text().await.map_err(|err| {
             log_failed_cloud_request(context, Some(status), request_id.as_deref());
-            DeviceSyncError::Http(err)
+            DeviceSyncError::from(err)
         })?;
         log_failed_cloud_request(context, Some(status), request_id.as_deref());
         if let Ok(error) = serde_json::from_str::<ApiErrorResponse>(&body) {
@@ -513,19 +594,19 @@ impl DeviceSyncClient {
             return Err(DeviceSyncError::api_structured(
                 status.as_u16(),
                 code,
-                with_request_metadata(error.message, context, request_id.as_deref()),
+                self.api_error_message(error.message, context),
                 details_with_request_metadata(error.details, context, request_id.as_deref()),
-            ));
+                request_id,
+            )
+            .with_retry_after(retry_after));
         }
 
         Err(DeviceSyncError::api(
             status.as_u16(),
-            with_request_metadata(
-                fallback_api_error_message(&body),
-                context,
-                request_id.as_deref(),
-            ),
-        ))
+            self.api_error_message(fallback_api_error_message(&body), context),
+        )
+        .with_request_id(request_id)
+        .with_retry_after(retry_after))
     }
 
     fn parse_required_header_i32(headers: &HeaderMap, name: &'static str) -> Result<i32> {
@@ -636,6 +717,7 @@ impl DeviceSyncClient {
     }
 
     /// Revoke a device's trust.
+    /// An unknown device ID fails with [`DeviceSyncError::InvalidRequest`].
     ///
     /// POST /api/v1/sync/team/devices/{deviceId}/revoke
     pub async fn revoke_device(&self, token: &str, device_id: &str) -> Result<SuccessResponse> {
@@ -646,6 +728,42 @@ impl DeviceSyncClient {
             None,
         )
         .await
+        .map_err(|e| match e.status_code() {
+            Some(404) => {
+                DeviceSyncError::invalid_request(format!("Device {} does not exist", device_id))
+            }
+            _ => e,
+        })
+    }
+
+    /// Rotate a device's credential without re-enrolling.
+    /// The server invalidates the previous public key once it accepts the new one.
+    ///
+    /// POST /api/v1/sync/team/devices/{deviceId}/credential/rotate
+    pub async fn rotate_device_credential(
+        &self,
+        token: &str,
+        device_id: &str,
+        req: RotateDeviceCredentialRequest,
+    ) -> Result<RotateDeviceCredentialResponse> {
+        self.send_json_body(
+            Method::POST,
+            format!("/api/v1/sync/team/devices/{}/credential/rotate", device_id),
+            token,
+            Some(device_id),
+            &req,
+        )
+        .await
+        .map_err(|e| match e.status_code() {
+            Some(404) => DeviceSyncError::api_structured(
+                404,
+                SYNC_CREDENTIAL_ROTATION_UNSUPPORTED,
+                "The sync server does not support credential rotation",
+                None,
+                None,
+            ),
+            _ => e,
+        })
     }
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -762,7 +880,8 @@ impl DeviceSyncClient {
     // Sync Events + Snapshots
     // ─────────────────────────────────────────────────────────────────────────
 
-    /// Push local outbox events.
+    /// Push local outbox events. The request carries an idempotency key derived from the
+    /// batch, so a resent batch is applied once.
     ///
     /// POST /api/v1/sync/events/push
     pub async fn push_events(
@@ -771,12 +890,14 @@ impl DeviceSyncClient {
         device_id: &str,
         req: SyncPushRequest,
     ) -> Result<SyncPushResponse> {
-        self.send_json_body(
+        let idempotency_key = push_idempotency_key(&req);
+        self.send_json_body_with_idempotency_key(
             Method::POST,
             "/api/v1/sync/events/push".to_string(),
             token,
             Some(device_id),
             &req,
+            Some(&idempotency_key),
         )
         .await
     }
@@ -921,9 +1042,9 @@ impl DeviceSyncClient {
             .await
             .map_err(|err| {
                 log_failed_cloud_request(&context, None, None);
-                DeviceSyncError::Http(err)
+                DeviceSyncError::from(err)
             })?;
-        let response = Self::parse_binary_response(response, &context).await?;
+        let response = self.parse_binary_response(response, &context).await?;
         let headers = response.headers().clone();
         let body = response.bytes().await?.to_vec();
 
@@ -945,7 +1066,7 @@ impl DeviceSyncClient {
     ///
     /// The client performs single-call idempotent upload with retry hardening:
     /// - validates size/checksum against payload bytes
-    /// - reuses the same `X-Snapshot-Event-Id` across retries
+    /// - reuses the same `X-Snapshot-Event-Id`, also sent as the `Idempotency-Key`, across retries
     /// - retries transient/unknown-outcome failures with exponential backoff + jitter
     ///
     /// POST /api/v1/sync/snapshots/upload
@@ -981,21 +1102,17 @@ impl DeviceSyncClient {
                 upload_headers.size_bytes, payload_size
             )));
         }
-        if !is_valid_sha256_checksum(&upload_headers.checksum) {
+        if !is_valid_checksum(&upload_headers.checksum) {
             return Err(DeviceSyncError::invalid_request(
                 "Invalid snapshot checksum format; expected sha256:<hex>",
             ));
         }
-        let computed_checksum = compute_sha256_checksum(&payload);
-        if !upload_headers
-            .checksum
-            .eq_ignore_ascii_case(&computed_checksum)
-        {
+        if !verify_checksum(&payload, &upload_headers.checksum) {
             return Err(DeviceSyncError::invalid_request(
                 "Snapshot checksum does not match payload bytes",
             ));
         }
-        upload_headers.checksum = computed_checksum.to_ascii_lowercase();
+        upload_headers.checksum = sha256_checksum(&payload);
 
         let stable_event_id = match upload_headers.event_id.take() {
             Some(value) => {
@@ -1063,12 +1180,10 @@ impl DeviceSyncClient {
                 HeaderValue::from_static("application/octet-stream"),
             );
             if let Some(event_id) = upload_headers.event_id.as_deref() {
-                headers.insert(
-                    "x-snapshot-event-id",
-                    HeaderValue::from_str(event_id).map_err(|_| {
-                        DeviceSyncError::invalid_request("Invalid snapshot event ID")
-                    })?,
-                );
+                let event_id = HeaderValue::from_str(event_id)
+                    .map_err(|_| DeviceSyncError::invalid_request("Invalid snapshot event ID"))?;
+                headers.insert(IDEMPOTENCY_KEY_HEADER, event_id.clone());
+                headers.insert("x-snapshot-event-id", event_id);
             }
             headers.insert(
                 "x-snapshot-schema-version",
@@ -1125,13 +1240,14 @@ impl DeviceSyncClient {
                 Ok(response) => {
                     let status = response.status();
                     if status.is_success() {
-                        return Self::parse_response(response, &context).await;
+                        return self.parse_response(response, &context).await;
                     }
 
                     let request_id = server_request_id(response.headers());
+                    let retry_after = retry_after_header(response.headers());
                     let body = response.text().await.map_err(|err| {
                         log_failed_cloud_request(&context, Some(status), request_id.as_deref());
-                        DeviceSyncError::Http(err)
+                        DeviceSyncError::from(err)
                     })?;
                     log_failed_cloud_request(&context, Some(status), request_id.as_deref());
                     let mut parsed_error_code: Option<String> = None;
@@ -1149,23 +1265,22 @@ impl DeviceSyncClient {
                             DeviceSyncError::api_structured(
                                 status.as_u16(),
                                 code,
-                                with_request_metadata(message, &context, request_id.as_deref()),
+                                self.api_error_message(message, &context),
                                 details_with_request_metadata(
                                     api_error.details,
                                     &context,
                                     request_id.as_deref(),
                                 ),
+                                request_id,
                             )
                         } else {
                             DeviceSyncError::api(
                                 status.as_u16(),
-                                with_request_metadata(
-                                    fallback_api_error_message(&body),
-                                    &context,
-                                    request_id.as_deref(),
-                                ),
+                                self.api_error_message(fallback_api_error_message(&body), &context),
                             )
-                        };
+                            .with_request_id(request_id)
+                        }
+                        .with_retry_after(retry_after);
 
                     if is_retryable_snapshot_error(
                         status.as_u16(),
@@ -1173,7 +1288,12 @@ impl DeviceSyncClient {
                         parsed_error_message.as_deref(),
                     ) && attempt < SNAPSHOT_UPLOAD_MAX_ATTEMPTS
                     {
-                        let backoff = snapshot_backoff_with_jitter(attempt);
+                        // The upload is held open while it waits, so the server's hint is
+                        // honored only up to our own backoff cap.
+                        let backoff = retry_after.map_or_else(
+                            || snapshot_backoff_with_jitter(attempt),
+                            |delay| delay.min(SNAPSHOT_UPLOAD_RETRY.max_delay),
+                        );
                         debug!(
                             "Snapshot upload retry attempt {}/{} after HTTP {} code={:?} (event_id={})",
                             attempt + 1,
@@ -1202,7 +1322,7 @@ impl DeviceSyncClient {
                         sleep(backoff).await;
                         continue;
                     }
-                    return Err(DeviceSyncError::Http(err));
+                    return Err(DeviceSyncError::from(err));
                 }
             }
         }
@@ -1430,6 +1550,7 @@ mod tests {
     #[derive(Debug, Clone)]
     struct CapturedUploadRequest {
         event_id: Option<String>,
+        idempotency_key: Option<String>,
         client_request_id: Option<String>,
         request_id: Option<String>,
         device_id: Option<String>,
@@ -1467,7 +1588,7 @@ mod tests {
             schema_version: 1,
             covers_tables: vec!["accounts".to_string(), "assets".to_string()],
             size_bytes: payload.len() as i64,
-            checksum: compute_sha256_checksum(payload),
+            checksum: sha256_checksum(payload),
             metadata_payload: "meta".to_string(),
             payload_key_version: 1,
             base_seq: None,
@@ -1582,6 +1703,7 @@ mod tests {
                         return;
                     };
                     let event_id = headers.get("x-snapshot-event-id").cloned();
+                    let idempotency_key = headers.get(IDEMPOTENCY_KEY_HEADER).cloned();
                     let client_request_id = headers.get(CLIENT_REQUEST_ID_HEADER).cloned();
                     let request_id = headers.get(SERVER_REQUEST_ID_HEADER).cloned();
                     let device_id = headers.get("x-wf-device-id").cloned();
@@ -1589,6 +1711,7 @@ mod tests {
                     let snapshot_size_bytes = headers.get("x-snapshot-size-bytes").cloned();
                     captured_inner.lock().await.push(CapturedUploadRequest {
                         event_id,
+                        idempotency_key,
                         client_request_id,
                         request_id,
                         device_id,
@@ -1675,11 +1798,7 @@ mod tests {
         let body = r#"{"path":["snapshotId"],"message":"Invalid UUID"}"#;
         let err = DeviceSyncError::api(
             400,
-            with_request_metadata(
-                fallback_api_error_message(body),
-                &context,
-                Some("server-req-1"),
-            ),
+            with_request_metadata(fallback_api_error_message(body), &context),
         );
 
         assert!(err.is_snapshot_id_validation_error());
@@ -1720,6 +1839,14 @@ mod tests {
         let second_id = requests[1].event_id.clone().expect("second event id");
         assert_eq!(first_id, second_id);
         assert!(Uuid::parse_str(&first_id).is_ok());
+        assert_eq!(
+            requests[0].idempotency_key.as_deref(),
+            Some(first_id.as_str())
+        );
+        assert_eq!(
+            requests[1].idempotency_key.as_deref(),
+            Some(first_id.as_str())
+        );
         let first_client_request_id = requests[0]
             .client_request_id
             .clone()
@@ -1744,6 +1871,69 @@ mod tests {
         server.abort();
     }
 
+    #[tokio::test]
+    async fn retried_event_push_reuses_its_idempotency_key() {
+        let push_ok = r#"{"accepted":[],"duplicate":[],"serverCursor":7}"#.to_string();
+        let (base_url, captured, server) = start_mock_upload_server(vec![
+            MockUploadOutcome::Respond {
+                status: 503,
+                body: api_error_body("UNAVAILABLE", "retry please"),
+                delay_ms: 0,
+            },
+            MockUploadOutcome::Respond {
+                status: 200,
+                body: push_ok.clone(),
+                delay_ms: 0,
+            },
+            MockUploadOutcome::Respond {
+                status: 200,
+                body: push_ok,
+                delay_ms: 0,
+            },
+        ])
+        .await;
+        let client = DeviceSyncClient::new(&base_url).with_http_retry(HttpRetryPolicy {
+            max_retries: 1,
+            base_backoff: Duration::from_millis(1),
+            ..HttpRetryPolicy::default()
+        });
+        let batch = || SyncPushRequest {
+            events: vec![SyncPushEventRequest {
+                event_id: "019bb9fe-f707-71e9-a40d-733575f4f246".to_string(),
+                device_id: "device-1".to_string(),
+                event_type: "update".to_string(),
+                entity: SyncEntity::Account,
+                entity_id: "acc-1".to_string(),
+                client_timestamp: "2026-01-01T00:00:00.000Z".to_string(),
+                payload: "payload".to_string(),
+                payload_key_version: 1,
+            }],
+        };
+
+        client
+            .push_events("token", "device-1", batch())
+            .await
+            .expect("push success after retry");
+        // The next sync cycle resends the same outbox batch.
+        client
+            .push_events("token", "device-1", batch())
+            .await
+            .expect("push success");
+
+        let requests = captured.lock().await.clone();
+        assert_eq!(requests.len(), 3);
+        let key = requests[0]
+            .idempotency_key
+            .clone()
+            .expect("idempotency key");
+        assert!(Uuid::parse_str(&key).is_ok());
+        assert!(requests
+            .iter()
+            .all(|request| request.idempotency_key.as_deref() == Some(key.as_str())));
+
+        server.abort();
+    }
+
     #[tokio::test]
     async fn snapshot_upload_retries_unknown_outcome_with_same_event_id() {
         let stable_event_id = Uuid::new_v4().to_string();
@@ -1862,6 +2052,77 @@ mod tests {
         server.abort();
     }
 
+    #[tokio::test]
+    async fn oversized_error_messages_are_truncated_but_details_are_kept() {
+        let trace = "at handler (worker.js:1:1)\\n".repeat(500);
+        let body = format!(
+            r#"{{"error":"error","code":"invalid_format","message":"Invalid UUID for snapshotId: {}","details":{{"trace":"{}"}}}}"#,
+            trace, trace
+        );
+        let (base_url, _, server) = start_mock_upload_server(vec![
+            MockUploadOutcome::Respond {
+                status: 400,
+                body,
+                delay_ms: 0,
+            },
+            MockUploadOutcome::Respond {
+                status: 413,
+                body: format!("<html>{}</html>", "x".repeat(10_000)),
+                delay_ms: 0,
+            },
+        ])
+        .await;
+        let client = DeviceSyncClient::new(&base_url).with_max_error_message_len(256);
+        let payload = b"snapshot-payload-oversized-error".to_vec();
+
+        let err = client
+            .upload_snapshot(
+                "token",
+                "019bb9fe-f707-71e9-a40d-733575f4f246",
+                build_upload_headers(None, &payload),
+                payload,
+            )
+            .await
+            .expect_err("upload rejected");
+
+        assert!(err.is_snapshot_id_validation_error());
+        let DeviceSyncError::Api {
+            message, details, ..
+        } = &err
+        else {
+            panic!("expected an API error, got {:?}", err);
+        };
+        let (text, metadata) = message
+            .split_once(" (clientRequestId=")
+            .expect("request id");
+        assert!(text.len() <= 256);
+        assert!(text.ends_with('…'));
+        assert!(!metadata.is_empty());
+        assert!(err.to_string().len() < 512);
+        let kept_trace = details
+            .as_ref()
+            .and_then(|details| details.get("trace"))
+            .and_then(|trace| trace.as_str())
+            .expect("trace detail");
+        assert_eq!(kept_trace.len(), trace.replace("\\n", "\n").len());
+
+        // A non-JSON body is cut the same way.
+        let payload = b"snapshot-payload-html-error".to_vec();
+        let err = client
+            .upload_snapshot(
+                "token",
+                "019bb9fe-f707-71e9-a40d-733575f4f246",
+                build_upload_headers(None, &payload),
+                payload,
+            )
+            .await
+            .expect_err("upload rejected");
+        assert!(matches!(err, DeviceSyncError::Api { status: 413, .. }));
+        assert!(err.to_string().len() < 512);
+
+        server.abort();
+    }
+
     #[tokio::test]
     async fn snapshot_upload_accepts_idempotent_200_response() {
         let (base_url, _captured, server) =
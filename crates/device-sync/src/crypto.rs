@@ -11,11 +11,20 @@ use chacha20poly1305::{
     aead::{Aead, KeyInit},
     XChaCha20Poly1305, XNonce,
 };
+use curve25519_dalek::{
+    edwards::EdwardsPoint,
+    montgomery::MontgomeryPoint,
+    scalar::{clamp_integer, Scalar},
+};
+use ed25519_dalek::{
+    hazmat::{raw_sign, ExpandedSecretKey},
+    Signature, Verifier, VerifyingKey,
+};
 use hkdf::Hkdf;
 use hmac::{Hmac, Mac};
 use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 use x25519_dalek::{PublicKey, StaticSecret};
 
 /// Key sizes
@@ -26,6 +35,8 @@ const NONCE_SIZE: usize = 24; // XChaCha20 uses 24-byte nonce
 const DEK_INFO: &[u8] = b"wealthfolio-dek";
 const SESSION_INFO: &[u8] = b"wealthfolio-session";
 const SAS_INFO: &[u8] = b"wealthfolio-sas";
+/// Domain tag for deriving the deterministic signing nonce prefix from a device secret key
+const DEVICE_SIGN_INFO: &[u8] = b"wealthfolio-device-sign";
 
 /// Generate a cryptographically secure root key (32 bytes)
 pub fn generate_root_key() -> String {
@@ -236,6 +247,64 @@ pub fn hmac_sha256(key_b64: &str, data: &str) -> Result<String, String> {
     Ok(result.iter().map(|b| format!("{:02x}", b)).collect())
 }
 
+/// Sign a message with a device's X25519 secret key (XEdDSA-style).
+///
+/// The clamped X25519 scalar is used as an Ed25519 signing scalar, negated when needed so
+/// that the matching Edwards point has a zero sign bit. Anyone holding the device's X25519
+/// public key can then check the signature with [`verify_device_signature`].
+/// Returns a base64 encoded 64-byte Ed25519 signature.
+pub fn sign_with_device_key(secret_key_b64: &str, message: &str) -> Result<String, String> {
+    let secret_bytes: [u8; 32] = BASE64
+        .decode(secret_key_b64)
+        .map_err(|e| format!("Invalid secret key: {}", e))?
+        .try_into()
+        .map_err(|_| "Secret key must be 32 bytes")?;
+
+    let mut scalar = Scalar::from_bytes_mod_order(clamp_integer(secret_bytes));
+    if EdwardsPoint::mul_base(&scalar).compress().as_bytes()[31] & 0x80 != 0 {
+        scalar = -scalar;
+    }
+
+    let mut hasher = Sha512::new();
+    hasher.update(DEVICE_SIGN_INFO);
+    hasher.update(secret_bytes);
+    let mut hash_prefix = [0u8; 32];
+    hash_prefix.copy_from_slice(&hasher.finalize()[..32]);
+
+    let expanded = ExpandedSecretKey {
+        scalar,
+        hash_prefix,
+    };
+    let verifying_key = VerifyingKey::from(&expanded);
+    let signature = raw_sign::<Sha512>(&expanded, message.as_bytes(), &verifying_key);
+    Ok(BASE64.encode(signature.to_bytes()))
+}
+
+/// Verify a [`sign_with_device_key`] signature against a device's X25519 public key.
+pub fn verify_device_signature(public_key_b64: &str, message: &str, signature_b64: &str) -> bool {
+    let Some(public_bytes) = BASE64
+        .decode(public_key_b64)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+    else {
+        return false;
+    };
+    let Some(signature) = BASE64
+        .decode(signature_b64)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+    else {
+        return false;
+    };
+    let Some(verifying_key) = MontgomeryPoint(public_bytes)
+        .to_edwards(0)
+        .and_then(|point| VerifyingKey::from_bytes(point.compress().as_bytes()).ok())
+    else {
+        return false;
+    };
+    verifying_key.verify(message.as_bytes(), &signature).is_ok()
+}
+
 /// Compute Short Authentication String (SAS) from shared secret
 /// Returns a 6-digit numeric string for human verification
 pub fn compute_sas(shared_secret_b64: &str) -> Result<String, String> {
@@ -259,6 +328,27 @@ pub fn sha256_checksum(data: &[u8]) -> String {
     format!("sha256:{:x}", digest)
 }
 
+/// Checksum of a payload in the format the sync server computes and checks
+/// (`SYNC_SEGMENT_CHECKSUM_MISMATCH`, `SYNC_SNAPSHOT_CHECKSUM_MISMATCH`): SHA-256 of the exact
+/// bytes sent, i.e. after encryption, as `sha256:` and 64 lowercase hex digits.
+pub fn compute_checksum(data: &[u8]) -> String {
+    sha256_checksum(data)
+}
+
+/// Whether `checksum` is a well-formed `sha256:<64 hex digits>` value.
+pub fn is_valid_checksum(checksum: &str) -> bool {
+    let Some(hex) = checksum.strip_prefix("sha256:") else {
+        return false;
+    };
+    hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Whether `data` matches the `expected` checksum from [`compute_checksum`]. The hex digits
+/// may be in either case; a malformed `expected` never matches.
+pub fn verify_checksum(data: &[u8], expected: &str) -> bool {
+    is_valid_checksum(expected) && expected.eq_ignore_ascii_case(&compute_checksum(data))
+}
+
 /// Generate a UUID v4 device ID
 pub fn generate_device_id() -> String {
     uuid::Uuid::new_v4().to_string()
@@ -352,6 +442,67 @@ mod tests {
         assert_ne!(mac1, mac4);
     }
 
+    #[test]
+    fn test_device_signature_verifies_with_public_key() {
+        let keypair = generate_ephemeral_keypair();
+        let other = generate_ephemeral_keypair();
+        let message = "device-1:new-public-key";
+
+        let signature = sign_with_device_key(&keypair.secret_key, message).unwrap();
+        assert!(verify_device_signature(
+            &keypair.public_key,
+            message,
+            &signature
+        ));
+        assert!(!verify_device_signature(
+            &keypair.public_key,
+            "device-1:other-key",
+            &signature
+        ));
+        assert!(!verify_device_signature(
+            &other.public_key,
+            message,
+            &signature
+        ));
+        assert!(!verify_device_signature(
+            &keypair.public_key,
+            message,
+            "not-base64"
+        ));
+    }
+
+    #[test]
+    fn test_checksum_vectors() {
+        // SHA-256 test vectors (FIPS 180-2).
+        assert_eq!(
+            compute_checksum(b""),
+            "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            compute_checksum(b"abc"),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        assert!(verify_checksum(
+            b"abc",
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        ));
+        assert!(verify_checksum(
+            b"abc",
+            "sha256:BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD"
+        ));
+        // One flipped byte, a missing prefix, and a truncated digest.
+        assert!(!verify_checksum(
+            b"abd",
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        ));
+        assert!(!verify_checksum(
+            b"abc",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        ));
+        assert!(!verify_checksum(b"abc", "sha256:ba7816bf"));
+    }
+
     #[test]
     fn test_hash_pairing_code() {
         let code = "ABC123";
@@ -0,0 +1,87 @@
+//! Forecast for when the local sync cursor falls out of the server's event retention.
+//!
+//! Once the server prunes events older than the cursor, the next pull reports a stale cursor
+//! and the device has to bootstrap from a snapshot. Pulling before `expires_at` keeps sync
+//! incremental.
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorExpiryForecast {
+    /// Seconds since the cursor last advanced. `None` when the device has never pulled.
+    pub cursor_age_seconds: Option<i64>,
+    pub server_retention_days: Option<i64>,
+    /// `false` when no retention window is known; the expiry fields are then `None`.
+    pub retention_known: bool,
+    pub expires_at: Option<String>,
+    /// Whole days left before the cursor expires, floored and never negative.
+    pub days_remaining: Option<i64>,
+}
+
+/// Computes the expiry forecast for a cursor last advanced at `last_pull_at`.
+pub fn forecast_cursor_expiry(
+    last_pull_at: Option<DateTime<Utc>>,
+    server_retention_days: Option<i64>,
+    now: DateTime<Utc>,
+) -> CursorExpiryForecast {
+    let server_retention_days = server_retention_days.filter(|days| *days > 0);
+    let cursor_age_seconds = last_pull_at.map(|pulled| (now - pulled).num_seconds().max(0));
+    let expires_at = last_pull_at
+        .zip(server_retention_days)
+        .map(|(pulled, days)| pulled + Duration::days(days));
+
+    CursorExpiryForecast {
+        cursor_age_seconds,
+        server_retention_days,
+        retention_known: server_retention_days.is_some(),
+        expires_at: expires_at.map(|at| at.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        days_remaining: expires_at.map(|at| ((at - now).num_seconds() / SECONDS_PER_DAY).max(0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn forecasts_expiry_from_cursor_age_and_retention() {
+        let last_pull_at = now() - Duration::days(27) - Duration::hours(6);
+
+        let forecast = forecast_cursor_expiry(Some(last_pull_at), Some(30), now());
+
+        assert_eq!(
+            forecast.cursor_age_seconds,
+            Some(27 * SECONDS_PER_DAY + 6 * 60 * 60)
+        );
+        assert!(forecast.retention_known);
+        assert_eq!(forecast.expires_at.as_deref(), Some("2026-03-13T06:00:00Z"));
+        assert_eq!(forecast.days_remaining, Some(2));
+    }
+
+    #[test]
+    fn expired_cursor_reports_zero_days_remaining() {
+        let forecast = forecast_cursor_expiry(Some(now() - Duration::days(45)), Some(30), now());
+
+        assert_eq!(forecast.days_remaining, Some(0));
+    }
+
+    #[test]
+    fn unknown_retention_leaves_expiry_empty() {
+        let forecast = forecast_cursor_expiry(Some(now() - Duration::days(3)), None, now());
+
+        assert_eq!(forecast.cursor_age_seconds, Some(3 * SECONDS_PER_DAY));
+        assert!(!forecast.retention_known);
+        assert_eq!(forecast.server_retention_days, None);
+        assert_eq!(forecast.expires_at, None);
+        assert_eq!(forecast.days_remaining, None);
+    }
+}
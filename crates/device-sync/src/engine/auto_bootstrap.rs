@@ -0,0 +1,69 @@
+//! Automatic snapshot bootstrap after a background cycle finds local state unrecoverable.
+//!
+//! An integrity error (a segment that fails its checksum, a missing object) or a cursor the
+//! server no longer serves will fail every incremental cycle, so the background engine
+//! re-downloads the latest snapshot instead of retrying. A snapshot that is itself broken would
+//! make that loop forever; [`AutoBootstrapGuard`] caps it at
+//! [`DEVICE_SYNC_AUTO_BOOTSTRAP_LIMIT`] bootstraps per [`DEVICE_SYNC_AUTO_BOOTSTRAP_WINDOW`],
+//! after which cycles keep reporting the error until the window frees up.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Automatic bootstraps allowed within one window.
+pub const DEVICE_SYNC_AUTO_BOOTSTRAP_LIMIT: usize = 3;
+/// Rolling window the limit applies to.
+pub const DEVICE_SYNC_AUTO_BOOTSTRAP_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Counts automatic bootstraps over a rolling window. Bootstraps the user starts are not counted.
+#[derive(Debug, Default)]
+pub struct AutoBootstrapGuard {
+    started: Mutex<VecDeque<Instant>>,
+}
+
+impl AutoBootstrapGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a bootstrap and returns `true`, or returns `false` when the limit is reached.
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    pub fn try_acquire_at(&self, now: Instant) -> bool {
+        let Ok(mut started) = self.started.lock() else {
+            return false;
+        };
+        while started.front().is_some_and(|at| {
+            now.saturating_duration_since(*at) >= DEVICE_SYNC_AUTO_BOOTSTRAP_WINDOW
+        }) {
+            started.pop_front();
+        }
+        if started.len() >= DEVICE_SYNC_AUTO_BOOTSTRAP_LIMIT {
+            return false;
+        }
+        started.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_three_bootstraps_per_hour() {
+        let guard = AutoBootstrapGuard::new();
+        let start = Instant::now();
+
+        for minutes in [0, 10, 20] {
+            assert!(guard.try_acquire_at(start + Duration::from_secs(minutes * 60)));
+        }
+        assert!(!guard.try_acquire_at(start + Duration::from_secs(59 * 60)));
+        // The first bootstrap leaves the window an hour after it ran.
+        assert!(guard.try_acquire_at(start + Duration::from_secs(60 * 60)));
+        assert!(!guard.try_acquire_at(start + Duration::from_secs(65 * 60)));
+    }
+}
@@ -3,23 +3,32 @@ use log::{debug, info, warn};
 use std::sync::Arc;
 use std::time::Duration;
 use wealthfolio_core::sync::{SyncEntity, SyncOperation};
+use wealthfolio_core::utils::backoff::exponential_backoff;
 
 use crate::{
     sync_entity_from_remote, ApiRetryClass, SyncPushEventRequest, SyncPushRequest, SyncState,
+    MAX_RETRY_AFTER,
 };
 
+pub mod auto_bootstrap;
 pub mod ports;
+mod rejections;
+pub mod replay_failure;
 mod runtime;
+pub mod state_store;
 
+pub use auto_bootstrap::AutoBootstrapGuard;
 pub use ports::{
     CredentialStore, OutboxStore, ReadyReconcileStore, ReplayEvent, ReplayStore,
     SyncBootstrapResult, SyncCycleResult, SyncIdentity, SyncReadyReconcileResult, SyncTransport,
     TransportError,
 };
+pub use replay_failure::{ReplayApplyError, ReplayFailurePolicy, ReplayFailureRecovery};
 pub use runtime::{
     DeviceSyncRuntimeState, DeviceSyncWakeHandle, OverwriteInfo, OverwriteTableInfo,
     PairingFlowPhase, PairingFlowResponse, PairingFlowState,
 };
+pub use state_store::{KeyValueClient, KeyValueSyncStateStore, SyncStateStore};
 
 /// Default periodic sync cadence for the background engine.
 pub const DEVICE_SYNC_PERIODIC_INTERVAL_SECS: u64 = 5 * 60;
@@ -39,13 +48,25 @@ pub const DEVICE_SYNC_SENT_OUTBOX_RETENTION_DAYS: i64 = 7;
 pub const DEVICE_SYNC_DEAD_OUTBOX_RETENTION_DAYS: i64 = 30;
 const MAX_REMOTE_ENTITY_ID_LEN: usize = 256;
 
-/// Exponential backoff in seconds with cap.
+/// Exponential backoff in seconds with cap: 5s with no failures yet, doubling per failure.
 pub fn backoff_seconds(consecutive_failures: i32) -> i64 {
-    const MAX_EXPONENT: i32 = 8;
-    const BASE_DELAY_SECONDS: i64 = 5;
+    const BASE_DELAY: Duration = Duration::from_secs(5);
+    const MAX_DELAY: Duration = Duration::from_secs(5 * 256);
 
-    let capped = i64::from(consecutive_failures.clamp(0, MAX_EXPONENT));
-    2_i64.pow(capped as u32) * BASE_DELAY_SECONDS
+    let attempt = consecutive_failures.max(0) as u32 + 1;
+    exponential_backoff(BASE_DELAY, attempt, MAX_DELAY).as_secs() as i64
+}
+
+/// Delay before retrying a push the server throttled: its `Retry-After` when it sent one, up to
+/// [`MAX_RETRY_AFTER`], otherwise the exponential `fallback`.
+fn push_retry_delay_seconds(retry_after: Option<std::time::Duration>, fallback: i64) -> i64 {
+    match retry_after.map(|delay| delay.min(MAX_RETRY_AFTER)) {
+        Some(delay) => i64::try_from(delay.as_secs())
+            .unwrap_or(i64::MAX)
+            .saturating_add(i64::from(delay.subsec_nanos() > 0))
+            .max(1),
+        None => fallback,
+    }
 }
 
 fn remote_entity_id_is_valid(_entity: &SyncEntity, entity_id: &str) -> bool {
@@ -110,6 +131,7 @@ fn retry_class_code(class: ApiRetryClass) -> &'static str {
         ApiRetryClass::Retryable => "retryable",
         ApiRetryClass::Permanent => "permanent",
         ApiRetryClass::ReauthRequired => "reauth_required",
+        ApiRetryClass::Recover => "recover",
     }
 }
 
@@ -199,6 +221,47 @@ impl<'a, R: ReplayStore + ?Sized> CycleContext<'a, R> {
             dead_letter_count: 0,
         })
     }
+
+    /// Recovers from `SYNC_CURSOR_TOO_OLD`. The events between the local cursor and what the
+    /// server still retains are gone, but local state is intact, so instead of a bootstrap the
+    /// latest snapshot is restored with unsynced local changes kept and the cursor resumes from
+    /// it. `None` when the host could not restore; the cycle then asks for a bootstrap.
+    async fn recover_from_stale_cursor(&self) -> Result<Option<SyncCycleResult>, String> {
+        warn!(
+            "[DeviceSync] Cursor {} is too old. Resuming from the latest snapshot.",
+            self.local_cursor
+        );
+        let restored = match self.replay_store.restore_snapshot_for_stale_cursor().await {
+            Ok(value) => value,
+            Err(err) => {
+                warn!("[DeviceSync] Stale cursor recovery failed: {}", err);
+                return Ok(None);
+            }
+        };
+        if restored.status != "applied" {
+            info!(
+                "[DeviceSync] Stale cursor recovery did not restore a snapshot (status={}): {}",
+                restored.status, restored.message
+            );
+            return Ok(None);
+        }
+        let cursor = self
+            .replay_store
+            .get_cursor()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Some(SyncCycleResult {
+            status: "cursor_reset".to_string(),
+            lock_version: self.lock_version,
+            pushed_count: self.pushed_count,
+            pulled_count: self.pulled_count,
+            cursor,
+            needs_bootstrap: false,
+            bootstrap_snapshot_id: restored.snapshot_id,
+            bootstrap_snapshot_seq: Some(cursor),
+            dead_letter_count: 0,
+        }))
+    }
 }
 
 pub async fn run_sync_cycle<P>(ports: &P, post_bootstrap: bool) -> Result<SyncCycleResult, String>
@@ -414,10 +477,35 @@ where
         }
     }
 
-    ctx.lock_version = ports
+    ctx.lock_version = match ports
         .acquire_cycle_lock()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+    {
+        Some(lock_version) => lock_version,
+        None => {
+            debug!("[DeviceSync] Another instance holds the cycle lock. Skipping this cycle.");
+            ports
+                .mark_cycle_outcome(
+                    "lock_held".to_string(),
+                    ctx.started_at.elapsed().as_millis() as i64,
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            return Ok(SyncCycleResult {
+                status: "lock_held".to_string(),
+                lock_version: 0,
+                pushed_count: 0,
+                pulled_count: 0,
+                cursor: ctx.local_cursor,
+                needs_bootstrap: false,
+                bootstrap_snapshot_id: None,
+                bootstrap_snapshot_seq: None,
+                dead_letter_count: 0,
+            });
+        }
+    };
     ctx.local_cursor = ports.get_cursor().await.map_err(|e| e.to_string())?;
     debug!("[DeviceSync] Local cursor: {}", ctx.local_cursor);
     let lock_version = ctx.lock_version;
@@ -636,22 +724,77 @@ where
                         ports
                             .schedule_outbox_retry(
                                 push_event_ids,
-                                backoff,
+                                push_retry_delay_seconds(err.retry_after, backoff),
                                 Some(err_str),
                                 Some(retry_class_code(retry_class).to_string()),
                             )
                             .await
                             .map_err(|e| e.to_string())?;
                     }
-                    ApiRetryClass::Permanent => {
+                    ApiRetryClass::Recover => {
+                        // The events are fine; they go out again once local state is rebuilt.
                         ports
-                            .mark_outbox_dead(
+                            .schedule_outbox_retry(
                                 push_event_ids,
+                                backoff,
                                 Some(err_str),
                                 Some(retry_class_code(retry_class).to_string()),
                             )
                             .await
                             .map_err(|e| e.to_string())?;
+                        if err.error_code.as_deref() == Some(crate::error::SYNC_CURSOR_TOO_OLD) {
+                            if let Some(result) = ctx.recover_from_stale_cursor().await? {
+                                return Ok(result);
+                            }
+                        }
+                        warn!(
+                            "[DeviceSync] Push error code {} — bootstrap required",
+                            err.error_code.as_deref().unwrap_or("unknown")
+                        );
+                        return ctx
+                            .fail("stale_cursor", format!("Push failed: {}", err), None)
+                            .await;
+                    }
+                    ApiRetryClass::Permanent => {
+                        let rejections = rejections::attribute_push_rejection(
+                            &push_event_ids,
+                            err.details.as_ref(),
+                        );
+                        let error_code = err
+                            .error_code
+                            .clone()
+                            .unwrap_or_else(|| retry_class_code(retry_class).to_string());
+                        let innocent_ids: Vec<String> = push_event_ids
+                            .into_iter()
+                            .filter(|id| !rejections.iter().any(|r| &r.event_id == id))
+                            .collect();
+                        if !innocent_ids.is_empty() {
+                            ports
+                                .schedule_outbox_retry(
+                                    innocent_ids,
+                                    backoff,
+                                    Some(err_str.clone()),
+                                    Some(retry_class_code(ApiRetryClass::Retryable).to_string()),
+                                )
+                                .await
+                                .map_err(|e| e.to_string())?;
+                        }
+                        warn!(
+                            "[DeviceSync] Cloud rejected {} outbox event(s) as invalid ({})",
+                            rejections.len(),
+                            error_code
+                        );
+                        for rejection in rejections {
+                            ports
+                                .mark_outbox_rejected(
+                                    rejection.event_id,
+                                    Some(err_str.clone()),
+                                    Some(error_code.clone()),
+                                    rejection.field_errors,
+                                )
+                                .await
+                                .map_err(|e| e.to_string())?;
+                        }
                     }
                 }
                 return ctx
@@ -709,8 +852,14 @@ where
                             )
                             .await;
                     }
-                    // Check for SYNC_CURSOR_TOO_OLD or integrity errors — trigger bootstrap
+                    // A stale cursor resumes from the latest snapshot; integrity errors, or a
+                    // stale cursor that could not be recovered, need a bootstrap.
                     if let Some(code) = err.error_code.as_deref() {
+                        if code == crate::error::SYNC_CURSOR_TOO_OLD {
+                            if let Some(result) = ctx.recover_from_stale_cursor().await? {
+                                return Ok(result);
+                            }
+                        }
                         if code == crate::error::SYNC_CURSOR_TOO_OLD
                             || crate::error::is_integrity_code(code)
                         {
@@ -857,6 +1006,46 @@ where
                 .await
             {
                 Ok(applied) => applied,
+                Err(err) if ports.replay_failure_policy() == ReplayFailurePolicy::Halt => {
+                    // The batch was rolled back as a whole, so keeping the cursor before it
+                    // keeps local state and cursor consistent.
+                    let recovery = err.recovery();
+                    let err = err.to_string();
+                    let failed_event = match replay_failure::failing_event(&decoded_events, &err) {
+                        Some(event) => format!(
+                            "event_id={} entity={:?} entity_id={} op={:?} seq={}",
+                            event.event_id, event.entity, event.entity_id, event.op, event.seq
+                        ),
+                        None => "event unknown".to_string(),
+                    };
+                    log::error!(
+                        "[DeviceSync] Replay batch rolled back at cursor {} ({} events, {}); recovery={}: {}",
+                        local_cursor,
+                        decoded_events.len(),
+                        failed_event,
+                        recovery.as_str(),
+                        err
+                    );
+                    let message = format!(
+                        "Replay apply failed ({}); cursor kept at {}: {}",
+                        failed_event, local_cursor, err
+                    );
+                    return match recovery {
+                        ReplayFailureRecovery::Retry => {
+                            let failures = ports
+                                .get_engine_status()
+                                .await
+                                .map(|status| status.consecutive_failures)
+                                .unwrap_or(0);
+                            ctx.fail("replay_error", message, Some(backoff_seconds(failures)))
+                                .await
+                        }
+                        // Reported like a stale cursor so the usual bootstrap recovery runs.
+                        ReplayFailureRecovery::Bootstrap => {
+                            ctx.fail("stale_cursor", message, None).await
+                        }
+                    };
+                }
                 Err(err) => {
                     warn!(
                         "[DeviceSync] Batch replay apply failed ({}). Falling back to per-event apply with dead-letter skip.",
@@ -1215,6 +1404,51 @@ async fn wait_for_wake_debounce_with_limits(
     }
 }
 
+/// Runs one background cycle. When it reports that local state needs a bootstrap (an
+/// integrity error or a cursor the server no longer serves), re-downloads the snapshot right
+/// away instead of pulling incrementally again, and reruns the cycle on top of it. Limited by
+/// the runtime's [`AutoBootstrapGuard`] so a broken snapshot cannot loop.
+async fn run_background_cycle<P>(
+    runtime: &DeviceSyncRuntimeState,
+    ports: &P,
+) -> Result<SyncCycleResult, String>
+where
+    P: OutboxStore + ReplayStore + SyncTransport + CredentialStore + Send + Sync,
+{
+    let result = runtime.run_cycle_serialized(ports, false).await?;
+    if !result.needs_bootstrap {
+        return Ok(result);
+    }
+    if !runtime.auto_bootstrap_guard.try_acquire() {
+        warn!(
+            "[DeviceSync] Cycle status={} needs a bootstrap, but {} automatic bootstraps already ran this hour",
+            result.status,
+            auto_bootstrap::DEVICE_SYNC_AUTO_BOOTSTRAP_LIMIT
+        );
+        return Ok(result);
+    }
+
+    info!(
+        "[DeviceSync] Cycle status={} needs a bootstrap. Re-syncing from the latest snapshot.",
+        result.status
+    );
+    let bootstrap = match ports.auto_bootstrap(&result.status).await {
+        Ok(value) => value,
+        Err(err) => {
+            warn!("[DeviceSync] Automatic bootstrap failed: {}", err);
+            return Ok(result);
+        }
+    };
+    if bootstrap.status != "applied" {
+        info!(
+            "[DeviceSync] Automatic bootstrap did not apply a snapshot (status={}): {}",
+            bootstrap.status, bootstrap.message
+        );
+        return Ok(result);
+    }
+    runtime.run_cycle_serialized(ports, true).await
+}
+
 pub async fn run_background_loop<P>(runtime: Arc<DeviceSyncRuntimeState>, ports: Arc<P>)
 where
     P: OutboxStore + ReplayStore + SyncTransport + CredentialStore + Send + Sync,
@@ -1233,7 +1467,7 @@ where
             break;
         }
 
-        let cycle_result = runtime.run_cycle_serialized(ports.as_ref(), false).await;
+        let cycle_result = run_background_cycle(&runtime, ports.as_ref()).await;
         if let Err(err) = &cycle_result {
             warn!("[DeviceSync] Background cycle failed: {}", err);
             consecutive_not_ready = 0;
@@ -1292,6 +1526,8 @@ mod tests {
     use tokio::sync::Mutex;
     use wealthfolio_core::sync::SyncEngineStatus;
 
+    type RejectedEvent = (String, Vec<wealthfolio_core::sync::SyncFieldError>);
+
     #[derive(Clone)]
     struct TestPorts {
         cursor: i64,
@@ -1300,10 +1536,15 @@ mod tests {
         fail_mark_cycle_outcome: bool,
         pending_outbox: Arc<Mutex<Vec<wealthfolio_core::sync::SyncOutboxEvent>>>,
         dead_outbox_batches: Arc<Mutex<Vec<Vec<String>>>>,
+        /// Event id and field errors of each event the cloud rejected.
+        rejected_events: Arc<Mutex<Vec<RejectedEvent>>>,
         pull_responses: Arc<Mutex<VecDeque<crate::SyncPullResponse>>>,
         set_cursor_calls: Arc<Mutex<Vec<i64>>>,
         applied_events: Arc<Mutex<Vec<ReplayEvent>>>,
         push_error: Option<TransportError>,
+        pull_error: Option<TransportError>,
+        auto_bootstrap_reasons: Arc<Mutex<Vec<String>>>,
+        stale_cursor_snapshot: Option<(String, i64)>,
         reconcile_response: crate::ReconcileReadyStateResponse,
         persisted_trust_states: Arc<Mutex<Vec<String>>>,
         cycle_outcomes: Arc<Mutex<Vec<String>>>,
@@ -1312,6 +1553,9 @@ mod tests {
         reconcile_delay_ms: u64,
         active_reconcile_count: Arc<AtomicUsize>,
         max_active_reconcile_count: Arc<AtomicUsize>,
+        batch_apply_error: Option<ReplayApplyError>,
+        replay_failure_policy: ReplayFailurePolicy,
+        cycle_lock_held_elsewhere: bool,
     }
 
     impl TestPorts {
@@ -1323,10 +1567,14 @@ mod tests {
                 fail_mark_cycle_outcome: false,
                 pending_outbox: Arc::new(Mutex::new(Vec::new())),
                 dead_outbox_batches: Arc::new(Mutex::new(Vec::new())),
+                rejected_events: Arc::new(Mutex::new(Vec::new())),
                 pull_responses: Arc::new(Mutex::new(VecDeque::new())),
                 set_cursor_calls: Arc::new(Mutex::new(Vec::new())),
                 applied_events: Arc::new(Mutex::new(Vec::new())),
                 push_error: None,
+                pull_error: None,
+                auto_bootstrap_reasons: Arc::new(Mutex::new(Vec::new())),
+                stale_cursor_snapshot: None,
                 reconcile_response: crate::ReconcileReadyStateResponse {
                     action: "NOOP".to_string(),
                     cursor: Some(0),
@@ -1339,6 +1587,9 @@ mod tests {
                 reconcile_delay_ms: 0,
                 active_reconcile_count: Arc::new(AtomicUsize::new(0)),
                 max_active_reconcile_count: Arc::new(AtomicUsize::new(0)),
+                batch_apply_error: None,
+                replay_failure_policy: ReplayFailurePolicy::Halt,
+                cycle_lock_held_elsewhere: false,
             }
         }
 
@@ -1376,6 +1627,20 @@ mod tests {
             Ok(())
         }
 
+        async fn mark_outbox_rejected(
+            &self,
+            event_id: String,
+            _error_message: Option<String>,
+            _error_code: Option<String>,
+            field_errors: Vec<wealthfolio_core::sync::SyncFieldError>,
+        ) -> Result<(), String> {
+            self.rejected_events
+                .lock()
+                .await
+                .push((event_id.clone(), field_errors));
+            self.mark_outbox_dead(vec![event_id], None, None).await
+        }
+
         async fn mark_outbox_sent(&self, _event_ids: Vec<String>) -> Result<(), String> {
             Ok(())
         }
@@ -1401,8 +1666,8 @@ mod tests {
 
     #[async_trait]
     impl ReplayStore for TestPorts {
-        async fn acquire_cycle_lock(&self) -> Result<i64, String> {
-            Ok(1)
+        async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String> {
+            Ok((!self.cycle_lock_held_elsewhere).then_some(1))
         }
 
         async fn verify_cycle_lock(&self, _lock_version: i64) -> Result<bool, String> {
@@ -1421,7 +1686,11 @@ mod tests {
         async fn apply_remote_events_lww_batch(
             &self,
             events: Vec<ReplayEvent>,
-        ) -> Result<usize, String> {
+        ) -> Result<usize, ReplayApplyError> {
+            // A failed batch is rolled back, so nothing of it is recorded.
+            if let Some(err) = &self.batch_apply_error {
+                return Err(err.clone());
+            }
             let applied = events.len();
             self.applied_events.lock().await.extend(events);
             Ok(applied)
@@ -1431,6 +1700,10 @@ mod tests {
             Ok(false)
         }
 
+        fn replay_failure_policy(&self) -> ReplayFailurePolicy {
+            self.replay_failure_policy
+        }
+
         async fn mark_pull_completed(&self) -> Result<(), String> {
             Ok(())
         }
@@ -1478,6 +1751,35 @@ mod tests {
                 last_cycle_duration_ms: None,
             })
         }
+
+        async fn auto_bootstrap(&self, reason: &str) -> Result<SyncBootstrapResult, String> {
+            self.auto_bootstrap_reasons
+                .lock()
+                .await
+                .push(reason.to_string());
+            Ok(SyncBootstrapResult {
+                status: "applied".to_string(),
+                message: "Snapshot restored".to_string(),
+                snapshot_id: Some("snap-1".to_string()),
+            })
+        }
+
+        async fn restore_snapshot_for_stale_cursor(&self) -> Result<SyncBootstrapResult, String> {
+            let Some((snapshot_id, seq)) = self.stale_cursor_snapshot.clone() else {
+                return Ok(SyncBootstrapResult {
+                    status: "unsupported".to_string(),
+                    message: "not configured".to_string(),
+                    snapshot_id: None,
+                });
+            };
+            // The host moves the cursor to the restored snapshot.
+            self.set_cursor(seq).await?;
+            Ok(SyncBootstrapResult {
+                status: "applied".to_string(),
+                message: "Snapshot restored".to_string(),
+                snapshot_id: Some(snapshot_id),
+            })
+        }
     }
 
     #[async_trait]
@@ -1513,6 +1815,9 @@ mod tests {
             _from_cursor: Option<i64>,
             _limit: Option<i64>,
         ) -> Result<crate::SyncPullResponse, TransportError> {
+            if let Some(err) = &self.pull_error {
+                return Err(err.clone());
+            }
             self.pull_responses
                 .lock()
                 .await
@@ -1522,6 +1827,7 @@ mod tests {
                     retry_class: ApiRetryClass::Permanent,
                     error_code: None,
                     details: None,
+                    retry_after: None,
                 })
         }
 
@@ -1822,6 +2128,46 @@ mod tests {
         assert!(ports.cycle_outcomes.lock().await.is_empty());
     }
 
+    #[test]
+    fn throttled_push_waits_as_long_as_the_server_asked() {
+        assert_eq!(
+            push_retry_delay_seconds(Some(std::time::Duration::from_secs(120)), 5),
+            120
+        );
+        assert_eq!(
+            push_retry_delay_seconds(Some(std::time::Duration::from_millis(1_500)), 5),
+            2
+        );
+        assert_eq!(
+            push_retry_delay_seconds(Some(std::time::Duration::ZERO), 5),
+            1
+        );
+        assert_eq!(push_retry_delay_seconds(None, backoff_seconds(2)), 20);
+    }
+
+    #[test]
+    fn backoff_doubles_from_five_seconds_up_to_the_cap() {
+        let delays: Vec<i64> = (-1..=3).map(backoff_seconds).collect();
+        assert_eq!(delays, vec![5, 5, 10, 20, 40]);
+        assert_eq!(backoff_seconds(8), 1_280);
+        assert_eq!(backoff_seconds(i32::MAX), 1_280);
+    }
+
+    #[test]
+    fn throttled_push_waits_at_most_the_retry_after_cap() {
+        assert_eq!(
+            push_retry_delay_seconds(Some(std::time::Duration::from_secs(u64::MAX)), 5),
+            MAX_RETRY_AFTER.as_secs() as i64
+        );
+        assert_eq!(
+            push_retry_delay_seconds(
+                Some(MAX_RETRY_AFTER + std::time::Duration::from_millis(1)),
+                5
+            ),
+            MAX_RETRY_AFTER.as_secs() as i64
+        );
+    }
+
     #[test]
     fn revoked_identity_requires_device_id_without_root_key() {
         assert!(!sync_identity_is_revoked(None));
@@ -2090,6 +2436,23 @@ mod tests {
         assert!(ports.engine_errors.lock().await.is_empty());
     }
 
+    #[tokio::test]
+    async fn run_sync_cycle_skips_when_another_instance_holds_the_lock() {
+        let mut ports = TestPorts::new(Some(ready_identity()), Ok(SyncState::Ready));
+        ports.reconcile_response.action = "PULL_TAIL".to_string();
+        ports.reconcile_response.cursor = Some(8);
+        ports.cycle_lock_held_elsewhere = true;
+
+        let result = run_sync_cycle(&ports, false)
+            .await
+            .expect("cycle should skip while the lock is held");
+
+        assert_eq!(result.status, "lock_held");
+        assert!(ports.set_cursor_calls.lock().await.is_empty());
+        assert!(ports.engine_errors.lock().await.is_empty());
+        assert_eq!(ports.cycle_outcomes.lock().await.as_slice(), ["lock_held"]);
+    }
+
     #[tokio::test]
     async fn run_sync_cycle_replays_known_remote_entity_normally() {
         let mut ports = TestPorts::new(Some(ready_identity()), Ok(SyncState::Ready));
@@ -2128,6 +2491,121 @@ mod tests {
         assert_eq!(applied_events[0].event_id, "evt-account-1");
     }
 
+    fn two_account_events_pull_response() -> crate::SyncPullResponse {
+        crate::SyncPullResponse {
+            from: 5,
+            to: 9,
+            next_cursor: 9,
+            has_more: false,
+            events: vec![
+                pull_event(
+                    "evt-account-1",
+                    "account",
+                    "account.update.v1",
+                    "account-1",
+                    8,
+                    r#"{"id":"account-1"}"#,
+                ),
+                pull_event(
+                    "evt-account-2",
+                    "account",
+                    "account.update.v1",
+                    "account-2",
+                    9,
+                    r#"{"id":"account-2"}"#,
+                ),
+            ],
+            gc_watermark: None,
+            latest_snapshot_seq: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn run_sync_cycle_mid_batch_apply_failure_keeps_cursor_before_the_batch() {
+        let mut ports = TestPorts::new(Some(ready_identity()), Ok(SyncState::Ready));
+        ports.cursor = 5;
+        ports.reconcile_response.action = "PULL_TAIL".to_string();
+        ports.reconcile_response.cursor = Some(9);
+        ports.batch_apply_error = Some(ReplayApplyError::Conflict(
+            "Replay apply failed for entity=Account table=accounts pk=id entity_id=account-2 \
+             op=Update event_id=evt-account-2 seq=9: FOREIGN KEY constraint failed"
+                .to_string(),
+        ));
+        ports
+            .pull_responses
+            .lock()
+            .await
+            .push_back(two_account_events_pull_response());
+
+        let result = run_sync_cycle(&ports, false)
+            .await
+            .expect("cycle should report the replay failure");
+
+        assert_eq!(result.status, "stale_cursor");
+        assert!(result.needs_bootstrap);
+        assert_eq!(result.cursor, 5);
+        assert_eq!(result.pulled_count, 0);
+        assert!(ports.set_cursor_calls.lock().await.is_empty());
+        assert!(ports.applied_events.lock().await.is_empty());
+        let errors = ports.engine_errors.lock().await;
+        assert!(
+            errors[0].contains("event_id=evt-account-2"),
+            "{}",
+            errors[0]
+        );
+        assert!(errors[0].contains("cursor kept at 5"), "{}", errors[0]);
+    }
+
+    #[tokio::test]
+    async fn run_sync_cycle_unclassified_apply_failure_is_retried_without_bootstrap() {
+        let mut ports = TestPorts::new(Some(ready_identity()), Ok(SyncState::Ready));
+        ports.cursor = 5;
+        ports.reconcile_response.action = "PULL_TAIL".to_string();
+        ports.reconcile_response.cursor = Some(9);
+        ports.batch_apply_error = Some(ReplayApplyError::Failed(
+            "Writer actor stopped unexpectedly".to_string(),
+        ));
+        ports
+            .pull_responses
+            .lock()
+            .await
+            .push_back(two_account_events_pull_response());
+
+        let result = run_sync_cycle(&ports, false)
+            .await
+            .expect("cycle should report the replay failure");
+
+        assert_eq!(result.status, "replay_error");
+        assert!(!result.needs_bootstrap);
+        assert_eq!(result.cursor, 5);
+        assert!(ports.set_cursor_calls.lock().await.is_empty());
+        assert!(ports.auto_bootstrap_reasons.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn run_sync_cycle_skip_policy_moves_past_a_failed_batch() {
+        let mut ports = TestPorts::new(Some(ready_identity()), Ok(SyncState::Ready));
+        ports.cursor = 5;
+        ports.reconcile_response.action = "PULL_TAIL".to_string();
+        ports.reconcile_response.cursor = Some(9);
+        ports.batch_apply_error = Some(ReplayApplyError::Failed(
+            "Database error: database is locked".to_string(),
+        ));
+        ports.replay_failure_policy = ReplayFailurePolicy::SkipFailed;
+        ports
+            .pull_responses
+            .lock()
+            .await
+            .push_back(two_account_events_pull_response());
+
+        let result = run_sync_cycle(&ports, false)
+            .await
+            .expect("cycle should skip the failed batch");
+
+        assert_eq!(result.status, "ok");
+        assert_eq!(ports.set_cursor_calls.lock().await.as_slice(), [9]);
+    }
+
     #[tokio::test]
     async fn run_sync_cycle_key_version_mismatch_drops_only_stale_outbox_events() {
         let identity = SyncIdentity {
@@ -2141,6 +2619,7 @@ mod tests {
             retry_class: ApiRetryClass::Permanent,
             error_code: Some("SYNC_KEY_VERSION_MISMATCH".to_string()),
             details: None,
+            retry_after: None,
         });
         {
             let mut pending = ports.pending_outbox.lock().await;
@@ -2188,6 +2667,7 @@ mod tests {
             retry_class: ApiRetryClass::Permanent,
             error_code: Some("SYNC_KEY_VERSION_MISMATCH".to_string()),
             details: None,
+            retry_after: None,
         });
         {
             let mut pending = ports.pending_outbox.lock().await;
@@ -2213,6 +2693,168 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn run_sync_cycle_validation_rejection_records_the_offending_event() {
+        let identity = SyncIdentity {
+            device_id: Some("019cb093-06a8-7534-8677-546317b17957".to_string()),
+            root_key: Some("root-key".to_string()),
+            key_version: Some(1),
+        };
+        let mut ports = TestPorts::new(Some(identity), Ok(SyncState::Ready));
+        ports.push_error = Some(TransportError {
+            message: "API error (400): VALIDATION_ERROR: Invalid request".to_string(),
+            retry_class: ApiRetryClass::Permanent,
+            error_code: Some("VALIDATION_ERROR".to_string()),
+            details: Some(serde_json::json!({
+                "issues": [{
+                    "path": ["events", 1, "payload", "snapshotId"],
+                    "message": "Invalid UUID"
+                }]
+            })),
+            retry_after: None,
+        });
+        {
+            let mut pending = ports.pending_outbox.lock().await;
+            pending.push(outbox_event(
+                "evt-valid",
+                "019cb093-06a8-7534-8677-546317b17957",
+                1,
+            ));
+            pending.push(outbox_event(
+                "evt-invalid",
+                "019cb093-06a8-7534-8677-546317b17958",
+                1,
+            ));
+        }
+
+        let result = run_sync_cycle(&ports, false)
+            .await
+            .expect("cycle should report the push failure");
+
+        assert_eq!(result.status, "push_error");
+        let rejected = ports.rejected_events.lock().await.clone();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].0, "evt-invalid");
+        assert_eq!(rejected[0].1[0].path, "payload.snapshotId");
+        assert_eq!(rejected[0].1[0].message, "Invalid UUID");
+        let remaining_ids = ports
+            .pending_outbox
+            .lock()
+            .await
+            .iter()
+            .map(|event| event.event_id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(remaining_ids, vec!["evt-valid".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn run_sync_cycle_push_conflict_needing_recovery_requests_bootstrap() {
+        let identity = SyncIdentity {
+            device_id: Some("019cb093-06a8-7534-8677-546317b17957".to_string()),
+            root_key: Some("root-key".to_string()),
+            key_version: Some(1),
+        };
+        let mut ports = TestPorts::new(Some(identity), Ok(SyncState::Ready));
+        ports.push_error = Some(TransportError {
+            message: "API error (409): SYNC_CURSOR_TOO_OLD: Cursor too old".to_string(),
+            retry_class: ApiRetryClass::Recover,
+            error_code: Some(crate::error::SYNC_CURSOR_TOO_OLD.to_string()),
+            details: None,
+            retry_after: None,
+        });
+        ports.pending_outbox.lock().await.push(outbox_event(
+            "evt-current",
+            "019cb093-06a8-7534-8677-546317b17957",
+            1,
+        ));
+
+        let result = run_sync_cycle(&ports, false)
+            .await
+            .expect("cycle should report the stale cursor");
+
+        assert_eq!(result.status, "stale_cursor");
+        assert!(result.needs_bootstrap);
+        assert!(ports.rejected_events.lock().await.is_empty());
+        assert!(ports.dead_outbox_batches.lock().await.is_empty());
+        assert_eq!(ports.pending_outbox.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn background_cycle_bootstraps_on_integrity_error_at_most_three_times_an_hour() {
+        let mut ports = TestPorts::new(Some(ready_identity()), Ok(SyncState::Ready));
+        ports.reconcile_response.action = "PULL_TAIL".to_string();
+        ports.reconcile_response.cursor = Some(9);
+        ports.pull_error = Some(TransportError {
+            message: "API error (409): SYNC_SEGMENT_CHECKSUM_MISMATCH: Segment checksum mismatch"
+                .to_string(),
+            retry_class: ApiRetryClass::Recover,
+            error_code: Some(crate::error::SYNC_SEGMENT_CHECKSUM_MISMATCH.to_string()),
+            details: None,
+            retry_after: None,
+        });
+        let runtime = DeviceSyncRuntimeState::new();
+
+        let result = run_background_cycle(&runtime, &ports)
+            .await
+            .expect("cycle should report the integrity error");
+
+        assert!(result.needs_bootstrap);
+        assert_eq!(
+            ports.auto_bootstrap_reasons.lock().await.as_slice(),
+            ["stale_cursor"]
+        );
+        // The cycle after the bootstrap ran instead of an incremental retry.
+        assert_eq!(
+            ports.cycle_outcomes.lock().await.as_slice(),
+            ["stale_cursor", "stale_cursor"]
+        );
+
+        for _ in 0..3 {
+            run_background_cycle(&runtime, &ports)
+                .await
+                .expect("cycle should report the integrity error");
+        }
+        assert_eq!(
+            ports.auto_bootstrap_reasons.lock().await.len(),
+            auto_bootstrap::DEVICE_SYNC_AUTO_BOOTSTRAP_LIMIT
+        );
+    }
+
+    #[tokio::test]
+    async fn run_sync_cycle_recovers_stale_cursor_from_snapshot_keeping_outbox() {
+        let identity = SyncIdentity {
+            device_id: Some("019cb093-06a8-7534-8677-546317b17957".to_string()),
+            root_key: Some("root-key".to_string()),
+            key_version: Some(1),
+        };
+        let mut ports = TestPorts::new(Some(identity), Ok(SyncState::Ready));
+        ports.push_error = Some(TransportError {
+            message: "API error (409): SYNC_CURSOR_TOO_OLD: Cursor too old".to_string(),
+            retry_class: ApiRetryClass::Recover,
+            error_code: Some(crate::error::SYNC_CURSOR_TOO_OLD.to_string()),
+            details: None,
+            retry_after: None,
+        });
+        ports.stale_cursor_snapshot = Some(("snap-7".to_string(), 42));
+        ports.pending_outbox.lock().await.push(outbox_event(
+            "evt-unsynced",
+            "019cb093-06a8-7534-8677-546317b17957",
+            1,
+        ));
+
+        let result = run_sync_cycle(&ports, false)
+            .await
+            .expect("cycle should recover the stale cursor");
+
+        assert_eq!(result.status, "cursor_reset");
+        assert!(!result.needs_bootstrap);
+        assert_eq!(result.bootstrap_snapshot_id.as_deref(), Some("snap-7"));
+        assert_eq!(ports.set_cursor_calls.lock().await.as_slice(), [42]);
+        assert!(ports.auto_bootstrap_reasons.lock().await.is_empty());
+        assert!(ports.dead_outbox_batches.lock().await.is_empty());
+        assert_eq!(ports.pending_outbox.lock().await.len(), 1);
+    }
+
     #[derive(Clone)]
     struct ReconcileTestPorts {
         sync_state: Result<SyncState, String>,
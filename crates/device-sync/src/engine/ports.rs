@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use wealthfolio_core::sync::{SyncEngineStatus, SyncEntity, SyncOperation, SyncOutboxEvent};
+use wealthfolio_core::sync::{
+    SyncEngineStatus, SyncEntity, SyncFieldError, SyncOperation, SyncOutboxEvent,
+};
 
+use super::replay_failure::{ReplayApplyError, ReplayFailurePolicy};
 use crate::{
     ApiRetryClass, ReconcileReadyStateResponse, SyncCursorResponse, SyncPullResponse,
     SyncPushRequest, SyncPushResponse, SyncState,
@@ -74,6 +77,8 @@ pub struct TransportError {
     pub retry_class: ApiRetryClass,
     pub error_code: Option<String>,
     pub details: Option<serde_json::Value>,
+    /// Server-requested delay before retrying (`Retry-After`).
+    pub retry_after: Option<std::time::Duration>,
 }
 
 impl std::fmt::Display for TransportError {
@@ -93,6 +98,18 @@ pub trait OutboxStore: Send + Sync {
         error_message: Option<String>,
         error_code: Option<String>,
     ) -> Result<(), String>;
+    /// Dead-letters one event the cloud rejected as invalid, keeping the field-level reasons
+    /// so the user can see what to fix. Stores that cannot keep them just dead-letter it.
+    async fn mark_outbox_rejected(
+        &self,
+        event_id: String,
+        error_message: Option<String>,
+        error_code: Option<String>,
+        _field_errors: Vec<SyncFieldError>,
+    ) -> Result<(), String> {
+        self.mark_outbox_dead(vec![event_id], error_message, error_code)
+            .await
+    }
     async fn mark_outbox_sent(&self, event_ids: Vec<String>) -> Result<(), String>;
     async fn schedule_outbox_retry(
         &self,
@@ -107,15 +124,18 @@ pub trait OutboxStore: Send + Sync {
 
 #[async_trait]
 pub trait ReplayStore: Send + Sync {
-    async fn acquire_cycle_lock(&self) -> Result<i64, String>;
+    /// New lock version for this cycle, or `None` while another instance holds the lock.
+    async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String>;
     async fn verify_cycle_lock(&self, lock_version: i64) -> Result<bool, String>;
     async fn get_cursor(&self) -> Result<i64, String>;
     async fn set_cursor(&self, cursor: i64) -> Result<(), String>;
     async fn apply_remote_events_lww_batch(
         &self,
         events: Vec<ReplayEvent>,
-    ) -> Result<usize, String>;
+    ) -> Result<usize, ReplayApplyError>;
     async fn apply_remote_event_lww(&self, event: ReplayEvent) -> Result<bool, String>;
+    /// What to do when a pulled batch fails to apply; see [`ReplayFailurePolicy`].
+    fn replay_failure_policy(&self) -> ReplayFailurePolicy;
     async fn mark_pull_completed(&self) -> Result<(), String>;
     async fn mark_cycle_outcome(
         &self,
@@ -136,6 +156,26 @@ pub trait ReplayStore: Send + Sync {
     async fn on_pull_complete(&self, _pulled_count: usize) -> Result<(), String> {
         Ok(())
     }
+    /// Called when the background engine re-downloads the snapshot because a cycle reported
+    /// `reason` (its status) and needs a bootstrap. Implementations tell the UI it is
+    /// re-syncing from scratch, then bootstrap; status `applied` means local state was rebuilt.
+    async fn auto_bootstrap(&self, _reason: &str) -> Result<SyncBootstrapResult, String> {
+        Ok(SyncBootstrapResult {
+            status: "unsupported".to_string(),
+            message: "Automatic bootstrap is not supported by this host".to_string(),
+            snapshot_id: None,
+        })
+    }
+    /// Restores the latest snapshot and moves the cursor to it after the server reported the
+    /// cursor too old, keeping local changes that were not pushed yet (pending in the outbox
+    /// and applied again on top). Status `applied` means the cycle can resume from there.
+    async fn restore_snapshot_for_stale_cursor(&self) -> Result<SyncBootstrapResult, String> {
+        Ok(SyncBootstrapResult {
+            status: "unsupported".to_string(),
+            message: "Stale cursor recovery is not supported by this host".to_string(),
+            snapshot_id: None,
+        })
+    }
 }
 
 #[async_trait]
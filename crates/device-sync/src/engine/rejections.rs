@@ -0,0 +1,179 @@
+//! Attributing a rejected push to the records that caused it.
+//!
+//! A validation failure rejects the whole push batch, but the structured `details` of the API
+//! error usually name the offending fields, often with the index of the event in the batch
+//! (`["events", 3, "payload", "snapshotId"]`). Those events are dead-lettered with their
+//! field-level reasons; the rest of the batch is retried.
+
+use serde_json::Value;
+use wealthfolio_core::sync::SyncFieldError;
+
+/// One event the cloud refused, with the reasons that apply to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EventRejection {
+    pub event_id: String,
+    pub field_errors: Vec<SyncFieldError>,
+}
+
+/// A field error and, when the path or issue says so, the event it belongs to.
+struct LocatedFieldError {
+    event_index: Option<usize>,
+    event_id: Option<String>,
+    error: SyncFieldError,
+}
+
+/// Issue objects in the details: a list, a list under `issues`/`errors`/`apiDetails`, or a
+/// single issue carrying a `path`.
+fn issue_values(details: &Value) -> Vec<&Value> {
+    if let Some(items) = details.as_array() {
+        return items.iter().collect();
+    }
+    for key in ["issues", "errors", "apiDetails"] {
+        if let Some(items) = details.get(key).and_then(Value::as_array) {
+            return items.iter().collect();
+        }
+    }
+    if details.get("path").is_some() {
+        return vec![details];
+    }
+    Vec::new()
+}
+
+fn parse_issue(issue: &Value) -> Option<LocatedFieldError> {
+    let message = issue.get("message").and_then(Value::as_str)?.to_string();
+    let segments: Vec<&Value> = match issue.get("path") {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(path @ Value::String(_)) => vec![path],
+        _ => Vec::new(),
+    };
+
+    let mut event_index = None;
+    let mut rest = segments.as_slice();
+    if let [first, index, tail @ ..] = rest {
+        if first.as_str() == Some("events") {
+            if let Some(index) = index.as_u64() {
+                event_index = usize::try_from(index).ok();
+                rest = tail;
+            }
+        }
+    }
+    let path = rest
+        .iter()
+        .map(|segment| match segment {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+
+    Some(LocatedFieldError {
+        event_index,
+        event_id: issue
+            .get("eventId")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        error: SyncFieldError {
+            path,
+            message,
+            code: issue
+                .get("code")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        },
+    })
+}
+
+/// Splits a rejected batch into the events to dead-letter. When the details pinpoint events,
+/// only those are returned; otherwise every event is, carrying every reason.
+pub(crate) fn attribute_push_rejection(
+    event_ids: &[String],
+    details: Option<&Value>,
+) -> Vec<EventRejection> {
+    let located: Vec<LocatedFieldError> = details
+        .map(issue_values)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(parse_issue)
+        .collect();
+
+    let target_of = |error: &LocatedFieldError| -> Option<&String> {
+        if let Some(id) = &error.event_id {
+            return event_ids.iter().find(|candidate| *candidate == id);
+        }
+        error.event_index.and_then(|index| event_ids.get(index))
+    };
+    let pinpointed = located.iter().any(|error| target_of(error).is_some());
+    let shared: Vec<SyncFieldError> = located
+        .iter()
+        .filter(|error| target_of(error).is_none())
+        .map(|error| error.error.clone())
+        .collect();
+
+    event_ids
+        .iter()
+        .filter_map(|event_id| {
+            let mut field_errors: Vec<SyncFieldError> = located
+                .iter()
+                .filter(|error| target_of(error) == Some(event_id))
+                .map(|error| error.error.clone())
+                .collect();
+            if pinpointed && field_errors.is_empty() {
+                return None;
+            }
+            field_errors.extend(shared.iter().cloned());
+            Some(EventRejection {
+                event_id: event_id.clone(),
+                field_errors,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn indexed_issues_pinpoint_the_offending_event() {
+        let details = json!({
+            "issues": [{
+                "path": ["events", 1, "payload", "snapshotId"],
+                "message": "Invalid UUID",
+                "code": "invalid_format"
+            }],
+            "clientRequestId": "req-1"
+        });
+
+        let rejections = attribute_push_rejection(&ids(&["a", "b", "c"]), Some(&details));
+
+        assert_eq!(
+            rejections,
+            vec![EventRejection {
+                event_id: "b".to_string(),
+                field_errors: vec![SyncFieldError {
+                    path: "payload.snapshotId".to_string(),
+                    message: "Invalid UUID".to_string(),
+                    code: Some("invalid_format".to_string()),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn unlocated_issues_reject_the_whole_batch() {
+        let details = json!({ "path": ["snapshotId"], "message": "Invalid UUID" });
+
+        let rejections = attribute_push_rejection(&ids(&["a", "b"]), Some(&details));
+
+        assert_eq!(rejections.len(), 2);
+        assert!(rejections
+            .iter()
+            .all(|r| r.field_errors.len() == 1 && r.field_errors[0].path == "snapshotId"));
+        assert_eq!(attribute_push_rejection(&ids(&["a"]), None).len(), 1);
+    }
+}
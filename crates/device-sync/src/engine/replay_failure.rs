@@ -0,0 +1,106 @@
+//! What a sync cycle does when a pulled batch of events cannot be applied.
+//!
+//! A batch is applied in one transaction, so a failure leaves nothing of it behind. With the
+//! default [`ReplayFailurePolicy::Halt`] the cycle stops there without advancing the cursor, and
+//! the failure is classified by its [`ReplayApplyError`] variant: a conflict with local state
+//! (an event referencing an entity this device does not have, or a duplicate key) means local
+//! state has diverged and needs a bootstrap; any other failure is retried with backoff.
+//! [`ReplayFailurePolicy::SkipFailed`] keeps the older behavior of applying the batch event by
+//! event, dead-lettering the ones that fail and moving past them.
+
+use super::ports::ReplayEvent;
+
+/// Selects the policy: `halt` (default) or `skip`.
+pub const REPLAY_FAILURE_POLICY_ENV: &str = "DEVICE_SYNC_REPLAY_FAILURE_POLICY";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplayFailurePolicy {
+    /// Keep the batch rolled back and the cursor before it.
+    #[default]
+    Halt,
+    /// Apply the batch event by event, skipping the events that fail.
+    SkipFailed,
+}
+
+impl ReplayFailurePolicy {
+    /// Reads [`REPLAY_FAILURE_POLICY_ENV`]; unknown values fall back to
+    /// [`ReplayFailurePolicy::Halt`]. Read once when the engine ports are built.
+    pub fn from_env() -> Self {
+        match std::env::var(REPLAY_FAILURE_POLICY_ENV) {
+            Ok(value) if value.trim().eq_ignore_ascii_case("skip") => Self::SkipFailed,
+            _ => Self::Halt,
+        }
+    }
+}
+
+/// How a halted cycle recovers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFailureRecovery {
+    /// The failure is not known to involve local state; the same batch is retried later.
+    Retry,
+    /// The batch conflicts with local state and will keep failing until it is rebuilt.
+    Bootstrap,
+}
+
+impl ReplayFailureRecovery {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Retry => "retry",
+            Self::Bootstrap => "bootstrap",
+        }
+    }
+}
+
+/// Why a pulled batch could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReplayApplyError {
+    /// An event conflicts with local state: it references a missing entity or breaks a
+    /// uniqueness constraint. Applying the same batch again keeps failing.
+    #[error("{0}")]
+    Conflict(String),
+    /// Any other failure, e.g. a busy or unavailable store.
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl ReplayApplyError {
+    pub fn recovery(&self) -> ReplayFailureRecovery {
+        match self {
+            Self::Conflict(_) => ReplayFailureRecovery::Bootstrap,
+            Self::Failed(_) => ReplayFailureRecovery::Retry,
+        }
+    }
+}
+
+/// The event an apply error names, if any. Failures found only when the batch commits (deferred
+/// foreign key checks) do not name one.
+pub fn failing_event<'a>(events: &'a [ReplayEvent], message: &str) -> Option<&'a ReplayEvent> {
+    events
+        .iter()
+        .find(|event| message.contains(&format!("event_id={} ", event.event_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicts_bootstrap_and_other_failures_are_retried() {
+        assert_eq!(
+            ReplayApplyError::Conflict(
+                "Replay batch foreign key check failed: table=activities parent=accounts"
+                    .to_string()
+            )
+            .recovery(),
+            ReplayFailureRecovery::Bootstrap
+        );
+        assert_eq!(
+            ReplayApplyError::Failed("Database error: database is locked".to_string()).recovery(),
+            ReplayFailureRecovery::Retry
+        );
+        assert_eq!(
+            ReplayApplyError::Failed("something nobody anticipated".to_string()).recovery(),
+            ReplayFailureRecovery::Retry
+        );
+    }
+}
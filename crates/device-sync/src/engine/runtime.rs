@@ -5,6 +5,7 @@ use tokio::sync::Mutex;
 use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 
+use super::auto_bootstrap::AutoBootstrapGuard;
 use super::{
     run_background_loop, run_sync_cycle, CredentialStore, OutboxStore, ReplayStore,
     SyncCycleResult, SyncTransport,
@@ -86,6 +87,7 @@ pub struct DeviceSyncRuntimeState {
     wake_handle: DeviceSyncWakeHandle,
     pub snapshot_upload_cancelled: AtomicBool,
     pairing_flows: std::sync::Mutex<HashMap<String, PairingFlowState>>,
+    pub(crate) auto_bootstrap_guard: AutoBootstrapGuard,
 }
 
 impl DeviceSyncRuntimeState {
@@ -100,6 +102,7 @@ impl DeviceSyncRuntimeState {
             wake_handle,
             snapshot_upload_cancelled: AtomicBool::new(false),
             pairing_flows: std::sync::Mutex::new(HashMap::new()),
+            auto_bootstrap_guard: AutoBootstrapGuard::new(),
         }
     }
 }
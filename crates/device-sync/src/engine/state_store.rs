@@ -0,0 +1,222 @@
+//! Sync state storage shared by the engine's cursor and cycle lock.
+//!
+//! The local SQLite store is the default. Multi-instance deployments can point every
+//! instance at the same key-value backend so they advance a single cursor and take turns
+//! through an exclusive, expiring cycle lock.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+/// How long an instance keeps the cycle lock. An instance that stops syncing loses it after
+/// this long, so another one takes over.
+pub const CYCLE_LOCK_TTL: Duration = Duration::from_secs(120);
+
+/// Cursor and cycle-lock state for the sync engine.
+///
+/// `acquire_cycle_lock` returns a new lock version, or `None` while another instance holds
+/// the lock; a cycle holding an older version must stop before committing once
+/// `verify_cycle_lock` returns `false`.
+#[async_trait]
+pub trait SyncStateStore: Send + Sync {
+    async fn get_cursor(&self) -> Result<i64, String>;
+    async fn set_cursor(&self, cursor: i64) -> Result<(), String>;
+    async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String>;
+    async fn verify_cycle_lock(&self, lock_version: i64) -> Result<bool, String>;
+}
+
+/// Minimal key-value operations needed by [`KeyValueSyncStateStore`].
+#[async_trait]
+pub trait KeyValueClient: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>, String>;
+    async fn set(&self, key: &str, value: &str) -> Result<(), String>;
+    /// Atomically increments the integer at `key` (starting from 0) and returns the new value.
+    async fn incr(&self, key: &str) -> Result<i64, String>;
+    /// Sets `key` with an expiry only if it does not exist (`SET NX PX`). Returns whether it
+    /// was set.
+    async fn set_if_absent(&self, key: &str, value: &str, ttl: Duration) -> Result<bool, String>;
+    /// Resets the expiry of an existing `key`. Returns `false` when the key is gone.
+    async fn expire(&self, key: &str, ttl: Duration) -> Result<bool, String>;
+}
+
+/// Sync state kept in an external key-value store, namespaced per sync team or deployment.
+///
+/// The cycle lock names the instance holding it. The holder renews it on every cycle and
+/// other instances skip their cycles until it expires.
+pub struct KeyValueSyncStateStore {
+    client: Arc<dyn KeyValueClient>,
+    namespace: String,
+    instance_id: String,
+}
+
+impl KeyValueSyncStateStore {
+    pub fn new(client: Arc<dyn KeyValueClient>, namespace: impl Into<String>) -> Self {
+        Self {
+            client,
+            namespace: namespace.into(),
+            instance_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}:{}", self.namespace, name)
+    }
+
+    async fn get_i64(&self, name: &str) -> Result<Option<i64>, String> {
+        self.client
+            .get(&self.key(name))
+            .await?
+            .map(|value| {
+                value
+                    .parse::<i64>()
+                    .map_err(|e| format!("Invalid sync state value for {}: {}", name, e))
+            })
+            .transpose()
+    }
+
+    async fn holds_cycle_lock(&self) -> Result<bool, String> {
+        Ok(self.client.get(&self.key("lock_holder")).await?.as_deref()
+            == Some(self.instance_id.as_str()))
+    }
+}
+
+#[async_trait]
+impl SyncStateStore for KeyValueSyncStateStore {
+    async fn get_cursor(&self) -> Result<i64, String> {
+        Ok(self.get_i64("cursor").await?.unwrap_or(0))
+    }
+
+    async fn set_cursor(&self, cursor: i64) -> Result<(), String> {
+        self.client
+            .set(&self.key("cursor"), &cursor.to_string())
+            .await
+    }
+
+    async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String> {
+        let holder_key = self.key("lock_holder");
+        let acquired = self
+            .client
+            .set_if_absent(&holder_key, &self.instance_id, CYCLE_LOCK_TTL)
+            .await?
+            || (self.holds_cycle_lock().await?
+                && self.client.expire(&holder_key, CYCLE_LOCK_TTL).await?);
+        if !acquired {
+            return Ok(None);
+        }
+        // The version fences a cycle that outlived its lock: once another instance takes
+        // over it bumps the version and the older cycle stops before committing.
+        self.client.incr(&self.key("lock_version")).await.map(Some)
+    }
+
+    async fn verify_cycle_lock(&self, lock_version: i64) -> Result<bool, String> {
+        Ok(self.holds_cycle_lock().await?
+            && self.get_i64("lock_version").await? == Some(lock_version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockKeyValueClient {
+        values: Mutex<HashMap<String, String>>,
+    }
+
+    #[async_trait]
+    impl KeyValueClient for MockKeyValueClient {
+        async fn get(&self, key: &str) -> Result<Option<String>, String> {
+            Ok(self.values.lock().await.get(key).cloned())
+        }
+
+        async fn set(&self, key: &str, value: &str) -> Result<(), String> {
+            self.values
+                .lock()
+                .await
+                .insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+
+        async fn incr(&self, key: &str) -> Result<i64, String> {
+            let mut values = self.values.lock().await;
+            let next = values
+                .get(key)
+                .map(|value| value.parse::<i64>().map_err(|e| e.to_string()))
+                .transpose()?
+                .unwrap_or(0)
+                + 1;
+            values.insert(key.to_string(), next.to_string());
+            Ok(next)
+        }
+
+        async fn set_if_absent(
+            &self,
+            key: &str,
+            value: &str,
+            _ttl: Duration,
+        ) -> Result<bool, String> {
+            let mut values = self.values.lock().await;
+            if values.contains_key(key) {
+                return Ok(false);
+            }
+            values.insert(key.to_string(), value.to_string());
+            Ok(true)
+        }
+
+        async fn expire(&self, key: &str, _ttl: Duration) -> Result<bool, String> {
+            Ok(self.values.lock().await.contains_key(key))
+        }
+    }
+
+    #[tokio::test]
+    async fn key_value_store_round_trips_cursor() {
+        let store = KeyValueSyncStateStore::new(Arc::new(MockKeyValueClient::default()), "team-1");
+
+        assert_eq!(store.get_cursor().await.unwrap(), 0);
+        store.set_cursor(42).await.unwrap();
+        assert_eq!(store.get_cursor().await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn instances_sharing_a_backend_share_cursor_and_fence_cycles() {
+        let client: Arc<dyn KeyValueClient> = Arc::new(MockKeyValueClient::default());
+        let first = KeyValueSyncStateStore::new(Arc::clone(&client), "team-1");
+        let second = KeyValueSyncStateStore::new(Arc::clone(&client), "team-1");
+        let other_team = KeyValueSyncStateStore::new(client, "team-2");
+
+        first.set_cursor(7).await.unwrap();
+        assert_eq!(second.get_cursor().await.unwrap(), 7);
+        assert_eq!(other_team.get_cursor().await.unwrap(), 0);
+
+        let first_lock = first.acquire_cycle_lock().await.unwrap().expect("lock");
+        assert!(first.verify_cycle_lock(first_lock).await.unwrap());
+
+        // The lock is exclusive: the other instance skips while the first one holds it.
+        assert_eq!(second.acquire_cycle_lock().await.unwrap(), None);
+        assert!(!second.verify_cycle_lock(first_lock).await.unwrap());
+        assert!(other_team.acquire_cycle_lock().await.unwrap().is_some());
+
+        // The holder renews the lock with a newer version, fencing its older cycle.
+        let renewed_lock = first.acquire_cycle_lock().await.unwrap().expect("lock");
+        assert!(!first.verify_cycle_lock(first_lock).await.unwrap());
+        assert!(first.verify_cycle_lock(renewed_lock).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn lock_passes_to_another_instance_once_it_expires() {
+        let client = Arc::new(MockKeyValueClient::default());
+        let first = KeyValueSyncStateStore::new(client.clone(), "team-1");
+        let second = KeyValueSyncStateStore::new(client.clone(), "team-1");
+
+        let first_lock = first.acquire_cycle_lock().await.unwrap().expect("lock");
+        client.values.lock().await.remove("team-1:lock_holder");
+
+        let second_lock = second.acquire_cycle_lock().await.unwrap().expect("lock");
+        assert!(second.verify_cycle_lock(second_lock).await.unwrap());
+        assert!(!first.verify_cycle_lock(first_lock).await.unwrap());
+        assert_eq!(first.acquire_cycle_lock().await.unwrap(), None);
+    }
+}
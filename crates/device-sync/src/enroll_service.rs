@@ -3,17 +3,19 @@
 //! High-level service that orchestrates device enrollment and E2EE setup.
 //! Accepts a SecretStore via dependency injection for cross-platform compatibility.
 
+use async_trait::async_trait;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, OnceLock};
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 use tokio::time::{sleep, Duration};
 
 use wealthfolio_core::secrets::SecretStore;
 
 use crate::{
-    crypto, CommitInitializeKeysRequest, DevicePlatform, DeviceSyncClient, EnrollDeviceResponse,
-    InitializeKeysResult, RegisterDeviceRequest, TrustState, TrustedDeviceSummary,
+    crypto, error::SYNC_CREDENTIAL_ROTATION_UNSUPPORTED, CommitInitializeKeysRequest,
+    DevicePlatform, DeviceSyncClient, DeviceSyncError, EnrollDeviceResponse, InitializeKeysResult,
+    RegisterDeviceRequest, RotateDeviceCredentialRequest, TrustState, TrustedDeviceSummary,
 };
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -21,7 +23,11 @@ use crate::{
 // ─────────────────────────────────────────────────────────────────────────────
 
 const SYNC_IDENTITY_KEY: &str = "sync_identity";
+/// Device ID stored on its own by older versions, next to the identity.
+const LEGACY_DEVICE_ID_KEY: &str = "sync_device_id";
 const RESET_REASON_REINITIALIZE: &str = "reinitialize";
+const REENROLL_REQUIRED_CODE: &str = "REENROLL_REQUIRED";
+const ROTATION_UNSUPPORTED_CODE: &str = "ROTATION_UNSUPPORTED";
 
 static ENROLL_OPERATION_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
@@ -48,7 +54,19 @@ pub struct SyncIdentity {
     pub device_public_key: Option<String>,
 }
 
-/// Current sync state
+/// Current sync state.
+///
+/// Transitions:
+/// - `FRESH` → `REGISTERED` when enrollment needs pairing with a trusted device, `READY` when
+///   this device initializes or already holds the keys, `ORPHANED` when keys exist but no
+///   trusted device is left to pair with.
+/// - `REGISTERED` → `READY` once pairing delivers the keys.
+/// - `READY` → `STALE` when another device rotates the keys; re-pairing returns to `READY`.
+/// - `ORPHANED` → `READY` through `reinitialize_sync`.
+/// - Any enrolled state → `RECOVERY` when the server revoked or removed the device.
+/// - Any state → `FRESH` when sync data is cleared.
+///
+/// Transitions are published through [`DeviceEnrollService::subscribe_state`].
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SyncState {
@@ -91,6 +109,33 @@ pub struct EnableSyncResult {
     pub trusted_devices: Vec<TrustedDeviceSummary>,
 }
 
+/// Result from rotate_device_credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateCredentialResult {
+    pub device_id: String,
+    pub device_public_key: String,
+}
+
+/// Result from revoke_device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeDeviceResult {
+    pub device_id: String,
+    /// The revoked device was this one; its engine, enrollment and local sync session are gone.
+    pub was_current: bool,
+}
+
+/// Local sync state the app keeps besides the enrollment: the background engine and the synced
+/// session in its database. Revoking this device tears both down through it.
+#[async_trait]
+pub trait LocalSyncTeardown: Send + Sync {
+    /// Stops the background engine, returning once it no longer runs.
+    async fn stop_engine(&self) -> Result<(), String>;
+    /// Forgets the local sync session: cursor, outbox and snapshot floor.
+    async fn reset_local_session(&self) -> Result<(), String>;
+}
+
 /// Service error
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -139,6 +184,8 @@ pub struct DeviceEnrollService {
     client: DeviceSyncClient,
     device_display_name: String,
     app_version: Option<String>,
+    /// Last state this service determined; `None` until the first check.
+    state_tx: watch::Sender<Option<SyncState>>,
 }
 
 impl DeviceEnrollService {
@@ -154,9 +201,30 @@ impl DeviceEnrollService {
             client: DeviceSyncClient::new(base_url),
             device_display_name,
             app_version,
+            state_tx: watch::channel(None).0,
         }
     }
 
+    /// Observes state transitions. Receivers are notified only when the state changes, not on
+    /// every check that finds the same state.
+    pub fn subscribe_state(&self) -> watch::Receiver<Option<SyncState>> {
+        self.state_tx.subscribe()
+    }
+
+    fn publish_state(&self, state: &SyncState) {
+        self.state_tx.send_if_modified(|current| {
+            if current.as_ref() == Some(state) {
+                return false;
+            }
+            info!(
+                "[DeviceEnrollService] State changed: {:?} -> {:?}",
+                current, state
+            );
+            *current = Some(state.clone());
+            true
+        });
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // PUBLIC API
     // ═══════════════════════════════════════════════════════════════════════════
@@ -164,6 +232,15 @@ impl DeviceEnrollService {
     /// Get the current sync state.
     /// Reads from secret store and optionally verifies with server.
     pub async fn get_sync_state(&self, token: &str) -> Result<SyncStateResult, EnrollServiceError> {
+        let result = self.get_sync_state_inner(token).await?;
+        self.publish_state(&result.state);
+        Ok(result)
+    }
+
+    async fn get_sync_state_inner(
+        &self,
+        token: &str,
+    ) -> Result<SyncStateResult, EnrollServiceError> {
         // Read identity from secret store
         let identity = self.read_identity()?;
 
@@ -307,7 +384,9 @@ impl DeviceEnrollService {
     /// 4. Save all credentials to secret store
     pub async fn enable_sync(&self, token: &str) -> Result<EnableSyncResult, EnrollServiceError> {
         let _guard = enroll_operation_lock().lock().await;
-        self.enable_sync_inner(token).await
+        let result = self.enable_sync_inner(token).await?;
+        self.publish_state(&result.state);
+        Ok(result)
     }
 
     async fn enable_sync_inner(&self, token: &str) -> Result<EnableSyncResult, EnrollServiceError> {
@@ -412,6 +491,7 @@ impl DeviceEnrollService {
             device_nonce: preserved_nonce,
             ..Default::default()
         })?;
+        self.publish_state(&SyncState::Fresh);
         Ok(())
     }
 
@@ -435,8 +515,183 @@ impl DeviceEnrollService {
             device_nonce: Some(preserved_nonce),
             ..Default::default()
         })?;
+        self.publish_state(&SyncState::Fresh);
+
+        let result = self.enable_sync_inner(token).await?;
+        self.publish_state(&result.state);
+        Ok(result)
+    }
+
+    /// Rotate this device's credential in place, without re-enrolling.
+    ///
+    /// Generates a new device keypair, has the server swap it in for (and invalidate) the
+    /// current public key, then stores it with a verified write. Device ID, nonce and E2EE
+    /// keys are kept, so the sync cursor and trust state carry over. Fails with
+    /// `REENROLL_REQUIRED` when the server no longer accepts the current credential, and with
+    /// `ROTATION_UNSUPPORTED` when the server has no rotation endpoint.
+    pub async fn rotate_device_credential(
+        &self,
+        token: &str,
+    ) -> Result<RotateCredentialResult, EnrollServiceError> {
+        let _guard = enroll_operation_lock().lock().await;
+        info!("[DeviceEnrollService] Rotating device credential...");
+
+        let mut identity = self.read_identity()?;
+        let (device_id, current_secret_key) =
+            match (&identity.device_id, &identity.device_secret_key) {
+                (Some(device_id), Some(secret_key)) => (device_id.clone(), secret_key.clone()),
+                _ => {
+                    return Err(EnrollServiceError {
+                        code: "NOT_ENROLLED".to_string(),
+                        message: "This device has no credential to rotate. Enable sync first."
+                            .to_string(),
+                    });
+                }
+            };
+
+        let keypair = crypto::generate_ephemeral_keypair();
+        // Signed with the current device key so the server can check it against the public
+        // key it already has on record for this device.
+        let signature = crypto::sign_with_device_key(
+            &current_secret_key,
+            &format!("{}:{}", device_id, keypair.public_key),
+        )
+        .map_err(|e| format!("Failed to sign credential rotation: {}", e))?;
+
+        let response = self
+            .client
+            .rotate_device_credential(
+                token,
+                &device_id,
+                RotateDeviceCredentialRequest {
+                    device_public_key: keypair.public_key.clone(),
+                    signature,
+                },
+            )
+            .await
+            .map_err(|e| {
+                if e.is_reenroll_required() {
+                    warn!("[DeviceEnrollService] Credential rotation rejected: re-enrollment required");
+                    EnrollServiceError {
+                        code: REENROLL_REQUIRED_CODE.to_string(),
+                        message: "The server no longer accepts this device's credential. Reset sync on this device and enable it again to re-enroll.".to_string(),
+                    }
+                } else if e.error_code() == Some(SYNC_CREDENTIAL_ROTATION_UNSUPPORTED) {
+                    EnrollServiceError {
+                        code: ROTATION_UNSUPPORTED_CODE.to_string(),
+                        message: "The sync server does not support credential rotation yet. Your current credential is unchanged.".to_string(),
+                    }
+                } else {
+                    format!("Credential rotation failed: {}", e).into()
+                }
+            })?;
+        if !response.success {
+            return Err(EnrollServiceError {
+                code: "ROTATION_REJECTED".to_string(),
+                message: "Credential rotation was not accepted. Please try again.".to_string(),
+            });
+        }
+
+        // The old credential is already invalid server-side; a failed write leaves this
+        // device unable to authenticate, so report it as needing re-enrollment.
+        identity.device_secret_key = Some(keypair.secret_key);
+        identity.device_public_key = Some(keypair.public_key.clone());
+        self.save_identity_verified(&identity)
+            .map_err(|e| EnrollServiceError {
+                code: REENROLL_REQUIRED_CODE.to_string(),
+                message: format!(
+                    "Credential rotated but could not be stored ({}). Reset sync on this device and enable it again.",
+                    e.message
+                ),
+            })?;
 
-        self.enable_sync_inner(token).await
+        info!(
+            "[DeviceEnrollService] Device credential rotated: {}",
+            device_id
+        );
+        Ok(RotateCredentialResult {
+            device_id,
+            device_public_key: keypair.public_key,
+        })
+    }
+
+    /// Revoke an enrolled device, e.g. a lost one.
+    ///
+    /// Revoking another device is only the server call. Revoking this device also tears it
+    /// down locally through `local`: the engine is stopped first, then the enrollment is
+    /// cleared as [`Self::clear_sync_data`] does and the local sync session is reset. An unknown
+    /// device ID fails with `INVALID_REQUEST`, a refusal with `REVOKE_REJECTED`, and a revoke
+    /// whose local teardown went wrong with `LOCAL_CLEANUP_FAILED`.
+    pub async fn revoke_device(
+        &self,
+        token: &str,
+        device_id: &str,
+        local: &dyn LocalSyncTeardown,
+    ) -> Result<RevokeDeviceResult, EnrollServiceError> {
+        let _guard = enroll_operation_lock().lock().await;
+        info!("[DeviceEnrollService] Revoking device: {}", device_id);
+
+        let response = self
+            .client
+            .revoke_device(token, device_id)
+            .await
+            .map_err(|e| match e {
+                DeviceSyncError::InvalidRequest(message) => EnrollServiceError {
+                    code: "INVALID_REQUEST".to_string(),
+                    message,
+                },
+                e => format!("Failed to revoke device: {}", e).into(),
+            })?;
+        if !response.success {
+            return Err(EnrollServiceError {
+                code: "REVOKE_REJECTED".to_string(),
+                message: "Revoking the device was not accepted. Please try again.".to_string(),
+            });
+        }
+
+        let was_current = self.read_identity()?.device_id.as_deref() == Some(device_id);
+        if was_current {
+            self.tear_down_current_device(local).await?;
+        }
+        Ok(RevokeDeviceResult {
+            device_id: device_id.to_string(),
+            was_current,
+        })
+    }
+
+    /// Stops the engine before the enrollment it runs on is cleared. Every step is attempted
+    /// even when an earlier one fails; the failures are reported together.
+    async fn tear_down_current_device(
+        &self,
+        local: &dyn LocalSyncTeardown,
+    ) -> Result<(), EnrollServiceError> {
+        let mut failures = Vec::new();
+        if let Err(e) = local.stop_engine().await {
+            failures.push(format!("stopping the sync engine: {}", e));
+        }
+        if let Err(e) = self.clear_sync_data() {
+            failures.push(format!("clearing the enrollment: {}", e.message));
+        }
+        if let Err(e) = self.secret_store.delete_secret(LEGACY_DEVICE_ID_KEY) {
+            failures.push(format!("clearing the device ID: {}", e));
+        }
+        if let Err(e) = local.reset_local_session().await {
+            failures.push(format!("resetting the local sync session: {}", e));
+        }
+        if failures.is_empty() {
+            return Ok(());
+        }
+        warn!(
+            "[DeviceEnrollService] Local teardown after revoke failed: {}",
+            failures.join("; ")
+        );
+        Err(EnrollServiceError {
+            code: "LOCAL_CLEANUP_FAILED".to_string(),
+            message: format!(
+                "The device was revoked, but cleaning up this device failed while {}.",
+                failures.join("; ")
+            ),
+        })
     }
 
     // ═══════════════════════════════════════════════════════════════════════════
@@ -571,6 +826,21 @@ impl DeviceEnrollService {
             .map_err(|e| format!("Failed to save identity: {}", e).into())
     }
 
+    /// Save the identity and read it back, failing if the stored keys differ.
+    fn save_identity_verified(&self, identity: &SyncIdentity) -> Result<(), EnrollServiceError> {
+        self.save_identity(identity)?;
+        let stored = self.read_identity()?;
+        if stored.device_id != identity.device_id
+            || stored.device_secret_key != identity.device_secret_key
+            || stored.device_public_key != identity.device_public_key
+        {
+            return Err("Stored identity does not match what was written"
+                .to_string()
+                .into());
+        }
+        Ok(())
+    }
+
     fn ensure_device_nonce(
         &self,
         identity: &mut SyncIdentity,
@@ -791,3 +1061,318 @@ impl DeviceEnrollService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use wealthfolio_core::secrets::MemorySecretStore;
+
+    /// Serves a single canned response and returns the request line it received.
+    async fn start_mock_server(
+        status: u16,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = Vec::new();
+            let mut chunk = [0_u8; 4096];
+            loop {
+                let read = stream.read(&mut chunk).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                buffer.extend_from_slice(&chunk[..read]);
+                let text = String::from_utf8_lossy(&buffer);
+                let Some(header_end) = text.find("\r\n\r\n") else {
+                    continue;
+                };
+                let content_length = text[..header_end]
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                    .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                if buffer.len() >= header_end + 4 + content_length {
+                    break;
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            let _ = stream.flush().await;
+            String::from_utf8_lossy(&buffer)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        });
+        (format!("http://{}", addr), handle)
+    }
+
+    fn enrolled_identity() -> SyncIdentity {
+        let keypair = crypto::generate_ephemeral_keypair();
+        SyncIdentity {
+            version: 2,
+            device_nonce: Some("nonce-1".to_string()),
+            device_id: Some("device-1".to_string()),
+            root_key: Some(crypto::generate_root_key()),
+            key_version: Some(3),
+            device_secret_key: Some(keypair.secret_key),
+            device_public_key: Some(keypair.public_key),
+        }
+    }
+
+    fn service_with_identity(
+        base_url: &str,
+        identity: &SyncIdentity,
+    ) -> (DeviceEnrollService, Arc<MemorySecretStore>) {
+        let store = Arc::new(MemorySecretStore::default());
+        store
+            .set_secret(SYNC_IDENTITY_KEY, &serde_json::to_string(identity).unwrap())
+            .unwrap();
+        let service =
+            DeviceEnrollService::new(store.clone(), base_url, "Test device".to_string(), None);
+        (service, store)
+    }
+
+    fn stored_identity(store: &MemorySecretStore) -> SyncIdentity {
+        serde_json::from_str(&store.get_secret(SYNC_IDENTITY_KEY).unwrap().unwrap()).unwrap()
+    }
+
+    /// Records the teardown steps, noting whether the device was still enrolled when the engine
+    /// stopped.
+    struct RecordingTeardown {
+        store: Arc<MemorySecretStore>,
+        steps: std::sync::Mutex<Vec<String>>,
+        reset_error: Option<&'static str>,
+    }
+
+    impl RecordingTeardown {
+        fn new(store: &Arc<MemorySecretStore>) -> Self {
+            Self {
+                store: store.clone(),
+                steps: std::sync::Mutex::new(Vec::new()),
+                reset_error: None,
+            }
+        }
+
+        fn steps(&self) -> Vec<String> {
+            self.steps.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl LocalSyncTeardown for RecordingTeardown {
+        async fn stop_engine(&self) -> Result<(), String> {
+            let enrolled = stored_identity(&self.store).device_id.is_some();
+            self.steps
+                .lock()
+                .unwrap()
+                .push(format!("stop_engine(enrolled={})", enrolled));
+            Ok(())
+        }
+
+        async fn reset_local_session(&self) -> Result<(), String> {
+            self.steps
+                .lock()
+                .unwrap()
+                .push("reset_local_session".to_string());
+            self.reset_error.map_or(Ok(()), |e| Err(e.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn rotation_replaces_device_keys_and_keeps_enrollment() {
+        let (base_url, server) = start_mock_server(200, r#"{"success":true}"#).await;
+        let before = enrolled_identity();
+        let (service, store) = service_with_identity(&base_url, &before);
+
+        let result = service.rotate_device_credential("token").await.unwrap();
+
+        let request_line = server.await.unwrap();
+        assert!(
+            request_line.starts_with("POST /api/v1/sync/team/devices/device-1/credential/rotate")
+        );
+        let after = stored_identity(&store);
+        assert_eq!(result.device_id, "device-1");
+        assert_eq!(
+            after.device_public_key.as_deref(),
+            Some(result.device_public_key.as_str())
+        );
+        assert_ne!(after.device_public_key, before.device_public_key);
+        assert_ne!(after.device_secret_key, before.device_secret_key);
+        // Everything the sync cursor and trust depend on is unchanged.
+        assert_eq!(after.device_id, before.device_id);
+        assert_eq!(after.device_nonce, before.device_nonce);
+        assert_eq!(after.root_key, before.root_key);
+        assert_eq!(after.key_version, before.key_version);
+    }
+
+    #[tokio::test]
+    async fn state_transitions_are_published_once_per_change() {
+        let (base_url, server) = start_mock_server(
+            200,
+            r#"{"id":"device-1","userId":"user-1","displayName":"Test device","platform":"linux","trustState":"trusted","trustedKeyVersion":3,"createdAt":"2026-01-01T00:00:00Z"}"#,
+        )
+        .await;
+        let (service, _store) = service_with_identity(&base_url, &enrolled_identity());
+        let mut states = service.subscribe_state();
+        assert_eq!(*states.borrow_and_update(), None);
+
+        let result = service.get_sync_state("token").await.unwrap();
+        server.await.unwrap();
+        assert_eq!(result.state, SyncState::Ready);
+        assert!(states.has_changed().unwrap());
+        assert_eq!(*states.borrow_and_update(), Some(SyncState::Ready));
+
+        service.clear_sync_data().unwrap();
+        assert_eq!(*states.borrow_and_update(), Some(SyncState::Fresh));
+
+        // Still FRESH (the nonce is kept, the device id is gone): no new notification.
+        let result = service.get_sync_state("token").await.unwrap();
+        assert_eq!(result.state, SyncState::Fresh);
+        assert!(!states.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejected_rotation_requires_reenroll_and_keeps_old_credential() {
+        let (base_url, _server) = start_mock_server(
+            409,
+            r#"{"error":"conflict","code":"SYNC_DEVICE_REENROLL_REQUIRED","message":"Device must re-enroll"}"#,
+        )
+        .await;
+        let before = enrolled_identity();
+        let (service, store) = service_with_identity(&base_url, &before);
+
+        let err = service.rotate_device_credential("token").await.unwrap_err();
+
+        assert_eq!(err.code, REENROLL_REQUIRED_CODE);
+        let after = stored_identity(&store);
+        assert_eq!(after.device_secret_key, before.device_secret_key);
+        assert_eq!(after.device_public_key, before.device_public_key);
+    }
+
+    #[tokio::test]
+    async fn rotation_without_server_support_is_unsupported_not_reenroll() {
+        let (base_url, _server) =
+            start_mock_server(404, r#"{"error":"not_found","message":"Not Found"}"#).await;
+        let before = enrolled_identity();
+        let (service, store) = service_with_identity(&base_url, &before);
+
+        let err = service.rotate_device_credential("token").await.unwrap_err();
+
+        assert_eq!(err.code, ROTATION_UNSUPPORTED_CODE);
+        let after = stored_identity(&store);
+        assert_eq!(after.device_secret_key, before.device_secret_key);
+        assert_eq!(after.device_public_key, before.device_public_key);
+    }
+
+    #[tokio::test]
+    async fn revoking_this_device_clears_its_enrollment() {
+        let (base_url, server) = start_mock_server(200, r#"{"success":true}"#).await;
+        let (service, store) = service_with_identity(&base_url, &enrolled_identity());
+        store.set_secret(LEGACY_DEVICE_ID_KEY, "device-1").unwrap();
+        let local = RecordingTeardown::new(&store);
+        let mut states = service.subscribe_state();
+
+        let result = service
+            .revoke_device("token", "device-1", &local)
+            .await
+            .unwrap();
+
+        assert!(server
+            .await
+            .unwrap()
+            .starts_with("POST /api/v1/sync/team/devices/device-1/revoke"));
+        assert!(result.was_current);
+        let after = stored_identity(&store);
+        assert_eq!(after.device_id, None);
+        assert_eq!(after.root_key, None);
+        assert_eq!(after.device_secret_key, None);
+        assert_eq!(after.device_nonce.as_deref(), Some("nonce-1"));
+        assert_eq!(store.get_secret(LEGACY_DEVICE_ID_KEY).unwrap(), None);
+        assert_eq!(*states.borrow_and_update(), Some(SyncState::Fresh));
+        // The engine stops while the enrollment it runs on is still there.
+        assert_eq!(
+            local.steps(),
+            vec!["stop_engine(enrolled=true)", "reset_local_session"]
+        );
+    }
+
+    #[tokio::test]
+    async fn revoking_this_device_reports_a_failed_local_teardown() {
+        let (base_url, server) = start_mock_server(200, r#"{"success":true}"#).await;
+        let (service, store) = service_with_identity(&base_url, &enrolled_identity());
+        let local = RecordingTeardown {
+            reset_error: Some("database is locked"),
+            ..RecordingTeardown::new(&store)
+        };
+
+        let err = service
+            .revoke_device("token", "device-1", &local)
+            .await
+            .unwrap_err();
+
+        server.await.unwrap();
+        assert_eq!(err.code, "LOCAL_CLEANUP_FAILED");
+        assert!(err.message.contains("database is locked"));
+        // The steps that could run still did.
+        assert_eq!(stored_identity(&store).device_id, None);
+        assert_eq!(local.steps().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn revoking_another_device_keeps_this_enrollment() {
+        let (base_url, server) = start_mock_server(200, r#"{"success":true}"#).await;
+        let before = enrolled_identity();
+        let (service, store) = service_with_identity(&base_url, &before);
+        let local = RecordingTeardown::new(&store);
+        let states = service.subscribe_state();
+
+        let result = service
+            .revoke_device("token", "device-2", &local)
+            .await
+            .unwrap();
+
+        assert!(server
+            .await
+            .unwrap()
+            .starts_with("POST /api/v1/sync/team/devices/device-2/revoke"));
+        assert!(!result.was_current);
+        let after = stored_identity(&store);
+        assert_eq!(after.device_id, before.device_id);
+        assert_eq!(after.root_key, before.root_key);
+        assert_eq!(after.device_secret_key, before.device_secret_key);
+        assert!(!states.has_changed().unwrap());
+        assert!(local.steps().is_empty());
+    }
+
+    #[tokio::test]
+    async fn revoking_an_unknown_device_is_an_invalid_request() {
+        let (base_url, _server) = start_mock_server(
+            404,
+            r#"{"error":"not_found","code":"SYNC_DEVICE_NOT_FOUND","message":"Device not found"}"#,
+        )
+        .await;
+        let before = enrolled_identity();
+        let (service, store) = service_with_identity(&base_url, &before);
+        let local = RecordingTeardown::new(&store);
+
+        let err = service
+            .revoke_device("token", "device-9", &local)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.code, "INVALID_REQUEST");
+        assert_eq!(err.message, "Device device-9 does not exist");
+        assert_eq!(stored_identity(&store).device_id, before.device_id);
+    }
+}
@@ -1,6 +1,10 @@
 //! Error types for the device sync crate.
 
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 use thiserror::Error;
+use wealthfolio_core::errors::is_subscription_required_response;
 
 /// Result type alias for device sync operations.
 pub type Result<T> = std::result::Result<T, DeviceSyncError>;
@@ -11,6 +15,9 @@ pub enum ApiRetryClass {
     Retryable,
     Permanent,
     ReauthRequired,
+    /// Local sync state must be repaired first (cursor reset or bootstrap); retrying the same
+    /// request keeps failing until then.
+    Recover,
 }
 
 // Known error codes returned by the sync-v2 API.
@@ -22,19 +29,48 @@ pub const SYNC_SEGMENT_STREAM_MISMATCH: &str = "SYNC_SEGMENT_STREAM_MISMATCH";
 pub const SYNC_EVENT_INDEX_MISMATCH: &str = "SYNC_EVENT_INDEX_MISMATCH";
 pub const SYNC_SNAPSHOT_OBJECT_MISSING: &str = "SYNC_SNAPSHOT_OBJECT_MISSING";
 pub const SYNC_SNAPSHOT_CHECKSUM_MISMATCH: &str = "SYNC_SNAPSHOT_CHECKSUM_MISMATCH";
+pub const SYNC_DEVICE_REENROLL_REQUIRED: &str = "SYNC_DEVICE_REENROLL_REQUIRED";
+/// Set by the client (not the API) when the cloud has no credential-rotation endpoint.
+pub const SYNC_CREDENTIAL_ROTATION_UNSUPPORTED: &str = "SYNC_CREDENTIAL_ROTATION_UNSUPPORTED";
+pub use wealthfolio_core::errors::SYNC_SUBSCRIPTION_REQUIRED;
+
+/// Which stored data an integrity error code is about, so recovery can re-fetch only that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityKind {
+    Segment,
+    Snapshot,
+    EventIndex,
+}
+
+impl IntegrityKind {
+    /// Stable lowercase name, for metric labels and logs.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Segment => "segment",
+            Self::Snapshot => "snapshot",
+            Self::EventIndex => "event_index",
+        }
+    }
+}
+
+/// The kind of integrity problem the given code indicates, if any.
+pub fn integrity_kind(code: &str) -> Option<IntegrityKind> {
+    match code {
+        SYNC_SEGMENT_OBJECT_MISSING
+        | SYNC_SEGMENT_OFFSET_INVALID
+        | SYNC_SEGMENT_CHECKSUM_MISMATCH
+        | SYNC_SEGMENT_STREAM_MISMATCH => Some(IntegrityKind::Segment),
+        SYNC_EVENT_INDEX_MISMATCH => Some(IntegrityKind::EventIndex),
+        SYNC_SNAPSHOT_OBJECT_MISSING | SYNC_SNAPSHOT_CHECKSUM_MISMATCH => {
+            Some(IntegrityKind::Snapshot)
+        }
+        _ => None,
+    }
+}
 
 /// Returns true when the given code indicates an integrity problem.
 pub fn is_integrity_code(code: &str) -> bool {
-    matches!(
-        code,
-        SYNC_SEGMENT_OBJECT_MISSING
-            | SYNC_SEGMENT_OFFSET_INVALID
-            | SYNC_SEGMENT_CHECKSUM_MISMATCH
-            | SYNC_SEGMENT_STREAM_MISMATCH
-            | SYNC_EVENT_INDEX_MISMATCH
-            | SYNC_SNAPSHOT_OBJECT_MISSING
-            | SYNC_SNAPSHOT_CHECKSUM_MISMATCH
-    )
+    integrity_kind(code).is_some()
 }
 
 /// Errors that can occur during device sync operations.
@@ -42,19 +78,30 @@ pub fn is_integrity_code(code: &str) -> bool {
 pub enum DeviceSyncError {
     /// HTTP client error
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[source] reqwest::Error),
+
+    /// The request did not complete within the client's connect or read timeout
+    #[error("Request timed out: {0}")]
+    Timeout(#[source] reqwest::Error),
 
     /// JSON serialization/deserialization error
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
     /// API error response from the cloud service
-    #[error("API error ({status}): {code}: {message}")]
+    #[error(
+        "API error ({status}{}): {code}: {message}",
+        request_id_suffix(request_id)
+    )]
     Api {
         status: u16,
         code: String,
         message: String,
         details: Option<serde_json::Value>,
+        /// How long the server asked us to wait (`Retry-After`), if it said.
+        retry_after: Option<Duration>,
+        /// The server's ID for the failed request (`x-request-id`), for support.
+        request_id: Option<String>,
     },
 
     /// Invalid request (missing required data, etc.)
@@ -66,6 +113,23 @@ pub enum DeviceSyncError {
     Auth(String),
 }
 
+fn request_id_suffix(request_id: &Option<String>) -> String {
+    match request_id {
+        Some(request_id) => format!(", requestId={}", request_id),
+        None => String::new(),
+    }
+}
+
+impl From<reqwest::Error> for DeviceSyncError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Self::Timeout(err)
+        } else {
+            Self::Http(err)
+        }
+    }
+}
+
 impl DeviceSyncError {
     /// Create an API error from status and message
     pub fn api(status: u16, message: impl Into<String>) -> Self {
@@ -74,24 +138,46 @@ impl DeviceSyncError {
             code: String::new(),
             message: message.into(),
             details: None,
+            retry_after: None,
+            request_id: None,
         }
     }
 
-    /// Create an API error with structured code and details
+    /// Create an API error with structured code and details, and the server's request ID
+    /// when the response carried one.
     pub fn api_structured(
         status: u16,
         code: impl Into<String>,
         message: impl Into<String>,
         details: Option<serde_json::Value>,
+        request_id: Option<String>,
     ) -> Self {
         Self::Api {
             status,
             code: code.into(),
             message: message.into(),
             details,
+            retry_after: None,
+            request_id,
         }
     }
 
+    /// Attaches the server's request ID to an API error; other errors are unchanged.
+    pub fn with_request_id(mut self, id: Option<String>) -> Self {
+        if let Self::Api { request_id, .. } = &mut self {
+            *request_id = id;
+        }
+        self
+    }
+
+    /// Attaches the server's `Retry-After` delay to an API error; other errors are unchanged.
+    pub fn with_retry_after(mut self, delay: Option<Duration>) -> Self {
+        if let Self::Api { retry_after, .. } = &mut self {
+            *retry_after = delay;
+        }
+        self
+    }
+
     /// Create an invalid request error
     pub fn invalid_request(message: impl Into<String>) -> Self {
         Self::InvalidRequest(message.into())
@@ -110,6 +196,14 @@ impl DeviceSyncError {
         }
     }
 
+    /// The server's request ID if this is an API error and the response carried one.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::Api { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+
     /// Machine-readable error code, if present.
     pub fn error_code(&self) -> Option<&str> {
         match self {
@@ -140,16 +234,46 @@ impl DeviceSyncError {
         self.error_code() == Some(SYNC_CURSOR_TOO_OLD)
     }
 
+    /// Returns true when the cloud no longer recognizes the device credential and
+    /// only a full re-enrollment can recover.
+    pub fn is_reenroll_required(&self) -> bool {
+        self.error_code() == Some(SYNC_DEVICE_REENROLL_REQUIRED) || self.status_code() == Some(410)
+    }
+
+    /// Returns true when the cloud refused the request because the subscription expired or
+    /// does not cover it. Signing in again does not help; only a plan change does.
+    pub fn is_subscription_required(&self) -> bool {
+        self.status_code()
+            .is_some_and(|status| is_subscription_required_response(status, self.error_code()))
+    }
+
+    /// How long the server asked to wait before retrying, from its `Retry-After` header.
+    pub fn retry_after_hint(&self) -> Option<Duration> {
+        match self {
+            Self::Api { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// True when the request timed out, as opposed to failing to connect or being rejected.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout(_))
+    }
+
     /// Classify error for retry policy.
     pub fn retry_class(&self) -> ApiRetryClass {
         match self {
+            Self::Api { .. } if self.is_subscription_required() => ApiRetryClass::Permanent,
             Self::Api { status, .. } => match *status {
                 401 | 403 => ApiRetryClass::ReauthRequired,
+                409 if self.is_stale_cursor() || self.is_integrity_error() => {
+                    ApiRetryClass::Recover
+                }
                 408 | 409 | 423 | 425 | 429 => ApiRetryClass::Retryable,
                 500..=599 => ApiRetryClass::Retryable,
                 _ => ApiRetryClass::Permanent,
             },
-            Self::Http(_) => ApiRetryClass::Retryable,
+            Self::Http(_) | Self::Timeout(_) => ApiRetryClass::Retryable,
             Self::Json(_) => ApiRetryClass::Permanent,
             Self::InvalidRequest(_) => ApiRetryClass::Permanent,
             Self::Auth(_) => ApiRetryClass::ReauthRequired,
@@ -176,10 +300,72 @@ impl DeviceSyncError {
     }
 }
 
+/// Longest API error message kept by default, in bytes. Error bodies can be whole stack traces or
+/// HTML pages; structured `details` are kept in full regardless.
+pub const DEFAULT_MAX_ERROR_MESSAGE_LEN: usize = 2048;
+
+const TRUNCATION_ELLIPSIS: &str = "…";
+
+/// Cuts `message` to at most `max_len` bytes, ellipsis included, on a character boundary. The
+/// start is kept, so checks on the message (such as
+/// [`DeviceSyncError::is_snapshot_id_validation_error`]) still see where the server put the
+/// reason.
+pub fn truncate_error_message(message: String, max_len: usize) -> String {
+    if message.len() <= max_len {
+        return message;
+    }
+    let mut end = max_len.saturating_sub(TRUNCATION_ELLIPSIS.len());
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &message[..end], TRUNCATION_ELLIPSIS)
+}
+
+/// Longest `Retry-After` that is honored. A larger (or bogus) hint is clamped to it so a single
+/// response cannot stall sync for days.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// Parses a `Retry-After` value, either delay-seconds (`120`) or an HTTP date
+/// (`Wed, 21 Oct 2026 07:28:00 GMT`). A date in the past means retry now.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((at - now).to_std().unwrap_or(Duration::ZERO))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn integrity_codes_are_classified_by_kind() {
+        assert_eq!(
+            integrity_kind(SYNC_SEGMENT_CHECKSUM_MISMATCH),
+            Some(IntegrityKind::Segment)
+        );
+        assert_eq!(
+            integrity_kind(SYNC_SEGMENT_STREAM_MISMATCH),
+            Some(IntegrityKind::Segment)
+        );
+        assert_eq!(
+            integrity_kind(SYNC_SNAPSHOT_OBJECT_MISSING),
+            Some(IntegrityKind::Snapshot)
+        );
+        assert_eq!(
+            integrity_kind(SYNC_EVENT_INDEX_MISMATCH),
+            Some(IntegrityKind::EventIndex)
+        );
+        assert_eq!(integrity_kind(SYNC_CURSOR_TOO_OLD), None);
+        assert!(is_integrity_code(SYNC_SNAPSHOT_CHECKSUM_MISMATCH));
+        assert!(!is_integrity_code(SYNC_DEVICE_REENROLL_REQUIRED));
+        assert_eq!(IntegrityKind::EventIndex.as_str(), "event_index");
+    }
+
     #[test]
     fn snapshot_validation_error_detected() {
         let err = DeviceSyncError::api(
@@ -195,11 +381,74 @@ mod tests {
         assert_eq!(err.retry_class(), ApiRetryClass::ReauthRequired);
     }
 
+    #[test]
+    fn retry_after_parses_seconds_and_http_dates() {
+        let now = DateTime::parse_from_rfc3339("2026-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2026 07:29:30 GMT", now),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2026 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+
+        let err =
+            DeviceSyncError::api(429, "slow down").with_retry_after(Some(Duration::from_secs(120)));
+        assert_eq!(err.retry_class(), ApiRetryClass::Retryable);
+        assert_eq!(err.retry_after_hint(), Some(Duration::from_secs(120)));
+        assert_eq!(DeviceSyncError::api(503, "down").retry_after_hint(), None);
+    }
+
+    #[tokio::test]
+    async fn request_timeouts_get_their_own_variant() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Accept the connection and never answer.
+        let server = tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let err = DeviceSyncError::from(
+            client
+                .get(format!("http://{}/sync", addr))
+                .send()
+                .await
+                .unwrap_err(),
+        );
+        server.abort();
+
+        assert!(err.is_timeout());
+        assert_eq!(err.retry_class(), ApiRetryClass::Retryable);
+        assert!(err.to_string().starts_with("Request timed out"));
+
+        let unreachable = reqwest::Client::new()
+            .get("http://127.0.0.1:1/sync")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(!DeviceSyncError::from(unreachable).is_timeout());
+    }
+
     #[test]
     fn stale_cursor_detected() {
-        let err = DeviceSyncError::api_structured(409, SYNC_CURSOR_TOO_OLD, "Cursor too old", None);
+        let err =
+            DeviceSyncError::api_structured(409, SYNC_CURSOR_TOO_OLD, "Cursor too old", None, None);
         assert!(err.is_stale_cursor());
         assert!(!err.is_integrity_error());
+        assert_eq!(err.retry_class(), ApiRetryClass::Recover);
     }
 
     #[test]
@@ -209,8 +458,105 @@ mod tests {
             SYNC_SEGMENT_CHECKSUM_MISMATCH,
             "Checksum mismatch",
             None,
+            None,
         );
         assert!(err.is_integrity_error());
         assert!(!err.is_stale_cursor());
+        assert_eq!(err.retry_class(), ApiRetryClass::Recover);
+    }
+
+    #[test]
+    fn other_conflicts_stay_retryable() {
+        assert_eq!(
+            DeviceSyncError::api(409, "conflict").retry_class(),
+            ApiRetryClass::Retryable
+        );
+        let busy = DeviceSyncError::api_structured(
+            409,
+            "SYNC_CYCLE_IN_PROGRESS",
+            "Cycle running",
+            None,
+            None,
+        );
+        assert_eq!(busy.retry_class(), ApiRetryClass::Retryable);
+        // The codes only mean "recover" on a conflict; a server error carrying one is transient.
+        let flaky = DeviceSyncError::api_structured(
+            503,
+            SYNC_SEGMENT_CHECKSUM_MISMATCH,
+            "Checksum mismatch",
+            None,
+            None,
+        );
+        assert_eq!(flaky.retry_class(), ApiRetryClass::Retryable);
+    }
+
+    #[test]
+    fn request_id_is_kept_and_shown() {
+        let err = DeviceSyncError::api_structured(
+            503,
+            "UNAVAILABLE",
+            "Try again later",
+            None,
+            Some("req-42".to_string()),
+        );
+        assert_eq!(err.request_id(), Some("req-42"));
+        assert_eq!(
+            err.to_string(),
+            "API error (503, requestId=req-42): UNAVAILABLE: Try again later"
+        );
+
+        let err = DeviceSyncError::api(500, "boom").with_request_id(Some("req-43".to_string()));
+        assert_eq!(err.request_id(), Some("req-43"));
+        assert_eq!(
+            DeviceSyncError::api(500, "boom").to_string(),
+            "API error (500): : boom"
+        );
+        assert_eq!(DeviceSyncError::auth("expired").request_id(), None);
+    }
+
+    #[test]
+    fn subscription_required_detected() {
+        let expired = DeviceSyncError::api_structured(
+            403,
+            SYNC_SUBSCRIPTION_REQUIRED,
+            "Subscription expired",
+            None,
+            None,
+        );
+        assert!(expired.is_subscription_required());
+        assert_eq!(expired.retry_class(), ApiRetryClass::Permanent);
+        let payment_required = DeviceSyncError::api(402, "Payment required");
+        assert!(payment_required.is_subscription_required());
+        assert_eq!(payment_required.retry_class(), ApiRetryClass::Permanent);
+
+        // A plain 403 is still an auth problem.
+        let forbidden = DeviceSyncError::api(403, "forbidden");
+        assert!(!forbidden.is_subscription_required());
+        assert_eq!(forbidden.retry_class(), ApiRetryClass::ReauthRequired);
+        assert!(!DeviceSyncError::auth("expired").is_subscription_required());
+    }
+
+    #[test]
+    fn reenroll_required_detected() {
+        let err = DeviceSyncError::api_structured(
+            409,
+            SYNC_DEVICE_REENROLL_REQUIRED,
+            "Device must re-enroll",
+            None,
+            None,
+        );
+        assert!(err.is_reenroll_required());
+        assert!(DeviceSyncError::api(410, "gone").is_reenroll_required());
+        assert!(!DeviceSyncError::api(404, "not found").is_reenroll_required());
+        assert!(!DeviceSyncError::api(500, "boom").is_reenroll_required());
+    }
+
+    #[test]
+    fn long_messages_are_truncated_on_a_char_boundary() {
+        assert_eq!(truncate_error_message("short".to_string(), 16), "short");
+
+        let truncated = truncate_error_message("é".repeat(10), 8);
+        assert!(truncated.len() <= 8);
+        assert_eq!(truncated, "éé…");
     }
 }
@@ -34,19 +34,35 @@
 //! }
 //! ```
 
+mod blob_storage;
 mod client;
 pub mod crypto;
+mod cursor_expiry;
 pub mod engine;
 mod enroll_service;
 mod error;
+mod retry;
+mod snapshot_verify;
 mod time;
 mod types;
 
+pub use blob_storage::{SyncBlobStorage, DEVICE_SYNC_STORAGE_DIR_ENV};
 pub use client::DeviceSyncClient;
+pub use crypto::{compute_checksum, sha256_checksum, verify_checksum};
+pub use cursor_expiry::{forecast_cursor_expiry, CursorExpiryForecast};
 pub use enroll_service::{
-    DeviceEnrollService, EnableSyncResult, EnrollServiceError, SyncIdentity, SyncState,
-    SyncStateResult,
+    DeviceEnrollService, EnableSyncResult, EnrollServiceError, LocalSyncTeardown,
+    RevokeDeviceResult, RotateCredentialResult, SyncIdentity, SyncState, SyncStateResult,
+};
+pub use error::{
+    integrity_kind, truncate_error_message, ApiRetryClass, DeviceSyncError, IntegrityKind, Result,
+    DEFAULT_MAX_ERROR_MESSAGE_LEN, MAX_RETRY_AFTER, SYNC_SUBSCRIPTION_REQUIRED,
+};
+pub use retry::{backoff_with_jitter, backoff_with_jitter_fraction, RetryPolicy};
+pub use snapshot_verify::{
+    fetch_latest_verified_snapshot, fetch_verified_snapshot, is_snapshot_missing,
+    verify_snapshot_checksum, SnapshotFetchError, MAX_SNAPSHOT_FETCH_ATTEMPTS,
+    MAX_SNAPSHOT_MANIFEST_REFRESHES, SNAPSHOT_SCHEMA_VERSION,
 };
-pub use error::{ApiRetryClass, DeviceSyncError, Result};
 pub use time::{normalize_sync_datetime, parse_sync_datetime_to_utc};
 pub use types::*;
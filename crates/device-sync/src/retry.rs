@@ -0,0 +1,195 @@
+//! Bounded exponential backoff for retrying cloud requests.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::{ApiRetryClass, DeviceSyncError};
+
+/// Full-jitter backoff: a random delay between zero and `base` doubled once per failed attempt
+/// (counting from 1), never above `cap`. Spreads out devices that failed at the same moment.
+pub fn backoff_with_jitter(attempt: u32, base: Duration, cap: Duration) -> Duration {
+    backoff_with_jitter_fraction(attempt, base, cap, rand::thread_rng().gen::<f64>())
+}
+
+/// [`backoff_with_jitter`] with the random draw supplied as `fraction`, clamped to `0.0..=1.0`.
+pub fn backoff_with_jitter_fraction(
+    attempt: u32,
+    base: Duration,
+    cap: Duration,
+    fraction: f64,
+) -> Duration {
+    let ceiling = RetryPolicy {
+        base_delay: base,
+        max_delay: cap,
+        multiplier: 2.0,
+        max_attempts: u32::MAX,
+    }
+    .backoff(attempt);
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    ceiling.mul_f64(fraction)
+}
+
+/// Backoff schedule: `base_delay`, then multiplied by `multiplier` after each failed attempt,
+/// never above `max_delay`, for at most `max_attempts` attempts in total.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Growth factor per failed attempt; values below `1.0` are treated as `1.0`.
+    pub multiplier: f64,
+    pub max_attempts: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            max_attempts: 5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay after failed attempt number `attempt` (counting from 1), ignoring `max_attempts`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let steps = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let factor = self.multiplier.max(1.0).powi(steps);
+        Duration::try_from_secs_f64(self.base_delay.as_secs_f64() * factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// Delay before the attempt after `attempt`, or `None` once all attempts are used.
+    pub fn next_delay(&self, attempt: u32) -> Option<Duration> {
+        (attempt < self.max_attempts).then(|| self.backoff(attempt))
+    }
+
+    /// Like [`Self::next_delay`], but errors that retrying cannot fix stop immediately.
+    pub fn next_delay_for(&self, class: ApiRetryClass, attempt: u32) -> Option<Duration> {
+        match class {
+            ApiRetryClass::Retryable => self.next_delay(attempt),
+            ApiRetryClass::Permanent | ApiRetryClass::ReauthRequired | ApiRetryClass::Recover => {
+                None
+            }
+        }
+    }
+
+    /// Classifies `error` and prefers the server's `Retry-After` over the computed backoff.
+    pub fn next_delay_for_error(&self, error: &DeviceSyncError, attempt: u32) -> Option<Duration> {
+        let delay = self.next_delay_for(error.retry_class(), attempt)?;
+        Some(error.retry_after_hint().unwrap_or(delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(2),
+            multiplier: 2.0,
+            max_attempts: 6,
+        }
+    }
+
+    #[test]
+    fn delays_double_up_to_the_cap_until_attempts_run_out() {
+        let delays: Vec<_> = (1..=6)
+            .map(|attempt| policy().next_delay(attempt))
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(250)),
+                Some(Duration::from_millis(500)),
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_secs(2)),
+                Some(Duration::from_secs(2)),
+                None,
+            ]
+        );
+        assert_eq!(policy().backoff(u32::MAX), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn multiplier_sets_the_growth_per_attempt() {
+        let tripling = RetryPolicy {
+            multiplier: 3.0,
+            max_delay: Duration::from_secs(60),
+            ..policy()
+        };
+        assert_eq!(tripling.backoff(1), Duration::from_millis(250));
+        assert_eq!(tripling.backoff(2), Duration::from_millis(750));
+        assert_eq!(tripling.backoff(3), Duration::from_millis(2250));
+        assert_eq!(tripling.backoff(20), Duration::from_secs(60));
+
+        let flat = RetryPolicy {
+            multiplier: 0.5,
+            ..policy()
+        };
+        assert_eq!(flat.backoff(4), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn jittered_backoff_stays_between_zero_and_the_capped_exponential() {
+        let base = Duration::from_millis(500);
+        let cap = Duration::from_secs(10);
+
+        assert_eq!(
+            backoff_with_jitter_fraction(3, base, cap, 1.0),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            backoff_with_jitter_fraction(3, base, cap, 0.25),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            backoff_with_jitter_fraction(3, base, cap, 0.0),
+            Duration::ZERO
+        );
+        assert_eq!(backoff_with_jitter_fraction(40, base, cap, 1.0), cap);
+        assert_eq!(backoff_with_jitter_fraction(1, base, cap, 7.0), base);
+        assert_eq!(
+            backoff_with_jitter_fraction(1, base, cap, f64::NAN),
+            Duration::ZERO
+        );
+        for attempt in 1..=8 {
+            assert!(backoff_with_jitter(attempt, base, cap) <= cap);
+        }
+    }
+
+    #[test]
+    fn only_retryable_errors_get_a_delay() {
+        let policy = policy();
+        assert_eq!(
+            policy.next_delay_for(ApiRetryClass::Retryable, 2),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(policy.next_delay_for(ApiRetryClass::Permanent, 1), None);
+        assert_eq!(policy.next_delay_for(ApiRetryClass::Recover, 1), None);
+        assert_eq!(
+            policy.next_delay_for(ApiRetryClass::ReauthRequired, 1),
+            None
+        );
+
+        let throttled =
+            DeviceSyncError::api(429, "slow down").with_retry_after(Some(Duration::from_secs(30)));
+        assert_eq!(
+            policy.next_delay_for_error(&throttled, 1),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(policy.next_delay_for_error(&throttled, 6), None);
+        assert_eq!(
+            policy.next_delay_for_error(&DeviceSyncError::api(401, "expired"), 1),
+            None
+        );
+    }
+}
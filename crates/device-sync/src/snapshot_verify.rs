@@ -0,0 +1,372 @@
+//! Snapshot integrity verification for bootstrap.
+//!
+//! A downloaded snapshot is checked against both its download header and the checksum
+//! advertised by the latest-snapshot manifest before the caller decodes or restores it.
+//! Corrupt downloads are re-fetched a bounded number of times and never reach local storage.
+//!
+//! A snapshot the manifest still advertises may already have been pruned server-side
+//! (`SYNC_SNAPSHOT_OBJECT_MISSING`). The bootstrap then asks the manifest for the latest
+//! snapshot again and retries with that one instead of requesting the missing id forever.
+
+use std::future::Future;
+
+use thiserror::Error;
+
+use crate::crypto::{sha256_checksum, verify_checksum};
+use crate::error::{
+    DeviceSyncError, SYNC_SNAPSHOT_CHECKSUM_MISMATCH, SYNC_SNAPSHOT_OBJECT_MISSING,
+};
+use crate::types::{SnapshotDownloadHeaders, SnapshotLatestResponse};
+
+/// Snapshot schema this build writes and can restore. Newer snapshots are refused.
+pub const SNAPSHOT_SCHEMA_VERSION: i32 = 1;
+
+/// Downloads attempted before a corrupt snapshot aborts the bootstrap.
+pub const MAX_SNAPSHOT_FETCH_ATTEMPTS: u32 = 3;
+
+/// Times a missing snapshot is replaced by the manifest's latest before the bootstrap gives up.
+pub const MAX_SNAPSHOT_MANIFEST_REFRESHES: u32 = 2;
+
+#[derive(Debug, Error)]
+pub enum SnapshotFetchError {
+    /// The download itself failed; not retried here.
+    #[error(transparent)]
+    Download(#[from] DeviceSyncError),
+
+    /// Every attempt returned a payload that did not match the expected checksum.
+    #[error("Snapshot checksum mismatch after {attempts} attempt(s): {message}")]
+    ChecksumMismatch { attempts: u32, message: String },
+
+    /// The snapshot is gone and the manifest keeps pointing at it (or at other missing ones).
+    #[error("Snapshot {snapshot_id} is missing and the manifest offers no replacement")]
+    ObjectMissing { snapshot_id: String },
+
+    /// The snapshot is gone and no other snapshot exists; only an events-only bootstrap is left.
+    #[error("Snapshot {snapshot_id} is missing and no other snapshot exists")]
+    NoSnapshotAvailable { snapshot_id: String },
+}
+
+impl SnapshotFetchError {
+    /// Machine-readable error code, if present.
+    pub fn error_code(&self) -> Option<&str> {
+        match self {
+            Self::Download(err) => err.error_code(),
+            Self::ChecksumMismatch { .. } => Some(SYNC_SNAPSHOT_CHECKSUM_MISMATCH),
+            Self::ObjectMissing { .. } | Self::NoSnapshotAvailable { .. } => {
+                Some(SYNC_SNAPSHOT_OBJECT_MISSING)
+            }
+        }
+    }
+}
+
+/// Whether a download failed because the snapshot no longer exists server-side.
+pub fn is_snapshot_missing(err: &DeviceSyncError) -> bool {
+    err.error_code() == Some(SYNC_SNAPSHOT_OBJECT_MISSING) || err.status_code() == Some(404)
+}
+
+/// Checks a snapshot payload against its download header and, when known, the manifest
+/// checksum. Returns a description of the first mismatch.
+pub fn verify_snapshot_checksum(
+    headers: &SnapshotDownloadHeaders,
+    manifest_checksum: Option<&str>,
+    payload: &[u8],
+) -> Result<(), String> {
+    if !verify_checksum(payload, &headers.checksum) {
+        return Err(format!(
+            "download header expected={}, got={}",
+            headers.checksum,
+            sha256_checksum(payload)
+        ));
+    }
+    if let Some(expected) = manifest_checksum {
+        if !verify_checksum(payload, expected) {
+            return Err(format!(
+                "latest metadata expected={}, got={}",
+                expected,
+                sha256_checksum(payload)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Runs `fetch` until it returns a payload that passes [`verify_snapshot_checksum`], up to
+/// `max_attempts` times.
+pub async fn fetch_verified_snapshot<F, Fut>(
+    manifest_checksum: Option<&str>,
+    max_attempts: u32,
+    mut fetch: F,
+) -> Result<(SnapshotDownloadHeaders, Vec<u8>), SnapshotFetchError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = crate::Result<(SnapshotDownloadHeaders, Vec<u8>)>>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut last_mismatch = String::new();
+    for attempt in 1..=max_attempts {
+        let (headers, payload) = fetch().await?;
+        match verify_snapshot_checksum(&headers, manifest_checksum, &payload) {
+            Ok(()) => return Ok((headers, payload)),
+            Err(mismatch) => {
+                log::warn!(
+                    "[DeviceSync] Snapshot checksum mismatch on attempt {}/{}: {}",
+                    attempt,
+                    max_attempts,
+                    mismatch
+                );
+                last_mismatch = mismatch;
+            }
+        }
+    }
+    Err(SnapshotFetchError::ChecksumMismatch {
+        attempts: max_attempts,
+        message: last_mismatch,
+    })
+}
+
+/// Downloads and verifies the snapshot described by `manifest`. When it turns out to be missing,
+/// `latest_manifest` is asked for the current latest snapshot and the download is retried with
+/// it, up to `max_refreshes` times. Returns the manifest of the snapshot actually downloaded.
+pub async fn fetch_latest_verified_snapshot<M, MFut, D, DFut>(
+    manifest: SnapshotLatestResponse,
+    max_attempts: u32,
+    max_refreshes: u32,
+    mut latest_manifest: M,
+    mut download: D,
+) -> Result<(SnapshotLatestResponse, SnapshotDownloadHeaders, Vec<u8>), SnapshotFetchError>
+where
+    M: FnMut() -> MFut,
+    MFut: Future<Output = crate::Result<Option<SnapshotLatestResponse>>>,
+    D: FnMut(String) -> DFut,
+    DFut: Future<Output = crate::Result<(SnapshotDownloadHeaders, Vec<u8>)>>,
+{
+    let mut manifest = manifest;
+    let mut tried: Vec<String> = Vec::new();
+    loop {
+        let snapshot_id = manifest.snapshot_id.trim().to_string();
+        let checksum = Some(manifest.checksum.trim())
+            .filter(|checksum| !checksum.is_empty())
+            .map(str::to_string);
+        match fetch_verified_snapshot(checksum.as_deref(), max_attempts, || {
+            download(snapshot_id.clone())
+        })
+        .await
+        {
+            Ok((headers, payload)) => return Ok((manifest, headers, payload)),
+            Err(SnapshotFetchError::Download(err)) if is_snapshot_missing(&err) => {}
+            Err(err) => return Err(err),
+        }
+
+        tried.push(snapshot_id.clone());
+        if tried.len() as u32 > max_refreshes {
+            return Err(SnapshotFetchError::ObjectMissing { snapshot_id });
+        }
+        let latest = match latest_manifest().await {
+            Ok(latest) => latest,
+            Err(err) if is_snapshot_missing(&err) => None,
+            Err(err) => return Err(err.into()),
+        };
+        match latest.filter(|latest| !latest.snapshot_id.trim().is_empty()) {
+            None => return Err(SnapshotFetchError::NoSnapshotAvailable { snapshot_id }),
+            Some(latest) if tried.iter().any(|id| id == latest.snapshot_id.trim()) => {
+                return Err(SnapshotFetchError::ObjectMissing { snapshot_id });
+            }
+            Some(latest) => {
+                log::warn!(
+                    "[DeviceSync] Snapshot {} is missing; retrying bootstrap with latest snapshot {}",
+                    snapshot_id,
+                    latest.snapshot_id
+                );
+                manifest = latest;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const GOOD: &[u8] = b"snapshot-bytes";
+
+    fn download(payload: &[u8]) -> (SnapshotDownloadHeaders, Vec<u8>) {
+        (
+            SnapshotDownloadHeaders {
+                schema_version: 1,
+                covers_tables: vec![],
+                checksum: sha256_checksum(GOOD),
+            },
+            payload.to_vec(),
+        )
+    }
+
+    #[tokio::test]
+    async fn corrupt_snapshot_is_rejected_after_bounded_refetch() {
+        let fetches = AtomicU32::new(0);
+        let manifest = sha256_checksum(GOOD);
+
+        let result = fetch_verified_snapshot(Some(&manifest), MAX_SNAPSHOT_FETCH_ATTEMPTS, || {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            async { Ok(download(b"corrupted")) }
+        })
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err,
+            SnapshotFetchError::ChecksumMismatch { attempts, .. } if attempts == MAX_SNAPSHOT_FETCH_ATTEMPTS
+        ));
+        assert_eq!(err.error_code(), Some(SYNC_SNAPSHOT_CHECKSUM_MISMATCH));
+        assert_eq!(fetches.load(Ordering::SeqCst), MAX_SNAPSHOT_FETCH_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn refetch_recovers_from_a_transient_corrupt_download() {
+        let fetches = AtomicU32::new(0);
+
+        let (_, payload) = fetch_verified_snapshot(None, MAX_SNAPSHOT_FETCH_ATTEMPTS, || {
+            let attempt = fetches.fetch_add(1, Ordering::SeqCst);
+            async move {
+                Ok(if attempt == 0 {
+                    download(b"corrupted")
+                } else {
+                    download(GOOD)
+                })
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(payload, GOOD);
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn manifest_mismatch_is_rejected_even_when_header_matches() {
+        let result =
+            fetch_verified_snapshot(Some("sha256:00"), 1, || async { Ok(download(GOOD)) }).await;
+
+        assert!(matches!(
+            result,
+            Err(SnapshotFetchError::ChecksumMismatch { attempts: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn checksum_case_does_not_matter() {
+        let (mut headers, payload) = download(GOOD);
+        headers.checksum = headers
+            .checksum
+            .to_ascii_uppercase()
+            .replace("SHA256:", "sha256:");
+        let manifest = sha256_checksum(GOOD)
+            .to_ascii_uppercase()
+            .replace("SHA256:", "sha256:");
+
+        assert!(verify_snapshot_checksum(&headers, Some(&manifest), &payload).is_ok());
+    }
+
+    #[tokio::test]
+    async fn download_errors_are_not_retried() {
+        let fetches = AtomicU32::new(0);
+
+        let result = fetch_verified_snapshot(None, MAX_SNAPSHOT_FETCH_ATTEMPTS, || {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            async { Err(DeviceSyncError::api(404, "not found")) }
+        })
+        .await;
+
+        assert!(matches!(result, Err(SnapshotFetchError::Download(_))));
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    fn manifest(snapshot_id: &str) -> SnapshotLatestResponse {
+        SnapshotLatestResponse {
+            snapshot_id: snapshot_id.to_string(),
+            schema_version: 1,
+            covers_tables: vec![],
+            oplog_seq: 7,
+            size_bytes: GOOD.len() as i64,
+            checksum: sha256_checksum(GOOD),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn missing() -> DeviceSyncError {
+        DeviceSyncError::api_structured(
+            404,
+            SYNC_SNAPSHOT_OBJECT_MISSING,
+            "Snapshot object missing",
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn missing_snapshot_is_replaced_by_the_latest_from_the_manifest() {
+        let downloads = std::sync::Mutex::new(Vec::new());
+
+        let (used, _, payload) = fetch_latest_verified_snapshot(
+            manifest("pruned"),
+            MAX_SNAPSHOT_FETCH_ATTEMPTS,
+            MAX_SNAPSHOT_MANIFEST_REFRESHES,
+            || async { Ok(Some(manifest("latest"))) },
+            |snapshot_id| {
+                downloads.lock().unwrap().push(snapshot_id.clone());
+                async move {
+                    if snapshot_id == "pruned" {
+                        Err(missing())
+                    } else {
+                        Ok(download(GOOD))
+                    }
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(used.snapshot_id, "latest");
+        assert_eq!(payload, GOOD);
+        assert_eq!(*downloads.lock().unwrap(), vec!["pruned", "latest"]);
+    }
+
+    #[tokio::test]
+    async fn missing_snapshot_without_replacement_stops_instead_of_looping() {
+        let downloads = AtomicU32::new(0);
+
+        // The manifest still advertises the pruned snapshot.
+        let result = fetch_latest_verified_snapshot(
+            manifest("pruned"),
+            MAX_SNAPSHOT_FETCH_ATTEMPTS,
+            MAX_SNAPSHOT_MANIFEST_REFRESHES,
+            || async { Ok(Some(manifest("pruned"))) },
+            |_| {
+                downloads.fetch_add(1, Ordering::SeqCst);
+                async { Err(missing()) }
+            },
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(SnapshotFetchError::ObjectMissing { ref snapshot_id }) if snapshot_id == "pruned"
+        ));
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+
+        // No snapshot exists at all.
+        let result = fetch_latest_verified_snapshot(
+            manifest("pruned"),
+            MAX_SNAPSHOT_FETCH_ATTEMPTS,
+            MAX_SNAPSHOT_MANIFEST_REFRESHES,
+            || async { Err(DeviceSyncError::api(404, "no snapshot")) },
+            |_| async { Err(missing()) },
+        )
+        .await;
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err,
+            SnapshotFetchError::NoSnapshotAvailable { .. }
+        ));
+        assert_eq!(err.error_code(), Some(SYNC_SNAPSHOT_OBJECT_MISSING));
+    }
+}
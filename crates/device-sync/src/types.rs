@@ -182,6 +182,9 @@ pub struct Device {
     /// Last time this device was seen
     #[serde(alias = "last_seen_at")]
     pub last_seen_at: Option<String>,
+    /// Last event cursor the device pulled to, when the server reports it
+    #[serde(default)]
+    pub cursor: Option<i64>,
     /// When the device was registered
     #[serde(alias = "created_at")]
     pub created_at: String,
@@ -313,6 +316,24 @@ pub struct CommitRotateKeysResponse {
     pub key_version: i32,
 }
 
+/// Request to rotate a device's credential in place.
+/// Note: Uses snake_case for cloud API serialization.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotateDeviceCredentialRequest {
+    /// Base64 encoded replacement public key
+    pub device_public_key: String,
+    /// Base64 encoded Ed25519 (XEdDSA) signature over `{device_id}:{device_public_key}`,
+    /// made with the current device key and verifiable with its registered public key
+    pub signature: String,
+}
+
+/// Response from rotating a device credential.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateDeviceCredentialResponse {
+    pub success: bool,
+}
+
 /// Response from resetting team sync.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -752,6 +773,26 @@ mod tests {
         assert_eq!(sync_entity_from_remote(&response.events[0].entity), None);
     }
 
+    #[test]
+    fn device_reports_last_seen_and_cursor_when_the_server_sends_them() {
+        let devices: Vec<Device> = serde_json::from_str(
+            r#"[{"id":"device-1","userId":"user-1","displayName":"Laptop","platform":"mac","trustState":"trusted","lastSeenAt":"2026-10-01T08:00:00Z","cursor":42,"createdAt":"2026-01-01T00:00:00Z"},{"id":"device-2","user_id":"user-1","display_name":"Phone","platform":"ios","trust_state":"untrusted","created_at":"2026-02-01T00:00:00Z"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            devices[0].last_seen_at.as_deref(),
+            Some("2026-10-01T08:00:00Z")
+        );
+        assert_eq!(devices[0].cursor, Some(42));
+        assert_eq!(devices[1].last_seen_at, None);
+        assert_eq!(devices[1].cursor, None);
+        assert_eq!(
+            serde_json::to_value(&devices[0]).unwrap()["cursor"],
+            serde_json::json!(42)
+        );
+    }
+
     #[test]
     fn remote_entity_conversion_accepts_known_entities() {
         assert_eq!(
@@ -1362,6 +1362,16 @@ mod tests {
             unimplemented!()
         }
 
+        async fn bulk_upsert_removing_stale(
+            &self,
+            _account_id: &str,
+            _activities: Vec<wealthfolio_core::activities::ActivityUpsert>,
+            _select_stale: wealthfolio_core::activities::StaleActivitySelector,
+        ) -> wealthfolio_core::Result<wealthfolio_core::activities::ActivityReconcileResult>
+        {
+            unimplemented!()
+        }
+
         async fn reassign_asset(&self, _: &str, _: &str) -> wealthfolio_core::Result<u32> {
             unimplemented!()
         }
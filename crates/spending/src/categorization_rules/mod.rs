@@ -10,7 +10,8 @@ pub mod traits;
 
 pub use matcher::{compile_regex_pattern, match_rules, RuleMatch, MAX_REGEX_PATTERN_LEN};
 pub use model::{
-    CategorizationRule, NewCategorizationRule, RuleMatchType, UpdateCategorizationRule,
+    CategorizationRule, NewCategorizationRule, RuleMatchType, RulePreview, RulePreviewChange,
+    UpdateCategorizationRule,
 };
 pub use presets::{ImportPresetResult, RemovePresetResult, RulePreset, RulePresetSummary};
 pub use service::CategorizationRulesService;
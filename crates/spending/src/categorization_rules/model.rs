@@ -104,6 +104,28 @@ pub struct UpdateCategorizationRule {
     pub account_id: Option<Option<String>>,
 }
 
+/// One category assignment a rule re-run would write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulePreviewChange {
+    pub activity_id: String,
+    pub rule_id: String,
+    pub rule_name: String,
+    pub taxonomy_id: String,
+    pub category_id: String,
+    /// Category the rule would replace; `None` for uncategorized activities.
+    pub previous_category_id: Option<String>,
+}
+
+/// Dry-run result of a rule re-run. Nothing is written.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulePreview {
+    /// Activities matched by a rule, same count `rerun_all` returns.
+    pub matched: usize,
+    pub changes: Vec<RulePreviewChange>,
+}
+
 fn deserialize_optional_string<'de, D>(deserializer: D) -> Result<Option<Option<String>>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -7,7 +7,8 @@ use wealthfolio_core::activities::ActivityRepositoryTrait;
 
 use super::matcher::{compile_regex_pattern, compile_rules, match_compiled, MAX_REGEX_PATTERN_LEN};
 use super::model::{
-    CategorizationRule, NewCategorizationRule, RuleMatchType, UpdateCategorizationRule,
+    CategorizationRule, NewCategorizationRule, RuleMatchType, RulePreview, RulePreviewChange,
+    UpdateCategorizationRule,
 };
 use super::presets::{self, ImportPresetResult, RemovePresetResult, RulePresetSummary};
 use super::traits::CategorizationRulesRepositoryTrait;
@@ -111,6 +112,46 @@ impl CategorizationRulesService {
         // Hold for the entire read-skip-set + write phase so two reruns can't
         // interleave (each computes its own skip-set, then both writes fire).
         let _guard = self.rerun_lock.lock().await;
+        let (matched_count, changes) = self.plan_rerun(account_ids, only_uncategorized).await?;
+        let writes = changes
+            .into_iter()
+            .map(|change| NewActivityTaxonomyAssignment {
+                id: None,
+                activity_id: change.activity_id,
+                taxonomy_id: change.taxonomy_id,
+                category_id: change.category_id,
+                weight: 10_000,
+                source: "rule".to_string(),
+            })
+            .collect();
+
+        self.assignment_service
+            .bulk_apply_rule_assignments(writes, only_uncategorized)
+            .await?;
+        Ok(matched_count)
+    }
+
+    /// Dry run of [`Self::rerun_all`]: the assignments it would write, without
+    /// writing them.
+    pub async fn preview_rerun(
+        &self,
+        account_ids: &[String],
+        only_uncategorized: bool,
+    ) -> Result<RulePreview> {
+        if account_ids.is_empty() {
+            return Ok(RulePreview::default());
+        }
+        let (matched, changes) = self.plan_rerun(account_ids, only_uncategorized).await?;
+        Ok(RulePreview { matched, changes })
+    }
+
+    /// Matches every activity of `account_ids` against the rules and returns the
+    /// match count plus the assignments a rerun writes.
+    async fn plan_rerun(
+        &self,
+        account_ids: &[String],
+        only_uncategorized: bool,
+    ) -> Result<(usize, Vec<RulePreviewChange>)> {
         let activities = self
             .activity_repo
             .get_activities_by_account_ids(account_ids)
@@ -126,26 +167,25 @@ impl CategorizationRulesService {
         // assignment row's own `source` field.
         //
         // - `manual_keys`: pairs that must never be overwritten (manual wins).
-        // - `any_keys`:    pairs with any existing assignment — used only when
-        //   `only_uncategorized=true` to block rule-overwrites of rule/import/ai
-        //   assignments.
+        // - `current`:     pairs with any existing assignment — used to block
+        //   rule-overwrites of rule/import/ai assignments when
+        //   `only_uncategorized=true`, and reported as the previous category.
         let mut manual_keys: std::collections::HashSet<(String, String)> =
             std::collections::HashSet::new();
-        let mut any_keys: std::collections::HashSet<(String, String)> =
-            std::collections::HashSet::new();
+        let mut current: HashMap<(String, String), String> = HashMap::new();
         for a in &assignments {
             let key = (a.activity_id.clone(), a.taxonomy_id.clone());
             if a.source == "manual" {
                 manual_keys.insert(key.clone());
             }
-            any_keys.insert(key);
+            current.insert(key, a.category_id.clone());
         }
 
         let rules = self.repo.list().await?;
         let compiled = compile_rules(&rules);
 
         let mut matched_count = 0usize;
-        let mut writes: Vec<NewActivityTaxonomyAssignment> = Vec::with_capacity(activities.len());
+        let mut changes: Vec<RulePreviewChange> = Vec::with_capacity(activities.len());
         for a in &activities {
             let notes_raw = a.notes.as_deref().unwrap_or("");
             let notes_upper = notes_raw.to_uppercase();
@@ -166,24 +206,21 @@ impl CategorizationRulesService {
                 if manual_keys.contains(&key) {
                     continue;
                 }
-                if only_uncategorized && any_keys.contains(&key) {
+                let previous_category_id = current.get(&key).cloned();
+                if only_uncategorized && previous_category_id.is_some() {
                     continue;
                 }
-                writes.push(NewActivityTaxonomyAssignment {
-                    id: None,
+                changes.push(RulePreviewChange {
                     activity_id: a.id.clone(),
+                    rule_id: m.rule.id.clone(),
+                    rule_name: m.rule.name.clone(),
                     taxonomy_id: tax_id,
                     category_id: cat_id,
-                    weight: 10_000,
-                    source: "rule".to_string(),
+                    previous_category_id,
                 });
             }
         }
-
-        self.assignment_service
-            .bulk_apply_rule_assignments(writes, only_uncategorized)
-            .await?;
-        Ok(matched_count)
+        Ok((matched_count, changes))
     }
 
     /// List the bundled presets, marking which ones the user already has installed
@@ -820,6 +857,15 @@ mod tests {
         ) -> wealthfolio_core::Result<BulkUpsertResult> {
             unimplemented!()
         }
+        async fn bulk_upsert_removing_stale(
+            &self,
+            _account_id: &str,
+            _activities: Vec<wealthfolio_core::activities::ActivityUpsert>,
+            _select_stale: wealthfolio_core::activities::StaleActivitySelector,
+        ) -> wealthfolio_core::Result<wealthfolio_core::activities::ActivityReconcileResult>
+        {
+            unimplemented!()
+        }
         async fn reassign_asset(&self, _: &str, _: &str) -> wealthfolio_core::Result<u32> {
             unimplemented!()
         }
@@ -927,6 +973,82 @@ mod tests {
         assert!(still_there);
     }
 
+    #[tokio::test]
+    async fn preview_lists_matching_activities_without_writing() {
+        let mut dividend_rule = mk_rule("r-div", "", "income_sources", "src_dividends", 20);
+        dividend_rule.activity_type = Some("DIVIDEND".to_string());
+        dividend_rule.is_global = false;
+        dividend_rule.account_id = Some("acct1".to_string());
+        let rules_repo = Arc::new(MockRulesRepo {
+            rules: Mutex::new(vec![
+                dividend_rule,
+                mk_rule("r-food", "AMAZON", "spending_categories", "cat_food", 10),
+                mk_rule(
+                    "r-shop",
+                    "AMAZON",
+                    "spending_categories",
+                    "cat_shopping",
+                    10,
+                ),
+            ]),
+        });
+        let assignment_repo = Arc::new(MockAssignmentRepo::default());
+        let mut dividend = mk_activity("act-div", "acct1", "Quarterly dividend");
+        dividend.activity_type = "DIVIDEND".to_string();
+        let mut other_account_dividend = mk_activity("act-div2", "acct2", "Quarterly dividend");
+        other_account_dividend.activity_type = "DIVIDEND".to_string();
+        let activity_repo = Arc::new(MockActivityRepo {
+            activities: vec![
+                dividend,
+                other_account_dividend,
+                mk_activity("act-amzn", "acct1", "AMAZON ORDER #123"),
+                mk_activity("act-rent", "acct1", "RENT MARCH"),
+            ],
+        });
+        let assignment_service = Arc::new(ActivityTaxonomyAssignmentService::new(
+            assignment_repo.clone() as Arc<dyn ActivityTaxonomyAssignmentRepositoryTrait>,
+        ));
+        let svc = CategorizationRulesService::new(
+            rules_repo as Arc<dyn CategorizationRulesRepositoryTrait>,
+            activity_repo as Arc<dyn ActivityRepositoryTrait>,
+            assignment_service,
+        );
+
+        let preview = svc
+            .preview_rerun(&["acct1".to_string(), "acct2".to_string()], true)
+            .await
+            .unwrap();
+
+        assert_eq!(preview.matched, 2);
+        let targets: Vec<(&str, &str, &str)> = preview
+            .changes
+            .iter()
+            .map(|c| {
+                (
+                    c.activity_id.as_str(),
+                    c.rule_id.as_str(),
+                    c.category_id.as_str(),
+                )
+            })
+            .collect();
+        // Equal priorities: the rule listed first wins.
+        assert_eq!(
+            targets,
+            vec![
+                ("act-div", "r-div", "src_dividends"),
+                ("act-amzn", "r-food", "cat_food"),
+            ]
+        );
+        assert!(assignment_repo.writes.lock().unwrap().is_empty());
+
+        let matched = svc
+            .rerun_all(&["acct1".to_string(), "acct2".to_string()], true)
+            .await
+            .unwrap();
+        assert_eq!(matched, preview.matched);
+        assert_eq!(assignment_repo.writes.lock().unwrap().len(), 2);
+    }
+
     #[tokio::test]
     async fn rerun_preserves_manual_same_taxonomy() {
         let rule = mk_rule("r1", "AMAZON", "spending_categories", "cat_food", 10);
@@ -0,0 +1 @@
+ALTER TABLE sync_outbox DROP COLUMN last_error_details;
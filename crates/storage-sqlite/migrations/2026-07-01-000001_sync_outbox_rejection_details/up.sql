@@ -0,0 +1 @@
+ALTER TABLE sync_outbox ADD COLUMN last_error_details TEXT;
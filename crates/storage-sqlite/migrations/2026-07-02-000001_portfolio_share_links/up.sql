@@ -0,0 +1,12 @@
+-- Read-only portfolio snapshot links (server mode).
+-- Local-only table: not part of device sync. Only token hashes are stored.
+CREATE TABLE portfolio_share_links (
+    id TEXT PRIMARY KEY NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    snapshot_json TEXT NOT NULL,
+    include_account_details INTEGER NOT NULL DEFAULT 0,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX idx_portfolio_share_links_expires ON portfolio_share_links (expires_at);
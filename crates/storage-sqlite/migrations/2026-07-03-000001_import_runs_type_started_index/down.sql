@@ -0,0 +1 @@
+DROP INDEX ix_import_runs_run_type_started_at;
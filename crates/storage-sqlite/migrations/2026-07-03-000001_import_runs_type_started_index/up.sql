@@ -0,0 +1,2 @@
+-- Sync history reads filter by run type and a started_at window.
+CREATE INDEX ix_import_runs_run_type_started_at ON import_runs(run_type, started_at);
@@ -5,14 +5,19 @@ use diesel::sqlite::SqliteConnection;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::activities::ActivityDB;
 use crate::db::{get_connection, WriteHandle};
 use crate::errors::StorageError;
+use crate::portfolio::snapshot::AccountStateSnapshotDB;
 use crate::schema::accounts;
 use crate::schema::accounts::dsl::*;
+use crate::schema::{activities, daily_account_valuation, holdings_snapshots, import_runs};
+use crate::sync::import_run::ImportRunDB;
 
 use super::model::AccountDB;
 use wealthfolio_core::accounts::{
-    Account, AccountAccountingSettings, AccountRepositoryTrait, AccountUpdate, NewAccount,
+    Account, AccountAccountingSettings, AccountMergePlan, AccountMergeRepositoryTrait,
+    AccountRepositoryTrait, AccountUpdate, NewAccount,
 };
 use wealthfolio_core::errors::Result;
 
@@ -210,6 +215,144 @@ impl AccountRepositoryTrait for AccountRepository {
     }
 }
 
+#[async_trait]
+impl AccountMergeRepositoryTrait for AccountRepository {
+    async fn apply_account_merge(&self, plan: &AccountMergePlan) -> Result<()> {
+        let plan = plan.clone();
+        self.writer
+            .exec_tx(move |tx| {
+                let source_id = plan.source_account_id.as_str();
+                let target_id = plan.target_account_id.as_str();
+                let now = chrono::Utc::now().to_rfc3339();
+
+                // Duplicates go first so their idempotency keys are free for the moved rows.
+                let duplicates = activities::table
+                    .filter(activities::account_id.eq(source_id))
+                    .filter(activities::id.eq_any(&plan.duplicate_activity_ids))
+                    .select(ActivityDB::as_select())
+                    .load::<ActivityDB>(tx.conn())
+                    .map_err(StorageError::from)?;
+                diesel::delete(
+                    activities::table
+                        .filter(activities::account_id.eq(source_id))
+                        .filter(activities::id.eq_any(&plan.duplicate_activity_ids)),
+                )
+                .execute(tx.conn())
+                .map_err(StorageError::from)?;
+                for duplicate in &duplicates {
+                    tx.delete_model(duplicate);
+                }
+
+                for activity_move in &plan.moves {
+                    diesel::update(
+                        activities::table
+                            .filter(activities::account_id.eq(source_id))
+                            .filter(activities::id.eq(&activity_move.activity_id)),
+                    )
+                    .set((
+                        activities::account_id.eq(target_id),
+                        activities::idempotency_key.eq(&activity_move.idempotency_key),
+                        activities::updated_at.eq(&now),
+                    ))
+                    .execute(tx.conn())
+                    .map_err(StorageError::from)?;
+                }
+                let moved_ids: Vec<&str> =
+                    plan.moves.iter().map(|m| m.activity_id.as_str()).collect();
+                let moved_rows = activities::table
+                    .filter(activities::id.eq_any(&moved_ids))
+                    .select(ActivityDB::as_select())
+                    .load::<ActivityDB>(tx.conn())
+                    .map_err(StorageError::from)?;
+                for moved_row in &moved_rows {
+                    tx.update(moved_row)?;
+                }
+
+                // Anything not part of the plan (e.g. written since the preview) would be
+                // cascade-deleted with the account; refuse instead.
+                let leftover: i64 = activities::table
+                    .filter(activities::account_id.eq(source_id))
+                    .count()
+                    .get_result(tx.conn())
+                    .map_err(StorageError::from)?;
+                if leftover > 0 {
+                    return Err(wealthfolio_core::Error::Validation(
+                        wealthfolio_core::errors::ValidationError::InvalidInput(format!(
+                            "Account {} has {} activities not covered by the merge; retry it",
+                            source_id, leftover
+                        )),
+                    ));
+                }
+
+                let moved_runs = import_runs::table
+                    .filter(import_runs::account_id.eq(source_id))
+                    .select(ImportRunDB::as_select())
+                    .load::<ImportRunDB>(tx.conn())
+                    .map_err(StorageError::from)?;
+                diesel::update(import_runs::table.filter(import_runs::account_id.eq(source_id)))
+                    .set(import_runs::account_id.eq(target_id))
+                    .execute(tx.conn())
+                    .map_err(StorageError::from)?;
+                for mut run in moved_runs {
+                    run.account_id = target_id.to_string();
+                    tx.update(&run)?;
+                }
+
+                // Manually entered or imported snapshots carry data of their own and move over
+                // unless the target already has one for that date. Calculated snapshots and
+                // valuations are derived and get rebuilt for the target.
+                let target_dates: Vec<String> = holdings_snapshots::table
+                    .filter(holdings_snapshots::account_id.eq(target_id))
+                    .select(holdings_snapshots::snapshot_date)
+                    .load::<String>(tx.conn())
+                    .map_err(StorageError::from)?;
+                let source_snapshots = holdings_snapshots::table
+                    .filter(holdings_snapshots::account_id.eq(source_id))
+                    .load::<AccountStateSnapshotDB>(tx.conn())
+                    .map_err(StorageError::from)?;
+                let (kept, dropped): (Vec<_>, Vec<_>) =
+                    source_snapshots.into_iter().partition(|snapshot| {
+                        snapshot.source != "CALCULATED"
+                            && !target_dates.contains(&snapshot.snapshot_date)
+                    });
+                let kept_ids: Vec<&str> = kept.iter().map(|s| s.id.as_str()).collect();
+                diesel::update(
+                    holdings_snapshots::table.filter(holdings_snapshots::id.eq_any(&kept_ids)),
+                )
+                .set(holdings_snapshots::account_id.eq(target_id))
+                .execute(tx.conn())
+                .map_err(StorageError::from)?;
+                for mut snapshot in kept {
+                    snapshot.account_id = target_id.to_string();
+                    tx.update(&snapshot)?;
+                }
+                diesel::delete(
+                    holdings_snapshots::table.filter(holdings_snapshots::account_id.eq(source_id)),
+                )
+                .execute(tx.conn())
+                .map_err(StorageError::from)?;
+                for snapshot in &dropped {
+                    tx.delete_model(snapshot);
+                }
+                diesel::delete(
+                    daily_account_valuation::table
+                        .filter(daily_account_valuation::account_id.eq(source_id)),
+                )
+                .execute(tx.conn())
+                .map_err(StorageError::from)?;
+
+                let deleted = diesel::delete(accounts.find(source_id))
+                    .execute(tx.conn())
+                    .map_err(StorageError::from)?;
+                if deleted > 0 {
+                    tx.delete::<AccountDB>(source_id.to_string());
+                }
+                Ok(())
+            })
+            .await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,7 +360,8 @@ mod tests {
     use crate::schema::accounts::dsl as accounts_dsl;
     use tempfile::tempdir;
     use wealthfolio_core::accounts::{
-        CostBasisMethod, CostBasisProfile, LotSelectionStrategy, PoolingScope, TrackingMode,
+        AccountMergeMove, CostBasisMethod, CostBasisProfile, LotSelectionStrategy, PoolingScope,
+        TrackingMode,
     };
 
     async fn setup() -> (
@@ -340,4 +484,106 @@ mod tests {
         );
         assert_eq!(setting.settings_json, "{\"source\":\"test\"}");
     }
+
+    fn insert_deposit(
+        pool: &Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
+        activity_id: &str,
+        account: &str,
+        date: &str,
+    ) {
+        let mut conn = get_connection(pool).unwrap();
+        diesel::sql_query(format!(
+            "INSERT INTO activities (id, account_id, activity_type, status, activity_date, \
+             amount, currency, idempotency_key, is_user_modified, needs_review, created_at, \
+             updated_at) \
+             VALUES ('{0}', '{1}', 'DEPOSIT', 'POSTED', '{2}T00:00:00Z', '100', 'USD', \
+             'key-{0}', 0, 0, '{2}T00:00:00Z', '{2}T00:00:00Z')",
+            activity_id, account, date
+        ))
+        .execute(&mut conn)
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn merge_moves_activities_drops_duplicates_and_removes_source() {
+        let (repo, pool, _dir) = setup().await;
+        insert_account_without_settings(&pool, "acc-old");
+        insert_account_without_settings(&pool, "acc-new");
+        insert_deposit(&pool, "old-1", "acc-old", "2024-01-02");
+        insert_deposit(&pool, "old-2", "acc-old", "2024-02-01");
+        insert_deposit(&pool, "new-1", "acc-new", "2024-01-02");
+        let mut conn = get_connection(&pool).unwrap();
+        diesel::sql_query(
+            "INSERT INTO holdings_snapshots (id, account_id, snapshot_date, currency, positions, \
+             cash_balances, cost_basis, net_contribution, calculated_at, net_contribution_base, \
+             cash_total_account_currency, cash_total_base_currency, source) \
+             VALUES ('acc-old_2024-01-02', 'acc-old', '2024-01-02', 'USD', '{}', '{}', '0', \
+             '0', '2024-01-02T00:00:00Z', '0', '0', '0', 'CALCULATED')",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        repo.apply_account_merge(&AccountMergePlan {
+            source_account_id: "acc-old".to_string(),
+            target_account_id: "acc-new".to_string(),
+            moves: vec![AccountMergeMove {
+                activity_id: "old-2".to_string(),
+                idempotency_key: Some("key-old-2-moved".to_string()),
+            }],
+            duplicate_activity_ids: vec!["old-1".to_string()],
+        })
+        .await
+        .unwrap();
+
+        let remaining: Vec<(String, String, Option<String>)> = activities::table
+            .select((
+                activities::id,
+                activities::account_id,
+                activities::idempotency_key,
+            ))
+            .order(activities::id.asc())
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(
+            remaining,
+            vec![
+                (
+                    "new-1".to_string(),
+                    "acc-new".to_string(),
+                    Some("key-new-1".to_string())
+                ),
+                (
+                    "old-2".to_string(),
+                    "acc-new".to_string(),
+                    Some("key-old-2-moved".to_string())
+                ),
+            ]
+        );
+        assert!(repo.get_by_id("acc-old").is_err());
+        let snapshots: i64 = holdings_snapshots::table
+            .count()
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(snapshots, 0);
+    }
+
+    #[tokio::test]
+    async fn merge_refuses_to_drop_activities_missing_from_the_plan() {
+        let (repo, pool, _dir) = setup().await;
+        insert_account_without_settings(&pool, "acc-old");
+        insert_account_without_settings(&pool, "acc-new");
+        insert_deposit(&pool, "old-1", "acc-old", "2024-01-02");
+
+        let result = repo
+            .apply_account_merge(&AccountMergePlan {
+                source_account_id: "acc-old".to_string(),
+                target_account_id: "acc-new".to_string(),
+                moves: vec![],
+                duplicate_activity_ids: vec![],
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert!(repo.get_by_id("acc-old").is_ok());
+    }
 }
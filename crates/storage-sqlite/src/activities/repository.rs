@@ -16,10 +16,12 @@ use wealthfolio_core::accounts::{account_supports_purpose, AccountPurpose};
 use wealthfolio_core::activities::ActivityError;
 use wealthfolio_core::activities::{
     import_type, is_cash_symbol, Activity, ActivityBulkIdentifierMapping,
-    ActivityBulkMutationResult, ActivityDetails, ActivityRepositoryTrait, ActivitySearchResponse,
+    ActivityBulkMutationResult, ActivityCursor, ActivityDetails, ActivityPage,
+    ActivityReconcileResult, ActivityRepositoryTrait, ActivitySearchResponse,
     ActivitySearchResponseMeta, ActivityUpdate, ActivityUpsert, BulkUpsertResult, ImportMapping,
-    ImportTemplate, IncomeData, NewActivity, Sort, ACTIVITY_TYPE_TRANSFER_IN,
-    ACTIVITY_TYPE_TRANSFER_OUT, INCOME_ACTIVITY_TYPES, TRADING_ACTIVITY_TYPES,
+    ImportTemplate, IncomeData, NewActivity, RecentActivity, Sort, StaleActivitySelector,
+    ACTIVITY_TYPE_TRANSFER_IN, ACTIVITY_TYPE_TRANSFER_OUT, INCOME_ACTIVITY_TYPES,
+    TRADING_ACTIVITY_TYPES,
 };
 use wealthfolio_core::limits::ContributionActivity;
 use wealthfolio_core::{Error, Result};
@@ -29,7 +31,7 @@ use crate::db::{get_connection, WriteHandle};
 use crate::errors::StorageError;
 use crate::schema::{
     accounts, activities, assets, import_account_templates, import_runs, import_templates,
-    spending_activity_splits,
+    platforms, spending_activity_splits,
 };
 use crate::spending::activity_splits::ActivitySplitDB;
 use crate::spending::activity_sync::should_sync_activity_local_id_outbox;
@@ -43,6 +45,14 @@ use async_trait::async_trait;
 use diesel::dsl::{max, min, sql};
 use num_traits::Zero;
 
+/// How a filtered activity query is paged.
+enum ActivityPaging<'a> {
+    /// Page `page` of `sort`, counting every match.
+    Offset { page: i64, sort: Option<Sort> },
+    /// Rows after `after`, newest first by `(activity_date, id)`, without a count.
+    Keyset { after: Option<&'a ActivityCursor> },
+}
+
 /// Repository for managing activity data in the database
 pub struct ActivityRepository {
     pool: Arc<Pool<ConnectionManager<SqliteConnection>>>,
@@ -416,9 +426,47 @@ impl ActivityRepository {
         date_to_utc_exclusive: Option<DateTime<Utc>>,
         instrument_type_filter: Option<Vec<String>>,
     ) -> Result<ActivitySearchResponse> {
+        let (data, total_row_count) = self.query_activities(
+            ActivityPaging::Offset { page, sort },
+            page_size,
+            account_id_filter,
+            activity_type_filter,
+            asset_id_keyword,
+            needs_review_filter,
+            date_from_utc,
+            date_to_utc_exclusive,
+            instrument_type_filter,
+        )?;
+
+        Ok(ActivitySearchResponse {
+            data,
+            meta: ActivitySearchResponseMeta {
+                total_row_count: total_row_count.unwrap_or_default(),
+            },
+        })
+    }
+
+    /// Loads one page of filtered activities; the total count is only computed for offset
+    /// paging.
+    #[allow(clippy::too_many_arguments)]
+    fn query_activities(
+        &self,
+        paging: ActivityPaging<'_>,
+        page_size: i64,
+        account_id_filter: Option<Vec<String>>,
+        activity_type_filter: Option<Vec<String>>,
+        asset_id_keyword: Option<String>,
+        needs_review_filter: Option<bool>,
+        date_from_utc: Option<DateTime<Utc>>,
+        date_to_utc_exclusive: Option<DateTime<Utc>>,
+        instrument_type_filter: Option<Vec<String>>,
+    ) -> Result<(Vec<ActivityDetails>, Option<i64>)> {
         let mut conn = get_connection(&self.pool)?;
 
-        let offset = page * page_size;
+        let (sort, keyset_after) = match &paging {
+            ActivityPaging::Offset { sort, .. } => (sort.as_ref(), None),
+            ActivityPaging::Keyset { after } => (None, Some(*after)),
+        };
 
         let create_base_query = |_conn: &SqliteConnection| {
             let mut query = activities::table
@@ -466,7 +514,18 @@ impl ActivityRepository {
                 query = query.filter(assets::instrument_type.eq_any(instrument_types));
             }
 
-            if let Some(ref sort) = sort {
+            if let Some(after) = keyset_after {
+                if let Some(cursor) = after {
+                    query = query.filter(
+                        activities::activity_date.lt(cursor.date.clone()).or(
+                            activities::activity_date
+                                .eq(cursor.date.clone())
+                                .and(activities::id.lt(cursor.id.clone())),
+                        ),
+                    );
+                }
+                query = query.order((activities::activity_date.desc(), activities::id.desc()));
+            } else if let Some(sort) = sort {
                 match sort.id.as_str() {
                     "date" => {
                         if sort.desc {
@@ -529,10 +588,17 @@ impl ActivityRepository {
             query
         };
 
-        let total_row_count = create_base_query(&conn)
-            .count()
-            .get_result::<i64>(&mut conn)
-            .map_err(StorageError::from)?;
+        let (limit, offset, total_row_count) = match &paging {
+            ActivityPaging::Offset { page, .. } => {
+                let total_row_count = create_base_query(&conn)
+                    .count()
+                    .get_result::<i64>(&mut conn)
+                    .map_err(StorageError::from)?;
+                (page_size, *page * page_size, Some(total_row_count))
+            }
+            // One extra row tells whether another page follows.
+            ActivityPaging::Keyset { .. } => (page_size.saturating_add(1), 0, None),
+        };
 
         let results_db = create_base_query(&conn)
             .select((
@@ -571,7 +637,7 @@ impl ActivityRepository {
                 assets::instrument_type.nullable(),
                 activities::metadata,
             ))
-            .limit(page_size)
+            .limit(limit)
             .offset(offset)
             .load::<ActivityDetailsDB>(&mut conn)
             .map_err(StorageError::from)?;
@@ -579,11 +645,353 @@ impl ActivityRepository {
         let results: Vec<ActivityDetails> =
             results_db.into_iter().map(ActivityDetails::from).collect();
 
-        Ok(ActivitySearchResponse {
-            data: results,
-            meta: ActivitySearchResponseMeta { total_row_count },
+        Ok((results, total_row_count))
+    }
+}
+
+/// Deletes `delete_ids` inside `tx`, together with the transfer counterparts sharing their
+/// source group. Returns every deleted activity.
+fn delete_activities_tx(
+    tx: &mut crate::db::write_actor::DbWriteTx<'_>,
+    delete_ids: &[String],
+) -> Result<Vec<Activity>> {
+    let mut deleted = Vec::new();
+    let delete_id_set: std::collections::HashSet<&str> =
+        delete_ids.iter().map(|s| s.as_str()).collect();
+    let mut already_deleted: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+    for delete_id in delete_ids {
+        if already_deleted.contains(delete_id) {
+            continue;
+        }
+        let activity_db = activities::table
+            .select(ActivityDB::as_select())
+            .find(delete_id)
+            .first::<ActivityDB>(tx.conn())
+            .map_err(StorageError::from)?;
+        if let Some(ref group_id) = activity_db.source_group_id.clone() {
+            let counterpart_ids: Vec<String> = activities::table
+                .filter(activities::source_group_id.eq(group_id))
+                .filter(activities::id.ne(delete_id))
+                .select(activities::id)
+                .load::<String>(tx.conn())
+                .map_err(StorageError::from)?;
+            for cid in counterpart_ids {
+                if already_deleted.contains(&cid) {
+                    continue;
+                }
+                if delete_id_set.contains(cid.as_str()) {
+                    // Explicitly in delete list — main loop will handle it
+                    continue;
+                }
+                let cp_db = activities::table
+                    .select(ActivityDB::as_select())
+                    .find(&cid)
+                    .first::<ActivityDB>(tx.conn())
+                    .map_err(StorageError::from)?;
+                diesel::delete(activities::table.filter(activities::id.eq(&cid)))
+                    .execute(tx.conn())
+                    .map_err(StorageError::from)?;
+                if should_sync_raw_activity_outbox(&cp_db) {
+                    tx.delete::<ActivityDB>(cid.clone());
+                }
+                deleted.push(Activity::from(cp_db));
+                already_deleted.insert(cid);
+            }
+        }
+        diesel::delete(activities::table.filter(activities::id.eq(delete_id)))
+            .execute(tx.conn())
+            .map_err(StorageError::from)?;
+        if should_sync_raw_activity_outbox(&activity_db) {
+            tx.delete::<ActivityDB>(delete_id.clone());
+        }
+        deleted.push(Activity::from(activity_db));
+        already_deleted.insert(delete_id.clone());
+    }
+    Ok(deleted)
+}
+
+/// Upserts `activity_rows` inside `tx`. See [`ActivityRepositoryTrait::bulk_upsert`].
+fn bulk_upsert_tx(
+    tx: &mut crate::db::write_actor::DbWriteTx<'_>,
+    activity_rows: Vec<ActivityDB>,
+) -> Result<BulkUpsertResult> {
+    use diesel::upsert::excluded;
+
+    // Collect all activity IDs, source identities, and idempotency keys for batch lookup.
+    let activity_ids: Vec<String> = activity_rows.iter().map(|a| a.id.clone()).collect();
+    let source_identities: Vec<(String, String, String)> = activity_rows
+        .iter()
+        .filter_map(|a| {
+            let source_system = a.source_system.as_deref()?.trim();
+            let source_record_id = a.source_record_id.as_deref()?.trim();
+            if source_system.is_empty() || source_record_id.is_empty() {
+                return None;
+            }
+            Some((
+                source_system.to_string(),
+                a.account_id.clone(),
+                source_record_id.to_string(),
+            ))
         })
+        .collect();
+    let idempotency_keys: Vec<String> = activity_rows
+        .iter()
+        .filter_map(|a| a.idempotency_key.clone())
+        .collect();
+
+    // Fetch existing activities by ID or idempotency_key in one query.
+    let existing_activities: Vec<(String, Option<String>, i32)> = activities::table
+        .filter(
+            activities::id
+                .eq_any(&activity_ids)
+                .or(activities::idempotency_key.eq_any(&idempotency_keys)),
+        )
+        .select((
+            activities::id,
+            activities::idempotency_key,
+            activities::is_user_modified,
+        ))
+        .load::<(String, Option<String>, i32)>(tx.conn())
+        .map_err(StorageError::from)?;
+
+    #[allow(clippy::type_complexity)]
+    let existing_source_activities: Vec<(String, String, Option<String>, Option<String>, i32)> =
+        if source_identities.is_empty() {
+            Vec::new()
+        } else {
+            let source_systems: Vec<Option<String>> = source_identities
+                .iter()
+                .map(|(source_system, _, _)| Some(source_system.clone()))
+                .collect();
+            let account_ids: Vec<String> = source_identities
+                .iter()
+                .map(|(_, account_id, _)| account_id.clone())
+                .collect();
+            let source_record_ids: Vec<Option<String>> = source_identities
+                .iter()
+                .map(|(_, _, source_record_id)| Some(source_record_id.clone()))
+                .collect();
+
+            activities::table
+                .filter(activities::account_id.eq_any(&account_ids))
+                .filter(activities::source_system.eq_any(&source_systems))
+                .filter(activities::source_record_id.eq_any(&source_record_ids))
+                .select((
+                    activities::id,
+                    activities::account_id,
+                    activities::source_system,
+                    activities::source_record_id,
+                    activities::is_user_modified,
+                ))
+                .load::<(String, String, Option<String>, Option<String>, i32)>(tx.conn())
+                .map_err(StorageError::from)?
+        };
+
+    // Build lookup maps for quick access.
+    let mut existing_by_id: HashMap<String, i32> = HashMap::new();
+    let mut existing_by_idemp: HashMap<String, (String, i32)> = HashMap::new();
+    let mut existing_by_source: HashMap<(String, String, String), (String, i32)> = HashMap::new();
+
+    for (id, idemp_key, is_modified) in existing_activities {
+        existing_by_id.insert(id.clone(), is_modified);
+        if let Some(key) = idemp_key {
+            existing_by_idemp.insert(key, (id, is_modified));
+        }
+    }
+
+    for (id, account_id, source_system, source_record_id, is_modified) in existing_source_activities
+    {
+        if let (Some(source_system), Some(source_record_id)) = (source_system, source_record_id) {
+            existing_by_source.insert(
+                (source_system, account_id, source_record_id),
+                (id, is_modified),
+            );
+        }
     }
+
+    let mut result = BulkUpsertResult::default();
+
+    for mut activity_db in activity_rows {
+        let now_update = chrono::Utc::now().to_rfc3339();
+        let activity_id = activity_db.id.clone();
+        let idempotency_key = activity_db.idempotency_key.clone();
+
+        let source_identity = activity_db
+            .source_system
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .zip(
+                activity_db
+                    .source_record_id
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty()),
+            )
+            .map(|(source_system, source_record_id)| {
+                (
+                    source_system.to_string(),
+                    activity_db.account_id.clone(),
+                    source_record_id.to_string(),
+                )
+            });
+        let mut will_update = false;
+
+        // Check if this activity exists and is user-modified.
+        // First check by ID.
+        if let Some(&is_modified) = existing_by_id.get(&activity_id) {
+            will_update = true;
+            if is_modified != 0 {
+                log::debug!(
+                    "Skipping user-modified activity {} (type={})",
+                    activity_id,
+                    activity_db.activity_type
+                );
+                result.skipped += 1;
+                continue;
+            }
+        }
+
+        // Match by provider identity before falling back to semantic idempotency.
+        if !will_update {
+            if let Some(ref source_key) = source_identity {
+                if let Some((existing_id, is_modified)) = existing_by_source.get(source_key) {
+                    if *is_modified != 0 {
+                        log::debug!(
+                            "Skipping update for user-modified activity (matched by source identity: {} -> {})",
+                            activity_id,
+                            existing_id
+                        );
+                        result.skipped += 1;
+                        continue;
+                    }
+                    log::debug!(
+                        "Activity {} matched existing {} by source identity, updating existing",
+                        activity_id,
+                        existing_id
+                    );
+                    activity_db.id = existing_id.clone();
+                    will_update = true;
+                }
+            }
+        }
+
+        // If still unmatched, fall back to semantic idempotency.
+        if !will_update {
+            if let Some(ref key) = idempotency_key {
+                if let Some((existing_id, is_modified)) = existing_by_idemp.get(key) {
+                    if *is_modified != 0 {
+                        log::debug!(
+                            "Skipping update for user-modified activity (matched by idempotency_key: {} -> {})",
+                            activity_id,
+                            existing_id
+                        );
+                        result.skipped += 1;
+                        continue;
+                    }
+                    // Found by idempotency_key - update the existing record instead
+                    log::debug!(
+                        "Activity {} matched existing {} by idempotency_key, updating existing",
+                        activity_id,
+                        existing_id
+                    );
+                    activity_db.id = existing_id.clone();
+                    will_update = true;
+                }
+            }
+        }
+
+        match diesel::insert_into(activities::table)
+            .values(&activity_db)
+            .on_conflict(activities::id)
+            .do_update()
+            .set((
+                activities::account_id.eq(excluded(activities::account_id)),
+                activities::asset_id.eq(excluded(activities::asset_id)),
+                activities::activity_type.eq(excluded(activities::activity_type)),
+                activities::subtype.eq(excluded(activities::subtype)),
+                activities::activity_date.eq(excluded(activities::activity_date)),
+                activities::quantity.eq(excluded(activities::quantity)),
+                activities::unit_price.eq(excluded(activities::unit_price)),
+                activities::currency.eq(excluded(activities::currency)),
+                activities::fee.eq(excluded(activities::fee)),
+                activities::tax.eq(excluded(activities::tax)),
+                activities::amount.eq(excluded(activities::amount)),
+                activities::status.eq(excluded(activities::status)),
+                activities::notes.eq(excluded(activities::notes)),
+                activities::fx_rate.eq(excluded(activities::fx_rate)),
+                activities::metadata.eq(excluded(activities::metadata)),
+                activities::source_system.eq(excluded(activities::source_system)),
+                activities::source_record_id.eq(excluded(activities::source_record_id)),
+                activities::source_group_id.eq(excluded(activities::source_group_id)),
+                activities::needs_review.eq(excluded(activities::needs_review)),
+                activities::idempotency_key.eq(excluded(activities::idempotency_key)),
+                activities::import_run_id.eq(excluded(activities::import_run_id)),
+                activities::updated_at.eq(now_update),
+            ))
+            .execute(tx.conn())
+        {
+            Ok(count) => {
+                if count > 0 {
+                    if will_update {
+                        tx.update(&activity_db)?;
+                    } else {
+                        tx.insert(&activity_db)?;
+                    }
+
+                    existing_by_id.insert(activity_db.id.clone(), 0);
+                    if let Some(key) = activity_db.idempotency_key.clone() {
+                        existing_by_idemp.insert(key, (activity_db.id.clone(), 0));
+                    }
+                    if let Some(source_key) = source_identity.clone() {
+                        existing_by_source.insert(source_key, (activity_db.id.clone(), 0));
+                    }
+
+                    result.upserted += count;
+                    if will_update {
+                        result.updated += count;
+                    } else {
+                        result.created += count;
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to upsert activity {} (type={}): {:?}",
+                    activity_db.id,
+                    activity_db.activity_type,
+                    e
+                );
+                return Err(StorageError::from(e).into());
+            }
+        }
+    }
+
+    let pending_patch_count = apply_pending_broker_activity_user_patches_tx(tx.conn())?;
+    if pending_patch_count > 0 {
+        log::debug!(
+            "Applied {} pending broker activity user patches after bulk upsert",
+            pending_patch_count
+        );
+    }
+
+    if result.skipped > 0 {
+        log::info!(
+            "Skipped {} user-modified activities during bulk upsert",
+            result.skipped
+        );
+    }
+
+    log::debug!(
+        "Bulk upsert complete: {} upserted ({} created, {} updated), {} skipped",
+        result.upserted,
+        result.created,
+        result.updated,
+        result.skipped
+    );
+
+    Ok(result)
 }
 
 // Implement the trait for the repository
@@ -719,6 +1127,84 @@ impl ActivityRepositoryTrait for ActivityRepository {
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn search_activities_page(
+        &self,
+        cursor: Option<&ActivityCursor>,
+        limit: i64,
+        account_id_filter: Option<Vec<String>>,
+        activity_type_filter: Option<Vec<String>>,
+        asset_id_keyword: Option<String>,
+        needs_review_filter: Option<bool>,
+        date_from_utc: Option<DateTime<Utc>>,
+        date_to_utc_exclusive: Option<DateTime<Utc>>,
+        instrument_type_filter: Option<Vec<String>>,
+    ) -> Result<ActivityPage> {
+        let (rows, _) = self.query_activities(
+            ActivityPaging::Keyset { after: cursor },
+            limit,
+            account_id_filter,
+            activity_type_filter,
+            asset_id_keyword,
+            needs_review_filter,
+            date_from_utc,
+            date_to_utc_exclusive,
+            instrument_type_filter,
+        )?;
+        Ok(ActivityPage::from_rows(rows, limit))
+    }
+
+    fn get_recent_activities(&self, limit: i64) -> Result<Vec<RecentActivity>> {
+        let activities = self
+            .search_activities_with_utc_bounds(
+                0,
+                limit,
+                None,
+                None,
+                None,
+                Some(Sort {
+                    id: "date".to_string(),
+                    desc: true,
+                }),
+                None,
+                None,
+                None,
+                None,
+            )?
+            .data;
+
+        let account_ids: Vec<&str> = activities
+            .iter()
+            .map(|activity| activity.account_id.as_str())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut conn = get_connection(&self.pool)?;
+        let broker_names: HashMap<String, Option<String>> = accounts::table
+            .left_join(platforms::table)
+            .filter(accounts::id.eq_any(&account_ids))
+            .select((
+                accounts::id,
+                accounts::platform_id,
+                platforms::name.nullable(),
+            ))
+            .load::<(String, Option<String>, Option<String>)>(&mut conn)
+            .map_err(StorageError::from)?
+            .into_iter()
+            .map(|(account_id, platform_id, platform_name)| {
+                (account_id, platform_name.or(platform_id))
+            })
+            .collect();
+
+        Ok(activities
+            .into_iter()
+            .map(|activity| RecentActivity {
+                broker_name: broker_names.get(&activity.account_id).cloned().flatten(),
+                activity,
+            })
+            .collect())
+    }
+
     async fn create_activity(&self, new_activity: NewActivity) -> Result<Activity> {
         new_activity.validate()?;
         let activity_db_owned: ActivityDB = new_activity.into();
@@ -1100,61 +1586,10 @@ impl ActivityRepositoryTrait for ActivityRepository {
     ) -> Result<ActivityBulkMutationResult> {
         self.writer
             .exec_tx(move |tx| -> Result<ActivityBulkMutationResult> {
-                let mut outcome = ActivityBulkMutationResult::default();
-
-                let delete_id_set: std::collections::HashSet<&str> =
-                    delete_ids.iter().map(|s| s.as_str()).collect();
-                let mut already_deleted: std::collections::HashSet<String> =
-                    std::collections::HashSet::new();
-
-                for delete_id in &delete_ids {
-                    if already_deleted.contains(delete_id) {
-                        continue;
-                    }
-                    let activity_db = activities::table
-                        .select(ActivityDB::as_select())
-                        .find(delete_id)
-                        .first::<ActivityDB>(tx.conn())
-                        .map_err(StorageError::from)?;
-                    if let Some(ref group_id) = activity_db.source_group_id.clone() {
-                        let counterpart_ids: Vec<String> = activities::table
-                            .filter(activities::source_group_id.eq(group_id))
-                            .filter(activities::id.ne(delete_id))
-                            .select(activities::id)
-                            .load::<String>(tx.conn())
-                            .map_err(StorageError::from)?;
-                        for cid in counterpart_ids {
-                            if already_deleted.contains(&cid) {
-                                continue;
-                            }
-                            if delete_id_set.contains(cid.as_str()) {
-                                // Explicitly in delete list — main loop will handle it
-                                continue;
-                            }
-                            let cp_db = activities::table
-                                .select(ActivityDB::as_select())
-                                .find(&cid)
-                                .first::<ActivityDB>(tx.conn())
-                                .map_err(StorageError::from)?;
-                            diesel::delete(activities::table.filter(activities::id.eq(&cid)))
-                                .execute(tx.conn())
-                                .map_err(StorageError::from)?;
-                            if should_sync_raw_activity_outbox(&cp_db) {
-                                tx.delete::<ActivityDB>(cid.clone());
-                            }
-                            outcome.deleted.push(Activity::from(cp_db));
-                            already_deleted.insert(cid);
-                        }
-                    }
-                    diesel::delete(activities::table.filter(activities::id.eq(delete_id)))
-                        .execute(tx.conn())
-                        .map_err(StorageError::from)?;
-                    if should_sync_raw_activity_outbox(&activity_db) {
-                        tx.delete::<ActivityDB>(delete_id.clone());
-                    }
-                    outcome.deleted.push(Activity::from(activity_db));
-                    already_deleted.insert(delete_id.clone());
-                }
+                let mut outcome = ActivityBulkMutationResult {
+                    deleted: delete_activities_tx(tx, &delete_ids)?,
+                    ..ActivityBulkMutationResult::default()
+                };
 
                 for update in updates {
                     update.validate()?;
@@ -2041,7 +2476,9 @@ impl ActivityRepositoryTrait for ActivityRepository {
 
         let query = format!(
             "SELECT strftime('%Y-%m', a.activity_date) as date,
+             strftime('%Y-%m-%d', a.activity_date) as activity_date,
              a.activity_type as income_type,
+             a.subtype,
              COALESCE(a.asset_id, 'CASH') as asset_id,
              COALESCE(ast.kind, 'CASH') as asset_kind,
              COALESCE(ast.display_code, 'CASH') as symbol,
@@ -2082,7 +2519,11 @@ impl ActivityRepositoryTrait for ActivityRepository {
             #[diesel(sql_type = diesel::sql_types::Text)]
             pub date: String,
             #[diesel(sql_type = diesel::sql_types::Text)]
+            pub activity_date: String,
+            #[diesel(sql_type = diesel::sql_types::Text)]
             pub income_type: String,
+            #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+            pub subtype: Option<String>,
             #[diesel(sql_type = diesel::sql_types::Text)]
             pub asset_id: String,
             #[diesel(sql_type = diesel::sql_types::Text)]
@@ -2117,7 +2558,9 @@ impl ActivityRepositoryTrait for ActivityRepository {
                 let amount = Decimal::from_str(&raw.amount).unwrap_or_else(|_| Decimal::zero());
                 Some(Ok(IncomeData {
                     date: raw.date,
+                    activity_date: raw.activity_date,
                     income_type: raw.income_type,
+                    subtype: raw.subtype,
                     asset_id: raw.asset_id,
                     asset_kind: raw.asset_kind,
                     symbol: raw.symbol,
@@ -2333,8 +2776,6 @@ impl ActivityRepositoryTrait for ActivityRepository {
     ///
     /// Returns statistics about the operation.
     async fn bulk_upsert(&self, activities_vec: Vec<ActivityUpsert>) -> Result<BulkUpsertResult> {
-        use diesel::upsert::excluded;
-
         if activities_vec.is_empty() {
             return Ok(BulkUpsertResult::default());
         }
@@ -2344,287 +2785,55 @@ impl ActivityRepositoryTrait for ActivityRepository {
             activities_vec.into_iter().map(ActivityDB::from).collect();
 
         self.writer
-            .exec_tx(move |tx| -> Result<BulkUpsertResult> {
-                // Collect all activity IDs, source identities, and idempotency keys for batch lookup.
-                let activity_ids: Vec<String> =
-                    activity_rows.iter().map(|a| a.id.clone()).collect();
-                let source_identities: Vec<(String, String, String)> = activity_rows
-                    .iter()
-                    .filter_map(|a| {
-                        let source_system = a.source_system.as_deref()?.trim();
-                        let source_record_id = a.source_record_id.as_deref()?.trim();
-                        if source_system.is_empty() || source_record_id.is_empty() {
-                            return None;
-                        }
-                        Some((
-                            source_system.to_string(),
-                            a.account_id.clone(),
-                            source_record_id.to_string(),
-                        ))
-                    })
+            .exec_tx(move |tx| bulk_upsert_tx(tx, activity_rows))
+            .await
+    }
+
+    async fn bulk_upsert_removing_stale(
+        &self,
+        account_id: &str,
+        activities_vec: Vec<ActivityUpsert>,
+        select_stale: StaleActivitySelector,
+    ) -> Result<ActivityReconcileResult> {
+        let account_id = account_id.to_string();
+        let activity_rows: Vec<ActivityDB> =
+            activities_vec.into_iter().map(ActivityDB::from).collect();
+
+        self.writer
+            .exec_tx(move |tx| -> Result<ActivityReconcileResult> {
+                let upserted = if activity_rows.is_empty() {
+                    BulkUpsertResult::default()
+                } else {
+                    bulk_upsert_tx(tx, activity_rows)?
+                };
+                let stored: Vec<Activity> = activities::table
+                    .filter(activities::account_id.eq(&account_id))
+                    .select(ActivityDB::as_select())
+                    .load::<ActivityDB>(tx.conn())
+                    .map_err(StorageError::from)?
+                    .into_iter()
+                    .map(Activity::from)
                     .collect();
-                let idempotency_keys: Vec<String> = activity_rows
+                let mut plan = select_stale(&stored);
+                // Only rows of this account are removed, and edited ones are kept whatever the
+                // selector returned.
+                let edited: HashMap<&str, bool> = stored
                     .iter()
-                    .filter_map(|a| a.idempotency_key.clone())
+                    .map(|activity| (activity.id.as_str(), activity.is_user_modified))
                     .collect();
-
-                // Fetch existing activities by ID or idempotency_key in one query.
-                let existing_activities: Vec<(String, Option<String>, i32)> = activities::table
-                    .filter(
-                        activities::id
-                            .eq_any(&activity_ids)
-                            .or(activities::idempotency_key.eq_any(&idempotency_keys)),
-                    )
-                    .select((
-                        activities::id,
-                        activities::idempotency_key,
-                        activities::is_user_modified,
-                    ))
-                    .load::<(String, Option<String>, i32)>(tx.conn())
-                    .map_err(StorageError::from)?;
-
-                let existing_source_activities: Vec<(String, String, Option<String>, Option<String>, i32)> =
-                    if source_identities.is_empty() {
-                        Vec::new()
-                    } else {
-                        let source_systems: Vec<Option<String>> = source_identities
-                            .iter()
-                            .map(|(source_system, _, _)| Some(source_system.clone()))
-                            .collect();
-                        let account_ids: Vec<String> = source_identities
-                            .iter()
-                            .map(|(_, account_id, _)| account_id.clone())
-                            .collect();
-                        let source_record_ids: Vec<Option<String>> = source_identities
-                            .iter()
-                            .map(|(_, _, source_record_id)| Some(source_record_id.clone()))
-                            .collect();
-
-                        activities::table
-                            .filter(activities::account_id.eq_any(&account_ids))
-                            .filter(activities::source_system.eq_any(&source_systems))
-                            .filter(activities::source_record_id.eq_any(&source_record_ids))
-                            .select((
-                                activities::id,
-                                activities::account_id,
-                                activities::source_system,
-                                activities::source_record_id,
-                                activities::is_user_modified,
-                            ))
-                            .load::<(String, String, Option<String>, Option<String>, i32)>(tx.conn())
-                            .map_err(StorageError::from)?
-                    };
-
-                // Build lookup maps for quick access.
-                let mut existing_by_id: HashMap<String, i32> = HashMap::new();
-                let mut existing_by_idemp: HashMap<String, (String, i32)> = HashMap::new();
-                let mut existing_by_source: HashMap<(String, String, String), (String, i32)> =
-                    HashMap::new();
-
-                for (id, idemp_key, is_modified) in existing_activities {
-                    existing_by_id.insert(id.clone(), is_modified);
-                    if let Some(key) = idemp_key {
-                        existing_by_idemp.insert(key, (id, is_modified));
-                    }
-                }
-
-                for (id, account_id, source_system, source_record_id, is_modified) in
-                    existing_source_activities
-                {
-                    if let (Some(source_system), Some(source_record_id)) =
-                        (source_system, source_record_id)
-                    {
-                        existing_by_source.insert(
-                            (source_system, account_id, source_record_id),
-                            (id, is_modified),
-                        );
-                    }
-                }
-
-                let mut result = BulkUpsertResult::default();
-
-                for mut activity_db in activity_rows {
-                    let now_update = chrono::Utc::now().to_rfc3339();
-                    let activity_id = activity_db.id.clone();
-                    let idempotency_key = activity_db.idempotency_key.clone();
-
-                    let source_identity = activity_db
-                        .source_system
-                        .as_deref()
-                        .map(str::trim)
-                        .filter(|value| !value.is_empty())
-                        .zip(
-                            activity_db
-                                .source_record_id
-                                .as_deref()
-                                .map(str::trim)
-                                .filter(|value| !value.is_empty()),
-                        )
-                        .map(|(source_system, source_record_id)| {
-                            (
-                                source_system.to_string(),
-                                activity_db.account_id.clone(),
-                                source_record_id.to_string(),
-                            )
-                        });
-                    let mut will_update = false;
-
-                    // Check if this activity exists and is user-modified.
-                    // First check by ID.
-                    if let Some(&is_modified) = existing_by_id.get(&activity_id) {
-                        will_update = true;
-                        if is_modified != 0 {
-                            log::debug!(
-                                "Skipping user-modified activity {} (type={})",
-                                activity_id,
-                                activity_db.activity_type
-                            );
-                            result.skipped += 1;
-                            continue;
-                        }
-                    }
-
-                    // Match by provider identity before falling back to semantic idempotency.
-                    if !will_update {
-                        if let Some(ref source_key) = source_identity {
-                            if let Some((existing_id, is_modified)) = existing_by_source.get(source_key)
-                            {
-                                if *is_modified != 0 {
-                                    log::debug!(
-                                        "Skipping update for user-modified activity (matched by source identity: {} -> {})",
-                                        activity_id,
-                                        existing_id
-                                    );
-                                    result.skipped += 1;
-                                    continue;
-                                }
-                                log::debug!(
-                                    "Activity {} matched existing {} by source identity, updating existing",
-                                    activity_id,
-                                    existing_id
-                                );
-                                activity_db.id = existing_id.clone();
-                                will_update = true;
-                            }
-                        }
-                    }
-
-                    // If still unmatched, fall back to semantic idempotency.
-                    if !will_update {
-                        if let Some(ref key) = idempotency_key {
-                            if let Some((existing_id, is_modified)) = existing_by_idemp.get(key) {
-                                if *is_modified != 0 {
-                                    log::debug!(
-                                        "Skipping update for user-modified activity (matched by idempotency_key: {} -> {})",
-                                        activity_id,
-                                        existing_id
-                                    );
-                                    result.skipped += 1;
-                                    continue;
-                                }
-                                // Found by idempotency_key - update the existing record instead
-                                log::debug!(
-                                    "Activity {} matched existing {} by idempotency_key, updating existing",
-                                    activity_id,
-                                    existing_id
-                                );
-                                activity_db.id = existing_id.clone();
-                                will_update = true;
-                            }
-                        }
-                    }
-
-                    match diesel::insert_into(activities::table)
-                        .values(&activity_db)
-                        .on_conflict(activities::id)
-                        .do_update()
-                        .set((
-                            activities::account_id.eq(excluded(activities::account_id)),
-                            activities::asset_id.eq(excluded(activities::asset_id)),
-                            activities::activity_type.eq(excluded(activities::activity_type)),
-                            activities::subtype.eq(excluded(activities::subtype)),
-                            activities::activity_date.eq(excluded(activities::activity_date)),
-                            activities::quantity.eq(excluded(activities::quantity)),
-                            activities::unit_price.eq(excluded(activities::unit_price)),
-                            activities::currency.eq(excluded(activities::currency)),
-                            activities::fee.eq(excluded(activities::fee)),
-                            activities::tax.eq(excluded(activities::tax)),
-                            activities::amount.eq(excluded(activities::amount)),
-                            activities::status.eq(excluded(activities::status)),
-                            activities::notes.eq(excluded(activities::notes)),
-                            activities::fx_rate.eq(excluded(activities::fx_rate)),
-                            activities::metadata.eq(excluded(activities::metadata)),
-                            activities::source_system.eq(excluded(activities::source_system)),
-                            activities::source_record_id.eq(excluded(activities::source_record_id)),
-                            activities::source_group_id.eq(excluded(activities::source_group_id)),
-                            activities::needs_review.eq(excluded(activities::needs_review)),
-                            activities::idempotency_key.eq(excluded(activities::idempotency_key)),
-                            activities::import_run_id.eq(excluded(activities::import_run_id)),
-                            activities::updated_at.eq(now_update),
-                        ))
-                        .execute(tx.conn())
-                    {
-                        Ok(count) => {
-                            if count > 0 {
-                                if will_update {
-                                    tx.update(&activity_db)?;
-                                } else {
-                                    tx.insert(&activity_db)?;
-                                }
-
-                                existing_by_id.insert(activity_db.id.clone(), 0);
-                                if let Some(key) = activity_db.idempotency_key.clone() {
-                                    existing_by_idemp.insert(key, (activity_db.id.clone(), 0));
-                                }
-                                if let Some(source_key) = source_identity.clone() {
-                                    existing_by_source.insert(source_key, (activity_db.id.clone(), 0));
-                                }
-
-                                result.upserted += count;
-                                if will_update {
-                                    result.updated += count;
-                                } else {
-                                    result.created += count;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            log::error!(
-                                "Failed to upsert activity {} (type={}): {:?}",
-                                activity_db.id,
-                                activity_db.activity_type,
-                                e
-                            );
-                            return Err(StorageError::from(e).into());
-                        }
-                    }
-                }
-
-                let pending_patch_count =
-                    apply_pending_broker_activity_user_patches_tx(tx.conn())?;
-                if pending_patch_count > 0 {
-                    log::debug!(
-                        "Applied {} pending broker activity user patches after bulk upsert",
-                        pending_patch_count
-                    );
-                }
-
-                if result.skipped > 0 {
-                    log::info!(
-                        "Skipped {} user-modified activities during bulk upsert",
-                        result.skipped
-                    );
-                }
-
-                log::debug!(
-                    "Bulk upsert complete: {} upserted ({} created, {} updated), {} skipped",
-                    result.upserted,
-                    result.created,
-                    result.updated,
-                    result.skipped
-                );
-
-                Ok(result)
+                let (kept, remove_ids): (Vec<String>, Vec<String>) = plan
+                    .remove_ids
+                    .into_iter()
+                    .filter(|id| edited.contains_key(id.as_str()))
+                    .partition(|id| edited[id.as_str()]);
+                plan.preserved_ids.extend(kept);
+                let removed = delete_activities_tx(tx, &remove_ids)?;
+                plan.remove_ids = remove_ids;
+                Ok(ActivityReconcileResult {
+                    upserted,
+                    plan,
+                    removed,
+                })
             })
             .await
     }
@@ -2710,7 +2919,9 @@ mod tests {
     use crate::schema::{spending_activity_splits, sync_outbox};
     use rust_decimal::Decimal;
     use tempfile::tempdir;
-    use wealthfolio_core::activities::{import_type, ActivityStatus, ActivityUpsert};
+    use wealthfolio_core::activities::{
+        import_type, ActivityStatus, ActivityUpsert, StaleActivityPlan,
+    };
 
     fn setup_db() -> (Arc<Pool<ConnectionManager<SqliteConnection>>>, WriteHandle) {
         std::env::set_var("CONNECT_API_URL", "http://test.local");
@@ -3188,6 +3399,144 @@ mod tests {
         assert_eq!(response.data[0].id, "local-evening-transfer");
     }
 
+    fn insert_deposit(conn: &mut SqliteConnection, id: &str, activity_date: &str) {
+        diesel::sql_query(format!(
+            "INSERT INTO activities \
+             (id, account_id, asset_id, activity_type, activity_type_override, source_type, subtype, \
+              status, activity_date, settlement_date, quantity, unit_price, amount, fee, currency, \
+              fx_rate, notes, metadata, source_system, source_record_id, source_group_id, \
+              idempotency_key, import_run_id, is_user_modified, needs_review, created_at, updated_at) \
+             VALUES \
+             ('{id}', 'manual-account', NULL, 'DEPOSIT', NULL, NULL, NULL, \
+              'POSTED', '{activity_date}', NULL, NULL, NULL, '10', NULL, 'USD', \
+              NULL, NULL, NULL, 'MANUAL', NULL, NULL, 'key-{id}', NULL, 0, 0, \
+              '{activity_date}', '{activity_date}')"
+        ))
+        .execute(conn)
+        .expect("insert deposit");
+    }
+
+    #[tokio::test]
+    async fn keyset_pages_are_complete_and_disjoint_across_inserts() {
+        let (pool, writer) = setup_db();
+        let repo = ActivityRepository::new(pool.clone(), writer);
+
+        {
+            let mut conn = get_connection(&pool).expect("conn");
+            insert_account(&mut conn, "manual-account");
+            // Several activities share a date, so pages must break ties on the id.
+            for (id, date) in [
+                ("a1", "2024-01-01T00:00:00+00:00"),
+                ("a2", "2024-01-02T00:00:00+00:00"),
+                ("a3", "2024-01-02T00:00:00+00:00"),
+                ("a4", "2024-01-02T00:00:00+00:00"),
+                ("a5", "2024-01-03T00:00:00+00:00"),
+                ("a6", "2024-01-04T00:00:00+00:00"),
+                ("a7", "2024-01-04T00:00:00+00:00"),
+            ] {
+                insert_deposit(&mut conn, id, date);
+            }
+        }
+
+        let page = |cursor: Option<&ActivityCursor>| {
+            repo.search_activities_page(cursor, 3, None, None, None, None, None, None, None)
+                .expect("keyset page")
+        };
+
+        let mut seen: Vec<String> = Vec::new();
+        let first = page(None);
+        seen.extend(first.data.iter().map(|a| a.id.clone()));
+        assert_eq!(seen, vec!["a7", "a6", "a5"]);
+
+        {
+            let mut conn = get_connection(&pool).expect("conn");
+            // Newer than the cursor: belongs before page one, so later pages skip it.
+            insert_deposit(&mut conn, "new-latest", "2024-02-01T00:00:00+00:00");
+            // Older than the cursor: shows up once, in its place.
+            insert_deposit(&mut conn, "new-middle", "2024-01-02T00:00:00+00:00");
+        }
+
+        let mut cursor = first.next_cursor;
+        while let Some(raw) = cursor {
+            let decoded = ActivityCursor::decode(&raw).expect("cursor round-trips");
+            let next = page(Some(&decoded));
+            seen.extend(next.data.iter().map(|a| a.id.clone()));
+            cursor = next.next_cursor;
+        }
+
+        assert_eq!(
+            seen,
+            vec!["a7", "a6", "a5", "new-middle", "a4", "a3", "a2", "a1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn recent_activities_span_accounts_newest_first_with_broker_names() {
+        let (pool, writer) = setup_db();
+        let repo = ActivityRepository::new(pool.clone(), writer);
+
+        {
+            let mut conn = get_connection(&pool).expect("conn");
+            insert_broker_account_and_import_run(&mut conn);
+            insert_account(&mut conn, "manual-account");
+            diesel::sql_query(
+                "INSERT INTO platforms (id, name, url, external_id, kind, website_url, logo_url) \
+                 VALUES ('QUESTRADE', 'Questrade', 'https://questrade.com', NULL, 'BROKERAGE', NULL, NULL)",
+            )
+            .execute(&mut conn)
+            .expect("insert platform");
+            diesel::sql_query(
+                "UPDATE accounts SET platform_id = 'QUESTRADE' WHERE id = 'broker-local-account'",
+            )
+            .execute(&mut conn)
+            .expect("link platform");
+            diesel::sql_query(
+                "INSERT INTO activities \
+                 (id, account_id, asset_id, activity_type, activity_type_override, source_type, subtype, \
+                  status, activity_date, settlement_date, quantity, unit_price, amount, fee, currency, \
+                  fx_rate, notes, metadata, source_system, source_record_id, source_group_id, \
+                  idempotency_key, import_run_id, is_user_modified, needs_review, created_at, updated_at) \
+                 VALUES \
+                 ('oldest', 'manual-account', NULL, 'DEPOSIT', NULL, NULL, NULL, \
+                  'POSTED', '2024-01-01T10:00:00+00:00', NULL, NULL, NULL, '100', NULL, 'USD', \
+                  NULL, NULL, NULL, 'MANUAL', NULL, NULL, 'recent-oldest', NULL, 0, 0, \
+                  '2024-01-01T10:00:00+00:00', '2024-01-01T10:00:00+00:00'), \
+                 ('newest', 'broker-local-account', NULL, 'DEPOSIT', NULL, NULL, NULL, \
+                  'POSTED', '2024-03-01T10:00:00+00:00', NULL, NULL, NULL, '300', NULL, 'USD', \
+                  NULL, NULL, NULL, 'SNAPTRADE', 'recent-2', NULL, 'recent-newest', \
+                  'local-import-run', 0, 0, \
+                  '2024-03-01T10:00:00+00:00', '2024-03-01T10:00:00+00:00'), \
+                 ('middle', 'manual-account', NULL, 'WITHDRAWAL', NULL, NULL, NULL, \
+                  'POSTED', '2024-02-01T10:00:00+00:00', NULL, NULL, NULL, '50', NULL, 'USD', \
+                  NULL, NULL, NULL, 'MANUAL', NULL, NULL, 'recent-middle', NULL, 0, 0, \
+                  '2024-02-01T10:00:00+00:00', '2024-02-01T10:00:00+00:00')",
+            )
+            .execute(&mut conn)
+            .expect("insert activities");
+        }
+
+        let recent = repo.get_recent_activities(10).expect("recent activities");
+        let ids: Vec<&str> = recent.iter().map(|r| r.activity.id.as_str()).collect();
+        assert_eq!(ids, vec!["newest", "middle", "oldest"]);
+        assert_eq!(recent[0].activity.account_name, "Broker Account");
+        assert_eq!(recent[0].broker_name.as_deref(), Some("Questrade"));
+        assert_eq!(
+            recent[0].activity.source_system.as_deref(),
+            Some("SNAPTRADE")
+        );
+        assert_eq!(
+            recent[0].activity.import_run_id.as_deref(),
+            Some("local-import-run")
+        );
+        assert_eq!(recent[1].broker_name, None);
+
+        let limited = repo
+            .get_recent_activities(2)
+            .expect("limited recent activities");
+        assert_eq!(limited.len(), 2);
+        assert_eq!(limited[1].activity.id, "middle");
+    }
+
     #[tokio::test]
     async fn notes_only_broker_edit_preserves_type_override_and_base_type_clears_it() {
         let (pool, writer) = setup_db();
@@ -4477,6 +4826,76 @@ mod tests {
         assert_eq!(rows[0].4.as_deref(), Some("idemp-2"));
     }
 
+    #[tokio::test]
+    async fn bulk_upsert_removing_stale_writes_and_removes_in_one_pass() {
+        let (pool, writer) = setup_db();
+        let repo = ActivityRepository::new(pool.clone(), writer);
+        let mut conn = get_connection(&pool).expect("conn");
+
+        insert_account(&mut conn, "acc-sync");
+
+        let synced = |id: &str| ActivityUpsert {
+            id: id.to_string(),
+            account_id: "acc-sync".to_string(),
+            asset_id: None,
+            activity_type: "DEPOSIT".to_string(),
+            subtype: None,
+            activity_date: "2024-01-15".to_string(),
+            quantity: None,
+            unit_price: None,
+            currency: "USD".to_string(),
+            fee: None,
+            tax: None,
+            amount: Some(Decimal::from(100)),
+            status: None,
+            notes: None,
+            fx_rate: None,
+            metadata: None,
+            needs_review: None,
+            source_system: Some("SNAPTRADE".to_string()),
+            source_record_id: Some(format!("txn-{}", id)),
+            source_group_id: None,
+            idempotency_key: None,
+            import_run_id: None,
+        };
+        repo.bulk_upsert(vec![synced("stale"), synced("edited")])
+            .await
+            .expect("initial upsert succeeds");
+        diesel::update(activities::table.filter(activities::id.eq("edited")))
+            .set(activities::is_user_modified.eq(1))
+            .execute(&mut conn)
+            .expect("mark edited");
+
+        let result = repo
+            .bulk_upsert_removing_stale(
+                "acc-sync",
+                vec![synced("fresh")],
+                Box::new(|stored: &[Activity]| {
+                    // The selector sees the rows written by the same call.
+                    assert!(stored.iter().any(|activity| activity.id == "fresh"));
+                    StaleActivityPlan {
+                        remove_ids: vec!["stale".to_string(), "edited".to_string()],
+                        preserved_ids: Vec::new(),
+                    }
+                }),
+            )
+            .await
+            .expect("resync write succeeds");
+
+        assert_eq!(result.upserted.created, 1);
+        // A row edited before the write is never deleted, whatever the selector chose.
+        assert_eq!(result.plan.remove_ids, vec!["stale".to_string()]);
+        assert_eq!(result.plan.preserved_ids, vec!["edited".to_string()]);
+        assert_eq!(result.removed.len(), 1);
+        let remaining: Vec<String> = activities::table
+            .filter(activities::account_id.eq("acc-sync"))
+            .select(activities::id)
+            .order(activities::id.asc())
+            .load(&mut conn)
+            .expect("load activities");
+        assert_eq!(remaining, vec!["edited".to_string(), "fresh".to_string()]);
+    }
+
     #[tokio::test]
     async fn bulk_upsert_collapses_duplicate_source_identity_within_same_batch() {
         let (pool, writer) = setup_db();
@@ -0,0 +1,158 @@
+//! Optional encryption of the database file at rest, using SQLCipher.
+//!
+//! Opt-in on two levels: the crate must be built with the `sqlcipher` feature (which bundles
+//! SQLCipher in place of plain SQLite), and the host must pass a passphrase to
+//! [`set_database_key`] before [`super::init`]. Every connection the crate opens then sends
+//! `PRAGMA key` before anything else, so the file, its WAL and backups made with
+//! `VACUUM INTO` are unreadable without the passphrase.
+//!
+//! Tradeoffs: every page is encrypted with AES-256, which costs roughly 5–15% on reads and
+//! writes, and the passphrase is stretched with PBKDF2 each time a connection is opened (pooled
+//! connections pay this once). A forgotten passphrase cannot be recovered, and an existing
+//! plaintext database is not converted; it has to be exported into an encrypted one with
+//! SQLCipher's `sqlcipher_export`.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use diesel::connection::SimpleConnection;
+use diesel::sqlite::SqliteConnection;
+use rusqlite::{Connection as RusqliteConnection, ErrorCode, OptionalExtension};
+
+use wealthfolio_core::errors::{DatabaseError, Error, Result};
+
+/// Passphrase that unlocks an encrypted database. `Debug` never prints it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DatabaseKey(String);
+
+impl DatabaseKey {
+    pub fn new(passphrase: impl Into<String>) -> Result<Self> {
+        let passphrase = passphrase.into();
+        if passphrase.trim().is_empty() {
+            return Err(Error::Database(DatabaseError::ConnectionFailed(
+                "Database encryption passphrase must not be empty".to_string(),
+            )));
+        }
+        Ok(Self(passphrase))
+    }
+
+    fn pragma(&self) -> String {
+        format!("PRAGMA key = '{}';", self.0.replace('\'', "''"))
+    }
+}
+
+impl fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DatabaseKey(<redacted>)")
+    }
+}
+
+static DATABASE_KEY: OnceLock<DatabaseKey> = OnceLock::new();
+
+/// Encrypts every database connection opened from now on with `key`. Can be set once per
+/// process; setting the same key again is a no-op.
+pub fn set_database_key(key: DatabaseKey) -> Result<()> {
+    let configured = DATABASE_KEY.get_or_init(|| key.clone());
+    if *configured != key {
+        return Err(Error::Database(DatabaseError::Internal(
+            "A different database encryption key is already configured".to_string(),
+        )));
+    }
+    Ok(())
+}
+
+pub fn is_encryption_enabled() -> bool {
+    DATABASE_KEY.get().is_some()
+}
+
+pub(crate) fn configured_key() -> Option<&'static DatabaseKey> {
+    DATABASE_KEY.get()
+}
+
+/// Must run before any other statement on the connection.
+pub(crate) fn apply_key(conn: &mut SqliteConnection) -> diesel::QueryResult<()> {
+    match configured_key() {
+        Some(key) => conn.batch_execute(&key.pragma()),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn apply_key_rusqlite(conn: &RusqliteConnection) -> rusqlite::Result<()> {
+    match configured_key() {
+        Some(key) => conn.execute_batch(&key.pragma()),
+        None => Ok(()),
+    }
+}
+
+/// Opens `db_path` with `key` (or none) and reads its schema, so a wrong key, an encrypted
+/// database opened without one, or a build without SQLCipher fails with a clear message
+/// instead of a generic error on the first query. Creates the file if it does not exist.
+pub fn verify_database_key(db_path: &str, key: Option<&DatabaseKey>) -> Result<()> {
+    let failed = |message: String| Error::Database(DatabaseError::ConnectionFailed(message));
+    let conn = RusqliteConnection::open(db_path).map_err(|e| failed(e.to_string()))?;
+
+    if let Some(key) = key {
+        let cipher_version: Option<String> = conn
+            .query_row("PRAGMA cipher_version;", [], |row| row.get(0))
+            .optional()
+            .map_err(|e| failed(e.to_string()))?;
+        if cipher_version.is_none() {
+            return Err(failed(
+                "Database encryption requires a build with the `sqlcipher` feature".to_string(),
+            ));
+        }
+        conn.execute_batch(&key.pragma())
+            .map_err(|e| failed(e.to_string()))?;
+    }
+
+    match conn.query_row("SELECT count(*) FROM sqlite_master;", [], |row| {
+        row.get::<_, i64>(0)
+    }) {
+        Ok(_) => Ok(()),
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::NotADatabase => {
+            Err(failed(if key.is_some() {
+                "Wrong database encryption passphrase, or the database is not encrypted".to_string()
+            } else {
+                "The database is encrypted; a passphrase is required to open it".to_string()
+            }))
+        }
+        Err(e) => Err(failed(e.to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "sqlcipher"))]
+mod tests {
+    use super::*;
+
+    fn key(passphrase: &str) -> DatabaseKey {
+        DatabaseKey::new(passphrase).unwrap()
+    }
+
+    #[test]
+    fn encrypted_database_opens_only_with_the_right_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("app.db");
+        let db_path = db_path.to_str().unwrap();
+
+        {
+            let conn = RusqliteConnection::open(db_path).unwrap();
+            conn.execute_batch(&key("correct horse").pragma()).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE holdings (symbol TEXT); INSERT INTO holdings VALUES ('VTI');",
+            )
+            .unwrap();
+        }
+
+        assert!(verify_database_key(db_path, Some(&key("correct horse"))).is_ok());
+        let wrong = verify_database_key(db_path, Some(&key("wrong horse"))).unwrap_err();
+        assert!(wrong
+            .to_string()
+            .contains("Wrong database encryption passphrase"));
+        let missing = verify_database_key(db_path, None).unwrap_err();
+        assert!(missing.to_string().contains("passphrase is required"));
+
+        let raw = std::fs::read(db_path).unwrap();
+        assert!(!raw.starts_with(b"SQLite format 3"));
+        assert!(!raw.windows(3).any(|window| window == b"VTI"));
+    }
+}
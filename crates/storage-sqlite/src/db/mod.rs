@@ -26,9 +26,17 @@ const BACKUP_FILENAME_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
 pub type DbPool = r2d2::Pool<ConnectionManager<SqliteConnection>>;
 pub type DbConnection = PooledConnection<ConnectionManager<SqliteConnection>>;
 
+pub mod encryption;
 pub mod write_actor;
 pub use write_actor::WriteHandle;
 
+/// Opens a connection, unlocked with the configured encryption key if there is one.
+fn establish_connection(db_path: &str) -> Result<SqliteConnection> {
+    let mut conn = SqliteConnection::establish(db_path).map_err(StorageError::from)?;
+    encryption::apply_key(&mut conn).map_err(StorageError::from)?;
+    Ok(conn)
+}
+
 pub fn init(app_data_dir: &str) -> Result<String> {
     let db_path = get_db_path(app_data_dir);
 
@@ -38,8 +46,11 @@ pub fn init(app_data_dir: &str) -> Result<String> {
         fs::create_dir_all(db_dir)?;
     }
 
+    // 2. Fail clearly on a wrong or missing encryption key
+    encryption::verify_database_key(&db_path, encryption::configured_key())?;
+
     {
-        let mut conn = SqliteConnection::establish(&db_path).map_err(StorageError::from)?;
+        let mut conn = establish_connection(&db_path)?;
         conn.batch_execute(
             "\n            PRAGMA journal_mode = WAL;\n            PRAGMA foreign_keys = ON;\n            PRAGMA busy_timeout = 30000;\n            PRAGMA synchronous  = NORMAL;\n        ",
         ).map_err(StorageError::from)?;
@@ -62,7 +73,7 @@ pub fn create_pool(db_path: &str) -> Result<Arc<DbPool>> {
 
 pub fn run_migrations(db_path: &str) -> Result<()> {
     info!("Running database migrations");
-    let mut connection = SqliteConnection::establish(db_path).map_err(StorageError::from)?;
+    let mut connection = establish_connection(db_path)?;
 
     connection
         .batch_execute(
@@ -374,6 +385,9 @@ pub fn backup_database_to_file(app_data_dir: &str, backup_path: &str) -> Result<
         Error::Database(DatabaseError::BackupFailed(e.to_string()))
     })?;
 
+    encryption::apply_key_rusqlite(&source_conn)
+        .map_err(|e| Error::Database(DatabaseError::BackupFailed(e.to_string())))?;
+
     source_conn
         .busy_timeout(Duration::from_secs(30))
         .map_err(|e| Error::Database(DatabaseError::BackupFailed(e.to_string())))?;
@@ -513,7 +527,7 @@ pub fn restore_database(app_data_dir: &str, backup_file_path: &str) -> Result<()
     }
 
     // Ensure desired journal mode; recreate WAL after restore for consistency
-    if let Ok(mut conn) = SqliteConnection::establish(&db_path) {
+    if let Ok(mut conn) = establish_connection(&db_path) {
         let _ = conn.batch_execute("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;");
     }
 
@@ -529,7 +543,7 @@ pub fn restore_database_safe(app_data_dir: &str, backup_file_path: &str) -> Resu
     let db_path = get_db_path(app_data_dir);
 
     // Try to checkpoint the database before restore
-    if let Ok(mut conn) = SqliteConnection::establish(&db_path) {
+    if let Ok(mut conn) = establish_connection(&db_path) {
         use diesel::RunQueryDsl;
         let _ = diesel::sql_query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&mut conn);
         // Try to temporarily switch to DELETE journal mode to minimize WAL interactions
@@ -557,6 +571,8 @@ impl r2d2::CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for Connec
         &self,
         conn: &mut SqliteConnection,
     ) -> std::result::Result<(), diesel::r2d2::Error> {
+        encryption::apply_key(conn).map_err(diesel::r2d2::Error::QueryError)?;
+
         // IMPORTANT: Use batch_execute (sqlite3_exec) instead of sql_query (sqlite3_prepare_v2).
         // sql_query only executes the FIRST statement; subsequent PRAGMAs are silently ignored.
         conn.batch_execute(
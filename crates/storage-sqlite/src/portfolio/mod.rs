@@ -1,5 +1,6 @@
 //! SQLite storage implementation for portfolio data.
 
 pub mod allocation_targets;
+pub mod share_links;
 pub mod snapshot;
 pub mod valuation;
@@ -0,0 +1,133 @@
+//! Repository for read-only portfolio share links (server mode).
+//!
+//! Each row holds a snapshot frozen at creation and the hash of the token that unlocks it. Token
+//! generation and hashing happen in the host; this repository persists the rows and never
+//! returns an expired one.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::db::{get_connection, DbPool, WriteHandle};
+use crate::errors::StorageError;
+use crate::schema::portfolio_share_links;
+use wealthfolio_core::errors::Result;
+
+#[derive(Queryable, Identifiable, Selectable, Insertable, Serialize, Deserialize, Debug, Clone)]
+#[diesel(table_name = crate::schema::portfolio_share_links)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ShareLinkDB {
+    pub id: String,
+    pub token_hash: String,
+    pub snapshot_json: String,
+    pub include_account_details: bool,
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+impl ShareLinkDB {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.expires_at)
+            .map(|expiry| expiry <= now)
+            .unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewShareLink {
+    pub token_hash: String,
+    pub snapshot_json: String,
+    pub include_account_details: bool,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Timestamps are stored in one fixed format so they compare as strings.
+fn format_timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub struct ShareLinkRepository {
+    pool: Arc<DbPool>,
+    writer: WriteHandle,
+}
+
+impl ShareLinkRepository {
+    pub fn new(pool: Arc<DbPool>, writer: WriteHandle) -> Self {
+        Self { pool, writer }
+    }
+
+    pub async fn create(&self, new_link: NewShareLink) -> Result<ShareLinkDB> {
+        let row = ShareLinkDB {
+            id: Uuid::new_v4().to_string(),
+            token_hash: new_link.token_hash,
+            snapshot_json: new_link.snapshot_json,
+            include_account_details: new_link.include_account_details,
+            expires_at: format_timestamp(new_link.expires_at),
+            created_at: format_timestamp(Utc::now()),
+        };
+        self.writer
+            .exec(move |conn| {
+                diesel::insert_into(portfolio_share_links::table)
+                    .values(&row)
+                    .returning(ShareLinkDB::as_returning())
+                    .get_result(conn)
+                    .map_err(|e| StorageError::from(e).into())
+            })
+            .await
+    }
+
+    /// Links that have not expired yet, newest first.
+    pub fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<ShareLinkDB>> {
+        let mut conn = get_connection(&self.pool)?;
+        portfolio_share_links::table
+            .filter(portfolio_share_links::expires_at.gt(format_timestamp(now)))
+            .order(portfolio_share_links::created_at.desc())
+            .select(ShareLinkDB::as_select())
+            .load(&mut conn)
+            .map_err(|e| StorageError::from(e).into())
+    }
+
+    /// The link unlocked by a token hash, or `None` when there is none or it has expired.
+    pub fn find_active(&self, token_hash: &str, now: DateTime<Utc>) -> Result<Option<ShareLinkDB>> {
+        let mut conn = get_connection(&self.pool)?;
+        let row = portfolio_share_links::table
+            .filter(portfolio_share_links::token_hash.eq(token_hash))
+            .select(ShareLinkDB::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(StorageError::from)?;
+        Ok(row.filter(|link| !link.is_expired(now)))
+    }
+
+    /// Returns false when the id does not exist.
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let id = id.to_string();
+        self.writer
+            .exec(move |conn| {
+                let deleted = diesel::delete(
+                    portfolio_share_links::table.filter(portfolio_share_links::id.eq(&id)),
+                )
+                .execute(conn)
+                .map_err(StorageError::from)?;
+                Ok(deleted > 0)
+            })
+            .await
+    }
+
+    /// Deletes every link that has expired by `now`. Returns how many were removed.
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<usize> {
+        let now = format_timestamp(now);
+        self.writer
+            .exec(move |conn| {
+                diesel::delete(
+                    portfolio_share_links::table.filter(portfolio_share_links::expires_at.le(&now)),
+                )
+                .execute(conn)
+                .map_err(|e| StorageError::from(e).into())
+            })
+            .await
+    }
+}
@@ -249,6 +249,18 @@ impl PortfolioRepositoryTrait for PortfolioRepository {
                 Ok(ids)
             }
             AccountScope::Accounts { account_ids } => Ok(account_ids.clone()),
+            AccountScope::Unassigned => {
+                use crate::schema::accounts::dsl::*;
+                let assigned = portfolio_accounts::table.select(portfolio_accounts::account_id);
+                let ids = accounts
+                    .filter(is_archived.eq(false))
+                    .filter(diesel::dsl::not(id.eq_any(assigned)))
+                    .order(name.asc())
+                    .select(id)
+                    .load::<String>(&mut conn)
+                    .map_err(StorageError::from)?;
+                Ok(ids)
+            }
         }
     }
 }
@@ -305,4 +317,24 @@ mod tests {
 
         assert_eq!(ids, vec!["active", "hidden"]);
     }
+
+    #[tokio::test]
+    async fn resolve_unassigned_excludes_portfolio_members() {
+        let (repo, _dir) = setup();
+        insert_account(&repo, "grouped", "Grouped", true, false);
+        insert_account(&repo, "loose", "Loose", true, false);
+        insert_account(&repo, "archived", "Archived", true, true);
+        repo.create(NewPortfolio {
+            name: "Retirement".to_string(),
+            description: None,
+            sort_order: 0,
+            account_ids: vec!["grouped".to_string()],
+        })
+        .await
+        .unwrap();
+
+        let ids = repo.resolve_account_ids(&AccountScope::Unassigned).unwrap();
+
+        assert_eq!(ids, vec!["loose"]);
+    }
 }
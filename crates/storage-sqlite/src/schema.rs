@@ -523,6 +523,7 @@ diesel::table! {
         last_error_code -> Nullable<Text>,
         device_id -> Nullable<Text>,
         created_at -> Text,
+        last_error_details -> Nullable<Text>,
     }
 }
 
@@ -784,6 +785,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    portfolio_share_links (id) {
+        id -> Text,
+        token_hash -> Text,
+        snapshot_json -> Text,
+        include_account_details -> Bool,
+        expires_at -> Text,
+        created_at -> Text,
+    }
+}
+
 diesel::table! {
     mcp_audit_log (id) {
         id -> Text,
@@ -896,4 +908,5 @@ diesel::allow_tables_to_appear_in_same_query!(
     allocation_target_weights,
     personal_access_tokens,
     mcp_audit_log,
+    portfolio_share_links,
 );
@@ -1,18 +1,41 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use wealthfolio_device_sync::engine::{OutboxStore, ReplayEvent, ReplayStore};
+use wealthfolio_core::errors::{DatabaseError, Error};
+use wealthfolio_device_sync::engine::{
+    OutboxStore, ReplayApplyError, ReplayEvent, ReplayFailurePolicy, ReplayStore, SyncStateStore,
+};
 
 use super::repository::AppSyncRepository;
 
 #[derive(Clone)]
 pub struct SqliteSyncEngineDbPorts {
     repository: Arc<AppSyncRepository>,
+    replay_failure_policy: ReplayFailurePolicy,
 }
 
 impl SqliteSyncEngineDbPorts {
     pub fn new(repository: Arc<AppSyncRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            replay_failure_policy: ReplayFailurePolicy::default(),
+        }
+    }
+
+    pub fn with_replay_failure_policy(mut self, policy: ReplayFailurePolicy) -> Self {
+        self.replay_failure_policy = policy;
+        self
+    }
+}
+
+/// A constraint the batch breaks means local state diverged from the events; anything else
+/// may clear up on a later cycle.
+fn replay_apply_error(err: Error) -> ReplayApplyError {
+    match err {
+        Error::Database(
+            DatabaseError::ForeignKeyViolation(_) | DatabaseError::UniqueViolation(_),
+        ) => ReplayApplyError::Conflict(err.to_string()),
+        _ => ReplayApplyError::Failed(err.to_string()),
     }
 }
 
@@ -39,6 +62,19 @@ impl OutboxStore for SqliteSyncEngineDbPorts {
             .map_err(|e| e.to_string())
     }
 
+    async fn mark_outbox_rejected(
+        &self,
+        event_id: String,
+        error_message: Option<String>,
+        error_code: Option<String>,
+        field_errors: Vec<wealthfolio_core::sync::SyncFieldError>,
+    ) -> Result<(), String> {
+        self.repository
+            .mark_outbox_rejected(event_id, error_message, error_code, field_errors)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     async fn mark_outbox_sent(&self, event_ids: Vec<String>) -> Result<(), String> {
         self.repository
             .mark_outbox_sent(event_ids)
@@ -76,10 +112,11 @@ impl OutboxStore for SqliteSyncEngineDbPorts {
 
 #[async_trait]
 impl ReplayStore for SqliteSyncEngineDbPorts {
-    async fn acquire_cycle_lock(&self) -> Result<i64, String> {
+    async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String> {
         self.repository
             .acquire_cycle_lock()
             .await
+            .map(Some)
             .map_err(|e| e.to_string())
     }
 
@@ -103,7 +140,7 @@ impl ReplayStore for SqliteSyncEngineDbPorts {
     async fn apply_remote_events_lww_batch(
         &self,
         events: Vec<ReplayEvent>,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, ReplayApplyError> {
         self.repository
             .apply_remote_events_lww_batch(
                 events
@@ -122,7 +159,7 @@ impl ReplayStore for SqliteSyncEngineDbPorts {
                     .collect(),
             )
             .await
-            .map_err(|e| e.to_string())
+            .map_err(replay_apply_error)
     }
 
     async fn apply_remote_event_lww(&self, event: ReplayEvent) -> Result<bool, String> {
@@ -140,6 +177,10 @@ impl ReplayStore for SqliteSyncEngineDbPorts {
             .map_err(|e| e.to_string())
     }
 
+    fn replay_failure_policy(&self) -> ReplayFailurePolicy {
+        self.replay_failure_policy
+    }
+
     async fn mark_pull_completed(&self) -> Result<(), String> {
         self.repository
             .mark_pull_completed()
@@ -191,3 +232,99 @@ impl ReplayStore for SqliteSyncEngineDbPorts {
             .map_err(|e| e.to_string())
     }
 }
+
+/// Local (default) sync state: cursor and cycle lock live in the app database.
+#[async_trait]
+impl SyncStateStore for SqliteSyncEngineDbPorts {
+    async fn get_cursor(&self) -> Result<i64, String> {
+        ReplayStore::get_cursor(self).await
+    }
+
+    async fn set_cursor(&self, cursor: i64) -> Result<(), String> {
+        ReplayStore::set_cursor(self, cursor).await
+    }
+
+    async fn acquire_cycle_lock(&self) -> Result<Option<i64>, String> {
+        ReplayStore::acquire_cycle_lock(self).await
+    }
+
+    async fn verify_cycle_lock(&self, lock_version: i64) -> Result<bool, String> {
+        ReplayStore::verify_cycle_lock(self, lock_version).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use wealthfolio_core::sync::{SyncEntity, SyncOperation};
+
+    use crate::db::{create_pool, init, run_migrations, write_actor::spawn_writer};
+
+    fn setup_ports() -> SqliteSyncEngineDbPorts {
+        let app_data = tempdir()
+            .expect("tempdir")
+            .keep()
+            .to_string_lossy()
+            .to_string();
+        let db_path = init(&app_data).expect("init db");
+        run_migrations(&db_path).expect("migrate db");
+        let pool = create_pool(&db_path).expect("create pool");
+        let writer = spawn_writer(pool.as_ref().clone()).expect("spawn writer");
+        SqliteSyncEngineDbPorts::new(Arc::new(AppSyncRepository::new(pool, writer)))
+    }
+
+    #[tokio::test]
+    async fn local_state_store_round_trips_cursor_and_lock() {
+        let store: Arc<dyn SyncStateStore> = Arc::new(setup_ports());
+
+        assert_eq!(store.get_cursor().await.expect("cursor"), 0);
+        store.set_cursor(42).await.expect("set cursor");
+        assert_eq!(store.get_cursor().await.expect("cursor"), 42);
+
+        let first = store.acquire_cycle_lock().await.expect("lock").unwrap();
+        assert!(store.verify_cycle_lock(first).await.expect("verify"));
+        let second = store.acquire_cycle_lock().await.expect("lock").unwrap();
+        assert!(!store.verify_cycle_lock(first).await.expect("verify"));
+        assert!(store.verify_cycle_lock(second).await.expect("verify"));
+    }
+
+    #[tokio::test]
+    async fn batch_referencing_a_missing_entity_is_a_conflict() {
+        let ports = setup_ports();
+
+        let err = ports
+            .apply_remote_events_lww_batch(vec![ReplayEvent {
+                entity: SyncEntity::SpendingActivityEvent,
+                entity_id: "missing-activity".to_string(),
+                op: SyncOperation::Create,
+                event_id: "evt-missing-activity".to_string(),
+                client_timestamp: "2026-02-17T00:00:01Z".to_string(),
+                seq: 7,
+                payload: serde_json::json!({
+                    "activity_id": "missing-activity",
+                    "event_id": "missing-spending-event",
+                    "created_at": "2026-02-17T00:00:01Z",
+                    "updated_at": "2026-02-17T00:00:01Z",
+                }),
+            }])
+            .await
+            .expect_err("missing FK should fail the batch");
+
+        assert!(matches!(err, ReplayApplyError::Conflict(_)), "{err:?}");
+        assert_eq!(ReplayStore::get_cursor(&ports).await.expect("cursor"), 0);
+    }
+
+    #[test]
+    fn errors_other_than_constraint_violations_are_retried() {
+        assert_eq!(
+            replay_apply_error(Error::Database(DatabaseError::ConnectionFailed(
+                "database is locked".to_string()
+            ))),
+            ReplayApplyError::Failed(
+                "Database operation failed: Failed to connect to database: database is locked"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -144,6 +144,8 @@ pub struct SyncOutboxEventDB {
     pub last_error_code: Option<String>,
     pub device_id: Option<String>,
     pub created_at: String,
+    /// JSON list of field-level reasons when the cloud rejected the event as invalid.
+    pub last_error_details: Option<String>,
 }
 
 #[derive(
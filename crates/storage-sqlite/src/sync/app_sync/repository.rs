@@ -13,9 +13,11 @@ use wealthfolio_core::constants::DECIMAL_PRECISION;
 use wealthfolio_core::errors::{DatabaseError, Error, Result};
 use wealthfolio_core::portfolio::snapshot::Position;
 use wealthfolio_core::sync::{
-    should_apply_lww, SyncEngineStatus, SyncEntity, SyncEntityMetadata, SyncOperation,
-    SyncOutboxEvent, SyncOutboxStatus, APP_SYNC_TABLES,
+    should_apply_lww, suggest_sync_fix, SyncEngineStatus, SyncEntity, SyncEntityMetadata,
+    SyncFieldError, SyncOperation, SyncOutboxEvent, SyncOutboxStatus, SyncRejection,
+    SyncRejectionPage, SyncRejectionQuery, APP_SYNC_TABLES,
 };
+use wealthfolio_device_sync::SyncBlobStorage;
 
 use crate::db::{get_connection, WriteHandle};
 use crate::errors::StorageError;
@@ -84,6 +86,18 @@ fn escape_sqlite_str(value: &str) -> String {
     value.replace('\'', "''")
 }
 
+/// Attaches a plaintext database file. `KEY ''` stops SQLCipher builds from applying the main
+/// database's key to it, so exported snapshots open on other devices and plaintext ones restore.
+fn attach_plaintext_database(
+    conn: &mut SqliteConnection,
+    path: &str,
+    alias: &str,
+) -> diesel::QueryResult<usize> {
+    diesel::sql_query(format!("ATTACH DATABASE ? AS {} KEY ''", alias))
+        .bind::<diesel::sql_types::Text, _>(path)
+        .execute(conn)
+}
+
 fn quote_identifier(value: &str) -> String {
     format!("`{}`", value.replace('`', "``"))
 }
@@ -965,10 +979,14 @@ fn replay_apply_error(
     let message = format!(
         "Replay apply failed for entity={entity:?}{table_context} entity_id={entity_id} op={op:?} event_id={event_id} seq={seq}: {err}"
     );
-    if is_foreign_key_error_message(&message) {
-        Error::Database(DatabaseError::ForeignKeyViolation(message))
-    } else {
-        Error::Database(DatabaseError::Internal(message))
+    match err {
+        Error::Database(DatabaseError::UniqueViolation(_)) => {
+            Error::Database(DatabaseError::UniqueViolation(message))
+        }
+        _ if is_foreign_key_error_message(&message) => {
+            Error::Database(DatabaseError::ForeignKeyViolation(message))
+        }
+        _ => Error::Database(DatabaseError::Internal(message)),
     }
 }
 
@@ -1296,6 +1314,7 @@ pub(in crate::sync::app_sync) fn insert_outbox_event(
         last_error_code: None,
         device_id,
         created_at: now,
+        last_error_details: None,
     };
 
     diesel::insert_into(sync_outbox::table)
@@ -2065,6 +2084,7 @@ fn apply_remote_event_lww_tx(
 pub struct AppSyncRepository {
     pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
     writer: WriteHandle,
+    blob_storage: SyncBlobStorage,
 }
 
 impl AppSyncRepository {
@@ -2072,7 +2092,21 @@ impl AppSyncRepository {
         pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
         writer: WriteHandle,
     ) -> Self {
-        Self { pool, writer }
+        Self {
+            pool,
+            writer,
+            blob_storage: SyncBlobStorage::temp(),
+        }
+    }
+
+    /// Stages snapshot images in `blob_storage` instead of the system temp dir.
+    pub fn with_blob_storage(mut self, blob_storage: SyncBlobStorage) -> Self {
+        self.blob_storage = blob_storage;
+        self
+    }
+
+    pub fn blob_storage(&self) -> &SyncBlobStorage {
+        &self.blob_storage
     }
 
     pub fn get_cursor(&self) -> Result<i64> {
@@ -2944,6 +2978,111 @@ impl AppSyncRepository {
             .await
     }
 
+    /// Dead-letters an event the cloud rejected, keeping its field-level reasons.
+    pub async fn mark_outbox_rejected(
+        &self,
+        event_id: String,
+        error_message: Option<String>,
+        error_code: Option<String>,
+        field_errors: Vec<SyncFieldError>,
+    ) -> Result<()> {
+        let details = serde_json::to_string(&field_errors)?;
+        self.writer
+            .exec(move |conn| {
+                diesel::update(sync_outbox::table.find(event_id))
+                    .set((
+                        sync_outbox::status.eq(enum_to_db(&SyncOutboxStatus::Dead)?),
+                        sync_outbox::last_error.eq(error_message),
+                        sync_outbox::last_error_code.eq(error_code),
+                        sync_outbox::last_error_details.eq(Some(details)),
+                    ))
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(())
+            })
+            .await
+    }
+
+    /// One page of dead-lettered outbox events, newest first: local changes that will not
+    /// sync until the record is fixed.
+    pub fn list_sync_rejections(&self, query: &SyncRejectionQuery) -> Result<SyncRejectionPage> {
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.page_size.unwrap_or(50).clamp(1, 200);
+        let dead_status = enum_to_db(&SyncOutboxStatus::Dead)?;
+        let entities = query
+            .entities
+            .iter()
+            .map(enum_to_db)
+            .collect::<Result<Vec<String>>>()?;
+        let mut conn = get_connection(&self.pool)?;
+
+        let mut count_query = sync_outbox::table
+            .filter(sync_outbox::status.eq(dead_status.clone()))
+            .into_boxed();
+        let mut rows_query = sync_outbox::table
+            .filter(sync_outbox::status.eq(dead_status))
+            .into_boxed();
+        if !entities.is_empty() {
+            count_query = count_query.filter(sync_outbox::entity.eq_any(entities.clone()));
+            rows_query = rows_query.filter(sync_outbox::entity.eq_any(entities));
+        }
+
+        let total_count: i64 = count_query
+            .count()
+            .get_result(&mut conn)
+            .map_err(StorageError::from)?;
+        let rows = rows_query
+            .order((sync_outbox::created_at.desc(), sync_outbox::event_id.desc()))
+            .limit(page_size)
+            .offset((page - 1) * page_size)
+            .load::<SyncOutboxEventDB>(&mut conn)
+            .map_err(StorageError::from)?;
+
+        let items = rows
+            .into_iter()
+            .map(|row| {
+                let field_errors: Vec<SyncFieldError> = row
+                    .last_error_details
+                    .as_deref()
+                    .and_then(|details| serde_json::from_str(details).ok())
+                    .unwrap_or_default();
+                Ok(SyncRejection {
+                    suggested_fix: suggest_sync_fix(row.last_error_code.as_deref(), &field_errors),
+                    event_id: row.event_id,
+                    entity: enum_from_db(&row.entity)?,
+                    entity_id: row.entity_id,
+                    op: enum_from_db(&row.op)?,
+                    error_code: row.last_error_code,
+                    error_message: row.last_error,
+                    field_errors,
+                    created_at: row.created_at,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(SyncRejectionPage { items, total_count })
+    }
+
+    /// Removes rejections the user has seen, in one statement. Only dead-lettered events are
+    /// touched, so an id that has since been retried is left alone. Returns how many went.
+    pub async fn acknowledge_sync_rejections(&self, event_ids: Vec<String>) -> Result<usize> {
+        if event_ids.is_empty() {
+            return Ok(0);
+        }
+
+        self.writer
+            .exec(move |conn| {
+                let deleted = diesel::delete(
+                    sync_outbox::table
+                        .filter(sync_outbox::status.eq(enum_to_db(&SyncOutboxStatus::Dead)?))
+                        .filter(sync_outbox::event_id.eq_any(event_ids)),
+                )
+                .execute(conn)
+                .map_err(StorageError::from)?;
+                Ok(deleted)
+            })
+            .await
+    }
+
     pub async fn mark_cycle_outcome(
         &self,
         status_value: String,
@@ -3017,6 +3156,7 @@ impl AppSyncRepository {
 
     pub async fn export_snapshot_sqlite_image(&self, tables: Vec<String>) -> Result<Vec<u8>> {
         let pool = Arc::clone(&self.pool);
+        let snapshot_path = self.blob_storage.blob_path("wf_snapshot_export");
         tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
             let mut conn = get_connection(&pool)?;
             let table_set = if tables.is_empty() {
@@ -3031,14 +3171,10 @@ impl AppSyncRepository {
                 validate_sync_table(table)?;
             }
 
-            let snapshot_path =
-                std::env::temp_dir().join(format!("wf_snapshot_export_{}.db", Uuid::now_v7()));
-            let escaped_path = escape_sqlite_str(&snapshot_path.to_string_lossy());
+            let snapshot_path = snapshot_path.to_string_lossy().into_owned();
             let snapshot_alias = format!("snapshot_export_{}", Uuid::now_v7().simple());
-            let attach_sql = format!("ATTACH DATABASE '{}' AS {}", escaped_path, snapshot_alias);
             let tx_result = conn.immediate_transaction::<_, StorageError, _>(|tx| {
-                diesel::sql_query(attach_sql.clone())
-                    .execute(tx)
+                attach_plaintext_database(tx, &snapshot_path, &snapshot_alias)
                     .map_err(StorageError::from)?;
 
                 let run_export = (|| -> Result<()> {
@@ -3094,6 +3230,48 @@ impl AppSyncRepository {
         cursor_value: i64,
         device_id_value: String,
         key_version_value: Option<i32>,
+    ) -> Result<()> {
+        self.restore_snapshot_tables(
+            snapshot_db_path,
+            tables,
+            cursor_value,
+            device_id_value,
+            key_version_value,
+            false,
+        )
+        .await
+    }
+
+    /// Like [`Self::restore_snapshot_tables_from_file`], but local changes that were never
+    /// pushed survive: their outbox rows stay pending and their payloads are applied again on
+    /// top of the snapshot, in the same transaction.
+    pub async fn restore_snapshot_tables_keeping_unsynced_from_file(
+        &self,
+        snapshot_db_path: String,
+        tables: Vec<String>,
+        cursor_value: i64,
+        device_id_value: String,
+        key_version_value: Option<i32>,
+    ) -> Result<()> {
+        self.restore_snapshot_tables(
+            snapshot_db_path,
+            tables,
+            cursor_value,
+            device_id_value,
+            key_version_value,
+            true,
+        )
+        .await
+    }
+
+    async fn restore_snapshot_tables(
+        &self,
+        snapshot_db_path: String,
+        tables: Vec<String>,
+        cursor_value: i64,
+        device_id_value: String,
+        key_version_value: Option<i32>,
+        keep_unsynced: bool,
     ) -> Result<()> {
         self.writer
             .exec(move |conn| {
@@ -3101,18 +3279,28 @@ impl AppSyncRepository {
                 let table_set_lookup = table_set.iter().cloned().collect::<HashSet<_>>();
 
                 let now = Utc::now().to_rfc3339();
-                let escaped_path = escape_sqlite_str(&snapshot_db_path);
                 let snapshot_alias = format!("snapshot_{}", Uuid::new_v4().simple());
-                let attach_sql =
-                    format!("ATTACH DATABASE '{}' AS {}", escaped_path, snapshot_alias);
 
                 // APP_SYNC_TABLES is parent-first for inserts. Restore clears the
                 // selected tables in reverse order, then inserts in canonical order.
-                diesel::sql_query(attach_sql)
-                    .execute(conn)
+                attach_plaintext_database(conn, &snapshot_db_path, &snapshot_alias)
                     .map_err(StorageError::from)?;
 
                 let restore_result = (|| -> Result<()> {
+                    let unsynced = if keep_unsynced {
+                        sync_outbox::table
+                            .filter(
+                                sync_outbox::status
+                                    .eq(enum_to_db(&SyncOutboxStatus::Pending)?)
+                                    .and(sync_outbox::sent.eq(0)),
+                            )
+                            .order(sync_outbox::created_at.asc())
+                            .load::<SyncOutboxEventDB>(conn)
+                            .map_err(StorageError::from)?
+                    } else {
+                        Vec::new()
+                    };
+
                     // Bootstrap reset: clear control-plane sync state so stale events/metadata
                     // never leak into the newly restored snapshot baseline.
                     diesel::delete(sync_outbox::table)
@@ -3233,6 +3421,23 @@ impl AppSyncRepository {
                             .execute(conn)
                             .map_err(StorageError::from)?;
                     }
+                    for row in unsynced {
+                        let payload = serde_json::from_str(&row.payload)?;
+                        apply_remote_event_lww_tx(
+                            conn,
+                            enum_from_db(&row.entity)?,
+                            row.entity_id.clone(),
+                            enum_from_db(&row.op)?,
+                            row.event_id.clone(),
+                            row.client_timestamp.clone(),
+                            cursor_value,
+                            payload,
+                        )?;
+                        diesel::insert_into(sync_outbox::table)
+                            .values(&row)
+                            .execute(conn)
+                            .map_err(StorageError::from)?;
+                    }
                     ensure_no_foreign_key_violations_tx(
                         conn,
                         restore_plans.iter().map(|plan| plan.table.as_str()),
@@ -4525,6 +4730,52 @@ mod tests {
         assert!(message.contains("parent="), "{message}");
     }
 
+    #[tokio::test]
+    async fn replay_batch_failure_rolls_back_applied_events_and_keeps_cursor() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer);
+        repo.set_cursor(5).await.expect("set cursor");
+
+        repo.apply_remote_events_lww_batch(vec![
+            (
+                SyncEntity::Platform,
+                "platform-rolled-back".to_string(),
+                SyncOperation::Create,
+                "evt-platform-rolled-back".to_string(),
+                "2026-02-17T00:00:00Z".to_string(),
+                6,
+                serde_json::json!({
+                    "id": "platform-rolled-back",
+                    "name": "Rolled Back Platform",
+                    "url": "https://rolled-back.example",
+                    "external_id": serde_json::Value::Null,
+                    "kind": "BROKERAGE",
+                    "website_url": serde_json::Value::Null,
+                    "logo_url": serde_json::Value::Null
+                }),
+            ),
+            (
+                SyncEntity::SpendingActivityEvent,
+                "missing-activity-mid-batch".to_string(),
+                SyncOperation::Create,
+                "evt-missing-activity-mid-batch".to_string(),
+                "2026-02-17T00:00:01Z".to_string(),
+                7,
+                serde_json::json!({
+                    "activity_id": "missing-activity-mid-batch",
+                    "event_id": "missing-spending-event-mid-batch",
+                    "created_at": "2026-02-17T00:00:01Z",
+                    "updated_at": "2026-02-17T00:00:01Z",
+                }),
+            ),
+        ])
+        .await
+        .expect_err("missing FK should fail the batch");
+
+        assert_eq!(count_platform_rows(&pool, "platform-rolled-back"), 0);
+        assert_eq!(repo.get_cursor().expect("cursor"), 5);
+    }
+
     #[tokio::test]
     async fn remote_snapshot_single_event_is_preserved_before_account_arrives() {
         #[derive(diesel::QueryableByName)]
@@ -5772,6 +6023,59 @@ mod tests {
         assert_eq!(applied_count, 0);
     }
 
+    #[tokio::test]
+    async fn snapshot_restore_keeping_unsynced_reapplies_pending_outbox() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool.clone(), writer);
+        let snapshot_path = create_snapshot_db_with_account("acc-from-snapshot");
+
+        let event_id = {
+            let mut conn = get_connection(&pool).expect("conn");
+            insert_outbox_event(
+                &mut conn,
+                OutboxWriteRequest::new(
+                    SyncEntity::Account,
+                    "acc-local-unsynced",
+                    SyncOperation::Create,
+                    serde_json::json!({
+                        "id": "acc-local-unsynced",
+                        "name": "Local Only",
+                        "accountType": "cash",
+                        "group": serde_json::Value::Null,
+                        "currency": "USD",
+                        "isDefault": false,
+                        "platformId": serde_json::Value::Null,
+                        "accountNumber": serde_json::Value::Null,
+                        "meta": serde_json::Value::Null,
+                        "provider": serde_json::Value::Null,
+                        "providerAccountId": serde_json::Value::Null,
+                        "isArchived": false,
+                        "isActive": true,
+                        "trackingMode": "portfolio"
+                    }),
+                ),
+            )
+            .expect("write outbox")
+        };
+
+        repo.restore_snapshot_tables_keeping_unsynced_from_file(
+            snapshot_path,
+            vec!["accounts".to_string()],
+            300,
+            "device-1".to_string(),
+            Some(1),
+        )
+        .await
+        .expect("restore snapshot");
+
+        assert_eq!(repo.get_cursor().expect("cursor"), 300);
+        assert_eq!(count_account_rows(&pool, "acc-from-snapshot"), 1);
+        assert_eq!(count_account_rows(&pool, "acc-local-unsynced"), 1);
+        let pending = repo.list_pending_outbox(10).expect("pending outbox");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event_id, event_id);
+    }
+
     #[tokio::test]
     async fn reset_local_sync_session_clears_control_plane_and_zeroes_cursors() {
         let (pool, writer) = setup_db();
@@ -5998,6 +6302,134 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn rejected_outbox_event_is_listed_with_its_reason() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool, writer.clone());
+        let now = Utc::now();
+        for event_id in ["rejected-0001", "pending-0001"] {
+            insert_outbox_row_for_prune_test(
+                &repo,
+                &writer,
+                event_id,
+                SyncOutboxStatus::Pending,
+                now,
+            )
+            .await;
+        }
+
+        repo.mark_outbox_rejected(
+            "rejected-0001".to_string(),
+            Some("Invalid request".to_string()),
+            Some("VALIDATION_ERROR".to_string()),
+            vec![SyncFieldError {
+                path: "payload.snapshotId".to_string(),
+                message: "Invalid UUID".to_string(),
+                code: None,
+            }],
+        )
+        .await
+        .expect("mark rejected");
+
+        let page = repo
+            .list_sync_rejections(&SyncRejectionQuery::default())
+            .expect("list rejections");
+        assert_eq!(page.total_count, 1);
+        let rejections = page.items;
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].event_id, "rejected-0001");
+        assert_eq!(
+            rejections[0].error_code.as_deref(),
+            Some("VALIDATION_ERROR")
+        );
+        assert_eq!(rejections[0].field_errors[0].path, "payload.snapshotId");
+        assert!(!rejections[0].suggested_fix.is_empty());
+        let pending_ids: Vec<String> = repo
+            .list_pending_outbox(100)
+            .expect("pending")
+            .into_iter()
+            .map(|event| event.event_id)
+            .collect();
+        assert_eq!(pending_ids, vec!["pending-0001".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn sync_rejections_are_paged_filtered_and_acknowledged_in_bulk() {
+        let (pool, writer) = setup_db();
+        let repo = AppSyncRepository::new(pool, writer.clone());
+        let now = Utc::now();
+        for (offset, event_id) in ["dead-a-00001", "dead-b-00001", "dead-c-00001"]
+            .into_iter()
+            .enumerate()
+        {
+            insert_outbox_row_for_prune_test(
+                &repo,
+                &writer,
+                event_id,
+                SyncOutboxStatus::Dead,
+                now - chrono::Duration::minutes(offset as i64),
+            )
+            .await;
+        }
+        insert_outbox_row_for_prune_test(
+            &repo,
+            &writer,
+            "pending-0001",
+            SyncOutboxStatus::Pending,
+            now,
+        )
+        .await;
+        writer
+            .exec(|conn| {
+                diesel::update(sync_outbox::table.find("dead-c-00001"))
+                    .set(sync_outbox::entity.eq(enum_to_db(&SyncEntity::Activity)?))
+                    .execute(conn)
+                    .map_err(StorageError::from)?;
+                Ok(())
+            })
+            .await
+            .expect("retag entity");
+
+        let first_page = repo
+            .list_sync_rejections(&SyncRejectionQuery {
+                page: Some(1),
+                page_size: Some(2),
+                ..Default::default()
+            })
+            .expect("first page");
+        assert_eq!(first_page.total_count, 3);
+        let ids: Vec<&str> = first_page
+            .items
+            .iter()
+            .map(|r| r.event_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["dead-a-00001", "dead-b-00001"]);
+
+        let activities = repo
+            .list_sync_rejections(&SyncRejectionQuery {
+                entities: vec![SyncEntity::Activity],
+                ..Default::default()
+            })
+            .expect("filtered");
+        assert_eq!(activities.total_count, 1);
+        assert_eq!(activities.items[0].event_id, "dead-c-00001");
+
+        let acknowledged = repo
+            .acknowledge_sync_rejections(vec![
+                "dead-a-00001".to_string(),
+                "dead-b-00001".to_string(),
+                "pending-0001".to_string(),
+            ])
+            .await
+            .expect("acknowledge");
+        assert_eq!(acknowledged, 2);
+        let remaining = repo
+            .list_sync_rejections(&SyncRejectionQuery::default())
+            .expect("remaining");
+        assert_eq!(remaining.total_count, 1);
+        assert_eq!(repo.list_pending_outbox(100).expect("pending").len(), 1);
+    }
+
     #[tokio::test]
     async fn prune_sync_outbox_deletes_only_old_sent_and_dead_rows() {
         let (_pool, writer) = setup_db();
@@ -6818,6 +7250,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn snapshot_export_stages_image_in_configured_blob_dir() {
+        let (pool, writer) = setup_db();
+        let blob_dir = tempdir().expect("blob dir");
+        let storage = SyncBlobStorage::new(blob_dir.path().join("sync")).expect("blob storage");
+        let repo = AppSyncRepository::new(pool.clone(), writer).with_blob_storage(storage);
+        let mut conn = get_connection(&pool).expect("conn");
+        insert_account_for_test(&mut conn, "acc-export").expect("insert account");
+
+        let payload = repo
+            .export_snapshot_sqlite_image(vec!["accounts".to_string()])
+            .await
+            .expect("export snapshot");
+        assert!(payload.starts_with(b"SQLite format 3\0"));
+        // The staged image is removed once read back.
+        let staged = std::fs::read_dir(repo.blob_storage().dir()).expect("read blob dir");
+        assert_eq!(staged.count(), 0);
+
+        // Exports fail rather than silently falling back when the directory disappears.
+        std::fs::remove_dir_all(repo.blob_storage().dir()).expect("remove blob dir");
+        assert!(repo
+            .export_snapshot_sqlite_image(vec!["accounts".to_string()])
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn snapshot_export_filters_broker_snapshots_and_manual_quotes() {
         #[derive(diesel::QueryableByName)]
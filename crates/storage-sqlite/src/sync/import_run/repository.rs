@@ -1,13 +1,15 @@
 //! Repository for import run persistence.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use diesel::prelude::*;
 use diesel::r2d2::{self, Pool};
 use diesel::sqlite::SqliteConnection;
 use std::sync::Arc;
 
 use wealthfolio_connect::broker_ingest::{
-    ImportRun, ImportRunRepositoryTrait as ConnectImportRunRepositoryTrait,
+    ImportRun, ImportRunRepositoryTrait as ConnectImportRunRepositoryTrait, ImportRunTotals,
+    ImportRunWindow,
 };
 use wealthfolio_core::errors::Result;
 
@@ -17,6 +19,16 @@ use crate::schema::import_runs;
 
 use super::model::ImportRunDB;
 
+/// Stored timestamps are RFC 3339 text, which only sorts like time when the offsets match. Text
+/// ranges are widened by this much and the exact bounds are checked on the parsed time.
+const TEXT_RANGE_MARGIN_HOURS: i64 = 24;
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
 pub struct ImportRunRepository {
     pool: Arc<Pool<r2d2::ConnectionManager<SqliteConnection>>>,
     writer: WriteHandle,
@@ -139,6 +151,90 @@ impl ImportRunRepository {
 
         Ok(results.into_iter().map(Into::into).collect())
     }
+
+    /// Import runs of `run_type` started in `[from, to)`, newest first, at most `limit`.
+    /// Served from the `(run_type, started_at)` index. Runs whose start time does not parse
+    /// are skipped.
+    pub fn get_by_run_type_started_between(
+        &self,
+        run_type: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<ImportRunWindow> {
+        let mut conn = get_connection(&self.pool)?;
+        let margin = Duration::hours(TEXT_RANGE_MARGIN_HOURS);
+
+        // One row past the limit tells whether the window goes on beyond it.
+        let rows = import_runs::table
+            .filter(import_runs::run_type.eq(run_type))
+            .filter(import_runs::started_at.ge((from - margin).to_rfc3339()))
+            .filter(import_runs::started_at.lt((to + margin).to_rfc3339()))
+            .order(import_runs::started_at.desc())
+            .limit(limit.saturating_add(1))
+            .load::<ImportRunDB>(&mut conn)
+            .map_err(StorageError::from)?;
+
+        let limit = usize::try_from(limit).unwrap_or(0);
+        let more_rows = rows.len() > limit;
+        let mut runs: Vec<(DateTime<Utc>, ImportRunDB)> = rows
+            .into_iter()
+            .filter_map(|row| parse_timestamp(&row.started_at).map(|started_at| (started_at, row)))
+            .collect();
+        // Rows past the limit may still be in the window unless the last one read is older.
+        let oldest_in_window = runs
+            .last()
+            .is_some_and(|(started_at, _)| *started_at >= from);
+        runs.retain(|(started_at, _)| *started_at >= from && *started_at < to);
+        runs.sort_by_key(|(started_at, _)| std::cmp::Reverse(*started_at));
+        let truncated = runs.len() > limit || (more_rows && oldest_in_window);
+        runs.truncate(limit);
+
+        Ok(ImportRunWindow {
+            runs: runs.into_iter().map(|(_, row)| row.into()).collect(),
+            truncated,
+        })
+    }
+
+    /// Totals over every import run of `run_type`, aggregated in SQL. Summaries that are not
+    /// valid JSON count as empty, as they do when a run is loaded. The latest times are picked
+    /// by instant rather than by text.
+    pub fn get_totals_by_run_type(&self, run_type: &str) -> Result<ImportRunTotals> {
+        use diesel::sql_types::{BigInt, Nullable, Text};
+
+        #[derive(QueryableByName)]
+        struct TotalsRow {
+            #[diesel(sql_type = BigInt)]
+            activities_synced: i64,
+            #[diesel(sql_type = Nullable<Text>)]
+            last_started_at: Option<String>,
+            #[diesel(sql_type = Nullable<Text>)]
+            last_applied_at: Option<String>,
+        }
+
+        let mut conn = get_connection(&self.pool)?;
+        let row: TotalsRow = diesel::sql_query(
+            "SELECT \
+             COALESCE(SUM(CASE WHEN json_valid(summary) THEN \
+                 COALESCE(json_extract(summary, '$.inserted'), 0) \
+                 + COALESCE(json_extract(summary, '$.updated'), 0) END), 0) AS activities_synced, \
+             (SELECT started_at FROM import_runs WHERE run_type = ?1 \
+                 ORDER BY julianday(started_at) DESC LIMIT 1) AS last_started_at, \
+             (SELECT finished_at FROM import_runs WHERE run_type = ?1 AND status = 'APPLIED' \
+                 AND finished_at IS NOT NULL \
+                 ORDER BY julianday(finished_at) DESC LIMIT 1) AS last_applied_at \
+             FROM import_runs WHERE run_type = ?1",
+        )
+        .bind::<Text, _>(run_type)
+        .get_result(&mut conn)
+        .map_err(StorageError::from)?;
+
+        Ok(ImportRunTotals {
+            activities_synced: u64::try_from(row.activities_synced).unwrap_or(0),
+            last_started_at: row.last_started_at.as_deref().and_then(parse_timestamp),
+            last_applied_at: row.last_applied_at.as_deref().and_then(parse_timestamp),
+        })
+    }
 }
 
 #[async_trait]
@@ -166,4 +262,155 @@ impl ConnectImportRunRepositoryTrait for ImportRunRepository {
     fn get_by_run_type(&self, run_type: &str, limit: i64, offset: i64) -> Result<Vec<ImportRun>> {
         ImportRunRepository::get_by_run_type(self, run_type, limit, offset)
     }
+
+    fn get_by_run_type_started_between(
+        &self,
+        run_type: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<ImportRunWindow> {
+        ImportRunRepository::get_by_run_type_started_between(self, run_type, from, to, limit)
+    }
+
+    fn get_totals_by_run_type(&self, run_type: &str) -> Result<ImportRunTotals> {
+        ImportRunRepository::get_totals_by_run_type(self, run_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{create_pool, init, run_migrations, write_actor::spawn_writer};
+    use chrono::{Duration, TimeZone};
+    use diesel::sql_query;
+    use tempfile::tempdir;
+    use wealthfolio_connect::broker_ingest::{
+        ImportRunMode, ImportRunStatus, ImportRunSummary, ImportRunType, ReviewMode,
+    };
+
+    fn setup_repository() -> ImportRunRepository {
+        std::env::set_var("CONNECT_API_URL", "http://test.local");
+        let app_data = tempdir()
+            .expect("tempdir")
+            .keep()
+            .to_string_lossy()
+            .to_string();
+        let db_path = init(&app_data).expect("init db");
+        run_migrations(&db_path).expect("migrate db");
+        let pool = create_pool(&db_path).expect("create pool");
+        let writer = spawn_writer(pool.as_ref().clone()).expect("spawn writer");
+        let mut conn = get_connection(&pool).expect("connection");
+        sql_query(
+            "INSERT INTO accounts (id, name, account_type, `group`, currency, is_default, is_active, \
+             created_at, updated_at, platform_id, account_number, meta, provider, provider_account_id, \
+             is_archived, tracking_mode) VALUES ('acc-1', 'Test', 'cash', NULL, 'USD', 1, 1, \
+             CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, NULL, NULL, NULL, NULL, NULL, 0, 'portfolio')",
+        )
+        .execute(&mut conn)
+        .expect("insert account");
+        ImportRunRepository::new(pool, writer)
+    }
+
+    fn run(
+        run_type: ImportRunType,
+        status: ImportRunStatus,
+        started_at: DateTime<Utc>,
+        inserted: u32,
+    ) -> ImportRun {
+        let mut run = ImportRun::new(
+            "acc-1".to_string(),
+            "SNAPTRADE".to_string(),
+            run_type,
+            ImportRunMode::Incremental,
+            ReviewMode::Never,
+        );
+        run.status = status;
+        run.started_at = started_at;
+        run.finished_at = Some(started_at + Duration::seconds(30));
+        run.summary = Some(ImportRunSummary {
+            inserted,
+            updated: 1,
+            ..Default::default()
+        });
+        run
+    }
+
+    #[tokio::test]
+    async fn reads_sync_history_by_window_and_totals_without_loading_every_run() {
+        let repository = setup_repository();
+        let now = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+        let day = |d: i64| now - Duration::days(d);
+        for seeded in [
+            run(ImportRunType::Sync, ImportRunStatus::Applied, day(1), 5),
+            run(ImportRunType::Sync, ImportRunStatus::Failed, day(2), 0),
+            run(ImportRunType::Sync, ImportRunStatus::Applied, day(40), 10),
+            run(ImportRunType::Import, ImportRunStatus::Applied, day(1), 100),
+        ] {
+            repository.create(seeded).await.unwrap();
+        }
+
+        let window = repository
+            .get_by_run_type_started_between("SYNC", day(30), now, 10)
+            .unwrap();
+        let started: Vec<_> = window.runs.iter().map(|run| run.started_at).collect();
+        assert_eq!(started, vec![day(1), day(2)]);
+        assert!(!window.truncated);
+        let limited = repository
+            .get_by_run_type_started_between("SYNC", day(60), now, 2)
+            .unwrap();
+        assert_eq!(limited.runs.len(), 2);
+        assert_eq!(limited.runs[0].started_at, day(1));
+        assert!(limited.truncated);
+        let exact = repository
+            .get_by_run_type_started_between("SYNC", day(30), now, 2)
+            .unwrap();
+        assert_eq!(exact.runs.len(), 2);
+        assert!(!exact.truncated);
+
+        let totals = repository.get_totals_by_run_type("SYNC").unwrap();
+        assert_eq!(totals.activities_synced, 18);
+        assert_eq!(totals.last_started_at, Some(day(1)));
+        assert_eq!(totals.last_applied_at, Some(day(1) + Duration::seconds(30)));
+        assert_eq!(
+            repository.get_totals_by_run_type("NONE").unwrap(),
+            ImportRunTotals::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn compares_start_times_by_instant_across_utc_offsets() {
+        let repository = setup_repository();
+        let now = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+        let utc = run(ImportRunType::Sync, ImportRunStatus::Applied, now, 1);
+        let offset = run(
+            ImportRunType::Sync,
+            ImportRunStatus::Applied,
+            now - Duration::hours(1),
+            1,
+        );
+        let offset_id = offset.id.clone();
+        repository.create(utc).await.unwrap();
+        repository.create(offset).await.unwrap();
+        // 13:00 at +02:00 is 11:00 UTC, but sorts after "2026-03-31T12:00:00+00:00" as text.
+        let mut conn = get_connection(&repository.pool).unwrap();
+        sql_query("UPDATE import_runs SET started_at = '2026-03-31T13:00:00+02:00' WHERE id = ?1")
+            .bind::<diesel::sql_types::Text, _>(&offset_id)
+            .execute(&mut conn)
+            .unwrap();
+
+        let window = repository
+            .get_by_run_type_started_between(
+                "SYNC",
+                now - Duration::minutes(90),
+                now - Duration::minutes(30),
+                10,
+            )
+            .unwrap();
+        let ids: Vec<_> = window.runs.iter().map(|run| run.id.as_str()).collect();
+        assert_eq!(ids, vec![offset_id.as_str()]);
+
+        let totals = repository.get_totals_by_run_type("SYNC").unwrap();
+        assert_eq!(totals.last_started_at, Some(now));
+    }
 }
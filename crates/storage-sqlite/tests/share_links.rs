@@ -0,0 +1,59 @@
+//! Integration tests for portfolio share links: the migration applies and
+//! expired links are never handed out.
+
+use chrono::{Duration, Utc};
+use tempfile::tempdir;
+use wealthfolio_storage_sqlite::db;
+use wealthfolio_storage_sqlite::portfolio::share_links::{NewShareLink, ShareLinkRepository};
+
+fn setup() -> (
+    tempfile::TempDir,
+    std::sync::Arc<db::DbPool>,
+    db::WriteHandle,
+) {
+    let dir = tempdir().unwrap();
+    let db_path = db::init(dir.path().to_str().unwrap()).unwrap();
+    db::run_migrations(&db_path).unwrap();
+    let pool = db::create_pool(&db_path).unwrap();
+    let writer = db::write_actor::spawn_writer((*pool).clone()).unwrap();
+    (dir, pool, writer)
+}
+
+fn new_link(hash: &str, expires_in: Duration) -> NewShareLink {
+    NewShareLink {
+        token_hash: hash.to_string(),
+        snapshot_json: r#"{"version":1}"#.to_string(),
+        include_account_details: false,
+        expires_at: Utc::now() + expires_in,
+    }
+}
+
+#[tokio::test]
+async fn expired_share_link_is_rejected_and_purged() {
+    let (_dir, pool, writer) = setup();
+    let repo = ShareLinkRepository::new(pool, writer);
+
+    let active = repo
+        .create(new_link("hash-active", Duration::hours(1)))
+        .await
+        .unwrap();
+    repo.create(new_link("hash-expired", Duration::hours(-1)))
+        .await
+        .unwrap();
+
+    let now = Utc::now();
+    let found = repo.find_active("hash-active", now).unwrap().unwrap();
+    assert_eq!(found.id, active.id);
+    assert_eq!(found.snapshot_json, r#"{"version":1}"#);
+    assert!(repo.find_active("hash-expired", now).unwrap().is_none());
+    assert!(repo.find_active("unknown", now).unwrap().is_none());
+    assert_eq!(repo.list_active(now).unwrap().len(), 1);
+
+    // The active link expires once its time is up.
+    let later = now + Duration::hours(2);
+    assert!(repo.find_active("hash-active", later).unwrap().is_none());
+
+    assert_eq!(repo.purge_expired(now).await.unwrap(), 1);
+    assert!(repo.delete(&active.id).await.unwrap());
+    assert!(!repo.delete(&active.id).await.unwrap());
+}